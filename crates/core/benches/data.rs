@@ -0,0 +1,106 @@
+//! Benchmarks for the primitive [`DataCursor`] operations that every format parser is built on.
+//!
+//! Run with `cargo bench -p orthrus-core --features synthetic`.
+
+#![allow(unused_crate_dependencies)]
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use orthrus_core::prelude::*;
+use orthrus_core::synthetic;
+
+const LENGTH: usize = 0x10_0000;
+
+fn read_primitives(c: &mut Criterion) {
+    let data = synthetic::random(LENGTH, 0);
+    let mut group = c.benchmark_group("DataCursor/read");
+    group.throughput(Throughput::Bytes(LENGTH as u64));
+
+    for endian in [Endian::Little, Endian::Big] {
+        group.bench_function(format!("u8/{endian:?}"), |b| {
+            b.iter(|| {
+                let mut cursor = DataCursorRef::new(&data, endian);
+                let mut sum = 0u64;
+                while let Ok(value) = cursor.read_u8() {
+                    sum = sum.wrapping_add(u64::from(value));
+                }
+                black_box(sum)
+            })
+        });
+        group.bench_function(format!("u32/{endian:?}"), |b| {
+            b.iter(|| {
+                let mut cursor = DataCursorRef::new(&data, endian);
+                let mut sum = 0u64;
+                while let Ok(value) = cursor.read_u32() {
+                    sum = sum.wrapping_add(u64::from(value));
+                }
+                black_box(sum)
+            })
+        });
+    }
+
+    group.bench_function("slice/0x100", |b| {
+        b.iter(|| {
+            let mut cursor = DataCursorRef::new(&data, Endian::Little);
+            while let Ok(slice) = cursor.read_slice(0x100) {
+                black_box(slice);
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn write_primitives(c: &mut Criterion) {
+    let mut group = c.benchmark_group("DataCursor/write");
+    group.throughput(Throughput::Bytes(LENGTH as u64));
+
+    for endian in [Endian::Little, Endian::Big] {
+        group.bench_function(format!("u32/{endian:?}"), |b| {
+            b.iter_batched_ref(
+                || vec![0u8; LENGTH],
+                |buffer| {
+                    let mut cursor = DataCursorMut::new(buffer, endian);
+                    let mut value = 0u32;
+                    while cursor.write_u32(value).is_ok() {
+                        value = value.wrapping_add(1);
+                    }
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+fn copy_within(c: &mut Criterion) {
+    let mut group = c.benchmark_group("DataCursor/copy_within");
+    group.throughput(Throughput::Bytes(LENGTH as u64));
+
+    // Distance 1 is the worst case, since every byte overlaps the previous one (a run-length copy). Lengths
+    // cover Yaz0's shortest long match and its longest match, where overlapping copies benefit the most.
+    for length in [0x12usize, 0x111] {
+        for distance in [1usize, 3, 0x20, 0x1000] {
+            group.bench_function(format!("length/{length:#X}/distance/{distance:#X}"), |b| {
+                b.iter_batched_ref(
+                    || DataCursor::new(synthetic::random(LENGTH, 1), Endian::Little),
+                    |cursor| {
+                        let mut dest = distance;
+                        while dest + length <= LENGTH {
+                            cursor.copy_within(dest - distance..dest - distance + length, dest).unwrap();
+                            dest += length;
+                        }
+                    },
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, read_primitives, write_primitives, copy_within);
+criterion_main!(benches);
@@ -0,0 +1,161 @@
+//! AES-128 block cipher, along with the CTR and XTS modes that console file systems are encrypted with.
+//!
+//! The cipher itself and CTR mode come from the [`aes`](::aes) and [`ctr`] crates, while XTS is built on top
+//! of them here, since console file systems use a tweak that isn't supported elsewhere.
+//!
+//! # Usage
+//! ```
+//! use orthrus_core::aes::Aes128;
+//!
+//! // Known-answer test from FIPS-197, Appendix C.1
+//! let key: [u8; 16] = core::array::from_fn(|i| i as u8);
+//! let cipher = Aes128::new(&key);
+//!
+//! let mut block: [u8; 16] = core::array::from_fn(|i| (i as u8) * 0x11);
+//! cipher.encrypt_block(&mut block);
+//! assert_eq!(block, 0x69C4E0D86A7B0430D8CDB78070B4C55Au128.to_be_bytes());
+//! cipher.decrypt_block(&mut block);
+//! assert_eq!(block, 0x00112233445566778899AABBCCDDEEFFu128.to_be_bytes());
+//! ```
+//!
+//! [`Aes128::apply_ctr`] and [`Xts::decrypt`] build on top of this to handle larger buffers.
+
+use ::aes::cipher::{BlockDecrypt, BlockEncrypt, InnerIvInit, KeyInit, StreamCipher};
+
+/// Size of a single AES block, in bytes.
+pub const BLOCK_SIZE: usize = 16;
+
+/// AES with a 128-bit key, see the [module documentation](self) for more information.
+#[derive(Clone)]
+pub struct Aes128 {
+    cipher: ::aes::Aes128,
+}
+
+impl core::fmt::Debug for Aes128 {
+    // Avoid printing the expanded key
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Aes128").finish_non_exhaustive()
+    }
+}
+
+impl Aes128 {
+    /// Expands `key` into the round keys used for both encryption and decryption.
+    #[must_use]
+    #[inline]
+    pub fn new(key: &[u8; 16]) -> Self {
+        Self { cipher: ::aes::Aes128::new(key.into()) }
+    }
+
+    /// Encrypts a single block in place.
+    #[inline]
+    pub fn encrypt_block(&self, block: &mut [u8; BLOCK_SIZE]) {
+        self.cipher.encrypt_block(block.into());
+    }
+
+    /// Decrypts a single block in place.
+    #[inline]
+    pub fn decrypt_block(&self, block: &mut [u8; BLOCK_SIZE]) {
+        self.cipher.decrypt_block(block.into());
+    }
+
+    /// Decrypts every complete block of `data` in place, with each block decrypted independently (ECB).
+    ///
+    /// Any trailing bytes that don't fill a block are left as-is.
+    pub fn decrypt_ecb(&self, data: &mut [u8]) {
+        for chunk in data.chunks_exact_mut(BLOCK_SIZE) {
+            self.cipher.decrypt_block(chunk.into());
+        }
+    }
+
+    /// Encrypts or decrypts `data` in place using counter (CTR) mode, where `counter` is the big-endian
+    /// counter block for the start of `data`, and is incremented for every block afterwards.
+    ///
+    /// `data` doesn't need to be a multiple of the block size, but has to start on a block boundary.
+    ///
+    /// # Examples
+    /// ```
+    /// use orthrus_core::aes::Aes128;
+    ///
+    /// // Known-answer test from NIST SP 800-38A, F.5.1, where the counter overflows its lowest byte
+    /// let cipher = Aes128::new(&0x2B7E151628AED2A6ABF7158809CF4F3Cu128.to_be_bytes());
+    /// let mut data = [0x6BC1BEE22E409F96E93D7E117393172Au128, 0xAE2D8A571E03AC9C9EB76FAC45AF8E51]
+    ///     .map(u128::to_be_bytes)
+    ///     .concat();
+    /// cipher.apply_ctr(0xF0F1F2F3F4F5F6F7F8F9FAFBFCFDFEFF, &mut data);
+    /// assert_eq!(data[..16], 0x874D6191B620E3261BEF6864990DB6CEu128.to_be_bytes());
+    /// assert_eq!(data[16..], 0x9806F66B7970FDFF8617187BB9FFFDFFu128.to_be_bytes());
+    /// ```
+    #[inline]
+    pub fn apply_ctr(&self, counter: u128, data: &mut [u8]) {
+        let core = ctr::CtrCore::inner_iv_init(self.cipher.clone(), &counter.to_be_bytes().into());
+        ctr::Ctr128BE::from_core(core).apply_keystream(data);
+    }
+}
+
+/// AES-128 in XTS mode, used for disk-like storage where each sector is encrypted independently.
+///
+/// This uses a big-endian sector number as the tweak, as used by Nintendo, instead of the little-endian
+/// tweak from IEEE 1619.
+///
+/// # Examples
+/// ```
+/// use orthrus_core::aes::Xts;
+///
+/// // Vectors 1 and 2 from IEEE 1619, Appendix B, with the sector number byte-swapped into a big-endian tweak
+/// let mut data: Vec<u8> = [0x917CF69EBD68B2EC9B9FE9A3EADDA692u128, 0xCD43D2F59598ED858C02C2652FBF922E]
+///     .map(u128::to_be_bytes)
+///     .concat();
+/// Xts::new(&[0; 32]).decrypt(&mut data, 32, 0);
+/// assert_eq!(data, [0; 32]);
+///
+/// let mut data: Vec<u8> = [0xC454185E6A16936E39334038ACEF838Bu128, 0xFB186FFF7480ADC4289382ECD6D394F0]
+///     .map(u128::to_be_bytes)
+///     .concat();
+/// let key: [u8; 32] = core::array::from_fn(|i| if i < 16 { 0x11 } else { 0x22 });
+/// Xts::new(&key).decrypt(&mut data, 32, 0x3333333333u128.swap_bytes());
+/// assert_eq!(data, [0x44; 32]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Xts {
+    data: Aes128,
+    tweak: Aes128,
+}
+
+impl Xts {
+    /// Creates a new XTS cipher from a 256-bit key, which is the data key followed by the tweak key.
+    #[must_use]
+    pub fn new(key: &[u8; 32]) -> Self {
+        let (data, tweak) = key.split_at(16);
+        Self {
+            data: Aes128::new(data.try_into().unwrap()),
+            tweak: Aes128::new(tweak.try_into().unwrap()),
+        }
+    }
+
+    /// Decrypts `data` in place, which is split into sectors of `sector_size` bytes numbered from
+    /// `first_sector` onwards.
+    ///
+    /// # Panics
+    /// Panics if `sector_size` is zero. Only complete blocks are decrypted, so `sector_size` should be a
+    /// multiple of the block size.
+    pub fn decrypt(&self, data: &mut [u8], sector_size: usize, first_sector: u128) {
+        for (index, sector) in data.chunks_mut(sector_size).enumerate() {
+            let mut tweak = first_sector.wrapping_add(index as u128).to_be_bytes();
+            self.tweak.encrypt_block(&mut tweak);
+
+            for chunk in sector.chunks_exact_mut(BLOCK_SIZE) {
+                let block: &mut [u8; BLOCK_SIZE] = chunk.try_into().unwrap();
+                block.iter_mut().zip(tweak).for_each(|(byte, tweak)| *byte ^= tweak);
+                self.data.decrypt_block(block);
+                block.iter_mut().zip(tweak).for_each(|(byte, tweak)| *byte ^= tweak);
+
+                // Multiply the tweak by x in GF(2^128), which is stored little-endian
+                let carry = tweak[15] >> 7;
+                for i in (1..BLOCK_SIZE).rev() {
+                    tweak[i] = (tweak[i] << 1) | (tweak[i - 1] >> 7);
+                }
+                tweak[0] = (tweak[0] << 1) ^ (carry * 0x87);
+            }
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! Arena storage for parsers that would otherwise make thousands of tiny allocations.
+//!
+//! Formats like BAM and BFSAR store many small lists (the children of every node, or the name of every
+//! sound), and giving each of them its own `Vec` or `String` means most of the time spent loading a large
+//! file goes to the allocator. The types in this module store all of the lists in one buffer instead, and
+//! hand out indices that are used to look them up, the same way that objects are referred to by ID inside of
+//! the files themselves.
+//!
+//! * [`SliceArena`] stores any number of slices back-to-back, and returns the index of each slice.
+//! * [`StrArena`] does the same for strings.
+//!
+//! Lists can be replaced, which appends the new list and leaves the old one unused until the arena is
+//! dropped, so these are best suited to data that's mostly read after it's been parsed.
+//!
+//! # Usage
+//! ```
+//! use orthrus_core::arena::{SliceArena, StrArena};
+//!
+//! let mut children = SliceArena::new();
+//! let root = children.push([1, 2, 3]);
+//! let leaf = children.push([]);
+//! assert_eq!(children[root], [1, 2, 3]);
+//! assert!(children[leaf].is_empty());
+//!
+//! let mut names = StrArena::new();
+//! let name = names.push("SE_PLAYER_JUMP");
+//! names.replace(name, "SE_PLAYER_HOP");
+//! assert_eq!(names.get(name), Some("SE_PLAYER_HOP"));
+//! ```
+
+extern crate alloc;
+use alloc::vec::Vec;
+use core::ops::{Index, Range};
+
+/// Where a single list is stored in its arena.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Span {
+    start: u32,
+    length: u32,
+}
+
+impl Span {
+    #[inline]
+    const fn range(self) -> Range<usize> {
+        self.start as usize..self.start as usize + self.length as usize
+    }
+}
+
+/// Stores many slices in a single buffer, see the [module documentation](self) for more information.
+///
+/// Each slice is referred to by its index, in the order it was pushed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SliceArena<T> {
+    items: Vec<T>,
+    spans: Vec<Span>,
+}
+
+impl<T> SliceArena<T> {
+    /// Creates a new empty arena.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self { items: Vec::new(), spans: Vec::new() }
+    }
+
+    /// Creates a new empty arena, with room for `slices` slices holding `items` items in total.
+    #[must_use]
+    #[inline]
+    pub fn with_capacity(slices: usize, items: usize) -> Self {
+        Self { items: Vec::with_capacity(items), spans: Vec::with_capacity(slices) }
+    }
+
+    /// Adds a new slice to the end of the arena, returning its index.
+    ///
+    /// # Panics
+    /// Panics if the arena would hold more than [`u32::MAX`] items.
+    pub fn push<I: IntoIterator<Item = T>>(&mut self, items: I) -> usize {
+        let span = self.append(items);
+        self.spans.push(span);
+        self.spans.len() - 1
+    }
+
+    /// Replaces the slice at `index`, returning `false` if there's no slice at that index. The old items
+    /// are kept until the arena is dropped.
+    ///
+    /// # Panics
+    /// Panics if the arena would hold more than [`u32::MAX`] items.
+    pub fn replace<I: IntoIterator<Item = T>>(&mut self, index: usize, items: I) -> bool {
+        if index >= self.spans.len() {
+            return false;
+        }
+        self.spans[index] = self.append(items);
+        true
+    }
+
+    fn append<I: IntoIterator<Item = T>>(&mut self, items: I) -> Span {
+        let start = self.items.len();
+        self.items.extend(items);
+        let to_u32 = |value: usize| u32::try_from(value).expect("Arena holds more than u32::MAX items");
+        Span { start: to_u32(start), length: to_u32(self.items.len() - start) }
+    }
+
+    /// Returns the slice at `index`, or [`None`] if there's no slice at that index.
+    #[must_use]
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&[T]> {
+        let span = self.spans.get(index)?;
+        self.items.get(span.range())
+    }
+
+    /// Returns the slice at `index` mutably, or [`None`] if there's no slice at that index.
+    #[must_use]
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut [T]> {
+        let span = self.spans.get(index)?;
+        self.items.get_mut(span.range())
+    }
+
+    /// Returns the number of slices in the arena.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Returns `true` if the arena doesn't hold any slices.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Returns every slice, in the order they were pushed.
+    #[inline]
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &[T]> {
+        self.spans.iter().map(|span| &self.items[span.range()])
+    }
+}
+
+impl<T> Default for SliceArena<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<usize> for SliceArena<T> {
+    type Output = [T];
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.items[self.spans[index].range()]
+    }
+}
+
+/// Stores many strings in a single buffer, see the [module documentation](self) for more information.
+///
+/// Each string is referred to by its index, in the order it was pushed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StrArena {
+    bytes: SliceArena<u8>,
+}
+
+impl StrArena {
+    /// Creates a new empty arena.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self { bytes: SliceArena::new() }
+    }
+
+    /// Creates a new empty arena, with room for `strings` strings holding `bytes` bytes in total.
+    #[must_use]
+    #[inline]
+    pub fn with_capacity(strings: usize, bytes: usize) -> Self {
+        Self { bytes: SliceArena::with_capacity(strings, bytes) }
+    }
+
+    /// Adds a new string to the end of the arena, returning its index.
+    ///
+    /// # Panics
+    /// Panics if the arena would hold more than [`u32::MAX`] bytes.
+    #[inline]
+    pub fn push(&mut self, string: &str) -> usize {
+        self.bytes.push(string.bytes())
+    }
+
+    /// Replaces the string at `index`, returning `false` if there's no string at that index. The old string
+    /// is kept until the arena is dropped.
+    ///
+    /// # Panics
+    /// Panics if the arena would hold more than [`u32::MAX`] bytes.
+    #[inline]
+    pub fn replace(&mut self, index: usize, string: &str) -> bool {
+        self.bytes.replace(index, string.bytes())
+    }
+
+    /// Returns the string at `index`, or [`None`] if there's no string at that index.
+    #[must_use]
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&str> {
+        // SAFETY: Only whole strings are ever added, so every span holds valid UTF-8.
+        self.bytes.get(index).map(|bytes| unsafe { core::str::from_utf8_unchecked(bytes) })
+    }
+
+    /// Returns the number of strings in the arena.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns `true` if the arena doesn't hold any strings.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns every string, in the order they were pushed.
+    #[inline]
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &str> {
+        // SAFETY: Only whole strings are ever added, so every span holds valid UTF-8.
+        self.bytes.iter().map(|bytes| unsafe { core::str::from_utf8_unchecked(bytes) })
+    }
+}
@@ -0,0 +1,153 @@
+//! Cancellation for long-running operations, so that a frontend can stop them without leaving broken output.
+//!
+//! Compressing a large file, extracting an archive, or hashing a whole folder can take long enough that a
+//! user may want to stop partway through. Operations that support this take a [`CancellationToken`], which
+//! is cheap to clone and can be cancelled from any thread, such as a UI thread or a signal handler. Each
+//! operation checks it between units of work (every file, every step, or every few kilobytes of input), so
+//! nothing is ever left half-written:
+//! * Operations that build up a result one piece at a time, such as extracting or hashing each file, return
+//!   a [`Progress`] with everything that was finished before they were cancelled.
+//! * Operations where a partial result would be useless or misleading, such as compression, return an
+//!   error instead.
+//!
+//! Operations that return a [`std::io::Error`] signal cancellation with an
+//! [`Other`](std::io::ErrorKind::Other) error holding [`Cancelled`], rather than
+//! [`Interrupted`](std::io::ErrorKind::Interrupted), since readers such as
+//! [`DataStream`](crate::data::DataStream) retry interrupted reads. Use [`Cancelled::is`] to check for it.
+//!
+//! # Usage
+//! ```
+//! use orthrus_core::cancel::{CancellationToken, Progress};
+//!
+//! let token = CancellationToken::new();
+//! let worker = token.clone();
+//! let handle = std::thread::spawn(move || {
+//!     let mut finished = Vec::new();
+//!     for file in 0..100 {
+//!         if worker.is_cancelled() {
+//!             return Progress::Cancelled(finished);
+//!         }
+//!         finished.push(file);
+//!         std::thread::sleep(std::time::Duration::from_millis(1));
+//!     }
+//!     Progress::Complete(finished)
+//! });
+//!
+//! token.cancel();
+//! let progress = handle.join().unwrap();
+//! assert!(progress.is_cancelled());
+//! assert!(progress.into_inner().len() < 100);
+//! ```
+
+extern crate alloc;
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use snafu::prelude::*;
+
+/// Shared flag for stopping an operation, see the [module documentation](self) for more information.
+///
+/// Every clone refers to the same flag, so cancelling any of them cancels all of them.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a new token that hasn't been cancelled.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks every operation using this token to stop as soon as it can. This can't be undone.
+    #[inline]
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if [`cancel`](Self::cancel) has been called on this token or any of its clones.
+    #[must_use]
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Error returned by an operation that was stopped using a [`CancellationToken`].
+#[derive(Debug, Snafu, Clone, Copy, PartialEq, Eq, Hash)]
+#[snafu(display("Operation was cancelled!"))]
+pub struct Cancelled;
+
+#[cfg(feature = "std")]
+impl Cancelled {
+    /// Returns `true` if `error` was caused by cancelling an operation.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::io::{Error, ErrorKind};
+    ///
+    /// use orthrus_core::cancel::Cancelled;
+    ///
+    /// assert!(Cancelled::is(&Cancelled.into()));
+    /// assert!(!Cancelled::is(&Error::from(ErrorKind::Interrupted)));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn is(error: &std::io::Error) -> bool {
+        error.get_ref().is_some_and(|inner| inner.is::<Self>())
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<Cancelled> for std::io::Error {
+    #[inline]
+    fn from(error: Cancelled) -> Self {
+        Self::other(error)
+    }
+}
+
+/// Result of an operation that can be cancelled partway through, see the [module documentation](self) for
+/// more information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[must_use]
+pub enum Progress<T> {
+    /// The operation ran to completion.
+    Complete(T),
+    /// The operation was cancelled, and this only holds what was finished before that.
+    Cancelled(T),
+}
+
+impl<T> Progress<T> {
+    /// Returns `true` if the operation ran to completion.
+    #[must_use]
+    #[inline]
+    pub const fn is_complete(&self) -> bool {
+        matches!(self, Self::Complete(_))
+    }
+
+    /// Returns `true` if the operation was cancelled.
+    #[must_use]
+    #[inline]
+    pub const fn is_cancelled(&self) -> bool {
+        matches!(self, Self::Cancelled(_))
+    }
+
+    /// Returns the result, whether or not the operation was cancelled.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Complete(value) | Self::Cancelled(value) => value,
+        }
+    }
+
+    /// Converts the result using `f`, keeping whether the operation was cancelled.
+    #[inline]
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Progress<U> {
+        match self {
+            Self::Complete(value) => Progress::Complete(f(value)),
+            Self::Cancelled(value) => Progress::Cancelled(f(value)),
+        }
+    }
+}
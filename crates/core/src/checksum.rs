@@ -0,0 +1,399 @@
+//! Checksums and hashes that are commonly stored inside game archives.
+//!
+//! The hashes themselves come from the [`md-5`](::md5) and [`crc32fast`] crates, and are wrapped here so that
+//! they share the [`Checksum`] trait and can be used without depending on either crate directly.
+//!
+//! # Usage
+//! ```
+//! use orthrus_core::checksum::{md5, Md5};
+//!
+//! let expected = md5(b"Hello, world!");
+//!
+//! let mut hasher = Md5::new();
+//! hasher.update(b"Hello, ");
+//! hasher.update(b"world!");
+//! assert_eq!(hasher.finalize(), expected);
+//! ```
+//!
+//! [`Crc32`] and [`crc32`] work the same way, using the standard (IEEE 802.3) polynomial.
+//!
+//! # Streaming
+//! [`HashingReader`] and [`HashingWriter`] wrap any reader or writer and hash everything that passes through
+//! them, so files can be verified or added to a manifest while extracting or packing them, instead of reading
+//! them a second time afterwards. Any hasher implementing [`Checksum`] can be used.
+//!
+//! ```
+//! use orthrus_core::checksum::{crc32, Crc32, HashingReader};
+//! use orthrus_core::prelude::*;
+//!
+//! let mut reader = HashingReader::new(DataCursorRef::new(b"Hello, world!", Endian::Big), Crc32::new());
+//! assert_eq!(reader.read_u32()?, 0x48656C6C);
+//! reader.remaining_slice()?;
+//! assert_eq!(reader.count(), 13);
+//! assert_eq!(reader.finalize().1, crc32(b"Hello, world!"));
+//! # Ok::<(), DataError>(())
+//! ```
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
+
+use ::md5::Digest;
+
+use crate::data::{DataError, Endian, EndianExt, ReadExt, WriteExt};
+
+/// Streaming MD5 hasher, for when the data isn't available all at once.
+///
+/// For hashing a single slice, see [`md5`].
+#[derive(Debug, Clone, Default)]
+pub struct Md5 {
+    hasher: ::md5::Md5,
+}
+
+impl Md5 {
+    /// Creates a new hasher with the initial MD5 state.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self { hasher: ::md5::Md5::new() }
+    }
+
+    /// Adds more data to the hash.
+    #[inline]
+    pub fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    /// Consumes the hasher and returns the final digest.
+    #[must_use]
+    #[inline]
+    pub fn finalize(self) -> [u8; 16] {
+        self.hasher.finalize().into()
+    }
+}
+
+/// Returns the MD5 digest of the given data.
+///
+/// # Examples
+/// ```
+/// use orthrus_core::checksum::md5;
+///
+/// assert_eq!(
+///     md5(b"The quick brown fox jumps over the lazy dog"),
+///     [
+///         0x9E, 0x10, 0x7D, 0x9D, 0x37, 0x2B, 0xB6, 0x82, 0x6B, 0xD8, 0x1D, 0x35, 0x42, 0xA4, 0x19,
+///         0xD6
+///     ]
+/// );
+/// ```
+#[must_use]
+#[inline]
+pub fn md5(data: &[u8]) -> [u8; 16] {
+    let mut hasher = Md5::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+/// Streaming CRC-32 hasher, for when the data isn't available all at once.
+///
+/// For hashing a single slice, see [`crc32`].
+#[derive(Debug, Clone, Default)]
+pub struct Crc32 {
+    hasher: crc32fast::Hasher,
+}
+
+impl Crc32 {
+    /// Creates a new hasher with the initial CRC-32 state.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self { hasher: crc32fast::Hasher::new() }
+    }
+
+    /// Adds more data to the hash.
+    #[inline]
+    pub fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    /// Consumes the hasher and returns the final checksum.
+    #[must_use]
+    #[inline]
+    pub fn finalize(self) -> u32 {
+        self.hasher.finalize()
+    }
+}
+
+/// Returns the CRC-32 checksum of the given data.
+///
+/// # Examples
+/// ```
+/// use orthrus_core::checksum::crc32;
+///
+/// assert_eq!(crc32(b"The quick brown fox jumps over the lazy dog"), 0x414FA339);
+/// ```
+#[must_use]
+#[inline]
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut hasher = Crc32::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+/// Trait for hashers that can be updated a piece at a time, so that [`HashingReader`] and [`HashingWriter`]
+/// work with any of them.
+pub trait Checksum {
+    /// Type of the final checksum or digest.
+    type Output;
+
+    /// Adds more data to the hash.
+    fn update(&mut self, data: &[u8]);
+
+    /// Consumes the hasher and returns the final checksum or digest.
+    fn finalize(self) -> Self::Output;
+}
+
+impl Checksum for Md5 {
+    type Output = [u8; 16];
+
+    #[inline]
+    fn update(&mut self, data: &[u8]) {
+        Md5::update(self, data);
+    }
+
+    #[inline]
+    fn finalize(self) -> Self::Output {
+        Md5::finalize(self)
+    }
+}
+
+impl Checksum for Crc32 {
+    type Output = u32;
+
+    #[inline]
+    fn update(&mut self, data: &[u8]) {
+        Crc32::update(self, data);
+    }
+
+    #[inline]
+    fn finalize(self) -> Self::Output {
+        Crc32::finalize(self)
+    }
+}
+
+/// Adapter which hashes everything read from a stream, see the [module documentation](self#streaming).
+///
+/// Only data that is actually read gets hashed, so skipping ahead or seeking would make the result
+/// meaningless, which is why this doesn't implement [`SeekExt`](crate::data::SeekExt).
+#[derive(Debug, Clone)]
+pub struct HashingReader<R, H> {
+    inner: R,
+    hasher: H,
+    count: u64,
+}
+
+impl<R, H: Checksum> HashingReader<R, H> {
+    /// Creates a new reader which passes everything read from `inner` to `hasher`.
+    #[must_use]
+    #[inline]
+    pub const fn new(inner: R, hasher: H) -> Self {
+        Self { inner, hasher, count: 0 }
+    }
+
+    /// Returns how many bytes have been read and hashed so far.
+    #[must_use]
+    #[inline]
+    pub const fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns a reference to the underlying reader.
+    #[must_use]
+    #[inline]
+    pub const fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Consumes the adapter, returning the underlying reader and the hash of everything that was read.
+    #[inline]
+    pub fn finalize(self) -> (R, H::Output) {
+        (self.inner, self.hasher.finalize())
+    }
+
+    #[inline]
+    fn consume(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+        self.count += data.len() as u64;
+    }
+}
+
+impl<R: EndianExt, H> EndianExt for HashingReader<R, H> {
+    #[inline]
+    fn endian(&self) -> Endian {
+        self.inner.endian()
+    }
+
+    #[inline]
+    fn set_endian(&mut self, endian: Endian) {
+        self.inner.set_endian(endian);
+    }
+}
+
+impl<R: ReadExt, H: Checksum> ReadExt for HashingReader<R, H> {
+    #[inline]
+    fn read_exact<const N: usize>(&mut self) -> Result<[u8; N], DataError> {
+        let result = self.inner.read_exact::<N>()?;
+        self.consume(&result);
+        Ok(result)
+    }
+
+    #[inline]
+    fn read_length(&mut self, buffer: &mut [u8]) -> Result<usize, DataError> {
+        let length = self.inner.read_length(buffer)?;
+        self.consume(&buffer[..length]);
+        Ok(length)
+    }
+
+    #[inline]
+    #[cfg(not(feature = "alloc"))]
+    fn read_slice(&mut self, length: usize) -> Result<&[u8], DataError> {
+        let result = self.inner.read_slice(length)?;
+        self.hasher.update(result);
+        self.count += result.len() as u64;
+        Ok(result)
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn read_slice(&mut self, length: usize) -> Result<Cow<'_, [u8]>, DataError> {
+        let result = self.inner.read_slice(length)?;
+        self.hasher.update(&result);
+        self.count += result.len() as u64;
+        Ok(result)
+    }
+
+    #[inline]
+    #[cfg(not(feature = "alloc"))]
+    fn remaining_slice(&mut self) -> Result<&[u8], DataError> {
+        let result = self.inner.remaining_slice()?;
+        self.hasher.update(result);
+        self.count += result.len() as u64;
+        Ok(result)
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn remaining_slice(&mut self) -> Result<Cow<'_, [u8]>, DataError> {
+        let result = self.inner.remaining_slice()?;
+        self.hasher.update(&result);
+        self.count += result.len() as u64;
+        Ok(result)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read, H: Checksum> Read for HashingReader<R, H> {
+    #[inline]
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        let length = self.inner.read(buffer)?;
+        self.consume(&buffer[..length]);
+        Ok(length)
+    }
+}
+
+/// Adapter which hashes everything written to a stream, see the [module documentation](self#streaming).
+///
+/// # Example
+/// ```
+/// use std::io::Write;
+///
+/// use orthrus_core::checksum::{md5, HashingWriter, Md5};
+///
+/// let mut writer = HashingWriter::new(Vec::new(), Md5::new());
+/// writer.write_all(b"Hello, ")?;
+/// writer.write_all(b"world!")?;
+/// let (output, digest) = writer.finalize();
+/// assert_eq!(digest, md5(&output));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct HashingWriter<W, H> {
+    inner: W,
+    hasher: H,
+    count: u64,
+}
+
+impl<W, H: Checksum> HashingWriter<W, H> {
+    /// Creates a new writer which passes everything written to `inner` to `hasher`.
+    #[must_use]
+    #[inline]
+    pub const fn new(inner: W, hasher: H) -> Self {
+        Self { inner, hasher, count: 0 }
+    }
+
+    /// Returns how many bytes have been written and hashed so far.
+    #[must_use]
+    #[inline]
+    pub const fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns a reference to the underlying writer.
+    #[must_use]
+    #[inline]
+    pub const fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Consumes the adapter, returning the underlying writer and the hash of everything that was written.
+    #[inline]
+    pub fn finalize(self) -> (W, H::Output) {
+        (self.inner, self.hasher.finalize())
+    }
+
+    #[inline]
+    fn consume(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+        self.count += data.len() as u64;
+    }
+}
+
+impl<W: EndianExt, H> EndianExt for HashingWriter<W, H> {
+    #[inline]
+    fn endian(&self) -> Endian {
+        self.inner.endian()
+    }
+
+    #[inline]
+    fn set_endian(&mut self, endian: Endian) {
+        self.inner.set_endian(endian);
+    }
+}
+
+impl<W: WriteExt, H: Checksum> WriteExt for HashingWriter<W, H> {
+    #[inline]
+    fn write_exact<const N: usize>(&mut self, bytes: &[u8; N]) -> Result<(), DataError> {
+        self.inner.write_exact(bytes)?;
+        self.consume(bytes);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: Write, H: Checksum> Write for HashingWriter<W, H> {
+    #[inline]
+    fn write(&mut self, buffer: &[u8]) -> std::io::Result<usize> {
+        // Only hash what the writer actually accepted, since the rest will be passed in again
+        let length = self.inner.write(buffer)?;
+        self.consume(&buffer[..length]);
+        Ok(length)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,269 @@
+//! Conversions between the coordinate systems used by different engines, so that every loader and exporter
+//! swaps axes the same way.
+//!
+//! Each [`CoordinateSystem`] follows Panda3D's definitions, where +X is always right and the other axes depend
+//! on which way is up and the handedness:
+//!
+//! | System | Up | Forward | Used by |
+//! |--------|----|---------|---------|
+//! | [`ZUpRight`](CoordinateSystem::ZUpRight) | +Z | +Y | Panda3D |
+//! | [`ZUpLeft`](CoordinateSystem::ZUpLeft)   | +Z | -Y | |
+//! | [`YUpRight`](CoordinateSystem::YUpRight) | +Y | -Z | Bevy, glTF |
+//! | [`YUpLeft`](CoordinateSystem::YUpLeft)   | +Y | +Z | |
+//!
+//! A [`Conversion`] between two systems only ever swaps and negates axes, so it's exact and can be applied
+//! to vectors, quaternions, and matrices without any rounding. Values are passed as arrays to avoid depending
+//! on any specific math library, quaternions are stored as `[x, y, z, w]`, and matrices are stored as four
+//! columns. Enabling the `glam` feature also allows converting [`glam`] types directly.
+//!
+//! # Usage
+//! ```
+//! use orthrus_core::coordinates::{Conversion, CoordinateSystem};
+//!
+//! let conversion = Conversion::new(CoordinateSystem::PANDA3D, CoordinateSystem::BEVY);
+//! // Forward in Panda3D is forward in Bevy
+//! assert_eq!(conversion.vector([0.0, 1.0, 0.0]), [0.0, 0.0, -1.0]);
+//! assert_eq!(conversion.vector(CoordinateSystem::PANDA3D.up()), CoordinateSystem::BEVY.up());
+//!
+//! // A quarter turn around Panda3D's up axis becomes a quarter turn around Bevy's up axis
+//! let half = core::f32::consts::FRAC_1_SQRT_2;
+//! assert_eq!(conversion.quaternion([0.0, 0.0, half, half]), [0.0, half, 0.0, half]);
+//!
+//! // Matrices are converted so that they still apply the same transform to converted vectors
+//! let translation = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [1.0, 2.0, 3.0, 1.0]];
+//! assert_eq!(conversion.matrix(translation)[3], [1.0, 3.0, -2.0, 1.0]);
+//!
+//! // Converting back gives the original values
+//! assert_eq!(conversion.inverse().vector([1.0, 3.0, -2.0]), [1.0, 2.0, 3.0]);
+//! ```
+
+use core::ops::Neg;
+
+/// Which way is up, and whether the axes are right-handed or left-handed. See the
+/// [module documentation](self) for the axes of each system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CoordinateSystem {
+    /// Z is up and Y is forward, which is the default for Panda3D.
+    #[default]
+    ZUpRight,
+    /// Z is up and -Y is forward.
+    ZUpLeft,
+    /// Y is up and -Z is forward, as used by Bevy and glTF.
+    YUpRight,
+    /// Y is up and Z is forward.
+    YUpLeft,
+}
+
+impl CoordinateSystem {
+    /// Coordinate system used by Panda3D.
+    pub const PANDA3D: Self = Self::ZUpRight;
+    /// Coordinate system used by Bevy.
+    pub const BEVY: Self = Self::YUpRight;
+    /// Coordinate system used by glTF.
+    pub const GLTF: Self = Self::YUpRight;
+
+    /// Returns the axis and sign of right, forward, and up, in that order.
+    const fn basis(self) -> [(usize, bool); 3] {
+        match self {
+            Self::ZUpRight => [(0, false), (1, false), (2, false)],
+            Self::ZUpLeft => [(0, false), (1, true), (2, false)],
+            Self::YUpRight => [(0, false), (2, true), (1, false)],
+            Self::YUpLeft => [(0, false), (2, false), (1, false)],
+        }
+    }
+
+    /// Returns a unit vector along the given entry of [`basis`](Self::basis).
+    const fn unit(self, index: usize) -> [f32; 3] {
+        let (axis, negate) = self.basis()[index];
+        let mut vector = [0.0; 3];
+        vector[axis] = match negate {
+            true => -1.0,
+            false => 1.0,
+        };
+        vector
+    }
+
+    /// Returns the unit vector pointing right.
+    #[must_use]
+    #[inline]
+    pub const fn right(self) -> [f32; 3] {
+        self.unit(0)
+    }
+
+    /// Returns the unit vector pointing forward.
+    #[must_use]
+    #[inline]
+    pub const fn forward(self) -> [f32; 3] {
+        self.unit(1)
+    }
+
+    /// Returns the unit vector pointing up.
+    #[must_use]
+    #[inline]
+    pub const fn up(self) -> [f32; 3] {
+        self.unit(2)
+    }
+
+    /// Returns whether the axes are right-handed.
+    #[must_use]
+    #[inline]
+    pub const fn is_right_handed(self) -> bool {
+        matches!(self, Self::ZUpRight | Self::YUpRight)
+    }
+}
+
+/// Converts values from one [`CoordinateSystem`] to another, see the [module documentation](self) for more
+/// information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Conversion {
+    /// Axis of the source that each axis of the target is read from
+    axes: [usize; 3],
+    /// Whether each axis of the target is negated
+    negate: [bool; 3],
+}
+
+impl Default for Conversion {
+    #[inline]
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Conversion {
+    /// Conversion that leaves every value unchanged.
+    pub const IDENTITY: Self = Self { axes: [0, 1, 2], negate: [false; 3] };
+
+    /// Creates a conversion from values in the `from` coordinate system to values in the `to` coordinate
+    /// system.
+    #[must_use]
+    pub const fn new(from: CoordinateSystem, to: CoordinateSystem) -> Self {
+        let (from, to) = (from.basis(), to.basis());
+        let mut conversion = Self::IDENTITY;
+        // Each direction is read from its axis in the source, and written to its axis in the target
+        let mut i = 0;
+        while i < 3 {
+            let ((source, source_negate), (target, target_negate)) = (from[i], to[i]);
+            conversion.axes[target] = source;
+            conversion.negate[target] = source_negate != target_negate;
+            i += 1;
+        }
+        conversion
+    }
+
+    /// Returns the conversion in the opposite direction.
+    #[must_use]
+    pub const fn inverse(self) -> Self {
+        let mut inverse = Self::IDENTITY;
+        let mut i = 0;
+        while i < 3 {
+            inverse.axes[self.axes[i]] = i;
+            inverse.negate[self.axes[i]] = self.negate[i];
+            i += 1;
+        }
+        inverse
+    }
+
+    /// Returns whether this conversion leaves every value unchanged.
+    #[must_use]
+    #[inline]
+    pub const fn is_identity(self) -> bool {
+        matches!(self, Self { axes: [0, 1, 2], negate: [false, false, false] })
+    }
+
+    /// Returns whether this conversion changes handedness, which reverses the winding order of triangles, so
+    /// their indices need to be swapped to keep facing the same way.
+    #[must_use]
+    pub const fn flips_winding(self) -> bool {
+        // Swapping two axes (leaving one in place) or negating one changes handedness, while a cyclic shift of
+        // all three axes doesn't
+        let mut in_place = 0;
+        let mut negated = false;
+        let mut i = 0;
+        while i < 3 {
+            if self.axes[i] == i {
+                in_place += 1;
+            }
+            negated ^= self.negate[i];
+            i += 1;
+        }
+        (in_place == 1) != negated
+    }
+
+    /// Converts a position or direction.
+    #[must_use]
+    #[inline]
+    pub fn vector<T: Copy + Neg<Output = T>>(self, vector: [T; 3]) -> [T; 3] {
+        core::array::from_fn(|i| match self.negate[i] {
+            true => -vector[self.axes[i]],
+            false => vector[self.axes[i]],
+        })
+    }
+
+    /// Converts a scale along each axis, which only swaps axes since scales don't have a direction.
+    #[must_use]
+    #[inline]
+    pub fn scale<T: Copy>(self, scale: [T; 3]) -> [T; 3] {
+        core::array::from_fn(|i| scale[self.axes[i]])
+    }
+
+    /// Converts a rotation, stored as a quaternion in `[x, y, z, w]` order.
+    #[must_use]
+    #[inline]
+    pub fn quaternion<T: Copy + Neg<Output = T>>(self, quaternion: [T; 4]) -> [T; 4] {
+        let [x, y, z, w] = quaternion;
+        // The rotation axis is a pseudovector, so it's also negated when the handedness changes
+        let [x, y, z] = self.vector([x, y, z]);
+        match self.flips_winding() {
+            true => [-x, -y, -z, w],
+            false => [x, y, z, w],
+        }
+    }
+
+    /// Converts a 4x4 transform matrix, stored as four columns.
+    ///
+    /// The result applies the same transform to converted values, which is the same as converting from the
+    /// target to the source, applying `matrix`, and then converting back.
+    #[must_use]
+    pub fn matrix<T: Copy + Neg<Output = T>>(self, matrix: [[T; 4]; 4]) -> [[T; 4]; 4] {
+        // The fourth row and column are left in place, so that translations are converted like vectors
+        let axis = |i: usize| match i {
+            3 => (3, false),
+            _ => (self.axes[i], self.negate[i]),
+        };
+        core::array::from_fn(|column| {
+            let (source_column, negate_column) = axis(column);
+            core::array::from_fn(|row| {
+                let (source_row, negate_row) = axis(row);
+                let value = matrix[source_column][source_row];
+                match negate_column != negate_row {
+                    true => -value,
+                    false => value,
+                }
+            })
+        })
+    }
+
+    /// Converts a [`glam::Vec3`] position or direction, see [`vector`](Self::vector).
+    #[cfg(feature = "glam")]
+    #[must_use]
+    #[inline]
+    pub fn vec3(self, vector: glam::Vec3) -> glam::Vec3 {
+        self.vector(vector.to_array()).into()
+    }
+
+    /// Converts a [`glam::Quat`] rotation, see [`quaternion`](Self::quaternion).
+    #[cfg(feature = "glam")]
+    #[must_use]
+    #[inline]
+    pub fn quat(self, quaternion: glam::Quat) -> glam::Quat {
+        glam::Quat::from_array(self.quaternion(quaternion.to_array()))
+    }
+
+    /// Converts a [`glam::Mat4`] transform matrix, see [`matrix`](Self::matrix).
+    #[cfg(feature = "glam")]
+    #[must_use]
+    #[inline]
+    pub fn mat4(self, matrix: glam::Mat4) -> glam::Mat4 {
+        glam::Mat4::from_cols_array_2d(&self.matrix(matrix.to_cols_array_2d()))
+    }
+}
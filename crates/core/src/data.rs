@@ -4,36 +4,51 @@
 //! * [`DataCursor`] is for data where it owns the byte slice directly, such as in-memory files.
 //! * [`DataCursorRef`] is for borrowed data and allows for reading.
 //! * [`DataCursorMut`] is for borrowed mutable data and allows both reading and writing.
-//! * [`DataStream`] allows for any stream that supports [`Read`]/[`Write`]/[`Seek`].
+//! * [`DataStream`] allows for any stream that supports [`Read`]/[`Write`]/[`Seek`], including ones backed by
+//!   a network connection, see [`RetryPolicy`].
+//! * [`BoundedReader`] limits any reader to a section of its data, for parsing files inside of containers.
+//! * [`BufferedWriter`] buffers writes to a stream, and can patch earlier data such as header sizes.
+//! * [`IoReader`] allows any endian-aware reader to be used as a [`Read`] and [`Seek`].
+//!
+//! With the `std` feature, the in-memory cursors also implement [`Read`] and [`Seek`] (and [`Write`] for
+//! the mutable ones), so they can be passed directly to crates that expect a standard stream.
 //!
 //! Additionally, this provides several traits to allow for a more modular integration.
 //! * [`IntoDataStream`] allows you to convert into the above types in a generic way.
 //! * [`ReadExt`] provides for endian-aware reading.
 //! * [`WriteExt`] provides for endian-aware writing.
-//! * [`SeekExt`] provides for optional seeking, if `ReadExt` and `WriteExt` are not enough.
+//! * [`SeekExt`] provides for optional seeking, if `ReadExt` and `WriteExt` are not enough, and
+//!   [`ScopedSeek`] for returning to a saved position.
 
 use core::mem::MaybeUninit;
 use core::ops::{Deref, DerefMut};
+#[cfg(feature = "std")]
 use std::fs::File;
+#[cfg(feature = "std")]
 use std::io::{BufReader, Cursor, Empty};
+#[cfg(feature = "std")]
 use std::sync::Arc;
 
 use snafu::prelude::*;
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use crate::no_std::*;
 #[cfg(feature = "alloc")]
 use alloc::borrow::Cow;
 #[cfg(feature = "std")]
 use std::{
     io::{ErrorKind, Read, Seek, SeekFrom, Write},
     path::Path,
+    time::Duration,
 };
 
 #[derive(Debug, Snafu)]
 pub enum Utf8ErrorSource {
     #[snafu(display("Invalid UTF-8 sequence"))]
     Slice { source: core::str::Utf8Error },
+    #[cfg(feature = "alloc")]
     #[snafu(display("Invalid UTF-8 sequence"))]
     String { source: alloc::string::FromUtf8Error },
 }
@@ -50,6 +65,11 @@ pub enum DataError {
     #[snafu(display("{source}"))]
     InvalidString { source: Utf8ErrorSource },
 
+    /// Thrown if a [fixed-capacity container](crate::fixed) doesn't have room for everything being read.
+    #[cfg(feature = "fixed")]
+    #[snafu(display("Ran out of room in a fixed-capacity container, which holds {capacity}"))]
+    CapacityExceeded { capacity: usize },
+
     /// Thrown when an I/O operation fails on a [`DataStream`].
     #[cfg(feature = "std")]
     #[snafu(display("I/O error: {source}"))]
@@ -63,6 +83,7 @@ impl From<core::str::Utf8Error> for DataError {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl From<alloc::string::FromUtf8Error> for DataError {
     #[inline]
     fn from(source: alloc::string::FromUtf8Error) -> Self {
@@ -98,6 +119,33 @@ pub trait EndianExt {
 
     /// Sets the endianness.
     fn set_endian(&mut self, endian: Endian);
+
+    /// Runs `f` with the endianness temporarily set to `endian`, then restores the previous endianness, even
+    /// if `f` returns early with an error. This is useful for sections which are stored with a different
+    /// endianness than the rest of the file.
+    ///
+    /// # Example
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// use orthrus_core::data::EndianExt;
+    ///
+    /// let mut data = DataCursorRef::new(&[0x12, 0x34, 0x12, 0x34], Endian::Big);
+    /// assert_eq!(data.with_endian(Endian::Little, |data| data.read_u16())?, 0x3412);
+    /// assert_eq!(data.read_u16()?, 0x1234);
+    /// # Ok::<(), DataError>(())
+    /// ```
+    #[inline]
+    fn with_endian<R, F>(&mut self, endian: Endian, f: F) -> R
+    where
+        Self: Sized,
+        F: FnOnce(&mut Self) -> R,
+    {
+        let previous = self.endian();
+        self.set_endian(endian);
+        let result = f(self);
+        self.set_endian(previous);
+        result
+    }
 }
 
 /// Trait for types that support seeking operations.
@@ -122,6 +170,91 @@ pub trait SeekExt {
     /// # Errors
     /// Returns an error if unable to determine either the length of the stream or the position inside it.
     fn is_empty(&mut self) -> Result<bool, DataError>;
+
+    /// Saves the current position, returning a [`ScopedSeek`] that can be used in place of `self` and moves
+    /// back to the saved position when it's dropped, even if parsing returns early with an error. This is
+    /// useful for following an offset into another part of the file and then continuing where it left off.
+    ///
+    /// # Errors
+    /// Returns an error if unable to determine the current position.
+    ///
+    /// # Example
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// // An offset to a value, followed by a value of its own
+    /// let mut data = DataCursorRef::new(&[0x00, 0x04, 0x56, 0x78, 0x12, 0x34], Endian::Big);
+    /// let offset = data.read_u16()?;
+    /// {
+    ///     let mut data = data.push_position()?;
+    ///     data.set_position(offset.into())?;
+    ///     assert_eq!(data.read_u16()?, 0x1234);
+    /// }
+    /// assert_eq!(data.read_u16()?, 0x5678);
+    /// # Ok::<(), DataError>(())
+    /// ```
+    #[inline]
+    fn push_position(&mut self) -> Result<ScopedSeek<'_, Self>, DataError>
+    where
+        Self: Sized,
+    {
+        let position = self.position()?;
+        Ok(ScopedSeek { inner: self, position })
+    }
+}
+
+/// Guard that moves back to a saved position when dropped, see [`SeekExt::push_position`].
+///
+/// Any errors while moving back are ignored when it's dropped, so use [`restore`](Self::restore) instead if
+/// they need to be handled.
+#[derive(Debug)]
+#[must_use = "the position is restored as soon as the guard is dropped"]
+pub struct ScopedSeek<'a, T: SeekExt> {
+    inner: &'a mut T,
+    position: u64,
+}
+
+impl<T: SeekExt> ScopedSeek<'_, T> {
+    /// Returns the position that will be restored.
+    #[must_use]
+    #[inline]
+    pub const fn saved_position(&self) -> u64 {
+        self.position
+    }
+
+    /// Moves back to the saved position, returning it.
+    ///
+    /// # Errors
+    /// Returns an error if the position cannot be set.
+    #[inline]
+    pub fn restore(self) -> Result<u64, DataError> {
+        let result = self.inner.set_position(self.position);
+        // Only holds a reference, so there's nothing else to clean up
+        core::mem::forget(self);
+        result
+    }
+}
+
+impl<T: SeekExt> Deref for ScopedSeek<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.inner
+    }
+}
+
+impl<T: SeekExt> DerefMut for ScopedSeek<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.inner
+    }
+}
+
+impl<T: SeekExt> Drop for ScopedSeek<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = self.inner.set_position(self.position);
+    }
 }
 
 /// Trait for types that support reading operations.
@@ -161,7 +294,7 @@ pub trait ReadExt: EndianExt {
     #[cfg(not(feature = "alloc"))]
     fn read_string(&mut self, length: usize) -> Result<&str, DataError> {
         let slice = self.read_slice(length)?;
-        core::str::from_utf8(slice).context(InvalidStrSnafu)
+        Ok(core::str::from_utf8(slice)?)
     }
 
     /// Returns the remaining data from the current position.
@@ -193,6 +326,15 @@ pub trait ReadExt: EndianExt {
         }
     }
 
+    /// Limits further reads to the next `limit` bytes, see [`BoundedReader`] for more information.
+    #[inline]
+    fn bounded(&mut self, limit: u64) -> BoundedReader<'_, Self>
+    where
+        Self: Sized,
+    {
+        BoundedReader::new(self, limit)
+    }
+
     /// Reads an unsigned 8-bit integer.
     ///
     /// # Errors
@@ -423,9 +565,60 @@ pub trait WriteExt: EndianExt {
     }
 }
 
+/// Copies `src` to `dest` within `data`, where a destination inside of the source range repeats the bytes
+/// between them instead of copying what was originally there, which is how LZ77-style back-references work.
+///
+/// Rather than copying one byte at a time, the repeated pattern is copied in chunks that double in size,
+/// since everything already written is a valid source for the next chunk. A distance of one is just a fill.
+///
+/// # Panics
+/// Panics if either range is out of bounds of `data`.
+///
+/// # Examples
+/// ```
+/// use orthrus_core::data::copy_repeating;
+///
+/// let mut data = *b"abc\0\0\0\0\0";
+/// copy_repeating(&mut data, 1..6, 3);
+/// assert_eq!(&data, b"abcbcbcb");
+/// ```
+#[inline]
+pub fn copy_repeating(data: &mut [u8], src: core::ops::Range<usize>, dest: usize) {
+    if src.is_empty() {
+        return;
+    }
+    let length = src.end - src.start;
+    if dest >= src.end {
+        let (source, destination) = data.split_at_mut(dest);
+        destination[..length].copy_from_slice(&source[src]);
+        return;
+    }
+    if dest <= src.start {
+        data.copy_within(src, dest);
+        return;
+    }
+
+    let distance = dest - src.start;
+    if distance == 1 {
+        let value = data[src.start];
+        data[dest..dest + length].fill(value);
+        return;
+    }
+
+    let mut copied = 0;
+    while copied < length {
+        // Everything from the start of the source to what's been written so far repeats every `distance`
+        // bytes, so the next chunk can be as large as that whole region
+        let size = (distance + copied).min(length - copied);
+        data.copy_within(src.start..src.start + size, dest + copied);
+        copied += size;
+    }
+}
+
 /// An owned, in-memory file that allows endian-aware read and write.
 ///
 /// This is architected to assume a fixed length, and is `no_std` compatible.
+#[cfg(feature = "alloc")]
 #[derive(Debug)]
 pub struct DataCursor {
     data: Box<[u8]>,
@@ -433,6 +626,7 @@ pub struct DataCursor {
     endian: Endian,
 }
 
+#[cfg(feature = "alloc")]
 impl DataCursor {
     /// Creates a new `DataCursor` with the given data and endianness.
     #[inline]
@@ -496,6 +690,12 @@ impl DataCursor {
     /// let mut cursor = DataCursor::new(vec![1, 2, 3, 4, 5].into_boxed_slice(), Endian::Little);
     /// cursor.copy_within(1..4, 2).unwrap();
     /// assert_eq!(&cursor.into_inner()[..], &[1, 2, 2, 2, 2]);
+    ///
+    /// // Longer distances repeat the whole pattern
+    /// let data = vec![1, 2, 3, 0, 0, 0, 0, 0, 0, 0];
+    /// let mut cursor = DataCursor::new(data.into_boxed_slice(), Endian::Little);
+    /// cursor.copy_within(0..7, 3).unwrap();
+    /// assert_eq!(&cursor.into_inner()[..], &[1, 2, 3, 1, 2, 3, 1, 2, 3, 1]);
     /// ```
     ///
     /// # Errors
@@ -509,29 +709,12 @@ impl DataCursor {
             EndOfFileSnafu
         );
 
-        if src.contains(&dest) {
-            for i in 0..length {
-                // SAFETY: We want specific behavior if the ranges overlap, due to how Yaz0 compression works.
-                // Both ranges are within bounds and have a valid alignment.
-                unsafe {
-                    *self.data.as_mut_ptr().add(dest.saturating_add(i)) =
-                        *self.data.as_ptr().add(src.start.saturating_add(i));
-                }
-            }
-        } else {
-            // SAFETY: Both ranges are within bounds, do not overlap, and have a valid alignment.
-            unsafe {
-                core::ptr::copy_nonoverlapping(
-                    self.data.as_ptr().add(src.start),
-                    self.data.as_mut_ptr().add(dest),
-                    length,
-                );
-            }
-        }
+        copy_repeating(&mut self.data, src, dest);
         Ok(())
     }
 }
 
+#[cfg(feature = "alloc")]
 impl EndianExt for DataCursor {
     #[inline]
     fn endian(&self) -> Endian {
@@ -544,6 +727,7 @@ impl EndianExt for DataCursor {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl SeekExt for DataCursor {
     #[inline]
     fn position(&mut self) -> Result<u64, DataError> {
@@ -568,6 +752,7 @@ impl SeekExt for DataCursor {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl ReadExt for DataCursor {
     #[inline]
     fn read_exact<const N: usize>(&mut self) -> Result<[u8; N], DataError> {
@@ -661,6 +846,7 @@ impl ReadExt for DataCursor {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl WriteExt for DataCursor {
     #[inline]
     fn write_exact<const N: usize>(&mut self, bytes: &[u8; N]) -> Result<(), DataError> {
@@ -677,6 +863,7 @@ impl WriteExt for DataCursor {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl From<Box<[u8]>> for DataCursor {
     #[inline]
     fn from(value: Box<[u8]>) -> Self {
@@ -692,6 +879,7 @@ impl From<Vec<u8>> for DataCursor {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl Deref for DataCursor {
     type Target = [u8];
 
@@ -701,6 +889,7 @@ impl Deref for DataCursor {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl DerefMut for DataCursor {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
@@ -708,6 +897,7 @@ impl DerefMut for DataCursor {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl AsMut<[u8]> for DataCursor {
     #[inline]
     fn as_mut(&mut self) -> &mut [u8] {
@@ -950,25 +1140,7 @@ impl<'a> DataCursorMut<'a> {
             EndOfFileSnafu
         );
 
-        if src.contains(&dest) {
-            for i in 0..length {
-                // SAFETY: We want specific behavior if they do overlap, due to how Yaz0 compression works.
-                // Both ranges are within bounds and have a valid alignment.
-                unsafe {
-                    *self.data.as_mut_ptr().add(dest.saturating_add(i)) =
-                        *self.data.as_ptr().add(src.start.saturating_add(i));
-                }
-            }
-        } else {
-            // SAFETY: Both ranges are within bounds, do not overlap, and have a valid alignment.
-            unsafe {
-                core::ptr::copy_nonoverlapping(
-                    self.data.as_ptr().add(src.start),
-                    self.data.as_mut_ptr().add(dest),
-                    length,
-                );
-            }
-        }
+        copy_repeating(self.data, src, dest);
         Ok(())
     }
 }
@@ -1141,26 +1313,352 @@ impl AsMut<[u8]> for DataCursorMut<'_> {
     }
 }
 
+#[cfg(feature = "std")]
+impl From<DataError> for std::io::Error {
+    #[inline]
+    fn from(error: DataError) -> Self {
+        match error {
+            DataError::EndOfFile => ErrorKind::UnexpectedEof.into(),
+            DataError::Io { source } => source,
+            error => Self::new(ErrorKind::InvalidData, error),
+        }
+    }
+}
+
+/// Resolves a [`SeekFrom`] against the current position and length of a stream.
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn seek_position(seek: SeekFrom, position: u64, length: u64) -> std::io::Result<u64> {
+    let (base, offset) = match seek {
+        SeekFrom::Start(offset) => return Ok(offset),
+        SeekFrom::Current(offset) => (position, offset),
+        SeekFrom::End(offset) => (length, offset),
+    };
+    base.checked_add_signed(offset)
+        .ok_or_else(|| std::io::Error::new(ErrorKind::InvalidInput, "Tried to seek to a negative position"))
+}
+
+/// Reads from the current position, the same as [`ReadExt::read_length`].
+#[cfg(feature = "alloc")]
+#[cfg(feature = "std")]
+impl Read for DataCursor {
+    #[inline]
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        Ok(self.read_length(buffer)?)
+    }
+}
+
+/// Writes as much as fits before the end of the data, since a `DataCursor` can't grow.
+#[cfg(feature = "alloc")]
+#[cfg(feature = "std")]
+impl Write for DataCursor {
+    #[inline]
+    fn write(&mut self, buffer: &[u8]) -> std::io::Result<usize> {
+        let length = buffer.len().min(self.data.len().saturating_sub(self.position));
+        self.data[self.position..self.position + length].copy_from_slice(&buffer[..length]);
+        self.position += length;
+        Ok(length)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Seeking past the end stops at the end, the same as [`SeekExt::set_position`].
+#[cfg(feature = "alloc")]
+#[cfg(feature = "std")]
+impl Seek for DataCursor {
+    #[inline]
+    fn seek(&mut self, seek: SeekFrom) -> std::io::Result<u64> {
+        let position = seek_position(seek, self.position as u64, self.data.len() as u64)?;
+        Ok(self.set_position(position)?)
+    }
+}
+
+/// Reads from the current position, the same as [`ReadExt::read_length`].
+#[cfg(feature = "std")]
+impl Read for DataCursorRef<'_> {
+    #[inline]
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        Ok(self.read_length(buffer)?)
+    }
+}
+
+/// Seeking past the end stops at the end, the same as [`SeekExt::set_position`].
+#[cfg(feature = "std")]
+impl Seek for DataCursorRef<'_> {
+    #[inline]
+    fn seek(&mut self, seek: SeekFrom) -> std::io::Result<u64> {
+        let position = seek_position(seek, self.position as u64, self.data.len() as u64)?;
+        Ok(self.set_position(position)?)
+    }
+}
+
+/// Reads from the current position, the same as [`ReadExt::read_length`].
+#[cfg(feature = "std")]
+impl Read for DataCursorMut<'_> {
+    #[inline]
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        Ok(self.read_length(buffer)?)
+    }
+}
+
+/// Writes as much as fits before the end of the data, since a `DataCursorMut` can't grow.
+#[cfg(feature = "std")]
+impl Write for DataCursorMut<'_> {
+    #[inline]
+    fn write(&mut self, buffer: &[u8]) -> std::io::Result<usize> {
+        let length = buffer.len().min(self.data.len().saturating_sub(self.position));
+        self.data[self.position..self.position + length].copy_from_slice(&buffer[..length]);
+        self.position += length;
+        Ok(length)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Seeking past the end stops at the end, the same as [`SeekExt::set_position`].
+#[cfg(feature = "std")]
+impl Seek for DataCursorMut<'_> {
+    #[inline]
+    fn seek(&mut self, seek: SeekFrom) -> std::io::Result<u64> {
+        let position = seek_position(seek, self.position as u64, self.data.len() as u64)?;
+        Ok(self.set_position(position)?)
+    }
+}
+
+/// Adapter which allows any endian-aware reader to be used as a [`Read`], and as a [`Seek`] if it supports
+/// seeking, so that it can be passed to other crates such as image decoders or zip readers without copying
+/// its data first.
+///
+/// In-memory cursors such as [`DataCursor`] already implement these traits directly, so this is for generic
+/// code, or readers such as [`BoundedReader`] that only implement [`ReadExt`].
+///
+/// # Example
+/// ```
+/// # use std::io::{Read, Seek, SeekFrom};
+/// # use orthrus_core::prelude::*;
+/// let mut data = DataCursorRef::new(&[1, 2, 3, 4, 5, 6], Endian::Big);
+/// data.set_position(1)?;
+///
+/// let mut reader = IoReader::new(data.bounded(4));
+/// reader.seek(SeekFrom::End(-1))?;
+/// let mut buffer = Vec::new();
+/// reader.read_to_end(&mut buffer)?;
+/// assert_eq!(buffer, [5]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct IoReader<T> {
+    inner: T,
+}
+
+#[cfg(feature = "std")]
+impl<T> IoReader<T> {
+    /// Creates a new adapter that reads from `inner`, starting at its current position.
+    #[inline]
+    pub const fn new(inner: T) -> Self {
+        Self { inner }
+    }
+
+    /// Returns a reference to the underlying reader.
+    #[inline]
+    pub const fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Consumes the adapter and returns the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: ReadExt> Read for IoReader<T> {
+    #[inline]
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        Ok(self.inner.read_length(buffer)?)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: SeekExt> Seek for IoReader<T> {
+    #[inline]
+    fn seek(&mut self, seek: SeekFrom) -> std::io::Result<u64> {
+        let position = seek_position(seek, self.inner.position()?, self.inner.len()?)?;
+        Ok(self.inner.set_position(position)?)
+    }
+}
+
+/// Decides whether a [`DataStream`] should retry a read that timed out, for streams backed by a network
+/// connection such as an HTTP range reader.
+///
+/// This is only asked about reads that fail with [`TimedOut`](ErrorKind::TimedOut) or
+/// [`WouldBlock`](ErrorKind::WouldBlock). Reads that fail with [`Interrupted`](ErrorKind::Interrupted) are
+/// always retried, and any other error is returned immediately.
+///
+/// Closures taking the error and the attempt number can be used as a policy directly.
+#[cfg(feature = "std")]
+pub trait RetryPolicy {
+    /// Called each time a read times out, with how many times in a row it has already been retried. Returns
+    /// how long to wait before trying again, or `None` to give up and return `error`.
+    fn retry(&mut self, error: &std::io::Error, attempt: u32) -> Option<Duration>;
+}
+
+#[cfg(feature = "std")]
+impl<F: FnMut(&std::io::Error, u32) -> Option<Duration>> RetryPolicy for F {
+    #[inline]
+    fn retry(&mut self, error: &std::io::Error, attempt: u32) -> Option<Duration> {
+        self(error, attempt)
+    }
+}
+
+/// [`RetryPolicy`] that never retries a read that timed out, which is the default for a [`DataStream`].
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoRetry;
+
+#[cfg(feature = "std")]
+impl RetryPolicy for NoRetry {
+    #[inline]
+    fn retry(&mut self, _error: &std::io::Error, _attempt: u32) -> Option<Duration> {
+        None
+    }
+}
+
+/// [`RetryPolicy`] that retries a read that timed out up to a fixed number of times in a row, waiting the
+/// same amount of time before each attempt.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+pub struct Retry {
+    attempts: u32,
+    delay: Duration,
+}
+
+#[cfg(feature = "std")]
+impl Retry {
+    /// Creates a new `Retry` which gives up after `attempts` retries in a row, waiting `delay` before each.
+    #[must_use]
+    #[inline]
+    pub const fn new(attempts: u32, delay: Duration) -> Self {
+        Self { attempts, delay }
+    }
+}
+
+#[cfg(feature = "std")]
+impl RetryPolicy for Retry {
+    #[inline]
+    fn retry(&mut self, _error: &std::io::Error, attempt: u32) -> Option<Duration> {
+        (attempt < self.attempts).then_some(self.delay)
+    }
+}
+
 /// A stream that allows endian-aware read and write.
 ///
 /// This struct is generic over any type `T` that implements some combination of
 /// `Read`, `Write`, and `Seek`. Methods are conditionally available based on
 /// the traits implemented by `T`.
+///
+/// # Partial Reads
+/// Streams backed by a network connection can return less data than was asked for, or fail part of the way
+/// through a read. Reads keep going until the buffer is full or the stream ends, so:
+/// * Reads that fail with [`Interrupted`](ErrorKind::Interrupted) are always retried.
+/// * Reads that fail with [`TimedOut`](ErrorKind::TimedOut) or [`WouldBlock`](ErrorKind::WouldBlock) are
+///   retried for as long as the [`RetryPolicy`] allows, see [`with_retry`](Self::with_retry). By default
+///   they're never retried.
+/// * [`read_length`](ReadExt::read_length) returns how many bytes were read if the stream ends early, and
+///   every other read returns an [`UnexpectedEof`](ErrorKind::UnexpectedEof) error.
+///
+/// If a read fails, any data it already read is lost and the position is left wherever the stream stopped.
+///
+/// # Example
+/// ```
+/// # use std::io::Cursor;
+/// # use std::time::Duration;
+/// # use orthrus_core::prelude::*;
+/// use orthrus_core::data::Retry;
+///
+/// let data = Cursor::new([0x12, 0x34, 0x56, 0x78]);
+/// let mut data = DataStream::new(data, Endian::Big).with_retry(Retry::new(3, Duration::from_millis(100)));
+/// assert_eq!(data.read_u32()?, 0x12345678);
+/// # Ok::<(), DataError>(())
+/// ```
+#[cfg(feature = "std")]
 #[derive(Debug)]
-pub struct DataStream<T> {
+pub struct DataStream<T, R = NoRetry> {
     inner: T,
     endian: Endian,
+    retry: R,
 }
 
+#[cfg(feature = "std")]
 impl<T> DataStream<T> {
     /// Creates a new `DataStream` with the given inner stream and endianness.
     #[inline]
     pub const fn new(inner: T, endian: Endian) -> Self {
-        Self { inner, endian }
+        Self { inner, endian, retry: NoRetry }
     }
 }
 
-impl<T> EndianExt for DataStream<T> {
+#[cfg(feature = "std")]
+impl<T, R> DataStream<T, R> {
+    /// Replaces how reads that time out are retried, see [Partial Reads](Self#partial-reads).
+    #[inline]
+    pub fn with_retry<P: RetryPolicy>(self, retry: P) -> DataStream<T, P> {
+        DataStream { inner: self.inner, endian: self.endian, retry }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Read, R: RetryPolicy> DataStream<T, R> {
+    /// Reads until `buffer` is full or the stream ends, retrying any reads that were interrupted or that the
+    /// [`RetryPolicy`] allows. Returns how many bytes were read.
+    fn fill(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        let mut filled = 0;
+        let mut attempt = 0;
+        while filled < buffer.len() {
+            match self.inner.read(&mut buffer[filled..]) {
+                Ok(0) => break,
+                Ok(length) => {
+                    filled += length;
+                    attempt = 0;
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) if matches!(e.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) => {
+                    let delay = self.retry.retry(&e, attempt).ok_or(e)?;
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(filled)
+    }
+
+    /// Same as [`fill`](Self::fill), but returns an error if the stream ends before `buffer` is full.
+    fn fill_exact(&mut self, buffer: &mut [u8]) -> Result<(), DataError> {
+        match self.fill(buffer).context(IoSnafu)? == buffer.len() {
+            true => Ok(()),
+            false => Err(DataError::Io { source: ErrorKind::UnexpectedEof.into() }),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, R> EndianExt for DataStream<T, R> {
     #[inline]
     fn endian(&self) -> Endian {
         self.endian
@@ -1172,7 +1670,8 @@ impl<T> EndianExt for DataStream<T> {
     }
 }
 
-impl<T: Seek> SeekExt for DataStream<T> {
+#[cfg(feature = "std")]
+impl<T: Seek, R> SeekExt for DataStream<T, R> {
     #[inline]
     fn position(&mut self) -> Result<u64, DataError> {
         self.inner.stream_position().context(IoSnafu)
@@ -1225,46 +1724,55 @@ impl<T: Seek> SeekExt for DataStream<T> {
     }
 }
 
-impl<T: Read> ReadExt for DataStream<T> {
+#[cfg(feature = "std")]
+impl<T: Read, R: RetryPolicy> ReadExt for DataStream<T, R> {
     #[inline]
     fn read_exact<const N: usize>(&mut self) -> Result<[u8; N], DataError> {
         let mut buffer = [0u8; N];
-        self.inner.read_exact(&mut buffer).context(IoSnafu)?;
+        self.fill_exact(&mut buffer)?;
         Ok(buffer)
     }
 
     #[inline]
     fn read_length(&mut self, buffer: &mut [u8]) -> Result<usize, DataError> {
-        match self.inner.read_exact(buffer) {
-            Ok(()) => Ok(buffer.len()),
-            Err(e) if e.kind() == ErrorKind::UnexpectedEof => self.inner.read(buffer).context(IoSnafu),
-            Err(e) => Err(DataError::Io { source: e }),
-        }
+        self.fill(buffer).context(IoSnafu)
     }
 
     #[inline]
     fn read_slice(&mut self, length: usize) -> Result<Cow<[u8]>, DataError> {
         let mut buffer = vec![0u8; length];
-        self.inner.read_exact(&mut buffer).context(IoSnafu)?;
+        self.fill_exact(&mut buffer)?;
         Ok(Cow::Owned(buffer))
     }
 
     #[inline]
     fn remaining_slice(&mut self) -> Result<Cow<[u8]>, DataError> {
+        // The length isn't known ahead of time, so keep reading in chunks until the stream ends
+        const CHUNK_SIZE: usize = 0x2000;
         let mut buffer = Vec::new();
-        self.inner.read_to_end(&mut buffer).context(IoSnafu)?;
+        loop {
+            let length = buffer.len();
+            buffer.resize(length + CHUNK_SIZE, 0);
+            let read = self.fill(&mut buffer[length..]).context(IoSnafu)?;
+            buffer.truncate(length + read);
+            if read < CHUNK_SIZE {
+                break;
+            }
+        }
         Ok(Cow::Owned(buffer))
     }
 }
 
-impl<T: Write> WriteExt for DataStream<T> {
+#[cfg(feature = "std")]
+impl<T: Write, R> WriteExt for DataStream<T, R> {
     #[inline]
     fn write_exact<const N: usize>(&mut self, bytes: &[u8; N]) -> Result<(), DataError> {
         self.inner.write_all(bytes).context(IoSnafu)
     }
 }
 
-impl<T> Deref for DataStream<T> {
+#[cfg(feature = "std")]
+impl<T, R> Deref for DataStream<T, R> {
     type Target = T;
 
     #[inline]
@@ -1273,13 +1781,416 @@ impl<T> Deref for DataStream<T> {
     }
 }
 
-impl<T> DerefMut for DataStream<T> {
+#[cfg(feature = "std")]
+impl<T, R> DerefMut for DataStream<T, R> {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.inner
     }
 }
 
+/// Adapter which limits how many bytes can be read from a stream, for parsing one section of a larger file.
+///
+/// Any read that would go past the end of the section returns [`EndOfFile`](DataError::EndOfFile) without
+/// reading anything, so a child parser can't read into data that belongs to the rest of the container.
+/// Positions are relative to the start of the section.
+///
+/// # Example
+/// ```
+/// # use orthrus_core::prelude::*;
+/// let mut data = DataCursorRef::new(&[1, 2, 3, 4, 5, 6], Endian::Big);
+///
+/// let mut section = data.bounded(4);
+/// assert_eq!(section.read_u16()?, 0x0102);
+/// assert!(matches!(section.read_u32(), Err(DataError::EndOfFile)));
+/// assert_eq!(section.read_u16()?, 0x0304);
+/// assert_eq!(section.remaining(), 0);
+///
+/// assert_eq!(data.read_u16()?, 0x0506);
+/// # Ok::<(), DataError>(())
+/// ```
+#[derive(Debug)]
+pub struct BoundedReader<'a, T> {
+    inner: &'a mut T,
+    limit: u64,
+    consumed: u64,
+}
+
+impl<'a, T> BoundedReader<'a, T> {
+    /// Creates a new reader which allows reading at most `limit` bytes from the current position of `inner`.
+    #[inline]
+    pub fn new(inner: &'a mut T, limit: u64) -> Self {
+        Self { inner, limit, consumed: 0 }
+    }
+
+    /// Returns the size of the section.
+    #[must_use]
+    #[inline]
+    pub const fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Returns how many bytes can still be read before reaching the end of the section.
+    #[must_use]
+    #[inline]
+    pub const fn remaining(&self) -> u64 {
+        self.limit - self.consumed
+    }
+
+    /// Returns the underlying stream, positioned wherever reading stopped.
+    #[inline]
+    pub fn into_inner(self) -> &'a mut T {
+        self.inner
+    }
+
+    /// Returns the remaining length as a `usize`, saturating on targets where it doesn't fit.
+    #[inline]
+    fn remaining_len(&self) -> usize {
+        usize::try_from(self.remaining()).unwrap_or(usize::MAX)
+    }
+}
+
+impl<T: SeekExt> BoundedReader<'_, T> {
+    /// Moves the underlying stream to the end of the section, skipping anything that wasn't read.
+    ///
+    /// # Errors
+    /// Returns an error if the underlying stream is unable to seek.
+    #[inline]
+    pub fn skip_remaining(&mut self) -> Result<(), DataError> {
+        self.set_position(self.limit).map(|_| ())
+    }
+}
+
+impl<T: EndianExt> EndianExt for BoundedReader<'_, T> {
+    #[inline]
+    fn endian(&self) -> Endian {
+        self.inner.endian()
+    }
+
+    #[inline]
+    fn set_endian(&mut self, endian: Endian) {
+        self.inner.set_endian(endian);
+    }
+}
+
+impl<T: SeekExt> SeekExt for BoundedReader<'_, T> {
+    #[inline]
+    fn position(&mut self) -> Result<u64, DataError> {
+        Ok(self.consumed)
+    }
+
+    #[inline]
+    fn set_position(&mut self, position: u64) -> Result<u64, DataError> {
+        let start = self.inner.position()? - self.consumed;
+        let pos = self.inner.set_position(start + core::cmp::min(position, self.limit))?;
+        self.consumed = pos - start;
+        Ok(self.consumed)
+    }
+
+    #[inline]
+    fn len(&mut self) -> Result<u64, DataError> {
+        Ok(self.limit)
+    }
+
+    #[inline]
+    fn is_empty(&mut self) -> Result<bool, DataError> {
+        Ok(self.remaining() == 0)
+    }
+}
+
+impl<T: ReadExt> ReadExt for BoundedReader<'_, T> {
+    #[inline]
+    fn read_exact<const N: usize>(&mut self) -> Result<[u8; N], DataError> {
+        ensure!(N <= self.remaining_len(), EndOfFileSnafu);
+        let result = self.inner.read_exact::<N>()?;
+        self.consumed += N as u64;
+        Ok(result)
+    }
+
+    #[inline]
+    fn read_length(&mut self, buffer: &mut [u8]) -> Result<usize, DataError> {
+        let length = buffer.len().min(self.remaining_len());
+        let length = self.inner.read_length(&mut buffer[..length])?;
+        self.consumed += length as u64;
+        Ok(length)
+    }
+
+    #[inline]
+    #[cfg(not(feature = "alloc"))]
+    fn read_slice(&mut self, length: usize) -> Result<&[u8], DataError> {
+        ensure!(length <= self.remaining_len(), EndOfFileSnafu);
+        let result = self.inner.read_slice(length)?;
+        self.consumed += length as u64;
+        Ok(result)
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn read_slice(&mut self, length: usize) -> Result<Cow<'_, [u8]>, DataError> {
+        ensure!(length <= self.remaining_len(), EndOfFileSnafu);
+        let result = self.inner.read_slice(length)?;
+        self.consumed += length as u64;
+        Ok(result)
+    }
+
+    /// Returns the rest of the section.
+    ///
+    /// # Errors
+    /// Returns [`EndOfFile`](DataError::EndOfFile) if the underlying stream ends before the section does.
+    #[inline]
+    #[cfg(not(feature = "alloc"))]
+    fn remaining_slice(&mut self) -> Result<&[u8], DataError> {
+        let length = self.remaining_len();
+        let result = self.inner.read_slice(length)?;
+        self.consumed = self.limit;
+        Ok(result)
+    }
+
+    /// Returns the rest of the section.
+    ///
+    /// # Errors
+    /// Returns [`EndOfFile`](DataError::EndOfFile) if the underlying stream ends before the section does.
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn remaining_slice(&mut self) -> Result<Cow<'_, [u8]>, DataError> {
+        let length = self.remaining_len();
+        let result = self.inner.read_slice(length)?;
+        self.consumed = self.limit;
+        Ok(result)
+    }
+}
+
+/// Buffered writer for building files front-to-back, which tracks the current offset without seeking and
+/// can go back to patch earlier data once it's known, such as a header that stores the total size.
+///
+/// Offsets are relative to where the underlying stream was when writing started. Any buffered data is
+/// written when the buffer fills up, when calling [`flush`](Self::flush), and when dropped, where errors are
+/// ignored, so prefer calling [`flush`](Self::flush) or [`into_inner`](Self::into_inner) when done.
+///
+/// # Example
+/// ```
+/// # use std::io::Cursor;
+/// # use orthrus_core::prelude::*;
+/// let mut data = DataStream::new(Cursor::new(Vec::new()), Endian::Big).buffered();
+///
+/// data.write_exact(b"ARC\0")?;
+/// data.write_u32(0)?; // Placeholder for the total size
+/// data.write_bytes(b"contents")?;
+/// data.align(0x20)?;
+///
+/// let size = data.position() as u32;
+/// data.write_at(4, &size.to_be_bytes())?;
+///
+/// let output = data.into_inner()?.into_inner();
+/// assert_eq!(output.len(), 0x20);
+/// assert_eq!(output[4..8], [0, 0, 0, 0x20]);
+/// # Ok::<(), DataError>(())
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct BufferedWriter<T: Write> {
+    /// Underlying stream, which is only taken out by [`into_inner`](Self::into_inner)
+    inner: Option<T>,
+    endian: Endian,
+    buffer: Vec<u8>,
+    capacity: usize,
+    /// How much has already been written to `inner`, which is where the buffer starts
+    flushed: u64,
+}
+
+#[cfg(feature = "std")]
+impl<T: Write> BufferedWriter<T> {
+    /// Default size of the buffer, in bytes.
+    pub const DEFAULT_CAPACITY: usize = 0x10000;
+
+    /// The stream is only taken out when consuming the writer, so it's always there otherwise.
+    const TAKEN: &str = "BufferedWriter used after into_inner";
+
+    /// Creates a new writer with the default buffer size.
+    #[inline]
+    pub fn new(inner: T, endian: Endian) -> Self {
+        Self::with_capacity(inner, endian, Self::DEFAULT_CAPACITY)
+    }
+
+    /// Creates a new writer which buffers up to `capacity` bytes before writing to `inner`.
+    #[inline]
+    pub fn with_capacity(inner: T, endian: Endian, capacity: usize) -> Self {
+        Self {
+            inner: Some(inner),
+            endian,
+            buffer: Vec::with_capacity(capacity),
+            capacity,
+            flushed: 0,
+        }
+    }
+
+    /// Returns the current offset, which is the total number of bytes written so far.
+    #[must_use]
+    #[inline]
+    pub const fn position(&self) -> u64 {
+        self.flushed + self.buffer.len() as u64
+    }
+
+    /// Returns whether the current offset is a multiple of `alignment`.
+    #[must_use]
+    #[inline]
+    pub const fn is_aligned(&self, alignment: u64) -> bool {
+        self.position().is_multiple_of(alignment)
+    }
+
+    /// Returns a reference to the underlying stream, which may not have received all buffered data yet.
+    #[must_use]
+    #[inline]
+    pub const fn get_ref(&self) -> &T {
+        self.inner.as_ref().expect(Self::TAKEN)
+    }
+
+    /// Returns a mutable reference to the underlying stream.
+    #[inline]
+    fn get_mut(&mut self) -> &mut T {
+        self.inner.as_mut().expect(Self::TAKEN)
+    }
+
+    /// Writes all of `bytes`.
+    ///
+    /// # Errors
+    /// Returns [`Io`](DataError::Io) if the buffer fills up and can't be written.
+    #[inline]
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), DataError> {
+        if self.buffer.len() + bytes.len() > self.capacity {
+            self.flush_buffer()?;
+        }
+        // Anything that wouldn't fit in the buffer anyway skips it entirely
+        match bytes.len() > self.capacity {
+            true => {
+                self.get_mut().write_all(bytes).context(IoSnafu)?;
+                self.flushed += bytes.len() as u64;
+            }
+            false => self.buffer.extend_from_slice(bytes),
+        }
+        Ok(())
+    }
+
+    /// Writes zeroes until the current offset is a multiple of `alignment`, returning the new offset.
+    ///
+    /// # Errors
+    /// Returns [`Io`](DataError::Io) if the buffer fills up and can't be written.
+    #[inline]
+    pub fn align(&mut self, alignment: u64) -> Result<u64, DataError> {
+        let position = self.position();
+        let padding = position.next_multiple_of(alignment) - position;
+        for _ in 0..padding / 0x10 {
+            self.write_bytes(&[0u8; 0x10])?;
+        }
+        self.write_bytes(&[0u8; 0x10][..(padding % 0x10) as usize])?;
+        Ok(self.position())
+    }
+
+    /// Writes any buffered data to the underlying stream, then flushes it.
+    ///
+    /// # Errors
+    /// Returns [`Io`](DataError::Io) if unable to write or flush the underlying stream.
+    #[inline]
+    pub fn flush(&mut self) -> Result<(), DataError> {
+        self.flush_buffer()?;
+        self.get_mut().flush().context(IoSnafu)
+    }
+
+    /// Flushes any buffered data, then returns the underlying stream.
+    ///
+    /// # Errors
+    /// Returns [`Io`](DataError::Io) if unable to write or flush the underlying stream.
+    #[inline]
+    pub fn into_inner(mut self) -> Result<T, DataError> {
+        self.flush()?;
+        Ok(self.inner.take().expect(Self::TAKEN))
+    }
+
+    #[inline]
+    fn flush_buffer(&mut self) -> Result<(), DataError> {
+        if !self.buffer.is_empty() {
+            let inner = self.inner.as_mut().expect(Self::TAKEN);
+            inner.write_all(&self.buffer).context(IoSnafu)?;
+            self.flushed += self.buffer.len() as u64;
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Write + Seek> BufferedWriter<T> {
+    /// Overwrites already written data at `offset` with `bytes`, without changing the current offset.
+    ///
+    /// This only seeks if the data has already left the buffer.
+    ///
+    /// # Errors
+    /// Returns [`EndOfFile`](DataError::EndOfFile) if `bytes` would go past the current offset, or
+    /// [`Io`](DataError::Io) if unable to seek or write the underlying stream.
+    pub fn write_at(&mut self, offset: u64, bytes: &[u8]) -> Result<(), DataError> {
+        let end = offset.checked_add(bytes.len() as u64).context(EndOfFileSnafu)?;
+        ensure!(end <= self.position(), EndOfFileSnafu);
+
+        if offset >= self.flushed {
+            let start = (offset - self.flushed) as usize;
+            self.buffer[start..start + bytes.len()].copy_from_slice(bytes);
+            return Ok(());
+        }
+        // Part of the data has already been written, so write the buffer first to patch everything at once
+        self.flush_buffer()?;
+        let flushed = self.flushed;
+        let inner = self.get_mut();
+        let current = inner.stream_position().context(IoSnafu)?;
+        let base = current - flushed;
+        inner.seek(SeekFrom::Start(base + offset)).context(IoSnafu)?;
+        inner.write_all(bytes).context(IoSnafu)?;
+        inner.seek(SeekFrom::Start(current)).context(IoSnafu)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Write> EndianExt for BufferedWriter<T> {
+    #[inline]
+    fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    #[inline]
+    fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Write> WriteExt for BufferedWriter<T> {
+    #[inline]
+    fn write_exact<const N: usize>(&mut self, bytes: &[u8; N]) -> Result<(), DataError> {
+        self.write_bytes(bytes)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Write> Drop for BufferedWriter<T> {
+    #[inline]
+    fn drop(&mut self) {
+        // Errors can't be returned from here, which is why `flush` should be called explicitly, and there's
+        // nothing left to flush once `into_inner` has taken the stream
+        if self.inner.is_some() {
+            let _ = self.flush();
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Write, R> DataStream<T, R> {
+    /// Converts this into a [`BufferedWriter`] with the same endianness, starting at offset zero.
+    #[inline]
+    pub fn buffered(self) -> BufferedWriter<T> {
+        BufferedWriter::new(self.inner, self.endian)
+    }
+}
+
 // TODO: these are a placeholder solution until specialization is stabilized
 // https://github.com/rust-lang/rust/issues/31844
 /// Trait to convert data types into an endian-aware stream.
@@ -1297,6 +2208,7 @@ pub trait IntoDataStream {
     fn into_stream(self, endian: Endian) -> Self::Reader;
 }
 
+#[cfg(feature = "alloc")]
 impl IntoDataStream for Box<[u8]> {
     type Reader = DataCursor;
 
@@ -1321,6 +2233,7 @@ impl<'a> IntoDataStream for &'a mut [u8] {
     }
 }
 
+#[cfg(feature = "std")]
 impl IntoDataStream for &File {
     type Reader = DataStream<Self>;
 
@@ -1329,6 +2242,7 @@ impl IntoDataStream for &File {
     }
 }
 
+#[cfg(feature = "std")]
 impl IntoDataStream for File {
     type Reader = DataStream<Self>;
 
@@ -1337,6 +2251,7 @@ impl IntoDataStream for File {
     }
 }
 
+#[cfg(feature = "std")]
 impl IntoDataStream for Arc<File> {
     type Reader = DataStream<Self>;
 
@@ -1345,6 +2260,7 @@ impl IntoDataStream for Arc<File> {
     }
 }
 
+#[cfg(feature = "std")]
 impl IntoDataStream for Empty {
     type Reader = DataStream<Self>;
 
@@ -1353,6 +2269,7 @@ impl IntoDataStream for Empty {
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: Read + Seek> IntoDataStream for Box<R> {
     type Reader = DataStream<Self>;
 
@@ -1361,6 +2278,7 @@ impl<R: Read + Seek> IntoDataStream for Box<R> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: Read + Seek> IntoDataStream for BufReader<R> {
     type Reader = DataStream<Self>;
 
@@ -1369,6 +2287,7 @@ impl<R: Read + Seek> IntoDataStream for BufReader<R> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<T: AsRef<[u8]>> IntoDataStream for Cursor<T> {
     type Reader = DataStream<Self>;
 
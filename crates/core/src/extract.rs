@@ -0,0 +1,412 @@
+//! Staged and dry-run extraction, for unpacking untrusted archives over an existing folder.
+//!
+//! Extractors normally write each file straight into the output folder as soon as it's read, so an archive
+//! that turns out to be damaged halfway through leaves a partial tree behind, mixed in with whatever was
+//! already there. Every extractor also has a version that takes an [`Extraction`] in place of the output
+//! folder, which decides where each file actually goes based on its [`ExtractMode`]:
+//! * [`Direct`](ExtractMode::Direct) writes straight into the output folder, the same as passing a path.
+//! * [`Staged`](ExtractMode::Staged) writes into a staging folder next to the output folder, and only moves
+//!   the files into place once [`commit`](Extraction::commit) is called. If extraction fails, the staging
+//!   folder is removed when the [`Extraction`] is dropped, and the output folder is never touched.
+//! * [`DryRun`](ExtractMode::DryRun) doesn't write anything, and only records what would have been written.
+//!
+//! In every mode, each file's path, size, and whether it replaces an existing file are recorded as a
+//! [`PlannedFile`], which [`commit`](Extraction::commit) returns once extraction has finished.
+//!
+//! ## Collisions
+//! A file collides if something already exists at its path in the output folder, or if an earlier file from
+//! the same extraction went to the same path. Archives can store the same name more than once, or names that
+//! only differ in case, which end up as the same file on Windows and macOS. The [`OverwritePolicy`] decides
+//! what happens to the later file:
+//! * [`Overwrite`](OverwritePolicy::Overwrite) replaces the earlier file, which is the default.
+//! * [`Skip`](OverwritePolicy::Skip) keeps the earlier file, and doesn't write the later one.
+//! * [`Rename`](OverwritePolicy::Rename) writes the later file with a numbered suffix, such as `name_1.bin`.
+//! * [`Error`](OverwritePolicy::Error) stops extracting with an
+//!   [`AlreadyExists`](std::io::ErrorKind::AlreadyExists) error, so when staged, nothing is moved into the
+//!   output folder.
+//!
+//! Names that only differ in case collide by default on Windows and macOS, which can be changed with
+//! [`case_insensitive`](Extraction::case_insensitive), such as to make sure that an extracted tree can be
+//! copied to any filesystem.
+//!
+//! ## Cancelling
+//! An extraction can be stopped from another thread using a [`CancellationToken`], see
+//! [`cancellation`](Extraction::cancellation). The next file the extractor asks for fails with a
+//! [`Cancelled`] error (see [`Cancelled::is`]), so every file that was written is complete, and
+//! [`finish`](Extraction::finish) returns them as [`Progress::Cancelled`]. When staged, nothing is moved into
+//! the output folder.
+//!
+//! # Usage
+//! ```
+//! use orthrus_core::extract::{ExtractMode, Extraction, OverwritePolicy};
+//!
+//! let extraction = Extraction::new("unpacked", ExtractMode::DryRun)?;
+//! // Extractors ask where to write each file, and skip writing it during a dry run
+//! assert_eq!(extraction.prepare("models/chest.bam", 0x1200)?, None);
+//!
+//! let files = extraction.commit()?;
+//! assert_eq!(files[0].path.to_str(), Some("models/chest.bam"));
+//! assert_eq!(files[0].size, 0x1200);
+//!
+//! // Keep both files when an archive stores names that only differ in case
+//! let extraction = Extraction::new("unpacked", ExtractMode::DryRun)?
+//!     .overwrite(OverwritePolicy::Rename)
+//!     .case_insensitive(true);
+//! extraction.prepare("Chest.bam", 0x1200)?;
+//! extraction.prepare("chest.bam", 0x800)?;
+//!
+//! let files = extraction.commit()?;
+//! assert_eq!(files[1].path.to_str(), Some("chest_1.bam"));
+//! assert_eq!(files[1].renamed_from.as_deref().and_then(|path| path.to_str()), Some("chest.bam"));
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use crate::cancel::{CancellationToken, Cancelled, Progress};
+
+/// Where an [`Extraction`] writes files, see the [module documentation](self) for more information.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ExtractMode {
+    /// Writes every file straight into the output folder.
+    #[default]
+    Direct,
+    /// Writes every file into a staging folder, and moves them into the output folder once committed.
+    Staged,
+    /// Doesn't write anything, only recording what would have been written.
+    DryRun,
+}
+
+/// What an [`Extraction`] does with a file that collides with another, see the
+/// [module documentation](self) for more information.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum OverwritePolicy {
+    /// Replaces the earlier file.
+    #[default]
+    Overwrite,
+    /// Keeps the earlier file, and doesn't write the new one.
+    Skip,
+    /// Writes the new file next to the earlier one, with a numbered suffix added to its name.
+    Rename,
+    /// Stops extracting with an error.
+    Error,
+}
+
+impl OverwritePolicy {
+    /// Returns the policy with the given name (`overwrite`, `skip`, `rename`, or `error`), or [`None`] if
+    /// there isn't one.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "overwrite" => Some(Self::Overwrite),
+            "skip" => Some(Self::Skip),
+            "rename" => Some(Self::Rename),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
+/// A single file written by an [`Extraction`], or that would have been written during a dry run.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlannedFile {
+    /// Path of the file, relative to the output folder
+    pub path: PathBuf,
+    /// Size of the file in bytes
+    pub size: u64,
+    /// Whether this replaces a file that's already in the output folder, or one that was extracted earlier
+    pub overwrites: bool,
+    /// Whether this collided with another file and wasn't written, see [`OverwritePolicy::Skip`]
+    pub skipped: bool,
+    /// Path that this file would have been written to if it hadn't collided with another file, see
+    /// [`OverwritePolicy::Rename`]
+    pub renamed_from: Option<PathBuf>,
+}
+
+/// Every file that an [`Extraction`] has seen so far.
+#[derive(Debug, Default)]
+struct State {
+    files: Vec<PlannedFile>,
+    /// Every path that's been extracted to, in the form used to check for collisions
+    taken: HashSet<OsString>,
+}
+
+/// Destination for an extractor, see the [module documentation](self) for more information.
+///
+/// This can be shared between threads, so extractors that write files in parallel can use it too.
+#[derive(Debug)]
+pub struct Extraction {
+    output: PathBuf,
+    mode: ExtractMode,
+    /// Folder that files are written to until they're committed, only used when staged
+    staging: Option<PathBuf>,
+    policy: OverwritePolicy,
+    case_insensitive: bool,
+    cancellation: Option<CancellationToken>,
+    state: Mutex<State>,
+}
+
+impl Extraction {
+    /// Creates a new extraction into `output`. When staged, this also creates the staging folder next to it.
+    ///
+    /// # Errors
+    /// Returns an error if unable to create the staging folder.
+    pub fn new<P: Into<PathBuf>>(output: P, mode: ExtractMode) -> std::io::Result<Self> {
+        let mut extraction = Self::direct(output);
+        extraction.mode = mode;
+        extraction.staging = match mode {
+            ExtractMode::Staged => {
+                let staging = Self::staging_path(&extraction.output)?;
+                if let Some(parent) = staging.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                // Never reuse an existing folder, since anything left inside it would be committed too
+                std::fs::create_dir(&staging)?;
+                Some(staging)
+            }
+            ExtractMode::Direct | ExtractMode::DryRun => None,
+        };
+        Ok(extraction)
+    }
+
+    /// Creates a new extraction that writes straight into `output`, which is what extractors use when they're
+    /// given a path.
+    #[must_use]
+    #[inline]
+    pub fn direct<P: Into<PathBuf>>(output: P) -> Self {
+        Self {
+            output: output.into(),
+            mode: ExtractMode::Direct,
+            staging: None,
+            policy: OverwritePolicy::Overwrite,
+            case_insensitive: cfg!(any(windows, target_os = "macos")),
+            cancellation: None,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Controls what happens to files that collide with another, see [`OverwritePolicy`].
+    #[must_use]
+    #[inline]
+    pub const fn overwrite(mut self, policy: OverwritePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Controls whether names that only differ in case collide with each other. This defaults to `true` on
+    /// Windows and macOS, where they're the same file, and `false` everywhere else.
+    ///
+    /// Files that are already in the output folder are found by the filesystem, so this only affects files
+    /// from the same extraction.
+    #[must_use]
+    #[inline]
+    pub const fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Allows the extraction to be stopped using `token`, see the [module documentation](self) for more
+    /// information.
+    #[must_use]
+    #[inline]
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Returns `true` if the extraction has been cancelled.
+    #[must_use]
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.as_ref().is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Returns a staging folder next to `output`, so that files can be moved into place by renaming them.
+    fn staging_path(output: &Path) -> std::io::Result<PathBuf> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let output = std::path::absolute(output)?;
+        let name = output.file_name().map_or_else(|| "output".into(), |name| name.to_string_lossy());
+        let parent = output.parent().unwrap_or(&output);
+        let count = COUNT.fetch_add(1, Ordering::Relaxed);
+        Ok(parent.join(format!(".{name}.staging-{}-{count}", std::process::id())))
+    }
+
+    /// Returns the output folder.
+    #[must_use]
+    #[inline]
+    pub fn output(&self) -> &Path {
+        &self.output
+    }
+
+    /// Returns where files are being written.
+    #[must_use]
+    #[inline]
+    pub const fn mode(&self) -> ExtractMode {
+        self.mode
+    }
+
+    /// Returns what happens to files that collide with another.
+    #[must_use]
+    #[inline]
+    pub const fn policy(&self) -> OverwritePolicy {
+        self.policy
+    }
+
+    /// Records that `size` bytes are about to be extracted to `path`, which is relative to the output folder
+    /// and should already be sanitized (see [`PathOptions`](crate::sanitize::PathOptions)). Returns where the
+    /// file should actually be written, or [`None`] if it shouldn't be written at all, either because this is
+    /// a dry run or because it collided with another file and is being skipped.
+    ///
+    /// The folder that the file goes in isn't created, so extractors still need to create it.
+    ///
+    /// # Errors
+    /// Returns an [`AlreadyExists`](ErrorKind::AlreadyExists) error if the file collides with another, and
+    /// the policy is [`OverwritePolicy::Error`], or a [`Cancelled`] error if the extraction has been
+    /// cancelled.
+    pub fn prepare<P: AsRef<Path>>(&self, path: P, size: u64) -> std::io::Result<Option<PathBuf>> {
+        if self.is_cancelled() {
+            return Err(Cancelled.into());
+        }
+        let mut path = path.as_ref().to_owned();
+        let mut renamed_from = None;
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let collides = self.collides(&state, &path);
+        if collides {
+            match self.policy {
+                OverwritePolicy::Overwrite | OverwritePolicy::Skip => {}
+                OverwritePolicy::Rename => {
+                    let renamed = (1..)
+                        .map(|count| Self::with_suffix(&path, count))
+                        .find(|renamed| !self.collides(&state, renamed))
+                        .unwrap_or_default();
+                    renamed_from = Some(core::mem::replace(&mut path, renamed));
+                }
+                OverwritePolicy::Error => {
+                    let message = format!("{} collides with another file", path.display());
+                    return Err(Error::new(ErrorKind::AlreadyExists, message));
+                }
+            }
+        }
+
+        let skipped = collides && self.policy == OverwritePolicy::Skip;
+        let target = match self.mode {
+            _ if skipped => None,
+            ExtractMode::Direct => Some(self.output.join(&path)),
+            ExtractMode::Staged => self.staging.as_ref().map(|staging| staging.join(&path)),
+            ExtractMode::DryRun => None,
+        };
+        let key = self.key(&path);
+        state.taken.insert(key);
+
+        let overwrites = collides && self.policy == OverwritePolicy::Overwrite;
+        state.files.push(PlannedFile { path, size, overwrites, skipped, renamed_from });
+        Ok(target)
+    }
+
+    /// Returns `true` if something is already in the output folder at `path`, or was extracted there earlier.
+    fn collides(&self, state: &State, path: &Path) -> bool {
+        state.taken.contains(&self.key(path)) || self.output.join(path).symlink_metadata().is_ok()
+    }
+
+    /// Returns the form of `path` that's used to check for collisions.
+    fn key(&self, path: &Path) -> OsString {
+        match self.case_insensitive {
+            true => path.to_string_lossy().to_lowercase().into(),
+            false => path.as_os_str().to_owned(),
+        }
+    }
+
+    /// Adds `_{count}` to the end of the file name in `path`, before its extension.
+    fn with_suffix(path: &Path, count: usize) -> PathBuf {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let name = match path.extension() {
+            Some(extension) => format!("{stem}_{count}.{}", extension.to_string_lossy()),
+            None => format!("{stem}_{count}"),
+        };
+        path.with_file_name(name)
+    }
+
+    /// Finishes the extraction, returning every file that was written (or would have been, for a dry run),
+    /// sorted by path.
+    ///
+    /// Staged files are moved into the output folder. If it doesn't exist yet, the whole staging folder is
+    /// renamed in a single step, so the output appears all at once. Otherwise, each file is moved into place
+    /// one at a time, replacing any existing file.
+    ///
+    /// If the extraction was cancelled, staged files are removed instead, see [`finish`](Self::finish).
+    ///
+    /// # Errors
+    /// Returns an error if unable to move a staged file into place, in which case the rest are removed along
+    /// with the staging folder.
+    #[inline]
+    pub fn commit(self) -> std::io::Result<Vec<PlannedFile>> {
+        self.finish().map(Progress::into_inner)
+    }
+
+    /// Finishes the extraction the same way as [`commit`](Self::commit), but also returns whether it was
+    /// cancelled.
+    ///
+    /// When cancelled, every file that was written before that is returned as [`Progress::Cancelled`]. Files
+    /// that were written straight into the output folder are kept, while staged files are removed without
+    /// touching the output folder, so it's never left with only part of an archive.
+    ///
+    /// # Errors
+    /// Returns an error if unable to move a staged file into place, in which case the rest are removed along
+    /// with the staging folder.
+    pub fn finish(mut self) -> std::io::Result<Progress<Vec<PlannedFile>>> {
+        let mut files =
+            core::mem::take(&mut self.state.get_mut().unwrap_or_else(PoisonError::into_inner).files);
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        if self.is_cancelled() {
+            // Dropping the extraction removes anything that was staged
+            return Ok(Progress::Cancelled(files));
+        }
+
+        if let Some(staging) = &self.staging {
+            match self.output.symlink_metadata().is_ok() {
+                true => {
+                    for file in files.iter().filter(|file| !file.skipped) {
+                        // Overwritten files were only staged once, which includes names that only differ in
+                        // case on case-insensitive filesystems, so they may have been moved already
+                        let source = staging.join(&file.path);
+                        if source.symlink_metadata().is_err() {
+                            continue;
+                        }
+                        let target = self.output.join(&file.path);
+                        if let Some(dir) = target.parent() {
+                            std::fs::create_dir_all(dir)?;
+                        }
+                        std::fs::rename(source, target)?;
+                    }
+                    std::fs::remove_dir_all(staging)?;
+                }
+                false => {
+                    if let Some(parent) = self.output.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::rename(staging, &self.output)?;
+                }
+            }
+            self.staging = None;
+        }
+        Ok(Progress::Complete(files))
+    }
+}
+
+impl Drop for Extraction {
+    #[inline]
+    fn drop(&mut self) {
+        // Anything still staged is from an extraction that failed or was never committed
+        if let Some(staging) = &self.staging {
+            let _ = std::fs::remove_dir_all(staging);
+        }
+    }
+}
@@ -0,0 +1,363 @@
+//! Fixed-capacity containers for parsing on targets without an allocator.
+//!
+//! Without the `alloc` feature there's no `Vec` or `String`, so a parser can't collect a list of entries or
+//! keep the names from a string table. The types in this module store up to a fixed number of items inline
+//! instead, so they can live on the stack or in a `static`, and return
+//! [`CapacityExceeded`](DataError::CapacityExceeded) if a file holds more than they have room for.
+//!
+//! * [`FixedVec`] is a list with room for up to `N` items.
+//! * [`FixedString`] is a UTF-8 string with room for up to `N` bytes, such as a name read from a header.
+//!
+//! Both work the same with or without `alloc`, so a parser written using them runs everywhere.
+//!
+//! # Usage
+//! ```
+//! use orthrus_core::fixed::{FixedString, FixedVec};
+//! use orthrus_core::prelude::*;
+//!
+//! // A count, followed by each name as a length and its (null-padded) bytes
+//! let mut data = DataCursorRef::new(b"\x02\x06SEQ_A\0\x06SEQ_B\0", Endian::Big);
+//! let mut names: FixedVec<FixedString<16>, 8> = FixedVec::new();
+//! for _ in 0..data.read_u8()? {
+//!     let length = data.read_u8()?;
+//!     names.push(FixedString::read(&mut data, length.into())?)?;
+//! }
+//! assert_eq!(names.len(), 2);
+//! assert_eq!(names[1], "SEQ_B");
+//!
+//! // Anything that doesn't fit is an error instead of an allocation
+//! let mut data = DataCursorRef::new(b"SE_PLAYER_JUMP_HIGH", Endian::Big);
+//! assert!(matches!(
+//!     FixedString::<16>::read(&mut data, 19),
+//!     Err(DataError::CapacityExceeded { capacity: 16 })
+//! ));
+//! # Ok::<(), DataError>(())
+//! ```
+
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::mem::MaybeUninit;
+use core::ops::{Deref, DerefMut};
+
+use crate::data::{DataError, ReadExt};
+
+/// List of up to `N` items stored inline, see the [module documentation](self) for more information.
+pub struct FixedVec<T, const N: usize> {
+    items: [MaybeUninit<T>; N],
+    length: usize,
+}
+
+impl<T, const N: usize> FixedVec<T, N> {
+    /// Creates a new empty list.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self { items: [const { MaybeUninit::uninit() }; N], length: 0 }
+    }
+
+    /// Adds an item to the end of the list.
+    ///
+    /// # Errors
+    /// Returns [`CapacityExceeded`](DataError::CapacityExceeded) if the list is already full.
+    #[inline]
+    pub fn push(&mut self, item: T) -> Result<(), DataError> {
+        match self.items.get_mut(self.length) {
+            Some(slot) => {
+                slot.write(item);
+                self.length += 1;
+                Ok(())
+            }
+            None => Err(DataError::CapacityExceeded { capacity: N }),
+        }
+    }
+
+    /// Removes the last item from the list and returns it, or [`None`] if it's empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.length = self.length.checked_sub(1)?;
+        // SAFETY: Every item below the old length was initialized, and it's no longer counted, so it won't
+        // be read or dropped again.
+        Some(unsafe { self.items[self.length].assume_init_read() })
+    }
+
+    /// Removes every item from the list.
+    #[inline]
+    pub fn clear(&mut self) {
+        while self.pop().is_some() {}
+    }
+
+    /// Returns the number of items in the list.
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the list doesn't hold any items.
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns `true` if the list can't hold any more items.
+    #[must_use]
+    #[inline]
+    pub const fn is_full(&self) -> bool {
+        self.length == N
+    }
+
+    /// Returns the number of items the list has room for.
+    #[must_use]
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the items as a slice.
+    #[must_use]
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: Every item below `length` is initialized, and `MaybeUninit<T>` has the same layout as `T`.
+        unsafe { core::slice::from_raw_parts(self.items.as_ptr().cast(), self.length) }
+    }
+
+    /// Returns the items as a mutable slice.
+    #[must_use]
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: Every item below `length` is initialized, and `MaybeUninit<T>` has the same layout as `T`.
+        unsafe { core::slice::from_raw_parts_mut(self.items.as_mut_ptr().cast(), self.length) }
+    }
+}
+
+impl<T, const N: usize> Drop for FixedVec<T, N> {
+    #[inline]
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Default for FixedVec<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for FixedVec<T, N> {
+    fn clone(&self) -> Self {
+        let mut clone = Self::new();
+        for item in self.iter() {
+            // Both lists have the same capacity, so this can't fail
+            let _ = clone.push(item.clone());
+        }
+        clone
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for FixedVec<T, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for FixedVec<T, N> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for FixedVec<T, N> {}
+
+impl<T, const N: usize> Deref for FixedVec<T, N> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for FixedVec<T, N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_slice()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a FixedVec<T, N> {
+    type IntoIter = core::slice::Iter<'a, T>;
+    type Item = &'a T;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// UTF-8 string of up to `N` bytes stored inline, see the [module documentation](self) for more information.
+#[derive(Clone, Copy)]
+pub struct FixedString<const N: usize> {
+    bytes: [u8; N],
+    length: usize,
+}
+
+impl<const N: usize> FixedString<N> {
+    /// Creates a new empty string.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self { bytes: [0; N], length: 0 }
+    }
+
+    /// Reads a string that's stored in `length` bytes, such as an entry in a string table or a name in a
+    /// header. Anything from the first null onwards is padding, and isn't included.
+    ///
+    /// # Errors
+    /// Returns [`EndOfFile`](DataError::EndOfFile) if trying to read out of bounds,
+    /// [`InvalidString`](DataError::InvalidString) if the string isn't valid UTF-8, or
+    /// [`CapacityExceeded`](DataError::CapacityExceeded) if it's longer than `N` bytes.
+    pub fn read<T: ReadExt>(data: &mut T, length: usize) -> Result<Self, DataError> {
+        let slice = data.read_slice(length)?;
+        let end = slice.iter().position(|&byte| byte == 0).unwrap_or(slice.len());
+        let mut string = Self::new();
+        string.push_str(core::str::from_utf8(&slice[..end])?)?;
+        Ok(string)
+    }
+
+    /// Adds `string` to the end of this string.
+    ///
+    /// # Errors
+    /// Returns [`CapacityExceeded`](DataError::CapacityExceeded) if there isn't room for all of `string`, in
+    /// which case nothing is added.
+    #[inline]
+    pub fn push_str(&mut self, string: &str) -> Result<(), DataError> {
+        let end = self.length + string.len();
+        match self.bytes.get_mut(self.length..end) {
+            Some(bytes) => {
+                bytes.copy_from_slice(string.as_bytes());
+                self.length = end;
+                Ok(())
+            }
+            None => Err(DataError::CapacityExceeded { capacity: N }),
+        }
+    }
+
+    /// Removes the contents of the string.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.length = 0;
+    }
+
+    /// Returns the length of the string in bytes.
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the string is empty.
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns the number of bytes the string has room for.
+    #[must_use]
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the contents as a `&str`.
+    #[must_use]
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // SAFETY: Only whole strings are ever added, so everything below `length` is valid UTF-8.
+        unsafe { core::str::from_utf8_unchecked(&self.bytes[..self.length]) }
+    }
+}
+
+impl<const N: usize> Default for FixedString<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> TryFrom<&str> for FixedString<N> {
+    type Error = DataError;
+
+    #[inline]
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut string = Self::new();
+        string.push_str(value)?;
+        Ok(string)
+    }
+}
+
+impl<const N: usize> Deref for FixedString<N> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for FixedString<N> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedString<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> fmt::Display for FixedString<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// Bytes past the end of the string may be left over from before it was cleared, so these only look at the
+// string itself
+impl<const N: usize> PartialEq for FixedString<N> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for FixedString<N> {}
+
+impl<const N: usize> PartialEq<str> for FixedString<N> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for FixedString<N> {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> Hash for FixedString<N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
@@ -0,0 +1,235 @@
+//! Reading remote files over HTTP, without downloading all of them.
+//!
+//! [`HttpReader`] implements [`Read`] and [`Seek`] using HTTP range requests, so an archive on a web server
+//! can be opened the same way as a local file, and only the parts that actually get read are downloaded.
+//! This makes it possible to list the files inside of a large archive using only its index.
+//!
+//! Data is downloaded in fixed-size chunks, and the most recently used chunks are kept in memory, so many
+//! small reads next to each other (such as reading an index one field at a time) only need a single request.
+//!
+//! # Usage
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use orthrus_core::data::Retry;
+//! use orthrus_core::http::HttpReader;
+//! use orthrus_core::prelude::*;
+//!
+//! let reader = HttpReader::open("https://example.com/phase_4.mf")?;
+//! let mut data = DataStream::new(reader, Endian::Little).with_retry(Retry::new(3, Duration::from_secs(1)));
+//! let mut magic = [0u8; 6];
+//! data.read_length(&mut magic)?;
+//! println!("Downloaded {} bytes in {} requests", data.downloaded(), data.requests());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! # Errors
+//! Requests that fail because of the connection, such as being unable to connect or the connection dropping
+//! part of the way through, are returned as [`TimedOut`](ErrorKind::TimedOut) errors, so a [`DataStream`]
+//! with a [`RetryPolicy`](crate::data::RetryPolicy) will try them again. Servers that don't support range
+//! requests return an [`Unsupported`](ErrorKind::Unsupported) error when opening, instead of silently
+//! downloading the whole file.
+
+use std::collections::VecDeque;
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::time::Duration;
+
+use crate::data::{seek_position, DataStream, Endian, IntoDataStream};
+
+/// Reader over a remote file using HTTP range requests, see the [module documentation](self) for more
+/// information.
+#[derive(Debug)]
+pub struct HttpReader {
+    agent: ureq::Agent,
+    url: String,
+    length: u64,
+    position: u64,
+    chunk_size: u64,
+    cache_size: usize,
+    /// Downloaded chunks along with their index, with the most recently used at the back
+    chunks: VecDeque<(u64, Box<[u8]>)>,
+    requests: usize,
+    downloaded: u64,
+}
+
+impl HttpReader {
+    /// Default number of bytes downloaded in each request.
+    pub const CHUNK_SIZE: usize = 0x10000;
+    /// Default number of chunks kept in memory.
+    pub const CACHE_SIZE: usize = 64;
+
+    /// Connects to `url` and checks that the server supports range requests, without downloading anything
+    /// else yet. Uses the default [`CHUNK_SIZE`](Self::CHUNK_SIZE) and [`CACHE_SIZE`](Self::CACHE_SIZE).
+    ///
+    /// # Errors
+    /// Returns [`Unsupported`](ErrorKind::Unsupported) if the server doesn't support range requests, or an
+    /// error if the request fails.
+    #[inline]
+    pub fn open(url: &str) -> std::io::Result<Self> {
+        Self::open_with_cache(url, Self::CHUNK_SIZE, Self::CACHE_SIZE)
+    }
+
+    /// Connects to `url` and checks that the server supports range requests, downloading `chunk_size` bytes
+    /// at a time and keeping up to `cache_size` chunks in memory.
+    ///
+    /// # Errors
+    /// Returns [`Unsupported`](ErrorKind::Unsupported) if the server doesn't support range requests, or an
+    /// error if the request fails.
+    pub fn open_with_cache(url: &str, chunk_size: usize, cache_size: usize) -> std::io::Result<Self> {
+        let agent = ureq::AgentBuilder::new()
+            .timeout_connect(Duration::from_secs(10))
+            .timeout_read(Duration::from_secs(30))
+            .build();
+        // Ask for a single byte, which tells us both the length and whether ranges are supported
+        let response = agent.get(url).set("Range", "bytes=0-0").call().map_err(request_error)?;
+        ensure_partial(&response)?;
+        let length = response
+            .header("Content-Range")
+            .and_then(|range| range.rsplit_once('/'))
+            .and_then(|(_, length)| length.parse().ok())
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    ErrorKind::Unsupported,
+                    "Server didn't return the length of the file",
+                )
+            })?;
+
+        Ok(Self {
+            agent,
+            url: url.to_owned(),
+            length,
+            position: 0,
+            chunk_size: chunk_size.max(1) as u64,
+            cache_size: cache_size.max(1),
+            chunks: VecDeque::new(),
+            requests: 1,
+            downloaded: 0,
+        })
+    }
+
+    /// Returns the total length of the remote file.
+    #[must_use]
+    #[inline]
+    pub const fn size(&self) -> u64 {
+        self.length
+    }
+
+    /// Returns how many requests have been sent to the server, including the one made when opening.
+    #[must_use]
+    #[inline]
+    pub const fn requests(&self) -> usize {
+        self.requests
+    }
+
+    /// Returns how many bytes of the file have been downloaded so far.
+    #[must_use]
+    #[inline]
+    pub const fn downloaded(&self) -> u64 {
+        self.downloaded
+    }
+
+    /// Returns the chunk at `index`, downloading it if it isn't already cached.
+    fn chunk(&mut self, index: u64) -> std::io::Result<&[u8]> {
+        match self.chunks.iter().position(|(cached, _)| *cached == index) {
+            Some(found) => {
+                // Move it to the back so it's the last to be evicted
+                if let Some(chunk) = self.chunks.remove(found) {
+                    self.chunks.push_back(chunk);
+                }
+            }
+            None => {
+                let start = index * self.chunk_size;
+                let end = (start + self.chunk_size).min(self.length);
+                let data = self.download(start, end)?;
+                if self.chunks.len() >= self.cache_size {
+                    self.chunks.pop_front();
+                }
+                self.chunks.push_back((index, data));
+            }
+        }
+        Ok(self.chunks.back().map_or(&[], |(_, data)| data))
+    }
+
+    /// Downloads the bytes from `start` up to `end`.
+    fn download(&mut self, start: u64, end: u64) -> std::io::Result<Box<[u8]>> {
+        let range = format!("bytes={start}-{}", end - 1);
+        let response = self.agent.get(&self.url).set("Range", &range).call().map_err(request_error)?;
+        self.requests += 1;
+        ensure_partial(&response)?;
+
+        let length = end - start;
+        let mut data = Vec::with_capacity(length as usize);
+        // Anything that goes wrong while the body is downloading is a problem with the connection
+        response
+            .into_reader()
+            .take(length)
+            .read_to_end(&mut data)
+            .map_err(|error| std::io::Error::new(ErrorKind::TimedOut, error))?;
+        self.downloaded += data.len() as u64;
+        match data.len() as u64 == length {
+            true => Ok(data.into_boxed_slice()),
+            false => Err(std::io::Error::new(
+                ErrorKind::TimedOut,
+                "Connection closed before the whole range was read",
+            )),
+        }
+    }
+}
+
+/// Returns an error if the server sent the whole file instead of only the range that was asked for.
+fn ensure_partial(response: &ureq::Response) -> std::io::Result<()> {
+    match response.status() {
+        206 => Ok(()),
+        status => Err(std::io::Error::new(
+            ErrorKind::Unsupported,
+            format!("Server doesn't support range requests (status {status})"),
+        )),
+    }
+}
+
+/// Converts a failed request into an I/O error, where connection problems are reported as timeouts so they
+/// can be retried.
+fn request_error(error: ureq::Error) -> std::io::Error {
+    match error {
+        ureq::Error::Status(status, _) => {
+            std::io::Error::other(format!("Request failed with status {status}"))
+        }
+        ureq::Error::Transport(transport) => match transport.kind() {
+            ureq::ErrorKind::ConnectionFailed | ureq::ErrorKind::Io => {
+                std::io::Error::new(ErrorKind::TimedOut, transport)
+            }
+            _ => std::io::Error::other(transport),
+        },
+    }
+}
+
+impl Read for HttpReader {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        if buffer.is_empty() || self.position >= self.length {
+            return Ok(0);
+        }
+        let offset = (self.position % self.chunk_size) as usize;
+        let chunk = self.chunk(self.position / self.chunk_size)?;
+        let length = buffer.len().min(chunk.len().saturating_sub(offset));
+        buffer[..length].copy_from_slice(&chunk[offset..offset + length]);
+        self.position += length as u64;
+        Ok(length)
+    }
+}
+
+impl Seek for HttpReader {
+    #[inline]
+    fn seek(&mut self, seek: SeekFrom) -> std::io::Result<u64> {
+        self.position = seek_position(seek, self.position, self.length)?;
+        Ok(self.position)
+    }
+}
+
+impl IntoDataStream for HttpReader {
+    type Reader = DataStream<Self>;
+
+    #[inline]
+    fn into_stream(self, endian: Endian) -> Self::Reader {
+        DataStream::new(self, endian)
+    }
+}
@@ -4,7 +4,37 @@
 //! take a significant amount of time, and "deep identification", which is allowed to perform any
 //! computation even if it may take multiple seconds, along with allowing recursion into nested
 //! types.
+//!
+//! Formats that can be recognized from their header alone can use a [`Signature`] instead of
+//! writing their own scanning code, which keeps basic identification fast.
+//!
+//! # Usage
+//! ```
+//! use orthrus_core::identify::{Check, Signature};
+//! use orthrus_core::prelude::*;
+//!
+//! // RARC: magic, a file size matching the data, and a header size of 0x20
+//! let rarc = Signature::new()
+//!     .magic(0, b"RARC")
+//!     .u32(4, Endian::Big, Check::EqualsDataLength)
+//!     .u32(8, Endian::Big, Check::Equals(0x20));
+//!
+//! let mut data = b"RARC\0\0\0\x40\0\0\0\x20".to_vec();
+//! data.resize(0x40, 0);
+//! assert!(rarc.matches(&data));
+//! assert!(!rarc.matches(&data[..0x30]));
+//!
+//! // Either byte order, as long as the version is sane
+//! let bntx = Signature::new().magic(0, b"BNTX").any_of([
+//!     Signature::new().magic(0xC, b"\xFF\xFE"),
+//!     Signature::new().magic(0xC, b"\xFE\xFF"),
+//! ]);
+//! assert!(bntx.identify(b"BNTX\0\0\0\0\0\0\x04\0\xFF\xFE", "NX Texture container").is_some());
+//! ```
+
+use core::ops::RangeInclusive;
 
+use crate::data::Endian;
 #[cfg(not(feature = "std"))]
 use crate::no_std::*;
 
@@ -45,3 +75,153 @@ pub trait FileIdentifier {
 /// Type alias for [`identify`](FileIdentifier::identify) and
 /// [`identify_deep`](FileIdentifier::identify_deep).
 pub type IdentifyFn = fn(&[u8]) -> Option<FileInfo>;
+
+/// Condition that a numeric field must meet for a [`Signature`] to match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Check {
+    /// The field must be exactly this value.
+    Equals(u64),
+    /// The field must be within this range, such as a known set of versions.
+    Range(RangeInclusive<u64>),
+    /// The field must be no larger than the data, such as an offset or the size of a section.
+    WithinData,
+    /// The field must be exactly the size of the data, such as a header's file size.
+    EqualsDataLength,
+}
+
+impl Check {
+    #[inline]
+    fn check(&self, value: u64, data: &[u8]) -> bool {
+        match self {
+            Self::Equals(expected) => value == *expected,
+            Self::Range(range) => range.contains(&value),
+            Self::WithinData => value <= data.len() as u64,
+            Self::EqualsDataLength => value == data.len() as u64,
+        }
+    }
+}
+
+/// A single rule inside of a [`Signature`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Rule {
+    MinLength(usize),
+    Magic {
+        offset: usize,
+        bytes: &'static [u8],
+    },
+    Field {
+        offset: usize,
+        size: usize,
+        endian: Endian,
+        check: Check,
+    },
+    AnyOf(Vec<Signature>),
+    Not(Signature),
+}
+
+impl Rule {
+    fn matches(&self, data: &[u8]) -> bool {
+        match self {
+            Self::MinLength(length) => data.len() >= *length,
+            Self::Magic { offset, bytes } => data.get(*offset..offset + bytes.len()) == Some(bytes),
+            Self::Field { offset, size, endian, check } => {
+                let Some(field) = data.get(*offset..offset + size) else {
+                    return false;
+                };
+                let value = match endian {
+                    Endian::Little => field.iter().rev().fold(0, |value, &byte| value << 8 | u64::from(byte)),
+                    Endian::Big => field.iter().fold(0, |value, &byte| value << 8 | u64::from(byte)),
+                };
+                check.check(value, data)
+            }
+            Self::AnyOf(signatures) => signatures.iter().any(|signature| signature.matches(data)),
+            Self::Not(signature) => !signature.matches(data),
+        }
+    }
+}
+
+/// A declarative description of a format's header, built from simple rules which must all match.
+///
+/// Rules are checked in the order they're added, so cheap and unique checks (such as the magic) should be
+/// added first. See the [module documentation](self) for an example.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct Signature {
+    rules: Vec<Rule>,
+}
+
+impl Signature {
+    /// Creates an empty signature, which matches any data.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Requires the data to be at least `length` bytes long.
+    #[inline]
+    pub fn min_length(mut self, length: usize) -> Self {
+        self.rules.push(Rule::MinLength(length));
+        self
+    }
+
+    /// Requires `bytes` to be at `offset`.
+    #[inline]
+    pub fn magic(mut self, offset: usize, bytes: &'static [u8]) -> Self {
+        self.rules.push(Rule::Magic { offset, bytes });
+        self
+    }
+
+    /// Requires the u16 at `offset` to pass `check`.
+    #[inline]
+    pub fn u16(self, offset: usize, endian: Endian, check: Check) -> Self {
+        self.field(offset, 2, endian, check)
+    }
+
+    /// Requires the u32 at `offset` to pass `check`.
+    #[inline]
+    pub fn u32(self, offset: usize, endian: Endian, check: Check) -> Self {
+        self.field(offset, 4, endian, check)
+    }
+
+    /// Requires the u64 at `offset` to pass `check`.
+    #[inline]
+    pub fn u64(self, offset: usize, endian: Endian, check: Check) -> Self {
+        self.field(offset, 8, endian, check)
+    }
+
+    #[inline]
+    fn field(mut self, offset: usize, size: usize, endian: Endian, check: Check) -> Self {
+        self.rules.push(Rule::Field { offset, size, endian, check });
+        self
+    }
+
+    /// Requires at least one of `signatures` to match, such as for formats with multiple magics.
+    #[inline]
+    pub fn any_of<I: IntoIterator<Item = Signature>>(mut self, signatures: I) -> Self {
+        self.rules.push(Rule::AnyOf(signatures.into_iter().collect()));
+        self
+    }
+
+    /// Requires `signature` to not match, such as for excluding a similar format.
+    #[inline]
+    pub fn not(mut self, signature: Signature) -> Self {
+        self.rules.push(Rule::Not(signature));
+        self
+    }
+
+    /// Returns whether every rule matches the data.
+    #[must_use]
+    #[inline]
+    pub fn matches(&self, data: &[u8]) -> bool {
+        self.rules.iter().all(|rule| rule.matches(data))
+    }
+
+    /// Returns a [`FileInfo`] containing `info` if the signature matches, for use in
+    /// [`identify`](FileIdentifier::identify).
+    #[must_use]
+    #[inline]
+    pub fn identify(&self, data: &[u8], info: &str) -> Option<FileInfo> {
+        self.matches(data).then(|| FileInfo::new(info.to_owned(), None))
+    }
+}
@@ -6,27 +6,58 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-#[cfg(feature = "alloc")]
-#[expect(unused_imports, reason = "TODO: verify no_std still works")]
+#[cfg(all(feature = "alloc", not(feature = "std")))]
 mod no_std {
     extern crate alloc;
-    use alloc::boxed::Box;
-    use alloc::format;
-    use alloc::string::String;
+    pub use alloc::boxed::Box;
+    pub use alloc::format;
+    pub use alloc::string::String;
+    pub use alloc::vec::Vec;
 }
 
 pub mod prelude;
 
+// Dev-dependencies are only used by benchmarks, but are still passed to the unit test target
+#[cfg(test)]
+use criterion as _;
+
 // Enable any crates that don't have dependencies by default
+pub mod checksum;
+pub mod coordinates;
 pub mod data;
+pub mod limits;
 pub mod util;
 
+#[cfg(feature = "alloc")]
+pub mod cancel;
+#[cfg(feature = "std")]
+pub mod extract;
 #[cfg(feature = "std")]
 pub mod identify;
+#[cfg(feature = "std")]
+pub mod sanitize;
 
 // Optional crates
+#[cfg(feature = "crypto")]
+pub mod aes;
+
+#[cfg(feature = "arena")]
+pub mod arena;
+
 #[cfg(feature = "certificate")]
 pub mod certificate;
 
+#[cfg(feature = "fixed")]
+pub mod fixed;
+
+#[cfg(feature = "http")]
+pub mod http;
+
+#[cfg(feature = "synthetic")]
+pub mod synthetic;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
 #[cfg(feature = "time")]
 pub mod time;
@@ -0,0 +1,103 @@
+//! Resource limits for safely handling untrusted input.
+//!
+//! Most formats store sizes and counts in their headers, which are trusted when allocating buffers. This is
+//! fine for known-good game files, but a malicious file can easily claim a 4GB output or billions of entries,
+//! and exhaust all available memory before any actual data is read.
+//!
+//! [`ResourceLimits`] can be passed to any of the `*_with_limits` functions to cap these values, which will
+//! return an error wrapping [`LimitError`] instead of allocating. The default limits are unlimited, which
+//! matches the behavior of the functions that don't take limits.
+//!
+//! # Usage
+//! ```
+//! use orthrus_core::limits::{LimitError, ResourceLimits};
+//!
+//! let limits = ResourceLimits { max_output_size: 0x100_0000, ..Default::default() };
+//! assert!(limits.check_output_size(0x1000).is_ok());
+//! assert!(matches!(limits.check_output_size(0xFFFF_FFFF), Err(LimitError::OutputTooLarge { .. })));
+//! ```
+
+use snafu::prelude::*;
+
+/// Error conditions for when a [`ResourceLimits`] is exceeded.
+#[derive(Debug, Snafu, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LimitError {
+    /// Thrown if the output would be larger than the maximum allowed size.
+    #[snafu(display("Output size {size:#X} exceeds the limit of {limit:#X} bytes!"))]
+    OutputTooLarge { size: u64, limit: u64 },
+
+    /// Thrown if there are more entries than the maximum allowed.
+    #[snafu(display("Entry count {count} exceeds the limit of {limit} entries!"))]
+    TooManyEntries { count: u64, limit: u64 },
+
+    /// Thrown if nesting goes deeper than the maximum allowed.
+    #[snafu(display("Nesting depth {depth} exceeds the limit of {limit}!"))]
+    TooDeep { depth: u32, limit: u32 },
+}
+
+/// Caps on the resources that parsing a single input is allowed to use.
+///
+/// Each format applies these where it makes sense:
+/// * `max_output_size` is the maximum number of bytes allocated for output, such as a decompression buffer
+///   or the sum of all files in an archive.
+/// * `max_entries` is the maximum number of entries, such as files in an archive or objects in a scene.
+/// * `max_depth` is the maximum level of nesting, such as nested directories or objects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResourceLimits {
+    /// Maximum number of bytes that can be allocated for output.
+    pub max_output_size: u64,
+    /// Maximum number of entries that can be read.
+    pub max_entries: u64,
+    /// Maximum level of nesting that can be read.
+    pub max_depth: u32,
+}
+
+impl ResourceLimits {
+    /// No limits, which is equivalent to trusting the input.
+    pub const UNLIMITED: Self =
+        Self { max_output_size: u64::MAX, max_entries: u64::MAX, max_depth: u32::MAX };
+
+    /// Returns an error if `size` is larger than `max_output_size`.
+    ///
+    /// # Errors
+    /// Returns [`OutputTooLarge`](LimitError::OutputTooLarge) if the limit is exceeded.
+    #[inline]
+    pub const fn check_output_size(&self, size: u64) -> Result<(), LimitError> {
+        match size > self.max_output_size {
+            true => Err(LimitError::OutputTooLarge { size, limit: self.max_output_size }),
+            false => Ok(()),
+        }
+    }
+
+    /// Returns an error if `count` is larger than `max_entries`.
+    ///
+    /// # Errors
+    /// Returns [`TooManyEntries`](LimitError::TooManyEntries) if the limit is exceeded.
+    #[inline]
+    pub const fn check_entries(&self, count: u64) -> Result<(), LimitError> {
+        match count > self.max_entries {
+            true => Err(LimitError::TooManyEntries { count, limit: self.max_entries }),
+            false => Ok(()),
+        }
+    }
+
+    /// Returns an error if `depth` is larger than `max_depth`.
+    ///
+    /// # Errors
+    /// Returns [`TooDeep`](LimitError::TooDeep) if the limit is exceeded.
+    #[inline]
+    pub const fn check_depth(&self, depth: u32) -> Result<(), LimitError> {
+        match depth > self.max_depth {
+            true => Err(LimitError::TooDeep { depth, limit: self.max_depth }),
+            false => Ok(()),
+        }
+    }
+}
+
+impl Default for ResourceLimits {
+    #[inline]
+    fn default() -> Self {
+        Self::UNLIMITED
+    }
+}
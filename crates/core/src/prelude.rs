@@ -5,18 +5,74 @@
 //! use orthrus_core::prelude::*;
 //! ```
 
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use crate::cancel::{CancellationToken, Cancelled, Progress};
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use crate::data::DataCursor;
 #[doc(inline)]
 pub use crate::data::{
-    DataCursor, DataCursorMut, DataCursorRef, DataError, DataStream, Endian, IntoDataStream, ReadExt,
+    BoundedReader, DataCursorMut, DataCursorRef, DataError, Endian, IntoDataStream, ReadExt, ScopedSeek,
     SeekExt, Utf8ErrorSource, WriteExt,
 };
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use crate::data::{BufferedWriter, DataStream, IoReader, RetryPolicy};
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use crate::extract::{ExtractMode, Extraction, OverwritePolicy};
+#[cfg(feature = "std")]
 #[doc(inline)]
 pub use crate::identify::{FileIdentifier, FileInfo, IdentifyFn};
+#[doc(inline)]
+pub use crate::limits::{LimitError, ResourceLimits};
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use crate::sanitize::{PathError, PathOptions};
 
-/// Includes [`util::format_size`], which allows for pretty-print of various lengths.
+/// Includes [`identify::Signature`] and [`identify::Check`], for describing formats declaratively.
+#[cfg(feature = "std")]
+pub mod identify {
+    #[doc(inline)]
+    pub use crate::identify::{Check, Signature};
+}
+
+/// Includes [`util::format_size`], which allows for pretty-print of various lengths,
+/// [`util::Replacement`] for in-place archive edits, [`util::PackReport`] for the layout of rebuilt
+/// archives, and [`util::SparseArray`] and [`util::BitArray`] for reading index masks. With the `std`
+/// feature, this also includes [`util::output_path`] and the other helpers for naming output files.
 pub mod util {
     #[doc(inline)]
-    pub use crate::util::format_size;
+    pub use crate::util::Replacement;
+    #[cfg(feature = "std")]
+    #[doc(inline)]
+    pub use crate::util::{append_suffix, map_into, output_dir, output_path};
+    #[cfg(feature = "alloc")]
+    #[doc(inline)]
+    pub use crate::util::{format_size, BitArray, PackReport, PackedMember, SparseArray};
+}
+
+/// Includes [`checksum::md5`] and [`checksum::crc32`] along with their streaming versions, for verifying file
+/// integrity, and [`checksum::HashingReader`] and [`checksum::HashingWriter`] for hashing data as it's read
+/// or written.
+pub mod checksum {
+    #[doc(inline)]
+    pub use crate::checksum::{crc32, md5, Checksum, Crc32, HashingReader, HashingWriter, Md5};
+}
+
+/// Includes [`coordinates::CoordinateSystem`] and [`coordinates::Conversion`], for converting positions,
+/// rotations, and transforms between engines.
+pub mod coordinates {
+    #[doc(inline)]
+    pub use crate::coordinates::{Conversion, CoordinateSystem};
+}
+
+/// Includes [`aes::Aes128`] and [`aes::Xts`], for decrypting console file systems with user-provided keys.
+#[cfg(feature = "crypto")]
+pub mod aes {
+    #[doc(inline)]
+    pub use crate::aes::{Aes128, Xts};
 }
 
 /// Includes all time functionality, for working with timestamps and the current time.
@@ -0,0 +1,186 @@
+//! Sanitization of file names stored inside archives, before they're used as paths on disk.
+//!
+//! Archive formats store each file's name as a plain string, which is trusted when extracting. A malicious
+//! archive can use an absolute path or `..` components to write outside of the output folder (also known as
+//! "zip slip"), and names that are fine on one platform can be invalid on another, such as `aux.dat` or
+//! `file?.txt` on Windows.
+//!
+//! [`PathOptions`] is used by every extractor to turn a stored name into a path relative to the output
+//! folder. By default, unsafe names are rejected with a [`PathError`], and Windows-reserved names are
+//! escaped.
+//!
+//! # Usage
+//! ```
+//! use std::path::Path;
+//!
+//! use orthrus_core::sanitize::{PathError, PathOptions};
+//!
+//! let options = PathOptions::default();
+//! assert_eq!(options.sanitize("models/chest.bam")?, Path::new("models/chest.bam"));
+//! assert_eq!(options.sanitize("data\\con.txt")?, Path::new("data/_con.txt"));
+//! assert!(matches!(options.sanitize("../../.bashrc"), Err(PathError::ParentTraversal { .. })));
+//! // A drive letter anywhere in the name would replace the whole path on Windows
+//! assert!(matches!(options.sanitize("a/C:/x"), Err(PathError::AbsolutePath { .. })));
+//!
+//! // Unsafe components can be dropped instead of rejecting the whole name
+//! let options = PathOptions { reject_unsafe: false, lowercase: true, ..Default::default() };
+//! assert_eq!(options.sanitize("/Stage/../Map.ARC")?, Path::new("stage/map.arc"));
+//! let options = PathOptions { reject_unsafe: false, escape_reserved: false, ..Default::default() };
+//! assert_eq!(options.sanitize("a/C:/x")?, Path::new("a/x"));
+//! assert_eq!(options.sanitize("C:foo")?, Path::new("foo"));
+//! # Ok::<(), PathError>(())
+//! ```
+
+use std::path::{Component, Path, PathBuf};
+
+use snafu::prelude::*;
+
+/// Error conditions for when a stored name can't safely be used as a path.
+#[derive(Debug, Snafu, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PathError {
+    /// Thrown if the name is an absolute path, or contains a drive letter.
+    #[snafu(display("Refusing to extract absolute path {name:?}!"))]
+    AbsolutePath { name: String },
+
+    /// Thrown if the name contains a `..` component, which could escape the output folder.
+    #[snafu(display("Refusing to extract {name:?}, which escapes the output folder!"))]
+    ParentTraversal { name: String },
+
+    /// Thrown if nothing is left of the name after sanitization.
+    #[snafu(display("Unable to extract {name:?}, which has an empty path!"))]
+    EmptyPath { name: String },
+}
+
+/// Names which Windows reserves for devices, regardless of extension.
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Controls how names stored inside of an archive are turned into paths when extracting.
+///
+/// Each field is independent:
+/// * `reject_unsafe` returns an error for absolute paths, drive letters, and `..` components, otherwise
+///   they're removed.
+/// * `lowercase` converts every name to lowercase, for games that look up files case-insensitively.
+/// * `escape_reserved` replaces characters that are invalid on Windows with `_`, and prefixes reserved device
+///   names (such as `CON` or `LPT1`) with `_`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PathOptions {
+    /// Whether to reject unsafe names, instead of removing the unsafe components.
+    pub reject_unsafe: bool,
+    /// Whether to convert all names to lowercase.
+    pub lowercase: bool,
+    /// Whether to escape names that aren't valid on Windows.
+    pub escape_reserved: bool,
+}
+
+impl PathOptions {
+    /// Rejects unsafe names and escapes Windows-reserved names, but otherwise keeps names as-is.
+    pub const DEFAULT: Self = Self { reject_unsafe: true, lowercase: false, escape_reserved: true };
+
+    /// Converts a stored name into a relative path, using either `/` or `\` as separators.
+    ///
+    /// # Errors
+    /// Returns [`AbsolutePath`](PathError::AbsolutePath) or [`ParentTraversal`](PathError::ParentTraversal)
+    /// if the name is unsafe and `reject_unsafe` is set, or [`EmptyPath`](PathError::EmptyPath) if there's
+    /// nothing left of the name.
+    pub fn sanitize(&self, name: &str) -> Result<PathBuf, PathError> {
+        // Leading separators make a path absolute
+        let absolute = name.starts_with(['/', '\\']);
+        ensure!(!(absolute && self.reject_unsafe), AbsolutePathSnafu { name });
+
+        let mut path = PathBuf::new();
+        for component in name.split(['/', '\\']) {
+            // Drive letters (such as "C:") make a path absolute in any component, since pushing one replaces
+            // the whole path on Windows, so they're always removed even if reserved names aren't escaped
+            let component = match Self::strip_drive(component) {
+                Some(rest) => {
+                    ensure!(!self.reject_unsafe, AbsolutePathSnafu { name });
+                    rest
+                }
+                None => component,
+            };
+
+            match component {
+                "" | "." => {}
+                ".." => ensure!(!self.reject_unsafe, ParentTraversalSnafu { name }),
+                component => {
+                    let component = match self.lowercase {
+                        true => component.to_lowercase(),
+                        false => component.to_owned(),
+                    };
+                    let component = match self.escape_reserved {
+                        true => Self::escape(&component),
+                        false => component,
+                    };
+
+                    // Anything else that this platform treats as absolute would also replace the whole path
+                    let absolute = Path::new(&component)
+                        .components()
+                        .any(|part| matches!(part, Component::Prefix(_) | Component::RootDir));
+                    match absolute {
+                        true => ensure!(!self.reject_unsafe, AbsolutePathSnafu { name }),
+                        false => path.push(component),
+                    }
+                }
+            }
+        }
+
+        ensure!(path.components().next().is_some(), EmptyPathSnafu { name });
+        Ok(path)
+    }
+
+    /// Sanitizes a stored name and joins it onto the output folder.
+    ///
+    /// # Errors
+    /// Returns an error if the name is unsafe or empty, see [`sanitize`](Self::sanitize).
+    #[inline]
+    pub fn join<P: AsRef<Path>>(&self, output: P, name: &str) -> Result<PathBuf, PathError> {
+        Ok(output.as_ref().join(self.sanitize(name)?))
+    }
+
+    /// Returns the rest of a path component if it starts with one or more drive letters.
+    fn strip_drive(component: &str) -> Option<&str> {
+        let mut rest = None;
+        let mut remaining = component;
+        while let [letter, b':', ..] = remaining.as_bytes() {
+            if !letter.is_ascii_alphabetic() {
+                break;
+            }
+            remaining = &remaining[2..];
+            rest = Some(remaining);
+        }
+        rest
+    }
+
+    /// Escapes a single path component so that it's valid on Windows.
+    fn escape(component: &str) -> String {
+        let mut escaped: String = component
+            .chars()
+            .map(|c| match c {
+                '<' | '>' | ':' | '"' | '|' | '?' | '*' | '\0'..='\x1F' => '_',
+                c => c,
+            })
+            .collect();
+
+        // Windows silently strips trailing dots and spaces, which could make two files collide
+        if escaped.ends_with(['.', ' ']) {
+            escaped.push('_');
+        }
+
+        let stem = escaped.split('.').next().unwrap_or_default();
+        if RESERVED_NAMES.iter().any(|reserved| stem.eq_ignore_ascii_case(reserved)) {
+            escaped.insert(0, '_');
+        }
+        escaped
+    }
+}
+
+impl Default for PathOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
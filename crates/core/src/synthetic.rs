@@ -0,0 +1,114 @@
+//! Deterministic synthetic data generators, for benchmarking and testing without real game files.
+//!
+//! Compression performance depends heavily on what the input looks like, so this module provides a few
+//! generators that approximate the kinds of data commonly found in game archives:
+//! * [`random`] is incompressible noise, which is the worst case for any match finder.
+//! * [`repeating`] is a single pattern repeated over and over, which is the best case (and exercises
+//!   long run-length copies during decompression).
+//! * [`structured`] mixes literals, short back-references, and runs, which is closer to real assets like
+//!   models, tables, and text.
+//!
+//! All generators are seeded and fully deterministic, so results are reproducible across runs.
+//!
+//! # Usage
+//! ```
+//! use orthrus_core::synthetic;
+//!
+//! let data = synthetic::structured(0x1000, 0x1234);
+//! assert_eq!(data.len(), 0x1000);
+//! assert_eq!(data, synthetic::structured(0x1000, 0x1234));
+//! ```
+
+extern crate alloc;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Simple xorshift64* PRNG, good enough for generating test data without any extra dependencies.
+struct Rng(u64);
+
+impl Rng {
+    #[inline]
+    fn new(seed: u64) -> Self {
+        // Zero is a fixed point for xorshift, so make sure we never start there.
+        Self(seed ^ 0x9E37_79B9_7F4A_7C15)
+    }
+
+    #[inline]
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    #[inline]
+    fn below(&mut self, max: usize) -> usize {
+        (self.next() % max as u64) as usize
+    }
+}
+
+/// Generates `length` bytes of pseudo-random data from the given seed.
+#[must_use]
+pub fn random(length: usize, seed: u64) -> Box<[u8]> {
+    let mut rng = Rng::new(seed);
+    let mut output = Vec::with_capacity(length + 8);
+    while output.len() < length {
+        output.extend_from_slice(&rng.next().to_le_bytes());
+    }
+    output.truncate(length);
+    output.into_boxed_slice()
+}
+
+/// Generates `length` bytes consisting of `pattern` repeated as many times as needed.
+///
+/// # Panics
+/// Panics if `pattern` is empty.
+#[must_use]
+pub fn repeating(length: usize, pattern: &[u8]) -> Box<[u8]> {
+    assert!(!pattern.is_empty(), "pattern must not be empty");
+    pattern.iter().copied().cycle().take(length).collect()
+}
+
+/// Generates `length` bytes of moderately compressible data from the given seed.
+///
+/// The output is a mix of random literals, copies from earlier in the buffer at varying distances, and
+/// single-byte runs, which roughly approximates the match distribution of real game data.
+#[must_use]
+pub fn structured(length: usize, seed: u64) -> Box<[u8]> {
+    let mut rng = Rng::new(seed);
+    let mut output = vec![0u8; length];
+    let mut position = 0;
+
+    while position < length {
+        let remaining = length - position;
+        match rng.below(8) {
+            // Back-reference into previously generated data
+            0..=3 if position >= 3 => {
+                let distance = 1 + rng.below(position.min(0x1000));
+                let count = (3 + rng.below(30)).min(remaining);
+                for n in 0..count {
+                    output[position + n] = output[position + n - distance];
+                }
+                position += count;
+            }
+            // Run of a single byte
+            4 => {
+                let count = (4 + rng.below(60)).min(remaining);
+                let value = rng.next() as u8;
+                output[position..position + count].fill(value);
+                position += count;
+            }
+            // Short string of literals
+            _ => {
+                let count = (1 + rng.below(16)).min(remaining);
+                for byte in &mut output[position..position + count] {
+                    *byte = rng.next() as u8;
+                }
+                position += count;
+            }
+        }
+    }
+
+    output.into_boxed_slice()
+}
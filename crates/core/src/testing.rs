@@ -0,0 +1,215 @@
+//! Round-trip fidelity helpers, for verifying that a writer produces data that its parser reads back
+//! identically.
+//!
+//! Each round trip runs parse → write → parse, and compares the two parsed values. Writers aren't required to
+//! be byte-identical to the original file (padding and ordering may differ), but whether they are is reported
+//! too, since a byte-identical writer is the easiest way to prove nothing was lost.
+//!
+//! When the parsed values differ, the first divergence is reported using their pretty-printed [`Debug`]
+//! output, along with a few lines of context from both sides, so no extra comparison code is needed per type.
+//!
+//! # Usage
+//! ```
+//! use orthrus_core::testing;
+//!
+//! #[derive(Debug, PartialEq)]
+//! struct Entry {
+//!     values: Vec<u8>,
+//! }
+//!
+//! let parse = |data: &[u8]| -> Result<Entry, &str> {
+//!     let (&length, rest) = data.split_first().ok_or("empty")?;
+//!     Ok(Entry { values: rest.get(..length.into()).ok_or("truncated")?.to_vec() })
+//! };
+//! let write = |entry: &Entry| -> Result<Vec<u8>, &str> {
+//!     let mut output = vec![entry.values.len() as u8];
+//!     output.extend(&entry.values);
+//!     Ok(output)
+//! };
+//!
+//! let result = testing::round_trip(&[3, 1, 2, 3], parse, write)?;
+//! assert!(result.byte_identical);
+//!
+//! // Trailing data is dropped when writing, which is fine structurally
+//! let result = testing::round_trip(&[3, 1, 2, 3, 0xFF], parse, write)?;
+//! assert_eq!(result.first_byte_difference, Some(4));
+//! # Ok::<(), testing::Divergence>(())
+//! ```
+//!
+//! For a whole directory of sample files, use [`round_trip_corpus`] and [`CorpusReport::assert_ok`].
+
+use core::fmt::{Debug, Display};
+use std::path::{Path, PathBuf};
+
+use snafu::prelude::*;
+
+/// Number of lines of context to show on each side of a mismatch.
+const CONTEXT_LINES: usize = 3;
+
+/// Describes the first point where a round trip diverged.
+#[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+#[non_exhaustive]
+pub enum Divergence {
+    /// Thrown if the original data fails to parse.
+    #[snafu(display("Failed to parse the original data: {error}"))]
+    ParseFailed { error: String },
+
+    /// Thrown if writing the parsed data fails.
+    #[snafu(display("Failed to write the parsed data: {error}"))]
+    WriteFailed { error: String },
+
+    /// Thrown if the written data fails to parse.
+    #[snafu(display("Failed to parse the written data: {error}"))]
+    ReparseFailed { error: String },
+
+    /// Thrown if the reparsed data doesn't match the original, where `line` is the first line of the
+    /// [`Debug`] output that differs, and `expected`/`actual` contain the surrounding lines.
+    #[snafu(display(
+        "Parsed data differs at line {}:\n--- expected\n{}\n+++ actual\n{}",
+        line + 1,
+        expected.join("\n"),
+        actual.join("\n")
+    ))]
+    Mismatch {
+        line: usize,
+        expected: Vec<String>,
+        actual: Vec<String>,
+    },
+}
+
+/// Result of a successful round trip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundTrip {
+    /// Whether the written data is identical to the original data
+    pub byte_identical: bool,
+    /// Offset of the first byte that differs, if any (including when one is longer than the other)
+    pub first_byte_difference: Option<usize>,
+}
+
+/// Returns the offset of the first byte that differs between two slices.
+fn first_difference(a: &[u8], b: &[u8]) -> Option<usize> {
+    match a.iter().zip(b).position(|(a, b)| a != b) {
+        Some(offset) => Some(offset),
+        None => (a.len() != b.len()).then_some(a.len().min(b.len())),
+    }
+}
+
+/// Compares the pretty-printed form of two values, and returns the first line that differs along with the
+/// surrounding lines from each.
+fn compare<T: Debug>(expected: &T, actual: &T) -> Divergence {
+    let (expected, actual) = (format!("{expected:#?}"), format!("{actual:#?}"));
+    let (expected, actual): (Vec<&str>, Vec<&str>) = (expected.lines().collect(), actual.lines().collect());
+
+    // If the Debug output is identical, the difference is somewhere PartialEq can see but Debug can't
+    let line = (0..expected.len().max(actual.len())).find(|&n| expected.get(n) != actual.get(n)).unwrap_or(0);
+    let context = |lines: &[&str]| -> Vec<String> {
+        let start = line.saturating_sub(CONTEXT_LINES);
+        let end = (line + CONTEXT_LINES + 1).min(lines.len());
+        lines.iter().take(end).skip(start).map(|&line| line.to_owned()).collect()
+    };
+    Divergence::Mismatch { line, expected: context(&expected), actual: context(&actual) }
+}
+
+/// Runs a single parse → write → parse round trip over `data`, and checks that both parsed values are equal.
+///
+/// # Errors
+/// Returns the first [`Divergence`], if parsing or writing fails, or if the parsed values don't match.
+pub fn round_trip<T, E, O, P, W>(data: &[u8], parse: P, write: W) -> Result<RoundTrip, Divergence>
+where
+    T: PartialEq + Debug,
+    E: Display,
+    O: AsRef<[u8]>,
+    P: Fn(&[u8]) -> Result<T, E>,
+    W: Fn(&T) -> Result<O, E>,
+{
+    let original = parse(data).map_err(|error| Divergence::ParseFailed { error: error.to_string() })?;
+    let written = write(&original).map_err(|error| Divergence::WriteFailed { error: error.to_string() })?;
+    let written = written.as_ref();
+    let reparsed = parse(written).map_err(|error| Divergence::ReparseFailed { error: error.to_string() })?;
+
+    if original != reparsed {
+        return Err(compare(&original, &reparsed));
+    }
+
+    let first_byte_difference = first_difference(data, written);
+    Ok(RoundTrip { byte_identical: first_byte_difference.is_none(), first_byte_difference })
+}
+
+/// Results from running [`round_trip_corpus`] over a directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorpusReport {
+    /// Files that round-tripped successfully, along with the result
+    pub passed: Vec<(PathBuf, RoundTrip)>,
+    /// Files that diverged, along with where
+    pub failed: Vec<(PathBuf, Divergence)>,
+}
+
+impl CorpusReport {
+    /// Returns the number of files that were written back byte-for-byte identically.
+    #[must_use]
+    #[inline]
+    pub fn byte_identical(&self) -> usize {
+        self.passed.iter().filter(|(_, result)| result.byte_identical).count()
+    }
+
+    /// Panics with the first divergence if any file failed to round-trip.
+    ///
+    /// # Panics
+    /// Panics if [`failed`](Self::failed) is not empty.
+    #[inline]
+    #[track_caller]
+    pub fn assert_ok(&self) {
+        if let Some((path, divergence)) = self.failed.first() {
+            panic!(
+                "{} of {} files failed to round-trip, first failure in {}:\n{divergence}",
+                self.failed.len(),
+                self.failed.len() + self.passed.len(),
+                path.display()
+            );
+        }
+    }
+}
+
+/// Recursively collects all files in a directory, optionally only including the given extension.
+fn collect_files(path: &Path, extension: Option<&str>, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(path)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, extension, files)?;
+        } else if extension.is_none_or(|extension| path.extension().is_some_and(|ext| ext == extension)) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Runs [`round_trip`] over every file in a directory (and its subdirectories), optionally only including
+/// files with the given extension.
+///
+/// # Errors
+/// Returns an error if the directory or any of its files can't be read. Files that fail to round-trip are
+/// reported in [`CorpusReport::failed`] instead.
+pub fn round_trip_corpus<T, E, O, P, W>(
+    directory: impl AsRef<Path>, extension: Option<&str>, parse: P, write: W,
+) -> std::io::Result<CorpusReport>
+where
+    T: PartialEq + Debug,
+    E: Display,
+    O: AsRef<[u8]>,
+    P: Fn(&[u8]) -> Result<T, E>,
+    W: Fn(&T) -> Result<O, E>,
+{
+    let mut files = Vec::new();
+    collect_files(directory.as_ref(), extension, &mut files)?;
+    files.sort();
+
+    let mut report = CorpusReport::default();
+    for path in files {
+        let data = std::fs::read(&path)?;
+        match round_trip(&data, &parse, &write) {
+            Ok(result) => report.passed.push((path, result)),
+            Err(divergence) => report.failed.push((path, divergence)),
+        }
+    }
+    Ok(report)
+}
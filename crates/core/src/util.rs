@@ -1,6 +1,11 @@
 //! Utility functions that can't be grouped into any other module.
 
-#[cfg(not(feature = "std"))]
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "alloc")]
+use crate::data::{DataError, ReadExt};
+#[cfg(all(feature = "alloc", not(feature = "std")))]
 use crate::no_std::*;
 
 /// Converts a file size in bytes to a human-readable format.
@@ -11,6 +16,7 @@ use crate::no_std::*;
 /// # Warnings
 /// This function uses f64, which on a 64-bit system will lose precision if the length is too large,
 /// but it should still round to a close-enough value.
+#[cfg(feature = "alloc")]
 #[must_use]
 #[inline]
 pub fn format_size(length: usize) -> String {
@@ -25,3 +31,471 @@ pub fn format_size(length: usize) -> String {
 
     format!("{:.2} {}", size, UNITS[unit_index])
 }
+
+/// Returns `output` if one was provided, or otherwise `input` with its extension replaced, for naming the
+/// result of converting a single file.
+///
+/// # Examples
+/// ```
+/// # use std::path::Path;
+/// # use orthrus_core::util::output_path;
+/// assert_eq!(output_path("Stage/map.szs", None::<&str>, "arc"), Path::new("Stage/map.arc"));
+/// assert_eq!(output_path("Stage/map.szs", Some("out.bin"), "arc"), Path::new("out.bin"));
+/// ```
+#[cfg(feature = "std")]
+#[must_use]
+#[inline]
+pub fn output_path<P: AsRef<Path>, O: Into<PathBuf>>(
+    input: P, output: Option<O>, extension: &str,
+) -> PathBuf {
+    match output {
+        Some(output) => output.into(),
+        None => input.as_ref().with_extension(extension),
+    }
+}
+
+/// Returns `output` if one was provided, or otherwise the current directory, for extracting files.
+#[cfg(feature = "std")]
+#[must_use]
+#[inline]
+pub fn output_dir<O: Into<PathBuf>>(output: Option<O>) -> PathBuf {
+    output.map_or_else(|| PathBuf::from("."), Into::into)
+}
+
+/// Adds `suffix` to the end of a file's name, before its extension.
+///
+/// # Examples
+/// ```
+/// # use std::path::Path;
+/// # use orthrus_core::util::append_suffix;
+/// assert_eq!(append_suffix("models/chest.bam", "_fixed"), Path::new("models/chest_fixed.bam"));
+/// assert_eq!(append_suffix("README", ".orig"), Path::new("README.orig"));
+/// ```
+#[cfg(feature = "std")]
+#[must_use]
+pub fn append_suffix<P: AsRef<Path>>(path: P, suffix: &str) -> PathBuf {
+    let path = path.as_ref();
+    let mut name = path.file_stem().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    if let Some(extension) = path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    path.with_file_name(name)
+}
+
+/// Moves `path` from inside of `root` to the same place inside of `output`, keeping any directories in
+/// between, or returns [`None`] if `path` isn't inside of `root`.
+///
+/// # Examples
+/// ```
+/// # use std::path::Path;
+/// # use orthrus_core::util::map_into;
+/// let path = map_into("game/data/Stage/map.arc", "game/data", "extracted");
+/// assert_eq!(path.as_deref(), Some(Path::new("extracted/Stage/map.arc")));
+/// assert_eq!(map_into("other/map.arc", "game/data", "extracted"), None);
+/// ```
+#[cfg(feature = "std")]
+#[must_use]
+#[inline]
+pub fn map_into<P: AsRef<Path>, R: AsRef<Path>, O: AsRef<Path>>(
+    path: P, root: R, output: O,
+) -> Option<PathBuf> {
+    let relative = path.as_ref().strip_prefix(root).ok()?;
+    Some(output.as_ref().join(relative))
+}
+
+/// Result of trying to replace a file inside of an archive without rewriting the rest of the archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub enum Replacement {
+    /// The file was overwritten in-place, with any leftover space filled with zeroes.
+    InPlace,
+    /// The new contents don't fit in the space used by the old file (or the file is stored in a way that
+    /// can't be edited in-place), so nothing was changed and the archive needs to be fully rebuilt.
+    RebuildRequired,
+}
+
+/// Size of a single file inside of a [`PackReport`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PackedMember {
+    /// Path of the file inside of the container
+    pub path: String,
+    /// Offset of the file from the start of the container
+    pub offset: u64,
+    /// Size of the file as it's stored, which is the compressed size if the file is compressed
+    pub stored_size: u64,
+    /// Size of the file after decompression, which is the same as `stored_size` if it isn't compressed
+    pub size: u64,
+    /// Padding after the file, needed to align whatever comes next
+    pub padding: u64,
+}
+
+#[cfg(feature = "alloc")]
+impl PackedMember {
+    /// Creates a new entry for a file, for use by container writers.
+    #[must_use]
+    #[inline]
+    pub const fn new(path: String, offset: u64, stored_size: u64, size: u64, padding: u64) -> Self {
+        Self { path, offset, stored_size, size, padding }
+    }
+}
+
+/// Breakdown of where the space in a newly written container goes, to help with picking alignment and
+/// compression settings when rebuilding archives for platforms with limited space.
+///
+/// Displaying a report lists every file in the order it's stored, followed by the totals.
+///
+/// # Examples
+/// ```
+/// # use orthrus_core::util::{PackReport, PackedMember};
+/// let members = vec![
+///     PackedMember::new("a.bin".to_owned(), 0x40, 0x10, 0x10, 0x10),
+///     PackedMember::new("b.szs".to_owned(), 0x60, 0x18, 0x30, 0x8),
+/// ];
+/// let report = PackReport::new(members, 0x40);
+/// assert_eq!(report.total_size, 0x80);
+/// assert_eq!(report.padding(), 0x18);
+/// assert_eq!(report.size(), 0x40);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PackReport {
+    /// Every file in the container, in the order they're stored
+    pub members: Vec<PackedMember>,
+    /// Size of all headers and tables before the first file, including their padding
+    pub header_size: u64,
+    /// Size of the entire container
+    pub total_size: u64,
+}
+
+#[cfg(feature = "alloc")]
+impl PackReport {
+    /// Creates a new report, where the total size is the header followed by every file and its padding.
+    #[must_use]
+    #[inline]
+    pub fn new(members: Vec<PackedMember>, header_size: u64) -> Self {
+        let total_size =
+            header_size + members.iter().map(|member| member.stored_size + member.padding).sum::<u64>();
+        Self { members, header_size, total_size }
+    }
+
+    /// Returns the total size of every file as it's stored.
+    #[must_use]
+    #[inline]
+    pub fn stored_size(&self) -> u64 {
+        self.members.iter().map(|member| member.stored_size).sum()
+    }
+
+    /// Returns the total size of every file after decompression.
+    #[must_use]
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.members.iter().map(|member| member.size).sum()
+    }
+
+    /// Returns the total padding used to align files, which is the space that could be saved with a smaller
+    /// alignment.
+    #[must_use]
+    #[inline]
+    pub fn padding(&self) -> u64 {
+        self.members.iter().map(|member| member.padding).sum()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Display for PackReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(
+            f,
+            "{:>10} {:>10} {:>10} {:>8}  Path",
+            "Offset", "Stored", "Size", "Padding"
+        )?;
+        for member in &self.members {
+            writeln!(
+                f,
+                "{:>#10X} {:>#10X} {:>#10X} {:>#8X}  {}",
+                member.offset, member.stored_size, member.size, member.padding, member.path
+            )?;
+        }
+        let percent = |value: u64| match self.total_size {
+            0 => 0.0,
+            total => value as f64 * 100.0 / total as f64,
+        };
+        writeln!(
+            f,
+            "{} files, {} stored ({} after decompression)",
+            self.members.len(),
+            format_size(self.stored_size() as usize),
+            format_size(self.size() as usize)
+        )?;
+        write!(
+            f,
+            "Total size {}, with {} of headers ({:.1}%) and {} of alignment padding ({:.1}%)",
+            format_size(self.total_size as usize),
+            format_size(self.header_size as usize),
+            percent(self.header_size),
+            format_size(self.padding() as usize),
+            percent(self.padding())
+        )
+    }
+}
+
+/// A set of indices stored as sorted, non-overlapping ranges, such as Panda3D's `SparseArray`, which is
+/// compact for masks made up of long runs.
+///
+/// Each range includes `begin` but not `end`. If the array is inverted, it instead contains every index
+/// *except* the ones in its ranges, which makes it unbounded. Indices are signed to match how they're stored,
+/// but [`rank`](Self::rank), [`select`](Self::select), and [`iter`](Self::iter) only count from zero.
+///
+/// # Examples
+/// ```
+/// # use orthrus_core::util::SparseArray;
+/// let array = SparseArray::new(vec![(2, 5), (8, 10)], false);
+/// assert!(array.contains(4) && !array.contains(5));
+/// assert_eq!(array.iter().collect::<Vec<_>>(), [2, 3, 4, 8, 9]);
+/// assert_eq!(array.rank(9), 4);
+/// assert_eq!(array.select(3), Some(8));
+///
+/// let inverse = SparseArray::new(vec![(2, 5)], true);
+/// assert_eq!(inverse.iter().take(4).collect::<Vec<_>>(), [0, 1, 5, 6]);
+/// assert_eq!(inverse.len(), None);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SparseArray {
+    /// Every range of indices, sorted by where they begin
+    pub subranges: Vec<(i32, i32)>,
+    /// Whether the array contains every index outside of the ranges, instead of inside them
+    pub inverse: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl SparseArray {
+    /// Creates a new array from sorted, non-overlapping ranges.
+    #[must_use]
+    #[inline]
+    pub const fn new(subranges: Vec<(i32, i32)>, inverse: bool) -> Self {
+        Self { subranges, inverse }
+    }
+
+    /// Reads an array stored as the number of ranges, the beginning and end of each range, and then whether
+    /// the array is inverted.
+    ///
+    /// # Errors
+    /// Returns [`EndOfFile`](DataError::EndOfFile) if trying to read out of bounds.
+    pub fn read<T: ReadExt>(data: &mut T) -> Result<Self, DataError> {
+        let num_subranges = data.read_u32()?;
+        let mut subranges = Vec::with_capacity(num_subranges as usize);
+        for _ in 0..num_subranges {
+            let begin = data.read_i32()?;
+            let end = data.read_i32()?;
+            subranges.push((begin, end));
+        }
+        let inverse = data.read_u8()? != 0;
+        Ok(Self { subranges, inverse })
+    }
+
+    /// Returns whether the array contains `index`.
+    #[must_use]
+    #[inline]
+    pub fn contains(&self, index: i32) -> bool {
+        let next = self.subranges.partition_point(|&(_, end)| end <= index);
+        let stored = self.subranges.get(next).is_some_and(|&(begin, _)| begin <= index);
+        stored != self.inverse
+    }
+
+    /// Returns the number of indices in the array, or [`None`] if it's inverted.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> Option<u64> {
+        match self.inverse {
+            true => None,
+            false => Some(self.subranges.iter().map(|&(begin, end)| range_len(begin, end)).sum()),
+        }
+    }
+
+    /// Returns whether the array doesn't contain any indices.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    /// Returns how many indices from zero up to (but not including) `index` are in the array.
+    #[must_use]
+    pub fn rank(&self, index: i32) -> u64 {
+        let stored = self.subranges.iter().map(|&(begin, end)| range_len(begin.max(0), end.min(index))).sum();
+        match self.inverse {
+            true => range_len(0, index).saturating_sub(stored),
+            false => stored,
+        }
+    }
+
+    /// Returns the `n`th index in the array (counting from zero), or [`None`] if there aren't enough indices.
+    #[must_use]
+    pub fn select(&self, mut n: u64) -> Option<i32> {
+        // Walk over either the ranges themselves, or the gaps between them if inverted
+        let mut position = 0;
+        for &(begin, end) in &self.subranges {
+            let (begin, end) = (begin.max(position), end.max(position));
+            let (start, length) = match self.inverse {
+                true => (position, range_len(position, begin)),
+                false => (begin, range_len(begin, end)),
+            };
+            if n < length {
+                return i32::try_from(i64::from(start) + n as i64).ok();
+            }
+            n -= length;
+            position = end;
+        }
+        match self.inverse {
+            true => i32::try_from(i64::from(position) + i64::try_from(n).ok()?).ok(),
+            false => None,
+        }
+    }
+
+    /// Returns every index in the array from zero upwards, which continues up to [`i32::MAX`] if it's
+    /// inverted.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = i32> + '_ {
+        let end = match self.inverse {
+            true => i32::MAX,
+            false => self.subranges.last().map_or(0, |&(_, end)| end),
+        };
+        (0..end).filter(|&index| self.contains(index))
+    }
+}
+
+/// Returns the number of indices from `begin` up to `end`, or zero if the range is empty.
+#[cfg(feature = "alloc")]
+#[inline]
+fn range_len(begin: i32, end: i32) -> u64 {
+    u64::try_from(i64::from(end) - i64::from(begin)).unwrap_or(0)
+}
+
+/// A set of indices stored as a bitmask, such as Panda3D's `BitArray`, where every bit past the stored words
+/// matches the highest bit.
+///
+/// # Examples
+/// ```
+/// # use orthrus_core::util::BitArray;
+/// let array = BitArray::new(vec![0b1010_0110], false);
+/// assert!(array.contains(1) && !array.contains(3));
+/// assert_eq!(array.iter().collect::<Vec<_>>(), [1, 2, 5, 7]);
+/// assert_eq!(array.rank(6), 3);
+/// assert_eq!(array.select(3), Some(7));
+///
+/// let array = BitArray::new(vec![0b1], true);
+/// assert!(array.contains(1000));
+/// assert_eq!(array.select(1), Some(32));
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BitArray {
+    /// Every stored bit, starting from the lowest bit of the first word
+    pub words: Vec<u32>,
+    /// Whether every bit past the stored words is set
+    pub highest_bits: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl BitArray {
+    /// Number of bits in each word.
+    const WORD_BITS: usize = u32::BITS as usize;
+
+    /// Creates a new array from its words, and whether every bit past them is set.
+    #[must_use]
+    #[inline]
+    pub const fn new(words: Vec<u32>, highest_bits: bool) -> Self {
+        Self { words, highest_bits }
+    }
+
+    /// Reads an array stored as the number of words, each 32-bit word, and then whether every bit past them
+    /// is set.
+    ///
+    /// # Errors
+    /// Returns [`EndOfFile`](DataError::EndOfFile) if trying to read out of bounds.
+    pub fn read<T: ReadExt>(data: &mut T) -> Result<Self, DataError> {
+        let num_words = data.read_u32()?;
+        let mut words = Vec::with_capacity(num_words as usize);
+        for _ in 0..num_words {
+            words.push(data.read_u32()?);
+        }
+        let highest_bits = data.read_u8()? != 0;
+        Ok(Self { words, highest_bits })
+    }
+
+    /// Returns whether bit `index` is set.
+    #[must_use]
+    #[inline]
+    pub fn contains(&self, index: usize) -> bool {
+        match self.words.get(index / Self::WORD_BITS) {
+            Some(word) => word >> (index % Self::WORD_BITS) & 1 != 0,
+            None => self.highest_bits,
+        }
+    }
+
+    /// Returns the number of set bits, or [`None`] if every bit past the stored words is set.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> Option<usize> {
+        match self.highest_bits {
+            true => None,
+            false => Some(self.words.iter().map(|word| word.count_ones() as usize).sum()),
+        }
+    }
+
+    /// Returns whether no bits are set.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    /// Returns how many bits below `index` are set.
+    #[must_use]
+    pub fn rank(&self, index: usize) -> usize {
+        let (full, partial) = (index / Self::WORD_BITS, index % Self::WORD_BITS);
+        let stored: usize = self.words.iter().take(full).map(|word| word.count_ones() as usize).sum();
+        let remaining = match self.words.get(full) {
+            Some(word) => (word & ((1 << partial) - 1)).count_ones() as usize,
+            None if self.highest_bits => index - self.words.len() * Self::WORD_BITS,
+            None => 0,
+        };
+        stored + remaining
+    }
+
+    /// Returns the index of the `n`th set bit (counting from zero), or [`None`] if there aren't enough set
+    /// bits.
+    #[must_use]
+    pub fn select(&self, mut n: usize) -> Option<usize> {
+        for (index, &word) in self.words.iter().enumerate() {
+            let count = word.count_ones() as usize;
+            if n < count {
+                // Clear the lowest set bits until the one we want is the lowest
+                let word = (0..n).fold(word, |word, _| word & (word - 1));
+                return Some(index * Self::WORD_BITS + word.trailing_zeros() as usize);
+            }
+            n -= count;
+        }
+        match self.highest_bits {
+            true => (self.words.len() * Self::WORD_BITS).checked_add(n),
+            false => None,
+        }
+    }
+
+    /// Returns the index of every set bit, which continues up to [`usize::MAX`] if every bit past the stored
+    /// words is set.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        let end = match self.highest_bits {
+            true => usize::MAX,
+            false => self.words.len() * Self::WORD_BITS,
+        };
+        (0..end).filter(|&index| self.contains(index))
+    }
+}
@@ -7,8 +7,15 @@
 mod no_std {
     extern crate alloc;
     pub use alloc::boxed::Box;
+    pub use alloc::string::String;
+    pub use alloc::vec::Vec;
     pub use alloc::{format, vec};
 }
 
 pub mod pck;
 pub mod prelude;
+pub mod project;
+
+// Dev-dependencies are only used by doctests, but are still passed to the unit test target
+#[cfg(test)]
+use orthrus_tools as _;
@@ -1,7 +1,8 @@
 #[cfg(feature = "std")]
 use std::{
+    collections::HashMap,
     fs::File,
-    io::{prelude::*, BufReader},
+    io::{prelude::*, BufReader, BufWriter, SeekFrom},
     path::Path,
 };
 
@@ -18,7 +19,22 @@ use std::{
 /// will try to open the file as an executable and find a section labeled "pck". If it can't find that,
 /// it will check the last 4 bytes of the file. If it matches the "GDPC" magic, it will load the
 /// mini-header at the end of the file to obtain the relative offset to the start of the PCK.
+///
+/// Both version 1 (Godot 3) and version 2 (Godot 4) packs can be read, as long as the file table isn't
+/// encrypted.
+///
+/// # Imported Resources
+/// Exported games don't contain the original assets. Instead, each one is replaced by the file Godot imported
+/// it as, stored under `.godot/imported/` (or `.import/` in Godot 3) with a hash added to its name, along
+/// with a `.import` file in place of the original that points at it. Resources that were converted when
+/// exporting, such as text scenes saved as binary or compiled scripts, are handled the same way using a
+/// `.remap` file. [`ExtractOptions::remap_imports`] moves each of these files back next to the resource it
+/// belongs to, without the hash, and points the `.import` or `.remap` file at its new location.
+use orthrus_core::prelude::checksum::{md5, Md5};
+use orthrus_core::prelude::util::{PackReport, PackedMember, Replacement};
 use orthrus_core::prelude::*;
+#[cfg(feature = "zstd")]
+use orthrus_ncompress::prelude::*;
 #[allow(unused_imports)]
 use orthrus_windows::pe::PortableExecutable;
 use snafu::prelude::*;
@@ -35,12 +51,40 @@ pub enum Error {
     /// Thrown if the header contains a magic number other than "pmf\0\n\r".
     #[snafu(display("Invalid Magic! Expected {:?}.", ResourcePack::MAGIC))]
     InvalidMagic,
+
+    /// Thrown if the pack is larger than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+
+    /// Thrown if trying to access a file that isn't in the pack.
+    #[snafu(display("Unable to find {path} in the pack!"))]
+    FileNotFound { path: String },
+
+    /// Thrown if a stored file path can't safely be extracted, see [`PathOptions`].
+    #[snafu(display("{source}"))]
+    InvalidPath { source: PathError },
+
+    /// Thrown if the pack uses a version of the format that isn't supported.
+    #[snafu(display("Unsupported PCK version {version}!"))]
+    UnsupportedVersion { version: u32 },
+
+    /// Thrown if the pack's file table is encrypted.
+    #[snafu(display("The file table is encrypted, which isn't supported!"))]
+    EncryptedDirectory,
+}
+
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Error::LimitExceeded { source }
+    }
 }
 
 impl From<DataError> for Error {
     #[inline]
     fn from(error: DataError) -> Self {
         match error {
+            DataError::Io { source } => Self::FileError { source },
             DataError::EndOfFile => Self::EndOfFile,
             _ => todo!(),
         }
@@ -54,11 +98,22 @@ impl From<std::io::Error> for Error {
     }
 }
 
+impl From<PathError> for Error {
+    #[inline]
+    fn from(source: PathError) -> Self {
+        Error::InvalidPath { source }
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Debug)]
 struct Header {
     pck_version: u32,
     godot_version: (u32, u32, u32),
+    /// Only stored in version 2, see [`ResourcePack::DIRECTORY_ENCRYPTED`]
+    pack_flags: u32,
+    /// Only stored in version 2, added to every file offset
+    file_base: u64,
 }
 
 #[allow(dead_code)]
@@ -68,6 +123,264 @@ struct FileEntry {
     file_offset: u64,
     file_size: u64,
     md5_hash: [u8; 16],
+    /// Where the MD5 is stored in the pack, so it can be fixed in-place.
+    md5_position: u64,
+}
+
+impl FileEntry {
+    /// Godot leaves the MD5 zeroed out when it isn't computed, so there's nothing to verify against.
+    #[inline]
+    fn has_checksum(&self) -> bool {
+        self.md5_hash != [0u8; 16]
+    }
+}
+
+/// A file inside a [`ResourcePack`] whose contents don't match the MD5 stored for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumMismatch {
+    /// Path of the file inside the pack, e.g. "res://icon.png".
+    pub path: String,
+    /// MD5 stored in the pack's file table.
+    pub expected: [u8; 16],
+    /// MD5 of the file's actual contents.
+    pub actual: [u8; 16],
+}
+
+/// Path and contents of a file inside a [`ResourcePack`], as returned by [`ResourcePack::read_files`].
+pub type PackedFile = (String, Box<[u8]>);
+
+/// Block table of a file compressed by Godot's `FileAccessCompressed`, which is split into blocks that are
+/// each compressed on their own.
+#[cfg(feature = "zstd")]
+struct CompressedFile {
+    /// Size of every block after decompression, except for the last one
+    block_size: u64,
+    /// Compressed size of every block that hasn't been read yet
+    sizes: std::vec::IntoIter<u32>,
+    /// Number of decompressed bytes that haven't been read yet
+    remaining: u64,
+    /// The most recently decompressed block
+    block: Box<[u8]>,
+    /// How much of `block` has already been read
+    position: usize,
+}
+
+#[cfg(feature = "zstd")]
+impl CompressedFile {
+    /// Magic used for regular compressed files.
+    const MAGIC: [u8; 4] = *b"GCPF";
+    /// Magic used for compressed binary resources, such as exported scenes.
+    const RESOURCE_MAGIC: [u8; 4] = *b"RSCC";
+    /// Compression mode for Zstandard, the default in Godot 4.
+    const MODE_ZSTD: u32 = 2;
+
+    /// Reads the header and block table if this is a Zstandard-compressed file, returning the table along
+    /// with the size of the header. Otherwise, seeks back to the start of the file and returns [`None`].
+    fn read_header<T: Read + Seek>(input: &mut T, size: u64) -> std::io::Result<Option<(Self, u64)>> {
+        if size < 16 {
+            return Ok(None);
+        }
+        let mut header = [0u8; 16];
+        input.read_exact(&mut header)?;
+        let field = |index: usize| u32::from_le_bytes(header[index..index + 4].try_into().unwrap());
+        let magic = [header[0], header[1], header[2], header[3]];
+        let (mode, block_size, total_size) = (field(4), field(8), field(12));
+        if !matches!(magic, Self::MAGIC | Self::RESOURCE_MAGIC) || mode != Self::MODE_ZSTD || block_size == 0
+        {
+            input.seek(SeekFrom::Current(-16))?;
+            return Ok(None);
+        }
+
+        // Godot always stores one more block than needed, which is empty if the size is a multiple of it
+        let count = u64::from(total_size / block_size) + 1;
+        let header_size = 16 + count * 4;
+        let invalid = || std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid compressed file");
+        if header_size > size {
+            return Err(invalid());
+        }
+        let mut table = vec![0u8; count as usize * 4];
+        input.read_exact(&mut table)?;
+        let sizes: Vec<u32> =
+            table.chunks_exact(4).map(|size| u32::from_le_bytes(size.try_into().unwrap())).collect();
+        if header_size + sizes.iter().map(|&size| u64::from(size)).sum::<u64>() > size {
+            return Err(invalid());
+        }
+
+        let file = Self {
+            block_size: block_size.into(),
+            sizes: sizes.into_iter(),
+            remaining: total_size.into(),
+            block: Box::default(),
+            position: 0,
+        };
+        Ok(Some((file, header_size)))
+    }
+
+    /// Reads from the current block, decompressing the next one from `input` once it runs out.
+    fn read<T: Read>(&mut self, input: &mut T, buf: &mut [u8]) -> std::io::Result<usize> {
+        let invalid = |reason| std::io::Error::new(std::io::ErrorKind::InvalidData, reason);
+        while self.position == self.block.len() {
+            if self.remaining == 0 {
+                return Ok(0);
+            }
+            let size = self.sizes.next().ok_or_else(|| invalid("Missing compressed block"))?;
+            let mut block = vec![0u8; size as usize];
+            input.read_exact(&mut block)?;
+
+            let expected = self.remaining.min(self.block_size);
+            let limits = ResourceLimits { max_output_size: expected, ..Default::default() };
+            self.block = Zstd::decompress_from_with_limits(&block, &limits)
+                .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
+            if self.block.len() as u64 != expected {
+                return Err(invalid("Compressed block is too small"));
+            }
+            self.remaining -= expected;
+            self.position = 0;
+        }
+
+        let length = buf.len().min(self.block.len() - self.position);
+        buf[..length].copy_from_slice(&self.block[self.position..self.position + length]);
+        self.position += length;
+        Ok(length)
+    }
+}
+
+/// Streams the contents of a single file in a [`ResourcePack`], see [`ResourcePack::reader`].
+pub struct FileReader<T> {
+    input: std::io::Take<T>,
+    #[cfg(feature = "zstd")]
+    compressed: Option<CompressedFile>,
+}
+
+impl<T: Read> Read for FileReader<T> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        #[cfg(feature = "zstd")]
+        if let Some(file) = &mut self.compressed {
+            return file.read(&mut self.input, buf);
+        }
+        self.input.read(buf)
+    }
+}
+
+/// Options for extracting a pack, see [`ResourcePack::extract_from_file_with_options`].
+#[derive(Debug, Clone, Copy)]
+#[must_use]
+pub struct ExtractOptions {
+    verify: bool,
+    remap_imports: bool,
+    paths: PathOptions,
+    limits: ResourceLimits,
+}
+
+impl ExtractOptions {
+    /// Creates new options which extract every file exactly where it's stored, without verifying them.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            verify: false,
+            remap_imports: false,
+            paths: PathOptions::DEFAULT,
+            limits: ResourceLimits::UNLIMITED,
+        }
+    }
+
+    /// Verifies each file against its stored MD5 while extracting it.
+    #[inline]
+    pub const fn verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    /// Moves the files that Godot imported or converted each resource into back next to that resource,
+    /// without the hash in their name, and points its `.import` or `.remap` file at their new location. This
+    /// gives a tree that can be opened in the Godot editor.
+    #[inline]
+    pub const fn remap_imports(mut self, remap_imports: bool) -> Self {
+        self.remap_imports = remap_imports;
+        self
+    }
+
+    /// Controls how file paths are sanitized, see [`PathOptions`].
+    #[inline]
+    pub const fn paths(mut self, paths: PathOptions) -> Self {
+        self.paths = paths;
+        self
+    }
+
+    /// Returns an error instead of extracting more files, or more data, than `limits` allow.
+    #[inline]
+    pub const fn limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+impl Default for ExtractOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Where each remapped resource gets extracted to, see [`ExtractOptions::remap_imports`].
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+struct Remaps {
+    /// New path of each imported or converted file, by its path in the pack
+    paths: HashMap<String, String>,
+    /// Contents of each `.import` or `.remap` file after pointing it at the new paths
+    sidecars: HashMap<String, String>,
+}
+
+/// Returns every file that a `.import` or `.remap` file points at, which are stored in its `[remap]` section
+/// as `path`, or as `path.s3tc`, `path.etc2`, and so on when there's one for each texture format.
+#[cfg(feature = "std")]
+fn remap_targets(sidecar: &str) -> Vec<&str> {
+    let mut targets = Vec::new();
+    let mut in_remap = false;
+    for line in sidecar.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_remap = line == "[remap]";
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        if in_remap && (key == "path" || key.starts_with("path.")) {
+            if let Some(value) = value.trim().strip_prefix('"').and_then(|value| value.strip_suffix('"')) {
+                targets.push(value);
+            }
+        }
+    }
+    targets
+}
+
+/// Returns where `target` should be extracted to so it sits next to `resource`, dropping the hash that Godot
+/// adds to its name (e.g. `icon.png-<md5>.ctex` becomes `icon.png.ctex`, and `export-<md5>-main.scn` becomes
+/// `main.scn`).
+#[cfg(feature = "std")]
+fn remapped_path(resource: &str, target: &str) -> String {
+    let is_hash = |hash: &str| hash.len() == 32 && hash.bytes().all(|byte| byte.is_ascii_hexdigit());
+
+    let name = target.rsplit('/').next().unwrap_or(target);
+    let name = match name.strip_prefix("export-").and_then(|name| name.get(..33).zip(name.get(33..))) {
+        Some((hash, name)) if hash.ends_with('-') && is_hash(&hash[..32]) => name.to_owned(),
+        _ => {
+            // Imported files have the hash after the resource's name, followed by the extension
+            let hash_start = name.match_indices('-').map(|(index, _)| index).find(|&index| {
+                name.get(index + 1..index + 33).is_some_and(is_hash)
+                    && matches!(name.as_bytes().get(index + 33), None | Some(b'.'))
+            });
+            match hash_start {
+                Some(index) => format!("{}{}", &name[..index], &name[index + 33..]),
+                None => name.to_owned(),
+            }
+        }
+    };
+    let directory = resource.rsplit_once('/').map_or("res:/", |(directory, _)| directory);
+    format!("{directory}/{name}")
 }
 
 #[derive(Debug)]
@@ -75,88 +388,809 @@ struct FileEntry {
 pub struct ResourcePack {
     header: Header,
     entries: Vec<FileEntry>,
+    /// Where the pack starts in the input, which is non-zero for packs embedded in an executable.
+    offset: u64,
 }
 
 impl ResourcePack {
     /// Unique identifier that tells us if we're reading a Godot PCK archive.
     pub const MAGIC: [u8; 4] = *b"GDPC";
 
+    /// Latest revision of the PCK format that can be read and written.
+    pub const VERSION: u32 = 1;
+
+    /// Alignment of each file's data inside the pack.
+    const ALIGNMENT: u64 = 0x10;
+
+    /// Pack flag set in version 2 if the file table is encrypted.
+    pub const DIRECTORY_ENCRYPTED: u32 = 1 << 0;
+
+    /// Pack flag set in version 2 if file offsets are relative to the start of the pack, instead of the
+    /// start of the file it's embedded in.
+    pub const RELATIVE_FILE_BASE: u32 = 1 << 1;
+
+    /// Returns where the pack starts in the input, following the same order Godot checks in: a plain pack,
+    /// a "pck" section in a Windows (PE) or Linux (ELF) executable, then a pack appended to any file.
+    fn find_pack<T: ReadExt + SeekExt>(data: &mut T) -> Result<u64, self::Error> {
+        data.set_position(0)?;
+        let magic = data.read_exact::<4>()?;
+        if magic == Self::MAGIC {
+            return Ok(0);
+        }
+
+        let section = match magic {
+            [b'M', b'Z', ..] => Self::find_pe_section(data)?,
+            [0x7F, b'E', b'L', b'F'] => Self::find_elf_section(data)?,
+            _ => None,
+        };
+        if let Some(offset) = section {
+            return Ok(offset);
+        }
+
+        // Appended packs end with the pack size followed by the magic, which also works on macOS
+        let length = data.len()?;
+        ensure!(length >= 12, InvalidMagicSnafu);
+        data.set_position(length - 4)?;
+        ensure!(data.read_exact::<4>()? == Self::MAGIC, InvalidMagicSnafu);
+        data.set_position(length - 12)?;
+        let size = data.read_u64()?;
+        (length - 12).checked_sub(size).context(InvalidMagicSnafu)
+    }
+
+    /// Returns the offset of the "pck" section in a Windows executable, if it has one.
+    fn find_pe_section<T: ReadExt + SeekExt>(data: &mut T) -> Result<Option<u64>, self::Error> {
+        data.set_position(0x3C)?;
+        let pe_offset = u64::from(data.read_u32()?);
+        data.set_position(pe_offset)?;
+        if data.read_exact::<4>()? != *b"PE\0\0" {
+            return Ok(None);
+        }
+
+        data.set_position(pe_offset + 6)?;
+        let section_count = data.read_u16()?;
+        data.set_position(pe_offset + 20)?;
+        let optional_size = u64::from(data.read_u16()?);
+
+        let table = pe_offset + 24 + optional_size;
+        for index in 0..u64::from(section_count) {
+            data.set_position(table + index * 40)?;
+            if data.read_exact::<8>()? == *b"pck\0\0\0\0\0" {
+                // Skip the virtual size, virtual address, and raw size
+                data.set_position(table + index * 40 + 20)?;
+                return Ok(Some(data.read_u32()?.into()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the offset of the "pck" section in a little-endian Linux executable, if it has one.
+    fn find_elf_section<T: ReadExt + SeekExt>(data: &mut T) -> Result<Option<u64>, self::Error> {
+        data.set_position(4)?;
+        let [class, endian] = data.read_exact::<2>()?;
+        if endian != 1 {
+            return Ok(None);
+        }
+        let is_64 = class == 2;
+
+        // Offsets of the section table fields and section header fields depend on the word size
+        let (table_field, name_field, offset_field) = match is_64 {
+            true => (0x28, 0x3A, 0x18),
+            false => (0x20, 0x2E, 0x10),
+        };
+        let read_word = |data: &mut T| -> Result<u64, DataError> {
+            match is_64 {
+                true => data.read_u64(),
+                false => data.read_u32().map(u64::from),
+            }
+        };
+
+        data.set_position(table_field)?;
+        let table = read_word(data)?;
+        data.set_position(name_field)?;
+        let (entry_size, count, names_index) = (data.read_u16()?, data.read_u16()?, data.read_u16()?);
+        let entry = |index: u16| table + u64::from(index) * u64::from(entry_size);
+
+        data.set_position(entry(names_index) + offset_field)?;
+        let names = read_word(data)?;
+        for index in 0..count {
+            data.set_position(entry(index))?;
+            let name = u64::from(data.read_u32()?);
+            data.set_position(names + name)?;
+            if data.read_exact::<4>()? == *b"pck\0" {
+                data.set_position(entry(index) + offset_field)?;
+                return Ok(Some(read_word(data)?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns where the pack starts in the input it was loaded from, which is non-zero if it was embedded in
+    /// an executable.
+    #[must_use]
+    #[inline]
+    pub const fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the version of Godot that created the pack, as (major, minor, patch).
+    #[must_use]
+    #[inline]
+    pub const fn godot_version(&self) -> (u32, u32, u32) {
+        self.header.godot_version
+    }
+
+    /// Returns the path of every file in the pack, in the order they're stored.
+    #[inline]
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|entry| entry.file_path.as_str())
+    }
+
+    /// Returns a reader for the contents of a single file, which reads it in chunks instead of loading the
+    /// entire file into memory. `path` can be given with or without the "res://" prefix.
+    ///
+    /// With the `zstd` feature, files that Godot compressed with Zstandard (starting with "GCPF", or "RSCC"
+    /// for binary resources) are decompressed one block at a time while reading. Otherwise, files are read
+    /// exactly as they're stored.
+    ///
+    /// The input must be the same pack this instance was loaded from.
+    ///
+    /// # Errors
+    /// Returns [`FileNotFound`](Error::FileNotFound) if the pack doesn't contain `path`, or
+    /// [`FileError`](Error::FileError) if unable to read the input.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::{Cursor, Read};
+    /// # use orthrus_godot::prelude::*;
+    /// let mut data = Cursor::new(Vec::new());
+    /// ResourcePack::create(&mut data, &[("intro.ogv", b"Video".as_slice())], (4, 2, 0))?;
+    ///
+    /// let pack = ResourcePack::load(Cursor::new(data.get_ref()))?;
+    /// let mut contents = String::new();
+    /// pack.reader(Cursor::new(data.get_ref()), "intro.ogv")?.read_to_string(&mut contents)?;
+    /// assert_eq!(contents, "Video");
+    /// # Ok::<(), pck::Error>(())
+    /// ```
+    #[inline]
+    pub fn reader<T: Read + Seek>(&self, input: T, path: &str) -> Result<FileReader<T>, self::Error> {
+        let index = self.entry_index(path)?;
+        Ok(Self::entry_reader(input, &self.entries[index])?)
+    }
+
+    /// Reads the contents of a single file, decompressing it if needed, see [`reader`](Self::reader).
+    ///
+    /// # Errors
+    /// Returns [`FileNotFound`](Error::FileNotFound) if the pack doesn't contain `path`, or
+    /// [`FileError`](Error::FileError) if unable to read the input or decompress the file.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[cfg(feature = "zstd")]
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use std::io::Cursor;
+    /// # use orthrus_godot::prelude::*;
+    /// # use orthrus_ncompress::prelude::*;
+    /// // Exported scenes are compressed in blocks, each of which is a separate Zstandard frame
+    /// let block = Zstd::compress_from(b"RSRC scene")?;
+    /// let mut scene = b"RSCC".to_vec();
+    /// for field in [2, 0x1000, 10, block.len() as u32] {
+    ///     scene.extend(field.to_le_bytes());
+    /// }
+    /// scene.extend(block);
+    ///
+    /// let mut data = Cursor::new(Vec::new());
+    /// ResourcePack::create(&mut data, &[("main.scn", scene)], (4, 2, 0))?;
+    /// let pack = ResourcePack::load(Cursor::new(data.get_ref()))?;
+    /// assert_eq!(&*pack.read_file(Cursor::new(data.get_ref()), "main.scn")?, b"RSRC scene");
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "zstd"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn read_file<T: Read + Seek>(&self, input: T, path: &str) -> Result<Box<[u8]>, self::Error> {
+        let mut contents = Vec::new();
+        self.reader(input, path)?.read_to_end(&mut contents)?;
+        Ok(contents.into_boxed_slice())
+    }
+
+    /// Returns the index of the entry with the given path, with or without the "res://" prefix.
+    fn entry_index(&self, path: &str) -> Result<usize, self::Error> {
+        self.entries
+            .iter()
+            .position(|entry| entry.file_path == path || entry.file_path.trim_start_matches("res://") == path)
+            .context(FileNotFoundSnafu { path })
+    }
+
+    /// Seeks to the start of an entry, and returns a reader for its contents.
+    fn entry_reader<T: Read + Seek>(mut input: T, entry: &FileEntry) -> std::io::Result<FileReader<T>> {
+        input.seek(SeekFrom::Start(entry.file_offset))?;
+        #[cfg(feature = "zstd")]
+        if let Some((file, header_size)) = CompressedFile::read_header(&mut input, entry.file_size)? {
+            let input = input.take(entry.file_size - header_size);
+            return Ok(FileReader { input, compressed: Some(file) });
+        }
+        Ok(FileReader {
+            input: input.take(entry.file_size),
+            #[cfg(feature = "zstd")]
+            compressed: None,
+        })
+    }
+
+    /// Reads the contents of every file in the pack, in the order they're stored, for use when repacking.
+    /// Files are read exactly as they're stored, without being decompressed.
+    ///
+    /// The input must be the same pack this instance was loaded from.
+    #[inline]
+    pub fn read_files<T: Read + Seek>(&self, input: T) -> Result<Vec<PackedFile>, self::Error> {
+        let mut data = DataStream::new(input, Endian::Little);
+        self.entries
+            .iter()
+            .map(|entry| {
+                data.set_position(entry.file_offset)?;
+                let contents = data.read_slice(entry.file_size as usize)?.into_owned().into_boxed_slice();
+                Ok((entry.file_path.clone(), contents))
+            })
+            .collect()
+    }
+
+    /// Writes a new pack containing `files` to `output`, starting at its current position, and returns the
+    /// size of the pack. Paths without a "res://" prefix have one added.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to write to the output.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::Cursor;
+    /// # use orthrus_godot::prelude::*;
+    /// let mut data = Cursor::new(Vec::new());
+    /// ResourcePack::create(&mut data, &[("icon.png", b"PNG".as_slice())], (3, 5, 0))?;
+    ///
+    /// let pack = ResourcePack::load(Cursor::new(data.get_ref()))?;
+    /// assert_eq!(pack.paths().collect::<Vec<_>>(), ["res://icon.png"]);
+    /// assert!(pack.verify_checksums(Cursor::new(data.get_ref()))?.is_empty());
+    /// # Ok::<(), pck::Error>(())
+    /// ```
+    #[inline]
+    pub fn create<W: Write + Seek, P: AsRef<str>, D: AsRef<[u8]>>(
+        output: W, files: &[(P, D)], godot_version: (u32, u32, u32),
+    ) -> Result<u64, self::Error> {
+        Ok(Self::create_with_report(output, files, godot_version)?.total_size)
+    }
+
+    /// Writes a new pack the same way as [`create`](Self::create), and returns where every file was stored
+    /// and how much padding was needed to align it.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to write to the output.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::Cursor;
+    /// # use orthrus_godot::prelude::*;
+    /// let mut data = Cursor::new(Vec::new());
+    /// let files = [("icon.png", b"PNG".as_slice())];
+    /// let report = ResourcePack::create_with_report(&mut data, &files, (3, 5, 0))?;
+    /// assert_eq!(report.total_size, data.get_ref().len() as u64);
+    /// assert_eq!(report.members[0].path, "res://icon.png");
+    /// assert_eq!(report.padding(), 0x10 - 3);
+    /// # Ok::<(), pck::Error>(())
+    /// ```
+    pub fn create_with_report<W: Write + Seek, P: AsRef<str>, D: AsRef<[u8]>>(
+        output: W, files: &[(P, D)], godot_version: (u32, u32, u32),
+    ) -> Result<PackReport, self::Error> {
+        let mut data = DataStream::new(output, Endian::Little);
+        let start = data.position()?;
+
+        data.write_exact(&Self::MAGIC)?;
+        data.write_u32(Self::VERSION)?;
+        data.write_u32(godot_version.0)?;
+        data.write_u32(godot_version.1)?;
+        data.write_u32(godot_version.2)?;
+        data.write_all(&[0u8; 64])?;
+
+        // Godot pads paths to a multiple of 4, and the data starts right after the file table
+        let paths: Vec<String> = files
+            .iter()
+            .map(|(path, _)| match path.as_ref().starts_with("res://") {
+                true => path.as_ref().to_owned(),
+                false => format!("res://{}", path.as_ref()),
+            })
+            .collect();
+        let padded = |path: &String| (path.len() as u64 + 1).next_multiple_of(4);
+        let table_size: u64 = paths.iter().map(|path| 4 + padded(path) + 8 + 8 + 16).sum();
+        let header_size = (0x58 + table_size).next_multiple_of(Self::ALIGNMENT);
+        let mut offset = header_size;
+
+        data.write_u32(files.len() as u32)?;
+        for (path, (_, contents)) in paths.iter().zip(files) {
+            let contents = contents.as_ref();
+            data.write_u32(padded(path) as u32)?;
+            data.write_all(path.as_bytes())?;
+            data.write_all(&[0u8; 4][..(padded(path) - path.len() as u64) as usize])?;
+            data.write_u64(offset)?;
+            data.write_u64(contents.len() as u64)?;
+            data.write_exact(&md5(contents))?;
+            offset = (offset + contents.len() as u64).next_multiple_of(Self::ALIGNMENT);
+        }
+
+        let mut members = Vec::with_capacity(files.len());
+        for (path, (_, contents)) in paths.into_iter().zip(files) {
+            let contents = contents.as_ref();
+            let position = data.position()? - start;
+            data.write_all(&[0u8; 0x10][..(position.next_multiple_of(Self::ALIGNMENT) - position) as usize])?;
+            data.write_all(contents)?;
+
+            // Godot doesn't compress files in the pack itself, so both sizes are the same
+            let length = contents.len() as u64;
+            let padding = length.next_multiple_of(Self::ALIGNMENT) - length;
+            members.push(PackedMember::new(
+                path,
+                position.next_multiple_of(Self::ALIGNMENT),
+                length,
+                length,
+                padding,
+            ));
+        }
+        let end = data.position()? - start;
+        data.write_all(&[0u8; 0x10][..(end.next_multiple_of(Self::ALIGNMENT) - end) as usize])?;
+        Ok(PackReport::new(members, header_size))
+    }
+
+    /// Writes `runtime` (an exported Godot executable without a pack) to `output`, followed by a new pack
+    /// containing `files`, creating a self-contained game that Godot loads on any platform.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to read the runtime or write to the output.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::Cursor;
+    /// # use orthrus_godot::prelude::*;
+    /// let mut data = Cursor::new(Vec::new());
+    /// let runtime = b"\x7FELF not really an executable".as_slice();
+    /// let files = [("main.tscn", b"[gd_scene]".as_slice())];
+    /// ResourcePack::create_embedded(runtime, &mut data, &files, (3, 5, 0))?;
+    ///
+    /// let pack = ResourcePack::load(Cursor::new(data.get_ref()))?;
+    /// assert_eq!(pack.offset(), 0x20);
+    /// # Ok::<(), pck::Error>(())
+    /// ```
+    pub fn create_embedded<R: Read, W: Write + Seek, P: AsRef<str>, D: AsRef<[u8]>>(
+        mut runtime: R, mut output: W, files: &[(P, D)], godot_version: (u32, u32, u32),
+    ) -> Result<u64, self::Error> {
+        std::io::copy(&mut runtime, &mut output)?;
+
+        // Godot expects embedded packs to start on a 64-bit boundary
+        let position = output.stream_position()?;
+        output.write_all(&[0u8; 8][..(position.next_multiple_of(8) - position) as usize])?;
+
+        let size = Self::create(&mut output, files, godot_version)?;
+        let mut data = DataStream::new(output, Endian::Little);
+        data.write_u64(size)?;
+        data.write_exact(&Self::MAGIC)?;
+        Ok(size + 12)
+    }
+
     #[inline]
     fn read_header<T: ReadExt>(data: &mut T) -> Result<Header, self::Error> {
         let magic = data.read_exact::<4>()?;
         ensure!(magic == Self::MAGIC, InvalidMagicSnafu);
 
         let pck_version = data.read_u32()?;
+        ensure!(pck_version <= 2, UnsupportedVersionSnafu { version: pck_version });
         let godot_version = (data.read_u32()?, data.read_u32()?, data.read_u32()?);
+        let (pack_flags, file_base) = match pck_version {
+            2 => (data.read_u32()?, data.read_u64()?),
+            _ => (0, 0),
+        };
+        ensure!(
+            pack_flags & Self::DIRECTORY_ENCRYPTED == 0,
+            EncryptedDirectorySnafu
+        );
         // TODO: these are reserved, verify they're actually zero?
         for _ in 0..16 {
             data.read_u32()?;
         }
-        Ok(Header { pck_version, godot_version })
+        Ok(Header { pck_version, godot_version, pack_flags, file_base })
     }
 
     #[inline]
     #[cfg(feature = "std")]
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, self::Error> {
+        Self::open_with_limits(path, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Opens a file on disk and parses its metadata, returning an error instead of reading more entries or
+    /// larger files than `limits` allow.
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn open_with_limits<P: AsRef<Path>>(path: P, limits: &ResourceLimits) -> Result<Self, self::Error> {
         // Wrap this in an inner function so we can better handle generics
-        fn inner(path: &Path) -> Result<ResourcePack, self::Error> {
+        fn inner(path: &Path, limits: &ResourceLimits) -> Result<ResourcePack, self::Error> {
             // We should be fine with a BufReader, this shouldn't need much seeking.
             let data = BufReader::new(File::open(path)?);
-            ResourcePack::load(data)
+            ResourcePack::load_with_limits(data, limits)
         }
-        inner(path.as_ref())
+        inner(path.as_ref(), limits)
     }
 
     #[inline]
     pub fn load<T: Read + Seek>(input: T) -> Result<Self, self::Error> {
-        //TODO: Support PE wrapper, add our cascade tree
+        Self::load_with_limits(input, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Parses the metadata from the given input, returning an error instead of reading more entries or
+    /// larger files than `limits` allow.
+    #[inline]
+    pub fn load_with_limits<T: Read + Seek>(input: T, limits: &ResourceLimits) -> Result<Self, self::Error> {
         let mut data = DataStream::new(input, Endian::Little);
-        Self::load_inner(&mut data)
+        Self::load_inner(&mut data, limits)
     }
 
     /// Loads the entire `ResourcePack` metadata and returns it as an object. Used for sharing a ReadExt +
     /// SeekExt stream across multiple operations.
     ///
-    /// This assumes that the input data is already at the start of a "GDPC" section, i.e. we've already
-    /// parsed out any potential PE data.
-    fn load_inner<T: ReadExt>(data: &mut T) -> Result<Self, self::Error> {
+    /// The pack may be embedded inside of an executable, in which case all file offsets are adjusted to be
+    /// relative to the start of the input.
+    fn load_inner<T: ReadExt + SeekExt>(data: &mut T, limits: &ResourceLimits) -> Result<Self, self::Error> {
+        let offset = Self::find_pack(data)?;
+        data.set_position(offset)?;
+
         // Grab the header, we need it in order to figure out which PCK version we're reading
-        // TODO: support v2 and v0 archives
         let header = ResourcePack::read_header(data)?;
 
-        // Then, let's collect all file metadata
+        // Version 1 offsets are relative to the pack, while version 2 adds its own base offset instead
+        let base = match header.pck_version {
+            2 if header.pack_flags & Self::RELATIVE_FILE_BASE == 0 => header.file_base,
+            2 => header.file_base + offset,
+            _ => offset,
+        };
+
+        // Then, let's collect all file metadata, making sure the count is sane before we allocate for it
         let file_count = data.read_u32()?;
+        limits.check_entries(file_count.into())?;
         let mut entries = Vec::with_capacity(file_count as usize);
+        let mut total_size = 0u64;
         for _ in 0..file_count {
-            entries.push(Self::read_entry(data)?);
+            let mut entry = Self::read_entry(data, header.pck_version)?;
+            entry.file_offset += base;
+            total_size = total_size.saturating_add(entry.file_size);
+            limits.check_output_size(total_size)?;
+            entries.push(entry);
         }
 
-        Ok(ResourcePack { header, entries })
+        Ok(ResourcePack { header, entries, offset })
     }
 
-    pub fn extract_from_file<P: AsRef<Path>>(input: P, output: P) -> Result<usize, self::Error> {
-        fn inner(input: &Path, _output: &Path) -> Result<usize, self::Error> {
-            // Use our existing functions to do the bulk of the loading
-            let file = BufReader::new(File::open(input)?);
-            let mut data = DataStream::new(file, Endian::Little);
-            let mut metadata = ResourcePack::load_inner(&mut data)?;
+    /// Extracts all files in a pack to the output directory, returning the number of files written.
+    ///
+    /// Checksums are not verified, see [`extract_from_file_verified`](Self::extract_from_file_verified).
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn extract_from_file<P: AsRef<Path>, O: AsRef<Path>>(
+        input: P, output: O,
+    ) -> Result<usize, self::Error> {
+        Self::extract_from_file_with_options(input, output, &ExtractOptions::new()).map(|(count, _)| count)
+    }
 
-            // In order to optimize seeking, we need to sort by file offset
-            metadata.entries.sort_by_key(|entry| entry.file_offset);
-            for entry in metadata.entries {
-                data.set_position(entry.file_offset)?;
+    /// Extracts all files in a pack to the output directory while verifying each file against its stored
+    /// MD5. Returns the number of files written, along with any files that didn't match.
+    ///
+    /// Files with mismatched checksums are still written, so they can be inspected.
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn extract_from_file_verified<P: AsRef<Path>, O: AsRef<Path>>(
+        input: P, output: O,
+    ) -> Result<(usize, Vec<ChecksumMismatch>), self::Error> {
+        Self::extract_from_file_with_options(input, output, &ExtractOptions::new().verify(true))
+    }
+
+    /// Extracts all files in a pack to the output directory, using `options` to control how file paths are
+    /// sanitized, whether each file is verified against its stored MD5, and whether imported resources are
+    /// moved back next to the resource they belong to. Returns the number of files written, along with any
+    /// files that didn't match.
+    ///
+    /// # Errors
+    /// Returns [`InvalidPath`](Error::InvalidPath) if a file path is unsafe, or an error if unable to read
+    /// the pack or write any of its files.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use orthrus_godot::prelude::*;
+    ///
+    /// // Extract a tree that can be opened in the Godot editor
+    /// let options = pck::ExtractOptions::new().remap_imports(true);
+    /// let (count, _) = ResourcePack::extract_from_file_with_options("game.pck", "game", &options)?;
+    /// println!("Extracted {count} files");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn extract_from_file_with_options<P: AsRef<Path>, O: AsRef<Path>>(
+        input: P, output: O, options: &ExtractOptions,
+    ) -> Result<(usize, Vec<ChecksumMismatch>), self::Error> {
+        Self::extract_from_file_into(input, &Extraction::direct(output.as_ref()), options)
+    }
+
+    /// Extracts all files in a pack to wherever `extraction` puts them, such as a staging folder or nowhere
+    /// at all for a dry run, see [`extract_from_file_with_options`](Self::extract_from_file_with_options).
+    /// The [`Extraction`] still needs to be committed afterwards.
+    ///
+    /// Checksums are still verified during a dry run, so it can be used to check a pack without writing it.
+    ///
+    /// # Errors
+    /// Returns the same errors as [`extract_from_file_with_options`](Self::extract_from_file_with_options).
+    #[cfg(feature = "std")]
+    pub fn extract_from_file_into<P: AsRef<Path>>(
+        input: P, extraction: &Extraction, options: &ExtractOptions,
+    ) -> Result<(usize, Vec<ChecksumMismatch>), self::Error> {
+        // Use our existing functions to do the bulk of the loading
+        let file = BufReader::new(File::open(input)?);
+        let mut data = DataStream::new(file, Endian::Little);
+        let mut metadata = ResourcePack::load_inner(&mut data, &options.limits)?;
+
+        // In order to optimize seeking, we need to sort by file offset
+        metadata.entries.sort_by_key(|entry| entry.file_offset);
+
+        let remaps = match options.remap_imports {
+            true => metadata.find_remaps(&mut *data)?,
+            false => Remaps::default(),
+        };
+
+        let mut mismatches = Vec::new();
+        for entry in &metadata.entries {
+            if options.verify && entry.has_checksum() {
+                let actual = Self::hash_entry(&mut data, entry)?;
+                if actual != entry.md5_hash {
+                    mismatches.push(ChecksumMismatch {
+                        path: entry.file_path.clone(),
+                        expected: entry.md5_hash,
+                        actual,
+                    });
+                }
+            }
+
+            let file_path = remaps.paths.get(&entry.file_path).unwrap_or(&entry.file_path);
+            let path =
+                options.paths.sanitize(file_path.trim_start_matches("res://").trim_start_matches('/'))?;
+            let sidecar = remaps.sidecars.get(&entry.file_path);
+            let size = sidecar.map_or(entry.file_size, |sidecar| sidecar.len() as u64);
+            let Some(path) = extraction.prepare(path, size)? else {
+                continue;
+            };
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
             }
-            Ok(0)
+            if let Some(sidecar) = sidecar {
+                std::fs::write(path, sidecar)?;
+                continue;
+            }
+            // Files are copied in chunks, so even large videos don't need to fit in memory
+            let mut reader = Self::entry_reader(&mut *data, entry)?;
+            let mut output = BufWriter::new(File::create(path)?);
+            std::io::copy(&mut reader, &mut output)?;
+            output.flush()?;
+        }
+        Ok((metadata.entries.len(), mismatches))
+    }
+
+    /// Reads every `.import` and `.remap` file in the pack, and works out where each file they point at
+    /// should be moved to, see [`ExtractOptions::remap_imports`]. Files they point at that aren't in the pack
+    /// are left alone.
+    #[cfg(feature = "std")]
+    fn find_remaps<T: Read + Seek>(&self, mut input: T) -> Result<Remaps, self::Error> {
+        // These are only ever a few lines long, so anything larger isn't one that Godot wrote
+        const MAX_SIDECAR_SIZE: u64 = 0x10000;
+
+        let mut remaps = Remaps::default();
+        for entry in &self.entries {
+            let Some(resource) =
+                entry.file_path.strip_suffix(".import").or_else(|| entry.file_path.strip_suffix(".remap"))
+            else {
+                continue;
+            };
+            if entry.file_size > MAX_SIDECAR_SIZE {
+                continue;
+            }
+            let mut sidecar = String::new();
+            if Self::entry_reader(&mut input, entry)?.read_to_string(&mut sidecar).is_err() {
+                continue;
+            }
+
+            let mut rewritten = sidecar.clone();
+            for target in remap_targets(&sidecar) {
+                if remaps.paths.contains_key(target)
+                    || !self.entries.iter().any(|entry| entry.file_path == target)
+                {
+                    continue;
+                }
+                let moved = remapped_path(resource, target);
+                rewritten = rewritten.replace(&format!("\"{target}\""), &format!("\"{moved}\""));
+                remaps.paths.insert(target.to_owned(), moved);
+            }
+            if rewritten != sidecar {
+                remaps.sidecars.insert(entry.file_path.clone(), rewritten);
+            }
+        }
+        Ok(remaps)
+    }
+
+    /// Computes the MD5 of a file's contents, without needing to read the entire file at once.
+    fn hash_entry<T: ReadExt + SeekExt>(data: &mut T, entry: &FileEntry) -> Result<[u8; 16], self::Error> {
+        const CHUNK_SIZE: u64 = 0x10000;
+
+        data.set_position(entry.file_offset)?;
+        let mut hasher = Md5::new();
+        let mut remaining = entry.file_size;
+        while remaining != 0 {
+            let length = remaining.min(CHUNK_SIZE);
+            hasher.update(&data.read_slice(length as usize)?);
+            remaining -= length;
         }
-        inner(input.as_ref(), output.as_ref())
+        Ok(hasher.finalize())
     }
 
-    fn read_entry<T: ReadExt>(data: &mut T) -> Result<FileEntry, self::Error> {
+    /// Verifies every file against the MD5 stored in the pack, returning all files that didn't match.
+    ///
+    /// The input must be the same pack this instance was loaded from. Files without a stored MD5 (all zeroes)
+    /// are skipped.
+    #[inline]
+    pub fn verify_checksums<T: Read + Seek>(&self, input: T) -> Result<Vec<ChecksumMismatch>, self::Error> {
+        let mut data = DataStream::new(input, Endian::Little);
+        let mut mismatches = Vec::new();
+        for entry in self.entries.iter().filter(|entry| entry.has_checksum()) {
+            let actual = Self::hash_entry(&mut data, entry)?;
+            if actual != entry.md5_hash {
+                mismatches.push(ChecksumMismatch {
+                    path: entry.file_path.clone(),
+                    expected: entry.md5_hash,
+                    actual,
+                });
+            }
+        }
+        Ok(mismatches)
+    }
+
+    /// Recomputes the MD5 of every file and writes any that changed back into the pack's file table, for use
+    /// after patching file contents in-place. Returns the number of checksums that were updated.
+    ///
+    /// The input must be the same pack this instance was loaded from. Unlike
+    /// [`verify_checksums`](Self::verify_checksums), files without a stored MD5 are also updated.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::Cursor;
+    /// # use orthrus_godot::prelude::*;
+    /// # let mut data = b"GDPC\x01\0\0\0\x03\0\0\0\x05\0\0\0\0\0\0\0".to_vec();
+    /// # data.extend_from_slice(&[0; 64]);
+    /// # data.extend_from_slice(&1u32.to_le_bytes());
+    /// # data.extend_from_slice(&12u32.to_le_bytes());
+    /// # data.extend_from_slice(b"res://a.txt\0");
+    /// # data.extend_from_slice(&(data.len() as u64 + 32).to_le_bytes());
+    /// # data.extend_from_slice(&5u64.to_le_bytes());
+    /// # data.extend_from_slice(&[0xFF; 16]);
+    /// # data.extend_from_slice(b"Hello");
+    /// // A pack where the single file's stored MD5 is wrong
+    /// let mut pack = ResourcePack::load(Cursor::new(&data))?;
+    /// assert_eq!(pack.verify_checksums(Cursor::new(&data))?.len(), 1);
+    ///
+    /// assert_eq!(pack.fix_checksums(Cursor::new(&mut data))?, 1);
+    /// assert!(pack.verify_checksums(Cursor::new(&data))?.is_empty());
+    /// assert!(ResourcePack::load(Cursor::new(&data))?.verify_checksums(Cursor::new(&data))?.is_empty());
+    /// # Ok::<(), pck::Error>(())
+    /// ```
+    #[inline]
+    pub fn fix_checksums<T: Read + Write + Seek>(&mut self, input: T) -> Result<usize, self::Error> {
+        let mut data = DataStream::new(input, Endian::Little);
+        let mut fixed = 0;
+        for entry in &mut self.entries {
+            let actual = Self::hash_entry(&mut data, entry)?;
+            if actual != entry.md5_hash {
+                data.set_position(entry.md5_position)?;
+                data.write_exact(&actual)?;
+                entry.md5_hash = actual;
+                fixed += 1;
+            }
+        }
+        Ok(fixed)
+    }
+
+    /// Overwrites a single file inside of the pack without rewriting the rest of it, as long as `contents`
+    /// is no larger than the original file. Any leftover space is filled with zeroes, and the file's size and
+    /// MD5 (if it has one) are updated to match.
+    ///
+    /// The input must be the same pack this instance was loaded from. `path` can be given with or without
+    /// the "res://" prefix.
+    ///
+    /// # Errors
+    /// Returns [`FileNotFound`](Error::FileNotFound) if the pack doesn't contain `path`, or
+    /// [`FileError`](Error::FileError) if unable to write to the input.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::Cursor;
+    /// # use orthrus_core::prelude::util::Replacement;
+    /// # use orthrus_godot::prelude::*;
+    /// # let mut data = b"GDPC\x01\0\0\0\x03\0\0\0\x05\0\0\0\0\0\0\0".to_vec();
+    /// # data.extend_from_slice(&[0; 64]);
+    /// # data.extend_from_slice(&1u32.to_le_bytes());
+    /// # data.extend_from_slice(&12u32.to_le_bytes());
+    /// # data.extend_from_slice(b"res://a.txt\0");
+    /// # data.extend_from_slice(&(data.len() as u64 + 32).to_le_bytes());
+    /// # data.extend_from_slice(&5u64.to_le_bytes());
+    /// # data.extend_from_slice(&[0xFF; 16]);
+    /// # data.extend_from_slice(b"Hello");
+    /// let mut pack = ResourcePack::load(Cursor::new(&data))?;
+    /// let result = pack.replace_in_place(Cursor::new(&mut data), "a.txt", b"Hi")?;
+    /// assert_eq!(result, Replacement::InPlace);
+    /// assert!(data.ends_with(b"Hi\0\0\0"));
+    ///
+    /// // Larger files need the entire pack to be rebuilt
+    /// let result = pack.replace_in_place(Cursor::new(&mut data), "res://a.txt", b"Goodbye")?;
+    /// assert_eq!(result, Replacement::RebuildRequired);
+    /// # Ok::<(), pck::Error>(())
+    /// ```
+    ///
+    /// Replacing every file with its own contents leaves the pack unchanged:
+    /// ```
+    /// # use std::io::Cursor;
+    /// use orthrus_core::testing;
+    /// use orthrus_godot::pck::{self, PackedFile, ResourcePack};
+    ///
+    /// let data = orthrus_tools::corpus::pck(3);
+    /// let parse = |data: &[u8]| ResourcePack::load(Cursor::new(data))?.read_files(Cursor::new(data));
+    /// let write = |files: &Vec<PackedFile>| -> Result<Vec<u8>, pck::Error> {
+    ///     let mut output = data.to_vec();
+    ///     let mut pack = ResourcePack::load(Cursor::new(&output))?;
+    ///     for (path, contents) in files {
+    ///         pack.replace_in_place(Cursor::new(&mut output), path, contents)?;
+    ///     }
+    ///     Ok(output)
+    /// };
+    /// assert!(testing::round_trip(&data, parse, write)?.byte_identical);
+    /// # Ok::<(), testing::Divergence>(())
+    /// ```
+    #[inline]
+    pub fn replace_in_place<T: Read + Write + Seek>(
+        &mut self, input: T, path: &str, contents: &[u8],
+    ) -> Result<Replacement, self::Error> {
+        let index = self.entry_index(path)?;
+        let entry = &mut self.entries[index];
+
+        let length = contents.len() as u64;
+        if length > entry.file_size {
+            return Ok(Replacement::RebuildRequired);
+        }
+
+        let mut data = DataStream::new(input, Endian::Little);
+        data.set_position(entry.file_offset)?;
+        data.write_all(contents)?;
+        std::io::copy(&mut std::io::repeat(0).take(entry.file_size - length), &mut *data)?;
+
+        // The size is stored directly before the MD5
+        data.set_position(entry.md5_position - 8)?;
+        data.write_u64(length)?;
+        if entry.has_checksum() {
+            entry.md5_hash = md5(contents);
+            data.write_exact(&entry.md5_hash)?;
+        }
+        entry.file_size = length;
+        Ok(Replacement::InPlace)
+    }
+
+    fn read_entry<T: ReadExt + SeekExt>(data: &mut T, version: u32) -> Result<FileEntry, self::Error> {
         let string_length = data.read_u32()?;
         let file_path = data.read_string(string_length as usize)?.trim_end_matches('\0').to_owned();
         let file_offset = data.read_u64()?;
         let file_size = data.read_u64()?;
+        let md5_position = data.position()?;
         let md5_hash = data.read_exact::<16>()?;
-        Ok(FileEntry { file_path, file_offset, file_size, md5_hash })
+        // Version 2 adds per-file flags, which are only used for encryption
+        if version == 2 {
+            let _flags = data.read_u32()?;
+        }
+        Ok(FileEntry { file_path, file_offset, file_size, md5_hash, md5_position })
     }
 }
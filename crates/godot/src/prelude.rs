@@ -7,8 +7,15 @@
 
 #[doc(inline)]
 pub use crate::pck::ResourcePack;
+#[doc(inline)]
+pub use crate::project::ProjectSettings;
 
 pub mod pck {
     #[doc(inline)]
-    pub use crate::pck::Error;
+    pub use crate::pck::{ChecksumMismatch, Error, ExtractOptions, FileReader, PackedFile};
+}
+
+pub mod project {
+    #[doc(inline)]
+    pub use crate::project::{Error, Variant};
 }
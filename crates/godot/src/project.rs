@@ -0,0 +1,583 @@
+//! Adds support for the `project.binary` settings file that Godot stores in exported packs, which is the
+//! binary form of a project's `project.godot`.
+//!
+//! Settings are kept in the order they're stored, so a file that's loaded and written back without any
+//! changes is identical to the original.
+//!
+//! # Format
+//! The file is a header followed by each setting, in little-endian format:
+//!
+//! | Offset | Field | Type | Notes |
+//! |--------|-------|------|-------|
+//! | 0x0    | Magic number  | u8\[4] | Unique identifier ("ECFG") to let us know we're reading project settings. |
+//! | 0x4    | Setting count | u32    | Number of settings that follow. |
+//!
+//! Each setting is then stored as:
+//!
+//! | Offset | Field | Type | Notes |
+//! |--------|-------|------|-------|
+//! | 0x0     | Key length   | u32        | Length of the key that follows. |
+//! | 0x4     | Key          | char\[len] | Section and name of the setting, such as "application/config/name". |
+//! | 0x4+len | Value length | u32        | Length of the value that follows. |
+//! | 0x8+len | Value        | u8\[len]   | Value of the setting, encoded as a Godot [`Variant`]. |
+//!
+//! Godot 4 renumbered most [`Variant`] types, so the version is detected from `config_version`, which is an
+//! integer in every version of Godot: 4 for Godot 3, and 5 for Godot 4.
+//!
+//! # Usage
+//! ```
+//! use orthrus_godot::project::{ProjectSettings, Variant};
+//!
+//! let mut settings = ProjectSettings::new(4);
+//! settings.set("application/config/name", Variant::String("Orthrus".into()));
+//! settings.set("application/run/main_scene", Variant::String("res://main.tscn".into()));
+//! let features = vec!["4.2".to_owned(), "Forward Plus".to_owned()];
+//! settings.set("application/config/features", Variant::StringArray(features));
+//!
+//! let settings = ProjectSettings::load(&settings.to_bytes()?)?;
+//! assert_eq!(settings.name(), Some("Orthrus"));
+//! assert_eq!(settings.main_scene(), Some("res://main.tscn"));
+//! assert_eq!(settings.godot_version(), Some((4, 2)));
+//! # Ok::<(), orthrus_godot::project::Error>(())
+//! ```
+
+use orthrus_core::prelude::*;
+use snafu::prelude::*;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
+/// Error conditions for when working with project settings.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown if a [`DataError`] other than EndOfFile is encountered.
+    #[snafu(display("Decoding Error {source}"))]
+    DataError { source: DataError },
+
+    /// Thrown if trying to read the file out of its current bounds.
+    #[snafu(display("Reached the end of the current stream!"))]
+    EndOfFile,
+
+    /// Thrown if the header contains a magic number other than "ECFG".
+    #[snafu(display("Invalid Magic! Expected {:?}.", ProjectSettings::MAGIC))]
+    InvalidMagic,
+
+    /// Thrown if the settings are larger than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+
+    /// Thrown if a setting's key isn't valid UTF-8.
+    #[snafu(display("Invalid setting name! {source}"))]
+    InvalidKey { source: core::str::Utf8Error },
+
+    /// Thrown if a setting's value uses a type that doesn't exist in the project's version of Godot, such as
+    /// a [`StringName`](Variant::StringName) in Godot 3.
+    #[snafu(display("Unable to store {key}, its value isn't supported by Godot {major_version}!"))]
+    UnsupportedValue { key: String, major_version: u32 },
+}
+type Result<T> = core::result::Result<T, Error>;
+
+impl From<DataError> for Error {
+    #[inline]
+    fn from(error: DataError) -> Self {
+        match error {
+            DataError::EndOfFile => Self::EndOfFile,
+            source => Self::DataError { source },
+        }
+    }
+}
+
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Self::LimitExceeded { source }
+    }
+}
+
+/// Value of a single setting, using Godot's own type names.
+///
+/// Only the types that are used by project settings are supported, anything else is kept as
+/// [`Raw`](Variant::Raw) data so that it's written back unchanged.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Variant {
+    /// No value
+    Nil,
+    /// A boolean
+    Bool(bool),
+    /// An integer, stored as 32-bit if it fits
+    Int(i64),
+    /// A floating-point number, stored as 32-bit if it doesn't lose precision
+    Float(f64),
+    /// A string
+    String(String),
+    /// An interned string, only supported by Godot 4
+    StringName(String),
+    /// A 2D vector
+    Vector2([f32; 2]),
+    /// A 2D vector of integers, only supported by Godot 4
+    Vector2i([i32; 2]),
+    /// An RGBA color
+    Color([f32; 4]),
+    /// A list of values of any type
+    Array(Vec<Variant>),
+    /// A list of key/value pairs, in the order they're stored
+    Dictionary(Vec<(Variant, Variant)>),
+    /// A list of strings (`PoolStringArray` in Godot 3, `PackedStringArray` in Godot 4)
+    StringArray(Vec<String>),
+    /// An encoded value of any other type, including its type header
+    Raw(Box<[u8]>),
+}
+
+/// Variant type IDs that were changed in Godot 4, every other type we support uses the same ID.
+struct TypeIds {
+    vector2i: Option<u32>,
+    color: u32,
+    string_name: Option<u32>,
+    dictionary: u32,
+    array: u32,
+    string_array: u32,
+}
+
+impl TypeIds {
+    const GODOT_3: Self = Self {
+        vector2i: None,
+        color: 14,
+        string_name: None,
+        dictionary: 18,
+        array: 19,
+        string_array: 23,
+    };
+    const GODOT_4: Self = Self {
+        vector2i: Some(6),
+        color: 20,
+        string_name: Some(21),
+        dictionary: 27,
+        array: 28,
+        string_array: 34,
+    };
+
+    #[inline]
+    const fn new(major_version: u32) -> &'static Self {
+        match major_version >= 4 {
+            true => &Self::GODOT_4,
+            false => &Self::GODOT_3,
+        }
+    }
+}
+
+impl Variant {
+    const NIL: u32 = 0;
+    const BOOL: u32 = 1;
+    const INT: u32 = 2;
+    const FLOAT: u32 = 3;
+    const STRING: u32 = 4;
+    const VECTOR2: u32 = 5;
+
+    /// Set in the type header when an integer or float is stored as 64-bit.
+    const FLAG_64: u32 = 1 << 16;
+
+    /// Returns the contents of a [`String`](Variant::String) or [`StringName`](Variant::StringName).
+    #[must_use]
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) | Self::StringName(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a [`Bool`](Variant::Bool).
+    #[must_use]
+    #[inline]
+    pub const fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of an [`Int`](Variant::Int).
+    #[must_use]
+    #[inline]
+    pub const fn as_int(&self) -> Option<i64> {
+        match self {
+            Self::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Decodes a single setting's value, keeping it as [`Raw`](Variant::Raw) data if it uses a type we
+    /// don't support, is nested deeper than `max_depth`, or if it wouldn't be written back exactly the same.
+    fn decode(data: &[u8], ids: &TypeIds, max_depth: u32) -> Self {
+        let mut cursor = DataCursorRef::new(data, Endian::Little);
+        let value = Self::read(&mut cursor, ids, max_depth).filter(|value| {
+            let mut encoded = Vec::with_capacity(data.len());
+            value.write(&mut encoded, ids).is_some() && encoded == data
+        });
+        value.unwrap_or_else(|| Self::Raw(data.into()))
+    }
+
+    /// Reads a length-prefixed string, which is padded to a multiple of 4 bytes. String arrays include a
+    /// null terminator in the length, which is removed.
+    fn read_string(data: &mut DataCursorRef<'_>, terminated: bool) -> Option<String> {
+        let length = data.read_u32().ok()? as usize;
+        let bytes = data.read_slice(length).ok()?;
+        let bytes = match terminated {
+            true => bytes.strip_suffix(&[0])?,
+            false => &bytes,
+        };
+        let string = String::from_utf8(bytes.to_vec()).ok()?;
+        data.read_slice((4 - length % 4) % 4).ok()?;
+        Some(string)
+    }
+
+    /// Reads a value, returning [`None`] if it's truncated, uses a type we don't support, or contains values
+    /// nested more than `depth` levels deeper.
+    fn read(data: &mut DataCursorRef<'_>, ids: &TypeIds, depth: u32) -> Option<Self> {
+        let header = data.read_u32().ok()?;
+        let (kind, flags) = (header & 0xFFFF, header & !0xFFFF);
+        let value = match (kind, flags) {
+            (Self::NIL, 0) => Self::Nil,
+            (Self::BOOL, 0) => Self::Bool(data.read_u32().ok()? != 0),
+            (Self::INT, 0) => Self::Int(data.read_i32().ok()?.into()),
+            (Self::INT, Self::FLAG_64) => Self::Int(data.read_i64().ok()?),
+            (Self::FLOAT, 0) => Self::Float(data.read_f32().ok()?.into()),
+            (Self::FLOAT, Self::FLAG_64) => Self::Float(data.read_f64().ok()?),
+            (Self::STRING, 0) => Self::String(Self::read_string(data, false)?),
+            (Self::VECTOR2, 0) => Self::Vector2([data.read_f32().ok()?, data.read_f32().ok()?]),
+            (kind, 0) if Some(kind) == ids.vector2i => {
+                Self::Vector2i([data.read_i32().ok()?, data.read_i32().ok()?])
+            }
+            (kind, 0) if kind == ids.color => Self::Color([
+                data.read_f32().ok()?,
+                data.read_f32().ok()?,
+                data.read_f32().ok()?,
+                data.read_f32().ok()?,
+            ]),
+            (kind, 0) if Some(kind) == ids.string_name => Self::StringName(Self::read_string(data, false)?),
+            // The top bit of the count marks a shared array or dictionary in Godot 3
+            (kind, 0) if kind == ids.array => {
+                let count = data.read_u32().ok()? & 0x7FFF_FFFF;
+                let depth = depth.checked_sub(1)?;
+                Self::Array((0..count).map(|_| Self::read(data, ids, depth)).collect::<Option<_>>()?)
+            }
+            (kind, 0) if kind == ids.dictionary => {
+                let count = data.read_u32().ok()? & 0x7FFF_FFFF;
+                let depth = depth.checked_sub(1)?;
+                let pairs =
+                    (0..count).map(|_| Some((Self::read(data, ids, depth)?, Self::read(data, ids, depth)?)));
+                Self::Dictionary(pairs.collect::<Option<_>>()?)
+            }
+            (kind, 0) if kind == ids.string_array => {
+                let count = data.read_u32().ok()?;
+                let strings = (0..count).map(|_| Self::read_string(data, true));
+                Self::StringArray(strings.collect::<Option<_>>()?)
+            }
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Writes a length-prefixed string, padded to a multiple of 4 bytes, optionally with a null terminator.
+    fn write_string(output: &mut Vec<u8>, string: &str, terminated: bool) {
+        let length = string.len() + usize::from(terminated);
+        output.extend_from_slice(&(length as u32).to_le_bytes());
+        output.extend_from_slice(string.as_bytes());
+        if terminated {
+            output.push(0);
+        }
+        output.resize(output.len() + (4 - length % 4) % 4, 0);
+    }
+
+    /// Writes a value, returning [`None`] if it uses a type that doesn't exist in this version of Godot.
+    fn write(&self, output: &mut Vec<u8>, ids: &TypeIds) -> Option<()> {
+        let kind = match self {
+            Self::Nil => Self::NIL,
+            Self::Bool(_) => Self::BOOL,
+            Self::Int(value) if i32::try_from(*value).is_ok() => Self::INT,
+            Self::Int(_) => Self::INT | Self::FLAG_64,
+            Self::Float(value) if f64::from(*value as f32) == *value => Self::FLOAT,
+            Self::Float(_) => Self::FLOAT | Self::FLAG_64,
+            Self::String(_) => Self::STRING,
+            Self::StringName(_) => ids.string_name?,
+            Self::Vector2(_) => Self::VECTOR2,
+            Self::Vector2i(_) => ids.vector2i?,
+            Self::Color(_) => ids.color,
+            Self::Array(_) => ids.array,
+            Self::Dictionary(_) => ids.dictionary,
+            Self::StringArray(_) => ids.string_array,
+            // Raw data already includes its type header
+            Self::Raw(data) => {
+                output.extend_from_slice(data);
+                return Some(());
+            }
+        };
+        output.extend_from_slice(&kind.to_le_bytes());
+
+        match self {
+            Self::Nil | Self::Raw(_) => {}
+            Self::Bool(value) => output.extend_from_slice(&u32::from(*value).to_le_bytes()),
+            Self::Int(value) => match i32::try_from(*value) {
+                Ok(value) => output.extend_from_slice(&value.to_le_bytes()),
+                Err(_) => output.extend_from_slice(&value.to_le_bytes()),
+            },
+            Self::Float(value) => match kind & Self::FLAG_64 {
+                0 => output.extend_from_slice(&(*value as f32).to_le_bytes()),
+                _ => output.extend_from_slice(&value.to_le_bytes()),
+            },
+            Self::String(string) | Self::StringName(string) => Self::write_string(output, string, false),
+            Self::Vector2(values) => {
+                values.iter().for_each(|value| output.extend_from_slice(&value.to_le_bytes()))
+            }
+            Self::Vector2i(values) => {
+                values.iter().for_each(|value| output.extend_from_slice(&value.to_le_bytes()))
+            }
+            Self::Color(values) => {
+                values.iter().for_each(|value| output.extend_from_slice(&value.to_le_bytes()))
+            }
+            Self::Array(values) => {
+                output.extend_from_slice(&(values.len() as u32).to_le_bytes());
+                for value in values {
+                    value.write(output, ids)?;
+                }
+            }
+            Self::Dictionary(pairs) => {
+                output.extend_from_slice(&(pairs.len() as u32).to_le_bytes());
+                for (key, value) in pairs {
+                    key.write(output, ids)?;
+                    value.write(output, ids)?;
+                }
+            }
+            Self::StringArray(strings) => {
+                output.extend_from_slice(&(strings.len() as u32).to_le_bytes());
+                for string in strings {
+                    Self::write_string(output, string, true);
+                }
+            }
+        }
+        Some(())
+    }
+}
+
+/// The settings of an exported Godot project, see the [module documentation](self) for more information.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectSettings {
+    major_version: u32,
+    settings: Vec<(String, Variant)>,
+}
+
+impl ProjectSettings {
+    /// Unique identifier that tells us if we're reading project settings.
+    pub const MAGIC: [u8; 4] = *b"ECFG";
+
+    /// Path of the settings file inside of a [`ResourcePack`](crate::pck::ResourcePack).
+    pub const PATH: &'static str = "res://project.binary";
+
+    /// Creates empty settings for the given major version of Godot (3 or 4), containing only the matching
+    /// `config_version`.
+    #[must_use]
+    #[inline]
+    pub fn new(major_version: u32) -> Self {
+        let major_version = major_version.clamp(3, 4);
+        let config_version = Variant::Int(i64::from(major_version) + 1);
+        Self {
+            major_version,
+            settings: vec![("config_version".into(), config_version)],
+        }
+    }
+
+    /// Parses the contents of a `project.binary` file.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the data isn't project settings,
+    /// [`EndOfFile`](Error::EndOfFile) if it's truncated, or [`InvalidKey`](Error::InvalidKey) if a
+    /// setting's name isn't valid UTF-8.
+    #[inline]
+    pub fn load(input: &[u8]) -> Result<Self> {
+        Self::load_with_limits(input, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Parses a `project.binary` file the same way as [`load`](Self::load), returning an error instead of
+    /// reading a larger file, or more settings than `limits` allow.
+    ///
+    /// Values that are nested more than `max_depth` arrays or dictionaries deep are kept as
+    /// [`Raw`](Variant::Raw) data instead of being decoded.
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the file exceeds `limits`, or any error from
+    /// [`load`](Self::load).
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_godot::project::{Error, ProjectSettings};
+    /// let data = ProjectSettings::new(4).to_bytes()?;
+    /// let limits = ResourceLimits { max_entries: 0, ..Default::default() };
+    /// let result = ProjectSettings::load_with_limits(&data, &limits);
+    /// assert!(matches!(result, Err(Error::LimitExceeded { .. })));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn load_with_limits(input: &[u8], limits: &ResourceLimits) -> Result<Self> {
+        limits.check_output_size(input.len() as u64)?;
+        let mut data = DataCursorRef::new(input, Endian::Little);
+        ensure!(*data.read_slice(4)? == Self::MAGIC, InvalidMagicSnafu);
+
+        let count = data.read_u32()?;
+        limits.check_entries(count.into())?;
+        let mut encoded = Vec::new();
+        for _ in 0..count {
+            let length = data.read_u32()? as usize;
+            let key = core::str::from_utf8(&data.read_slice(length)?).context(InvalidKeySnafu)?.to_owned();
+            let length = data.read_u32()? as usize;
+            encoded.push((key, data.read_slice(length)?.into_owned()));
+        }
+
+        // config_version is an integer in every version, so it can be read before knowing the type IDs
+        let ids = TypeIds::new(4);
+        let config_version = encoded
+            .iter()
+            .find(|(key, _)| key == "config_version")
+            .and_then(|(_, value)| Variant::decode(value, ids, limits.max_depth).as_int());
+        let major_version = match config_version {
+            Some(version) if version < 5 => 3,
+            _ => 4,
+        };
+
+        let ids = TypeIds::new(major_version);
+        let settings = encoded
+            .into_iter()
+            .map(|(key, value)| (key, Variant::decode(&value, ids, limits.max_depth)))
+            .collect();
+        Ok(Self { major_version, settings })
+    }
+
+    /// Encodes the settings as a `project.binary` file.
+    ///
+    /// # Errors
+    /// Returns [`UnsupportedValue`](Error::UnsupportedValue) if a setting uses a type that doesn't exist in
+    /// this version of Godot.
+    ///
+    /// # Examples
+    /// ```
+    /// use orthrus_core::testing;
+    /// use orthrus_godot::project::{ProjectSettings, Variant};
+    ///
+    /// let mut settings = ProjectSettings::new(3);
+    /// settings.set("application/config/name", Variant::String("Orthrus".into()));
+    /// settings.set("display/window/size/width", Variant::Int(1280));
+    /// let data = settings.to_bytes()?;
+    ///
+    /// let parse = |data: &[u8]| ProjectSettings::load(data);
+    /// assert!(testing::round_trip(&data, parse, ProjectSettings::to_bytes)?.byte_identical);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let ids = TypeIds::new(self.major_version);
+        let mut output = Vec::new();
+        output.extend_from_slice(&Self::MAGIC);
+        output.extend_from_slice(&(self.settings.len() as u32).to_le_bytes());
+
+        let mut value = Vec::new();
+        for (key, setting) in &self.settings {
+            value.clear();
+            setting
+                .write(&mut value, ids)
+                .context(UnsupportedValueSnafu { key: key.as_str(), major_version: self.major_version })?;
+            output.extend_from_slice(&(key.len() as u32).to_le_bytes());
+            output.extend_from_slice(key.as_bytes());
+            output.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            output.extend_from_slice(&value);
+        }
+        Ok(output)
+    }
+
+    /// Returns the major version of Godot that the settings are for, either 3 or 4.
+    #[must_use]
+    #[inline]
+    pub const fn major_version(&self) -> u32 {
+        self.major_version
+    }
+
+    /// Returns the number of settings.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.settings.len()
+    }
+
+    /// Returns whether there aren't any settings.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.settings.is_empty()
+    }
+
+    /// Returns every setting, in the order they're stored.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Variant)> {
+        self.settings.iter().map(|(key, value)| (key.as_str(), value))
+    }
+
+    /// Returns the value of the setting called `key`, such as "application/config/name".
+    #[must_use]
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&Variant> {
+        self.settings.iter().find(|(name, _)| name == key).map(|(_, value)| value)
+    }
+
+    /// Changes the value of the setting called `key`, or adds it to the end if it doesn't exist yet, and
+    /// returns the previous value.
+    pub fn set<K: Into<String>>(&mut self, key: K, value: Variant) -> Option<Variant> {
+        let key = key.into();
+        match self.settings.iter_mut().find(|(name, _)| *name == key) {
+            Some((_, setting)) => Some(core::mem::replace(setting, value)),
+            None => {
+                self.settings.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Removes the setting called `key`, returning its value.
+    pub fn remove(&mut self, key: &str) -> Option<Variant> {
+        let index = self.settings.iter().position(|(name, _)| name == key)?;
+        Some(self.settings.remove(index).1)
+    }
+
+    /// Returns the name of the project.
+    #[must_use]
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.get("application/config/name")?.as_str()
+    }
+
+    /// Returns the path of the scene that's loaded when the game starts, such as "res://main.tscn".
+    #[must_use]
+    #[inline]
+    pub fn main_scene(&self) -> Option<&str> {
+        self.get("application/run/main_scene")?.as_str()
+    }
+
+    /// Returns the features the project was exported with, such as "4.2" and "Forward Plus", which are
+    /// only stored by Godot 4.
+    #[must_use]
+    pub fn features(&self) -> Vec<&str> {
+        match self.get("application/config/features") {
+            Some(Variant::StringArray(features)) => features.iter().map(String::as_str).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the version of Godot that the project was last saved with as (major, minor), which is taken
+    /// from its [`features`](Self::features) and is only stored by Godot 4. See
+    /// [`major_version`](Self::major_version) for older projects.
+    #[must_use]
+    pub fn godot_version(&self) -> Option<(u32, u32)> {
+        self.features().into_iter().find_map(|feature| {
+            let (major, minor) = feature.split_once('.')?;
+            Some((major.parse().ok()?, minor.parse().ok()?))
+        })
+    }
+}
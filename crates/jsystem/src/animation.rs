@@ -0,0 +1,559 @@
+//! Adds support for the J3D animation formats.
+//!
+//! # Format
+//! All animations are [J3D files](crate::j3d) with a single section, and share the same keyframe layout:
+//! * BCK files contain an `ANK1` section, with a scale/rotation/translation track per joint.
+//! * BTK files contain a `TTK1` section, with a scale/rotation/translation track per texture matrix.
+//! * BRK files contain a `TRK1` section, with a RGBA track per TEV color register.
+//!
+//! Each track is stored as a `(count, index, tangent mode)` triple that points into a value table. A count
+//! of 1 is a constant value, otherwise there are `count` keyframes of `(time, value, tangent)` or
+//! `(time, value, in tangent, out tangent)` depending on the tangent mode, which are interpolated using
+//! Hermite curves.
+//!
+//! Joints are referred to by their index in the model's joint hierarchy (JNT1), and materials by name.
+//!
+//! # Usage
+//! ```
+//! use orthrus_jsystem::animation::{JointAnimation, LoopMode};
+//! # // Builds a BCK with a single joint that moves 100 units along X over 30 frames
+//! # let mut bck = b"J3D1bck1\0\0\0\xC0\0\0\0\x01SVR1".to_vec();
+//! # bck.resize(0x20, 0xFF);
+//! # bck.extend(b"ANK1\0\0\0\xA0\x02\0\0\x1E\0\x01\0\x01\0\x01\0\x07");
+//! # bck.extend([0, 0, 0, 0x40, 0, 0, 0, 0x78, 0, 0, 0, 0x7C, 0, 0, 0, 0x80]);
+//! # bck.resize(0x60, 0);
+//! # for translation in [[0, 2, 0, 1, 0, 0], [0, 1, 0, 0, 0, 0], [0, 1, 0, 0, 0, 0]] {
+//! #     bck.extend([0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0]);
+//! #     bck.extend(translation);
+//! # }
+//! # bck.resize(0x98, 0);
+//! # bck.extend(1.0f32.to_be_bytes());
+//! # bck.extend([0, 0, 0, 0]);
+//! # for value in [0.0f32, 0.0, 0.0, 0.0, 30.0, 100.0, 0.0] {
+//! #     bck.extend(value.to_be_bytes());
+//! # }
+//! let animation = JointAnimation::load(&bck)?;
+//! assert_eq!(animation.loop_mode, LoopMode::Repeat);
+//! assert_eq!(animation.duration, 30);
+//!
+//! let joint = &animation.joints[0];
+//! assert_eq!(joint.translation[0].keyframes.len(), 2);
+//! assert_eq!(joint.translation[0].keyframes[1].value, 100.0);
+//! assert_eq!(joint.scale[1].keyframes[0].value, 1.0);
+//! # Ok::<(), orthrus_jsystem::j3d::Error>(())
+//! ```
+
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use num_enum::FromPrimitive;
+use orthrus_core::prelude::*;
+
+use crate::j3d::{self, Error, FileHeader, InvalidDataSnafu};
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
+/// What happens when an animation reaches its last frame.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default, FromPrimitive)]
+#[repr(u8)]
+pub enum LoopMode {
+    /// Play once and stop on the last frame
+    #[default]
+    Once = 0,
+    /// Play once and then reset to the first frame
+    OnceAndReset = 1,
+    /// Loop back to the first frame
+    Repeat = 2,
+    /// Play forwards, then backwards, and stop on the first frame
+    MirrorOnce = 3,
+    /// Alternate between playing forwards and backwards
+    MirrorRepeat = 4,
+}
+
+/// A single keyframe, with Hermite tangents.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct Keyframe {
+    /// Frame number of this keyframe
+    pub time: f32,
+    /// Value at this keyframe
+    pub value: f32,
+    /// Incoming tangent
+    pub in_tangent: f32,
+    /// Outgoing tangent, the same as the incoming tangent unless the track uses split tangents
+    pub out_tangent: f32,
+}
+
+/// A single animated value, such as the X translation of a joint.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Track {
+    /// All keyframes, where a single keyframe is a constant value
+    pub keyframes: Vec<Keyframe>,
+}
+
+/// Scale, rotation, and translation tracks for the X, Y, and Z axes.
+///
+/// Rotations are in degrees.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct TransformTracks {
+    /// Scale for each axis
+    pub scale: [Track; 3],
+    /// Rotation for each axis, in degrees
+    pub rotation: [Track; 3],
+    /// Translation for each axis
+    pub translation: [Track; 3],
+}
+
+/// Joint animation (BCK), with one set of transform tracks per joint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JointAnimation {
+    /// What happens when the animation reaches its last frame
+    pub loop_mode: LoopMode,
+    /// Length of the animation, in frames
+    pub duration: u16,
+    /// Transform tracks, indexed the same as the model's joints
+    pub joints: Vec<TransformTracks>,
+}
+
+/// Animation of a single texture matrix.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextureMatrixAnimation {
+    /// Name of the material that owns the texture matrix
+    pub material_name: String,
+    /// Index of the texture matrix inside the material
+    pub texture_matrix: u8,
+    /// Center of rotation and scaling
+    pub center: [f32; 3],
+    /// Transform tracks, where X/Y/Z are the S/T/Q texture coordinates
+    pub tracks: TransformTracks,
+}
+
+/// Texture SRT animation (BTK), with one set of transform tracks per texture matrix.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextureAnimation {
+    /// What happens when the animation reaches its last frame
+    pub loop_mode: LoopMode,
+    /// Length of the animation, in frames
+    pub duration: u16,
+    /// Animated texture matrices
+    pub matrices: Vec<TextureMatrixAnimation>,
+}
+
+/// Animation of a single TEV color register.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorRegisterAnimation {
+    /// Name of the material that owns the color register
+    pub material_name: String,
+    /// Index of the color register inside the material
+    pub register: u8,
+    /// Red, green, blue, and alpha tracks
+    pub tracks: [Track; 4],
+}
+
+/// TEV register animation (BRK), with a set of RGBA tracks per color register.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorAnimation {
+    /// What happens when the animation reaches its last frame
+    pub loop_mode: LoopMode,
+    /// Length of the animation, in frames
+    pub duration: u16,
+    /// Animated color registers (`GXSetTevColor`)
+    pub registers: Vec<ColorRegisterAnimation>,
+    /// Animated constant color registers (`GXSetTevKColor`)
+    pub constants: Vec<ColorRegisterAnimation>,
+}
+
+/// Reads an entire value table at the given absolute offset, converting it to floats.
+fn read_f32_table<T: ReadExt + SeekExt>(data: &mut T, offset: u64, count: u16) -> Result<Vec<f32>, Error> {
+    data.set_position(offset)?;
+    (0..count).map(|_| Ok(data.read_f32()?)).collect()
+}
+
+/// Reads an entire value table at the given absolute offset, converting it to floats.
+fn read_i16_table<T: ReadExt + SeekExt>(data: &mut T, offset: u64, count: u16) -> Result<Vec<f32>, Error> {
+    data.set_position(offset)?;
+    (0..count).map(|_| Ok(f32::from(data.read_i16()?))).collect()
+}
+
+/// Reads a `(count, index, tangent mode)` triple, and looks up the keyframes in the value table. Values and
+/// tangents are multiplied by `scale`, which is used to convert fixed-point rotations.
+fn read_track<T: ReadExt + SeekExt>(data: &mut T, table: &[f32], scale: f32) -> Result<Track, Error> {
+    let position = data.position()?;
+    let count = usize::from(data.read_u16()?);
+    let index = usize::from(data.read_u16()?);
+    let tangent_mode = data.read_u16()?;
+
+    let stride = match (count, tangent_mode) {
+        (1, _) => 1,
+        (_, 0) => 3,
+        (_, 1) => 4,
+        _ => InvalidDataSnafu { position: position + 4, reason: "Unknown Tangent Mode" }.fail()?,
+    };
+    let values = match table.get(index..index + count * stride) {
+        Some(values) => values,
+        None => InvalidDataSnafu { position, reason: "Keyframes Out Of Bounds" }.fail()?,
+    };
+
+    let keyframes = values
+        .chunks_exact(stride)
+        .map(|values| match values {
+            [value] => Keyframe { value: value * scale, ..Default::default() },
+            [time, value, tangent] => Keyframe {
+                time: *time,
+                value: value * scale,
+                in_tangent: tangent * scale,
+                out_tangent: tangent * scale,
+            },
+            [time, value, in_tangent, out_tangent, ..] => Keyframe {
+                time: *time,
+                value: value * scale,
+                in_tangent: in_tangent * scale,
+                out_tangent: out_tangent * scale,
+            },
+            _ => unreachable!(),
+        })
+        .collect();
+    Ok(Track { keyframes })
+}
+
+/// Reads scale, rotation, and translation tracks for all three axes, stored interleaved per axis.
+fn read_transform_tracks<T: ReadExt + SeekExt>(
+    data: &mut T, tables: &[Vec<f32>; 3], rotation_scale: f32,
+) -> Result<TransformTracks, Error> {
+    let mut tracks = TransformTracks::default();
+    for axis in 0..3 {
+        tracks.scale[axis] = read_track(data, &tables[0], 1.0)?;
+        tracks.rotation[axis] = read_track(data, &tables[1], rotation_scale)?;
+        tracks.translation[axis] = read_track(data, &tables[2], 1.0)?;
+    }
+    Ok(tracks)
+}
+
+/// Converts the angle multiplier stored in the header into the scale from fixed-point to degrees.
+#[inline]
+fn rotation_scale(multiplier: u8) -> f32 {
+    2f32.powi(multiplier.into()) * (180.0 / 32768.0)
+}
+
+impl JointAnimation {
+    /// Opens a file on disk, loads its contents, and parses it into a new instance.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if the file cannot be read, or any error from [`load`].
+    ///
+    /// [`load`]: Self::load
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::open_with_limits(path, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Opens a file on disk the same way as [`open`](Self::open), returning an error instead of reading a
+    /// larger file than `limits` allow, see [`load_with_limits`](Self::load_with_limits).
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the file exceeds `limits`, or any error from
+    /// [`open`](Self::open).
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn open_with_limits<P: AsRef<Path>>(path: P, limits: &ResourceLimits) -> Result<Self, Error> {
+        let data = j3d::read_file(path.as_ref(), limits)?;
+        Self::load_with_limits(&data, limits)
+    }
+
+    /// Parses a BCK file from memory.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if this isn't a BCK file, or
+    /// [`InvalidData`](Error::InvalidData) if any of the tracks are invalid.
+    #[inline]
+    pub fn load(input: &[u8]) -> Result<Self, Error> {
+        Self::load_with_limits(input, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Parses a BCK file the same way as [`load`](Self::load), returning an error instead of reading a larger
+    /// file, or more sections or joints than `limits` allow.
+    ///
+    /// J3D files don't nest, so `max_depth` isn't used.
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the file exceeds `limits`, or any error from
+    /// [`load`](Self::load).
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_jsystem::j3d::Error;
+    /// # use orthrus_jsystem::prelude::*;
+    /// let limits = ResourceLimits { max_output_size: 4, ..Default::default() };
+    /// let result = JointAnimation::load_with_limits(b"J3D1bck1", &limits);
+    /// assert!(matches!(result, Err(Error::LimitExceeded { .. })));
+    /// ```
+    pub fn load_with_limits(input: &[u8], limits: &ResourceLimits) -> Result<Self, Error> {
+        limits.check_output_size(input.len() as u64)?;
+        let mut data = DataCursorRef::new(input, Endian::Big);
+        let header = FileHeader::new(&mut data, "bck1")?;
+        limits.check_entries(header.section_count.into())?;
+        let section = header.find_section(&mut data, "ANK1")?;
+
+        data.set_position(section + 8)?;
+        let loop_mode = LoopMode::from(data.read_u8()?);
+        let rotation_scale = rotation_scale(data.read_u8()?);
+        let duration = data.read_u16()?;
+        let joint_count = data.read_u16()?;
+        limits.check_entries(joint_count.into())?;
+        let scale_count = data.read_u16()?;
+        let rotation_count = data.read_u16()?;
+        let translation_count = data.read_u16()?;
+        let joint_offset = section + u64::from(data.read_u32()?);
+        let scale_offset = section + u64::from(data.read_u32()?);
+        let rotation_offset = section + u64::from(data.read_u32()?);
+        let translation_offset = section + u64::from(data.read_u32()?);
+
+        let tables = [
+            read_f32_table(&mut data, scale_offset, scale_count)?,
+            read_i16_table(&mut data, rotation_offset, rotation_count)?,
+            read_f32_table(&mut data, translation_offset, translation_count)?,
+        ];
+
+        data.set_position(joint_offset)?;
+        let joints = (0..joint_count)
+            .map(|_| read_transform_tracks(&mut data, &tables, rotation_scale))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { loop_mode, duration, joints })
+    }
+}
+
+impl TextureAnimation {
+    /// Opens a file on disk, loads its contents, and parses it into a new instance.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if the file cannot be read, or any error from [`load`].
+    ///
+    /// [`load`]: Self::load
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::open_with_limits(path, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Opens a file on disk the same way as [`open`](Self::open), returning an error instead of reading a
+    /// larger file than `limits` allow, see [`load_with_limits`](Self::load_with_limits).
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the file exceeds `limits`, or any error from
+    /// [`open`](Self::open).
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn open_with_limits<P: AsRef<Path>>(path: P, limits: &ResourceLimits) -> Result<Self, Error> {
+        let data = j3d::read_file(path.as_ref(), limits)?;
+        Self::load_with_limits(&data, limits)
+    }
+
+    /// Parses a BTK file from memory.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if this isn't a BTK file, or
+    /// [`InvalidData`](Error::InvalidData) if any of the tracks are invalid.
+    #[inline]
+    pub fn load(input: &[u8]) -> Result<Self, Error> {
+        Self::load_with_limits(input, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Parses a BTK file the same way as [`load`](Self::load), returning an error instead of reading a larger
+    /// file, or more sections or texture matrices than `limits` allow.
+    ///
+    /// J3D files don't nest, so `max_depth` isn't used.
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the file exceeds `limits`, or any error from
+    /// [`load`](Self::load).
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_jsystem::j3d::Error;
+    /// # use orthrus_jsystem::prelude::*;
+    /// let limits = ResourceLimits { max_output_size: 4, ..Default::default() };
+    /// let result = TextureAnimation::load_with_limits(b"J3D1btk1", &limits);
+    /// assert!(matches!(result, Err(Error::LimitExceeded { .. })));
+    /// ```
+    pub fn load_with_limits(input: &[u8], limits: &ResourceLimits) -> Result<Self, Error> {
+        limits.check_output_size(input.len() as u64)?;
+        let mut data = DataCursorRef::new(input, Endian::Big);
+        let header = FileHeader::new(&mut data, "btk1")?;
+        limits.check_entries(header.section_count.into())?;
+        let section = header.find_section(&mut data, "TTK1")?;
+
+        data.set_position(section + 8)?;
+        let loop_mode = LoopMode::from(data.read_u8()?);
+        let rotation_scale = rotation_scale(data.read_u8()?);
+        let duration = data.read_u16()?;
+        // Stored as the total number of tracks, three per texture matrix
+        let matrix_count = data.read_u16()? / 3;
+        limits.check_entries(matrix_count.into())?;
+        let scale_count = data.read_u16()?;
+        let rotation_count = data.read_u16()?;
+        let translation_count = data.read_u16()?;
+        let animation_offset = section + u64::from(data.read_u32()?);
+        let _remap_offset = data.read_u32()?;
+        let name_offset = section + u64::from(data.read_u32()?);
+        let index_offset = section + u64::from(data.read_u32()?);
+        let center_offset = section + u64::from(data.read_u32()?);
+        let scale_offset = section + u64::from(data.read_u32()?);
+        let rotation_offset = section + u64::from(data.read_u32()?);
+        let translation_offset = section + u64::from(data.read_u32()?);
+
+        let tables = [
+            read_f32_table(&mut data, scale_offset, scale_count)?,
+            read_i16_table(&mut data, rotation_offset, rotation_count)?,
+            read_f32_table(&mut data, translation_offset, translation_count)?,
+        ];
+        let names = j3d::read_string_table(&mut data, name_offset)?;
+
+        let mut matrices = Vec::with_capacity(matrix_count.into());
+        for index in 0..u64::from(matrix_count) {
+            data.set_position(index_offset + index)?;
+            let texture_matrix = data.read_u8()?;
+            data.set_position(center_offset + index * 12)?;
+            let center = [data.read_f32()?, data.read_f32()?, data.read_f32()?];
+            data.set_position(animation_offset + index * 0x36)?;
+            let tracks = read_transform_tracks(&mut data, &tables, rotation_scale)?;
+            let material_name = names.get(index as usize).cloned().unwrap_or_default();
+            matrices.push(TextureMatrixAnimation { material_name, texture_matrix, center, tracks });
+        }
+
+        Ok(Self { loop_mode, duration, matrices })
+    }
+}
+
+impl ColorAnimation {
+    /// Opens a file on disk, loads its contents, and parses it into a new instance.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if the file cannot be read, or any error from [`load`].
+    ///
+    /// [`load`]: Self::load
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::open_with_limits(path, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Opens a file on disk the same way as [`open`](Self::open), returning an error instead of reading a
+    /// larger file than `limits` allow, see [`load_with_limits`](Self::load_with_limits).
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the file exceeds `limits`, or any error from
+    /// [`open`](Self::open).
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn open_with_limits<P: AsRef<Path>>(path: P, limits: &ResourceLimits) -> Result<Self, Error> {
+        let data = j3d::read_file(path.as_ref(), limits)?;
+        Self::load_with_limits(&data, limits)
+    }
+
+    /// Parses a BRK file from memory.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if this isn't a BRK file, or
+    /// [`InvalidData`](Error::InvalidData) if any of the tracks are invalid.
+    #[inline]
+    pub fn load(input: &[u8]) -> Result<Self, Error> {
+        Self::load_with_limits(input, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Parses a BRK file the same way as [`load`](Self::load), returning an error instead of reading a larger
+    /// file, or more sections or color registers than `limits` allow.
+    ///
+    /// J3D files don't nest, so `max_depth` isn't used.
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the file exceeds `limits`, or any error from
+    /// [`load`](Self::load).
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_jsystem::j3d::Error;
+    /// # use orthrus_jsystem::prelude::*;
+    /// let limits = ResourceLimits { max_output_size: 4, ..Default::default() };
+    /// let result = ColorAnimation::load_with_limits(b"J3D1brk1", &limits);
+    /// assert!(matches!(result, Err(Error::LimitExceeded { .. })));
+    /// ```
+    pub fn load_with_limits(input: &[u8], limits: &ResourceLimits) -> Result<Self, Error> {
+        limits.check_output_size(input.len() as u64)?;
+        let mut data = DataCursorRef::new(input, Endian::Big);
+        let header = FileHeader::new(&mut data, "brk1")?;
+        limits.check_entries(header.section_count.into())?;
+        let section = header.find_section(&mut data, "TRK1")?;
+
+        data.set_position(section + 8)?;
+        let loop_mode = LoopMode::from(data.read_u8()?);
+        let _padding = data.read_u8()?;
+        let duration = data.read_u16()?;
+        let register_count = data.read_u16()?;
+        let constant_count = data.read_u16()?;
+        limits.check_entries(u64::from(register_count) + u64::from(constant_count))?;
+        let mut value_counts = [0u16; 8];
+        for count in &mut value_counts {
+            *count = data.read_u16()?;
+        }
+        let register_offset = section + u64::from(data.read_u32()?);
+        let constant_offset = section + u64::from(data.read_u32()?);
+        let _register_remap_offset = data.read_u32()?;
+        let _constant_remap_offset = data.read_u32()?;
+        let register_names_offset = section + u64::from(data.read_u32()?);
+        let constant_names_offset = section + u64::from(data.read_u32()?);
+        let mut value_offsets = [0u64; 8];
+        for offset in &mut value_offsets {
+            *offset = section + u64::from(data.read_u32()?);
+        }
+
+        // Color values are stored as separate R/G/B/A tables, first for the registers and then the constants
+        let mut tables: [Vec<f32>; 8] = Default::default();
+        for ((table, offset), count) in tables.iter_mut().zip(value_offsets).zip(value_counts) {
+            *table = read_i16_table(&mut data, offset, count)?;
+        }
+        let (register_tables, constant_tables) = tables.split_at(4);
+
+        let registers = Self::read_registers(
+            &mut data,
+            register_offset,
+            register_count,
+            register_names_offset,
+            register_tables,
+        )?;
+        let constants = Self::read_registers(
+            &mut data,
+            constant_offset,
+            constant_count,
+            constant_names_offset,
+            constant_tables,
+        )?;
+
+        Ok(Self { loop_mode, duration, registers, constants })
+    }
+
+    fn read_registers<T: ReadExt + SeekExt>(
+        data: &mut T, offset: u64, count: u16, names_offset: u64, tables: &[Vec<f32>],
+    ) -> Result<Vec<ColorRegisterAnimation>, Error> {
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        let names = j3d::read_string_table(data, names_offset)?;
+
+        let mut registers = Vec::with_capacity(count.into());
+        for index in 0..u64::from(count) {
+            data.set_position(offset + index * 0x1C)?;
+            let mut tracks: [Track; 4] = Default::default();
+            for (track, table) in tracks.iter_mut().zip(tables) {
+                *track = read_track(data, table, 1.0)?;
+            }
+            let register = data.read_u8()?;
+            let material_name = names.get(index as usize).cloned().unwrap_or_default();
+            registers.push(ColorRegisterAnimation { material_name, register, tracks });
+        }
+        Ok(registers)
+    }
+}
@@ -0,0 +1,191 @@
+//! Adds support for J3D material tables (BMT), which replace the materials and textures of a model at runtime.
+//!
+//! # Format
+//! BMT files are [J3D files](crate::j3d) of kind "bmt3", containing a `MAT3` section with the materials (see
+//! [`material`](crate::material)) and usually a `TEX1` section with the textures they use. The `TEX1` section
+//! is laid out as follows:
+//!
+//! | Offset | Field                | Type | Notes                                                     |
+//! |--------|----------------------|------|-----------------------------------------------------------|
+//! | 0x00   | Magic                | u32  | Always "TEX1"                                             |
+//! | 0x04   | Section Size         | u32  | Size of the entire section                                |
+//! | 0x08   | Texture Count        | u16  |                                                           |
+//! | 0x0A   | Padding              | u16  | Usually 0xFFFF                                            |
+//! | 0x0C   | Texture Table Offset | u32  | Offset to an array of 0x20-byte [BTI headers](crate::bti) |
+//! | 0x10   | Name Table Offset    | u32  | Offset to a [string table](crate::j3d::read_string_table) |
+//!
+//! Both offsets are relative to the start of the section, and the texture slots of each material are indices
+//! into the texture table.
+//!
+//! # Usage
+//! ```
+//! use orthrus_jsystem::bmt::BinaryMaterialTable;
+//! use orthrus_jsystem::bti::BinaryTextureImage;
+//! use orthrus_texture::gx::TextureFormat;
+//! # // Builds a BMT without any materials, and a single 8x8 texture called "eye"
+//! # let section = |magic: &[u8], mut body: Vec<u8>| {
+//! #     body.resize((body.len() + 8).next_multiple_of(0x20) - 8, 0);
+//! #     let mut section = magic.to_vec();
+//! #     section.extend((8 + body.len() as u32).to_be_bytes());
+//! #     section.extend(body);
+//! #     section
+//! # };
+//! # let mut mat3 = vec![0u8; 0x7C];
+//! # mat3[4..12].copy_from_slice(&[0, 0, 0, 0x84, 0, 0, 0, 0x84]);
+//! # let texture = BinaryTextureImage::from_rgba(&[0xFF; 8 * 8 * 4], 8, 8, TextureFormat::I8)?.to_bytes()?;
+//! # let mut tex1 = vec![0, 1, 0xFF, 0xFF, 0, 0, 0, 0x20];
+//! # tex1.extend((0x20 + texture.len() as u32).to_be_bytes());
+//! # tex1.resize(0x18, 0);
+//! # tex1.extend(texture);
+//! # tex1.extend(b"\0\x01\xFF\xFF\0\0\0\x08eye\0");
+//! # let mut bmt = b"J3D2bmt3".to_vec();
+//! # bmt.extend([0; 4]);
+//! # bmt.extend([0, 0, 0, 2]);
+//! # bmt.extend(b"SVR3");
+//! # bmt.resize(0x20, 0xFF);
+//! # bmt.extend(section(b"MAT3", mat3));
+//! # bmt.extend(section(b"TEX1", tex1));
+//! let table = BinaryMaterialTable::load(&bmt)?;
+//! assert!(table.materials.materials.is_empty());
+//!
+//! let texture = table.find_texture("eye").unwrap();
+//! assert_eq!(texture.header.format, TextureFormat::I8);
+//! let image = texture.decode(0)?;
+//! assert_eq!((image.width, image.height), (8, 8));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use orthrus_core::prelude::*;
+use snafu::prelude::*;
+
+use crate::bti::BinaryTextureImage;
+use crate::j3d::{self, Error, FileHeader, TextureSnafu};
+use crate::material::MaterialTable;
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
+/// A single texture from the `TEX1` section, along with its name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Texture {
+    /// Name of the texture, from the name table
+    pub name: String,
+    /// The texture itself
+    pub image: BinaryTextureImage,
+}
+
+/// Materials and textures read from a BMT file.
+///
+/// See the [module documentation](self) for more information.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BinaryMaterialTable {
+    /// Materials from the `MAT3` section
+    pub materials: MaterialTable,
+    /// Textures from the `TEX1` section, indexed the same as the texture slots of each material
+    pub textures: Vec<Texture>,
+}
+
+impl BinaryMaterialTable {
+    /// Size of a single texture header, in bytes.
+    pub const TEXTURE_HEADER_SIZE: u64 = 0x20;
+
+    /// Opens a file on disk, loads its contents, and parses it into a new instance.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if the file cannot be read, or any error from [`load`].
+    ///
+    /// [`load`]: Self::load
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::open_with_limits(path, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Opens a file on disk the same way as [`open`](Self::open), returning an error instead of reading a
+    /// larger file than `limits` allow, see [`load_with_limits`](Self::load_with_limits).
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the file exceeds `limits`, or any error from
+    /// [`open`](Self::open).
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn open_with_limits<P: AsRef<Path>>(path: P, limits: &ResourceLimits) -> Result<Self, Error> {
+        let data = j3d::read_file(path.as_ref(), limits)?;
+        Self::load_with_limits(&data, limits)
+    }
+
+    /// Parses a BMT file from memory. Files without a `TEX1` section are read as having no textures.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if this isn't a BMT file,
+    /// [`MissingSection`](Error::MissingSection) if it doesn't have any materials,
+    /// [`TextureError`](Error::TextureError) if any of the textures are invalid, or any error from
+    /// [`MaterialTable::load`].
+    #[inline]
+    pub fn load(input: &[u8]) -> Result<Self, Error> {
+        Self::load_with_limits(input, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Parses a BMT file the same way as [`load`](Self::load), returning an error instead of reading a larger
+    /// file, more sections, materials, or textures, or any texture that would decode to more data than
+    /// `limits` allow.
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the file exceeds `limits`, or any error from
+    /// [`load`](Self::load).
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_jsystem::bmt::BinaryMaterialTable;
+    /// # use orthrus_jsystem::j3d::Error;
+    /// let limits = ResourceLimits { max_output_size: 4, ..Default::default() };
+    /// let result = BinaryMaterialTable::load_with_limits(b"J3D2bmt3", &limits);
+    /// assert!(matches!(result, Err(Error::LimitExceeded { .. })));
+    /// ```
+    pub fn load_with_limits(input: &[u8], limits: &ResourceLimits) -> Result<Self, Error> {
+        limits.check_output_size(input.len() as u64)?;
+        let mut data = DataCursorRef::new(input, Endian::Big);
+        let header = FileHeader::new(&mut data, "bmt3")?;
+        limits.check_entries(header.section_count.into())?;
+
+        let materials = MaterialTable::load_with_limits(input, limits)?;
+        let textures = match header.find_section(&mut data, "TEX1") {
+            Ok(section) => Self::read_textures(input, &mut data, section, limits)?,
+            Err(Error::MissingSection { .. }) => Vec::new(),
+            Err(error) => return Err(error),
+        };
+        Ok(Self { materials, textures })
+    }
+
+    /// Reads every texture from the `TEX1` section at the given absolute offset.
+    fn read_textures(
+        input: &[u8], data: &mut DataCursorRef, section: u64, limits: &ResourceLimits,
+    ) -> Result<Vec<Texture>, Error> {
+        data.set_position(section + 8)?;
+        let count = data.read_u16()?;
+        limits.check_entries(count.into())?;
+        let _padding = data.read_u16()?;
+        let headers = section + u64::from(data.read_u32()?);
+        let names = section + u64::from(data.read_u32()?);
+        let names = j3d::read_string_table(data, names)?;
+
+        let mut textures = Vec::with_capacity(count.into());
+        for index in 0..count {
+            let offset = headers + u64::from(index) * Self::TEXTURE_HEADER_SIZE;
+            let image = BinaryTextureImage::read(input, offset, limits).context(TextureSnafu)?;
+            let name = names.get(usize::from(index)).cloned().unwrap_or_default();
+            textures.push(Texture { name, image });
+        }
+        Ok(textures)
+    }
+
+    /// Returns the texture with the given name, if any.
+    #[must_use]
+    #[inline]
+    pub fn find_texture(&self, name: &str) -> Option<&BinaryTextureImage> {
+        self.textures.iter().find(|texture| texture.name == name).map(|texture| &texture.image)
+    }
+}
@@ -0,0 +1,542 @@
+//! Adds support for Binary Texture Images (BTI), the standalone texture format used by JSystem.
+//!
+//! # Format
+//! BTI files are a single big-endian 0x20-byte header, followed by the palette (if any) and the image data.
+//! All offsets are relative to the start of the header, since the same header is also embedded inside
+//! models (see TEX1 in BMD/BDL), where the data follows each header in a table.
+//!
+//! Image data can be in any [GX texture format](orthrus_texture::gx), with each mipmap following the last.
+//!
+//! # Usage
+//! ```
+//! use orthrus_jsystem::bti::BinaryTextureImage;
+//! use orthrus_texture::gx::TextureFormat;
+//!
+//! // Create a new texture from RGBA8 data, and then write it out
+//! let rgba = vec![0xFF; 16 * 16 * 4];
+//! let texture = BinaryTextureImage::from_rgba(&rgba, 16, 16, TextureFormat::CMPR)?;
+//! let bytes = texture.to_bytes()?;
+//!
+//! // Then load it back in and decode it
+//! let texture = BinaryTextureImage::load(&bytes)?;
+//! assert_eq!(texture.header.width, 16);
+//! let image = texture.decode(0)?;
+//! assert_eq!(&*image.data, &*rgba);
+//! # Ok::<(), orthrus_jsystem::bti::Error>(())
+//! ```
+
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use orthrus_core::prelude::*;
+use orthrus_texture::gx::{self, PaletteFormat, TextureFormat};
+use snafu::prelude::*;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
+/// Error conditions when working with Binary Texture Images.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    #[cfg(feature = "std")]
+    #[snafu(display("Filesystem Error {}", source))]
+    FileError { source: std::io::Error },
+
+    /// Thrown if trying to read the file out of its current bounds.
+    #[snafu(display("Reached the end of the current stream!"))]
+    EndOfFile,
+
+    /// Thrown if a [`DataError`] other than EndOfFile is encountered.
+    #[snafu(display("Decoding Error {source}"))]
+    DataError { source: DataError },
+
+    /// Thrown when encountering unexpected values.
+    #[snafu(display(
+        "Unexpected value encountered at position {:#X}! Reason: {}",
+        position,
+        reason
+    ))]
+    InvalidData { position: u64, reason: &'static str },
+
+    /// Thrown if the texture is larger than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+
+    /// Thrown if trying to access a mipmap level that doesn't exist.
+    #[snafu(display("Mipmap level {level} does not exist, texture only has {count}!"))]
+    InvalidMipmap { level: usize, count: usize },
+
+    /// Thrown if encoding or decoding the texture data fails.
+    #[snafu(display("{source}"))]
+    Texture { source: gx::Error },
+}
+
+impl From<DataError> for Error {
+    #[inline]
+    fn from(error: DataError) -> Self {
+        match error {
+            #[cfg(feature = "std")]
+            DataError::Io { source } => Self::FileError { source },
+            DataError::EndOfFile => Self::EndOfFile,
+            source => Self::DataError { source },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(error: std::io::Error) -> Self {
+        Error::FileError { source: error }
+    }
+}
+
+impl From<gx::Error> for Error {
+    #[inline]
+    fn from(source: gx::Error) -> Self {
+        Error::Texture { source }
+    }
+}
+
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Error::LimitExceeded { source }
+    }
+}
+
+/// Texture header, shared between standalone BTI files and the texture tables inside models.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// Format of the image data
+    pub format: TextureFormat,
+    /// Whether the texture uses alpha (0 = opaque, 1 = one-bit, 2 = full)
+    pub alpha: u8,
+    /// Width of the base level, in pixels
+    pub width: u16,
+    /// Height of the base level, in pixels
+    pub height: u16,
+    /// Horizontal wrap mode (0 = clamp, 1 = repeat, 2 = mirror)
+    pub wrap_s: u8,
+    /// Vertical wrap mode (0 = clamp, 1 = repeat, 2 = mirror)
+    pub wrap_t: u8,
+    /// Whether the texture has a palette
+    pub palettes_enabled: bool,
+    /// Format of the palette entries
+    pub palette_format: PaletteFormat,
+    /// Number of palette entries
+    pub palette_count: u16,
+    /// Relative to the start of the header
+    pub palette_offset: u32,
+    /// Whether the texture has mipmaps
+    pub mipmaps_enabled: bool,
+    /// Whether edge level-of-detail is enabled
+    pub edge_lod: bool,
+    /// Whether the level-of-detail bias is clamped
+    pub bias_clamp: bool,
+    /// Maximum anisotropic filtering (0 = 1x, 1 = 2x, 2 = 4x)
+    pub max_anisotropy: u8,
+    /// Minification filter
+    pub min_filter: u8,
+    /// Magnification filter
+    pub mag_filter: u8,
+    /// Minimum level-of-detail, in fixed-point 1/8ths
+    pub min_lod: i8,
+    /// Maximum level-of-detail, in fixed-point 1/8ths
+    pub max_lod: i8,
+    /// Number of images (including the base level)
+    pub image_count: u8,
+    /// Level-of-detail bias, in fixed-point 1/100ths
+    pub lod_bias: i16,
+    /// Relative to the start of the header
+    pub image_offset: u32,
+}
+
+impl Header {
+    /// Size of the header, in bytes.
+    pub const SIZE: usize = 0x20;
+
+    /// Reads a header from the current position.
+    ///
+    /// # Errors
+    /// Returns [`EndOfFile`](Error::EndOfFile) if the header is truncated, or
+    /// [`InvalidData`](Error::InvalidData) if the texture or palette format is unknown.
+    #[inline]
+    pub fn new<T: ReadExt + SeekExt>(data: &mut T) -> Result<Self, self::Error> {
+        let format = match TextureFormat::try_from(data.read_u8()?) {
+            Ok(format) => format,
+            Err(_) => InvalidDataSnafu { position: data.position()? - 1, reason: "Unknown Texture Format" }
+                .fail()?,
+        };
+        let alpha = data.read_u8()?;
+        let width = data.read_u16()?;
+        let height = data.read_u16()?;
+        ensure!(
+            width != 0 && height != 0,
+            InvalidDataSnafu { position: data.position()? - 4, reason: "Dimensions Must Be Non-Zero" }
+        );
+        let wrap_s = data.read_u8()?;
+        let wrap_t = data.read_u8()?;
+        let palettes_enabled = data.read_u8()? != 0;
+        // The palette format is only meaningful for paletted textures, others may have garbage here
+        let palette_format = match (PaletteFormat::try_from(data.read_u8()?), format.is_paletted()) {
+            (Ok(palette_format), _) => palette_format,
+            (Err(_), false) => PaletteFormat::default(),
+            (Err(_), true) => {
+                InvalidDataSnafu { position: data.position()? - 1, reason: "Unknown Palette Format" }
+                    .fail()?
+            }
+        };
+        let palette_count = data.read_u16()?;
+        let palette_offset = data.read_u32()?;
+        let mipmaps_enabled = data.read_u8()? != 0;
+        let edge_lod = data.read_u8()? != 0;
+        let bias_clamp = data.read_u8()? != 0;
+        let max_anisotropy = data.read_u8()?;
+        let min_filter = data.read_u8()?;
+        let mag_filter = data.read_u8()?;
+        let min_lod = data.read_i8()?;
+        let max_lod = data.read_i8()?;
+        let image_count = data.read_u8()?;
+        let _unknown = data.read_u8()?;
+        let lod_bias = data.read_i16()?;
+        let image_offset = data.read_u32()?;
+
+        Ok(Self {
+            format,
+            alpha,
+            width,
+            height,
+            wrap_s,
+            wrap_t,
+            palettes_enabled,
+            palette_format,
+            palette_count,
+            palette_offset,
+            mipmaps_enabled,
+            edge_lod,
+            bias_clamp,
+            max_anisotropy,
+            min_filter,
+            mag_filter,
+            min_lod,
+            max_lod,
+            image_count,
+            lod_bias,
+            image_offset,
+        })
+    }
+
+    /// Writes the header to the current position.
+    ///
+    /// # Errors
+    /// Returns [`EndOfFile`](Error::EndOfFile) if there isn't enough room for the header.
+    #[inline]
+    pub fn write<T: WriteExt>(&self, data: &mut T) -> Result<(), self::Error> {
+        data.write_u8(self.format as u8)?;
+        data.write_u8(self.alpha)?;
+        data.write_u16(self.width)?;
+        data.write_u16(self.height)?;
+        data.write_u8(self.wrap_s)?;
+        data.write_u8(self.wrap_t)?;
+        data.write_u8(self.palettes_enabled.into())?;
+        data.write_u8(self.palette_format as u8)?;
+        data.write_u16(self.palette_count)?;
+        data.write_u32(self.palette_offset)?;
+        data.write_u8(self.mipmaps_enabled.into())?;
+        data.write_u8(self.edge_lod.into())?;
+        data.write_u8(self.bias_clamp.into())?;
+        data.write_u8(self.max_anisotropy)?;
+        data.write_u8(self.min_filter)?;
+        data.write_u8(self.mag_filter)?;
+        data.write_i8(self.min_lod)?;
+        data.write_i8(self.max_lod)?;
+        data.write_u8(self.image_count)?;
+        data.write_u8(0)?;
+        data.write_i16(self.lod_bias)?;
+        data.write_u32(self.image_offset)?;
+        Ok(())
+    }
+
+    /// Returns the number of mipmap levels, including the base level.
+    #[must_use]
+    #[inline]
+    pub fn mipmap_count(&self) -> usize {
+        usize::from(self.image_count).max(1)
+    }
+
+    /// Returns the dimensions of the given mipmap level.
+    #[must_use]
+    #[inline]
+    pub fn mipmap_size(&self, level: usize) -> (u32, u32) {
+        let shift = level.min(31) as u32;
+        (
+            (u32::from(self.width) >> shift).max(1),
+            (u32::from(self.height) >> shift).max(1),
+        )
+    }
+}
+
+/// A single decoded texture level, in tightly packed RGBA8.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    /// Width of the image, in pixels
+    pub width: u32,
+    /// Height of the image, in pixels
+    pub height: u32,
+    /// RGBA8 pixel data
+    pub data: Box<[u8]>,
+}
+
+/// A texture with all of its mipmaps and palette data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryTextureImage {
+    /// Texture header, describing the format and sampling parameters
+    pub header: Header,
+    /// Raw palette data, empty if the texture isn't paletted
+    palette: Box<[u8]>,
+    /// Raw image data, containing all mipmap levels
+    data: Box<[u8]>,
+}
+
+impl BinaryTextureImage {
+    /// Opens a file on disk, loads its contents, and parses it into a new instance. The instance can then be
+    /// used for further operations.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if the file cannot be read, or any error from [`load`].
+    ///
+    /// [`load`]: Self::load
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, self::Error> {
+        Self::open_with_limits(path, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Opens a file on disk the same way as [`open`](Self::open), returning an error instead of reading a
+    /// larger file than `limits` allow, see [`load_with_limits`](Self::load_with_limits).
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the file exceeds `limits`, or any error from
+    /// [`open`](Self::open).
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn open_with_limits<P: AsRef<Path>>(path: P, limits: &ResourceLimits) -> Result<Self, self::Error> {
+        // Check the size before reading the whole file into memory
+        let path = path.as_ref();
+        limits.check_output_size(std::fs::metadata(path)?.len())?;
+        let data = std::fs::read(path)?;
+        Self::load_with_limits(&data, limits)
+    }
+
+    /// Parses a BTI file from memory.
+    ///
+    /// # Errors
+    /// Returns [`EndOfFile`](Error::EndOfFile) if the palette or image data is truncated, or
+    /// [`InvalidData`](Error::InvalidData) if the header is invalid.
+    #[inline]
+    pub fn load(input: &[u8]) -> Result<Self, self::Error> {
+        Self::load_embedded(input, 0)
+    }
+
+    /// Parses a BTI file the same way as [`load`](Self::load), returning an error instead of reading a
+    /// larger file, or a texture that would decode to more data than `limits` allow.
+    ///
+    /// `max_output_size` applies to both the file and the total size of every mipmap decoded to RGBA8. BTI
+    /// files only contain a single texture, so `max_entries` and `max_depth` aren't used.
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the texture exceeds `limits`, or any error from
+    /// [`load`](Self::load).
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_jsystem::bti::{BinaryTextureImage, Error};
+    /// # use orthrus_texture::gx::TextureFormat;
+    /// let texture = BinaryTextureImage::from_rgba(&[0xFF; 64 * 64 * 4], 64, 64, TextureFormat::I4)?;
+    /// let bytes = texture.to_bytes()?;
+    ///
+    /// // The file itself fits, but decoding it to RGBA8 doesn't
+    /// let limits = ResourceLimits { max_output_size: 0x1000, ..Default::default() };
+    /// let result = BinaryTextureImage::load_with_limits(&bytes, &limits);
+    /// assert!(matches!(result, Err(Error::LimitExceeded { .. })));
+    /// # Ok::<(), Error>(())
+    /// ```
+    #[inline]
+    pub fn load_with_limits(input: &[u8], limits: &ResourceLimits) -> Result<Self, self::Error> {
+        limits.check_output_size(input.len() as u64)?;
+        Self::read(input, 0, limits)
+    }
+
+    /// Parses a texture whose header is at `offset` inside a larger file, such as the texture table of a
+    /// model. All offsets in the header are relative to the header itself.
+    ///
+    /// # Errors
+    /// Returns [`EndOfFile`](Error::EndOfFile) if the palette or image data is truncated, or
+    /// [`InvalidData`](Error::InvalidData) if the header is invalid.
+    #[inline]
+    pub fn load_embedded(input: &[u8], offset: u64) -> Result<Self, self::Error> {
+        Self::read(input, offset, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Reads the texture whose header is at `offset`, checking its decoded size against `limits`.
+    pub(crate) fn read(input: &[u8], offset: u64, limits: &ResourceLimits) -> Result<Self, self::Error> {
+        let mut data = DataCursorRef::new(input, Endian::Big);
+        data.set_position(offset)?;
+        let header = Header::new(&mut data)?;
+
+        let decoded_size = (0..header.mipmap_count())
+            .map(|level| {
+                let (width, height) = header.mipmap_size(level);
+                u64::from(width) * u64::from(height) * 4
+            })
+            .sum();
+        limits.check_output_size(decoded_size)?;
+
+        let palette = match header.format.is_paletted() {
+            true => {
+                data.set_position(offset + u64::from(header.palette_offset))?;
+                Box::from(&*data.read_slice(usize::from(header.palette_count) * 2)?)
+            }
+            false => Box::default(),
+        };
+
+        let size = (0..header.mipmap_count())
+            .map(|level| {
+                let (width, height) = header.mipmap_size(level);
+                gx::data_size(width, height, header.format)
+            })
+            .sum();
+        data.set_position(offset + u64::from(header.image_offset))?;
+        let image = Box::from(&*data.read_slice(size)?);
+
+        Ok(Self { header, palette, data: image })
+    }
+
+    /// Creates a new single-level texture by encoding RGBA8 data, using default sampling parameters.
+    ///
+    /// # Errors
+    /// Returns [`InvalidData`](Error::InvalidData) if the dimensions don't fit in a BTI, or
+    /// [`Texture`](Error::Texture) if encoding fails, such as when using a paletted format.
+    pub fn from_rgba(
+        rgba: &[u8], width: u32, height: u32, format: TextureFormat,
+    ) -> Result<Self, self::Error> {
+        ensure!(
+            (1..=0x400).contains(&width) && (1..=0x400).contains(&height),
+            InvalidDataSnafu { position: 0u64, reason: "Dimensions Must Be Between 1 and 1024" }
+        );
+        let data = gx::encode(rgba, width, height, format)?;
+        let has_alpha = rgba.chunks_exact(4).any(|pixel| pixel[3] != 0xFF);
+
+        let header = Header {
+            format,
+            alpha: has_alpha.into(),
+            width: width as u16,
+            height: height as u16,
+            wrap_s: 0,
+            wrap_t: 0,
+            palettes_enabled: false,
+            palette_format: PaletteFormat::default(),
+            palette_count: 0,
+            palette_offset: 0,
+            mipmaps_enabled: false,
+            edge_lod: false,
+            bias_clamp: false,
+            max_anisotropy: 0,
+            min_filter: 1,
+            mag_filter: 1,
+            min_lod: 0,
+            max_lod: 0,
+            image_count: 1,
+            lod_bias: 0,
+            image_offset: Header::SIZE as u32,
+        };
+        Ok(Self { header, palette: Box::default(), data })
+    }
+
+    /// Serializes the texture into a standalone BTI file, with the palette directly after the header and
+    /// the image data aligned to 0x20 bytes after that.
+    ///
+    /// # Errors
+    /// Returns [`EndOfFile`](Error::EndOfFile) if the header can't be written.
+    ///
+    /// # Examples
+    /// ```
+    /// use orthrus_core::testing;
+    /// use orthrus_jsystem::bti::BinaryTextureImage;
+    /// use orthrus_texture::gx::TextureFormat;
+    ///
+    /// let rgba: Vec<u8> = (0..8 * 8 * 4).map(|i| i as u8).collect();
+    /// let data = BinaryTextureImage::from_rgba(&rgba, 8, 8, TextureFormat::RGBA32)?.to_bytes()?;
+    ///
+    /// let parse = |data: &[u8]| BinaryTextureImage::load(data);
+    /// let write = |texture: &BinaryTextureImage| texture.to_bytes();
+    /// assert!(testing::round_trip(&data, parse, write)?.byte_identical);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn to_bytes(&self) -> Result<Box<[u8]>, self::Error> {
+        let mut header = self.header.clone();
+        header.palette_offset = match self.palette.is_empty() {
+            true => 0,
+            false => Header::SIZE as u32,
+        };
+        header.image_offset = (Header::SIZE + self.palette.len().next_multiple_of(0x20)) as u32;
+
+        let mut output = vec![0u8; header.image_offset as usize + self.data.len()];
+        let mut cursor = DataCursorMut::new(&mut output, Endian::Big);
+        header.write(&mut cursor)?;
+        output[Header::SIZE..Header::SIZE + self.palette.len()].copy_from_slice(&self.palette);
+        output[header.image_offset as usize..].copy_from_slice(&self.data);
+        Ok(output.into_boxed_slice())
+    }
+
+    /// Returns the decoded palette, or an empty list if the texture isn't paletted.
+    #[must_use]
+    #[inline]
+    pub fn palette(&self) -> Vec<[u8; 4]> {
+        gx::decode_palette(&self.palette, self.header.palette_format)
+    }
+
+    /// Returns the raw encoded data for the given mipmap level.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMipmap`](Error::InvalidMipmap) if the level doesn't exist.
+    pub fn raw_mipmap(&self, level: usize) -> Result<&[u8], self::Error> {
+        let count = self.header.mipmap_count();
+        ensure!(level < count, InvalidMipmapSnafu { level, count });
+
+        let sizes = (0..=level).map(|level| {
+            let (width, height) = self.header.mipmap_size(level);
+            gx::data_size(width, height, self.header.format)
+        });
+        let (mut start, mut end) = (0, 0);
+        for size in sizes {
+            (start, end) = (end, end + size);
+        }
+        Ok(&self.data[start..end])
+    }
+
+    /// Decodes the given mipmap level into RGBA8, where level 0 is the full-size image.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMipmap`](Error::InvalidMipmap) if the level doesn't exist, or
+    /// [`Texture`](Error::Texture) if decoding fails.
+    pub fn decode(&self, level: usize) -> Result<Image, self::Error> {
+        let (width, height) = self.header.mipmap_size(level);
+        let data = self.raw_mipmap(level)?;
+        let palette = self.palette();
+        let data = gx::decode(data, width, height, self.header.format, Some(&palette))?;
+        Ok(Image { width, height, data })
+    }
+
+    /// Decodes every mipmap level into RGBA8, starting with the full-size image.
+    ///
+    /// # Errors
+    /// Returns [`Texture`](Error::Texture) if decoding fails.
+    #[inline]
+    pub fn decode_mipmaps(&self) -> Result<Vec<Image>, self::Error> {
+        (0..self.header.mipmap_count()).map(|level| self.decode(level)).collect()
+    }
+}
@@ -0,0 +1,215 @@
+//! Shared building blocks for the J3D family of formats (models, materials, and animations).
+//!
+//! # Format
+//! Every J3D file starts with the same big-endian 0x20-byte header, followed by a list of sections that are
+//! each identified by a four-character magic and a size:
+//!
+//! | Offset | Field         | Description                                           |
+//! |--------|---------------|-------------------------------------------------------|
+//! | 0x00   | Magic         | Always "J3D1" (or "J3D2" for newer BDL models)        |
+//! | 0x04   | Kind          | File type, such as "bmd3", "bck1", "btk1", or "brk1"  |
+//! | 0x08   | File Size     | Size of the entire file                               |
+//! | 0x0C   | Section Count | Number of sections following the header               |
+//! | 0x10   | Subversion    | Usually "SVR1" or "SVR3", followed by padding         |
+//!
+//! All offsets inside a section are relative to the start of that section. Anything that refers to joints or
+//! materials does so by their index in the model (see the JNT1 and MAT3 sections of BMD/BDL), and names are
+//! stored in a shared [string table](read_string_table) layout.
+
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use orthrus_core::prelude::*;
+use snafu::prelude::*;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
+/// Error conditions when working with J3D files.
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+#[non_exhaustive]
+pub enum Error {
+    #[cfg(feature = "std")]
+    #[snafu(display("Filesystem Error {}", source))]
+    FileError { source: std::io::Error },
+
+    /// Thrown if trying to read the file out of its current bounds.
+    #[snafu(display("Reached the end of the current stream!"))]
+    EndOfFile,
+
+    /// Thrown if a [`DataError`] other than EndOfFile is encountered.
+    #[snafu(display("Decoding Error {source}"))]
+    DataError { source: DataError },
+
+    /// Thrown if the header or a section contains an unexpected magic number.
+    #[snafu(display("Invalid Magic! Expected {:?}.", expected))]
+    InvalidMagic { expected: &'static str },
+
+    /// Thrown if a required section is missing from the file.
+    #[snafu(display("Missing Section! Expected {:?}.", expected))]
+    MissingSection { expected: &'static str },
+
+    /// Thrown when encountering unexpected values.
+    #[snafu(display(
+        "Unexpected value encountered at position {:#X}! Reason: {}",
+        position,
+        reason
+    ))]
+    InvalidData { position: u64, reason: &'static str },
+
+    /// Thrown if a texture embedded in the file can't be read.
+    #[snafu(display("Invalid Texture! {source}"))]
+    TextureError { source: crate::bti::Error },
+
+    /// Thrown if the file is larger than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+
+    /// Thrown if parsed data can't be written as JSON.
+    #[cfg(feature = "serde")]
+    #[snafu(display("Unable to write JSON: {source}"))]
+    Json { source: serde_json::Error },
+}
+
+impl From<DataError> for Error {
+    #[inline]
+    fn from(error: DataError) -> Self {
+        match error {
+            #[cfg(feature = "std")]
+            DataError::Io { source } => Self::FileError { source },
+            DataError::EndOfFile => Self::EndOfFile,
+            source => Self::DataError { source },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(error: std::io::Error) -> Self {
+        Error::FileError { source: error }
+    }
+}
+
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Error::LimitExceeded { source }
+    }
+}
+
+/// Reads a whole file from disk, returning an error instead of reading a larger file than `limits` allow.
+#[cfg(feature = "std")]
+pub(crate) fn read_file(path: &Path, limits: &ResourceLimits) -> Result<Vec<u8>, self::Error> {
+    // Check the size before reading the whole file into memory
+    limits.check_output_size(std::fs::metadata(path)?.len())?;
+    Ok(std::fs::read(path)?)
+}
+
+/// Header shared by all J3D files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileHeader {
+    /// File type, such as "bck1"
+    pub kind: [u8; 4],
+    /// Size of the entire file
+    pub file_size: u32,
+    /// Number of sections following the header
+    pub section_count: u32,
+}
+
+impl FileHeader {
+    /// Size of the header, in bytes.
+    pub const SIZE: u64 = 0x20;
+
+    /// Reads the header from the start of the file, and ensures it's the expected kind of file.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the file isn't a J3D file of the given kind.
+    #[inline]
+    pub fn new<T: ReadExt + SeekExt>(data: &mut T, kind: &'static str) -> Result<Self, self::Error> {
+        let header = Self::read(data)?;
+        ensure!(
+            header.kind == kind.as_bytes(),
+            InvalidMagicSnafu { expected: kind }
+        );
+        Ok(header)
+    }
+
+    /// Reads the header from the start of the file, accepting any kind of J3D file.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the file isn't a J3D file.
+    pub fn read<T: ReadExt + SeekExt>(data: &mut T) -> Result<Self, self::Error> {
+        data.set_position(0)?;
+        let magic = data.read_exact::<4>()?;
+        ensure!(
+            matches!(&magic, b"J3D1" | b"J3D2"),
+            InvalidMagicSnafu { expected: "J3D1" }
+        );
+        let kind = data.read_exact::<4>()?;
+        let file_size = data.read_u32()?;
+        let section_count = data.read_u32()?;
+        Ok(Self { kind, file_size, section_count })
+    }
+
+    /// Returns the absolute offset of the first section with the given magic.
+    ///
+    /// # Errors
+    /// Returns [`MissingSection`](Error::MissingSection) if there is no section with that magic.
+    pub fn find_section<T: ReadExt + SeekExt>(
+        &self, data: &mut T, magic: &'static str,
+    ) -> Result<u64, self::Error> {
+        let mut offset = Self::SIZE;
+        for _ in 0..self.section_count {
+            data.set_position(offset)?;
+            let section = data.read_exact::<4>()?;
+            if section == magic.as_bytes() {
+                return Ok(offset);
+            }
+            let size = data.read_u32()?;
+            ensure!(
+                size >= 8,
+                InvalidDataSnafu { position: offset + 4, reason: "Section Size Too Small" }
+            );
+            offset += u64::from(size);
+        }
+        MissingSectionSnafu { expected: magic }.fail()
+    }
+}
+
+/// Reads a J3D string table at the given absolute offset.
+///
+/// The table starts with a `u16` count and padding, followed by a `(u16 hash, u16 offset)` pair per string,
+/// where each offset is relative to the start of the table and points to a null-terminated string.
+///
+/// # Errors
+/// Returns [`EndOfFile`](Error::EndOfFile) if the table or any of its strings are truncated.
+pub fn read_string_table<T: ReadExt + SeekExt>(
+    data: &mut T, offset: u64,
+) -> Result<Vec<String>, self::Error> {
+    data.set_position(offset)?;
+    let count = data.read_u16()?;
+    let _padding = data.read_u16()?;
+
+    let mut offsets = Vec::with_capacity(count.into());
+    for _ in 0..count {
+        let _hash = data.read_u16()?;
+        offsets.push(data.read_u16()?);
+    }
+
+    let mut strings = Vec::with_capacity(offsets.len());
+    for string_offset in offsets {
+        data.set_position(offset + u64::from(string_offset))?;
+        let mut bytes = Vec::new();
+        loop {
+            match data.read_u8()? {
+                0 => break,
+                byte => bytes.push(byte),
+            }
+        }
+        // Some games use Shift-JIS names, so don't fail the whole file over them
+        strings.push(String::from_utf8_lossy(&bytes).into_owned());
+    }
+    Ok(strings)
+}
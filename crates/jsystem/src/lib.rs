@@ -7,9 +7,20 @@
 mod no_std {
     extern crate alloc;
     pub use alloc::boxed::Box;
+    pub use alloc::string::String;
+    pub use alloc::vec::Vec;
     pub use alloc::{format, vec};
 }
 
+pub mod animation;
+pub mod bmt;
+pub mod bti;
+pub mod j3d;
+pub mod material;
 pub mod prelude;
 pub mod rarc;
 pub mod rarc2;
+
+// Dev-dependencies are only used by doctests, but are still passed to the unit test target
+#[cfg(test)]
+use orthrus_tools as _;
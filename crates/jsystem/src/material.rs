@@ -0,0 +1,432 @@
+//! Adds support for reading the materials of J3D models (BMD/BDL) and material tables (BMT), stored in their
+//! `MAT3` section. See [`bmt`](crate::bmt) for reading the textures of a BMT along with its materials.
+//!
+//! Only the parts of each material that describe how it's drawn are read for now: the TEV stages that combine
+//! textures and colors, the culling mode, and which textures are bound to each slot. GX enums (such as TEV
+//! inputs and operations) are kept as their raw values, so that they can be compared against the GX headers.
+//!
+//! # Format
+//! The section starts with a material count, followed by offsets (relative to the start of the section) to a
+//! table for each kind of setting. Each material is a 0x14C-byte entry of indices into those tables, where
+//! 0xFFFF (or 0xFF for single bytes) means the setting isn't used:
+//!
+//! | Offset | Field               | Type      | Notes                                            |
+//! |--------|---------------------|-----------|--------------------------------------------------|
+//! | 0x00   | Mode                | u8        | 1 for opaque, 2 for alpha tested, 4 for blended  |
+//! | 0x01   | Cull Mode           | u8        | Index into the cull mode table (`u32` each)      |
+//! | 0x84   | Textures            | [u16; 8]  | Index into the texture table (TEX1 indices)      |
+//! | 0x94   | Konst Colors        | [u16; 4]  | Index into the konst color table (RGBA8 each)    |
+//! | 0x9C   | Konst Color Selects | [u8; 16]  | `GXTevKColorSel` for each TEV stage              |
+//! | 0xAC   | Konst Alpha Selects | [u8; 16]  | `GXTevKAlphaSel` for each TEV stage              |
+//! | 0xBC   | TEV Orders          | [u16; 16] | Index into the TEV order table (4 bytes each)    |
+//! | 0xDC   | TEV Colors          | [u16; 4]  | Index into the TEV color table (`[i16; 4]` each) |
+//! | 0xE4   | TEV Stages          | [u16; 16] | Index into the TEV stage table (0x14 bytes each) |
+//!
+//! Materials are listed through a remap table, so several materials can share a single entry, and are named
+//! using a [string table](crate::j3d::read_string_table).
+//!
+//! # Usage
+//! ```
+//! use orthrus_jsystem::material::{CullMode, MaterialTable};
+//! # // Builds a BMD with a single material, one TEV stage that passes through texture 5
+//! # let mut mat3 = vec![0u8; 0x84];
+//! # mat3[..4].copy_from_slice(b"MAT3");
+//! # mat3[8..12].copy_from_slice(&[0, 1, 0xFF, 0xFF]);
+//! # let table = |mat3: &mut Vec<u8>, field: usize, bytes: &[u8]| {
+//! #     let offset = mat3.len() as u32;
+//! #     mat3[field..field + 4].copy_from_slice(&offset.to_be_bytes());
+//! #     mat3.extend(bytes);
+//! # };
+//! # let mut entry = vec![0xFFu8; 0x14C];
+//! # entry[..2].copy_from_slice(&[1, 0]);
+//! # entry[0x84..0x86].copy_from_slice(&[0, 0]);
+//! # entry[0xBC..0xBE].copy_from_slice(&[0, 0]);
+//! # entry[0xE4..0xE6].copy_from_slice(&[0, 0]);
+//! # table(&mut mat3, 0x0C, &entry);
+//! # table(&mut mat3, 0x10, &[0, 0]);
+//! # table(&mut mat3, 0x14, b"\0\x01\xFF\xFF\0\0\0\x08body\0");
+//! # table(&mut mat3, 0x1C, &[0, 0, 0, 2]);
+//! # table(&mut mat3, 0x48, &[0, 5]);
+//! # table(&mut mat3, 0x4C, &[0, 0, 4, 0xFF]);
+//! # table(&mut mat3, 0x5C, &[0xFF, 15, 8, 10, 15, 0, 0, 0, 1, 0, 7, 4, 5, 7, 0, 0, 0, 1, 0, 0xFF]);
+//! # mat3.resize(mat3.len().next_multiple_of(0x20), 0);
+//! # let size = mat3.len() as u32;
+//! # mat3[4..8].copy_from_slice(&size.to_be_bytes());
+//! # let mut bmd = b"J3D2bmd3".to_vec();
+//! # bmd.extend((0x20 + size).to_be_bytes());
+//! # bmd.extend([0, 0, 0, 1]);
+//! # bmd.extend(b"SVR3");
+//! # bmd.resize(0x20, 0xFF);
+//! # bmd.extend(mat3);
+//! let table = MaterialTable::load(&bmd)?;
+//! let material = table.find("body").unwrap();
+//! assert_eq!(material.cull_mode, CullMode::Back);
+//! assert_eq!(material.textures[0], Some(5));
+//!
+//! let stage = &material.tev_stages[0];
+//! assert_eq!(stage.tex_map, Some(0));
+//! assert_eq!(stage.color.inputs, [15, 8, 10, 15]);
+//! # Ok::<(), orthrus_jsystem::j3d::Error>(())
+//! ```
+//!
+//! With the `serde` feature, [`MaterialTable::to_json`] writes every material as JSON:
+//!
+//! ```no_run
+//! # #[cfg(feature = "serde")]
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use orthrus_jsystem::material::MaterialTable;
+//!
+//! let table = MaterialTable::open("mario.bmd")?;
+//! std::fs::write("mario.json", table.to_json()?)?;
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "serde"))]
+//! # fn main() {}
+//! ```
+
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use num_enum::FromPrimitive;
+use orthrus_core::prelude::*;
+use snafu::prelude::*;
+
+use crate::j3d::{self, Error, FileHeader, InvalidDataSnafu};
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
+/// Which polygons a material doesn't draw, based on which way they face (`GXCullMode`).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u32)]
+pub enum CullMode {
+    /// Draw every polygon
+    None = 0,
+    /// Don't draw front-facing polygons
+    Front = 1,
+    /// Don't draw back-facing polygons
+    Back = 2,
+    /// Don't draw any polygons
+    All = 3,
+    #[num_enum(catch_all)]
+    Unknown(u32),
+}
+
+/// The color or alpha half of a [`TevStage`], which computes `(d + lerp(a, b, c) + bias) * scale` (or a
+/// comparison, depending on the operation).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Combiner {
+    /// Inputs A, B, C, and D (`GXTevColorArg` or `GXTevAlphaArg`)
+    pub inputs: [u8; 4],
+    /// Operation to combine them with (`GXTevOp`)
+    pub operation: u8,
+    /// Bias added to the result (`GXTevBias`)
+    pub bias: u8,
+    /// Scale applied to the result (`GXTevScale`)
+    pub scale: u8,
+    /// Whether the result is clamped to 0-255
+    pub clamp: bool,
+    /// Register the result is written to (`GXTevRegID`)
+    pub output: u8,
+}
+
+impl Combiner {
+    #[inline]
+    fn read<T: ReadExt>(data: &mut T) -> Result<Self, Error> {
+        Ok(Self {
+            inputs: data.read_exact::<4>()?,
+            operation: data.read_u8()?,
+            bias: data.read_u8()?,
+            scale: data.read_u8()?,
+            clamp: data.read_u8()? != 0,
+            output: data.read_u8()?,
+        })
+    }
+}
+
+/// A single stage of the Texture Environment, which combines textures, vertex colors, and constant colors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TevStage {
+    /// Texture coordinates used to sample the texture, if any
+    pub tex_coord: Option<u8>,
+    /// Texture slot that gets sampled, see [`Material::textures`]
+    pub tex_map: Option<u8>,
+    /// Rasterized color channel (`GXChannelID`), if any
+    pub channel: Option<u8>,
+    /// How the color is computed
+    pub color: Combiner,
+    /// How the alpha is computed
+    pub alpha: Combiner,
+    /// Which konst color is used as a color input (`GXTevKColorSel`)
+    pub konst_color: u8,
+    /// Which konst alpha is used as an alpha input (`GXTevKAlphaSel`)
+    pub konst_alpha: u8,
+}
+
+/// A single material from a `MAT3` section.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Material {
+    /// Name of the material
+    pub name: String,
+    /// Which pass the material is drawn in, usually 1 for opaque, 2 for alpha tested, or 4 for translucent
+    pub mode: u8,
+    /// Which polygons aren't drawn
+    pub cull_mode: CullMode,
+    /// Index of the texture (in the model's TEX1 section) bound to each texture slot
+    pub textures: [Option<u16>; 8],
+    /// Every TEV stage, in the order they're run
+    pub tev_stages: Vec<TevStage>,
+    /// Initial values of the TEV color registers, as RGBA
+    pub tev_colors: [Option<[i16; 4]>; 4],
+    /// Konst colors, as RGBA
+    pub konst_colors: [Option<[u8; 4]>; 4],
+}
+
+/// Every material in a J3D model or material table.
+///
+/// See the [module documentation](self) for more information.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MaterialTable {
+    /// Materials, indexed the same as in the model
+    pub materials: Vec<Material>,
+}
+
+/// Offsets to each table used by the materials, relative to the start of the file.
+struct Tables {
+    cull_modes: Option<u64>,
+    textures: Option<u64>,
+    tev_orders: Option<u64>,
+    tev_colors: Option<u64>,
+    konst_colors: Option<u64>,
+    tev_stages: Option<u64>,
+}
+
+impl Tables {
+    /// Index used for settings that aren't used.
+    const UNUSED: u16 = 0xFFFF;
+
+    /// Seeks to an entry in a table, returning false if the setting or the whole table isn't used.
+    fn seek<T: ReadExt + SeekExt>(
+        data: &mut T, table: Option<u64>, index: u16, stride: u64,
+    ) -> Result<bool, Error> {
+        match table {
+            Some(table) if index != Self::UNUSED => {
+                data.set_position(table + u64::from(index) * stride)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+impl MaterialTable {
+    /// Size of a single material entry, in bytes.
+    pub const ENTRY_SIZE: u64 = 0x14C;
+
+    /// Opens a file on disk, loads its contents, and parses it into a new instance.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if the file cannot be read, or any error from [`load`].
+    ///
+    /// [`load`]: Self::load
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::open_with_limits(path, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Opens a file on disk the same way as [`open`](Self::open), returning an error instead of reading a
+    /// larger file than `limits` allow, see [`load_with_limits`](Self::load_with_limits).
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the file exceeds `limits`, or any error from
+    /// [`open`](Self::open).
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn open_with_limits<P: AsRef<Path>>(path: P, limits: &ResourceLimits) -> Result<Self, Error> {
+        let data = j3d::read_file(path.as_ref(), limits)?;
+        Self::load_with_limits(&data, limits)
+    }
+
+    /// Parses the `MAT3` section of any J3D file from memory, usually a BMD, BDL, or BMT.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if this isn't a J3D file,
+    /// [`MissingSection`](Error::MissingSection) if it doesn't have any materials, or
+    /// [`EndOfFile`](Error::EndOfFile) if any of the tables are truncated.
+    #[inline]
+    pub fn load(input: &[u8]) -> Result<Self, Error> {
+        Self::load_with_limits(input, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Parses the `MAT3` section the same way as [`load`](Self::load), returning an error instead of reading
+    /// a larger file, or more sections or materials than `limits` allow.
+    ///
+    /// J3D files don't nest, so `max_depth` isn't used.
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the file exceeds `limits`, or any error from
+    /// [`load`](Self::load).
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_jsystem::j3d::Error;
+    /// # use orthrus_jsystem::prelude::*;
+    /// let limits = ResourceLimits { max_output_size: 4, ..Default::default() };
+    /// let result = MaterialTable::load_with_limits(b"J3D2bmd3", &limits);
+    /// assert!(matches!(result, Err(Error::LimitExceeded { .. })));
+    /// ```
+    pub fn load_with_limits(input: &[u8], limits: &ResourceLimits) -> Result<Self, Error> {
+        limits.check_output_size(input.len() as u64)?;
+        let mut data = DataCursorRef::new(input, Endian::Big);
+        let header = FileHeader::read(&mut data)?;
+        limits.check_entries(header.section_count.into())?;
+        let section = header.find_section(&mut data, "MAT3")?;
+
+        data.set_position(section + 8)?;
+        let count = data.read_u16()?;
+        limits.check_entries(count.into())?;
+        let _padding = data.read_u16()?;
+        let mut offsets = [0u32; 30];
+        for offset in &mut offsets {
+            *offset = data.read_u32()?;
+        }
+        // Offsets of zero mean the table isn't stored at all
+        let table = |index: usize| (offsets[index] != 0).then(|| section + u64::from(offsets[index]));
+        let entries = table(0)
+            .context(InvalidDataSnafu { position: section + 0x0C, reason: "Missing Material Entries" })?;
+        let remap = table(1)
+            .context(InvalidDataSnafu { position: section + 0x10, reason: "Missing Material Remap Table" })?;
+        let names = match table(2) {
+            Some(offset) => j3d::read_string_table(&mut data, offset)?,
+            None => Vec::new(),
+        };
+        let tables = Tables {
+            cull_modes: table(4),
+            textures: table(15),
+            tev_orders: table(16),
+            tev_colors: table(17),
+            konst_colors: table(18),
+            tev_stages: table(20),
+        };
+
+        let mut materials = Vec::with_capacity(count.into());
+        for index in 0..count {
+            data.set_position(remap + u64::from(index) * 2)?;
+            let entry = entries + u64::from(data.read_u16()?) * Self::ENTRY_SIZE;
+            let name = names.get(usize::from(index)).cloned().unwrap_or_default();
+            materials.push(Self::read_material(&mut data, &tables, entry, name)?);
+        }
+        Ok(Self { materials })
+    }
+
+    /// Reads a single material entry, and looks up every setting in its table.
+    fn read_material<T: ReadExt + SeekExt>(
+        data: &mut T, tables: &Tables, entry: u64, name: String,
+    ) -> Result<Material, Error> {
+        let read_indices = |data: &mut T, offset: u64, indices: &mut [u16]| -> Result<(), Error> {
+            data.set_position(entry + offset)?;
+            for index in indices {
+                *index = data.read_u16()?;
+            }
+            Ok(())
+        };
+
+        data.set_position(entry)?;
+        let mode = data.read_u8()?;
+        let cull_index = match data.read_u8()? {
+            0xFF => Tables::UNUSED,
+            index => index.into(),
+        };
+
+        let (mut texture_indices, mut konst_indices) = ([0u16; 8], [0u16; 4]);
+        let (mut order_indices, mut color_indices, mut stage_indices) = ([0u16; 16], [0u16; 4], [0u16; 16]);
+        read_indices(data, 0x84, &mut texture_indices)?;
+        read_indices(data, 0x94, &mut konst_indices)?;
+        let konst_color_selects = data.read_exact::<16>()?;
+        let konst_alpha_selects = data.read_exact::<16>()?;
+        read_indices(data, 0xBC, &mut order_indices)?;
+        read_indices(data, 0xDC, &mut color_indices)?;
+        read_indices(data, 0xE4, &mut stage_indices)?;
+
+        // Back-face culling is the default for GX when a material doesn't set one
+        let cull_mode = match Tables::seek(data, tables.cull_modes, cull_index, 4)? {
+            true => CullMode::from_primitive(data.read_u32()?),
+            false => CullMode::Back,
+        };
+
+        let mut textures = [None; 8];
+        for (texture, index) in textures.iter_mut().zip(texture_indices) {
+            if Tables::seek(data, tables.textures, index, 2)? {
+                *texture = Some(data.read_u16()?);
+            }
+        }
+
+        let mut tev_colors = [None; 4];
+        for (color, index) in tev_colors.iter_mut().zip(color_indices) {
+            if Tables::seek(data, tables.tev_colors, index, 8)? {
+                *color = Some([
+                    data.read_i16()?,
+                    data.read_i16()?,
+                    data.read_i16()?,
+                    data.read_i16()?,
+                ]);
+            }
+        }
+
+        let mut konst_colors = [None; 4];
+        for (color, index) in konst_colors.iter_mut().zip(konst_indices) {
+            if Tables::seek(data, tables.konst_colors, index, 4)? {
+                *color = Some(data.read_exact::<4>()?);
+            }
+        }
+
+        // Stages are always stored first, so the first unused index marks the end
+        let mut tev_stages = Vec::new();
+        for (stage, &index) in stage_indices.iter().enumerate() {
+            let mut tev_stage = TevStage {
+                konst_color: konst_color_selects[stage],
+                konst_alpha: konst_alpha_selects[stage],
+                ..Default::default()
+            };
+            if !Tables::seek(data, tables.tev_stages, index, 0x14)? {
+                break;
+            }
+            let _unknown = data.read_u8()?;
+            tev_stage.color = Combiner::read(data)?;
+            tev_stage.alpha = Combiner::read(data)?;
+            if Tables::seek(data, tables.tev_orders, order_indices[stage], 4)? {
+                let [tex_coord, tex_map, channel, _] = data.read_exact::<4>()?;
+                let used = |value: u8| (value != 0xFF).then_some(value);
+                tev_stage.tex_coord = used(tex_coord);
+                tev_stage.tex_map = used(tex_map);
+                tev_stage.channel = used(channel);
+            }
+            tev_stages.push(tev_stage);
+        }
+
+        Ok(Material { name, mode, cull_mode, textures, tev_stages, tev_colors, konst_colors })
+    }
+
+    /// Returns the material with the given name, if any.
+    #[must_use]
+    #[inline]
+    pub fn find(&self, name: &str) -> Option<&Material> {
+        self.materials.iter().find(|material| material.name == name)
+    }
+
+    /// Writes every material as pretty-printed JSON.
+    ///
+    /// # Errors
+    /// Returns [`Json`](Error::Json) if the materials can't be written as JSON.
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string_pretty(self).context(j3d::JsonSnafu)
+    }
+}
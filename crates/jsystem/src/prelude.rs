@@ -5,10 +5,43 @@
 //! use orthrus_jsystem::prelude::*;
 //! ```
 
+#[doc(inline)]
+pub use crate::animation::{ColorAnimation, JointAnimation, TextureAnimation};
+#[doc(inline)]
+pub use crate::bmt::BinaryMaterialTable;
+#[doc(inline)]
+pub use crate::bti::BinaryTextureImage;
+#[doc(inline)]
+pub use crate::material::MaterialTable;
 #[doc(inline)]
 pub use crate::rarc2::ResourceArchive;
 
 pub mod rarc {
     #[doc(inline)]
-    pub use crate::rarc2::Error;
+    pub use crate::rarc2::{Attributes, Error, FileOptions, Preload, RepackOptions, Wrapping};
+}
+
+pub mod animation {
+    #[doc(inline)]
+    pub use crate::animation::{Keyframe, LoopMode, Track, TransformTracks};
+}
+
+pub mod bmt {
+    #[doc(inline)]
+    pub use crate::bmt::Texture;
+}
+
+pub mod bti {
+    #[doc(inline)]
+    pub use crate::bti::{Error, Header, Image};
+}
+
+pub mod material {
+    #[doc(inline)]
+    pub use crate::material::{Combiner, CullMode, Material, TevStage};
+}
+
+pub mod j3d {
+    #[doc(inline)]
+    pub use crate::j3d::Error;
 }
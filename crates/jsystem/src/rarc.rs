@@ -229,7 +229,7 @@ impl ResourceArchive {
     /// failing to create a file to write to (see [`write`](std::fs::write)).
     #[cfg(feature = "std")]
     #[inline]
-    pub fn extract_from_path<P: AsRef<Path>>(input: P, output: P) -> Result<()> {
+    pub fn extract_from_path<P: AsRef<Path>, O: AsRef<Path>>(input: P, output: O) -> Result<()> {
         let data = std::fs::read(input)?;
         Self::extract_from(&data, output)?;
         Ok(())
@@ -1,11 +1,53 @@
-use std::ffi::CString;
+//! Support for JSystem Resource Archives (RARC), the main container format used by first-party GameCube and
+//! Wii games.
+//!
+//! Archives are loaded into a tree of [`Directory`] and [`File`] entries, which can be freely edited and then
+//! written back out using [`ResourceArchive::to_bytes`]. File data is stored exactly as it appears in the
+//! archive, so any files marked as [`COMPRESSED`](Attributes::COMPRESSED) still need to be decompressed.
+//!
+//! Each file keeps the [`Attributes`] and ID it was loaded with, which control whether the game preloads it
+//! into main RAM, audio RAM, or streams it from the disc, so rebuilding an archive doesn't change how it gets
+//! loaded. These can be changed for individual files using [`RepackOptions`].
+//!
+//! With the `yaz0` feature, [`ResourceArchive::open`] also accepts Yaz0-compressed archives (usually `.szs`
+//! or `.arc`), and remembers how they were compressed in [`ResourceArchive::wrapping`] so that
+//! [`ResourceArchive::to_wrapped_bytes`] can compress them the same way again.
+//!
+//! # Usage
+//! ```
+//! use orthrus_jsystem::rarc2::{Directory, Entry, File, ResourceArchive};
+//!
+//! let mut root = Directory::new("root");
+//! root.entries.push(Entry::File(File::new("hello.txt", b"Hello!".as_slice())));
+//! let archive = ResourceArchive::new(root);
+//!
+//! let mut archive = ResourceArchive::load(archive.to_bytes())?;
+//! archive.file_mut("hello.txt").unwrap().data = b"Goodbye!".as_slice().into();
+//! assert_eq!(&*archive.file("hello.txt").unwrap().data, b"Goodbye!");
+//! # Ok::<(), orthrus_jsystem::rarc2::Error>(())
+//! ```
+
+#[cfg(all(feature = "std", not(feature = "yaz0")))]
+use std::io::BufReader;
 #[cfg(feature = "std")]
-use std::{fs::File, io::BufReader, path::Path};
+use std::{
+    io::{Read, Seek, Write},
+    path::Path,
+};
 
 use bitflags::bitflags;
+use orthrus_core::data::EndianExt;
+#[cfg(feature = "std")]
+use orthrus_core::prelude::util::Replacement;
+use orthrus_core::prelude::util::{PackReport, PackedMember};
 use orthrus_core::prelude::*;
+#[cfg(feature = "yaz0")]
+use orthrus_ncompress::prelude::*;
 use snafu::prelude::*;
 
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
 /// Error conditions when working with Resource Archives.
 #[derive(Debug, Snafu)]
 #[non_exhaustive]
@@ -29,6 +71,24 @@ pub enum Error {
         reason
     ))]
     InvalidData { position: u64, reason: &'static str },
+
+    /// Thrown if trying to access a file that isn't in the archive.
+    #[snafu(display("Unable to find {path} in the archive!"))]
+    FileNotFound { path: String },
+
+    /// Thrown if the archive is larger than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+
+    /// Thrown if a stored file name can't safely be extracted, see [`PathOptions`].
+    #[cfg(feature = "std")]
+    #[snafu(display("{source}"))]
+    InvalidPath { source: PathError },
+
+    /// Thrown if a Yaz0-compressed archive can't be decompressed, or the archive can't be compressed again.
+    #[cfg(feature = "yaz0")]
+    #[snafu(display("Yaz0 Error: {source}"))]
+    Yaz0Error { source: yaz0::Error },
 }
 
 impl From<DataError> for Error {
@@ -51,6 +111,21 @@ impl From<std::io::Error> for Error {
     }
 }
 
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Error::LimitExceeded { source }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<PathError> for Error {
+    #[inline]
+    fn from(source: PathError) -> Self {
+        Error::InvalidPath { source }
+    }
+}
+
 #[derive(Debug)]
 #[allow(dead_code)]
 pub struct Header {
@@ -102,6 +177,19 @@ impl Header {
             aram_data_size,
         })
     }
+
+    #[inline]
+    fn write<T: WriteExt>(&self, data: &mut T) -> Result<(), self::Error> {
+        data.write_exact(&self.magic)?;
+        data.write_u32(self.file_size)?;
+        data.write_u32(0x20)?;
+        data.write_u32(self.data_offset)?;
+        data.write_u32(self.data_size)?;
+        data.write_u32(self.mram_data_size)?;
+        data.write_u32(self.aram_data_size)?;
+        data.write_u32(0)?;
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
@@ -155,6 +243,20 @@ impl DataHeader {
             sync_file_ids,
         })
     }
+
+    #[inline]
+    fn write<T: WriteExt>(&self, data: &mut T) -> Result<(), self::Error> {
+        data.write_u32(self.directory_count)?;
+        data.write_u32(0x20)?;
+        data.write_u32(self.file_count)?;
+        data.write_u32(self.file_offset)?;
+        data.write_u32(self.string_table_size)?;
+        data.write_u32(self.string_table_offset)?;
+        data.write_u16(self.next_file_index)?;
+        data.write_u8(self.sync_file_ids.into())?;
+        data.write_exact(&[0u8; 5])?;
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
@@ -182,21 +284,74 @@ impl DirectoryNode {
         let file_node_offset = data.read_u32()?;
         Ok(Self { directory_name, string_offset, name_hash, file_count, file_node_offset })
     }
+
+    #[inline]
+    fn write<T: WriteExt>(&self, data: &mut T) -> Result<(), self::Error> {
+        data.write_exact(&self.directory_name)?;
+        data.write_u32(self.string_offset)?;
+        data.write_u16(self.name_hash)?;
+        data.write_u16(self.file_count)?;
+        data.write_u32(self.file_node_offset)?;
+        Ok(())
+    }
 }
 
 bitflags! {
-    #[derive(Debug)]
+    /// Flags stored in every node, which control how the game loads each file.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub struct Attributes: u8 {
+        /// Set on every file
         const FILE = 1 << 0;
+        /// Set on every directory, instead of [`FILE`](Self::FILE)
         const DIRECTORY = 1 << 1;
+        /// The file data is Yaz0 or Yay0 compressed
         const COMPRESSED = 1 << 2;
+        /// Preloaded into main RAM when the archive is mounted
         const LOAD_MRAM = 1 << 4;
+        /// Preloaded into audio RAM when the archive is mounted
         const LOAD_ARAM = 1 << 5;
+        /// Read from the disc when needed, instead of being preloaded
         const LOAD_DVD = 1 << 6;
+        /// Compressed with Yaz0 rather than Yay0, only used alongside [`COMPRESSED`](Self::COMPRESSED)
         const YAZ0_COMPRESS = 1 << 7;
     }
 }
 
+impl Attributes {
+    /// Returns where the file gets loaded, which is also the order file data is stored in.
+    #[must_use]
+    #[inline]
+    pub fn preload(self) -> Preload {
+        match self {
+            attributes if attributes.contains(Self::LOAD_DVD) => Preload::Dvd,
+            attributes if attributes.contains(Self::LOAD_ARAM) => Preload::Aram,
+            _ => Preload::Mram,
+        }
+    }
+}
+
+/// Where a file gets loaded when its archive is mounted, see [`Attributes::preload`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Preload {
+    /// Preloaded into main RAM
+    Mram,
+    /// Preloaded into audio RAM
+    Aram,
+    /// Read from the disc when needed
+    Dvd,
+}
+
+impl core::fmt::Display for Preload {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Mram => write!(f, "MRAM"),
+            Self::Aram => write!(f, "ARAM"),
+            Self::Dvd => write!(f, "DVD"),
+        }
+    }
+}
+
 #[derive(Debug)]
 #[allow(dead_code)]
 pub struct FileNode {
@@ -261,71 +416,931 @@ impl FileNode {
             node_size,
         })
     }
+
+    /// Creates a node that links to a [`DirectoryNode`].
+    #[inline]
+    fn directory(name: &str, string_offset: u32, index: u32) -> Self {
+        Self {
+            node_index: 0xFFFF,
+            node_hash: hash(name),
+            attributes: Attributes::DIRECTORY,
+            string_offset: string_offset as u16,
+            node_offset: index,
+            node_size: 0x10,
+        }
+    }
+
+    #[inline]
+    fn write<T: WriteExt>(&self, data: &mut T) -> Result<(), self::Error> {
+        data.write_u16(self.node_index)?;
+        data.write_u16(self.node_hash)?;
+        data.write_u8(self.attributes.bits())?;
+        data.write_u8(0)?;
+        data.write_u16(self.string_offset)?;
+        data.write_u32(self.node_offset)?;
+        data.write_u32(self.node_size)?;
+        data.write_u32(0)?;
+        Ok(())
+    }
 }
 
-#[derive(Debug)]
-pub struct ResourceArchive {}
+/// A single file stored in a [`ResourceArchive`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct File {
+    /// Name of the file, without any directories
+    pub name: String,
+    /// File ID, which is only used if the archive doesn't keep them synced to the node index
+    pub id: u16,
+    /// Attributes that control how the game loads this file
+    pub attributes: Attributes,
+    /// Raw file data, which is still compressed if marked as such
+    pub data: Box<[u8]>,
+}
+
+impl File {
+    /// Creates a new uncompressed file that gets preloaded into main RAM.
+    #[must_use]
+    #[inline]
+    pub fn new<S: Into<String>, D: Into<Box<[u8]>>>(name: S, data: D) -> Self {
+        Self {
+            name: name.into(),
+            id: 0,
+            attributes: Attributes::FILE | Attributes::LOAD_MRAM,
+            data: data.into(),
+        }
+    }
+
+    /// Returns whether the file data is marked as [`COMPRESSED`](Attributes::COMPRESSED).
+    #[must_use]
+    #[inline]
+    pub fn is_compressed(&self) -> bool {
+        self.attributes.contains(Attributes::COMPRESSED)
+    }
+
+    /// Returns where the file gets loaded, see [`Attributes::preload`].
+    #[must_use]
+    #[inline]
+    pub fn preload(&self) -> Preload {
+        self.attributes.preload()
+    }
+
+    /// Returns the size of the file after decompression, read from its Yaz0 or Yay0 header, or [`None`] if
+    /// it isn't marked as [`COMPRESSED`](Attributes::COMPRESSED).
+    #[must_use]
+    #[inline]
+    pub fn decompressed_size(&self) -> Option<u64> {
+        match self.is_compressed() {
+            true => decompressed_size(&self.data),
+            false => None,
+        }
+    }
+}
+
+/// Reads the size after decompression from a Yaz0 or Yay0 header.
+#[inline]
+fn decompressed_size(data: &[u8]) -> Option<u64> {
+    match data.get(..8)? {
+        [b'Y', b'a', b'z' | b'y', b'0', size @ ..] => {
+            Some(u32::from_be_bytes([size[0], size[1], size[2], size[3]]).into())
+        }
+        _ => None,
+    }
+}
+
+/// A directory stored in a [`ResourceArchive`], which can contain files and other directories.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Directory {
+    /// Name of the directory
+    pub name: String,
+    /// All files and subdirectories, in the order they're stored in the archive
+    pub entries: Vec<Entry>,
+}
+
+/// Either a [`File`] or a [`Directory`] inside of a [`Directory`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entry {
+    File(File),
+    Directory(Directory),
+}
+
+impl Directory {
+    /// Creates a new empty directory.
+    #[must_use]
+    #[inline]
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self { name: name.into(), entries: Vec::new() }
+    }
+
+    /// Returns the subdirectory at the given `/`-separated path, relative to this directory.
+    #[must_use]
+    #[inline]
+    pub fn directory(&self, path: &str) -> Option<&Directory> {
+        path.split('/').filter(|name| !name.is_empty()).try_fold(self, |directory, name| {
+            directory.entries.iter().find_map(|entry| match entry {
+                Entry::Directory(directory) if directory.name == name => Some(directory),
+                _ => None,
+            })
+        })
+    }
+
+    /// Returns a mutable reference to the subdirectory at the given `/`-separated path, relative to this
+    /// directory.
+    #[must_use]
+    #[inline]
+    pub fn directory_mut(&mut self, path: &str) -> Option<&mut Directory> {
+        path.split('/').filter(|name| !name.is_empty()).try_fold(self, |directory, name| {
+            directory.entries.iter_mut().find_map(|entry| match entry {
+                Entry::Directory(directory) if directory.name == name => Some(directory),
+                _ => None,
+            })
+        })
+    }
+
+    /// Returns the file at the given `/`-separated path, relative to this directory.
+    #[must_use]
+    #[inline]
+    pub fn file(&self, path: &str) -> Option<&File> {
+        let (directory, name) = match path.rsplit_once('/') {
+            Some((directory, name)) => (self.directory(directory)?, name),
+            None => (self, path),
+        };
+        directory.entries.iter().find_map(|entry| match entry {
+            Entry::File(file) if file.name == name => Some(file),
+            _ => None,
+        })
+    }
+
+    /// Returns a mutable reference to the file at the given `/`-separated path, relative to this directory.
+    #[must_use]
+    #[inline]
+    pub fn file_mut(&mut self, path: &str) -> Option<&mut File> {
+        let (directory, name) = match path.rsplit_once('/') {
+            Some((directory, name)) => (self.directory_mut(directory)?, name),
+            None => (self, path),
+        };
+        directory.entries.iter_mut().find_map(|entry| match entry {
+            Entry::File(file) if file.name == name => Some(file),
+            _ => None,
+        })
+    }
+
+    /// Returns every file in this directory and all subdirectories, along with their path relative to this
+    /// directory.
+    #[must_use]
+    pub fn files(&self) -> Vec<(String, &File)> {
+        let mut files = Vec::new();
+        self.collect_files("", &mut files);
+        files
+    }
+
+    fn collect_files<'a>(&'a self, prefix: &str, files: &mut Vec<(String, &'a File)>) {
+        for entry in &self.entries {
+            match entry {
+                Entry::File(file) => files.push((format!("{prefix}{}", file.name), file)),
+                Entry::Directory(directory) => {
+                    directory.collect_files(&format!("{prefix}{}/", directory.name), files);
+                }
+            }
+        }
+    }
+}
+
+/// Calculates the hash used to speed up name lookups.
+#[inline]
+fn hash(name: &str) -> u16 {
+    name.bytes().fold(0u16, |hash, byte| hash.wrapping_mul(3).wrapping_add(byte.into()))
+}
+
+/// Rounds up to the 0x20 byte alignment used by every section.
+#[inline]
+const fn align(value: usize) -> usize {
+    value.next_multiple_of(0x20)
+}
+
+/// Node tables needed to rebuild the directory tree when loading.
+struct Tables {
+    directories: Vec<DirectoryNode>,
+    nodes: Vec<FileNode>,
+    strings: Vec<u8>,
+    file_offset: u64,
+    string_offset: u64,
+    data_offset: u64,
+}
+
+impl Tables {
+    /// Reads a null-terminated name from the string table.
+    fn name(&self, offset: u32) -> Result<String, self::Error> {
+        let position = self.string_offset + u64::from(offset);
+        let bytes = self
+            .strings
+            .get(offset as usize..)
+            .context(InvalidDataSnafu { position, reason: "String Offset Out Of Bounds" })?;
+        let end = bytes.iter().position(|&byte| byte == 0).unwrap_or(bytes.len());
+        String::from_utf8(bytes[..end].to_vec())
+            .ok()
+            .context(InvalidDataSnafu { position, reason: "Invalid UTF-8 Name" })
+    }
+
+    /// Returns the index of the file node at the given `/`-separated path, relative to the root directory.
+    #[cfg(feature = "std")]
+    fn find(&self, path: &str) -> Option<usize> {
+        let mut directory = self.directories.first()?;
+        let mut names = path.split('/').filter(|name| !name.is_empty()).peekable();
+        while let Some(name) = names.next() {
+            let first = directory.file_node_offset as usize;
+            let (index, node) =
+                self.nodes.get(first..first + usize::from(directory.file_count))?.iter().enumerate().find(
+                    |(_, node)| self.name(node.string_offset.into()).is_ok_and(|node_name| node_name == name),
+                )?;
+            let is_directory = node.attributes.contains(Attributes::DIRECTORY);
+            match names.peek() {
+                Some(_) if is_directory => directory = self.directories.get(node.node_offset as usize)?,
+                None if !is_directory => return Some(first + index),
+                _ => return None,
+            }
+        }
+        None
+    }
+}
+
+/// Overrides for a single file when serializing an archive, see [`RepackOptions`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileOptions {
+    /// Replaces all of the file's [`Attributes`], such as to change where it gets preloaded
+    pub attributes: Option<Attributes>,
+    /// Replaces the file's ID, which is only used if the archive doesn't keep them synced to the node index
+    pub id: Option<u16>,
+}
+
+/// Controls how [`ResourceArchive::to_bytes_with_options`] stores each file.
+///
+/// Every file keeps the attributes and ID it was loaded with by default, so that an archive can be rebuilt
+/// without changing how the game loads it. Overrides are matched using the file's `/`-separated path,
+/// relative to the root directory.
+///
+/// # Examples
+/// ```
+/// # use orthrus_jsystem::rarc2::{Attributes, Directory, Entry, File, FileOptions, Preload, RepackOptions};
+/// # use orthrus_jsystem::rarc2::ResourceArchive;
+/// let mut root = Directory::new("root");
+/// root.entries.push(Entry::File(File::new("music.bin", b"Music".as_slice())));
+///
+/// // Stream the music from the disc instead of preloading it
+/// let attributes = Some(Attributes::FILE | Attributes::LOAD_DVD);
+/// let options = RepackOptions::new().with_file("music.bin", FileOptions { attributes, id: None });
+/// let data = ResourceArchive::new(root).to_bytes_with_options(&options);
+/// assert_eq!(ResourceArchive::load(data)?.file("music.bin").unwrap().preload(), Preload::Dvd);
+/// # Ok::<(), orthrus_jsystem::rarc2::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepackOptions {
+    files: Vec<(String, FileOptions)>,
+}
+
+impl RepackOptions {
+    /// Creates new options that keep every file as-is.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self { files: Vec::new() }
+    }
+
+    /// Overrides how the file at `path` is stored, replacing any earlier overrides for it.
+    #[must_use]
+    #[inline]
+    pub fn with_file<S: Into<String>>(mut self, path: S, options: FileOptions) -> Self {
+        let path = path.into();
+        self.files.retain(|(file, _)| *file != path);
+        self.files.push((path, options));
+        self
+    }
+
+    /// Returns the overrides for the file at `path`, if there are any.
+    #[must_use]
+    #[inline]
+    pub fn file(&self, path: &str) -> Option<&FileOptions> {
+        self.files.iter().find_map(|(file, options)| (file == path).then_some(options))
+    }
+}
+
+/// How an archive was stored before it was loaded, so that it can be stored the same way again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum Wrapping {
+    /// Stored directly, without any compression
+    #[default]
+    None,
+    /// Compressed with Yaz0, along with the alignment stored in its header (zero before the Wii U)
+    Yaz0 { alignment: u32 },
+}
+
+/// JSystem Resource Archive, see the [module documentation](self) for more information.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceArchive {
+    /// Endianness of the archive, which is little endian if the magic is "CRAR"
+    pub endian: Endian,
+    /// How the archive was stored, which is always [`Wrapping::None`] unless it was opened with the `yaz0`
+    /// feature
+    pub wrapping: Wrapping,
+    /// Root directory, containing all other files and directories
+    pub root: Directory,
+    /// File ID to use when adding another file to the archive
+    pub next_file_index: u16,
+    /// Whether file IDs are synced to their node index, instead of using [`File::id`]
+    pub sync_file_ids: bool,
+}
 
 impl ResourceArchive {
     /// Unique identifier that tells us if we're reading a Resource Archive.
     pub const MAGIC: [u8; 4] = *b"RARC";
 
+    /// Creates a new big endian archive with the given root directory, and file IDs synced to their node
+    /// index.
+    #[must_use]
+    #[inline]
+    pub const fn new(root: Directory) -> Self {
+        Self {
+            endian: Endian::Big,
+            wrapping: Wrapping::None,
+            root,
+            next_file_index: 0,
+            sync_file_ids: true,
+        }
+    }
+
     /// Opens a file on disk, loads its contents, and parses it into a new `ResourceArchive` instance. The
     /// instance can then be used for further operations.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the magic number does not match a Resource
+    /// Archive, [`InvalidData`](Error::InvalidData) if the archive is malformed, or
+    /// [`EndOfFile`](Error::EndOfFile) if trying to read out of bounds.
     #[inline]
-    #[cfg(feature = "std")]
+    #[cfg(all(feature = "std", not(feature = "yaz0")))]
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, self::Error> {
-        let data = BufReader::new(File::open(path)?);
-        Self::load(data)
+        Self::open_with_limits(path, &ResourceLimits::UNLIMITED)
     }
 
+    /// Opens a file on disk and parses it the same way as [`open`](Self::open), returning an error instead
+    /// of reading more entries, deeper directories, or larger files than `limits` allow.
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the archive exceeds `limits`, or any error from
+    /// [`open`](Self::open).
+    #[inline]
+    #[cfg(all(feature = "std", not(feature = "yaz0")))]
+    pub fn open_with_limits<P: AsRef<Path>>(path: P, limits: &ResourceLimits) -> Result<Self, self::Error> {
+        let data = BufReader::new(std::fs::File::open(path)?);
+        Self::load_with_limits(data, limits)
+    }
+
+    /// Opens a file on disk, loads its contents, and parses it into a new `ResourceArchive` instance, first
+    /// decompressing it if it's Yaz0-compressed. The instance can then be used for further operations.
+    ///
+    /// # Errors
+    /// Returns [`Yaz0Error`](Error::Yaz0Error) if the archive can't be decompressed,
+    /// [`InvalidMagic`](Error::InvalidMagic) if the magic number does not match a Resource Archive,
+    /// [`InvalidData`](Error::InvalidData) if the archive is malformed, or [`EndOfFile`](Error::EndOfFile)
+    /// if trying to read out of bounds.
+    #[inline]
+    #[cfg(feature = "yaz0")]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, self::Error> {
+        Self::open_with_limits(path, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Opens a file on disk and parses it the same way as [`open`](Self::open), returning an error instead
+    /// of decompressing to a larger size, or reading more entries, deeper directories, or larger files than
+    /// `limits` allow.
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the archive exceeds `limits`, or any error from
+    /// [`open`](Self::open).
+    #[inline]
+    #[cfg(feature = "yaz0")]
+    pub fn open_with_limits<P: AsRef<Path>>(path: P, limits: &ResourceLimits) -> Result<Self, self::Error> {
+        let data = std::fs::read(path)?;
+        Self::load_wrapped_with_limits(&data, limits)
+    }
+
+    /// Loads an archive that may be Yaz0-compressed, decompressing it first if needed, and records how it
+    /// was stored in [`wrapping`](Self::wrapping).
+    ///
+    /// # Errors
+    /// Returns [`Yaz0Error`](Error::Yaz0Error) if the archive can't be decompressed, or any error from
+    /// [`load`](Self::load).
+    #[inline]
+    #[cfg(feature = "yaz0")]
+    pub fn load_wrapped(data: &[u8]) -> Result<Self, self::Error> {
+        Self::load_wrapped_with_limits(data, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Loads an archive that may be Yaz0-compressed the same way as [`load_wrapped`](Self::load_wrapped),
+    /// returning an error instead of decompressing to a larger size, or reading more entries, deeper
+    /// directories, or larger files than `limits` allow.
+    ///
+    /// # Errors
+    /// Returns [`Yaz0Error`](Error::Yaz0Error) if the archive can't be decompressed or would decompress to
+    /// more than `limits` allow, or any error from [`load_with_limits`](Self::load_with_limits).
+    #[cfg(feature = "yaz0")]
+    pub fn load_wrapped_with_limits(data: &[u8], limits: &ResourceLimits) -> Result<Self, self::Error> {
+        // The Yaz0 header is always 0x10 bytes, so anything shorter can't be compressed
+        match data.len() >= 0x10 && data.starts_with(&Yaz0::MAGIC) {
+            true => {
+                let header = Yaz0::read_header(data).context(Yaz0Snafu)?;
+                let decompressed = Yaz0::decompress_from_with_limits(data, limits).context(Yaz0Snafu)?;
+                let mut archive = Self::load_with_limits(decompressed, limits)?;
+                archive.wrapping = Wrapping::Yaz0 { alignment: header.alignment };
+                Ok(archive)
+            }
+            false => Self::load_with_limits(data, limits),
+        }
+    }
+
+    /// Loads the data from the given input and parses it into a new `ResourceArchive` instance, reading
+    /// all file data into memory.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the magic number does not match a Resource
+    /// Archive, [`InvalidData`](Error::InvalidData) if the archive is malformed, or
+    /// [`EndOfFile`](Error::EndOfFile) if trying to read out of bounds.
     #[inline]
     pub fn load<T: IntoDataStream>(input: T) -> Result<Self, self::Error> {
+        Self::load_with_limits(input, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Loads the data from the given input the same way as [`load`](Self::load), returning an error
+    /// instead of reading more entries, deeper directories, or larger files than `limits` allow.
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the archive exceeds `limits`, or any error from
+    /// [`load`](Self::load).
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_jsystem::rarc2::{Directory, Entry, Error, File, ResourceArchive};
+    /// let mut root = Directory::new("root");
+    /// root.entries.push(Entry::Directory(Directory::new("nested")));
+    /// root.entries.push(Entry::File(File::new("hello.txt", b"Hello!".as_slice())));
+    /// let data = ResourceArchive::new(root).to_bytes();
+    ///
+    /// let limits = ResourceLimits { max_output_size: 4, ..Default::default() };
+    /// let result = ResourceArchive::load_with_limits(data.clone(), &limits);
+    /// assert!(matches!(result, Err(Error::LimitExceeded { .. })));
+    /// let limits = ResourceLimits { max_depth: 0, ..Default::default() };
+    /// let result = ResourceArchive::load_with_limits(data, &limits);
+    /// assert!(matches!(result, Err(Error::LimitExceeded { .. })));
+    /// ```
+    pub fn load_with_limits<T: IntoDataStream>(
+        input: T, limits: &ResourceLimits,
+    ) -> Result<Self, self::Error> {
         let mut data = input.into_stream(Endian::Big);
-        let header = Header::new(&mut data)?;
-        println!("{header:?}");
-        let data_header = DataHeader::new(&mut data)?;
-        println!("{data_header:?}");
-        let mut directory_nodes = Vec::with_capacity(data_header.directory_count as usize);
-        for _ in 0..data_header.directory_count {
-            let directory = DirectoryNode::new(&mut data)?;
-            //println!("{directory:?}");
-            directory_nodes.push(directory);
+        let (data_header, tables) = Self::read_tables(&mut data, limits)?;
+        let mut total_size = 0;
+        let root = Self::read_directory(&mut data, &tables, 0, 0, limits, &mut total_size)?;
+
+        Ok(Self {
+            endian: data.endian(),
+            wrapping: Wrapping::None,
+            root,
+            next_file_index: data_header.next_file_index,
+            sync_file_ids: data_header.sync_file_ids,
+        })
+    }
+
+    /// Reads both headers and all node tables, leaving file data to be read separately.
+    fn read_tables<T: ReadExt + SeekExt>(
+        data: &mut T, limits: &ResourceLimits,
+    ) -> Result<(DataHeader, Tables), self::Error> {
+        let header = Header::new(data)?;
+        let data_header = DataHeader::new(data)?;
+
+        // Make sure the counts are sane before we allocate for them
+        limits.check_entries(data_header.directory_count.into())?;
+        limits.check_entries(data_header.file_count.into())?;
+
+        let directories = (0..data_header.directory_count)
+            .map(|_| DirectoryNode::new(data))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // All offsets are relative to the end of the header
+        let file_offset = 0x20 + u64::from(data_header.file_offset);
+        data.set_position(file_offset)?;
+        let nodes =
+            (0..data_header.file_count).map(|_| FileNode::new(data)).collect::<Result<Vec<_>, _>>()?;
+
+        let string_offset = 0x20 + u64::from(data_header.string_table_offset);
+        data.set_position(string_offset)?;
+        let strings = data.read_slice(data_header.string_table_size as usize)?.to_vec();
+
+        let tables = Tables {
+            directories,
+            nodes,
+            strings,
+            file_offset,
+            string_offset,
+            data_offset: 0x20 + u64::from(header.data_offset),
+        };
+        ensure!(
+            !tables.directories.is_empty(),
+            InvalidDataSnafu { position: 0x20u64, reason: "Missing Root Directory" }
+        );
+        Ok((data_header, tables))
+    }
+
+    /// Recursively reads a directory and all of its contents, adding the size of each file to `total_size`.
+    fn read_directory<T: ReadExt + SeekExt>(
+        data: &mut T, tables: &Tables, index: usize, depth: usize, limits: &ResourceLimits,
+        total_size: &mut u64,
+    ) -> Result<Directory, self::Error> {
+        let position = 0x40 + index as u64 * 0x10;
+        limits.check_depth(u32::try_from(depth).unwrap_or(u32::MAX))?;
+        // Directories can only be nested as many times as there are directories, so anything deeper is a loop
+        ensure!(
+            depth < tables.directories.len(),
+            InvalidDataSnafu { position, reason: "Recursive Directory" }
+        );
+        let node = tables
+            .directories
+            .get(index)
+            .context(InvalidDataSnafu { position, reason: "Directory Index Out Of Bounds" })?;
+
+        let first = node.file_node_offset as usize;
+        let nodes = tables
+            .nodes
+            .get(first..first + usize::from(node.file_count))
+            .context(InvalidDataSnafu { position, reason: "File Nodes Out Of Bounds" })?;
+
+        let mut directory = Directory::new(tables.name(node.string_offset)?);
+        for file in nodes {
+            let name = tables.name(file.string_offset.into())?;
+            if file.attributes.contains(Attributes::DIRECTORY) {
+                // Every directory contains links to itself and its parent, which are implicit here
+                if name != "." && name != ".." {
+                    let index = file.node_offset as usize;
+                    let subdirectory =
+                        Self::read_directory(data, tables, index, depth + 1, limits, total_size)?;
+                    directory.entries.push(Entry::Directory(Directory { name, ..subdirectory }));
+                }
+            } else {
+                *total_size = total_size.saturating_add(file.node_size.into());
+                limits.check_output_size(*total_size)?;
+                data.set_position(tables.data_offset + u64::from(file.node_offset))?;
+                let contents = data.read_slice(file.node_size as usize)?.to_vec().into_boxed_slice();
+                directory.entries.push(Entry::File(File {
+                    name,
+                    id: file.node_index,
+                    attributes: file.attributes,
+                    data: contents,
+                }));
+            }
         }
-        let mut file_nodes = Vec::with_capacity(data_header.file_count as usize);
-        for _ in 0..data_header.file_count {
-            let file = FileNode::new(&mut data)?;
-            //println!("{file:?}");
-            file_nodes.push(file);
+        Ok(directory)
+    }
+
+    /// Returns the file at the given `/`-separated path, relative to the root directory.
+    #[must_use]
+    #[inline]
+    pub fn file(&self, path: &str) -> Option<&File> {
+        self.root.file(path)
+    }
+
+    /// Returns a mutable reference to the file at the given `/`-separated path, relative to the root
+    /// directory.
+    #[must_use]
+    #[inline]
+    pub fn file_mut(&mut self, path: &str) -> Option<&mut File> {
+        self.root.file_mut(path)
+    }
+
+    /// Serializes the archive, with directories stored breadth-first and file data grouped by where it gets
+    /// loaded (main RAM, then audio RAM, then DVD).
+    ///
+    /// # Examples
+    /// ```
+    /// use orthrus_core::testing;
+    /// use orthrus_jsystem::rarc2::{self, ResourceArchive};
+    ///
+    /// // Archives written by to_bytes are read back exactly the same
+    /// let data = orthrus_tools::corpus::rarc(3);
+    /// let parse = |data: &[u8]| ResourceArchive::load(data);
+    /// let write = |archive: &ResourceArchive| Ok::<_, rarc2::Error>(archive.to_bytes());
+    /// assert!(testing::round_trip(&data, parse, write)?.byte_identical);
+    /// # Ok::<(), testing::Divergence>(())
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn to_bytes(&self) -> Box<[u8]> {
+        self.build(&RepackOptions::new()).0
+    }
+
+    /// Serializes the archive the same way as [`to_bytes`](Self::to_bytes), using `options` to override the
+    /// attributes or ID of individual files.
+    #[must_use]
+    #[inline]
+    pub fn to_bytes_with_options(&self, options: &RepackOptions) -> Box<[u8]> {
+        self.build(options).0
+    }
+
+    /// Serializes the archive the same way as [`to_bytes`](Self::to_bytes), and also returns where every
+    /// file was stored, how large it is after decompression, and how much padding was needed to align it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_jsystem::rarc2::{Directory, Entry, File, ResourceArchive};
+    /// let mut root = Directory::new("root");
+    /// root.entries.push(Entry::File(File::new("hello.txt", b"Hello!".as_slice())));
+    /// let (data, report) = ResourceArchive::new(root).to_bytes_with_report();
+    /// assert_eq!(report.total_size, data.len() as u64);
+    /// assert_eq!(report.members[0].padding, 0x20 - 6);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn to_bytes_with_report(&self) -> (Box<[u8]>, PackReport) {
+        self.build(&RepackOptions::new())
+    }
+
+    fn build(&self, options: &RepackOptions) -> (Box<[u8]>, PackReport) {
+        // Each directory's nodes need to be contiguous, so assign directory indices breadth-first
+        let mut directories = vec![(&self.root, u32::MAX)];
+        let mut prefixes = vec![String::new()];
+        let mut first_nodes = Vec::new();
+        let mut node_count = 0;
+        let mut index = 0;
+        while let Some(&(directory, _)) = directories.get(index) {
+            first_nodes.push(node_count);
+            node_count += directory.entries.len() + 2;
+            for entry in &directory.entries {
+                if let Entry::Directory(subdirectory) = entry {
+                    directories.push((subdirectory, index as u32));
+                    prefixes.push(format!("{}{}/", prefixes[index], subdirectory.name));
+                }
+            }
+            index += 1;
         }
-        // The String Table is 0x10 aligned, so we need to make sure we are too
-        data.set_position(0x20 + u64::from(data_header.string_table_offset))?;
-        let string_table = data.read_slice(data_header.string_table_size as usize)?;
-        for directory in directory_nodes {
-            let end = string_table[directory.string_offset as usize..]
-                .iter()
-                .position(|&b| b == 0)
-                .map(|pos| pos + directory.string_offset as usize)
-                .unwrap();
-            println!(
-                "{:?}:",
-                CString::new(&string_table[directory.string_offset as usize..end]).unwrap()
-            );
-            println!("{directory:?}");
+
+        let mut strings = b".\0..\0".to_vec();
+        let mut push_string = |name: &str| -> u32 {
+            let offset = strings.len() as u32;
+            strings.extend_from_slice(name.as_bytes());
+            strings.push(0);
+            offset
+        };
+
+        // Build every node, linking subdirectories in the same order they were assigned above
+        let mut name_offsets = vec![0u32; directories.len()];
+        name_offsets[0] = push_string(&self.root.name);
+        let mut nodes = Vec::with_capacity(node_count);
+        let mut files = Vec::new();
+        let mut next_directory = 1;
+        for (index, &(directory, parent)) in directories.iter().enumerate() {
+            for entry in &directory.entries {
+                match entry {
+                    Entry::File(file) => {
+                        let node_index = nodes.len() as u16;
+                        let path = format!("{}{}", prefixes[index], file.name);
+                        let overrides = options.file(&path).copied().unwrap_or_default();
+                        let attributes = overrides.attributes.unwrap_or(file.attributes);
+                        files.push((nodes.len(), path, attributes, file));
+                        nodes.push(FileNode {
+                            node_index: match self.sync_file_ids {
+                                true => node_index,
+                                false => overrides.id.unwrap_or(file.id),
+                            },
+                            node_hash: hash(&file.name),
+                            attributes,
+                            string_offset: push_string(&file.name) as u16,
+                            node_offset: 0,
+                            node_size: file.data.len() as u32,
+                        });
+                    }
+                    Entry::Directory(subdirectory) => {
+                        let name_offset = push_string(&subdirectory.name);
+                        name_offsets[next_directory] = name_offset;
+                        nodes.push(FileNode::directory(
+                            &subdirectory.name,
+                            name_offset,
+                            next_directory as u32,
+                        ));
+                        next_directory += 1;
+                    }
+                }
+            }
+            nodes.push(FileNode::directory(".", 0, index as u32));
+            nodes.push(FileNode::directory("..", 2, parent));
         }
-        println!();
-        for file in file_nodes {
-            let end = string_table[file.string_offset as usize..]
-                .iter()
-                .position(|&b| b == 0)
-                .map(|pos| pos + file.string_offset as usize)
-                .unwrap();
-            println!(
-                "{:?}:",
-                CString::new(&string_table[file.string_offset as usize..end]).unwrap()
-            );
-            println!("{file:?}");
+
+        // Group file data by where it gets loaded, keeping the original order within each group
+        files.sort_by_key(|&(_, _, attributes, _)| attributes.preload());
+        let mut data_size = 0;
+        let (mut mram_size, mut aram_size) = (0, 0);
+        for &(node, _, attributes, file) in &files {
+            nodes[node].node_offset = data_size as u32;
+            data_size = align(data_size + file.data.len());
+            match attributes.preload() {
+                Preload::Mram => mram_size = data_size,
+                Preload::Aram => aram_size = data_size - mram_size,
+                Preload::Dvd => {}
+            }
+        }
+
+        let file_offset = align(0x40 + directories.len() * 0x10);
+        let string_offset = align(file_offset + nodes.len() * 0x14);
+        let data_offset = string_offset + align(strings.len());
+        let mut output = vec![0u8; data_offset + data_size];
+        let mut data = DataCursorMut::new(&mut output, self.endian);
+
+        // The buffer is always large enough for everything written, so these can never fail
+        let header = Header {
+            magic: match self.endian {
+                Endian::Big => Self::MAGIC,
+                Endian::Little => *b"CRAR",
+            },
+            file_size: (data_offset + data_size) as u32,
+            data_offset: (data_offset - 0x20) as u32,
+            data_size: data_size as u32,
+            mram_data_size: mram_size as u32,
+            aram_data_size: aram_size as u32,
+        };
+        header.write(&mut data).unwrap();
+        let data_header = DataHeader {
+            directory_count: directories.len() as u32,
+            file_count: nodes.len() as u32,
+            file_offset: (file_offset - 0x20) as u32,
+            string_table_size: align(strings.len()) as u32,
+            string_table_offset: (string_offset - 0x20) as u32,
+            next_file_index: match self.sync_file_ids {
+                true => nodes.len() as u16,
+                false => self.next_file_index,
+            },
+            sync_file_ids: self.sync_file_ids,
+        };
+        data_header.write(&mut data).unwrap();
+
+        for (index, &(directory, _)) in directories.iter().enumerate() {
+            let node = DirectoryNode {
+                directory_name: match index {
+                    0 => *b"ROOT",
+                    _ => {
+                        let mut identifier = *b"    ";
+                        for (dest, byte) in identifier.iter_mut().zip(directory.name.bytes()) {
+                            *dest = byte.to_ascii_uppercase();
+                        }
+                        identifier
+                    }
+                },
+                string_offset: name_offsets[index],
+                name_hash: hash(&directory.name),
+                file_count: (directory.entries.len() + 2) as u16,
+                file_node_offset: first_nodes[index] as u32,
+            };
+            node.write(&mut data).unwrap();
+        }
+
+        data.set_position(file_offset as u64).unwrap();
+        for node in &nodes {
+            node.write(&mut data).unwrap();
+        }
+
+        output[string_offset..string_offset + strings.len()].copy_from_slice(&strings);
+        let mut members = Vec::with_capacity(files.len());
+        for (node, path, attributes, file) in files {
+            let start = data_offset + nodes[node].node_offset as usize;
+            output[start..start + file.data.len()].copy_from_slice(&file.data);
+
+            let length = file.data.len() as u64;
+            let decompressed_size = match attributes.contains(Attributes::COMPRESSED) {
+                true => decompressed_size(&file.data),
+                false => None,
+            };
+            members.push(PackedMember::new(
+                path,
+                start as u64,
+                length,
+                decompressed_size.unwrap_or(length),
+                align(file.data.len()) as u64 - length,
+            ));
+        }
+        (
+            output.into_boxed_slice(),
+            PackReport::new(members, data_offset as u64),
+        )
+    }
+
+    /// Overwrites a single file inside of an archive without rewriting the rest of it, as long as `contents`
+    /// is no larger than the original file. Any leftover space is filled with zeroes, and the file's size is
+    /// updated to match.
+    ///
+    /// # Errors
+    /// Returns [`FileNotFound`](Error::FileNotFound) if the archive doesn't contain `path`,
+    /// [`InvalidData`](Error::InvalidData) if the archive is malformed, or
+    /// [`FileError`](Error::FileError) if unable to write to the input.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::Cursor;
+    /// # use orthrus_core::prelude::util::Replacement;
+    /// # use orthrus_jsystem::rarc2::{Directory, Entry, File, ResourceArchive};
+    /// # let mut root = Directory::new("root");
+    /// # root.entries.push(Entry::File(File::new("hello.txt", b"Hello!".as_slice())));
+    /// let mut data = ResourceArchive::new(root).to_bytes();
+    /// let result = ResourceArchive::replace_in_place(Cursor::new(&mut *data), "hello.txt", b"Hi!")?;
+    /// assert_eq!(result, Replacement::InPlace);
+    /// assert_eq!(&*ResourceArchive::load(data)?.file("hello.txt").unwrap().data, b"Hi!");
+    /// # Ok::<(), orthrus_jsystem::rarc2::Error>(())
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn replace_in_place<T: Read + Write + Seek>(
+        input: T, path: &str, contents: &[u8],
+    ) -> Result<Replacement, self::Error> {
+        let mut data = DataStream::new(input, Endian::Big);
+        let (_, tables) = Self::read_tables(&mut data, &ResourceLimits::UNLIMITED)?;
+        let index = tables.find(path).context(FileNotFoundSnafu { path })?;
+        let node = &tables.nodes[index];
+
+        let length = contents.len() as u32;
+        if length > node.node_size {
+            return Ok(Replacement::RebuildRequired);
+        }
+
+        data.set_position(tables.data_offset + u64::from(node.node_offset))?;
+        data.write_all(contents)?;
+        std::io::copy(
+            &mut std::io::repeat(0).take(u64::from(node.node_size - length)),
+            &mut *data,
+        )?;
+
+        // The size is the last field before the padding at the end of each node
+        data.set_position(tables.file_offset + index as u64 * 0x14 + 0xC)?;
+        data.write_u32(length)?;
+        Ok(Replacement::InPlace)
+    }
+
+    /// Serializes the archive, and then compresses it the same way as it was originally stored (see
+    /// [`wrapping`](Self::wrapping)).
+    ///
+    /// # Errors
+    /// Returns [`Yaz0Error`](Error::Yaz0Error) if the archive is too large to be compressed.
+    #[cfg(feature = "yaz0")]
+    pub fn to_wrapped_bytes(&self) -> Result<Box<[u8]>, self::Error> {
+        let data = self.to_bytes();
+        match self.wrapping {
+            Wrapping::None => Ok(data),
+            Wrapping::Yaz0 { alignment } => {
+                let mut output = Yaz0::compress_from(&data, yaz0::CompressionAlgo::MatchingOld, alignment)
+                    .context(Yaz0Snafu)?;
+                // The compressor only writes the header used before the Wii U, so restore the alignment
+                output[8..12].copy_from_slice(&alignment.to_be_bytes());
+                Ok(output)
+            }
+        }
+    }
+
+    /// Saves all files to the given folder, keeping the archive's directory structure.
+    ///
+    /// # Errors
+    /// Returns [`InvalidPath`](Error::InvalidPath) if a file name is unsafe, or an error if unable to create
+    /// the necessary directories (see [`create_dir_all`](std::fs::create_dir_all)), or failing to create a
+    /// file to write to (see [`write`](std::fs::write)).
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn extract_all<P: AsRef<Path>>(&self, output: P) -> Result<usize, self::Error> {
+        self.extract_all_with_options(output, &PathOptions::DEFAULT)
+    }
+
+    /// Saves all files to the given folder, using `options` to control how file names are sanitized.
+    ///
+    /// # Errors
+    /// Returns [`InvalidPath`](Error::InvalidPath) if a file name is unsafe, or an error if unable to create
+    /// the necessary directories (see [`create_dir_all`](std::fs::create_dir_all)), or failing to create a
+    /// file to write to (see [`write`](std::fs::write)).
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn extract_all_with_options<P: AsRef<Path>>(
+        &self, output: P, options: &PathOptions,
+    ) -> Result<usize, self::Error> {
+        self.extract_all_into(&Extraction::direct(output.as_ref()), options)
+    }
+
+    /// Saves all files to wherever `extraction` puts them, such as a staging folder or nowhere at all for a
+    /// dry run. The [`Extraction`] still needs to be committed afterwards.
+    ///
+    /// # Errors
+    /// Returns the same errors as [`extract_all_with_options`](Self::extract_all_with_options).
+    #[cfg(feature = "std")]
+    pub fn extract_all_into(
+        &self, extraction: &Extraction, options: &PathOptions,
+    ) -> Result<usize, self::Error> {
+        let files = self.root.files();
+        for (path, file) in &files {
+            let Some(path) = extraction.prepare(options.sanitize(path)?, file.data.len() as u64)? else {
+                continue;
+            };
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, &file.data)?;
         }
-        Ok(Self {})
+        Ok(files.len())
     }
 }
@@ -0,0 +1,94 @@
+//! Benchmarks for compression and decompression of all supported formats.
+//!
+//! All inputs are generated using [`orthrus_core::synthetic`] so no game files are required, with the
+//! exception of the example asset which is used if it's available. Run with `cargo bench -p
+//! orthrus-ncompress`.
+
+#![allow(unused_crate_dependencies)]
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use orthrus_core::synthetic;
+use orthrus_ncompress::prelude::*;
+
+/// Returns a set of named inputs covering the best, worst, and typical cases.
+fn corpora() -> Vec<(&'static str, Box<[u8]>)> {
+    let mut corpora = vec![
+        ("random", synthetic::random(0x4_0000, 0x1234)),
+        ("repeating", synthetic::repeating(0x4_0000, b"Orthrus")),
+        ("structured", synthetic::structured(0x4_0000, 0x1234)),
+        // Long runs of a single byte, which decompress as overlapping copies with a distance of one
+        ("zeroes", synthetic::repeating(0x4_0000, &[0])),
+    ];
+    if let Ok(data) = std::fs::read("../../examples/assets/tobudx.gb") {
+        corpora.push(("tobudx.gb", data.into_boxed_slice()));
+    }
+    corpora
+}
+
+fn yaz0(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Yaz0");
+    group.sample_size(10);
+
+    for (name, input) in corpora() {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        let compressed = Yaz0::compress_from(&input, yaz0::CompressionAlgo::MatchingOld, 0).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("compress", name), &input, |b, input| {
+            b.iter(|| Yaz0::compress_from(black_box(input), yaz0::CompressionAlgo::MatchingOld, 0))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("decompress", name),
+            &compressed,
+            |b, compressed| b.iter(|| Yaz0::decompress_from(black_box(compressed))),
+        );
+    }
+
+    group.finish();
+}
+
+fn yay0(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Yay0");
+    group.sample_size(10);
+
+    for (name, input) in corpora() {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        let compressed = Yay0::compress_from(&input, yay0::CompressionAlgo::MatchingOld, 0).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("compress", name), &input, |b, input| {
+            b.iter(|| Yay0::compress_from(black_box(input), yay0::CompressionAlgo::MatchingOld, 0))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("decompress", name),
+            &compressed,
+            |b, compressed| b.iter(|| Yay0::decompress_from(black_box(compressed))),
+        );
+    }
+
+    group.finish();
+}
+
+fn lz11(c: &mut Criterion) {
+    let mut group = c.benchmark_group("LZ11");
+    group.sample_size(10);
+
+    for (name, input) in corpora() {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        let compressed = Lz11::compress_from(&input).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("compress", name), &input, |b, input| {
+            b.iter(|| Lz11::compress_from(black_box(input)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("decompress", name),
+            &compressed,
+            |b, compressed| b.iter(|| Lz11::decompress_from(black_box(compressed))),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, yaz0, yay0, lz11);
+criterion_main!(benches);
@@ -19,17 +19,149 @@ const fn update_hash(hash: usize, byte: u8) -> usize {
     ((hash << H_SHIFT) ^ (byte as usize)) & HASH_MASK
 }
 
+// Returns how many bytes at the start of `a` and `b` are the same, up to `max_len`. With the `simd` feature,
+// most of the comparison is done a whole vector at a time, leaving only the last few bytes to check one by
+// one.
 fn longest_common_prefix(a: &[u8], b: &[u8], max_len: usize) -> usize {
-    for i in 0..max_len {
-        if a[i] != b[i] {
-            return i;
+    let (a, b) = (&a[..max_len], &b[..max_len]);
+    #[cfg(all(
+        feature = "simd",
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2"
+    ))]
+    let start = match simd::common_prefix(a, b) {
+        simd::Prefix::Mismatch(length) => return length,
+        simd::Prefix::Remaining(start) => start,
+    };
+    #[cfg(not(all(
+        feature = "simd",
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2"
+    )))]
+    let start = 0;
+
+    a[start..]
+        .iter()
+        .zip(&b[start..])
+        .position(|(a, b)| a != b)
+        .map_or(max_len, |length| start + length)
+}
+
+// Vectorized comparison of candidate matches, using SSE2 (or AVX2 if it's enabled at compile time) since it's
+// guaranteed to be available on x86_64. Other targets always use the scalar comparison.
+#[cfg(all(
+    feature = "simd",
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse2"
+))]
+mod simd {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    // Number of bytes compared at once
+    #[cfg(not(target_feature = "avx2"))]
+    const LANES: usize = 16;
+    #[cfg(target_feature = "avx2")]
+    const LANES: usize = 32;
+
+    pub(super) enum Prefix {
+        // The inputs differ, after this many bytes
+        Mismatch(usize),
+        // Every full vector matched, so the rest need to be compared starting from here
+        Remaining(usize),
+    }
+
+    // Returns a bitmask of which bytes in the vectors are different.
+    #[cfg(not(target_feature = "avx2"))]
+    #[inline]
+    fn mismatches(a: &[u8], b: &[u8]) -> u32 {
+        debug_assert!(a.len() == LANES && b.len() == LANES);
+        // SAFETY: SSE2 is enabled at compile time, both slices are exactly one vector long, and unaligned
+        // loads have no alignment requirement
+        let equal = unsafe {
+            let (a, b) = (
+                _mm_loadu_si128(a.as_ptr().cast()),
+                _mm_loadu_si128(b.as_ptr().cast()),
+            );
+            _mm_movemask_epi8(_mm_cmpeq_epi8(a, b))
+        };
+        !(equal as u32) & 0xFFFF
+    }
+
+    // Returns a bitmask of which bytes in the vectors are different.
+    #[cfg(target_feature = "avx2")]
+    #[inline]
+    fn mismatches(a: &[u8], b: &[u8]) -> u32 {
+        debug_assert!(a.len() == LANES && b.len() == LANES);
+        // SAFETY: AVX2 is enabled at compile time, both slices are exactly one vector long, and unaligned
+        // loads have no alignment requirement
+        let equal = unsafe {
+            let (a, b) = (
+                _mm256_loadu_si256(a.as_ptr().cast()),
+                _mm256_loadu_si256(b.as_ptr().cast()),
+            );
+            _mm256_movemask_epi8(_mm256_cmpeq_epi8(a, b))
+        };
+        !(equal as u32)
+    }
+
+    pub(super) fn common_prefix(a: &[u8], b: &[u8]) -> Prefix {
+        let mut offset = 0;
+        for (a, b) in a.chunks_exact(LANES).zip(b.chunks_exact(LANES)) {
+            let mask = mismatches(a, b);
+            if mask != 0 {
+                return Prefix::Mismatch(offset + mask.trailing_zeros() as usize);
+            }
+            offset += LANES;
+        }
+        Prefix::Remaining(offset)
+    }
+}
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use crate::no_std::*;
+use crate::profile::{SearchOrder, TieBreak};
+
+// Hash chains used by a Window, which are kept separately so they can be reused between inputs. Clearing
+// every chain takes longer than compressing a small input, so only the chains that were used get cleared.
+pub(crate) struct MatchTables {
+    // Head of hash chain for each hash value, or NULL
+    head: [u16; HASH_SIZE],
+    // Tail of hash chain for each hash value, or NULL
+    tail: [u16; HASH_SIZE],
+    // Next index in the hash chain, or NULL
+    next: [u16; WINDOW_SIZE],
+    // Previous index in the hash chain, or NULL, for searching nearest first
+    prev: [u16; WINDOW_SIZE],
+    // Hash values whose chains have been used since the last reset
+    used: Vec<u16>,
+}
+
+impl MatchTables {
+    pub(crate) fn new() -> Box<Self> {
+        Box::new(Self {
+            head: [NULL; HASH_SIZE],
+            tail: [NULL; HASH_SIZE],
+            next: [NULL; WINDOW_SIZE],
+            prev: [NULL; WINDOW_SIZE],
+            used: Vec::new(),
+        })
+    }
+
+    // Empties every hash chain that was used, so the tables can be used for a new input. Entries in `next`
+    // and `prev` are always written before they're read, so they don't need to be cleared.
+    fn reset(&mut self) {
+        for hash in self.used.drain(..) {
+            self.head[hash as usize] = NULL;
+            self.tail[hash as usize] = NULL;
         }
     }
-    max_len
 }
 
 // Finds the longest match in a 0x1000-byte sliding window, searching
-// front-to-back with a minimum match size of 3 bytes. The algorithm is similar
+// front-to-back by default with a minimum match size of 3 bytes. The algorithm is similar
 // to the one described in section 4 of RFC 1951
 // (https://www.rfc-editor.org/rfc/rfc1951.html#section-4), using a chained hash
 // table of 3-byte sequences to find matches. Each character in the window is
@@ -45,16 +177,17 @@ pub(crate) struct Window<'a> {
     hash_end: usize,
     // Maximum possible sequence able to be found
     max_match_length: usize,
-    // Head of hash chain for each hash value, or NULL
-    head: [u16; HASH_SIZE],
-    // Tail of hash chain for each hash value, or NULL
-    tail: [u16; HASH_SIZE],
-    // Next index in the hash chain, or NULL
-    next: [u16; WINDOW_SIZE],
+    // Hash chains, which are cleared when creating the window
+    tables: &'a mut MatchTables,
+    // Which end of the window to search first
+    order: SearchOrder,
+    // Which of several equally long matches to keep
+    ties: TieBreak,
 }
 
-impl Window<'_> {
-    pub(crate) fn new(input: &[u8], max_match_length: usize) -> Window {
+impl<'a> Window<'a> {
+    pub(crate) fn new(input: &'a [u8], max_match_length: usize, tables: &'a mut MatchTables) -> Self {
+        tables.reset();
         let mut hash = 0;
         for &b in input.iter().take(MIN_MATCH - 1) {
             hash = update_hash(hash, b);
@@ -66,12 +199,19 @@ impl Window<'_> {
             hash_start: hash,
             hash_end: hash,
             max_match_length,
-            head: [NULL; HASH_SIZE],
-            tail: [NULL; HASH_SIZE],
-            next: [NULL; WINDOW_SIZE],
+            tables,
+            order: SearchOrder::FarthestFirst,
+            ties: TieBreak::First,
         }
     }
 
+    // Changes how the window is searched, to match the quirks of a specific encoder.
+    pub(crate) const fn with_search(mut self, order: SearchOrder, ties: TieBreak) -> Self {
+        self.order = order;
+        self.ties = ties;
+        self
+    }
+
     // Advances the window by one byte, updating the hash chains.
     pub(crate) fn advance(&mut self) {
         if self.input_pos >= self.input.len() {
@@ -85,27 +225,30 @@ impl Window<'_> {
                 self.input[self.input_pos - WINDOW_SIZE + MIN_MATCH - 1],
             );
 
-            let head = self.head[self.hash_start];
-            let next = self.next[head as usize];
+            let head = self.tables.head[self.hash_start];
+            let next = self.tables.next[head as usize];
 
-            self.head[self.hash_start] = next;
-            if next == NULL {
-                self.tail[self.hash_start] = NULL;
+            self.tables.head[self.hash_start] = next;
+            match next == NULL {
+                true => self.tables.tail[self.hash_start] = NULL,
+                false => self.tables.prev[next as usize] = NULL,
             }
         }
 
         // Add the current byte to the hash chain
         if self.input_pos + MIN_MATCH < self.input.len() {
             self.hash_end = update_hash(self.hash_end, self.input[self.input_pos + MIN_MATCH - 1]);
-            let tail = self.tail[self.hash_end];
+            let tail = self.tables.tail[self.hash_end];
             let pos = (self.input_pos & WINDOW_MASK) as u16;
 
-            self.next[pos as usize] = NULL;
-            self.tail[self.hash_end] = pos;
+            self.tables.next[pos as usize] = NULL;
+            self.tables.prev[pos as usize] = tail;
+            self.tables.tail[self.hash_end] = pos;
             if tail == NULL {
-                self.head[self.hash_end] = pos;
+                self.tables.head[self.hash_end] = pos;
+                self.tables.used.push(self.hash_end as u16);
             } else {
-                self.next[tail as usize] = pos;
+                self.tables.next[tail as usize] = pos;
             }
         }
 
@@ -132,7 +275,10 @@ impl Window<'_> {
         }
 
         let hash = update_hash(self.hash_end, self.input[self.input_pos + MIN_MATCH - 1]);
-        let mut pos = self.head[hash];
+        let mut pos = match self.order {
+            SearchOrder::FarthestFirst => self.tables.head[hash],
+            SearchOrder::NearestFirst => self.tables.tail[hash],
+        };
         let mut best_len = MIN_MATCH - 1;
         let mut best_offset = 0;
 
@@ -142,9 +288,15 @@ impl Window<'_> {
             // WINDOW_SIZE`
             let match_offset = search_pos - 1 - (search_pos.wrapping_sub(pos as usize + 1) & WINDOW_MASK);
 
+            // Only check a byte that a better match would need to have, which for ties is the last byte of
+            // the best match so far
+            let probe = match self.ties {
+                TieBreak::First => best_len,
+                TieBreak::Last => best_len - 1,
+            };
             if self.input[search_pos] == self.input[match_offset]
                 && self.input[search_pos + 1] == self.input[match_offset + 1]
-                && self.input[search_pos + best_len] == self.input[match_offset + best_len]
+                && self.input[search_pos + probe] == self.input[match_offset + probe]
             {
                 // The hash function guarantees that if the first two bytes match, the third byte
                 // will too
@@ -154,16 +306,23 @@ impl Window<'_> {
                         &self.input[match_offset + MIN_MATCH..],
                         max_match - MIN_MATCH,
                     );
-                if candidate_len > best_len {
+                let better = match self.ties {
+                    TieBreak::First => candidate_len > best_len,
+                    TieBreak::Last => candidate_len >= best_len,
+                };
+                if better {
                     best_len = candidate_len;
                     best_offset = match_offset;
-                    if best_len == max_match {
+                    if best_len == max_match && self.ties == TieBreak::First {
                         break;
                     }
                 }
             }
 
-            pos = self.next[pos as usize];
+            pos = match self.order {
+                SearchOrder::FarthestFirst => self.tables.next[pos as usize],
+                SearchOrder::NearestFirst => self.tables.prev[pos as usize],
+            };
         }
         (best_offset as u32, best_len as u32)
     }
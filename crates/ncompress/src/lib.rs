@@ -1,21 +1,43 @@
 //! This crate contains modules for [Orthrus](https://crates.io/crates/orthrus) that add support for
 //! Nintendo compression formats that are shared across multiple games or systems.
+//!
+//! Without the `alloc` feature (enabled by `std`), only reading headers and decompressing into an existing
+//! buffer are available, such as [`Yaz0::decompress`](yaz0::Yaz0::decompress), so that files can be
+//! decompressed on devices without an allocator.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-#[cfg(not(feature = "std"))]
+#[cfg(all(feature = "alloc", not(feature = "std")))]
 mod no_std {
     extern crate alloc;
     pub use alloc::boxed::Box;
-    pub use alloc::{format, vec};
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
 }
 
 // All public modules
+pub mod lz11;
+pub mod profile;
+#[cfg(feature = "alloc")]
+pub mod scan;
+#[cfg(feature = "alloc")]
+pub mod session;
+#[cfg(feature = "alloc")]
+pub mod statistics;
 pub mod yay0;
 pub mod yaz0;
+#[cfg(feature = "flate")]
+pub mod zlib;
+#[cfg(feature = "zstd")]
+pub mod zstd;
 
 // For internal use only right now
+#[cfg(feature = "alloc")]
 mod algorithms;
 
 // Prelude, for convenience
 pub mod prelude;
+
+// Dev-dependencies are only used by benchmarks, but are still passed to the unit test target
+#[cfg(test)]
+use criterion as _;
@@ -0,0 +1,648 @@
+//! Adds support for the LZ11 compression format used for first-party DS, 3DS, and Switch games.
+//!
+//! Because the LZ11 format is so lightweight, this module is designed to not have any persistence.
+//! It takes in data, and will return the de/compressed data contained inside.
+//!
+//! # Format
+//! The LZ11 format is part of the [Lempel-Ziv family of algorithms](https://w.wiki/F6n), which use
+//! a "sliding window" to allow for copying repetitive data from previously in the output buffer.
+//! The input stream consists of lookback+length pairs, unique bytes to copy, and "flag bytes" which
+//! determine which of the two operations to do.
+//!
+//! ## Header
+//! The header is as follows, in little-endian format:
+//!
+//! | Offset | Field | Type | Notes |
+//! |--------|-------|------|-------|
+//! | 0x0 | Magic number  | u8  | Unique identifier (0x11) to let us know we're reading an LZ11-compressed file. |
+//! | 0x1 | Output size   | u24 | The size of the decompressed data, needed for the output buffer. |
+//! | 0x4 | Extended size | u32 | ***Only present if the output size is 0***, for files of 16MB or larger. |
+//!
+//! # Decompression
+//! The decompression algorithm is as follows, ran in a loop until you write enough bytes to fill
+//! the output buffer:
+//!
+//! * Read one byte from the input, which is 8 flag bits from high to low.
+//! * For each flag bit, if it is a 0, copy one byte from the input to the output.
+//! * If it is a 1, copy bytes from earlier in the output buffer:
+//!     * Get the first nibble of the next byte, which decides how many bytes the length uses:
+//!         * If it is 0, the length is the next 8 bits plus 0x11, for a total of 3 bytes.
+//!         * If it is 1, the length is the next 16 bits plus 0x111, for a total of 4 bytes.
+//!         * Otherwise, the length is the nibble plus 1, for a total of 2 bytes.
+//!     * Add 1 to the last 12 bits and treat that as how far back in the buffer to read, from the current
+//!       position.
+//!     * **Note that the count can overlap with the destination, and needs to be copied one byte at a time
+//!       for correct behavior.**
+//!     * Copy that amount of bytes from the lookback position to the current position.
+//!
+//! # Usage
+//! This module offers the following functionality:
+//! ## Decompression
+//! * [`decompress_from_path`](Lz11::decompress_from_path): Provide a path, get decompressed data back
+//! * [`decompress_from`](Lz11::decompress_from): Provide the input data, get decompressed data back
+//! * [`decompress`](Lz11::decompress): Provide the input data and output buffer, run the decompression
+//!   algorithm
+//! ## Compression
+//! * [`compress_from_path`](Lz11::compress_from_path): Provide a path, get compressed data back
+//! * [`compress_from`](Lz11::compress_from): Provide the input data, get compressed data back
+//! * [`compress`](Lz11::compress): Provide the input data and output buffer, run the compression
+//! ## Utilities
+//! * [`read_header`](Lz11::read_header): Returns the header information for a given LZ11 file
+//! * [`worst_possible_size`](Lz11::worst_possible_size): Calculates the worst possible compression size for a
+//!   given filesize
+
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use orthrus_core::data::copy_repeating;
+use orthrus_core::prelude::*;
+use snafu::prelude::*;
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use crate::no_std::*;
+#[cfg(feature = "alloc")]
+use crate::session::Session;
+#[cfg(feature = "alloc")]
+use crate::statistics::Statistics;
+
+/// Error conditions for when reading/writing LZ11 files
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown when trying to open a file or folder that doesn't exist.
+    #[snafu(display("Unable to find file/folder!"))]
+    NotFound,
+    /// Thrown if reading/writing tries to go out of bounds.
+    #[snafu(display("Unexpected End-Of-File!"))]
+    EndOfFile,
+    /// Thrown when unable to open a file or folder.
+    #[snafu(display("No permissions to open file/folder!"))]
+    PermissionDenied,
+    /// Thrown if the file is larger than `u32::MAX` since the header cannot store it.
+    #[snafu(display("File too large to fit into u32::MAX!"))]
+    FileTooBig,
+    /// Thrown if the header contains a magic number other than 0x11.
+    #[snafu(display("Invalid Magic! Expected {:#X}.", Lz11::MAGIC))]
+    InvalidMagic,
+    /// Thrown if the compressed data is malformed, such as copying from before the start of the output.
+    #[snafu(display("Invalid compressed data: {reason}"))]
+    InvalidData { reason: &'static str },
+    /// Thrown if the header claims a size larger than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+    /// Thrown if compression was stopped using the [`Session`]'s [`CancellationToken`].
+    #[snafu(display("Compression was cancelled!"))]
+    Cancelled,
+    /// Thrown if any other filesystem error occurs.
+    #[cfg(feature = "std")]
+    #[snafu(display("Filesystem Error {source}"))]
+    FileError { source: std::io::Error },
+}
+
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Self::LimitExceeded { source }
+    }
+}
+type Result<T> = core::result::Result<T, Error>;
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(error: std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => Self::NotFound,
+            std::io::ErrorKind::UnexpectedEof => Self::EndOfFile,
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+            _ => Self::FileError { source: error },
+        }
+    }
+}
+
+/// See the module [header](self#header) for more information.
+pub struct Header {
+    /// The size of the decompressed data, needed for the output buffer.
+    pub decompressed_size: u32,
+    /// Whether the size is stored in the extended 32-bit field, used for files of 16MB or larger.
+    pub extended: bool,
+}
+
+impl Header {
+    /// Returns the size of the header, which is 8 bytes if extended, or 4 bytes otherwise.
+    #[must_use]
+    #[inline]
+    pub const fn size(&self) -> usize {
+        match self.extended {
+            true => 8,
+            false => 4,
+        }
+    }
+}
+
+/// Utility struct for handling LZ11 compression.
+///
+/// LZ11 is stateless, and is merely a namespace for implementing certain traits.
+///
+/// See the [module documentation](self) for more information.
+pub struct Lz11;
+
+impl Lz11 {
+    /// Unique identifier that tells us if we're reading an LZ11-compressed file
+    pub const MAGIC: u8 = 0x11;
+    /// Largest size that fits into the regular 24-bit header, anything larger needs the extended header
+    pub const MAX_SHORT_SIZE: u32 = 0xFF_FFFF;
+
+    /// Returns the metadata from an LZ11 header.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_ncompress::prelude::*;
+    /// let header = Lz11::read_header(&[0x11, 0x00, 0x00, 0x01])?;
+    /// assert_eq!(header.decompressed_size, 0x10000);
+    /// assert!(!header.extended);
+    ///
+    /// // Files of 16MB or larger store a zero size, followed by the real size
+    /// let header = Lz11::read_header(&[0x11, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01])?;
+    /// assert_eq!(header.decompressed_size, 0x100_0000);
+    /// assert!(header.extended);
+    /// # Ok::<(), lz11::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the header does not match an LZ11 file, or
+    /// [`EndOfFile`](Error::EndOfFile) if the header is truncated.
+    #[inline]
+    pub fn read_header(data: &[u8]) -> Result<Header> {
+        ensure!(data.len() >= 4, EndOfFileSnafu);
+        ensure!(data[0] == Self::MAGIC, InvalidMagicSnafu);
+
+        let decompressed_size = u32::from_le_bytes([data[1], data[2], data[3], 0]);
+        if decompressed_size != 0 {
+            return Ok(Header { decompressed_size, extended: false });
+        }
+
+        ensure!(data.len() >= 8, EndOfFileSnafu);
+        let decompressed_size = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+        Ok(Header { decompressed_size, extended: true })
+    }
+
+    /// Calculates the filesize for the largest possible file that can be created with LZ11
+    /// compression.
+    ///
+    /// This consists of the extended 8-byte header, the length of the input file, and all flag bits
+    /// needed, rounded up.
+    #[must_use]
+    #[inline]
+    pub const fn worst_possible_size(input_len: usize) -> usize {
+        0x8 + input_len + input_len.div_ceil(8)
+    }
+
+    /// Loads an LZ11 file and returns the decompressed data.
+    ///
+    /// # Errors
+    /// Returns:
+    /// * [`NotFound`](Error::NotFound) if the path does not exist
+    /// * [`PermissionDenied`](Error::PermissionDenied) if unable to open the file
+    /// * [`InvalidMagic`](Error::InvalidMagic) if the header does not match an LZ11 file
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn decompress_from_path<P: AsRef<Path>>(path: P) -> Result<Box<[u8]>> {
+        let input = std::fs::read(path)?;
+        Self::decompress_from(&input)
+    }
+
+    /// Decompresses an LZ11 file and returns the decompressed data.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_ncompress::prelude::*;
+    /// let input = b"Orthrus Orthrus Orthrus".repeat(0x100);
+    /// let compressed = Lz11::compress_from(&input)?;
+    /// let output = Lz11::decompress_from(&compressed)?;
+    /// assert_eq!(*output, *input);
+    /// # Ok::<(), lz11::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the header does not match an LZ11 file.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn decompress_from(data: &[u8]) -> Result<Box<[u8]>> {
+        Self::decompress_from_with_limits(data, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Decompresses an LZ11 file and returns the decompressed data, refusing to allocate more than
+    /// `limits.max_output_size` bytes. Use this instead of [`decompress_from`](Self::decompress_from) for
+    /// untrusted input, since the extended header can claim up to 4GB of output.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_ncompress::prelude::*;
+    /// let input = [0x11, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF];
+    /// let limits = ResourceLimits { max_output_size: 0x1000, ..Default::default() };
+    /// let result = Lz11::decompress_from_with_limits(&input, &limits);
+    /// assert!(matches!(result, Err(lz11::Error::LimitExceeded { .. })));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the header does not match an LZ11 file, or
+    /// [`LimitExceeded`](Error::LimitExceeded) if the decompressed size is larger than the limit.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn decompress_from_with_limits(data: &[u8], limits: &ResourceLimits) -> Result<Box<[u8]>> {
+        let header = Self::read_header(data)?;
+        limits.check_output_size(header.decompressed_size.into())?;
+
+        //Allocate decompression buffer
+        let mut output = vec![0u8; header.decompressed_size as usize].into_boxed_slice();
+
+        //Perform the actual decompression
+        Self::decompress(data, &mut output)?;
+
+        //If we've gotten this far, output contains valid decompressed data
+        Ok(output)
+    }
+
+    /// Decompresses an LZ11 input file into the output buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_ncompress::prelude::*;
+    /// let compressed = Lz11::compress_from(&[0u8; 0x1000])?;
+    /// let header = Lz11::read_header(&compressed)?;
+    /// let mut output = vec![0xFFu8; header.decompressed_size as usize];
+    /// Lz11::decompress(&compressed, &mut output)?;
+    /// assert!(output.iter().all(|&byte| byte == 0));
+    ///
+    /// // Copying from before the start of the output is an error instead of a panic
+    /// let result = Lz11::decompress(&[0x11, 0x04, 0x00, 0x00, 0x80, 0x30, 0x00], &mut output[..4]);
+    /// assert!(matches!(result, Err(lz11::Error::InvalidData { .. })));
+    /// # Ok::<(), lz11::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`EndOfFile`](Error::EndOfFile) if the input ends before the output is full, or
+    /// [`InvalidData`](Error::InvalidData) if a copy starts before the beginning of the output.
+    #[inline]
+    pub fn decompress(input: &[u8], output: &mut [u8]) -> Result<()> {
+        let byte = |position: usize| input.get(position).copied().context(EndOfFileSnafu);
+
+        //Skip the extended size if the regular one is zero
+        let mut input_pos: usize = match input.get(1..4).context(EndOfFileSnafu)? == [0, 0, 0] {
+            true => 0x8,
+            false => 0x4,
+        };
+        let mut output_pos: usize = 0x0;
+        let mut mask: u8 = 0;
+        let mut flags: u8 = 0;
+
+        while output_pos < output.len() {
+            //Check if we need a new flag byte
+            if mask == 0 {
+                flags = byte(input_pos)?;
+                input_pos += 1;
+                mask = 1 << 7;
+            }
+
+            //Check what kind of copy we're doing
+            if (flags & mask) == 0 {
+                //Copy one byte from the input stream
+                output[output_pos] = byte(input_pos)?;
+                output_pos += 1;
+                input_pos += 1;
+            } else {
+                //RLE copy from previously in the buffer, the first nibble decides how long the length is
+                let first = usize::from(byte(input_pos)?);
+                let size = match first >> 4 {
+                    0 => {
+                        let size = (((first & 0xF) << 4) | usize::from(byte(input_pos + 1)? >> 4)) + 0x11;
+                        input_pos += 1;
+                        size
+                    }
+                    1 => {
+                        let size = (((first & 0xF) << 12)
+                            | (usize::from(byte(input_pos + 1)?) << 4)
+                            | usize::from(byte(input_pos + 2)? >> 4))
+                            + 0x111;
+                        input_pos += 2;
+                        size
+                    }
+                    n => n + 1,
+                };
+                let distance = (usize::from(byte(input_pos)? & 0xF) << 8) | usize::from(byte(input_pos + 1)?);
+                let back = output_pos
+                    .checked_sub(distance + 1)
+                    .context(InvalidDataSnafu { reason: "Copy starts before the beginning of the output" })?;
+                input_pos += 2;
+
+                //Don't write past the end of the buffer, even if the last copy claims to
+                let size = size.min(output.len() - output_pos);
+
+                //Overlapping ranges repeat the bytes between them
+                copy_repeating(output, back..back + size, output_pos);
+                output_pos += size;
+            }
+
+            mask >>= 1;
+        }
+        Ok(())
+    }
+
+    /// Loads a file and returns the LZ11-compressed data.
+    ///
+    /// # Errors
+    /// Returns:
+    /// * [`NotFound`](Error::NotFound) if the path does not exist
+    /// * [`PermissionDenied`](Error::PermissionDenied) if unable to open the file
+    /// * [`FileTooBig`](Error::FileTooBig) if too large for the filesize to be stored in the header
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn compress_from_path<P: AsRef<Path>>(path: P) -> Result<Box<[u8]>> {
+        let input = std::fs::read(path)?;
+        Self::compress_from(&input)
+    }
+
+    /// Compresses the input data, using the extended header if it's 16MB or larger.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_ncompress::prelude::*;
+    /// // The largest size that fits into the regular header
+    /// let input = vec![0u8; 0xFF_FFFF];
+    /// let compressed = Lz11::compress_from(&input)?;
+    /// assert!(!Lz11::read_header(&compressed)?.extended);
+    /// assert_eq!(*Lz11::decompress_from(&compressed)?, *input);
+    ///
+    /// // One byte larger needs the extended header
+    /// let input = vec![0u8; 0x100_0000];
+    /// let compressed = Lz11::compress_from(&input)?;
+    /// assert!(Lz11::read_header(&compressed)?.extended);
+    /// assert_eq!(*Lz11::decompress_from(&compressed)?, *input);
+    /// # Ok::<(), lz11::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`FileTooBig`](Error::FileTooBig) if the input is too large for the filesize to be
+    /// stored in the header.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn compress_from(input: &[u8]) -> Result<Box<[u8]>> {
+        Self::compress_from_with_session(input, &mut Session::new())
+    }
+
+    /// Compresses the input data, reusing the hash chains in `session`. This is faster when compressing many
+    /// small files, see [`session`](crate::session) for more information.
+    ///
+    /// # Errors
+    /// Returns [`FileTooBig`](Error::FileTooBig) if the input is too large for the filesize to be
+    /// stored in the header, or [`Cancelled`](Error::Cancelled) if `session` was cancelled.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn compress_from_with_session(input: &[u8], session: &mut Session) -> Result<Box<[u8]>> {
+        ensure!(u32::try_from(input.len()).is_ok(), FileTooBigSnafu);
+
+        //Assume an extended header, every byte is a copy, and include flag bytes (rounded up)
+        let mut output = vec![0u8; Self::worst_possible_size(input.len())];
+        let output_size = Self::compress_with_session(input, &mut output, session).context(CancelledSnafu)?;
+        output.truncate(output_size);
+
+        Ok(output.into_boxed_slice())
+    }
+
+    /// Compresses the input into the output buffer, and returns the size of the compressed data. The output
+    /// buffer needs to be at least [`worst_possible_size`](Self::worst_possible_size) bytes, and zeroed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_ncompress::prelude::*;
+    /// let input = b"Orthrus Orthrus Orthrus";
+    /// let mut output = vec![0u8; Lz11::worst_possible_size(input.len())];
+    /// let output_size = Lz11::compress(input, &mut output);
+    /// output.truncate(output_size);
+    /// assert_eq!(*Lz11::decompress_from(&output)?, *input);
+    /// # Ok::<(), lz11::Error>(())
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn compress(input: &[u8], output: &mut [u8]) -> usize {
+        // A new session can't be cancelled, so this always finishes
+        Self::compress_with_session(input, output, &mut Session::new()).unwrap_or_default()
+    }
+
+    /// Returns [`None`] if `session` was cancelled.
+    #[cfg(feature = "alloc")]
+    fn compress_with_session(input: &[u8], output: &mut [u8], session: &mut Session) -> Option<usize> {
+        //Empty files also need the extended header, since a zero size means it's extended
+        let size = input.len() as u32;
+        output[0] = Self::MAGIC;
+        let mut output_pos = match size == 0 || size > Self::MAX_SHORT_SIZE {
+            true => {
+                output[4..8].copy_from_slice(&size.to_le_bytes());
+                0x8
+            }
+            false => {
+                output[1..4].copy_from_slice(&size.to_le_bytes()[..3]);
+                0x4
+            }
+        };
+
+        let token = session.token();
+        let mut window = session.window(input, 0x10110);
+
+        let mut input_pos = 0;
+        let mut flag_byte_pos = 0;
+        let mut flag_byte_shift = 0;
+
+        while input_pos < input.len() {
+            if token.as_ref().is_some_and(CancellationToken::is_cancelled) {
+                return None;
+            }
+
+            //Check if we need to create a new flag byte
+            if flag_byte_shift == 0 {
+                flag_byte_shift = 0x80;
+                flag_byte_pos = output_pos;
+                output[output_pos] = 0;
+                output_pos += 1;
+            }
+
+            let (mut group_offset, mut group_size) = window.search(input_pos);
+            if group_size > 2 {
+                //Check one byte after this, and copy a byte first if it gets us a better match
+                let (_, new_size) = window.search(input_pos + 1);
+                if group_size + 1 < new_size {
+                    group_size = 0;
+                }
+            }
+
+            if group_size <= 2 {
+                //If the group is less than three bytes, it's smaller to just copy a byte
+                output[output_pos] = input[input_pos];
+                input_pos += 1;
+                output_pos += 1;
+            } else {
+                //Calculate the lookback offset, and pick the smallest encoding that fits the length
+                output[flag_byte_pos] |= flag_byte_shift;
+                group_offset = input_pos as u32 - group_offset - 1;
+                match group_size {
+                    3..=0x10 => {
+                        output[output_pos] = (((group_size - 1) << 4) | (group_offset >> 8)) as u8;
+                        output[output_pos + 1] = group_offset as u8;
+                        output_pos += 2;
+                    }
+                    0x11..=0x110 => {
+                        let length = group_size - 0x11;
+                        output[output_pos] = (length >> 4) as u8;
+                        output[output_pos + 1] = ((length << 4) | (group_offset >> 8)) as u8;
+                        output[output_pos + 2] = group_offset as u8;
+                        output_pos += 3;
+                    }
+                    _ => {
+                        let length = group_size - 0x111;
+                        output[output_pos] = (0x10 | (length >> 12)) as u8;
+                        output[output_pos + 1] = (length >> 4) as u8;
+                        output[output_pos + 2] = ((length << 4) | (group_offset >> 8)) as u8;
+                        output[output_pos + 3] = group_offset as u8;
+                        output_pos += 4;
+                    }
+                }
+                input_pos += group_size as usize;
+            }
+
+            flag_byte_shift >>= 1;
+        }
+
+        Some(output_pos)
+    }
+
+    /// Walks an LZ11 stream and gathers [`Statistics`] about it, including whether it exactly matches the
+    /// output of [`compress_from`](Self::compress_from).
+    ///
+    /// Back-references that store their length in 3 or 4 bytes instead of 2 are counted as long.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_ncompress::prelude::*;
+    /// let input = b"Orthrus Orthrus Orthrus".repeat(0x100);
+    /// let statistics = Lz11::statistics(&Lz11::compress_from(&input)?)?;
+    /// assert_eq!(statistics.literals + statistics.backref_bytes, input.len());
+    /// assert_eq!(statistics.encoder, Some("Orthrus"));
+    /// # Ok::<(), lz11::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the header does not match an LZ11 file,
+    /// [`EndOfFile`](Error::EndOfFile) if the stream is truncated, or [`InvalidData`](Error::InvalidData) if
+    /// a back-reference starts before the beginning of the output.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn statistics(data: &[u8]) -> Result<Statistics> {
+        Self::statistics_with_limits(data, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Walks an LZ11 stream the same way as [`statistics`](Self::statistics), refusing to decompress more
+    /// than `limits.max_output_size` bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_ncompress::prelude::*;
+    /// let input = [0x11, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF];
+    /// let limits = ResourceLimits { max_output_size: 0x1000, ..Default::default() };
+    /// let result = Lz11::statistics_with_limits(&input, &limits);
+    /// assert!(matches!(result, Err(lz11::Error::LimitExceeded { .. })));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the decompressed size is larger than the limit, or
+    /// any error from [`statistics`](Self::statistics).
+    #[cfg(feature = "alloc")]
+    pub fn statistics_with_limits(data: &[u8], limits: &ResourceLimits) -> Result<Statistics> {
+        let header = Self::read_header(data)?;
+        limits.check_output_size(header.decompressed_size.into())?;
+        let size = header.decompressed_size as usize;
+        let mut statistics = Statistics::new(data.len(), size);
+
+        let byte = |position: usize| data.get(position).copied().context(EndOfFileSnafu);
+        let mut position = header.size();
+        // The header can't be trusted yet, so let the output grow as the stream is walked
+        let mut output = Vec::new();
+        while output.len() < size {
+            let flags = byte(position)?;
+            position += 1;
+            for bit in (0..8).rev() {
+                if output.len() >= size {
+                    break;
+                }
+                match (flags >> bit) & 1 != 0 {
+                    false => {
+                        statistics.literal(&mut output, byte(position)?);
+                        position += 1;
+                    }
+                    true => {
+                        // The length takes up the top nibbles, and the distance takes up the last 12 bits
+                        let first = usize::from(byte(position)?);
+                        let (length, long) = match first >> 4 {
+                            0 => {
+                                position += 1;
+                                (
+                                    ((first & 0xF) << 4 | usize::from(byte(position)? >> 4)) + 0x11,
+                                    true,
+                                )
+                            }
+                            1 => {
+                                position += 2;
+                                let middle = usize::from(byte(position - 1)?);
+                                (
+                                    ((first & 0xF) << 12 | middle << 4 | usize::from(byte(position)? >> 4))
+                                        + 0x111,
+                                    true,
+                                )
+                            }
+                            n => (n + 1, false),
+                        };
+                        let distance =
+                            (usize::from(byte(position)? & 0xF) << 8 | usize::from(byte(position + 1)?)) + 1;
+                        position += 2;
+                        statistics.backref(&mut output, distance, length, long).context(
+                            InvalidDataSnafu { reason: "Copy starts before the beginning of the output" },
+                        )?;
+                    }
+                }
+            }
+        }
+        // The last copy can claim to be longer than the output, which the decoder ignores
+        output.truncate(size);
+
+        let recompressed = Self::compress_from_with_session(&output, &mut Session::new())?;
+        if Statistics::matches(data, &recompressed) {
+            statistics.encoder = Some("Orthrus");
+        }
+        Ok(statistics)
+    }
+}
+
+#[cfg(feature = "std")]
+impl FileIdentifier for Lz11 {
+    fn identify(data: &[u8]) -> Option<FileInfo> {
+        Self::read_header(data).ok().map(|header| {
+            let info = format!(
+                "Nintendo LZ11-compressed file, decompressed size: {}",
+                util::format_size(header.decompressed_size as usize)
+            );
+            FileInfo::new(info, None)
+        })
+    }
+
+    /// LZ11 only has a single byte of magic, so this only matches if the whole stream can be walked.
+    fn identify_deep(data: &[u8]) -> Option<FileInfo> {
+        let statistics = Self::statistics(data).ok()?;
+        let info = format!(
+            "Nintendo LZ11-compressed file, decompressed size: {}, {statistics}",
+            util::format_size(statistics.decompressed_size)
+        );
+        FileInfo::new(info, Self::decompress_from(data).ok()).into()
+    }
+}
@@ -7,6 +7,23 @@
 //! use orthrus_ncompress::prelude::*;
 //! ```
 
+#[doc(inline)]
+pub use crate::lz11::Lz11;
+
+/// Includes [`lz11::Error`] for Result handling, and [`lz11::Header`].
+pub mod lz11 {
+    #[doc(inline)]
+    pub use crate::lz11::{Error, Header};
+}
+
+#[doc(inline)]
+pub use crate::profile::EncoderProfile;
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use crate::session::Session;
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use crate::statistics::Statistics;
 #[doc(inline)]
 pub use crate::yay0::Yay0;
 
@@ -26,3 +43,25 @@ pub mod yaz0 {
     #[doc(inline)]
     pub use crate::yaz0::{CompressionAlgo, Error, Header};
 }
+
+#[cfg(feature = "flate")]
+#[doc(inline)]
+pub use crate::zlib::Zlib;
+
+/// Includes [`zlib::Error`] for Result handling, and [`zlib::Header`].
+#[cfg(feature = "flate")]
+pub mod zlib {
+    #[doc(inline)]
+    pub use crate::zlib::{Error, Header};
+}
+
+#[cfg(feature = "zstd")]
+#[doc(inline)]
+pub use crate::zstd::Zstd;
+
+/// Includes [`zstd::Error`] for Result handling, and [`zstd::Header`].
+#[cfg(feature = "zstd")]
+pub mod zstd {
+    #[doc(inline)]
+    pub use crate::zstd::{Error, Header};
+}
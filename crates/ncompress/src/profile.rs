@@ -0,0 +1,173 @@
+//! Encoder profiles, which describe the quirks of the tools that originally compressed Yaz0 and Yay0 files.
+//!
+//! Yaz0 and Yay0 only define how to decompress a stream, so every tool that wrote them made its own choices
+//! about which matches to use. Recompressing a file only reproduces the original bytes if those choices are
+//! made the same way, which is what an [`EncoderProfile`] describes:
+//! * [`SearchOrder`] is which end of the sliding window is searched first.
+//! * [`TieBreak`] is which of several equally long matches is kept, which together with the search order
+//!   decides whether the nearest or farthest copy is used.
+//! * [`Lookahead`] is whether the encoder checks if starting a match one byte later would be longer.
+//! * Whether the alignment is stored in the header, which only started with the Wii U.
+//!
+//! Each `CompressionAlgo` (such as [`yaz0::CompressionAlgo`](crate::yaz0::CompressionAlgo)) uses one of these
+//! profiles, and `Custom` allows trying out any other combination:
+//!
+//! | Profile         | Search order   | Ties  | Lookahead       | Alignment |
+//! |-----------------|----------------|-------|-----------------|-----------|
+//! | `MatchingOld`   | Farthest first | First | At least 2 more | No        |
+//! | `NintendoEadGc` | Nearest first  | First | At least 2 more | No        |
+//! | `WiiUNlib`      | Nearest first  | First | At least 1 more | Yes       |
+//! | `ModernGreedy`  | Nearest first  | First | None            | Yes       |
+//!
+//! # Contributing a Profile
+//! If files from a game don't byte-match any of the built-in profiles, try a `Custom` profile with different
+//! settings until [`statistics`](crate::yaz0::Yaz0::statistics) reports it as the encoder. Once it matches:
+//! 1. Add the settings as a constant on [`EncoderProfile`], and a variant to each `CompressionAlgo` it applies
+//!    to, including it in `CompressionAlgo::KNOWN` so that it's detected.
+//! 2. Add a fixture, either a decompressed and compressed file to `examples/assets` (if it can be
+//!    redistributed), or a short stream that only this profile produces, and a doctest checking that
+//!    compressing it gives the exact same bytes.
+//!
+//! ```
+//! use orthrus_ncompress::prelude::*;
+//! use orthrus_ncompress::profile::TieBreak;
+//!
+//! // Searching farthest first but keeping the last tie uses the nearest match, like NintendoEadGc
+//! let profile = EncoderProfile { name: "Custom", ties: TieBreak::Last, ..EncoderProfile::MATCHING_OLD };
+//! let input = b"abcdXabcdYabcdZ";
+//! let custom = Yaz0::compress_from(input, yaz0::CompressionAlgo::Custom(profile), 0)?;
+//! assert_eq!(custom, Yaz0::compress_from(input, yaz0::CompressionAlgo::NintendoEadGc, 0)?);
+//!
+//! // Only profiles from the Wii U onwards store the alignment
+//! let compressed = Yaz0::compress_from(input, yaz0::CompressionAlgo::WiiUNlib, 0x80)?;
+//! assert_eq!(Yaz0::read_header(&compressed)?.alignment, 0x80);
+//! let compressed = Yaz0::compress_from(input, yaz0::CompressionAlgo::MatchingOld, 0x80)?;
+//! assert_eq!(Yaz0::read_header(&compressed)?.alignment, 0);
+//! # Ok::<(), yaz0::Error>(())
+//! ```
+//!
+//! # Usage
+//! Each profile below is checked against a short input that it compresses differently from the others:
+//! ```
+//! use orthrus_ncompress::prelude::*;
+//!
+//! // "abcd" repeats at two distances, "abcdefg" starts one byte before a 2 byte longer match, and "hijkl"
+//! // starts one byte before a 1 byte longer match
+//! let input = b"abcdXabcdYabcdZbcdefgWabcdefgVhijQijklRhijklS";
+//! let fixtures: [(yaz0::CompressionAlgo, &[u8]); 4] = [
+//!     (
+//!         yaz0::CompressionAlgo::MatchingOld,
+//!         b"\xFAabcdX\x20\x04Y\x20\x09\xBEZ\x10\x0DefgWa\x40\x07\xFFVhijQijk\xDClR\x10\x08klS",
+//!     ),
+//!     (
+//!         yaz0::CompressionAlgo::NintendoEadGc,
+//!         b"\xFAabcdX\x20\x04Y\x20\x04\xBEZ\x10\x03efgWa\x40\x07\xFFVhijQijk\xDClR\x10\x08klS",
+//!     ),
+//!     (
+//!         yaz0::CompressionAlgo::WiiUNlib,
+//!         b"\xFAabcdX\x20\x04Y\x20\x04\xBEZ\x10\x03efgWa\x40\x07\xFFVhijQijk\xE8lRh\x20\x05S",
+//!     ),
+//!     (
+//!         yaz0::CompressionAlgo::ModernGreedy,
+//!         b"\xFAabcdX\x20\x04Y\x20\x04\xBCZ\x10\x03efgW\x20\x0B\x10\x07\xFFVhijQijk\xDClR\x10\x08klS",
+//!     ),
+//! ];
+//! for (algo, expected) in fixtures {
+//!     let compressed = Yaz0::compress_from(input, algo, 0)?;
+//!     assert_eq!(compressed[0x10..], *expected);
+//!     assert_eq!(Yaz0::statistics(&compressed)?.encoder, Some(algo.profile().name));
+//! }
+//! # Ok::<(), yaz0::Error>(())
+//! ```
+
+/// Which end of the sliding window is searched first, see the [module documentation](self) for more
+/// information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchOrder {
+    /// Searches from the oldest byte in the window to the newest.
+    FarthestFirst,
+    /// Searches from the newest byte in the window to the oldest.
+    NearestFirst,
+}
+
+/// Which of several equally long matches is kept, see the [module documentation](self) for more
+/// information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TieBreak {
+    /// Keeps the first match found, and stops searching once a match is as long as possible.
+    First,
+    /// Keeps the last match found, which means always searching the whole window.
+    Last,
+}
+
+/// Whether an encoder checks for a longer match starting one byte later, see the
+/// [module documentation](self) for more information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lookahead {
+    /// Always uses the match at the current position.
+    Greedy,
+    /// Copies one byte and uses the match at the next position instead, if it's at least `min_gain` bytes
+    /// longer.
+    Lazy { min_gain: u32 },
+}
+
+/// Quirks of a specific encoder, see the [module documentation](self) for more information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EncoderProfile {
+    /// Name reported by `statistics` when a file matches this profile
+    pub name: &'static str,
+    /// Which end of the sliding window is searched first
+    pub search: SearchOrder,
+    /// Which of several equally long matches is kept
+    pub ties: TieBreak,
+    /// Whether to check for a longer match starting one byte later
+    pub lookahead: Lookahead,
+    /// Whether the alignment is stored in the header, only used by Yaz0
+    pub stores_alignment: bool,
+}
+
+impl EncoderProfile {
+    /// Nintendo's original encoder, used for first-party N64, GameCube, and Wii games.
+    pub const MATCHING_OLD: Self = Self {
+        name: "MatchingOld",
+        search: SearchOrder::FarthestFirst,
+        ties: TieBreak::First,
+        lookahead: Lookahead::Lazy { min_gain: 2 },
+        stores_alignment: false,
+    };
+    /// Nintendo EAD's GameCube tools, which use the nearest of several equally long matches.
+    pub const NINTENDO_EAD_GC: Self = Self {
+        name: "NintendoEadGc",
+        search: SearchOrder::NearestFirst,
+        ties: TieBreak::First,
+        lookahead: Lookahead::Lazy { min_gain: 2 },
+        stores_alignment: false,
+    };
+    /// The Wii U's NLib tools, which use a match one byte later if it's any longer, and store the alignment.
+    pub const WII_U_NLIB: Self = Self {
+        name: "WiiUNlib",
+        search: SearchOrder::NearestFirst,
+        ties: TieBreak::First,
+        lookahead: Lookahead::Lazy { min_gain: 1 },
+        stores_alignment: true,
+    };
+    /// A greedy encoder that always uses the nearest longest match, like most modern tools.
+    pub const MODERN_GREEDY: Self = Self {
+        name: "ModernGreedy",
+        search: SearchOrder::NearestFirst,
+        ties: TieBreak::First,
+        lookahead: Lookahead::Greedy,
+        stores_alignment: true,
+    };
+
+    /// Returns `true` if the match starting one byte later should be used instead, given its size and the
+    /// size of the match at the current position.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub(crate) const fn prefers_later(&self, size: u32, later_size: u32) -> bool {
+        match self.lookahead {
+            Lookahead::Greedy => false,
+            Lookahead::Lazy { min_gain } => later_size >= size + min_gain,
+        }
+    }
+}
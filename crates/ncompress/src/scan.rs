@@ -0,0 +1,201 @@
+//! Scanning for compressed streams embedded inside of larger files, such as ROMs or RAM dumps.
+//!
+//! Many N64 and GameCube games store Yay0 or Yaz0 data in the middle of other files, without any table
+//! pointing to it. [`find_streams`] checks every offset for a known magic number, and then walks the stream
+//! to make sure it decompresses to the size in its header without going out of bounds, which filters out
+//! magic numbers that only appear by chance.
+//!
+//! # Usage
+//! ```
+//! use orthrus_ncompress::prelude::*;
+//! use orthrus_ncompress::scan::{find_streams, Format};
+//!
+//! let mut rom = vec![0xFFu8; 0x100];
+//! rom.extend_from_slice(&Yaz0::compress_from(b"Orthrus Orthrus", yaz0::CompressionAlgo::MatchingOld, 0)?);
+//!
+//! let streams = find_streams(&rom);
+//! assert_eq!(streams.len(), 1);
+//! let (offset, header) = &streams[0];
+//! assert_eq!((*offset, header.format), (0x100, Format::Yaz0));
+//! assert_eq!(&*header.decompress(&rom[*offset..]).unwrap(), b"Orthrus Orthrus");
+//! # Ok::<(), yaz0::Error>(())
+//! ```
+
+use orthrus_core::prelude::*;
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use crate::no_std::*;
+use crate::yay0::Yay0;
+use crate::yaz0::Yaz0;
+
+/// Compression formats that can be found by [`find_streams`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Format {
+    /// See [`Yay0`] for more information.
+    Yay0,
+    /// See [`Yaz0`] for more information.
+    Yaz0,
+}
+
+impl Format {
+    /// Returns the format whose magic number is at the start of `data`, if any.
+    #[must_use]
+    #[inline]
+    fn from_magic(data: &[u8]) -> Option<Self> {
+        match data.get(..4)? {
+            magic if magic == Yay0::MAGIC => Some(Self::Yay0),
+            magic if magic == Yaz0::MAGIC => Some(Self::Yaz0),
+            _ => None,
+        }
+    }
+
+    /// Walks the stream at the start of `data` without decompressing it, returning its compressed size if it
+    /// decompresses to exactly `size` bytes without going out of bounds.
+    fn walk(self, data: &[u8], size: usize) -> Option<usize> {
+        let mut sections = match self {
+            Self::Yay0 => {
+                let header = Yay0::read_header(data.get(..0x10)?).ok()?;
+                let lookback = header.lookback_offset as usize;
+                let copy_data = header.copy_data_offset as usize;
+                (lookback >= 0x10 && copy_data >= lookback).then_some(())?;
+                Sections { data, positions: [0x10, lookback, copy_data], interleaved: false }
+            }
+            Self::Yaz0 => Sections { data, positions: [0x10; 3], interleaved: true },
+        };
+
+        let mut output_pos = 0;
+        while output_pos < size {
+            let flags = sections.read(Sections::FLAGS)?;
+            for bit in (0..8).rev() {
+                if output_pos >= size {
+                    break;
+                }
+                match (flags >> bit) & 1 != 0 {
+                    true => {
+                        sections.read(Sections::COPY)?;
+                        output_pos += 1;
+                    }
+                    false => {
+                        let code = u16::from_be_bytes([
+                            sections.read(Sections::LOOKBACK)?,
+                            sections.read(Sections::LOOKBACK)?,
+                        ]);
+                        let length = match code >> 12 {
+                            0 => usize::from(sections.read(Sections::COPY)?) + 0x12,
+                            n => usize::from(n) + 2,
+                        };
+                        // Can't look back before the start of the output
+                        if usize::from(code & 0xFFF) >= output_pos {
+                            return None;
+                        }
+                        output_pos += length;
+                    }
+                }
+            }
+        }
+
+        (output_pos == size).then(|| sections.end())
+    }
+}
+
+/// Read positions for the flag, lookback, and copy data sections of a stream. Yaz0 interleaves all three, so
+/// it only uses the first position.
+struct Sections<'a> {
+    data: &'a [u8],
+    positions: [usize; 3],
+    interleaved: bool,
+}
+
+impl Sections<'_> {
+    const FLAGS: usize = 0;
+    const LOOKBACK: usize = 1;
+    const COPY: usize = 2;
+
+    /// Reads the next byte from the given section.
+    #[inline]
+    fn read(&mut self, section: usize) -> Option<u8> {
+        let index = match self.interleaved {
+            true => 0,
+            false => section,
+        };
+        let position = &mut self.positions[index];
+        let value = self.data.get(*position).copied();
+        *position += 1;
+        value
+    }
+
+    /// Returns the furthest position read from any section.
+    #[inline]
+    fn end(&self) -> usize {
+        self.positions.into_iter().max().unwrap_or_default()
+    }
+}
+
+/// Metadata about a compressed stream found by [`find_streams`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct StreamHeader {
+    /// Compression format of the stream
+    pub format: Format,
+    /// Size of the data after decompression
+    pub decompressed_size: u32,
+    /// Size of the compressed stream, including its header
+    pub compressed_size: usize,
+}
+
+impl StreamHeader {
+    /// Decompresses the stream at the start of `stream`, which should start at the offset it was found at.
+    ///
+    /// Returns [`None`] if `stream` doesn't contain the same stream this header was found for.
+    #[must_use]
+    pub fn decompress(&self, stream: &[u8]) -> Option<Box<[u8]>> {
+        let stream = stream.get(..self.compressed_size)?;
+        let size = self.decompressed_size as usize;
+        (Format::from_magic(stream)? == self.format && self.format.walk(stream, size)? == stream.len())
+            .then_some(())?;
+        match self.format {
+            Format::Yay0 => Yay0::decompress_from(stream).ok(),
+            Format::Yaz0 => Yaz0::decompress_from(stream).ok(),
+        }
+    }
+}
+
+/// Finds every valid-looking Yay0 or Yaz0 stream inside of `data`, and returns each one's offset and header,
+/// in order. See the [module documentation](self) for more information.
+#[must_use]
+#[inline]
+pub fn find_streams(data: &[u8]) -> Vec<(usize, StreamHeader)> {
+    find_streams_with_limits(data, &ResourceLimits::UNLIMITED)
+}
+
+/// Finds every valid-looking Yay0 or Yaz0 stream inside of `data`, and returns each one's offset and header,
+/// in order.
+///
+/// Streams which decompress to more than `limits.max_output_size` bytes are skipped, and scanning stops after
+/// `limits.max_entries` streams are found.
+#[must_use]
+pub fn find_streams_with_limits(data: &[u8], limits: &ResourceLimits) -> Vec<(usize, StreamHeader)> {
+    let mut streams = Vec::new();
+    let mut offset = 0;
+    while offset + 0x10 <= data.len() && (streams.len() as u64) < limits.max_entries {
+        let stream = &data[offset..];
+        let found = Format::from_magic(stream).and_then(|format| {
+            let decompressed_size = u32::from_be_bytes([stream[4], stream[5], stream[6], stream[7]]);
+            (decompressed_size != 0 && u64::from(decompressed_size) <= limits.max_output_size)
+                .then_some(())?;
+            let compressed_size = format.walk(stream, decompressed_size as usize)?;
+            Some(StreamHeader { format, decompressed_size, compressed_size })
+        });
+
+        // Compressed data can contain a magic number by chance, so skip past anything we've found
+        match found {
+            Some(header) => {
+                streams.push((offset, header));
+                offset += header.compressed_size;
+            }
+            None => offset += 1,
+        }
+    }
+    streams
+}
@@ -0,0 +1,93 @@
+//! Reusable state for compressing many buffers in a row, such as every file in an archive.
+//!
+//! Every compressor in this crate finds matches using a set of hash chains, which take longer to set up
+//! than it takes to compress a small file. A [`Session`] keeps them allocated between calls and only clears
+//! the chains that were used, so packing thousands of tiny files doesn't pay that cost every time. The
+//! output is identical to compressing each buffer on its own.
+//!
+//! # Usage
+//! ```
+//! use orthrus_ncompress::prelude::*;
+//!
+//! let files = [b"Orthrus".as_slice(), b"Orthrus Orthrus", b"Orthrus Orthrus Orthrus"];
+//! let algo = yaz0::CompressionAlgo::MatchingOld;
+//!
+//! let mut session = Session::new();
+//! for file in files {
+//!     let compressed = Yaz0::compress_from_with_session(file, algo, 0, &mut session)?;
+//!     assert_eq!(compressed, Yaz0::compress_from(file, algo, 0)?);
+//! }
+//! # Ok::<(), yaz0::Error>(())
+//! ```
+//!
+//! A session can also carry a [`CancellationToken`], which stops any compression using it with a
+//! `Cancelled` error, so large files can be compressed in the background without blocking a frontend:
+//! ```
+//! use orthrus_core::prelude::*;
+//! use orthrus_ncompress::prelude::*;
+//!
+//! let token = CancellationToken::new();
+//! let mut session = Session::new().cancellation(token.clone());
+//! let algo = yaz0::CompressionAlgo::MatchingOld;
+//!
+//! // Usually called from another thread, such as when a user presses cancel
+//! token.cancel();
+//! let result = Yaz0::compress_from_with_session(b"Orthrus", algo, 0, &mut session);
+//! assert!(matches!(result, Err(yaz0::Error::Cancelled)));
+//! ```
+
+use orthrus_core::cancel::CancellationToken;
+
+use crate::algorithms::{MatchTables, Window};
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use crate::no_std::*;
+
+/// Compression state that can be shared between calls, see the [module documentation](self) for more
+/// information.
+pub struct Session {
+    tables: Box<MatchTables>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl Session {
+    /// Creates a new session, allocating the hash chains used for finding matches.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self { tables: MatchTables::new(), cancellation: None }
+    }
+
+    /// Stops any compression using this session once `token` is cancelled.
+    #[must_use]
+    #[inline]
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Returns a copy of the cancellation token, which compressors hold onto while the hash chains are
+    /// borrowed by a [`Window`].
+    #[inline]
+    pub(crate) fn token(&self) -> Option<CancellationToken> {
+        self.cancellation.clone()
+    }
+
+    /// Returns a new sliding window over `input`, reusing this session's hash chains.
+    #[inline]
+    pub(crate) fn window<'a>(&'a mut self, input: &'a [u8], max_match_length: usize) -> Window<'a> {
+        Window::new(input, max_match_length, &mut self.tables)
+    }
+}
+
+impl Default for Session {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for Session {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Session").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,111 @@
+//! Statistics about compressed streams, used to check how a file was compressed.
+//!
+//! All of the LZ-based formats here are made up of the same two operations, copying a single byte from the
+//! input (a literal), and copying a run of bytes from earlier in the output (a back-reference). Counting
+//! these, and checking whether a known encoder produces the exact same stream, is useful when verifying that
+//! recompressing a file will produce a matching result.
+
+use core::fmt;
+
+use orthrus_core::data::copy_repeating;
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use crate::no_std::*;
+
+/// Statistics gathered from walking a compressed stream.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Statistics {
+    /// Size of the compressed file, including the header
+    pub compressed_size: usize,
+    /// Size of the data after decompression
+    pub decompressed_size: usize,
+    /// Number of bytes copied directly from the input
+    pub literals: usize,
+    /// Number of copies from earlier in the output
+    pub backrefs: usize,
+    /// Total number of bytes produced by back-references
+    pub backref_bytes: usize,
+    /// Number of back-references that needed an extra byte to store their length
+    pub long_backrefs: usize,
+    /// Furthest distance that any back-reference looks back
+    pub max_distance: usize,
+    /// Name of the known encoder that produces this exact stream, if any
+    pub encoder: Option<&'static str>,
+}
+
+impl Statistics {
+    #[inline]
+    pub(crate) fn new(compressed_size: usize, decompressed_size: usize) -> Self {
+        Self { compressed_size, decompressed_size, ..Default::default() }
+    }
+
+    /// Copies a literal byte to the output.
+    #[inline]
+    pub(crate) fn literal(&mut self, output: &mut Vec<u8>, byte: u8) {
+        self.literals += 1;
+        output.push(byte);
+    }
+
+    /// Copies a back-reference to the output, returning [`None`] if it points before the start of the output.
+    #[inline]
+    pub(crate) fn backref(
+        &mut self, output: &mut Vec<u8>, distance: usize, length: usize, long: bool,
+    ) -> Option<()> {
+        let dest = output.len();
+        let start = dest.checked_sub(distance)?;
+        output.resize(dest + length, 0);
+        copy_repeating(output, start..start + length, dest);
+        self.backrefs += 1;
+        self.backref_bytes += length;
+        self.long_backrefs += usize::from(long);
+        self.max_distance = self.max_distance.max(distance);
+        Some(())
+    }
+
+    /// Checks whether `recompressed` is identical to the original stream, ignoring any trailing padding.
+    #[inline]
+    pub(crate) fn matches(original: &[u8], recompressed: &[u8]) -> bool {
+        original.len() >= recompressed.len()
+            && original[..recompressed.len()] == *recompressed
+            && original[recompressed.len()..].iter().all(|&byte| byte == 0)
+    }
+
+    /// Returns the compressed size as a percentage of the decompressed size.
+    #[must_use]
+    #[inline]
+    pub fn ratio(&self) -> f64 {
+        match self.decompressed_size {
+            0 => 0.0,
+            size => self.compressed_size as f64 * 100.0 / size as f64,
+        }
+    }
+
+    /// Returns the average number of bytes produced by each back-reference.
+    #[must_use]
+    #[inline]
+    pub fn average_backref_length(&self) -> f64 {
+        match self.backrefs {
+            0 => 0.0,
+            count => self.backref_bytes as f64 / count as f64,
+        }
+    }
+}
+
+impl fmt::Display for Statistics {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.1}% of original size, {} literals, {} back-references (average length {:.1}, {} long, max \
+             distance {:#X}), encoder: {}",
+            self.ratio(),
+            self.literals,
+            self.backrefs,
+            self.average_backref_length(),
+            self.long_backrefs,
+            self.max_distance,
+            self.encoder.unwrap_or("unknown")
+        )
+    }
+}
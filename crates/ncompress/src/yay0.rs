@@ -52,17 +52,27 @@
 //!   (matching algorithm)
 //! ## Utilities
 //! * [`read_header`](Yay0::read_header): Returns the header information for a given Yay0 file
+//! * [`statistics`](Yay0::statistics): Returns statistics about the compressed stream, and which encoder
+//!   produced it
 //! * [`worst_possible_size`](Yay0::worst_possible_size): Calculates the worst possible compression size for a
 //!   given filesize
 
 #[cfg(feature = "std")]
 use std::path::Path;
 
+use orthrus_core::data::copy_repeating;
 use orthrus_core::prelude::*;
 use snafu::prelude::*;
 
-#[cfg(not(feature = "std"))]
+#[cfg(all(feature = "alloc", not(feature = "std")))]
 use crate::no_std::*;
+use crate::profile::EncoderProfile;
+#[cfg(feature = "alloc")]
+use crate::profile::Lookahead;
+#[cfg(feature = "alloc")]
+use crate::session::Session;
+#[cfg(feature = "alloc")]
+use crate::statistics::Statistics;
 
 /// Error conditions for when reading/writing Yay0 files
 #[derive(Debug, Snafu)]
@@ -88,6 +98,19 @@ pub enum Error {
     /// Thrown if the header contains a magic number other than "Yay0".
     #[snafu(display("Invalid Magic! Expected {:?}.", Yay0::MAGIC))]
     InvalidMagic,
+    /// Thrown if the header claims a size larger than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+    /// Thrown if compression was stopped using the [`Session`]'s [`CancellationToken`].
+    #[snafu(display("Compression was cancelled!"))]
+    Cancelled,
+}
+
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Self::LimitExceeded { source }
+    }
 }
 type Result<T> = core::result::Result<T, Error>;
 
@@ -111,12 +134,36 @@ pub struct Header {
     pub copy_data_offset: u32,
 }
 
-/// All supported Yay0 compression algorithms
+/// All supported Yay0 compression algorithms, see [`profile`](crate::profile) for how they differ.
 #[derive(Clone, Copy)]
 #[non_exhaustive]
 pub enum CompressionAlgo {
     /// This algorithm should create identical files for all data from N64 and GameCube.
     MatchingOld, //eggCompress
+    /// Uses the quirks of Nintendo EAD's GameCube tools, see [`EncoderProfile::NINTENDO_EAD_GC`].
+    NintendoEadGc,
+    /// Uses a greedy encoder like most modern tools, see [`EncoderProfile::MODERN_GREEDY`].
+    ModernGreedy,
+    /// Uses any other combination of quirks, such as when trying to match an unknown encoder.
+    Custom(EncoderProfile),
+}
+
+impl CompressionAlgo {
+    /// Every built-in algorithm, in the order that [`statistics`](Yay0::statistics) checks them.
+    pub const KNOWN: [Self; 3] = [Self::MatchingOld, Self::NintendoEadGc, Self::ModernGreedy];
+
+    /// Returns the quirks that this algorithm reproduces. Yay0 has nowhere to store the alignment, so
+    /// [`stores_alignment`](EncoderProfile::stores_alignment) is ignored.
+    #[must_use]
+    #[inline]
+    pub const fn profile(self) -> EncoderProfile {
+        match self {
+            Self::MatchingOld => EncoderProfile::MATCHING_OLD,
+            Self::NintendoEadGc => EncoderProfile::NINTENDO_EAD_GC,
+            Self::ModernGreedy => EncoderProfile::MODERN_GREEDY,
+            Self::Custom(profile) => profile,
+        }
+    }
 }
 
 /// Utility struct for handling Yay0 compression.
@@ -211,9 +258,35 @@ impl Yay0 {
     ///
     /// # Errors
     /// Returns [`InvalidMagic`](Error::InvalidMagic) if the header does not match a Yay0 file.
+    #[cfg(feature = "alloc")]
     #[inline]
     pub fn decompress_from(data: &[u8]) -> Result<Box<[u8]>> {
+        Self::decompress_from_with_limits(data, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Decompresses a Yay0 file and returns the decompressed data, refusing to allocate more than
+    /// `limits.max_output_size` bytes. Use this instead of [`decompress_from`](Self::decompress_from) for
+    /// untrusted input, since the header can claim up to 4GB of output.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_ncompress::prelude::*;
+    /// let input = std::fs::read("../../examples/assets/tobudx.yay0_n64")?;
+    /// let limits = ResourceLimits { max_output_size: 0x1000, ..Default::default() };
+    /// let result = Yay0::decompress_from_with_limits(&input, &limits);
+    /// assert!(matches!(result, Err(yay0::Error::LimitExceeded { .. })));
+    /// # Ok::<(), yay0::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the header does not match a Yay0 file, or
+    /// [`LimitExceeded`](Error::LimitExceeded) if the decompressed size is larger than the limit.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn decompress_from_with_limits(data: &[u8], limits: &ResourceLimits) -> Result<Box<[u8]>> {
         let header = Self::read_header(data)?;
+        limits.check_output_size(header.decompressed_size.into())?;
 
         //Allocate decompression buffer
         let mut output = vec![0u8; header.decompressed_size as usize].into_boxed_slice();
@@ -287,14 +360,8 @@ impl Yay0 {
                     n => usize::from(n) + 2,
                 };
 
-                //If the ranges are not overlapping, use the faster copy method
-                if (back < output_pos + size) && (output_pos < back + size) {
-                    for n in 0..size {
-                        output[output_pos + n] = output[back + n];
-                    }
-                } else {
-                    output.copy_within(back..back + size, output_pos);
-                }
+                //Overlapping ranges repeat the bytes between them
+                copy_repeating(output, back..back + size, output_pos);
                 output_pos += size;
             }
 
@@ -349,16 +416,31 @@ impl Yay0 {
     /// # Errors
     /// Returns [`FileTooBig`](Error::FileTooBig) if the input is too large for the filesize to be
     /// stored in the header.
+    #[cfg(feature = "alloc")]
     #[inline]
-    pub fn compress_from(input: &[u8], algo: CompressionAlgo, _align: u32) -> Result<Box<[u8]>> {
+    pub fn compress_from(input: &[u8], algo: CompressionAlgo, align: u32) -> Result<Box<[u8]>> {
+        Self::compress_from_with_session(input, algo, align, &mut Session::new())
+    }
+
+    /// Compresses the input data using a given compression algorithm, reusing the hash chains in `session`.
+    /// This is faster when compressing many small files, see [`session`](crate::session) for more
+    /// information.
+    ///
+    /// # Errors
+    /// Returns [`FileTooBig`](Error::FileTooBig) if the input is too large for the filesize to be
+    /// stored in the header, or [`Cancelled`](Error::Cancelled) if `session` was cancelled.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn compress_from_with_session(
+        input: &[u8], algo: CompressionAlgo, _align: u32, session: &mut Session,
+    ) -> Result<Box<[u8]>> {
         ensure!(u32::try_from(input.len()).is_ok(), FileTooBigSnafu);
 
         //Assume 0x10 header, every byte is a copy, and include flag bytes (rounded up)
         let mut output = vec![0u8; Self::worst_possible_size(input.len())];
 
-        let output_size = match algo {
-            CompressionAlgo::MatchingOld => Self::compress_n64(input, &mut output),
-        };
+        let output_size = Self::compress_with_profile(input, &mut output, &algo.profile(), session)
+            .context(CancelledSnafu)?;
 
         output.truncate(output_size);
 
@@ -383,8 +465,19 @@ impl Yay0 {
     /// assert_eq!(*output, *expected);
     /// # Ok::<(), yay0::Error>(())
     /// ```
+    #[cfg(feature = "alloc")]
     #[inline]
     pub fn compress_n64(input: &[u8], output: &mut [u8]) -> usize {
+        // A new session can't be cancelled, so this always finishes
+        Self::compress_with_profile(input, output, &EncoderProfile::MATCHING_OLD, &mut Session::new())
+            .unwrap_or_default()
+    }
+
+    /// Compresses the input using the quirks in `profile`, returning [`None`] if `session` was cancelled.
+    #[cfg(feature = "alloc")]
+    fn compress_with_profile(
+        input: &[u8], output: &mut [u8], profile: &EncoderProfile, session: &mut Session,
+    ) -> Option<usize> {
         //Set up all arrays so we can accumulate data before writing it, since we don't know how
         // big each section can be
         let mut flag_data = vec![0u8; input.len().div_ceil(8)];
@@ -398,11 +491,15 @@ impl Yay0 {
         let mut lookback_data = vec![0u8; input.len()];
         let mut lookback_pos = 0;
 
-        let mut window = crate::algorithms::Window::new(input, 0x111);
+        let token = session.token();
+        let mut window = session.window(input, 0x111).with_search(profile.search, profile.ties);
 
         let mut input_pos = 0;
 
         while input_pos < input.len() {
+            if token.as_ref().is_some_and(CancellationToken::is_cancelled) {
+                return None;
+            }
             let (mut group_offset, mut group_size) = window.search(input_pos);
             if group_size <= 2 {
                 //If the group is less than two bytes, it's smaller to just copy a byte
@@ -411,9 +508,12 @@ impl Yay0 {
                 input_pos += 1;
                 copy_pos += 1;
             } else {
-                //Check one byte after this, see if we can get a better match
-                let (new_offset, new_size) = window.search(input_pos + 1);
-                if group_size + 1 < new_size {
+                //Check one byte after this (unless greedy), see if we can get a better match
+                let (new_offset, new_size) = match profile.lookahead {
+                    Lookahead::Greedy => (0, 0),
+                    Lookahead::Lazy { .. } => window.search(input_pos + 1),
+                };
+                if profile.prefers_later(group_size, new_size) {
                     //If we did find a better match, copy a byte and then use the new slice
                     flag_byte |= flag_shift;
                     copy_data[copy_pos] = input[input_pos];
@@ -463,8 +563,8 @@ impl Yay0 {
             }
         }
 
-        //Check if there's still data to flush
-        if flag_byte != 0 {
+        //Check if there's still data to flush, even if every flag is zero since it's still read
+        if flag_shift != 0x80 {
             flag_data[flag_pos] = flag_byte;
             flag_pos += 1;
         }
@@ -482,10 +582,106 @@ impl Yay0 {
         output[output_pos..output_pos + copy_pos].copy_from_slice(&copy_data[..copy_pos]);
         output_pos += (copy_pos + 3) & !3;
 
-        (output_pos + 15) & !15
+        Some((output_pos + 15) & !15)
+    }
+
+    /// Walks a Yay0 stream and gathers [`Statistics`] about it, including whether it exactly matches the
+    /// output of one of the known encoders.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_ncompress::prelude::*;
+    /// let input = std::fs::read("../../examples/assets/tobudx.yay0_n64")?;
+    /// let statistics = Yay0::statistics(&input)?;
+    /// assert_eq!(statistics.literals + statistics.backref_bytes, 0x40000);
+    /// assert_eq!(statistics.encoder, Some("MatchingOld"));
+    /// # Ok::<(), yay0::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the header does not match a Yay0 file,
+    /// [`EndOfFile`](Error::EndOfFile) if the stream is truncated, or [`InvalidSize`](Error::InvalidSize) if
+    /// the stream doesn't decompress to the size in the header.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn statistics(data: &[u8]) -> Result<Statistics> {
+        Self::statistics_with_limits(data, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Walks a Yay0 stream the same way as [`statistics`](Self::statistics), refusing to decompress more
+    /// than `limits.max_output_size` bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_ncompress::prelude::*;
+    /// let compressed = Yay0::compress_from(&[0u8; 0x1000], yay0::CompressionAlgo::MatchingOld, 0)?;
+    /// let limits = ResourceLimits { max_output_size: 0x800, ..Default::default() };
+    /// let result = Yay0::statistics_with_limits(&compressed, &limits);
+    /// assert!(matches!(result, Err(yay0::Error::LimitExceeded { .. })));
+    /// # Ok::<(), yay0::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the decompressed size is larger than the limit, or
+    /// any error from [`statistics`](Self::statistics).
+    #[cfg(feature = "alloc")]
+    pub fn statistics_with_limits(data: &[u8], limits: &ResourceLimits) -> Result<Statistics> {
+        ensure!(data.len() >= 0x10, EndOfFileSnafu);
+        let header = Self::read_header(data)?;
+        limits.check_output_size(header.decompressed_size.into())?;
+        let size = header.decompressed_size as usize;
+        let mut statistics = Statistics::new(data.len(), size);
+
+        // Each of the three sections is read separately, see the module documentation for details
+        let section = |offset: u32| data.get(offset as usize..).unwrap_or_default().iter().copied();
+        let mut flags = data[0x10..].iter().copied();
+        let mut lookbacks = section(header.lookback_offset);
+        let mut copy_data = section(header.copy_data_offset);
+
+        // The header can't be trusted yet, so let the output grow as the stream is walked
+        let mut output = Vec::new();
+        while output.len() < size {
+            let flags = flags.next().context(EndOfFileSnafu)?;
+            for bit in (0..8).rev() {
+                if output.len() >= size {
+                    break;
+                }
+                match (flags >> bit) & 1 != 0 {
+                    true => statistics.literal(&mut output, copy_data.next().context(EndOfFileSnafu)?),
+                    false => {
+                        let code = u16::from_be_bytes([
+                            lookbacks.next().context(EndOfFileSnafu)?,
+                            lookbacks.next().context(EndOfFileSnafu)?,
+                        ]);
+                        let (length, long) = match code >> 12 {
+                            0 => (
+                                usize::from(copy_data.next().context(EndOfFileSnafu)?) + 0x12,
+                                true,
+                            ),
+                            n => (usize::from(n) + 2, false),
+                        };
+                        let distance = usize::from(code & 0xFFF) + 1;
+                        statistics.backref(&mut output, distance, length, long).context(InvalidSizeSnafu)?;
+                    }
+                }
+            }
+        }
+        ensure!(output.len() == size, InvalidSizeSnafu);
+
+        let mut session = Session::new();
+        for algo in CompressionAlgo::KNOWN {
+            let recompressed = Self::compress_from_with_session(&output, algo, 0, &mut session)?;
+            if Statistics::matches(data, &recompressed) {
+                statistics.encoder = Some(algo.profile().name);
+                break;
+            }
+        }
+        Ok(statistics)
     }
 }
 
+#[cfg(feature = "std")]
 impl FileIdentifier for Yay0 {
     fn identify(data: &[u8]) -> Option<FileInfo> {
         Self::read_header(data).ok().map(|header| {
@@ -503,6 +699,10 @@ impl FileIdentifier for Yay0 {
                 "Nintendo Yay0-compressed file, decompressed size: {}",
                 util::format_size(header.decompressed_size as usize)
             );
+            let info = match Self::statistics(data) {
+                Ok(statistics) => format!("{info}, {statistics}"),
+                Err(_) => info,
+            };
             let payload = Self::decompress_from(data).ok();
             FileInfo::new(info, payload)
         })
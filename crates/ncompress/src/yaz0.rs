@@ -50,17 +50,43 @@
 //!   (older matching algorithm)
 //! ## Utilities
 //! * [`read_header`](Yaz0::read_header): Returns the header information for a given Yaz0 file
+//! * [`statistics`](Yaz0::statistics): Returns statistics about the compressed stream, and which encoder
+//!   produced it
 //! * [`worst_possible_size`](Yaz0::worst_possible_size): Calculates the worst possible compression size for a
 //!   given filesize
+//!
+//! ## Example
+//! Compressing and decompressing data generated by `orthrus_core::synthetic`, which roughly matches the
+//! makeup of real game files:
+//!
+//! ```
+//! use orthrus_core::synthetic;
+//! use orthrus_ncompress::prelude::*;
+//!
+//! let data = synthetic::structured(0x2000, 0x1234);
+//! let compressed = Yaz0::compress_from(&data, yaz0::CompressionAlgo::MatchingOld, 0)?;
+//! assert!(compressed.len() < data.len());
+//! assert_eq!(Yaz0::read_header(&compressed)?.decompressed_size, data.len() as u32);
+//! assert_eq!(Yaz0::decompress_from(&compressed)?, data);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
 
 #[cfg(feature = "std")]
 use std::path::Path;
 
+use orthrus_core::data::copy_repeating;
 use orthrus_core::prelude::*;
 use snafu::prelude::*;
 
-#[cfg(not(feature = "std"))]
+#[cfg(all(feature = "alloc", not(feature = "std")))]
 use crate::no_std::*;
+use crate::profile::EncoderProfile;
+#[cfg(feature = "alloc")]
+use crate::profile::Lookahead;
+#[cfg(feature = "alloc")]
+use crate::session::Session;
+#[cfg(feature = "alloc")]
+use crate::statistics::Statistics;
 
 /// Error conditions for when reading/writing Yaz0 files
 #[derive(Debug, Snafu)]
@@ -86,6 +112,19 @@ pub enum Error {
     /// Thrown if the header contains a magic number other than "Yaz0".
     #[snafu(display("Invalid Magic! Expected {:?}.", Yaz0::MAGIC))]
     InvalidMagic,
+    /// Thrown if the header claims a size larger than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+    /// Thrown if compression was stopped using the [`Session`]'s [`CancellationToken`].
+    #[snafu(display("Compression was cancelled!"))]
+    Cancelled,
+}
+
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Self::LimitExceeded { source }
+    }
 }
 type Result<T> = core::result::Result<T, Error>;
 
@@ -102,12 +141,43 @@ impl From<std::io::Error> for Error {
     }
 }
 
-/// All supported Yaz0 compression algorithms
+/// All supported Yaz0 compression algorithms, see [`profile`](crate::profile) for how they differ.
 #[derive(Clone, Copy)]
 #[non_exhaustive]
 pub enum CompressionAlgo {
     /// This algorithm should create identical files for all data from N64, GameCube, and Wii.
     MatchingOld, //eggCompress
+    /// Uses the quirks of Nintendo EAD's GameCube tools, see [`EncoderProfile::NINTENDO_EAD_GC`].
+    NintendoEadGc,
+    /// Uses the quirks of the Wii U's NLib tools, see [`EncoderProfile::WII_U_NLIB`].
+    WiiUNlib,
+    /// Uses a greedy encoder like most modern tools, see [`EncoderProfile::MODERN_GREEDY`].
+    ModernGreedy,
+    /// Uses any other combination of quirks, such as when trying to match an unknown encoder.
+    Custom(EncoderProfile),
+}
+
+impl CompressionAlgo {
+    /// Every built-in algorithm, in the order that [`statistics`](Yaz0::statistics) checks them.
+    pub const KNOWN: [Self; 4] = [
+        Self::MatchingOld,
+        Self::NintendoEadGc,
+        Self::WiiUNlib,
+        Self::ModernGreedy,
+    ];
+
+    /// Returns the quirks that this algorithm reproduces.
+    #[must_use]
+    #[inline]
+    pub const fn profile(self) -> EncoderProfile {
+        match self {
+            Self::MatchingOld => EncoderProfile::MATCHING_OLD,
+            Self::NintendoEadGc => EncoderProfile::NINTENDO_EAD_GC,
+            Self::WiiUNlib => EncoderProfile::WII_U_NLIB,
+            Self::ModernGreedy => EncoderProfile::MODERN_GREEDY,
+            Self::Custom(profile) => profile,
+        }
+    }
 }
 
 /// See the module [header](self#header) for more information.
@@ -208,9 +278,35 @@ impl Yaz0 {
     ///
     /// # Errors
     /// Returns [`InvalidMagic`](Error::InvalidMagic) if the header does not match a Yaz0 file.
+    #[cfg(feature = "alloc")]
     #[inline]
     pub fn decompress_from(data: &[u8]) -> Result<Box<[u8]>> {
+        Self::decompress_from_with_limits(data, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Decompresses a Yaz0 file and returns the decompressed data, refusing to allocate more than
+    /// `limits.max_output_size` bytes. Use this instead of [`decompress_from`](Self::decompress_from) for
+    /// untrusted input, since the header can claim up to 4GB of output.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_ncompress::prelude::*;
+    /// let input = std::fs::read("../../examples/assets/tobudx.yaz0_n64")?;
+    /// let limits = ResourceLimits { max_output_size: 0x1000, ..Default::default() };
+    /// let result = Yaz0::decompress_from_with_limits(&input, &limits);
+    /// assert!(matches!(result, Err(yaz0::Error::LimitExceeded { .. })));
+    /// # Ok::<(), yaz0::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the header does not match a Yaz0 file, or
+    /// [`LimitExceeded`](Error::LimitExceeded) if the decompressed size is larger than the limit.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn decompress_from_with_limits(data: &[u8], limits: &ResourceLimits) -> Result<Box<[u8]>> {
         let header = Self::read_header(data)?;
+        limits.check_output_size(header.decompressed_size.into())?;
 
         //Allocate decompression buffer
         let mut output = vec![0u8; header.decompressed_size as usize].into_boxed_slice();
@@ -275,14 +371,8 @@ impl Yaz0 {
                     n => usize::from(n) + 2,
                 };
 
-                //If the ranges are not overlapping, use the faster copy method
-                if (back < output_pos + size) && (output_pos < back + size) {
-                    for n in 0..size {
-                        output[output_pos + n] = output[back + n];
-                    }
-                } else {
-                    output.copy_within(back..back + size, output_pos);
-                }
+                //Overlapping ranges repeat the bytes between them
+                copy_repeating(output, back..back + size, output_pos);
                 output_pos += size;
             }
 
@@ -336,21 +426,41 @@ impl Yaz0 {
     ///
     /// # Warnings
     /// Alignment should be zero for N64, GameCube, and Wii, and should be non-zero on Wii U and
-    /// Switch.
+    /// Switch. It's only stored by algorithms whose profile has
+    /// [`stores_alignment`](EncoderProfile::stores_alignment) set.
     ///
     /// # Errors
     /// Returns [`FileTooBig`](Error::FileTooBig) if the input is too large for the filesize to be
     /// stored in the header.
+    #[cfg(feature = "alloc")]
     #[inline]
-    pub fn compress_from(input: &[u8], algo: CompressionAlgo, _align: u32) -> Result<Box<[u8]>> {
+    pub fn compress_from(input: &[u8], algo: CompressionAlgo, align: u32) -> Result<Box<[u8]>> {
+        Self::compress_from_with_session(input, algo, align, &mut Session::new())
+    }
+
+    /// Compresses the input data using a given compression algorithm, reusing the hash chains in `session`.
+    /// This is faster when compressing many small files, see [`session`](crate::session) for more
+    /// information.
+    ///
+    /// # Errors
+    /// Returns [`FileTooBig`](Error::FileTooBig) if the input is too large for the filesize to be
+    /// stored in the header, or [`Cancelled`](Error::Cancelled) if `session` was cancelled.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn compress_from_with_session(
+        input: &[u8], algo: CompressionAlgo, align: u32, session: &mut Session,
+    ) -> Result<Box<[u8]>> {
         ensure!(u32::try_from(input.len()).is_ok(), FileTooBigSnafu);
 
         //Assume 0x10 header, every byte is a copy, and include flag bytes (rounded up)
         let mut output = vec![0u8; Self::worst_possible_size(input.len())];
 
-        let output_size = match algo {
-            CompressionAlgo::MatchingOld => Self::compress_n64(input, &mut output),
-        };
+        let profile = algo.profile();
+        let output_size =
+            Self::compress_with_profile(input, &mut output, &profile, session).context(CancelledSnafu)?;
+        if profile.stores_alignment {
+            output[8..12].copy_from_slice(&align.to_be_bytes());
+        }
 
         output.truncate(output_size);
 
@@ -376,13 +486,25 @@ impl Yaz0 {
     /// assert_eq!(*output, *expected);
     /// # Ok::<(), yaz0::Error>(())
     /// ```
+    #[cfg(feature = "alloc")]
     #[inline]
     pub fn compress_n64(input: &[u8], output: &mut [u8]) -> usize {
+        // A new session can't be cancelled, so this always finishes
+        Self::compress_with_profile(input, output, &EncoderProfile::MATCHING_OLD, &mut Session::new())
+            .unwrap_or_default()
+    }
+
+    /// Compresses the input using the quirks in `profile`, returning [`None`] if `session` was cancelled.
+    #[cfg(feature = "alloc")]
+    fn compress_with_profile(
+        input: &[u8], output: &mut [u8], profile: &EncoderProfile, session: &mut Session,
+    ) -> Option<usize> {
         output[0..4].copy_from_slice(b"Yaz0");
         output[4..8].copy_from_slice(&u32::to_be_bytes(input.len() as u32));
-        //Older files do not have alignment so this just leaves it as zero
+        //Alignment is left as zero, and filled in afterwards by profiles that store it
 
-        let mut window = crate::algorithms::Window::new(input, 0x111);
+        let token = session.token();
+        let mut window = session.window(input, 0x111).with_search(profile.search, profile.ties);
 
         let mut input_pos = 0;
         let mut output_pos = 0x11;
@@ -390,6 +512,9 @@ impl Yaz0 {
         let mut flag_byte_shift = 0x80;
 
         while input_pos < input.len() {
+            if token.as_ref().is_some_and(CancellationToken::is_cancelled) {
+                return None;
+            }
             let (mut group_offset, mut group_size) = window.search(input_pos);
             if group_size <= 2 {
                 //If the group is less than two bytes, it's smaller to just copy a byte
@@ -398,9 +523,12 @@ impl Yaz0 {
                 input_pos += 1;
                 output_pos += 1;
             } else {
-                //Check one byte after this, see if we can get a better match
-                let (new_offset, new_size) = window.search(input_pos + 1);
-                if group_size + 1 < new_size {
+                //Check one byte after this (unless greedy), see if we can get a better match
+                let (new_offset, new_size) = match profile.lookahead {
+                    Lookahead::Greedy => (0, 0),
+                    Lookahead::Lazy { .. } => window.search(input_pos + 1),
+                };
+                if profile.prefers_later(group_size, new_size) {
                     //If we did find a better match, copy a byte and then use the new slice
                     output[flag_byte_pos] |= flag_byte_shift;
                     output[output_pos] = input[input_pos];
@@ -448,10 +576,102 @@ impl Yaz0 {
             }
         }
 
-        output_pos
+        Some(output_pos)
+    }
+
+    /// Walks a Yaz0 stream and gathers [`Statistics`] about it, including whether it exactly matches the
+    /// output of one of the known encoders.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_ncompress::prelude::*;
+    /// let input = std::fs::read("../../examples/assets/tobudx.yaz0_n64")?;
+    /// let statistics = Yaz0::statistics(&input)?;
+    /// assert_eq!(statistics.literals + statistics.backref_bytes, 0x40000);
+    /// assert_eq!(statistics.encoder, Some("MatchingOld"));
+    /// # Ok::<(), yaz0::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the header does not match a Yaz0 file,
+    /// [`EndOfFile`](Error::EndOfFile) if the stream is truncated, or [`InvalidSize`](Error::InvalidSize) if
+    /// the stream doesn't decompress to the size in the header.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn statistics(data: &[u8]) -> Result<Statistics> {
+        Self::statistics_with_limits(data, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Walks a Yaz0 stream the same way as [`statistics`](Self::statistics), refusing to decompress more
+    /// than `limits.max_output_size` bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_ncompress::prelude::*;
+    /// let compressed = Yaz0::compress_from(&[0u8; 0x1000], yaz0::CompressionAlgo::MatchingOld, 0)?;
+    /// let limits = ResourceLimits { max_output_size: 0x800, ..Default::default() };
+    /// let result = Yaz0::statistics_with_limits(&compressed, &limits);
+    /// assert!(matches!(result, Err(yaz0::Error::LimitExceeded { .. })));
+    /// # Ok::<(), yaz0::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the decompressed size is larger than the limit, or
+    /// any error from [`statistics`](Self::statistics).
+    #[cfg(feature = "alloc")]
+    pub fn statistics_with_limits(data: &[u8], limits: &ResourceLimits) -> Result<Statistics> {
+        ensure!(data.len() >= 0x10, EndOfFileSnafu);
+        let header = Self::read_header(data)?;
+        limits.check_output_size(header.decompressed_size.into())?;
+        let size = header.decompressed_size as usize;
+        let mut statistics = Statistics::new(data.len(), size);
+
+        let mut input = data[0x10..].iter().copied();
+        let mut next = || input.next().context(EndOfFileSnafu);
+        // The header can't be trusted yet, so let the output grow as the stream is walked
+        let mut output = Vec::new();
+        while output.len() < size {
+            let flags = next()?;
+            for bit in (0..8).rev() {
+                if output.len() >= size {
+                    break;
+                }
+                match (flags >> bit) & 1 != 0 {
+                    true => statistics.literal(&mut output, next()?),
+                    false => {
+                        let code = u16::from_be_bytes([next()?, next()?]);
+                        let (length, long) = match code >> 12 {
+                            0 => (usize::from(next()?) + 0x12, true),
+                            n => (usize::from(n) + 2, false),
+                        };
+                        let distance = usize::from(code & 0xFFF) + 1;
+                        statistics.backref(&mut output, distance, length, long).context(InvalidSizeSnafu)?;
+                    }
+                }
+            }
+        }
+        ensure!(output.len() == size, InvalidSizeSnafu);
+
+        // Profiles that don't store the alignment can only match files where it's zero
+        let mut session = Session::new();
+        for algo in CompressionAlgo::KNOWN {
+            let profile = algo.profile();
+            if header.alignment != 0 && !profile.stores_alignment {
+                continue;
+            }
+            let recompressed =
+                Self::compress_from_with_session(&output, algo, header.alignment, &mut session)?;
+            if Statistics::matches(data, &recompressed) {
+                statistics.encoder = Some(profile.name);
+                break;
+            }
+        }
+        Ok(statistics)
     }
 }
 
+#[cfg(feature = "std")]
 impl FileIdentifier for Yaz0 {
     fn identify(data: &[u8]) -> Option<FileInfo> {
         Self::read_header(data).ok().map(|header| {
@@ -469,6 +689,10 @@ impl FileIdentifier for Yaz0 {
                 "Nintendo Yaz0-compressed file, decompressed size: {}",
                 util::format_size(header.decompressed_size as usize)
             );
+            let info = match Self::statistics(data) {
+                Ok(statistics) => format!("{info}, {statistics}"),
+                Err(_) => info,
+            };
             let payload = Self::decompress_from(data).ok();
             FileInfo::new(info, payload)
         })
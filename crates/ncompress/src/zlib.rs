@@ -0,0 +1,265 @@
+//! Adds support for the zlib compression format, used by Panda3D Multifiles, Godot resource packs, and many
+//! other engines.
+//!
+//! This is a thin wrapper around [`miniz_oxide`], so that every format crate uses the same backend and the
+//! same API as the other compression formats in this crate. It's only available with the `flate` feature.
+//!
+//! # Format
+//! A zlib stream is a 2-byte header describing the compression method and window size, raw DEFLATE data,
+//! then an Adler-32 checksum of the decompressed data. Unlike the Nintendo formats, the decompressed size
+//! isn't stored anywhere, so the output grows as it's decompressed.
+//!
+//! ## Header
+//! The header is as follows, in big-endian format:
+//!
+//! | Offset | Field | Type | Notes |
+//! |--------|-------|------|-------|
+//! | 0x0 | CMF | u8 | Compression method (8 for DEFLATE) in the low nibble, and window size in the high nibble. |
+//! | 0x1 | FLG | u8 | Compression level and preset dictionary flag, chosen so `CMF << 8 \| FLG` is a multiple of 31. |
+//!
+//! # Usage
+//! This module offers the following functionality:
+//! ## Decompression
+//! * [`decompress_from_path`](Zlib::decompress_from_path): Provide a path, get decompressed data back
+//! * [`decompress_from`](Zlib::decompress_from): Provide the input data, get decompressed data back
+//! * [`decompress_from_with_limits`](Zlib::decompress_from_with_limits): Provide the input data, get
+//!   decompressed data back as long as it fits within the limits
+//! ## Compression
+//! * [`compress_from_path`](Zlib::compress_from_path): Provide a path, get compressed data back
+//! * [`compress_from`](Zlib::compress_from): Provide the input data, get compressed data back
+//! * [`compress_from_with_level`](Zlib::compress_from_with_level): Provide the input data and a compression
+//!   level, get compressed data back
+//! ## Utilities
+//! * [`read_header`](Zlib::read_header): Returns the header information for a given zlib stream
+
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use miniz_oxide::inflate::TINFLStatus;
+use orthrus_core::prelude::*;
+use snafu::prelude::*;
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use crate::no_std::*;
+
+/// Error conditions for when reading/writing zlib streams
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown when trying to open a file or folder that doesn't exist.
+    #[snafu(display("Unable to find file/folder!"))]
+    NotFound,
+    /// Thrown if reading/writing tries to go out of bounds.
+    #[snafu(display("Unexpected End-Of-File!"))]
+    EndOfFile,
+    /// Thrown when unable to open a file or folder.
+    #[snafu(display("No permissions to open file/folder!"))]
+    PermissionDenied,
+    /// Thrown if the header isn't a valid zlib header.
+    #[snafu(display("Invalid zlib header!"))]
+    InvalidMagic,
+    /// Thrown if the compressed data is invalid, or doesn't match its checksum.
+    #[snafu(display("Invalid compressed data: {reason}"))]
+    InvalidData { reason: &'static str },
+    /// Thrown if the decompressed data is larger than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+    /// Thrown if any other filesystem error occurs.
+    #[cfg(feature = "std")]
+    #[snafu(display("Filesystem Error {source}"))]
+    FileError { source: std::io::Error },
+}
+
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Self::LimitExceeded { source }
+    }
+}
+type Result<T> = core::result::Result<T, Error>;
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(error: std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => Self::NotFound,
+            std::io::ErrorKind::UnexpectedEof => Self::EndOfFile,
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+            _ => Self::FileError { source: error },
+        }
+    }
+}
+
+/// See the module [header](self#header) for more information.
+pub struct Header {
+    /// Size of the window used for back-references, between 256 bytes and 32KB.
+    pub window_size: u32,
+    /// Whether a preset dictionary is needed to decompress the data, which isn't supported.
+    pub dictionary: bool,
+}
+
+/// Utility struct for handling zlib compression.
+///
+/// zlib is stateless, and is merely a namespace for implementing certain traits.
+///
+/// See the [module documentation](self) for more information.
+pub struct Zlib;
+
+impl Zlib {
+    /// Compression level used by [`compress_from`](Self::compress_from), which matches zlib's default.
+    pub const DEFAULT_LEVEL: u8 = 6;
+    /// Highest supported compression level, which is the slowest but produces the smallest output.
+    pub const MAX_LEVEL: u8 = 10;
+
+    /// Returns the metadata from a zlib header.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_ncompress::prelude::*;
+    /// let header = Zlib::read_header(&[0x78, 0x9C])?;
+    /// assert_eq!(header.window_size, 0x8000);
+    /// assert!(!header.dictionary);
+    /// # Ok::<(), zlib::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the header does not match a zlib stream, or
+    /// [`EndOfFile`](Error::EndOfFile) if the header is truncated.
+    #[inline]
+    pub fn read_header(data: &[u8]) -> Result<Header> {
+        ensure!(data.len() >= 2, EndOfFileSnafu);
+        let (method, flags) = (data[0], data[1]);
+        // Only DEFLATE with at most a 32KB window is defined, and the check bits make the header a multiple
+        // of 31
+        ensure!(
+            method & 0xF == 8 && method >> 4 <= 7 && u16::from_be_bytes([method, flags]) % 31 == 0,
+            InvalidMagicSnafu
+        );
+        Ok(Header { window_size: 1 << ((method >> 4) + 8), dictionary: flags & 0x20 != 0 })
+    }
+
+    /// Loads a zlib-compressed file and returns the decompressed data.
+    ///
+    /// # Errors
+    /// Returns:
+    /// * [`NotFound`](Error::NotFound) if the path does not exist
+    /// * [`PermissionDenied`](Error::PermissionDenied) if unable to open the file
+    /// * [`InvalidMagic`](Error::InvalidMagic) if the header does not match a zlib stream
+    /// * [`InvalidData`](Error::InvalidData) if the compressed data is invalid
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn decompress_from_path<P: AsRef<Path>>(path: P) -> Result<Box<[u8]>> {
+        let input = std::fs::read(path)?;
+        Self::decompress_from(&input)
+    }
+
+    /// Decompresses a zlib stream and returns the decompressed data.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_ncompress::prelude::*;
+    /// let input = b"Orthrus Orthrus Orthrus".repeat(0x100);
+    /// let compressed = Zlib::compress_from(&input)?;
+    /// let output = Zlib::decompress_from(&compressed)?;
+    /// assert_eq!(*output, *input);
+    /// # Ok::<(), zlib::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the header does not match a zlib stream, or
+    /// [`InvalidData`](Error::InvalidData) if the compressed data is invalid or truncated.
+    #[inline]
+    pub fn decompress_from(data: &[u8]) -> Result<Box<[u8]>> {
+        Self::decompress_from_with_limits(data, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Decompresses a zlib stream and returns the decompressed data, stopping once the output is larger than
+    /// `limits.max_output_size` bytes. Use this instead of [`decompress_from`](Self::decompress_from) for
+    /// untrusted input, since a tiny stream can decompress to gigabytes of data.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_ncompress::prelude::*;
+    /// let compressed = Zlib::compress_from(&[0u8; 0x10000])?;
+    /// let limits = ResourceLimits { max_output_size: 0x1000, ..Default::default() };
+    /// let result = Zlib::decompress_from_with_limits(&compressed, &limits);
+    /// assert!(matches!(result, Err(zlib::Error::LimitExceeded { .. })));
+    /// # Ok::<(), zlib::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the header does not match a zlib stream,
+    /// [`InvalidData`](Error::InvalidData) if the compressed data is invalid or truncated, or
+    /// [`LimitExceeded`](Error::LimitExceeded) if the decompressed data is larger than the limit.
+    #[inline]
+    pub fn decompress_from_with_limits(data: &[u8], limits: &ResourceLimits) -> Result<Box<[u8]>> {
+        let header = Self::read_header(data)?;
+        ensure!(
+            !header.dictionary,
+            InvalidDataSnafu { reason: "preset dictionaries aren't supported" }
+        );
+        let limit = usize::try_from(limits.max_output_size).unwrap_or(usize::MAX);
+        match miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(data, limit) {
+            Ok(output) => Ok(output.into_boxed_slice()),
+            // The actual size isn't known, only that it's at least one byte more than the limit
+            Err(error) if error.status == TINFLStatus::HasMoreOutput => {
+                let limit = limits.max_output_size;
+                Err(LimitError::OutputTooLarge { size: limit.saturating_add(1), limit }.into())
+            }
+            Err(error) => InvalidDataSnafu {
+                reason: match error.status {
+                    TINFLStatus::Adler32Mismatch => "checksum mismatch",
+                    TINFLStatus::FailedCannotMakeProgress | TINFLStatus::NeedsMoreInput => "truncated stream",
+                    _ => "invalid DEFLATE data",
+                },
+            }
+            .fail(),
+        }
+    }
+
+    /// Loads a file and returns it compressed using the [default level](Self::DEFAULT_LEVEL).
+    ///
+    /// # Errors
+    /// Returns:
+    /// * [`NotFound`](Error::NotFound) if the path does not exist
+    /// * [`PermissionDenied`](Error::PermissionDenied) if unable to open the file
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn compress_from_path<P: AsRef<Path>>(path: P) -> Result<Box<[u8]>> {
+        let input = std::fs::read(path)?;
+        Self::compress_from(&input)
+    }
+
+    /// Compresses the input data using the [default level](Self::DEFAULT_LEVEL).
+    ///
+    /// # Errors
+    /// This currently never fails, but returns a `Result` to match the other compression formats.
+    #[inline]
+    pub fn compress_from(input: &[u8]) -> Result<Box<[u8]>> {
+        Self::compress_from_with_level(input, Self::DEFAULT_LEVEL)
+    }
+
+    /// Compresses the input data, where `level` is between 0 (no compression) and
+    /// [`MAX_LEVEL`](Self::MAX_LEVEL). Higher levels are clamped to the maximum.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_ncompress::prelude::*;
+    /// let input = b"Orthrus Orthrus Orthrus".repeat(0x100);
+    /// let stored = Zlib::compress_from_with_level(&input, 0)?;
+    /// let compressed = Zlib::compress_from_with_level(&input, Zlib::MAX_LEVEL)?;
+    /// assert!(compressed.len() < stored.len());
+    /// assert_eq!(*Zlib::decompress_from(&stored)?, *input);
+    /// # Ok::<(), zlib::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// This currently never fails, but returns a `Result` to match the other compression formats.
+    #[inline]
+    pub fn compress_from_with_level(input: &[u8], level: u8) -> Result<Box<[u8]>> {
+        let level = level.min(Self::MAX_LEVEL);
+        Ok(miniz_oxide::deflate::compress_to_vec_zlib(input, level).into_boxed_slice())
+    }
+}
@@ -0,0 +1,384 @@
+//! Adds support for the Zstandard compression format, used by newer engines and asset bundles that want
+//! faster decompression than zlib.
+//!
+//! This is a thin wrapper around [`ruzstd`], so that every format crate uses the same backend and the same API
+//! as the other compression formats in this crate. It's only available with the `zstd` feature.
+//!
+//! # Format
+//! A Zstandard stream is one or more frames, each made of a frame header, a series of blocks, and an optional
+//! checksum of the decompressed data. Blocks are either stored, a single repeated byte, or compressed as a
+//! set of literals and a list of sequences that copy previous output, which are entropy coded with Huffman
+//! and Finite State Entropy (FSE) tables.
+//!
+//! Skippable frames are ignored when decompressing, and concatenated frames are decompressed one after
+//! another. Frames that need a dictionary aren't supported.
+//!
+//! The compressor uses the fastest level [`ruzstd`] supports, which is roughly equivalent to level 1 of the
+//! reference encoder.
+//!
+//! ## Header
+//! The frame header is as follows, in little-endian format:
+//!
+//! | Offset | Field | Type | Notes |
+//! |--------|-------|------|-------|
+//! | 0x0 | Magic number | u32 | Always `0xFD2FB528`. |
+//! | 0x4 | Frame header descriptor | u8 | Flags for which of the following fields are present. |
+//! | 0x5 | Window descriptor | u8 | Optional, maximum distance of back-references. |
+//! | ... | Dictionary ID | u8/u16/u32 | Optional, ID of the dictionary needed to decompress the frame. |
+//! | ... | Frame content size | u8/u16/u32/u64 | Optional, size of the decompressed data. |
+//!
+//! # Usage
+//! This module offers the following functionality:
+//! ## Decompression
+//! * [`decompress_from_path`](Zstd::decompress_from_path): Provide a path, get decompressed data back
+//! * [`decompress_from`](Zstd::decompress_from): Provide the input data, get decompressed data back
+//! * [`decompress_from_with_limits`](Zstd::decompress_from_with_limits): Provide the input data, get
+//!   decompressed data back as long as it fits within the limits
+//! ## Compression
+//! * [`compress_from_path`](Zstd::compress_from_path): Provide a path, get compressed data back
+//! * [`compress_from`](Zstd::compress_from): Provide the input data, get compressed data back
+//! ## Utilities
+//! * [`read_header`](Zstd::read_header): Returns the header information for a given Zstandard frame
+
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use orthrus_core::prelude::*;
+use ruzstd::decoding::errors::{DecodeBlockContentError, FrameDecoderError};
+use ruzstd::decoding::{BlockDecodingStrategy, FrameDecoder};
+use ruzstd::encoding::CompressionLevel;
+use snafu::prelude::*;
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use crate::no_std::*;
+
+/// Error conditions for when reading/writing Zstandard streams
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown when trying to open a file or folder that doesn't exist.
+    #[snafu(display("Unable to find file/folder!"))]
+    NotFound,
+    /// Thrown if reading/writing tries to go out of bounds.
+    #[snafu(display("Unexpected End-Of-File!"))]
+    EndOfFile,
+    /// Thrown when unable to open a file or folder.
+    #[snafu(display("No permissions to open file/folder!"))]
+    PermissionDenied,
+    /// Thrown if the header contains a magic number other than `0xFD2FB528`.
+    #[snafu(display("Invalid Magic! Expected 0xFD2FB528."))]
+    InvalidMagic,
+    /// Thrown if the compressed data is invalid.
+    #[snafu(display("Invalid compressed data: {reason}"))]
+    InvalidData { reason: &'static str },
+    /// Thrown if a frame needs a dictionary to be decompressed, which isn't supported.
+    #[snafu(display("Frame needs dictionary {id:#X}, which isn't supported!"))]
+    UnsupportedDictionary { id: u32 },
+    /// Thrown if the decompressed data doesn't match the checksum stored in the frame.
+    #[snafu(display("Checksum mismatch! Expected {expected:#010X}, got {actual:#010X}."))]
+    ChecksumMismatch { expected: u32, actual: u32 },
+    /// Thrown if the decompressed data is larger than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+    /// Thrown if any other filesystem error occurs.
+    #[cfg(feature = "std")]
+    #[snafu(display("Filesystem Error {source}"))]
+    FileError { source: std::io::Error },
+}
+
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Self::LimitExceeded { source }
+    }
+}
+type Result<T> = core::result::Result<T, Error>;
+
+impl From<FrameDecoderError> for Error {
+    #[inline]
+    fn from(error: FrameDecoderError) -> Self {
+        let reason = match error {
+            FrameDecoderError::FailedToReadBlockHeader(_)
+            | FrameDecoderError::FailedToReadBlockBody(DecodeBlockContentError::ReadError { .. })
+            | FrameDecoderError::FailedToReadChecksum(_) => "truncated stream",
+            FrameDecoderError::WindowSizeTooBig { .. } => "window is too large",
+            _ => "invalid Zstandard data",
+        };
+        Self::InvalidData { reason }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(error: std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => Self::NotFound,
+            std::io::ErrorKind::UnexpectedEof => Self::EndOfFile,
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+            _ => Self::FileError { source: error },
+        }
+    }
+}
+
+/// See the module [header](self#header) for more information.
+pub struct Header {
+    /// Size of the decompressed data, if it was stored in the frame.
+    pub content_size: Option<u64>,
+    /// Maximum distance of back-references, which is how much previous output the decoder needs to keep.
+    pub window_size: u64,
+    /// ID of the dictionary needed to decompress the frame, if any.
+    pub dictionary_id: Option<u32>,
+    /// Whether the frame ends with a checksum of the decompressed data.
+    pub checksum: bool,
+    /// Size of the frame header in bytes, including the magic number.
+    pub size: usize,
+}
+
+/// Utility struct for handling Zstandard compression.
+///
+/// Zstandard is stateless, and is merely a namespace for implementing certain traits.
+///
+/// See the [module documentation](self) for more information.
+pub struct Zstd;
+
+impl Zstd {
+    /// Unique identifier that tells us if we're reading a Zstandard frame.
+    pub const MAGIC: u32 = 0xFD2FB528;
+
+    /// Returns the metadata from a Zstandard frame header.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_ncompress::prelude::*;
+    /// let compressed = Zstd::compress_from(&[0u8; 0x1000])?;
+    /// let header = Zstd::read_header(&compressed)?;
+    /// assert_eq!(header.dictionary_id, None);
+    /// assert!(header.checksum);
+    /// # Ok::<(), zstd::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the magic number does not match a Zstandard frame,
+    /// [`InvalidData`](Error::InvalidData) if the reserved bit is set, or [`EndOfFile`](Error::EndOfFile) if
+    /// the header is truncated.
+    #[inline]
+    pub fn read_header(data: &[u8]) -> Result<Header> {
+        let magic = data.get(..4).context(EndOfFileSnafu)?;
+        ensure!(
+            u32::from_le_bytes(magic.try_into().unwrap()) == Self::MAGIC,
+            InvalidMagicSnafu
+        );
+        let descriptor = *data.get(4).context(EndOfFileSnafu)?;
+        ensure!(
+            descriptor & 0x8 == 0,
+            InvalidDataSnafu { reason: "reserved header bit is set" }
+        );
+
+        let single_segment = descriptor & 0x20 != 0;
+        let dictionary_size = [0, 1, 2, 4][usize::from(descriptor & 3)];
+        let content_size_size = match descriptor >> 6 {
+            0 => usize::from(single_segment),
+            flag => 1 << flag,
+        };
+        let size = 5 + usize::from(!single_segment) + dictionary_size + content_size_size;
+        ensure!(data.len() >= size, EndOfFileSnafu);
+
+        let mut position = 5;
+        let mut window_size = 0;
+        if !single_segment {
+            // Window size is stored as a power of two, plus eighths of that power
+            let exponent = data[position] >> 3;
+            let base = 1u64 << (10 + exponent);
+            window_size = base + (base / 8) * u64::from(data[position] & 7);
+            position += 1;
+        }
+        let dictionary_id = read_le(&data[position..position + dictionary_size]) as u32;
+        position += dictionary_size;
+        let content_size = match content_size_size {
+            0 => None,
+            // Two byte sizes are offset, since anything smaller than 256 would fit in one byte
+            2 => Some(read_le(&data[position..position + 2]) + 0x100),
+            _ => Some(read_le(&data[position..position + content_size_size])),
+        };
+        if single_segment {
+            window_size = content_size.unwrap_or_default();
+        }
+
+        Ok(Header {
+            content_size,
+            window_size,
+            dictionary_id: (dictionary_id != 0).then_some(dictionary_id),
+            checksum: descriptor & 0x4 != 0,
+            size,
+        })
+    }
+
+    /// Loads a Zstandard-compressed file and returns the decompressed data.
+    ///
+    /// # Errors
+    /// Returns:
+    /// * [`NotFound`](Error::NotFound) if the path does not exist
+    /// * [`PermissionDenied`](Error::PermissionDenied) if unable to open the file
+    /// * [`InvalidMagic`](Error::InvalidMagic) if the header does not match a Zstandard frame
+    /// * [`InvalidData`](Error::InvalidData) if the compressed data is invalid
+    /// * [`ChecksumMismatch`](Error::ChecksumMismatch) if the decompressed data doesn't match its checksum
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn decompress_from_path<P: AsRef<Path>>(path: P) -> Result<Box<[u8]>> {
+        let input = std::fs::read(path)?;
+        Self::decompress_from(&input)
+    }
+
+    /// Decompresses a Zstandard stream and returns the decompressed data.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_ncompress::prelude::*;
+    /// let input = b"Orthrus Orthrus Orthrus".repeat(0x100);
+    /// let compressed = Zstd::compress_from(&input)?;
+    /// let output = Zstd::decompress_from(&compressed)?;
+    /// assert_eq!(*output, *input);
+    /// # Ok::<(), zstd::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the header does not match a Zstandard frame,
+    /// [`InvalidData`](Error::InvalidData) if the compressed data is invalid or truncated,
+    /// [`UnsupportedDictionary`](Error::UnsupportedDictionary) if a frame needs a dictionary, or
+    /// [`ChecksumMismatch`](Error::ChecksumMismatch) if the decompressed data doesn't match its checksum.
+    #[inline]
+    pub fn decompress_from(data: &[u8]) -> Result<Box<[u8]>> {
+        Self::decompress_from_with_limits(data, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Decompresses a Zstandard stream and returns the decompressed data, stopping once the output is larger
+    /// than `limits.max_output_size` bytes. Use this instead of [`decompress_from`](Self::decompress_from)
+    /// for untrusted input, since a tiny stream can decompress to gigabytes of data.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_ncompress::prelude::*;
+    /// let compressed = Zstd::compress_from(&[0u8; 0x10000])?;
+    /// let limits = ResourceLimits { max_output_size: 0x1000, ..Default::default() };
+    /// let result = Zstd::decompress_from_with_limits(&compressed, &limits);
+    /// assert!(matches!(result, Err(zstd::Error::LimitExceeded { .. })));
+    /// # Ok::<(), zstd::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns the same errors as [`decompress_from`](Self::decompress_from), or
+    /// [`LimitExceeded`](Error::LimitExceeded) if the decompressed data is larger than the limit.
+    pub fn decompress_from_with_limits(data: &[u8], limits: &ResourceLimits) -> Result<Box<[u8]>> {
+        let mut decoder = FrameDecoder::new();
+        let mut output = Vec::new();
+        let mut input = data;
+        // An empty stream isn't valid, there always needs to be at least one frame
+        ensure!(!input.is_empty(), EndOfFileSnafu);
+        while !input.is_empty() {
+            let magic = input.get(..4).context(EndOfFileSnafu)?;
+            let magic = u32::from_le_bytes(magic.try_into().unwrap());
+            if magic & 0xFFFF_FFF0 == SKIPPABLE_MAGIC {
+                let size = input.get(4..8).context(EndOfFileSnafu)?;
+                let size = u32::from_le_bytes(size.try_into().unwrap()) as usize;
+                input = input.get(8 + size..).context(EndOfFileSnafu)?;
+                continue;
+            }
+
+            let header = Self::read_header(input)?;
+            if let Some(id) = header.dictionary_id {
+                return UnsupportedDictionarySnafu { id }.fail();
+            }
+            let frame_start = output.len();
+            if let Some(size) = header.content_size {
+                limits.check_output_size((frame_start as u64).saturating_add(size))?;
+                // Don't trust the stored size too much, since it could be anything
+                output.reserve(size.min(0x400_0000) as usize);
+            }
+
+            // Decode a block at a time, so the limit is checked before the output grows any further
+            decoder.init(&mut input)?;
+            loop {
+                let finished = decoder.decode_blocks(&mut input, BlockDecodingStrategy::UptoBlocks(1))?;
+                limits.check_output_size((output.len() + decoder.can_collect()) as u64)?;
+                if let Some(block) = decoder.collect() {
+                    output.extend_from_slice(&block);
+                }
+                if finished {
+                    break;
+                }
+            }
+
+            if let Some(size) = header.content_size {
+                let actual = (output.len() - frame_start) as u64;
+                ensure!(
+                    actual == size,
+                    InvalidDataSnafu { reason: "decompressed size doesn't match the header" }
+                );
+            }
+            if let (Some(expected), Some(actual)) = (
+                decoder.get_checksum_from_data(),
+                decoder.get_calculated_checksum(),
+            ) {
+                ensure!(expected == actual, ChecksumMismatchSnafu { expected, actual });
+            }
+        }
+        Ok(output.into_boxed_slice())
+    }
+
+    /// Loads a file and returns it compressed as a single Zstandard frame.
+    ///
+    /// # Errors
+    /// Returns:
+    /// * [`NotFound`](Error::NotFound) if the path does not exist
+    /// * [`PermissionDenied`](Error::PermissionDenied) if unable to open the file
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn compress_from_path<P: AsRef<Path>>(path: P) -> Result<Box<[u8]>> {
+        let input = std::fs::read(path)?;
+        Self::compress_from(&input)
+    }
+
+    /// Compresses the input data as a single Zstandard frame, which stores a checksum of the input.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_ncompress::prelude::*;
+    /// let input = b"Orthrus Orthrus Orthrus".repeat(0x100);
+    /// let compressed = Zstd::compress_from(&input)?;
+    /// assert!(compressed.len() < input.len() / 10);
+    /// # Ok::<(), zstd::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// This currently never fails, but returns a `Result` to match the other compression formats.
+    #[inline]
+    pub fn compress_from(input: &[u8]) -> Result<Box<[u8]>> {
+        Ok(ruzstd::encoding::compress_to_vec(input, CompressionLevel::Fastest).into_boxed_slice())
+    }
+}
+
+#[cfg(feature = "std")]
+impl FileIdentifier for Zstd {
+    fn identify(data: &[u8]) -> Option<FileInfo> {
+        Self::read_header(data).ok().map(|header| {
+            let size = match header.content_size {
+                Some(size) => util::format_size(size as usize),
+                None => "unknown".into(),
+            };
+            FileInfo::new(
+                format!("Zstandard-compressed file, decompressed size: {size}"),
+                None,
+            )
+        })
+    }
+}
+
+/// Magic number of skippable frames, where the lowest four bits can be anything.
+const SKIPPABLE_MAGIC: u32 = 0x184D2A50;
+
+/// Reads a little-endian integer of up to 8 bytes.
+fn read_le(data: &[u8]) -> u64 {
+    data.iter().rev().fold(0, |value, &byte| (value << 8) | u64::from(byte))
+}
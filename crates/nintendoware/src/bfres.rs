@@ -0,0 +1,660 @@
+//! Adds initial support for the Binary caFe RESource (BFRES) container, used by NintendoWare for Cafe and
+//! for the Nintendo Switch to store models, textures, and animations.
+//!
+//! This currently only handles the container itself, enumerating every resource inside of it (along with its
+//! name and offset), so that the individual resources can be converted later on.
+//!
+//! # Revisions
+//! **Wii U** files are big-endian, and all offsets are signed 32-bit values relative to the field they're
+//! stored in. Textures are stored directly in the file as FTEX resources.
+//!
+//! **Switch** files are (usually) little-endian, and all offsets are 64-bit values relative to the start of
+//! the file, which the relocation table lists so they can be turned into pointers at runtime. Textures are
+//! stored in an embedded [BNTX](crate::bntx) file instead.
+//!
+//! # Format
+//! Both revisions start with "FRES", followed by a version, byte order mark, and the file size. After that,
+//! each kind of resource is stored in its own group, along with a dictionary of names:
+//!
+//! | Wii U Group | Switch Group | Magic | Description                          |
+//! |-------------|--------------|-------|--------------------------------------|
+//! | 0           | 0            | FMDL  | Models                               |
+//! | 1           |              | FTEX  | Textures                             |
+//! | 2           | 1            | FSKA  | Skeletal animations                  |
+//! | 3, 4, 5     |              | FSHU  | Shader parameter/color/SRT animations|
+//! | 6           |              | FTXP  | Texture pattern animations           |
+//! |             | 2            | FMAA  | Material animations                  |
+//! | 7, 8        | 3            | FVIS  | Bone/material visibility animations  |
+//! | 9           | 4            | FSHA  | Shape animations                     |
+//! | 10          | 5            | FSCN  | Scene animations                     |
+//! | 11          | 6            |       | Embedded files                       |
+//!
+//! Names are shared between all resources through a single string pool.
+//!
+//! # Usage
+//! ```no_run
+//! use orthrus_nintendoware::bfres::{SectionKind, BFRES};
+//! use orthrus_nintendoware::bntx::BNTX;
+//!
+//! let bfres = BFRES::open("model.bfres")?;
+//! for section in &bfres.sections {
+//!     println!("{:?} {} at {:#X}", section.kind, section.name, section.offset);
+//! }
+//!
+//! // Switch models store their textures inside of an embedded BNTX
+//! for section in bfres.sections.iter().filter(|section| section.kind == SectionKind::EmbeddedFile) {
+//!     if let Some(data) = bfres.payload(section) {
+//!         if data.starts_with(&BNTX::MAGIC) {
+//!             let textures = BNTX::load(data)?;
+//!         }
+//!     }
+//! }
+//! # Ok::<(), orthrus_nintendoware::error::Error>(())
+//! ```
+
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use orthrus_core::data::EndianExt;
+use orthrus_core::prelude::*;
+use snafu::prelude::*;
+
+use crate::error::*;
+use crate::generation::Generation;
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
+/// Which console a BFRES file was made for.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Platform {
+    WiiU,
+    Switch,
+}
+
+/// Kind of resource stored in a [`Section`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum SectionKind {
+    Model,
+    Texture,
+    SkeletalAnim,
+    ShaderParamAnim,
+    ColorAnim,
+    TextureSrtAnim,
+    TexturePatternAnim,
+    MaterialAnim,
+    BoneVisibilityAnim,
+    MaterialVisibilityAnim,
+    ShapeAnim,
+    SceneAnim,
+    EmbeddedFile,
+}
+
+impl SectionKind {
+    /// Groups in a Wii U file, in the order they're stored.
+    const WII_U: [Self; 12] = [
+        Self::Model,
+        Self::Texture,
+        Self::SkeletalAnim,
+        Self::ShaderParamAnim,
+        Self::ColorAnim,
+        Self::TextureSrtAnim,
+        Self::TexturePatternAnim,
+        Self::BoneVisibilityAnim,
+        Self::MaterialVisibilityAnim,
+        Self::ShapeAnim,
+        Self::SceneAnim,
+        Self::EmbeddedFile,
+    ];
+
+    /// Returns the magic at the start of each resource, or [`None`] for embedded files.
+    #[must_use]
+    #[inline]
+    pub const fn magic(self) -> Option<[u8; 4]> {
+        match self {
+            Self::Model => Some(*b"FMDL"),
+            Self::Texture => Some(*b"FTEX"),
+            Self::SkeletalAnim => Some(*b"FSKA"),
+            Self::ShaderParamAnim | Self::ColorAnim | Self::TextureSrtAnim => Some(*b"FSHU"),
+            Self::TexturePatternAnim => Some(*b"FTXP"),
+            Self::MaterialAnim => Some(*b"FMAA"),
+            Self::BoneVisibilityAnim | Self::MaterialVisibilityAnim => Some(*b"FVIS"),
+            Self::ShapeAnim => Some(*b"FSHA"),
+            Self::SceneAnim => Some(*b"FSCN"),
+            Self::EmbeddedFile => None,
+        }
+    }
+}
+
+/// A single resource inside of a BFRES file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// Kind of resource
+    pub kind: SectionKind,
+    /// Name of the resource
+    pub name: String,
+    /// Absolute offset of the resource
+    pub offset: u64,
+    /// Size of the resource, which is only stored for embedded files
+    pub size: Option<u64>,
+}
+
+/// A run of pointers in a [`RelocationSection`].
+///
+/// Starting at `position`, there are `struct_count` repeats of `offset_count` consecutive pointers, each
+/// followed by `padding_count` pointer-sized values that should be skipped.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RelocationEntry {
+    pub position: u32,
+    pub struct_count: u16,
+    pub offset_count: u8,
+    pub padding_count: u8,
+}
+
+/// A region of the file that contains pointers, such as the main data or the memory pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelocationSection {
+    /// Absolute offset of the region
+    pub position: u32,
+    /// Size of the region
+    pub size: u32,
+    /// All pointers inside of this region
+    pub entries: Vec<RelocationEntry>,
+}
+
+/// Relocation table ("_RLT"), which lists the position of every offset in a Switch file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RelocationTable {
+    pub sections: Vec<RelocationSection>,
+}
+
+impl RelocationTable {
+    /// Unique identifier for the relocation table.
+    pub const MAGIC: [u8; 4] = *b"_RLT";
+
+    /// Reads the relocation table at the given offset.
+    fn read<T: ReadExt + SeekExt>(data: &mut T, offset: u64, limits: &ResourceLimits) -> Result<Self> {
+        data.set_position(offset)?;
+        let magic = data.read_exact::<4>()?;
+        ensure!(magic == Self::MAGIC, InvalidMagicSnafu { expected: Self::MAGIC });
+        let _position = data.read_u32()?;
+        let section_count = data.read_u32()?;
+        limits.check_entries(section_count.into())?;
+        let _padding = data.read_u32()?;
+
+        let mut headers = Vec::new();
+        for _ in 0..section_count {
+            let _pointer = data.read_u64()?;
+            let position = data.read_u32()?;
+            let size = data.read_u32()?;
+            let first_entry = data.read_u32()?;
+            let entry_count = data.read_u32()?;
+            headers.push((position, size, first_entry, entry_count));
+        }
+
+        // All entries are stored in one list after the section headers
+        let entries_start = data.position()?;
+        let mut sections = Vec::with_capacity(headers.len());
+        for (position, size, first_entry, entry_count) in headers {
+            data.set_position(entries_start + u64::from(first_entry) * 8)?;
+            limits.check_entries(entry_count.into())?;
+            let mut entries = Vec::new();
+            for _ in 0..entry_count {
+                let position = data.read_u32()?;
+                let struct_count = data.read_u16()?;
+                let offset_count = data.read_u8()?;
+                let padding_count = data.read_u8()?;
+                entries.push(RelocationEntry { position, struct_count, offset_count, padding_count });
+            }
+            sections.push(RelocationSection { position, size, entries });
+        }
+        Ok(Self { sections })
+    }
+
+    /// Returns the absolute position of every pointer in the file.
+    #[must_use]
+    pub fn pointers(&self) -> Vec<u64> {
+        let mut pointers = Vec::new();
+        for entry in self.sections.iter().flat_map(|section| &section.entries) {
+            let stride = u64::from(entry.offset_count) + u64::from(entry.padding_count);
+            for index in 0..u64::from(entry.struct_count) {
+                let start = u64::from(entry.position) + index * stride * 8;
+                pointers.extend((0..u64::from(entry.offset_count)).map(|n| start + n * 8));
+            }
+        }
+        pointers
+    }
+}
+
+/// Reads a null-terminated string at the given offset, then returns to the current position.
+fn read_terminated<T: ReadExt + SeekExt>(data: &mut T, offset: u64) -> Result<String> {
+    let mut data = data.push_position()?;
+    data.set_position(offset)?;
+    let mut bytes = Vec::new();
+    loop {
+        match data.read_u8()? {
+            0 => break,
+            byte => bytes.push(byte),
+        }
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Reads a Wii U offset, which is relative to the position of the offset itself.
+#[inline]
+fn read_relative<T: ReadExt + SeekExt>(data: &mut T) -> Result<Option<u64>> {
+    let position = data.position()?;
+    let offset = data.read_i32()?;
+    Ok(match offset {
+        0 => None,
+        offset => Some(position.wrapping_add_signed(offset.into())),
+    })
+}
+
+/// Adds `offset` to `base`, returning an error instead of overflowing on a malformed offset.
+#[inline]
+fn add_offset(base: u64, offset: u64) -> Result<u64> {
+    base.checked_add(offset)
+        .context(InvalidDataSnafu { position: base, reason: "Offset Out Of Bounds" })
+}
+
+/// Binary caFe RESource container
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BFRES {
+    /// Which console this file was made for
+    pub platform: Platform,
+    /// Raw version number
+    pub version: u32,
+    /// Name of the file, usually the model name
+    pub name: String,
+    /// All strings used for names
+    pub string_pool: Vec<String>,
+    /// All resources in the file, in the order they're stored in each group
+    pub sections: Vec<Section>,
+    /// Relocation table, only used on Switch
+    pub relocation_table: Option<RelocationTable>,
+    data: Box<[u8]>,
+}
+
+impl BFRES {
+    /// Unique identifier that tells us if we're reading a BFRES file.
+    pub const MAGIC: [u8; 4] = *b"FRES";
+
+    /// Reads a BFRES file from disk, see [`load`](Self::load) for more information.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn open<P: AsRef<Path>>(input: P) -> Result<Self> {
+        Self::open_with_limits(input, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Reads a BFRES file from disk, returning an error instead of reading a larger file, or more resources
+    /// or strings than `limits` allow.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn open_with_limits<P: AsRef<Path>>(input: P, limits: &ResourceLimits) -> Result<Self> {
+        // Check the size before reading the whole file into memory
+        let input = input.as_ref();
+        limits.check_output_size(std::fs::metadata(input)?.len())?;
+        let data = std::fs::read(input)?;
+        Self::load_with_limits(data, limits)
+    }
+
+    /// Loads a BFRES file, and enumerates all of its resources.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if this isn't a BFRES file (or a resource has the wrong
+    /// magic), [`InvalidEndian`](Error::InvalidEndian) if the byte order mark is invalid, or
+    /// [`InvalidData`](Error::InvalidData) if any of the offsets are invalid.
+    #[inline]
+    pub fn load<I: Into<Box<[u8]>>>(input: I) -> Result<Self> {
+        Self::load_with_limits(input, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Loads a BFRES file the same way as [`load`](Self::load), returning an error instead of keeping a
+    /// larger file, or reading more resources or strings than `limits` allow.
+    ///
+    /// BFRES files don't nest, so `max_depth` isn't used.
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the file exceeds `limits`, or any error from
+    /// [`load`](Self::load).
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_nintendoware::bfres::BFRES;
+    /// # use orthrus_nintendoware::error::Error;
+    /// let limits = ResourceLimits { max_output_size: 4, ..Default::default() };
+    /// let result = BFRES::load_with_limits(b"FRES    ".as_slice(), &limits);
+    /// assert!(matches!(result, Err(Error::LimitExceeded { .. })));
+    /// ```
+    pub fn load_with_limits<I: Into<Box<[u8]>>>(input: I, limits: &ResourceLimits) -> Result<Self> {
+        let mut data = DataCursor::new(input, Endian::Big);
+        limits.check_output_size(data.len()?)?;
+
+        let magic = data.read_exact::<4>()?;
+        ensure!(magic == Self::MAGIC, InvalidMagicSnafu { expected: Self::MAGIC });
+
+        // Switch files pad the magic out to 8 bytes with spaces
+        let mut file = match &data.read_exact::<4>()? {
+            b"    " => Self::read_switch(&mut data, limits)?,
+            _ => Self::read_wii_u(&mut data, limits)?,
+        };
+        limits.check_entries(file.sections.len() as u64)?;
+
+        // Make sure every resource actually starts with the magic we expect
+        for section in &file.sections {
+            if let Some(expected) = section.kind.magic() {
+                data.set_position(section.offset)?;
+                ensure!(
+                    data.read_exact::<4>()? == expected,
+                    InvalidMagicSnafu { expected }
+                );
+            }
+        }
+
+        file.data = data.into_inner();
+        Ok(file)
+    }
+
+    /// Sets the endian from the byte order mark at the current position.
+    #[inline]
+    fn read_byte_order<T: ReadExt>(data: &mut T) -> Result<()> {
+        match data.read_exact::<2>()? {
+            [0xFE, 0xFF] => data.set_endian(Endian::Big),
+            [0xFF, 0xFE] => data.set_endian(Endian::Little),
+            endian => InvalidEndianSnafu { endian }.fail()?,
+        }
+        Ok(())
+    }
+
+    fn read_wii_u(data: &mut DataCursor, limits: &ResourceLimits) -> Result<Self> {
+        data.set_position(0x04)?;
+        let version = data.read_u32()?;
+        Self::read_byte_order(data)?;
+
+        data.set_position(0x14)?;
+        let name = match read_relative(data)? {
+            Some(offset) => read_terminated(data, offset)?,
+            None => String::new(),
+        };
+        let string_size = data.read_u32()?;
+        let string_offset = read_relative(data)?;
+
+        let mut groups = [None; 12];
+        for group in &mut groups {
+            *group = read_relative(data)?;
+        }
+
+        // Each string is a u32 length, followed by the null-terminated string, aligned to 4 bytes
+        let mut string_pool = Vec::new();
+        if let Some(start) = string_offset {
+            let end = add_offset(start, string_size.into())?;
+            let mut position = start;
+            while add_offset(position, 4)? <= end {
+                data.set_position(position)?;
+                let length = data.read_u32()?;
+                string_pool.push(read_terminated(data, position + 4)?);
+                limits.check_entries(string_pool.len() as u64)?;
+                position = add_offset(position, 4 + u64::from(length) + 1 + 3)? & !3;
+            }
+        }
+
+        // Each group is an index group (a Patricia tree), where the first node is the root
+        let mut sections = Vec::new();
+        for (kind, group) in SectionKind::WII_U.into_iter().zip(groups) {
+            let Some(group) = group else { continue };
+            data.set_position(add_offset(group, 4)?)?;
+            let count = data.read_u32()?;
+            limits.check_entries(count.into())?;
+            for index in 1..=u64::from(count) {
+                let node = add_offset(group, 8 + index * 0x10)?;
+                data.set_position(add_offset(node, 8)?)?;
+                let name = match read_relative(data)? {
+                    Some(name_offset) => read_terminated(data, name_offset)?,
+                    None => String::new(),
+                };
+                let offset = read_relative(data)?
+                    .context(InvalidDataSnafu { position: node + 12, reason: "Missing Resource Offset" })?;
+
+                // Embedded files point to an offset and size, rather than to the data itself
+                let (offset, size) = match kind {
+                    SectionKind::EmbeddedFile => {
+                        data.set_position(offset)?;
+                        let file_offset = read_relative(data)?.unwrap_or(offset);
+                        (file_offset, Some(u64::from(data.read_u32()?)))
+                    }
+                    _ => (offset, None),
+                };
+                sections.push(Section { kind, name, offset, size });
+            }
+        }
+
+        Ok(Self {
+            platform: Platform::WiiU,
+            version,
+            name,
+            string_pool,
+            sections,
+            relocation_table: None,
+            data: Box::default(),
+        })
+    }
+
+    fn read_switch(data: &mut DataCursor, limits: &ResourceLimits) -> Result<Self> {
+        data.set_endian(Endian::Little);
+        data.set_position(0x08)?;
+        let version = data.read_u32()?;
+        Self::read_byte_order(data)?;
+
+        data.set_position(0x10)?;
+        let name_offset = data.read_u32()?;
+        let _flags = data.read_u16()?;
+        let _block_offset = data.read_u16()?;
+        let relocation_offset = data.read_u32()?;
+
+        // Every group is an array of resources, followed by a dictionary of names
+        data.set_position(0x28)?;
+        let mut groups = [(0u64, 0u64); 7];
+        for (index, group) in groups.iter_mut().enumerate() {
+            // The memory pool and buffer info sit between the scene animations and the embedded files
+            if index == 6 {
+                data.set_position(0x98)?;
+            }
+            *group = (data.read_u64()?, data.read_u64()?);
+        }
+        data.set_position(0xB0)?;
+        let string_offset = data.read_u64()?;
+        let _string_size = data.read_u32()?;
+        let mut counts = [0u16; 7];
+        for count in &mut counts {
+            *count = data.read_u16()?;
+        }
+
+        let name = match name_offset {
+            0 => String::new(),
+            offset => read_terminated(data, offset.into())?,
+        };
+
+        let kinds = [
+            SectionKind::Model,
+            SectionKind::SkeletalAnim,
+            SectionKind::MaterialAnim,
+            SectionKind::BoneVisibilityAnim,
+            SectionKind::ShapeAnim,
+            SectionKind::SceneAnim,
+            SectionKind::EmbeddedFile,
+        ];
+        let mut sections = Vec::new();
+        for ((kind, (array, dictionary)), count) in kinds.into_iter().zip(groups).zip(counts) {
+            if array == 0 || count == 0 {
+                continue;
+            }
+            limits.check_entries(count.into())?;
+            let names = Self::read_dictionary(data, dictionary, count)?;
+            let stride = match kind {
+                SectionKind::EmbeddedFile => 0x10,
+                kind => Self::find_stride(data, array, count, kind.magic().unwrap())?,
+            };
+
+            for (index, name) in (0..u64::from(count)).zip(names) {
+                let offset = add_offset(array, index * stride)?;
+                let (offset, size) = match kind {
+                    SectionKind::EmbeddedFile => {
+                        data.set_position(offset)?;
+                        (data.read_u64()?, Some(u64::from(data.read_u32()?)))
+                    }
+                    _ => (offset, None),
+                };
+                sections.push(Section { kind, name, offset, size });
+            }
+        }
+
+        let string_pool = match string_offset {
+            0 => Vec::new(),
+            offset => Self::read_string_block(data, offset, limits)?,
+        };
+
+        let relocation_table = match relocation_offset {
+            0 => None,
+            offset => Some(RelocationTable::read(data, offset.into(), limits)?),
+        };
+
+        Ok(Self {
+            platform: Platform::Switch,
+            version,
+            name,
+            string_pool,
+            sections,
+            relocation_table,
+            data: Box::default(),
+        })
+    }
+
+    /// Reads the names from a Switch dictionary ("_DIC"), skipping the root node.
+    fn read_dictionary(data: &mut DataCursor, offset: u64, count: u16) -> Result<Vec<String>> {
+        if offset == 0 {
+            return Ok(vec![String::new(); count.into()]);
+        }
+        data.set_position(offset)?;
+        let magic = data.read_exact::<4>()?;
+        ensure!(magic == *b"_DIC", InvalidMagicSnafu { expected: *b"_DIC" });
+
+        let mut names = Vec::with_capacity(count.into());
+        for index in 1..=u64::from(count) {
+            data.set_position(add_offset(offset, 8 + index * 0x10 + 8)?)?;
+            let name_offset = data.read_u64()?;
+            names.push(Self::read_switch_string(data, name_offset)?);
+        }
+        Ok(names)
+    }
+
+    /// Reads a Switch string, which is a `u16` length followed by the null-terminated string.
+    #[inline]
+    fn read_switch_string(data: &mut DataCursor, offset: u64) -> Result<String> {
+        match offset {
+            0 => Ok(String::new()),
+            offset => read_terminated(data, add_offset(offset, 2)?),
+        }
+    }
+
+    /// Finds the size of each resource in an array.
+    ///
+    /// Resources grow between versions, so rather than hardcoding every size, this looks for the magic of the
+    /// second resource.
+    fn find_stride(data: &mut DataCursor, array: u64, count: u16, magic: [u8; 4]) -> Result<u64> {
+        if count < 2 {
+            return Ok(0);
+        }
+        for stride in (8..0x400).step_by(8) {
+            data.set_position(add_offset(array, stride)?)?;
+            if data.read_exact::<4>()? == magic {
+                return Ok(stride);
+            }
+        }
+        InvalidDataSnafu { position: array, reason: "Unable To Find Resource Size" }.fail()
+    }
+
+    /// Reads the string pool ("_STR"), where each string is a `u16` length followed by the null-terminated
+    /// string, aligned to 2 bytes.
+    fn read_string_block(data: &mut DataCursor, offset: u64, limits: &ResourceLimits) -> Result<Vec<String>> {
+        data.set_position(offset)?;
+        let magic = data.read_exact::<4>()?;
+        ensure!(magic == *b"_STR", InvalidMagicSnafu { expected: *b"_STR" });
+
+        data.set_position(add_offset(offset, 0x10)?)?;
+        let count = data.read_u32()?;
+        limits.check_entries(count.into())?;
+        let mut position = add_offset(offset, 0x18)?;
+        let mut strings = Vec::new();
+        for _ in 0..count {
+            data.set_position(position)?;
+            let length = data.read_u16()?;
+            strings.push(read_terminated(data, add_offset(position, 2)?)?);
+            position = add_offset(position, 2 + u64::from(length) + 1 + 1)? & !1;
+        }
+        Ok(strings)
+    }
+
+    /// Returns the raw data of the entire file.
+    #[must_use]
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the raw data of a resource, if its size is known (currently only for embedded files).
+    #[must_use]
+    pub fn payload(&self, section: &Section) -> Option<&[u8]> {
+        let start = usize::try_from(section.offset).ok()?;
+        let size = usize::try_from(section.size?).ok()?;
+        self.data.get(start..start.checked_add(size)?)
+    }
+
+    /// Returns the generation of NintendoWare that built the file, or [`None`] if its version doesn't match
+    /// any known generation.
+    #[must_use]
+    #[inline]
+    pub const fn generation(&self) -> Option<Generation> {
+        Self::classify(self.platform, self.version)
+    }
+
+    /// Returns the generation of NintendoWare that builds files with this version for `platform`, see the
+    /// [`generation`](crate::generation) module for the known versions.
+    #[inline]
+    const fn classify(platform: Platform, version: u32) -> Option<Generation> {
+        match (platform, version >> 24, (version >> 16) & 0xFF) {
+            (Platform::WiiU, 3..=4, _) => Some(Generation::NW4F),
+            (Platform::Switch, 0, 5..=10) => Some(Generation::NX),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "identify")]
+impl FileIdentifier for BFRES {
+    fn identify(data: &[u8]) -> Option<FileInfo> {
+        if data.get(..4)? != Self::MAGIC {
+            return None;
+        }
+        // Switch files pad the magic out to 8 bytes, which moves the version and byte order mark back
+        let (platform, console, offset) = match data.get(4..8)? {
+            b"    " => (Platform::Switch, "Switch", 0x8),
+            _ => (Platform::WiiU, "Wii U", 0x4),
+        };
+        let (endian, byte_order) = match data.get(offset + 4..offset + 6)? {
+            [0xFE, 0xFF] => (Endian::Big, "big endian"),
+            [0xFF, 0xFE] => (Endian::Little, "little endian"),
+            _ => (Endian::Big, "invalid byte order mark"),
+        };
+        let mut cursor = DataCursorRef::new(data, endian);
+        cursor.set_position(offset as u64).ok()?;
+        let version = cursor.read_u32().ok()?;
+        let generation = crate::generation::describe(Self::classify(platform, version));
+        let [major, minor, patch, build] = version.to_be_bytes();
+        let version = format!("v{major}.{minor}.{patch}.{build}");
+        let info = format!(
+            "NintendoWare {console} resource container (BFRES) {version} ({generation}), {byte_order}"
+        );
+        Some(FileInfo::new(info, None))
+    }
+}
@@ -0,0 +1,574 @@
+//! Adds support for the Binary NX Texture (BNTX) container used by NintendoWare for the Nintendo Switch.
+//!
+//! BNTX files show up standalone, embedded inside of BFRES models and sound/layout archives, and in Switch
+//! ports of other engines (such as Godot), which all store their textures using the same container.
+//!
+//! # Format
+//! All values are little-endian, and every offset is absolute (from the start of the file).
+//!
+//! | Offset | Field               | Description                                               |
+//! |--------|---------------------|-----------------------------------------------------------|
+//! | 0x00   | Magic               | Always "BNTX"                                             |
+//! | 0x08   | Version             | Usually 0x40000                                           |
+//! | 0x0C   | Byte Order Mark     | Always 0xFEFF                                             |
+//! | 0x10   | Name Offset         | Points to a null-terminated file name                     |
+//! | 0x18   | Relocation Table    | Offset of the relocation table ("_RLT")                   |
+//! | 0x1C   | File Size           | Size of the entire file                                   |
+//! | 0x20   | Platform            | Always "NX  "                                             |
+//! | 0x24   | Texture Count       | Number of textures in the file                            |
+//! | 0x28   | Texture Array       | Offset of a `u64` array of texture info ("BRTI") offsets  |
+//! | 0x30   | Data Block          | Offset of the texture data block ("BRTD")                 |
+//!
+//! Each texture info block describes a single texture, with its name, format, dimensions, and a table of
+//! offsets for each mipmap. Names are stored as a `u16` length followed by a null-terminated string.
+//!
+//! Texture data is almost always stored in the [block-linear](orthrus_texture::tegra) layout, and all
+//! array layers are stored one after another, each with the full set of mipmaps.
+//!
+//! # Usage
+//! ```no_run
+//! use orthrus_nintendoware::bntx::BNTX;
+//!
+//! let bntx = BNTX::open("textures.bntx")?;
+//! for texture in &bntx.textures {
+//!     // Decode the base level of the first layer into RGBA8
+//!     let image = texture.decode(0, 0)?;
+//!     println!("{}: {}x{}", texture.name, image.width, image.height);
+//! }
+//! # Ok::<(), orthrus_nintendoware::error::Error>(())
+//! ```
+
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use num_enum::FromPrimitive;
+use orthrus_core::data::EndianExt;
+use orthrus_core::prelude::*;
+use orthrus_texture::{astc, bc, tegra};
+use snafu::prelude::*;
+
+use crate::error::*;
+use crate::generation::Generation;
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
+/// Layout of the data in each channel of a [`SurfaceFormat`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, FromPrimitive)]
+#[repr(u8)]
+pub enum FormatType {
+    Unorm = 0x01,
+    Snorm = 0x02,
+    Uint = 0x03,
+    Sint = 0x04,
+    Float = 0x05,
+    Srgb = 0x06,
+    UFloat = 0x0A,
+    #[num_enum(catch_all)]
+    Unknown(u8),
+}
+
+/// Format of the texture data, which is stored in the upper byte of the format field.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, FromPrimitive)]
+#[repr(u8)]
+pub enum SurfaceFormat {
+    R8 = 0x02,
+    R4G4B4A4 = 0x03,
+    R5G5B5A1 = 0x05,
+    R5G6B5 = 0x07,
+    R8G8 = 0x09,
+    R16 = 0x0A,
+    R8G8B8A8 = 0x0B,
+    B8G8R8A8 = 0x0C,
+    R10G10B10A2 = 0x0E,
+    R11G11B10 = 0x0F,
+    BC1 = 0x1A,
+    BC2 = 0x1B,
+    BC3 = 0x1C,
+    BC4 = 0x1D,
+    BC5 = 0x1E,
+    BC6H = 0x1F,
+    BC7 = 0x20,
+    Astc4x4 = 0x2D,
+    Astc5x4 = 0x2E,
+    Astc5x5 = 0x2F,
+    Astc6x5 = 0x30,
+    Astc6x6 = 0x31,
+    Astc8x5 = 0x32,
+    Astc8x6 = 0x33,
+    Astc8x8 = 0x34,
+    Astc10x5 = 0x35,
+    Astc10x6 = 0x36,
+    Astc10x8 = 0x37,
+    Astc10x10 = 0x38,
+    Astc12x10 = 0x39,
+    Astc12x12 = 0x3A,
+    #[num_enum(catch_all)]
+    Unknown(u8),
+}
+
+impl SurfaceFormat {
+    /// Returns the width and height of a single element (block) in pixels, and its size in bytes, or [`None`]
+    /// if the format is unknown.
+    #[must_use]
+    #[inline]
+    pub const fn element_info(self) -> Option<(u32, u32, u32)> {
+        let info = match self {
+            Self::R8 => (1, 1, 1),
+            Self::R4G4B4A4 | Self::R5G5B5A1 | Self::R5G6B5 | Self::R8G8 | Self::R16 => (1, 1, 2),
+            Self::R8G8B8A8 | Self::B8G8R8A8 | Self::R10G10B10A2 | Self::R11G11B10 => (1, 1, 4),
+            Self::BC1 | Self::BC4 => (4, 4, 8),
+            Self::BC2 | Self::BC3 | Self::BC5 | Self::BC6H | Self::BC7 => (4, 4, 16),
+            Self::Astc4x4 => (4, 4, 16),
+            Self::Astc5x4 => (5, 4, 16),
+            Self::Astc5x5 => (5, 5, 16),
+            Self::Astc6x5 => (6, 5, 16),
+            Self::Astc6x6 => (6, 6, 16),
+            Self::Astc8x5 => (8, 5, 16),
+            Self::Astc8x6 => (8, 6, 16),
+            Self::Astc8x8 => (8, 8, 16),
+            Self::Astc10x5 => (10, 5, 16),
+            Self::Astc10x6 => (10, 6, 16),
+            Self::Astc10x8 => (10, 8, 16),
+            Self::Astc10x10 => (10, 10, 16),
+            Self::Astc12x10 => (12, 10, 16),
+            Self::Astc12x12 => (12, 12, 16),
+            Self::Unknown(_) => return None,
+        };
+        Some(info)
+    }
+
+    /// Returns whether this is one of the ASTC formats.
+    #[must_use]
+    #[inline]
+    pub const fn is_astc(self) -> bool {
+        matches!(
+            self,
+            Self::Astc4x4
+                | Self::Astc5x4
+                | Self::Astc5x5
+                | Self::Astc6x5
+                | Self::Astc6x6
+                | Self::Astc8x5
+                | Self::Astc8x6
+                | Self::Astc8x8
+                | Self::Astc10x5
+                | Self::Astc10x6
+                | Self::Astc10x8
+                | Self::Astc10x10
+                | Self::Astc12x10
+                | Self::Astc12x12
+        )
+    }
+}
+
+/// How the texture data is laid out in memory.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default, FromPrimitive)]
+#[repr(u16)]
+pub enum TileMode {
+    /// Block-linear layout, see [`orthrus_texture::tegra`]
+    #[default]
+    Optimal,
+    /// Row-major layout, where each row is padded to [`LINEAR_PITCH_ALIGNMENT`] bytes
+    Linear,
+}
+
+/// Alignment of each row of a [`TileMode::Linear`] texture, in bytes.
+pub const LINEAR_PITCH_ALIGNMENT: usize = 32;
+
+/// A decoded image, stored as tightly packed RGBA8 data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub data: Box<[u8]>,
+}
+
+/// A single texture, along with all of its array layers and mipmaps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Texture {
+    /// Name of the texture
+    pub name: String,
+    /// Format of the texture data
+    pub format: SurfaceFormat,
+    /// Layout of the data in each channel
+    pub format_type: FormatType,
+    /// How the texture data is laid out in memory
+    pub tile_mode: TileMode,
+    /// Width of the base level, in pixels
+    pub width: u32,
+    /// Height of the base level, in pixels
+    pub height: u32,
+    /// Depth of the base level, for 3D textures
+    pub depth: u32,
+    /// Number of array layers (6 for cubemaps)
+    pub array_length: u32,
+    /// Number of mipmaps (including the base level)
+    pub mipmap_count: u16,
+    /// Block height of the base level, as a power of two number of GOBs
+    pub block_height_log2: u32,
+    /// Offset of each mipmap, relative to the start of each layer
+    pub mipmap_offsets: Box<[u64]>,
+    /// Raw texture data for all layers and mipmaps
+    pub data: Box<[u8]>,
+}
+
+impl Texture {
+    /// Size of a texture info block, in bytes.
+    pub const INFO_SIZE: usize = 0xA0;
+    /// Unique identifier for a texture info block.
+    pub const MAGIC: [u8; 4] = *b"BRTI";
+
+    /// Reads a texture info block (and its data) from the current position.
+    fn read(data: &mut DataCursor, limits: &ResourceLimits) -> Result<Self> {
+        let start = data.position()?;
+        let magic = data.read_exact::<4>()?;
+        ensure!(magic == Self::MAGIC, InvalidMagicSnafu { expected: Self::MAGIC });
+
+        data.set_position(start + 0x12)?;
+        let tile_mode = TileMode::from_primitive(data.read_u16()?);
+        let _swizzle = data.read_u16()?;
+        let mipmap_count = data.read_u16()?;
+        let _sample_count = data.read_u32()?;
+        let format = data.read_u32()?;
+        let _access_flags = data.read_u32()?;
+        let width = data.read_u32()?;
+        let height = data.read_u32()?;
+        let depth = data.read_u32()?;
+        let array_length = data.read_u32()?;
+        let block_height_log2 = data.read_u32()? & 7;
+
+        data.set_position(start + 0x50)?;
+        let image_size = data.read_u32()?;
+
+        data.set_position(start + 0x60)?;
+        let name_offset = data.read_u64()?;
+        let _parent_offset = data.read_u64()?;
+        let mipmap_table = data.read_u64()?;
+
+        ensure!(
+            mipmap_count > 0 && array_length > 0,
+            InvalidDataSnafu { position: start + 0x16, reason: "Texture Has No Surfaces" }
+        );
+
+        // Mipmap offsets are absolute, so make them relative to the first one
+        data.set_position(mipmap_table)?;
+        let mut mipmap_offsets = Vec::with_capacity(mipmap_count.into());
+        for _ in 0..mipmap_count {
+            mipmap_offsets.push(data.read_u64()?);
+        }
+        let base = mipmap_offsets[0];
+        ensure!(
+            mipmap_offsets.iter().all(|&offset| offset >= base && offset - base <= u64::from(image_size)),
+            InvalidDataSnafu { position: mipmap_table, reason: "Invalid Mipmap Offset" }
+        );
+
+        let name = read_name(data, name_offset)?;
+        limits.check_output_size(image_size.into())?;
+        let image_data = usize::try_from(base)
+            .ok()
+            .and_then(|base| data.get(base..base.checked_add(image_size as usize)?))
+            .context(InvalidDataSnafu { position: mipmap_table, reason: "Texture Data Out Of Bounds" })?;
+
+        Ok(Self {
+            name,
+            format: SurfaceFormat::from_primitive((format >> 8) as u8),
+            format_type: FormatType::from_primitive(format as u8),
+            tile_mode,
+            width,
+            height,
+            depth,
+            array_length,
+            mipmap_count,
+            block_height_log2,
+            mipmap_offsets: mipmap_offsets.iter().map(|offset| offset - base).collect(),
+            data: image_data.into(),
+        })
+    }
+
+    /// Returns the offset of a surface, relative to the start of the texture data.
+    #[inline]
+    fn surface_offset(&self, layer: u32, level: u32) -> u64 {
+        let layer_size = self.data.len() as u64 / u64::from(self.array_length);
+        u64::from(layer) * layer_size + self.mipmap_offsets[level as usize]
+    }
+
+    /// Returns the width and height of the given mipmap level, in pixels.
+    #[must_use]
+    #[inline]
+    pub fn mipmap_size(&self, level: u32) -> (u32, u32) {
+        ((self.width >> level).max(1), (self.height >> level).max(1))
+    }
+
+    /// Returns the data for a single surface, converted to row-major order. For compressed formats, each
+    /// element is a whole block.
+    ///
+    /// # Errors
+    /// Returns [`InvalidSurface`](Error::InvalidSurface) if the layer or mipmap doesn't exist,
+    /// [`UnsupportedFormat`](Error::UnsupportedFormat) if the format is unknown,
+    /// [`UnsupportedDepth`](Error::UnsupportedDepth) for 3D textures, or [`InvalidData`](Error::InvalidData)
+    /// if the texture data is too small.
+    pub fn deswizzle(&self, layer: u32, level: u32) -> Result<Box<[u8]>> {
+        ensure!(
+            layer < self.array_length && level < u32::from(self.mipmap_count),
+            InvalidSurfaceSnafu { layer, level }
+        );
+        ensure!(self.depth <= 1, UnsupportedDepthSnafu { depth: self.depth });
+        let (block_width, block_height, bytes) =
+            self.format.element_info().context(UnsupportedFormatSnafu { format: self.format })?;
+
+        let (width, height) = self.mipmap_size(level);
+        let (width, height) = (width.div_ceil(block_width), height.div_ceil(block_height));
+
+        let offset = self.surface_offset(layer, level);
+        let surface = &self.data[(offset as usize).min(self.data.len())..];
+
+        let too_small = InvalidDataSnafu { position: offset, reason: "Texture Data Too Small" };
+        match self.tile_mode {
+            TileMode::Optimal => {
+                let gobs = tegra::mip_block_height(height, 1 << self.block_height_log2);
+                tegra::deswizzle(surface, width, height, bytes, gobs).ok().context(too_small)
+            }
+            TileMode::Linear => {
+                let row = width as usize * bytes as usize;
+                let pitch = row.next_multiple_of(LINEAR_PITCH_ALIGNMENT);
+                let size = (height as usize).saturating_sub(1) * pitch + row;
+                let surface = surface.get(..size).context(too_small)?;
+                Ok(surface.chunks(pitch).flat_map(|line| &line[..row]).copied().collect())
+            }
+        }
+    }
+
+    /// Decodes a single surface into RGBA8 data. 3D textures aren't supported yet.
+    ///
+    /// BC4 and R8 are decoded to the red channel, and BC5 and R8G8 are decoded to the red and green channels.
+    ///
+    /// # Errors
+    /// Returns [`InvalidSurface`](Error::InvalidSurface) if the layer or mipmap doesn't exist,
+    /// [`UnsupportedFormat`](Error::UnsupportedFormat) if the format can't be decoded yet,
+    /// [`UnsupportedDepth`](Error::UnsupportedDepth) for 3D textures, or [`InvalidData`](Error::InvalidData)
+    /// if the texture data is too small.
+    pub fn decode(&self, layer: u32, level: u32) -> Result<Image> {
+        let raw = self.deswizzle(layer, level)?;
+        let (width, height) = self.mipmap_size(level);
+
+        let bc = |format| bc::decode(&raw, width, height, format).ok();
+        let data = match (self.format, self.format_type) {
+            (SurfaceFormat::R8, _) => Some(raw.iter().flat_map(|&r| [r, 0, 0, 0xFF]).collect()),
+            (SurfaceFormat::R8G8, _) => {
+                Some(raw.chunks_exact(2).flat_map(|rg| [rg[0], rg[1], 0, 0xFF]).collect())
+            }
+            (SurfaceFormat::R8G8B8A8, _) => Some(raw),
+            (SurfaceFormat::B8G8R8A8, _) => {
+                Some(raw.chunks_exact(4).flat_map(|bgra| [bgra[2], bgra[1], bgra[0], bgra[3]]).collect())
+            }
+            (SurfaceFormat::BC1, _) => bc(bc::Format::BC1),
+            (SurfaceFormat::BC2, _) => bc(bc::Format::BC2),
+            (SurfaceFormat::BC3, _) => bc(bc::Format::BC3),
+            (SurfaceFormat::BC4, FormatType::Unorm) => bc(bc::Format::BC4),
+            (SurfaceFormat::BC5, FormatType::Unorm) => bc(bc::Format::BC5),
+            (format, _) if format.is_astc() => {
+                let (block_width, block_height, _) = format.element_info().unwrap();
+                astc::decode(&raw, width, height, block_width, block_height).ok()
+            }
+            (format, _) => return UnsupportedFormatSnafu { format }.fail(),
+        };
+
+        // The deswizzled data is always the right size, so this should never fail
+        let data = data.context(InvalidDataSnafu {
+            position: self.surface_offset(layer, level),
+            reason: "Unable To Decode Texture",
+        })?;
+        Ok(Image { width, height, data })
+    }
+}
+
+/// Reads a name, stored as a `u16` length followed by the string itself.
+fn read_name(data: &mut DataCursor, offset: u64) -> Result<String> {
+    data.set_position(offset)?;
+    let length = data.read_u16()?;
+    let name = data.read_slice(length.into())?;
+    Ok(String::from_utf8_lossy(&name).into_owned())
+}
+
+/// Binary NX Texture container
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BNTX {
+    /// Version of the format
+    pub version: u32,
+    /// Name of the container
+    pub name: String,
+    /// All textures in the container
+    pub textures: Vec<Texture>,
+}
+
+impl BNTX {
+    /// Unique identifier for the data block.
+    pub const DATA_MAGIC: [u8; 4] = *b"BRTD";
+    /// Unique identifier that tells us if we're reading a BNTX file.
+    pub const MAGIC: [u8; 4] = *b"BNTX";
+    /// Identifier for the Switch platform.
+    pub const PLATFORM: [u8; 4] = *b"NX  ";
+
+    /// Reads a BNTX file from disk, see [`load`](Self::load) for more information.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn open<P: AsRef<Path>>(input: P) -> Result<Self> {
+        Self::open_with_limits(input, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Reads a BNTX file from disk, returning an error instead of reading a larger file, or more textures
+    /// or texture data than `limits` allow.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn open_with_limits<P: AsRef<Path>>(input: P, limits: &ResourceLimits) -> Result<Self> {
+        // Check the size before reading the whole file into memory
+        let input = input.as_ref();
+        limits.check_output_size(std::fs::metadata(input)?.len())?;
+        let data = std::fs::read(input)?;
+        Self::load_with_limits(data, limits)
+    }
+
+    /// Loads a BNTX file, and reads all of its textures.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if this isn't a BNTX file,
+    /// [`InvalidEndian`](Error::InvalidEndian) if the byte order mark is invalid, or
+    /// [`InvalidData`](Error::InvalidData) if any of the offsets are invalid.
+    #[inline]
+    pub fn load<I: Into<Box<[u8]>>>(input: I) -> Result<Self> {
+        Self::load_with_limits(input, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Loads a BNTX file the same way as [`load`](Self::load), returning an error instead of keeping a
+    /// larger file, or reading more textures or texture data than `limits` allow.
+    ///
+    /// Textures can share the same data, so `max_output_size` applies to the total size of every texture, as
+    /// well as to the file itself. BNTX files don't nest, so `max_depth` isn't used.
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the file exceeds `limits`, or any error from
+    /// [`load`](Self::load).
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_nintendoware::bntx::BNTX;
+    /// # use orthrus_nintendoware::error::Error;
+    /// let limits = ResourceLimits { max_output_size: 4, ..Default::default() };
+    /// let result = BNTX::load_with_limits(b"BNTX\0\0\0\0".as_slice(), &limits);
+    /// assert!(matches!(result, Err(Error::LimitExceeded { .. })));
+    /// ```
+    pub fn load_with_limits<I: Into<Box<[u8]>>>(input: I, limits: &ResourceLimits) -> Result<Self> {
+        let mut data = DataCursor::new(input, Endian::Little);
+        limits.check_output_size(data.len()?)?;
+
+        let magic = data.read_exact::<4>()?;
+        ensure!(magic == Self::MAGIC, InvalidMagicSnafu { expected: Self::MAGIC });
+
+        data.set_position(0x0C)?;
+        match data.read_exact::<2>()? {
+            [0xFF, 0xFE] => data.set_endian(Endian::Little),
+            [0xFE, 0xFF] => data.set_endian(Endian::Big),
+            endian => InvalidEndianSnafu { endian }.fail()?,
+        }
+
+        let _alignment = data.read_u16()?;
+        let name_offset = data.read_u32()?;
+
+        // The version comes before the byte order mark, so it can only be read now
+        data.set_position(0x08)?;
+        let version = data.read_u32()?;
+
+        data.set_position(0x20)?;
+        let platform = data.read_exact::<4>()?;
+        ensure!(
+            platform == Self::PLATFORM,
+            InvalidMagicSnafu { expected: Self::PLATFORM }
+        );
+        let texture_count = data.read_u32()?;
+        limits.check_entries(texture_count.into())?;
+        let texture_array = data.read_u64()?;
+        let data_block = data.read_u64()?;
+
+        data.set_position(data_block)?;
+        let magic = data.read_exact::<4>()?;
+        ensure!(
+            magic == Self::DATA_MAGIC,
+            InvalidMagicSnafu { expected: Self::DATA_MAGIC }
+        );
+
+        // The file name points directly at the string, rather than at its length
+        data.set_position(name_offset.into())?;
+        let mut name = Vec::new();
+        loop {
+            match data.read_u8()? {
+                0 => break,
+                byte => name.push(byte),
+            }
+        }
+        let name = String::from_utf8_lossy(&name).into_owned();
+
+        let mut textures = Vec::with_capacity(texture_count.min(0x1000) as usize);
+        let mut total_size = 0u64;
+        for index in 0..u64::from(texture_count) {
+            let position = texture_array
+                .checked_add(index * 8)
+                .context(InvalidDataSnafu { position: 0x28u64, reason: "Texture Array Out Of Bounds" })?;
+            data.set_position(position)?;
+            let offset = data.read_u64()?;
+            data.set_position(offset)?;
+            let texture = Texture::read(&mut data, limits)?;
+            total_size += texture.data.len() as u64;
+            limits.check_output_size(total_size)?;
+            textures.push(texture);
+        }
+
+        Ok(Self { version, name, textures })
+    }
+
+    /// Returns the generation of NintendoWare that built the container, or [`None`] if its version doesn't
+    /// match any known generation.
+    #[must_use]
+    #[inline]
+    pub const fn generation(&self) -> Option<Generation> {
+        Self::classify(self.version)
+    }
+
+    /// Returns the generation of NintendoWare that builds containers with this version, see the
+    /// [`generation`](crate::generation) module for the known versions.
+    #[inline]
+    const fn classify(version: u32) -> Option<Generation> {
+        match (version >> 24, (version >> 16) & 0xFF) {
+            (0, 3..=4) => Some(Generation::NX),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "identify")]
+impl FileIdentifier for BNTX {
+    fn identify(data: &[u8]) -> Option<FileInfo> {
+        if data.get(..4)? != Self::MAGIC || data.get(0x20..0x24)? != Self::PLATFORM {
+            return None;
+        }
+        let endian = match data.get(0x0C..0x0E)? {
+            [0xFF, 0xFE] => Endian::Little,
+            [0xFE, 0xFF] => Endian::Big,
+            _ => return None,
+        };
+        let mut cursor = DataCursorRef::new(data, endian);
+        cursor.set_position(0x08).ok()?;
+        let version = cursor.read_u32().ok()?;
+        cursor.set_position(0x24).ok()?;
+        let count = cursor.read_u32().ok()?;
+
+        let generation = crate::generation::describe(Self::classify(version));
+        let [major, minor, patch, build] = version.to_be_bytes();
+        let version = format!("v{major}.{minor}.{patch}.{build}");
+        let info = format!(
+            "NintendoWare NX Texture container (BNTX) {version} ({generation}), {count} texture{}",
+            if count == 1 { "" } else { "s" }
+        );
+        Some(FileInfo::new(info, None))
+    }
+}
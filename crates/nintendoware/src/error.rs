@@ -1,6 +1,9 @@
 use orthrus_core::prelude::*;
 use snafu::prelude::*;
 
+use crate::bntx::SurfaceFormat;
+use crate::switch::bwav::Codec;
+
 /// Error conditions for when working with NintendoWare files.
 #[derive(Debug, Snafu)]
 #[non_exhaustive]
@@ -34,6 +37,58 @@ pub enum Error {
     /// Thrown if unable to find a specific node in the tree.
     #[snafu(display("Node not found!"))]
     NodeNotFound,
+    /// Thrown if trying to give an item a name that can't be stored.
+    #[snafu(display("Invalid name {:?}, names can't be empty or contain a null!", name))]
+    InvalidName { name: String },
+    /// Thrown if trying to give an item a name that's already used by another item.
+    #[snafu(display("An item named {:?} already exists!", name))]
+    DuplicateName { name: String },
+    /// Thrown if trying to change a file that isn't stored the right way, such as replacing the data of a
+    /// file that's stored outside of the archive.
+    #[snafu(display("File {id} can't be changed this way!"))]
+    InvalidFileId { id: u32 },
+    /// Thrown if a new path doesn't fit in the space used by the old one.
+    #[snafu(display("Path {:?} is longer than the {} bytes available!", path, capacity))]
+    PathTooLong { path: String, capacity: usize },
+    /// Thrown if trying to access a texture layer or mipmap level that doesn't exist.
+    #[snafu(display("Texture has no layer {layer} with mipmap level {level}!"))]
+    InvalidSurface { layer: u32, level: u32 },
+    /// Thrown if a texture uses a format that can't be decoded yet.
+    #[snafu(display("Unsupported texture format {:?}!", format))]
+    UnsupportedFormat { format: SurfaceFormat },
+    /// Thrown if a texture has more than one depth slice, since 3D textures can't be decoded yet.
+    #[snafu(display("Unsupported texture depth {depth}, only 2D textures are supported!"))]
+    UnsupportedDepth { depth: u32 },
+    /// Thrown if audio uses a codec that can't be decoded yet.
+    #[snafu(display("Unsupported audio codec {codec}!"))]
+    UnsupportedCodec { codec: Codec },
+    /// Thrown if Opus audio can't be decoded.
+    #[cfg(feature = "opus")]
+    #[snafu(display("Unable to decode Opus audio: {source}"))]
+    Opus { source: audiopus::Error },
+    /// Thrown if a file is larger than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+    /// Thrown if an extracted file collides with another, see [`OverwritePolicy::Error`].
+    #[cfg(feature = "std")]
+    #[snafu(display("File already exists!"))]
+    AlreadyExists,
+    /// Thrown if extraction was cancelled, see [`Extraction::cancellation`].
+    #[cfg(feature = "std")]
+    #[snafu(display("Extraction was cancelled!"))]
+    Cancelled,
+    /// Thrown if any other filesystem error occurs.
+    #[cfg(feature = "std")]
+    #[snafu(display("Filesystem Error {source}"))]
+    FileError { source: std::io::Error },
+    /// Thrown if a stored file name can't safely be extracted, see [`PathOptions`].
+    #[cfg(feature = "std")]
+    #[snafu(display("{source}"))]
+    InvalidPath { source: PathError },
+    /// Thrown if metadata can't be written as JSON.
+    #[cfg(feature = "serde")]
+    #[snafu(display("Unable to write JSON: {source}"))]
+    Json { source: serde_json::Error },
 }
 pub(crate) type Result<T> = core::result::Result<T, Error>;
 
@@ -42,16 +97,31 @@ impl From<std::io::Error> for Error {
     #[inline]
     fn from(error: std::io::Error) -> Self {
         match error.kind() {
+            _ if Cancelled::is(&error) => Self::Cancelled,
             std::io::ErrorKind::NotFound => Self::NotFound,
             std::io::ErrorKind::UnexpectedEof => Self::EndOfFile,
             std::io::ErrorKind::PermissionDenied => Self::PermissionDenied,
-            kind => {
-                panic!("Unexpected std::io::error: {kind}! Something has gone horribly wrong")
-            }
+            std::io::ErrorKind::AlreadyExists => Self::AlreadyExists,
+            _ => Self::FileError { source: error },
         }
     }
 }
 
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Self::LimitExceeded { source }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<PathError> for Error {
+    #[inline]
+    fn from(source: PathError) -> Self {
+        Self::InvalidPath { source }
+    }
+}
+
 impl From<DataError> for Error {
     #[inline]
     fn from(error: DataError) -> Self {
@@ -0,0 +1,69 @@
+//! Maps the version numbers stored in NintendoWare files to the generation of the middleware that wrote them.
+//!
+//! Every console had its own release of NintendoWare, and tools written for one generation usually can't
+//! read files from another, even when the formats share a magic (such as BFSAR being used on both the Wii U
+//! and the Switch). The version in the header is the most reliable way to tell them apart, so each format
+//! checks its version against the ranges seen in retail files:
+//!
+//! | Format | Version         | Generation |
+//! |--------|-----------------|------------|
+//! | BCSAR  | 2.x             | NW4C       |
+//! | BFSAR  | 0.x (Wii U)     | NW4F       |
+//! | BFSAR  | 0.x (Switch)    | NX         |
+//! | BFRES  | 3.x, 4.x        | NW4F       |
+//! | BFRES  | 0.5 to 0.10     | NX         |
+//! | BNTX   | 0.3, 0.4        | NX         |
+//! | BARS   | 1.1, 1.2        | NX         |
+//! | BWAV   | 1               | NX         |
+//!
+//! Anything outside of these ranges is most likely damaged, or from a generation that isn't supported yet,
+//! so it's reported without a generation.
+//!
+//! # Usage
+//! ```
+//! use orthrus_nintendoware::prelude::*;
+//!
+//! let archive = SoundArchive::load(orthrus_tools::corpus::bfsar(0))?;
+//! assert_eq!(archive.generation(), Some(Generation::NW4F));
+//! assert_eq!(Generation::NW4F.to_string(), "NW4F");
+//! # Ok::<(), orthrus_nintendoware::error::Error>(())
+//! ```
+
+#[cfg(all(feature = "identify", not(feature = "std")))]
+use crate::no_std::*;
+
+/// Generation of NintendoWare that a file was written by, see the [module documentation](self) for more
+/// information.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Generation {
+    /// NintendoWare for Revolution, on the Wii
+    NW4R,
+    /// NintendoWare for CTR, on the 3DS
+    NW4C,
+    /// NintendoWare for Cafe, on the Wii U
+    NW4F,
+    /// NintendoWare for the Switch, which ships as part of the NintendoSDK
+    NX,
+}
+
+impl core::fmt::Display for Generation {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NW4R => write!(f, "NW4R"),
+            Self::NW4C => write!(f, "NW4C"),
+            Self::NW4F => write!(f, "NW4F"),
+            Self::NX => write!(f, "NX"),
+        }
+    }
+}
+
+/// Describes the generation for identification, or says that it's unknown if the version didn't match any.
+#[cfg(feature = "identify")]
+pub(crate) fn describe(generation: Option<Generation>) -> String {
+    match generation {
+        Some(generation) => format!("{generation}"),
+        None => "unknown generation".to_owned(),
+    }
+}
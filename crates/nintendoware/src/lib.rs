@@ -8,14 +8,23 @@
 mod no_std {
     extern crate alloc;
     pub use alloc::boxed::Box;
+    pub use alloc::string::String;
+    pub use alloc::vec::Vec;
     pub use alloc::{format, vec};
 }
 
 // All public modules
+pub mod bfres;
+pub mod bntx;
 pub mod error;
+pub mod generation;
 pub mod switch;
 
 // Prelude, for convenience
 pub mod prelude;
 
 pub mod rvl;
+
+// Dev-dependencies are only used by doctests, but are still passed to the unit test target
+#[cfg(test)]
+use orthrus_tools as _;
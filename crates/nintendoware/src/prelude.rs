@@ -5,14 +5,43 @@
 //! use orthrus_nintendoware::prelude::*;
 //! ```
 
+#[doc(inline)]
+pub use crate::generation::Generation;
+#[doc(inline)]
+pub use crate::switch::{Platform, SoundArchive};
+
 #[expect(non_snake_case)]
 pub mod Wii {
     #[doc(inline)]
     pub use crate::rvl::stream::StreamFile;
 }
 
+#[expect(non_snake_case)]
+pub mod ThreeDS {
+    #[doc(inline)]
+    pub use crate::switch::BCSAR;
+}
+
+#[expect(non_snake_case)]
+pub mod WiiU {
+    #[doc(inline)]
+    pub use crate::bfres::BFRES;
+    #[doc(inline)]
+    pub use crate::switch::BFSAR;
+}
+
 #[expect(non_snake_case)]
 pub mod Switch {
+    #[doc(inline)]
+    pub use crate::bfres::BFRES;
+    #[doc(inline)]
+    pub use crate::bntx::BNTX;
+    #[doc(inline)]
+    pub use crate::switch::bars::BARS;
+    #[doc(inline)]
+    pub use crate::switch::bwav::BWAV;
+    #[doc(inline)]
+    pub use crate::switch::opus::OpusStream;
     #[doc(inline)]
     pub use crate::switch::BFSAR;
 }
@@ -301,7 +301,7 @@ mod head_block {
                 InvalidDataSnafu { position: start_position, reason: "Unexpected Block Section" }
             );
             ensure!(
-                start_position == info.offset.into(),
+                start_position == u64::from(info.offset),
                 InvalidDataSnafu { position: start_position, reason: "Unexpected Block Alignment" }
             );
 
@@ -312,7 +312,7 @@ mod head_block {
             // Start of the Stream Info sub-block
             let position = data.position()?;
             ensure!(
-                position - start_position == header.stream_info.value.into(),
+                position - start_position == u64::from(header.stream_info.value),
                 InvalidDataSnafu { position, reason: "Unexpected Sub-Block Encountered" }
             );
             let stream_info = StreamInfo::new(data)?;
@@ -320,7 +320,7 @@ mod head_block {
             // Start of the Track Table sub-block
             let position = data.position()?;
             ensure!(
-                position - start_position == header.track_info.value.into(),
+                position - start_position == u64::from(header.track_info.value),
                 InvalidDataSnafu { position, reason: "Unexpected Sub-Block Encountered" }
             );
             let track_table = TrackTable::new(data, start_position)?;
@@ -328,7 +328,7 @@ mod head_block {
             // Start of the Channel Table sub-block
             let position = data.position()?;
             ensure!(
-                position - start_position == header.channel_info.value.into(),
+                position - start_position == u64::from(header.channel_info.value),
                 InvalidDataSnafu { position, reason: "Unexpected Sub-Block Encountered" }
             );
             let channel_table = ChannelTable::new(data, start_position)?;
@@ -0,0 +1,347 @@
+//! Adds support for the Audio Resource Set (BARS) archive, which newer Switch titles use to bundle their
+//! sounds in place of BFSAR sound archives.
+//!
+//! Each entry pairs an AMTA block, which holds the sound's metadata (such as its name and markers), with the
+//! sound itself, which is almost always a [`BWAV`].
+//!
+//! # Format
+//! All values use the byte order given by the byte order mark, which is always little endian on the Switch.
+//!
+//! | Offset | Field           | Type         | Notes                                                   |
+//! |--------|-----------------|--------------|---------------------------------------------------------|
+//! | 0x00   | Magic           | [u8; 4]      | Always "BARS"                                           |
+//! | 0x04   | File Size       | u32          | Size of the entire file                                 |
+//! | 0x08   | Byte Order Mark | u16          | 0xFEFF                                                  |
+//! | 0x0A   | Version         | u16          | 0x0101 or 0x0102                                        |
+//! | 0x0C   | Entry Count     | u32          | Number of entries in the archive                        |
+//! | 0x10   | Hash Table      | [u32]        | CRC32 of each entry's name, sorted in ascending order   |
+//! | ...    | Offset Table    | [(u32, u32)] | Pairs of absolute AMTA and asset offsets for each entry |
+//!
+//! Names are stored in a `STRG` section inside each AMTA block. Some newer versions of AMTA store them
+//! elsewhere, in which case the entry is named after its hash instead, such as `1A2B3C4D`.
+//!
+//! # Usage
+//! ```
+//! use orthrus_nintendoware::prelude::*;
+//!
+//! let bars = Switch::BARS::load(orthrus_tools::corpus::bars(3))?;
+//! for entry in bars.entries() {
+//!     let bwav = bars.bwav(entry)?;
+//!     println!("{}: {} channel(s) at {}Hz", entry.name, bwav.channels.len(), bwav.sample_rate());
+//! }
+//!
+//! // Entries are looked up using the hash of their name
+//! let entry = bars.find("file1").unwrap();
+//! assert_eq!(bars.bwav(entry)?.decode()?.len(), 1);
+//! # Ok::<(), orthrus_nintendoware::error::Error>(())
+//! ```
+//!
+//! [`BWAV`]: super::bwav::BWAV
+
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use orthrus_core::data::EndianExt;
+use orthrus_core::prelude::checksum::crc32;
+use orthrus_core::prelude::*;
+use snafu::prelude::*;
+
+use super::bwav::BWAV;
+use crate::error::*;
+use crate::generation::Generation;
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
+/// A single sound in a [`BARS`] archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Name of the sound, or its hash in hexadecimal if the name isn't stored
+    pub name: String,
+    /// CRC32 of the name
+    pub hash: u32,
+    metadata: Range<usize>,
+    asset: Range<usize>,
+}
+
+/// Audio Resource Set archive
+///
+/// See the [module documentation](self) for more information.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BARS {
+    /// Version of the format
+    pub version: u16,
+    entries: Vec<Entry>,
+    data: Box<[u8]>,
+}
+
+impl BARS {
+    /// Unique identifier for the metadata of each entry.
+    pub const AMTA_MAGIC: [u8; 4] = *b"AMTA";
+    /// Unique identifier that tells us if we're reading a BARS file.
+    pub const MAGIC: [u8; 4] = *b"BARS";
+    /// Unique identifier for the section containing an entry's name.
+    pub const STRG_MAGIC: [u8; 4] = *b"STRG";
+
+    /// Reads a BARS file from disk, see [`load`](Self::load) for more information.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn open<P: AsRef<Path>>(input: P) -> Result<Self> {
+        Self::open_with_limits(input, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Reads a BARS file from disk, returning an error instead of reading a larger file, or more entries or
+    /// entry data than `limits` allow.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn open_with_limits<P: AsRef<Path>>(input: P, limits: &ResourceLimits) -> Result<Self> {
+        // Check the size before reading the whole file into memory
+        let input = input.as_ref();
+        limits.check_output_size(std::fs::metadata(input)?.len())?;
+        let data = std::fs::read(input)?;
+        Self::load_with_limits(data, limits)
+    }
+
+    /// Reads the magic, byte order mark, version, and entry count.
+    fn read_header<T: ReadExt + SeekExt>(data: &mut T) -> Result<(u16, u32)> {
+        let magic = data.read_exact::<4>()?;
+        ensure!(magic == Self::MAGIC, InvalidMagicSnafu { expected: Self::MAGIC });
+
+        data.set_position(0x08)?;
+        match data.read_exact::<2>()? {
+            [0xFF, 0xFE] => data.set_endian(Endian::Little),
+            [0xFE, 0xFF] => data.set_endian(Endian::Big),
+            endian => InvalidEndianSnafu { endian }.fail()?,
+        }
+        let version = data.read_u16()?;
+        let count = data.read_u32()?;
+
+        ensure!(
+            0x10 + u64::from(count) * 12 <= data.len()?,
+            InvalidDataSnafu { position: 0x0Cu64, reason: "Entry Table Out Of Bounds" }
+        );
+        Ok((version, count))
+    }
+
+    /// Loads a BARS file, and reads the name and location of every entry.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if this isn't a BARS file,
+    /// [`InvalidEndian`](Error::InvalidEndian) if the byte order mark is invalid, or
+    /// [`InvalidData`](Error::InvalidData) if any of the offsets are invalid.
+    #[inline]
+    pub fn load<I: Into<Box<[u8]>>>(input: I) -> Result<Self> {
+        Self::load_with_limits(input, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Loads a BARS file the same way as [`load`](Self::load), returning an error instead of keeping a
+    /// larger file, or reading more entries than `limits` allow.
+    ///
+    /// Entries can share the same data, so `max_output_size` applies to the total size of every asset that
+    /// would be extracted, as well as to the file itself. BARS files don't nest, so `max_depth` isn't used.
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the file exceeds `limits`, or any error from
+    /// [`load`](Self::load).
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// # use orthrus_nintendoware::error::Error;
+    /// # use orthrus_nintendoware::switch::bars::BARS;
+    /// let mut data = b"BARS\0\0\0\0\xFF\xFE\x02\x01".to_vec();
+    /// data.extend_from_slice(&2u32.to_le_bytes());
+    /// data.resize(0x28, 0);
+    ///
+    /// let limits = ResourceLimits { max_entries: 1, ..Default::default() };
+    /// let result = BARS::load_with_limits(data, &limits);
+    /// assert!(matches!(result, Err(Error::LimitExceeded { .. })));
+    /// ```
+    pub fn load_with_limits<I: Into<Box<[u8]>>>(input: I, limits: &ResourceLimits) -> Result<Self> {
+        let mut data = DataCursor::new(input, Endian::Little);
+        limits.check_output_size(data.len()?)?;
+        let (version, count) = Self::read_header(&mut data)?;
+        limits.check_entries(count.into())?;
+
+        let mut hashes = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            hashes.push(data.read_u32()?);
+        }
+        let mut offsets = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            offsets.push((data.read_u32()? as usize, data.read_u32()? as usize));
+        }
+
+        // Sizes aren't stored, so each block ends where the next one starts
+        let length = data.len()? as usize;
+        let mut starts: Vec<usize> =
+            offsets.iter().flat_map(|&(metadata, asset)| [metadata, asset]).collect();
+        starts.sort_unstable();
+        let range = |start: usize| -> Result<Range<usize>> {
+            ensure!(
+                start < length,
+                InvalidDataSnafu { position: start as u64, reason: "Entry Out Of Bounds" }
+            );
+            let next = starts.partition_point(|&offset| offset <= start);
+            Ok(start..starts.get(next).copied().unwrap_or(length))
+        };
+
+        let endian = data.endian();
+        let mut entries = Vec::with_capacity(count as usize);
+        let mut total_size = 0u64;
+        for (hash, (metadata, asset)) in hashes.into_iter().zip(offsets) {
+            let metadata = range(metadata)?;
+            let name =
+                Self::read_name(&data[metadata.clone()], endian).unwrap_or_else(|| format!("{hash:08X}"));
+            let asset = range(asset)?;
+            total_size += asset.len() as u64;
+            limits.check_output_size(total_size)?;
+            entries.push(Entry { name, hash, metadata, asset });
+        }
+
+        Ok(Self { version, entries, data: data.into_inner() })
+    }
+
+    /// Reads the name from the `STRG` section of an AMTA block, if it has one.
+    fn read_name(metadata: &[u8], endian: Endian) -> Option<String> {
+        let mut data = DataCursorRef::new(metadata, endian);
+        if data.read_exact::<4>().ok()? != Self::AMTA_MAGIC {
+            return None;
+        }
+        data.set_position(0x18).ok()?;
+        let strings = data.read_u32().ok()?;
+
+        data.set_position(strings.into()).ok()?;
+        if data.read_exact::<4>().ok()? != Self::STRG_MAGIC {
+            return None;
+        }
+        let length = data.read_u32().ok()?;
+        let name = data.read_slice(length as usize).ok()?;
+        let name = name.split(|&byte| byte == 0).next().unwrap_or_default();
+        Some(String::from_utf8_lossy(name).into_owned())
+    }
+
+    /// Returns every entry, in the order they're stored.
+    #[must_use]
+    #[inline]
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Returns the generation of NintendoWare that built the archive, or [`None`] if its version doesn't
+    /// match any known generation.
+    #[must_use]
+    #[inline]
+    pub const fn generation(&self) -> Option<Generation> {
+        Self::classify(self.version)
+    }
+
+    /// Returns the generation of NintendoWare that builds archives with this version, see the
+    /// [`generation`](crate::generation) module for the known versions.
+    #[inline]
+    const fn classify(version: u16) -> Option<Generation> {
+        match version {
+            0x0101..=0x0102 => Some(Generation::NX),
+            _ => None,
+        }
+    }
+
+    /// Returns the entry with the given name, looking it up by its hash.
+    #[must_use]
+    #[inline]
+    pub fn find(&self, name: &str) -> Option<&Entry> {
+        let hash = crc32(name.as_bytes());
+        let index = self.entries.binary_search_by_key(&hash, |entry| entry.hash).ok()?;
+        self.entries.get(index)
+    }
+
+    /// Returns the raw AMTA metadata of an entry.
+    #[must_use]
+    #[inline]
+    pub fn metadata(&self, entry: &Entry) -> &[u8] {
+        &self.data[entry.metadata.clone()]
+    }
+
+    /// Returns the raw data of an entry, which is usually a [`BWAV`].
+    #[must_use]
+    #[inline]
+    pub fn asset(&self, entry: &Entry) -> &[u8] {
+        &self.data[entry.asset.clone()]
+    }
+
+    /// Loads the [`BWAV`] stored in an entry.
+    ///
+    /// # Errors
+    /// Returns any error from [`BWAV::load`].
+    #[inline]
+    pub fn bwav(&self, entry: &Entry) -> Result<BWAV> {
+        BWAV::load(self.asset(entry))
+    }
+
+    /// Saves the asset of every entry to disk, as `{name}.bwav`. Returns how many entries were saved.
+    ///
+    /// # Errors
+    /// Returns [`InvalidPath`](Error::InvalidPath) if an entry name is unsafe, or an error if unable to
+    /// create the necessary directories (see [`create_dir_all`](std::fs::create_dir_all)), or failing to
+    /// create a file to write to (see [`write`](std::fs::write)).
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn extract_all<P: AsRef<Path>>(&self, output: P) -> Result<usize> {
+        self.extract_all_with_options(output, &PathOptions::DEFAULT)
+    }
+
+    /// Saves the asset of every entry to disk, using `options` to control how entry names are sanitized.
+    ///
+    /// # Errors
+    /// Returns any error from [`extract_all`](Self::extract_all).
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn extract_all_with_options<P: AsRef<Path>>(
+        &self, output: P, options: &PathOptions,
+    ) -> Result<usize> {
+        self.extract_all_into(&Extraction::direct(output.as_ref()), options)
+    }
+
+    /// Saves the asset of every entry to wherever `extraction` puts them, such as a staging folder or nowhere
+    /// at all for a dry run. The [`Extraction`] still needs to be committed afterwards.
+    ///
+    /// # Errors
+    /// Returns any error from [`extract_all`](Self::extract_all).
+    #[cfg(feature = "std")]
+    pub fn extract_all_into(&self, extraction: &Extraction, options: &PathOptions) -> Result<usize> {
+        for entry in &self.entries {
+            let path = options.sanitize(&format!("{}.bwav", entry.name))?;
+            let Some(path) = extraction.prepare(path, self.asset(entry).len() as u64)? else {
+                continue;
+            };
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(path, self.asset(entry))?;
+        }
+        Ok(self.entries.len())
+    }
+}
+
+#[cfg(feature = "identify")]
+impl FileIdentifier for BARS {
+    fn identify(data: &[u8]) -> Option<FileInfo> {
+        let (version, count) = Self::read_header(&mut DataCursorRef::new(data, Endian::Little)).ok()?;
+        let info = format!(
+            "NintendoWare Audio Resource Set (BARS) v{}.{} ({}), {count} entr{}",
+            version >> 8,
+            version & 0xFF,
+            crate::generation::describe(Self::classify(version)),
+            if count == 1 { "y" } else { "ies" }
+        );
+        Some(FileInfo::new(info, None))
+    }
+
+    fn identify_deep(data: &[u8]) -> Option<FileInfo> {
+        let mut info = Self::identify(data)?;
+        let bars = Self::load(data).ok()?;
+        let sounds = bars.entries.iter().filter(|entry| bars.bwav(entry).is_ok()).count();
+        info.info += &format!(", {sounds} BWAV");
+        Some(info)
+    }
+}
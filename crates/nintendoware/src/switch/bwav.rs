@@ -0,0 +1,337 @@
+//! Adds support for the Binary Wave (BWAV) format, which newer Switch titles use for sound effects and
+//! streamed music in place of BFWAV/BFSTM. BWAV files are usually stored inside of a [`BARS`] archive.
+//!
+//! # Format
+//! All values use the byte order given by the byte order mark, which is always little endian on the Switch.
+//!
+//! ## Header
+//! | Offset | Field           | Type    | Notes                                        |
+//! |--------|-----------------|---------|----------------------------------------------|
+//! | 0x00   | Magic           | [u8; 4] | Always "BWAV"                                |
+//! | 0x04   | Byte Order Mark | u16     | 0xFEFF                                       |
+//! | 0x06   | Version         | u16     | Usually 1                                    |
+//! | 0x08   | Checksum        | u32     | CRC32 of the sample data                     |
+//! | 0x0C   | Prefetch        | u16     | Set if this only holds the start of a stream |
+//! | 0x0E   | Channel Count   | u16     | Number of channel info blocks that follow    |
+//!
+//! ## Channel Info
+//! Each channel has a 0x4C-byte info block, starting at 0x10.
+//!
+//! | Offset | Field            | Type      | Notes                                                 |
+//! |--------|------------------|-----------|-------------------------------------------------------|
+//! | 0x00   | Codec            | u16       | See [`Codec`]                                         |
+//! | 0x02   | Pan              | u16       | 0 is left, 1 is right, 2 is center                    |
+//! | 0x04   | Sample Rate      | u32       |                                                       |
+//! | 0x08   | Sample Count     | u32       | Number of samples in the full stream                  |
+//! | 0x0C   | Stored Samples   | u32       | Number of samples in this file, less when prefetched  |
+//! | 0x10   | Coefficients     | [i16; 16] | DSP ADPCM coefficients                                |
+//! | 0x30   | Stream Offset    | u32       | Offset of the samples in the full stream              |
+//! | 0x34   | Data Offset      | u32       | Offset of the samples in this file                    |
+//! | 0x38   | Looping          | u32       | 1 if the sound loops                                  |
+//! | 0x3C   | Loop End         | u32       | 0xFFFFFFFF if the sound doesn't loop                  |
+//! | 0x40   | Loop Start       | u32       |                                                       |
+//! | 0x44   | Predictor/Scale  | u16       | Initial DSP ADPCM frame header                        |
+//! | 0x46   | History          | [i16; 2]  | Initial DSP ADPCM history samples                     |
+//!
+//! # Codecs
+//! Most files use 16-bit PCM or DSP ADPCM, which can both be decoded using [`decode`](BWAV::decode). Some
+//! newer titles store Opus data instead, which is detected but can't be decoded yet.
+//!
+//! # Usage
+//! ```no_run
+//! use orthrus_nintendoware::switch::bwav::BWAV;
+//!
+//! let bwav = BWAV::open("BGM_Title.bwav")?;
+//! for samples in bwav.decode()? {
+//!     println!("{} samples at {}Hz", samples.len(), bwav.sample_rate());
+//! }
+//! # Ok::<(), orthrus_nintendoware::error::Error>(())
+//! ```
+//!
+//! [`BARS`]: super::bars::BARS
+
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use num_enum::FromPrimitive;
+use orthrus_core::prelude::*;
+use snafu::prelude::*;
+
+use crate::error::*;
+use crate::generation::Generation;
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
+/// How the samples of a [`Channel`] are encoded.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, FromPrimitive)]
+#[repr(u16)]
+pub enum Codec {
+    /// Signed 16-bit PCM
+    Pcm16 = 0,
+    /// Nintendo's GameCube DSP ADPCM
+    DspAdpcm = 1,
+    /// Opus, used by some newer titles
+    Opus = 2,
+    #[num_enum(catch_all)]
+    Unknown(u16),
+}
+
+impl core::fmt::Display for Codec {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Pcm16 => write!(f, "PCM16"),
+            Self::DspAdpcm => write!(f, "DSP ADPCM"),
+            Self::Opus => write!(f, "Opus"),
+            Self::Unknown(codec) => write!(f, "Unknown codec {codec:#X}"),
+        }
+    }
+}
+
+/// A single channel of a [`BWAV`], along with the information needed to decode it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Channel {
+    /// How the samples are encoded
+    pub codec: Codec,
+    /// Which speaker the channel plays from
+    pub pan: u16,
+    /// Number of samples per second
+    pub sample_rate: u32,
+    /// Number of samples in the full stream
+    pub sample_count: u32,
+    /// Number of samples stored in this file, which is less than `sample_count` for prefetch files
+    pub stored_sample_count: u32,
+    /// Start and end sample of the loop, if the sound loops
+    pub loop_points: Option<(u32, u32)>,
+    /// DSP ADPCM coefficients, as eight pairs
+    pub coefficients: [i16; 16],
+    /// Initial DSP ADPCM history samples
+    pub history: [i16; 2],
+    /// Offset of the samples, relative to the start of the file
+    data_offset: u32,
+}
+
+impl Channel {
+    /// Size of a channel info block, in bytes.
+    pub const INFO_SIZE: u64 = 0x4C;
+    /// Number of samples in each 8-byte DSP ADPCM frame.
+    const SAMPLES_PER_FRAME: usize = 14;
+
+    /// Reads a channel info block from the current position.
+    fn read<T: ReadExt>(data: &mut T) -> Result<Self> {
+        let codec = Codec::from_primitive(data.read_u16()?);
+        let pan = data.read_u16()?;
+        let sample_rate = data.read_u32()?;
+        let sample_count = data.read_u32()?;
+        let stored_sample_count = data.read_u32()?;
+        let mut coefficients = [0; 16];
+        for coefficient in &mut coefficients {
+            *coefficient = data.read_i16()?;
+        }
+        let _stream_offset = data.read_u32()?;
+        let data_offset = data.read_u32()?;
+        let looping = data.read_u32()?;
+        let loop_end = data.read_u32()?;
+        let loop_start = data.read_u32()?;
+        let _predictor_scale = data.read_u16()?;
+        let history = [data.read_i16()?, data.read_i16()?];
+
+        Ok(Self {
+            codec,
+            pan,
+            sample_rate,
+            sample_count,
+            stored_sample_count,
+            loop_points: (looping == 1 && loop_end != u32::MAX).then_some((loop_start, loop_end)),
+            coefficients,
+            history,
+            data_offset,
+        })
+    }
+
+    /// Returns the size of the samples stored in this file, in bytes.
+    fn data_size(&self) -> Result<usize> {
+        let samples = self.stored_sample_count as usize;
+        match self.codec {
+            Codec::Pcm16 => Ok(samples * 2),
+            Codec::DspAdpcm => Ok(samples.div_ceil(Self::SAMPLES_PER_FRAME) * 8),
+            codec => UnsupportedCodecSnafu { codec }.fail(),
+        }
+    }
+
+    /// Decodes DSP ADPCM frames into `samples` 16-bit samples.
+    fn decode_dsp(&self, data: &[u8], samples: usize) -> Vec<i16> {
+        let mut output = Vec::with_capacity(samples);
+        let [mut history1, mut history2] = self.history.map(i32::from);
+        for frame in data.chunks(8) {
+            let predictor = usize::from((frame[0] >> 4) & 0x7);
+            let scale = 1i32 << (frame[0] & 0xF);
+            let (coefficient1, coefficient2) = (
+                i32::from(self.coefficients[predictor * 2]),
+                i32::from(self.coefficients[predictor * 2 + 1]),
+            );
+            for &byte in &frame[1..] {
+                for nibble in [byte >> 4, byte & 0xF] {
+                    if output.len() == samples {
+                        return output;
+                    }
+                    // Sign-extend the nibble
+                    let nibble = i32::from((nibble << 4) as i8 >> 4);
+                    let sample =
+                        ((nibble * scale) << 11) + 1024 + coefficient1 * history1 + coefficient2 * history2;
+                    let sample = (sample >> 11).clamp(i16::MIN.into(), i16::MAX.into());
+                    history2 = history1;
+                    history1 = sample;
+                    output.push(sample as i16);
+                }
+            }
+        }
+        output
+    }
+}
+
+/// Binary Wave file
+///
+/// See the [module documentation](self) for more information.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BWAV {
+    /// Version of the format
+    pub version: u16,
+    /// Whether this only holds the start of a stream, with the rest stored elsewhere
+    pub is_prefetch: bool,
+    /// Every channel, in playback order
+    pub channels: Vec<Channel>,
+    endian: Endian,
+    data: Box<[u8]>,
+}
+
+impl BWAV {
+    /// Unique identifier that tells us if we're reading a BWAV file.
+    pub const MAGIC: [u8; 4] = *b"BWAV";
+
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn open<P: AsRef<Path>>(input: P) -> Result<Self> {
+        let data = std::fs::read(input)?;
+        Self::load(data)
+    }
+
+    /// Loads a BWAV file, and reads the information for all of its channels.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if this isn't a BWAV file,
+    /// [`InvalidEndian`](Error::InvalidEndian) if the byte order mark is invalid, or
+    /// [`EndOfFile`](Error::EndOfFile) if the channel info is truncated.
+    pub fn load<I: Into<Box<[u8]>>>(input: I) -> Result<Self> {
+        let mut data = DataCursor::new(input, Endian::Little);
+        let bwav = Self::read_info(&mut data)?;
+        Ok(Self { data: data.into_inner(), ..bwav })
+    }
+
+    /// Reads the header and every channel info block, without keeping the sample data.
+    fn read_info<T: ReadExt>(data: &mut T) -> Result<Self> {
+        let magic = data.read_exact::<4>()?;
+        ensure!(magic == Self::MAGIC, InvalidMagicSnafu { expected: Self::MAGIC });
+
+        let endian = match data.read_exact::<2>()? {
+            [0xFF, 0xFE] => Endian::Little,
+            [0xFE, 0xFF] => Endian::Big,
+            endian => InvalidEndianSnafu { endian }.fail()?,
+        };
+        data.set_endian(endian);
+
+        let version = data.read_u16()?;
+        let _checksum = data.read_u32()?;
+        let is_prefetch = data.read_u16()? == 1;
+        let channel_count = data.read_u16()?;
+
+        let mut channels = Vec::with_capacity(channel_count.into());
+        for _ in 0..channel_count {
+            channels.push(Channel::read(data)?);
+        }
+
+        Ok(Self { version, is_prefetch, channels, endian, data: Box::default() })
+    }
+
+    /// Returns the [`Codec`] of the first channel, since every channel uses the same one in practice.
+    #[must_use]
+    #[inline]
+    pub fn codec(&self) -> Option<Codec> {
+        self.channels.first().map(|channel| channel.codec)
+    }
+
+    /// Returns the sample rate of the first channel, or 0 if there aren't any channels.
+    #[must_use]
+    #[inline]
+    pub fn sample_rate(&self) -> u32 {
+        self.channels.first().map_or(0, |channel| channel.sample_rate)
+    }
+
+    /// Returns the generation of NintendoWare that built the file, or [`None`] if its version doesn't match
+    /// any known generation, see the [`generation`](crate::generation) module for the known versions.
+    #[must_use]
+    #[inline]
+    pub const fn generation(&self) -> Option<Generation> {
+        match self.version {
+            1 => Some(Generation::NX),
+            _ => None,
+        }
+    }
+
+    /// Decodes one of this file's [`channels`](Self::channels) into 16-bit PCM samples. Prefetch files only
+    /// contain the start of the stream, so only [`stored_sample_count`](Channel::stored_sample_count) samples
+    /// are returned.
+    ///
+    /// # Errors
+    /// Returns [`UnsupportedCodec`](Error::UnsupportedCodec) if the channel isn't PCM16 or DSP ADPCM, or
+    /// [`InvalidData`](Error::InvalidData) if the samples are out of bounds.
+    pub fn decode_channel(&self, channel: &Channel) -> Result<Vec<i16>> {
+        let start = channel.data_offset as usize;
+        let data = self
+            .data
+            .get(start..start + channel.data_size()?)
+            .context(InvalidDataSnafu { position: start as u64, reason: "Sample Data Out Of Bounds" })?;
+
+        let samples = match channel.codec {
+            Codec::Pcm16 => data
+                .chunks_exact(2)
+                .map(|sample| match self.endian {
+                    Endian::Little => i16::from_le_bytes([sample[0], sample[1]]),
+                    Endian::Big => i16::from_be_bytes([sample[0], sample[1]]),
+                })
+                .collect(),
+            _ => channel.decode_dsp(data, channel.stored_sample_count as usize),
+        };
+        Ok(samples)
+    }
+
+    /// Decodes every channel into 16-bit PCM samples, see [`decode_channel`](Self::decode_channel).
+    ///
+    /// # Errors
+    /// Returns any error from [`decode_channel`](Self::decode_channel).
+    #[inline]
+    pub fn decode(&self) -> Result<Vec<Vec<i16>>> {
+        self.channels.iter().map(|channel| self.decode_channel(channel)).collect()
+    }
+}
+
+#[cfg(feature = "identify")]
+impl FileIdentifier for BWAV {
+    fn identify(data: &[u8]) -> Option<FileInfo> {
+        let bwav = Self::read_info(&mut DataCursorRef::new(data, Endian::Little)).ok()?;
+        let channel = bwav.channels.first()?;
+        let mut info = format!(
+            "NintendoWare Binary Wave (BWAV) v{} ({}), {} channel{}, {}, {}Hz",
+            bwav.version,
+            crate::generation::describe(bwav.generation()),
+            bwav.channels.len(),
+            if bwav.channels.len() == 1 { "" } else { "s" },
+            channel.codec,
+            channel.sample_rate
+        );
+        if bwav.is_prefetch {
+            info += ", prefetch";
+        }
+        Some(FileInfo::new(info, None))
+    }
+}
@@ -0,0 +1,1965 @@
+//! Sound archives (BCSAR on 3DS, BFSAR on Wii U and Switch), which hold the metadata for every sound in a
+//! game.
+//!
+//! All three platforms share the same layout, which started on the 3DS and was carried over to the Wii U and
+//! then the Switch, so they're read by the same [`SoundArchive`] type. The [`Platform`] is decided by the
+//! magic and byte order: 3DS archives use `CSAR` and are always little endian, while `FSAR` archives are big
+//! endian on the Wii U and little endian on the Switch.
+//!
+//! Archives with a damaged byte order mark can still be read with
+//! [`load_with_endian`](SoundArchive::load_with_endian), where [`guess_endian`](SoundArchive::guess_endian)
+//! can find the byte order from the rest of the header.
+//!
+//! Newer Switch titles replaced sound archives with [`BARS`](bars::BARS) archives of [`BWAV`](bwav::BWAV)
+//! files, which are read by the [`bars`] and [`bwav`] modules. Voice lines and music are often stored as
+//! [Opus streams](opus::OpusStream) instead.
+//!
+//! # Renaming
+//! Every item in an archive is named in its string block, which also holds a Patricia tree that the game uses
+//! to look items up by name. [`SoundArchive::rename`] changes the name of an item, and
+//! [`SoundArchive::to_bytes`] writes the archive back out with a new string table and a tree rebuilt from
+//! every name, so the game can find the item using its new name.
+//!
+//! ```no_run
+//! use orthrus_nintendoware::prelude::*;
+//!
+//! let mut archive = SoundArchive::open("Sound.bfsar")?;
+//! archive.rename("SE_PLAYER_JUMP", "SE_PLAYER_JUMP_OLD")?;
+//! archive.rename("SE_CUSTOM_JUMP", "SE_PLAYER_JUMP")?;
+//! std::fs::write("Sound_renamed.bfsar", archive.to_bytes()?)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! # Rebuilding
+//! Files stored inside the archive, such as wave archives, can be swapped out using
+//! [`SoundArchive::replace_file`], and the paths of files stored next to it, such as streams, can be changed
+//! using [`SoundArchive::set_external_path`]. Both use the file's ID, which is its index in the info block.
+//!
+//! When writing, the file block is laid out again in the same order, with every file aligned to 0x20 bytes,
+//! and the info block is updated to point at where each file ended up. The structure of the archive stays
+//! the same, so sounds, banks, and groups can't be added or removed. Every block keeps its alignment, since
+//! anything after a block that changed size is moved by a multiple of 0x20 bytes.
+//!
+//! Groups that are stored inside the archive hold their own copy of each file they load, which isn't
+//! updated, so a replaced file also needs to be replaced in any group that contains it.
+//!
+//! ```no_run
+//! use orthrus_nintendoware::prelude::*;
+//!
+//! let mut archive = SoundArchive::open("Sound.bfsar")?;
+//! archive.replace_file(12, std::fs::read("WARC_PLAYER.bfwar")?)?;
+//! std::fs::write("Sound_rebuilt.bfsar", archive.to_bytes()?)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! # JSON
+//! With the `serde` feature, [`SoundArchive::to_json`] writes the archive's `platform`, `version`, and
+//! [`generation`](crate::generation), along with the metadata of every sound, so that other tools can use it
+//! without parsing the archive themselves. Each sound includes its `name` (if it has one), the IDs of its
+//! file and player, its volume, panning, and 3D settings, and its `details`, which describe the tracks and
+//! loop points of streamed sounds.
+//!
+//! ```no_run
+//! # #[cfg(feature = "serde")]
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use orthrus_nintendoware::prelude::*;
+//!
+//! let archive = SoundArchive::open("Sound.bfsar")?;
+//! std::fs::write("Sound.json", archive.to_json()?)?;
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "serde"))]
+//! # fn main() {}
+//! ```
+#![allow(dead_code)] //Tell rust to shut up
+
+pub mod bars;
+pub mod bwav;
+pub mod opus;
+
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use bitflags::bitflags;
+use num_enum::FromPrimitive;
+use orthrus_core::data::EndianExt;
+use orthrus_core::prelude::*;
+use snafu::prelude::*;
+
+use crate::error::*;
+use crate::generation::Generation;
+
+trait Read {
+    fn read<T: ReadExt + SeekExt>(data: &mut T) -> Result<Self>
+    where
+        Self: Sized;
+}
+
+struct Identifier;
+
+#[rustfmt::skip]
+impl Identifier {
+    const STRING: u16 = 0x1F01;
+
+    const STRING_BLOCK: u16 = 0x2000;
+    const INFO_BLOCK: u16 = 0x2001;
+    const FILE_BLOCK: u16 = 0x2002;
+
+    const SOUND_INFO_SECTION: u16 = 0x2100;
+    const BANK_INFO_SECTION: u16 = 0x2101;
+    const PLAYER_INFO_SECTION: u16 = 0x2102;
+    const WAVE_ARCHIVE_INFO_SECTION: u16 = 0x2103;
+    const SOUND_GROUP_INFO_SECTION: u16 = 0x2104;
+    const GROUP_INFO_SECTION: u16 = 0x2105;
+    const FILE_INFO_SECTION: u16 = 0x2106;
+
+    const SOUND_INFO: u16 = 0x2200;
+    const STREAM_SOUND_INFO: u16 = 0x2201;
+    const WAVE_SOUND_INFO: u16 = 0x2202;
+    const SEQUENCE_SOUND_INFO: u16 = 0x2203;
+
+    const FILE_INFO: u16 = 0x220A;
+    const SOUND_ARCHIVE_PLAYER_INFO: u16 = 0x220B;
+    const INTERNAL_FILE_INFO: u16 = 0x220C;
+    const EXTERNAL_FILE_INFO: u16 = 0x220D;
+
+    const STREAM_TRACK_INFO: u16 = 0x220E;
+
+    const STRING_TABLE: u16 = 0x2400;
+    const PATRICIA_TREE: u16 = 0x2401;
+}
+
+//-------------------------------------------------------------------------------------------------
+
+// TODO: merge with Endian in orthrus_core::data
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ByteOrderMark(u16);
+
+#[expect(non_upper_case_globals)]
+impl ByteOrderMark {
+    pub const Big: Self = Self(0xFEFF);
+    pub const Little: Self = Self(0xFFFE);
+}
+
+impl Default for ByteOrderMark {
+    #[cfg(target_endian = "little")]
+    #[inline]
+    fn default() -> Self {
+        Self::Little
+    }
+
+    #[cfg(target_endian = "big")]
+    #[inline]
+    fn default() -> Self {
+        Self::Big
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Version {
+    pub major: u8,
+    pub minor: u8,
+    pub patch: u8,
+}
+
+impl Read for Version {
+    fn read<T: ReadExt>(data: &mut T) -> Result<Self> {
+        // Stored as a single integer, so the byte order matters for little endian archives
+        let version = data.read_u32()?;
+        //The lowest byte should always be zero, but I'm not going to enforce an assert here
+        Ok(Self {
+            major: (version >> 24) as u8,
+            minor: (version >> 16) as u8,
+            patch: (version >> 8) as u8,
+        })
+    }
+}
+
+impl core::fmt::Display for Version {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "v{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+
+/// Which console a [`SoundArchive`] was built for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Platform {
+    /// 3DS (BCSAR), which is always little endian
+    Ctr,
+    /// Wii U (BFSAR), which is big endian
+    #[default]
+    Cafe,
+    /// Switch (BFSAR), which is little endian
+    NX,
+}
+
+impl Platform {
+    /// Returns the magic used by sound archives for this platform.
+    #[must_use]
+    #[inline]
+    pub const fn magic(self) -> [u8; 4] {
+        match self {
+            Self::Ctr => SoundArchive::CTR_MAGIC,
+            Self::Cafe | Self::NX => SoundArchive::MAGIC,
+        }
+    }
+
+    /// Returns the byte order used by sound archives for this platform.
+    #[must_use]
+    #[inline]
+    pub const fn endian(self) -> Endian {
+        match self {
+            Self::Cafe => Endian::Big,
+            Self::Ctr | Self::NX => Endian::Little,
+        }
+    }
+}
+
+impl core::fmt::Display for Platform {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Ctr => write!(f, "3DS"),
+            Self::Cafe => write!(f, "Wii U"),
+            Self::NX => write!(f, "Switch"),
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Default, PartialEq)]
+struct BinaryHeader {
+    magic: [u8; 4],
+    byte_order: ByteOrderMark,
+    size: u16,
+    version: Version,
+    file_size: u32,
+    num_sections: u16,
+    //padding: [u8; 2]
+}
+
+impl Read for BinaryHeader {
+    #[inline]
+    fn read<T: ReadExt + SeekExt>(data: &mut T) -> Result<Self> {
+        Self::read_with_endian(data, None)
+    }
+}
+
+impl BinaryHeader {
+    /// Reads the header, using `endian` instead of the Byte Order Mark if it's provided.
+    fn read_with_endian<T: ReadExt + SeekExt>(data: &mut T, endian: Option<Endian>) -> Result<Self> {
+        // Create a header, so we can copy in its magic
+        let mut header = Self::default();
+
+        // Read in the magic
+        data.read_length(&mut header.magic)?;
+
+        // Read the Byte Order Mark and use it to update our endianness, unless it's been overridden
+        header.byte_order = ByteOrderMark(data.read_u16()?);
+        let endian = match (endian, header.byte_order) {
+            (Some(endian), _) => endian,
+            (None, ByteOrderMark::Little) => Endian::Little,
+            (None, ByteOrderMark::Big) => Endian::Big,
+            (None, _) => {
+                InvalidDataSnafu { position: data.position()? - 2, reason: "Invalid Byte Order Mark" }
+                    .fail()?
+            }
+        };
+        data.set_endian(endian);
+
+        //Read the rest of the data
+        header.size = data.read_u16()?;
+        header.version = Version::read(data)?;
+        header.file_size = data.read_u32()?;
+        header.num_sections = data.read_u16()?;
+        data.read_u16()?; // Skip alignment
+
+        Ok(header)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+
+#[derive(Default, Debug, PartialEq)]
+struct SizedReference {
+    identifier: u16,
+    //padding: [u8; 2]
+    offset: u32,
+    size: u32,
+}
+
+impl Read for SizedReference {
+    fn read<T: ReadExt>(data: &mut T) -> Result<Self> {
+        let identifier = data.read_u16()?;
+        data.read_u16()?;
+
+        let offset = data.read_u32()?;
+        let size = data.read_u32()?;
+
+        Ok(Self { identifier, offset, size })
+    }
+}
+
+#[derive(Default, Debug, PartialEq)]
+struct Reference {
+    identifier: u16,
+    //padding: [u8; 2]
+    offset: u32,
+}
+
+impl Read for Reference {
+    fn read<T: ReadExt>(data: &mut T) -> Result<Self> {
+        let identifier = data.read_u16()?;
+        data.read_u16()?;
+
+        let offset = data.read_u32()?;
+
+        Ok(Self { identifier, offset })
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+
+#[derive(Default, Debug, PartialEq)]
+struct SectionHeader {
+    magic: [u8; 4],
+    size: u32,
+}
+
+impl Read for SectionHeader {
+    fn read<T: ReadExt>(data: &mut T) -> Result<Self> {
+        let mut header = SectionHeader::default();
+        data.read_length(&mut header.magic)?;
+        header.size = data.read_u32()?;
+        Ok(header)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+
+#[derive(Debug, PartialEq)]
+struct Table<V: Read> {
+    _marker: PhantomData<V>,
+}
+
+impl<V: Read> Table<V> {
+    fn read<T: ReadExt + SeekExt>(data: &mut T) -> Result<Vec<V>> {
+        Self::read_with_limits(data, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Reads a table, making sure the count is sane before we allocate for it.
+    fn read_with_limits<T: ReadExt + SeekExt>(data: &mut T, limits: &ResourceLimits) -> Result<Vec<V>> {
+        let count = data.read_u32()?;
+        limits.check_entries(count.into())?;
+
+        let mut values = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            values.push(V::read(data)?);
+        }
+
+        Ok(values)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+
+#[derive(Debug, PartialEq)]
+struct PatriciaNode {
+    flags: u16,
+    search_index: u16,
+    left_index: u32,
+    right_index: u32,
+    string_id: u32,
+    item_id: u32,
+}
+
+impl Read for PatriciaNode {
+    fn read<T: ReadExt>(data: &mut T) -> Result<Self> {
+        Ok(Self {
+            flags: data.read_u16()?,
+            search_index: data.read_u16()?,
+            left_index: data.read_u32()?,
+            right_index: data.read_u32()?,
+            string_id: data.read_u32()?,
+            item_id: data.read_u32()?,
+        })
+    }
+}
+
+impl Default for PatriciaNode {
+    fn default() -> Self {
+        Self {
+            flags: 0,
+            search_index: 0xFFFF,
+            left_index: 0xFFFFFFFF,
+            right_index: 0xFFFFFFFF,
+            string_id: 0xFFFFFFFF,
+            item_id: 0xFFFFFFFF,
+        }
+    }
+}
+
+#[derive(Default, Debug, PartialEq)]
+struct PatriciaTree {
+    root_index: u32,
+    nodes: Vec<PatriciaNode>,
+}
+
+impl PatriciaTree {
+    /// Size of each node when written.
+    const NODE_SIZE: usize = 0x14;
+
+    /// Returns whether the bit at `index` is set, counting from the highest bit of the first byte, where
+    /// anything past the end of the string is treated as zero.
+    #[inline]
+    fn bit(string: &[u8], index: usize) -> bool {
+        string.get(index >> 3).is_some_and(|byte| byte & (0x80 >> (index & 7)) != 0)
+    }
+
+    /// Follows the tree the same way the game does, returning the leaf that `string` would be stored in.
+    /// The leaf still needs to be checked against the string table, since only some bits are compared.
+    fn get_node(&self, string: &str) -> Result<&PatriciaNode> {
+        let mut node = self.nodes.get(self.root_index as usize).ok_or(Error::NodeNotFound)?;
+        let bytes = string.as_bytes();
+
+        // Loop as long as we haven't hit a leaf node, but never more than once per node in case of a cycle
+        for _ in 0..self.nodes.len() {
+            if (node.flags & 1) != 0 {
+                return Ok(node);
+            }
+            let node_index = match Self::bit(bytes, usize::from(node.search_index)) {
+                true => node.right_index as usize,
+                false => node.left_index as usize,
+            };
+            node = self.nodes.get(node_index).ok_or(Error::NodeNotFound)?;
+        }
+
+        Err(Error::NodeNotFound)
+    }
+
+    /// Builds a new tree from every string and the item it belongs to, as `(string, string_id, item_id)`.
+    fn build(mut leaves: Vec<(&[u8], u32, u32)>) -> Self {
+        // Once sorted, the first bit that differs between the first and last strings is where they split
+        leaves.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        let mut tree = Self { root_index: u32::MAX, nodes: Vec::with_capacity(leaves.len() * 2) };
+        if !leaves.is_empty() {
+            tree.root_index = tree.insert(&leaves);
+        }
+        tree
+    }
+
+    fn insert(&mut self, leaves: &[(&[u8], u32, u32)]) -> u32 {
+        let index = self.nodes.len() as u32;
+        let (first, last) = (leaves[0], leaves[leaves.len() - 1]);
+        if leaves.len() == 1 {
+            self.nodes.push(PatriciaNode {
+                flags: 1,
+                string_id: first.1,
+                item_id: first.2,
+                ..Default::default()
+            });
+            return index;
+        }
+
+        // Strings can't contain a null, so two different strings always differ somewhere
+        let length = first.0.len().max(last.0.len()) * 8;
+        let bit =
+            (0..length).find(|&bit| Self::bit(first.0, bit) != Self::bit(last.0, bit)).unwrap_or(length);
+        let split = leaves.partition_point(|leaf| !Self::bit(leaf.0, bit));
+
+        self.nodes.push(PatriciaNode { search_index: bit as u16, ..Default::default() });
+        let left_index = self.insert(&leaves[..split]);
+        let right_index = self.insert(&leaves[split..]);
+        let node = &mut self.nodes[index as usize];
+        node.left_index = left_index;
+        node.right_index = right_index;
+        index
+    }
+}
+
+impl PatriciaTree {
+    fn read<T: ReadExt + SeekExt>(data: &mut T, limits: &ResourceLimits) -> Result<Self> {
+        // First, get the root index
+        let root_index = data.read_u32()?;
+
+        // Then, we can load in the node table
+        let nodes = Table::read_with_limits(data, limits)?;
+
+        Ok(Self { root_index, nodes })
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct SendValue {
+    main_send: u8,
+    fx_send: [u8; 3],
+}
+
+impl Read for SendValue {
+    fn read<T: ReadExt>(data: &mut T) -> Result<Self> {
+        Ok(Self {
+            main_send: data.read_u8()?,
+            fx_send: [data.read_u8()?, data.read_u8()?, data.read_u8()?],
+        })
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct StreamSoundExtension {
+    stream_type_info: u32,
+    loop_start_frame: u32,
+    loop_end_frame: u32,
+    temp_position: u64,
+}
+
+impl Read for StreamSoundExtension {
+    fn read<T: ReadExt + SeekExt>(data: &mut T) -> Result<Self> {
+        Ok(Self {
+            stream_type_info: data.read_u32()?,
+            loop_start_frame: data.read_u32()?,
+            loop_end_frame: data.read_u32()?,
+            temp_position: data.position()? - 8,
+        })
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct StreamTrackInfo {
+    volume: u8,
+    pan: u8,
+    span: u8,
+    flags: u8,
+    lpf_freq: u8,
+    biquad_type: u8,
+    biquad_value: u8,
+    //padding: [u8; 1]
+    global_channel_indices: Vec<u8>,
+    send_value: SendValue,
+}
+
+impl Read for StreamTrackInfo {
+    fn read<T: ReadExt + SeekExt>(data: &mut T) -> Result<Self> {
+        // Save our relative position
+        let offset = data.position()?;
+
+        let volume = data.read_u8()?;
+        let pan = data.read_u8()?;
+        let span = data.read_u8()?;
+        let flags = data.read_u8()?;
+
+        let global_channel_ref = Reference::read(data)?;
+        let send_value_ref = Reference::read(data)?;
+
+        let lpf_freq = data.read_u8()?;
+        let biquad_type = data.read_u8()?;
+        let biquad_value = data.read_u8()?;
+        data.read_u8()?;
+
+        data.set_position(offset + u64::from(global_channel_ref.offset))?;
+        // This is a raw type so I just do this manually instead of calling Table::read
+        let index_count = data.read_u32()?;
+        let mut global_channel_indices = Vec::with_capacity(index_count as usize);
+        for _ in 0..index_count {
+            global_channel_indices.push(data.read_u8()?);
+        }
+
+        // Now we need to align, and theoretically that's where send_value is
+        let position = data.position()?;
+        data.set_position((position + 3) & !3)?;
+
+        data.set_position(offset + u64::from(send_value_ref.offset))?;
+        let send_value = SendValue::read(data)?;
+
+        Ok(Self {
+            volume,
+            pan,
+            span,
+            flags,
+            lpf_freq,
+            biquad_type,
+            biquad_value,
+            global_channel_indices,
+            send_value,
+        })
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct StreamSoundInfo {
+    // Stored as allocated track flags on the 3DS
+    valid_tracks: u16,
+    channel_count: u16,
+    pitch: f32,
+    prefetch_id: u32,
+    tracks: Vec<StreamTrackInfo>,
+    send_value: SendValue,
+    extension: StreamSoundExtension,
+}
+
+impl Read for StreamSoundInfo {
+    fn read<T: ReadExt + SeekExt>(data: &mut T) -> Result<Self> {
+        // Save relative position
+        let offset = data.position()?;
+
+        let valid_tracks = data.read_u16()?;
+        let channel_count = data.read_u16()?;
+
+        // Reference to TrackInfoTable
+        let track_info_ref = Reference::read(data)?;
+        let pitch = data.read_f32()?;
+
+        let send_value_ref = Reference::read(data)?;
+        let extension_ref = Reference::read(data)?;
+
+        let prefetch_id = data.read_u32()?;
+
+        // Get the TrackInfo, which is a reference table to a bunch of StreamTrackInfos
+        let track_table: Vec<Reference> = Table::read(data)?;
+
+        data.set_position(offset + u64::from(track_info_ref.offset))?;
+        // Pre-allocate and read all tracks in
+        let mut tracks = Vec::with_capacity(track_table.len());
+        for reference in &track_table {
+            match reference.identifier {
+                Identifier::STREAM_TRACK_INFO => {
+                    tracks.push(StreamTrackInfo::read(data)?);
+                }
+                _ => InvalidDataSnafu {
+                    position: data.position()?,
+                    reason: "Unexpected Track Info Reference!",
+                }
+                .fail()?,
+            }
+        }
+
+        data.set_position(offset + u64::from(send_value_ref.offset))?;
+        let send_value = SendValue::read(data)?;
+
+        data.set_position(offset + u64::from(extension_ref.offset))?;
+        let extension = StreamSoundExtension::read(data)?;
+
+        Ok(Self {
+            valid_tracks,
+            channel_count,
+            pitch,
+            prefetch_id,
+            tracks,
+            send_value,
+            extension,
+        })
+    }
+}
+
+impl StreamSoundInfo {
+    /// Reads the stream info from a 3DS archive, which only stores which tracks and how many channels are
+    /// used.
+    fn read_ctr<T: ReadExt>(data: &mut T) -> Result<Self> {
+        let valid_tracks = data.read_u32()? as u16;
+        let channel_count = data.read_u16()?;
+        Ok(Self { valid_tracks, channel_count, pitch: 1.0, ..Default::default() })
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
+enum SoundDetails {
+    Stream(StreamSoundInfo),
+    Wave,
+    Sequence,
+    #[default]
+    None,
+}
+
+bitflags! {
+    struct OptionFlags: u32 {
+        const StringId = 1 << 0;
+        const PanParams = 1 << 1;
+        const PlayerParams = 1 << 2;
+        const SinglePlayParams = 1 << 3;
+
+        const Sound3DOffset = 1 << 8;
+
+        const RVLParamOffset = 1 << 16;
+        const CTRParamOffset = 1 << 17;
+        const CAFEParamOffset = 1 << 18;
+
+        const UserParam = 1 << 31;
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u8)]
+enum PanMode {
+    #[default]
+    /// Treat stereo as two separate tracks
+    Dual,
+    /// Treat stereo as one balanced track
+    Balance,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u8)]
+enum PanCurve {
+    #[default]
+    /// Square Root Curve, -3 dB center, 0 dB ends
+    Sqrt,
+    /// Square Root Curve, 0 dB center, 3 dB ends
+    Sqrt0,
+    /// Square Root Curve, 0 dB center, 0dB ends
+    Sqrt0Clamp,
+    /// Sin/Cos Curve, -3 dB center, 0 dB ends
+    SinCos,
+    /// Sin/Cos Curve, 0 dB center, 3 dB ends
+    SinCos0,
+    /// Sin/Cos Curve, 0 dB center, 0 dB ends
+    SinCos0Clamp,
+    /// Linear Curve, -6 dB center, 0 dB ends
+    Linear,
+    /// Linear Curve, 0 dB center, 6 dB ends
+    Linear0,
+    /// Linear Curve, 0 dB center, 0 dB ends
+    Linear0Clamp,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u8)]
+enum PlayType {
+    #[default]
+    None,
+    Oldest,
+    OldestDuration,
+    Newest,
+    NewestDuration,
+}
+
+bitflags! {
+    #[derive(Default, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+    pub struct Sound3DFlags: u32 {
+        const Volume = 1 << 0;
+        const Priority = 1 << 1;
+        const Pan = 1 << 2;
+        const SurroundPan = 1 << 3;
+        const Filter = 1 << 4;
+    }
+}
+
+#[derive(Default, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct Sound3DInfo {
+    flags: Sound3DFlags,
+    decay_ratio: f32,
+    decay_curve: u8,
+    doppler_factor: u8,
+    //padding: [u8; 2]
+    options: u32,
+}
+
+impl Read for Sound3DInfo {
+    fn read<T: ReadExt>(data: &mut T) -> Result<Self> {
+        let flags = Sound3DFlags::from_bits_truncate(data.read_u32()?);
+        let decay_ratio = data.read_f32()?;
+        let decay_curve = data.read_u8()?;
+        let doppler_factor = data.read_u8()?;
+        data.read_u16()?;
+        let options = data.read_u32()?;
+        Ok(Self { flags, decay_ratio, decay_curve, doppler_factor, options })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct SoundInfo {
+    file_id: u32,
+    player_id: u32,
+    volume: u8,
+    filter: u8,
+    //padding: [u8; 2]
+    //SoundDetailsRef
+    options: u32,
+
+    string_id: u32,
+    pan_mode: PanMode,
+    pan_curve: PanCurve,
+    player_prio: u8,
+    player_actor_id: u8,
+    play_type: PlayType,
+    play_duration: u16,
+    is_front_bypass: bool,
+    user_param: u32,
+    virtual_info: Sound3DInfo,
+    details: SoundDetails,
+}
+
+impl Default for SoundInfo {
+    fn default() -> Self {
+        Self {
+            file_id: 0,
+            player_id: 0,
+            volume: 0,
+            filter: 0,
+            details: SoundDetails::None,
+            options: 0,
+
+            string_id: 0xFFFFFFFF,
+            pan_mode: PanMode::default(),
+            pan_curve: PanCurve::default(),
+            player_prio: 64,
+            player_actor_id: 0,
+            play_type: PlayType::default(),
+            play_duration: 0xFFFF,
+            is_front_bypass: false,
+            user_param: 0xFFFFFFFF,
+            virtual_info: Sound3DInfo::default(),
+        }
+    }
+}
+
+impl SoundInfo {
+    /// Checks if a specific bit is set, based on bit position (0-31)
+    fn get_value(&self, bit_pos: u32) -> Option<u64> {
+        // Check that we didn't accidentally go past bounds
+        if bit_pos > 31 {
+            return None;
+        }
+
+        // Check if the bit is even set
+        if (self.options & (1 << bit_pos)) == 0 {
+            return None;
+        }
+
+        // If it is, count the number of bits set before it (i.e. what set bit is it)
+        let mut count: u64 = 0;
+        for n in 0..bit_pos {
+            if (self.options & (1 << n)) != 0 {
+                count += 1;
+            }
+        }
+
+        Some(count * core::mem::size_of::<u32>() as u64)
+    }
+
+    fn read_string_id<T: ReadExt + SeekExt>(&mut self, data: &mut T, position: u64) -> Option<u32> {
+        // If the bit is set, get its data
+        if let Some(offset) = self.get_value(0) {
+            data.set_position(offset + position).unwrap();
+
+            let value = data.read_u32().ok().unwrap();
+            self.string_id = value;
+        }
+
+        Some(self.string_id)
+    }
+
+    fn read_pan_mode<T: ReadExt + SeekExt>(&mut self, data: &mut T, position: u64) -> Option<PanMode> {
+        // If the bit is set, get its data
+        if let Some(offset) = self.get_value(1) {
+            data.set_position(offset + position).unwrap();
+
+            let value = data.read_u32().ok().unwrap();
+            self.pan_mode = PanMode::from((value & 0xFF) as u8);
+        }
+
+        Some(self.pan_mode)
+    }
+
+    fn read_pan_curve<T: ReadExt + SeekExt>(&mut self, data: &mut T, position: u64) -> Option<PanCurve> {
+        // If the bit is set, get its data
+        if let Some(offset) = self.get_value(1) {
+            data.set_position(offset + position).unwrap();
+
+            let value = data.read_u32().ok().unwrap();
+            self.pan_curve = PanCurve::from(((value >> 8) & 0xFF) as u8);
+        }
+
+        Some(self.pan_curve)
+    }
+
+    fn read_player_prio<T: ReadExt + SeekExt>(&mut self, data: &mut T, position: u64) -> Option<u8> {
+        // If the bit is set, get its data
+        if let Some(offset) = self.get_value(2) {
+            data.set_position(offset + position).unwrap();
+
+            let value = data.read_u32().ok().unwrap();
+            self.player_prio = (value & 0xFF) as u8;
+        }
+
+        Some(self.player_prio)
+    }
+
+    fn read_player_actor_id<T: ReadExt + SeekExt>(&mut self, data: &mut T, position: u64) -> Option<u8> {
+        // If the bit is set, get its data
+        if let Some(offset) = self.get_value(2) {
+            data.set_position(offset + position).unwrap();
+
+            let value = data.read_u32().ok().unwrap();
+            self.player_actor_id = ((value >> 8) & 0xFF) as u8;
+        }
+
+        Some(self.player_actor_id)
+    }
+
+    fn read_play_type<T: ReadExt + SeekExt>(&mut self, data: &mut T, position: u64) -> Option<PlayType> {
+        // If the bit is set, get its data
+        if let Some(offset) = self.get_value(3) {
+            data.set_position(offset + position).unwrap();
+
+            let value = data.read_u32().ok().unwrap();
+            self.play_type = PlayType::from((value & 0xFF) as u8);
+        }
+
+        Some(self.play_type)
+    }
+
+    fn read_play_duration<T: ReadExt + SeekExt>(&mut self, data: &mut T, position: u64) -> Option<u16> {
+        // If the bit is set, get its data
+        if let Some(offset) = self.get_value(3) {
+            data.set_position(offset + position).unwrap();
+
+            let value = data.read_u32().ok().unwrap();
+            self.play_duration = ((value >> 16) & 0xFFFF) as u16;
+        }
+
+        Some(self.play_duration)
+    }
+
+    /// Returns an offset to Sound3DInfo parameters.
+    fn get_3d_info_offset<T: ReadExt + SeekExt>(&mut self, data: &mut T, position: u64) -> Option<u32> {
+        let mut value = None;
+        // If the bit is set, get its data
+        if let Some(offset) = self.get_value(8) {
+            data.set_position(offset + position).unwrap();
+
+            value = data.read_u32().ok();
+        }
+
+        value
+    }
+
+    fn is_front_bypass<T: ReadExt + SeekExt>(&mut self, data: &mut T, position: u64) -> Option<bool> {
+        // If the bit is set, get its data
+        if let Some(offset) = self.get_value(17) {
+            data.set_position(offset + position).unwrap();
+
+            let value = data.read_u32().ok().unwrap();
+            self.is_front_bypass = (value & 1) == 1;
+        }
+
+        Some(self.is_front_bypass)
+    }
+
+    fn read_user_param<T: ReadExt + SeekExt>(&mut self, data: &mut T, position: u64) -> Option<u32> {
+        // If the bit is set, get its data
+        if let Some(offset) = self.get_value(31) {
+            data.set_position(offset + position).unwrap();
+
+            let value = data.read_u32().ok().unwrap();
+            self.user_param = value;
+        }
+
+        Some(self.user_param)
+    }
+}
+
+impl SoundInfo {
+    fn read<T: ReadExt + SeekExt>(data: &mut T, platform: Platform) -> Result<Self> {
+        let readback = data.position()?;
+
+        let file_id = data.read_u32()?;
+        let player_id = data.read_u32()?;
+        let volume = data.read_u8()?;
+        let filter = data.read_u8()?;
+        data.read_u16()?;
+
+        // Reference to SoundDetails
+        let details_ref = Reference::read(data)?;
+        let options = data.read_u32()?;
+
+        let mut info = Self { file_id, player_id, volume, filter, options, ..Default::default() };
+
+        let position = data.position()?;
+
+        info.read_string_id(data, position);
+        info.read_pan_mode(data, position);
+        info.read_pan_curve(data, position);
+        info.read_player_prio(data, position);
+        info.read_player_actor_id(data, position);
+        info.read_play_type(data, position);
+        info.read_play_duration(data, position);
+
+        if let Some(offset) = info.get_3d_info_offset(data, position) {
+            data.set_position(readback + u64::from(offset))?;
+            info.virtual_info = Sound3DInfo::read(data)?;
+        }
+
+        info.is_front_bypass(data, position);
+        info.read_user_param(data, position);
+
+        data.set_position(readback + u64::from(details_ref.offset))?;
+        info.details = match details_ref.identifier {
+            Identifier::STREAM_SOUND_INFO => SoundDetails::Stream(match platform {
+                Platform::Ctr => StreamSoundInfo::read_ctr(data)?,
+                Platform::Cafe | Platform::NX => StreamSoundInfo::read(data)?,
+            }),
+            Identifier::WAVE_SOUND_INFO => SoundDetails::Wave,
+            Identifier::SEQUENCE_SOUND_INFO => SoundDetails::Sequence,
+            _ => SoundDetails::None,
+        };
+
+        Ok(info)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+
+/// Every string in a String Block, including their null terminators. With the `arena` feature, they're all
+/// stored in one buffer.
+#[cfg(not(feature = "arena"))]
+type Strings = Vec<String>;
+/// Every string in a String Block, including their null terminators. With the `arena` feature, they're all
+/// stored in one buffer.
+#[cfg(feature = "arena")]
+type Strings = orthrus_core::arena::StrArena;
+
+#[derive(Default, Debug, PartialEq)]
+struct StringBlock {
+    table: Strings,
+    tree: PatriciaTree,
+}
+
+impl StringBlock {
+    /// Unique identifier that tells us if we're reading a String Block.
+    pub const MAGIC: [u8; 4] = *b"STRG";
+
+    fn read_string_table<T: ReadExt + SeekExt>(data: &mut T, limits: &ResourceLimits) -> Result<Strings> {
+        // Store relative position
+        let offset = data.position()?;
+
+        // Read in the reference table
+        let references: Vec<SizedReference> = Table::read_with_limits(data, limits)?;
+
+        // Then we can process all strings, pre-allocate since we know the count ahead of time
+        #[cfg(not(feature = "arena"))]
+        let mut strings = Strings::with_capacity(references.len());
+        #[cfg(feature = "arena")]
+        let mut strings = Strings::with_capacity(
+            references.len(),
+            references.iter().map(|reference| reference.size as usize).sum(),
+        );
+        for reference in &references {
+            match reference.identifier {
+                Identifier::STRING => {
+                    // Go to that position in the string blob
+                    data.set_position(offset + u64::from(reference.offset))?;
+
+                    // Read the string and store it, includes the trailing \0
+                    let string = data.read_slice(reference.size as usize)?;
+                    let string = core::str::from_utf8(&string).map_err(|source| {
+                        DataError::InvalidString { source: Utf8ErrorSource::Slice { source } }
+                    })?;
+                    #[cfg(not(feature = "arena"))]
+                    strings.push(string.to_owned());
+                    #[cfg(feature = "arena")]
+                    strings.push(string);
+                }
+                _ => InvalidDataSnafu { position: data.position()?, reason: "Unexpected String Identifier!" }
+                    .fail()?,
+            }
+        }
+
+        Ok(strings)
+    }
+
+    /// Writes the block with a newly built Patricia tree, padded to `size_hint` plus any multiple of 0x20 so
+    /// everything after it keeps the same alignment.
+    fn to_bytes(&self, endian: Endian, size_hint: u32) -> Vec<u8> {
+        let u16_bytes = |value: u16| match endian {
+            Endian::Little => value.to_le_bytes(),
+            Endian::Big => value.to_be_bytes(),
+        };
+        let u32_bytes = |value: u32| match endian {
+            Endian::Little => value.to_le_bytes(),
+            Endian::Big => value.to_be_bytes(),
+        };
+
+        // Every string that has an item keeps it, and strings without one aren't added to the tree
+        let items: Vec<_> = self
+            .tree
+            .nodes
+            .iter()
+            .filter(|node| (node.flags & 1) != 0)
+            .map(|node| (node.string_id, node.item_id))
+            .collect();
+        let leaves = items
+            .iter()
+            .filter_map(|&(string_id, item_id)| {
+                let string = self.table.get(string_id as usize)?.trim_end_matches('\0');
+                Some((string.as_bytes(), string_id, item_id))
+            })
+            .collect();
+        let tree = PatriciaTree::build(leaves);
+
+        // The string table is a list of references to each string, followed by the strings themselves
+        let mut table = u32_bytes(self.table.len() as u32).to_vec();
+        let mut offset = 4 + self.table.len() * 12;
+        for string in self.table.iter() {
+            table.extend_from_slice(&u16_bytes(Identifier::STRING));
+            table.extend_from_slice(&[0, 0]);
+            table.extend_from_slice(&u32_bytes(offset as u32));
+            table.extend_from_slice(&u32_bytes(string.len() as u32));
+            offset += string.len();
+        }
+        for string in self.table.iter() {
+            table.extend_from_slice(string.as_bytes());
+        }
+        table.resize((table.len() + 3) & !3, 0);
+
+        let mut body =
+            Vec::with_capacity(0x10 + table.len() + 8 + tree.nodes.len() * PatriciaTree::NODE_SIZE);
+        for (identifier, offset) in [
+            (Identifier::STRING_TABLE, 0x10),
+            (Identifier::PATRICIA_TREE, 0x10 + table.len()),
+        ] {
+            body.extend_from_slice(&u16_bytes(identifier));
+            body.extend_from_slice(&[0, 0]);
+            body.extend_from_slice(&u32_bytes(offset as u32));
+        }
+        body.extend(table);
+        body.extend_from_slice(&u32_bytes(tree.root_index));
+        body.extend_from_slice(&u32_bytes(tree.nodes.len() as u32));
+        for node in &tree.nodes {
+            body.extend_from_slice(&u16_bytes(node.flags));
+            body.extend_from_slice(&u16_bytes(node.search_index));
+            // Only leaves point at a string and item, internal nodes always store 0xFFFFFFFF instead
+            let (string_id, item_id) = match node.flags & 1 {
+                0 => (u32::MAX, u32::MAX),
+                _ => (node.string_id, node.item_id),
+            };
+            for value in [node.left_index, node.right_index, string_id, item_id] {
+                body.extend_from_slice(&u32_bytes(value));
+            }
+        }
+
+        let length = 8 + body.len() as u32;
+        let size = length + (size_hint.wrapping_sub(length) & 0x1F);
+        let mut block = Self::MAGIC.to_vec();
+        block.extend_from_slice(&u32_bytes(size));
+        block.extend(body);
+        block.resize(size as usize, 0);
+        block
+    }
+}
+
+impl StringBlock {
+    fn read<T: ReadExt + SeekExt>(data: &mut T, limits: &ResourceLimits) -> Result<Self> {
+        // Read the header and make sure we're actually reading a String Block
+        let header = SectionHeader::read(data)?;
+        ensure!(
+            header.magic == Self::MAGIC,
+            InvalidMagicSnafu { expected: Self::MAGIC }
+        );
+
+        // Store the relative position for all offsets
+        let offset = data.position()?;
+
+        // Read both sections
+        let mut sections: [Reference; 2] = Default::default();
+
+        for section in &mut sections {
+            *section = Reference::read(data)?;
+        }
+
+        // Then process each section
+        let mut strings = Self::default();
+
+        for section in &mut sections {
+            data.set_position(offset + u64::from(section.offset))?;
+            match section.identifier {
+                Identifier::STRING_TABLE => {
+                    strings.table = Self::read_string_table(data, limits)?;
+                }
+                Identifier::PATRICIA_TREE => {
+                    strings.tree = PatriciaTree::read(data, limits)?;
+                }
+                _ => InvalidDataSnafu {
+                    position: data.position()?,
+                    reason: "Unexpected String Block Identifier!",
+                }
+                .fail()?,
+            }
+        }
+
+        Ok(strings)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+
+/// Where the data of a file is stored, along with the position of its info in the archive so it can be
+/// updated when writing.
+#[derive(Debug, PartialEq)]
+enum FileLocation {
+    /// Stored in the file block, at an offset relative to the start of the block's body
+    Internal {
+        position: u64,
+        offset: u32,
+        size: u32,
+        replacement: Option<Box<[u8]>>,
+    },
+    /// Stored in a separate file, with how many bytes are available for its path (without the null)
+    External {
+        position: u64,
+        path: String,
+        capacity: usize,
+    },
+    /// Only stored in a group outside of the archive
+    None,
+}
+
+#[derive(Default, Debug, PartialEq)]
+struct InfoBlock {
+    sounds: Vec<SoundInfo>,
+    files: Vec<FileLocation>,
+}
+
+impl InfoBlock {
+    /// Unique identifier that tells us if we're reading an Info Block.
+    pub const MAGIC: [u8; 4] = *b"INFO";
+
+    fn read<T: ReadExt + SeekExt>(data: &mut T, platform: Platform, limits: &ResourceLimits) -> Result<Self> {
+        let _header = SectionHeader::read(data)?;
+
+        // Store relative position
+        let offset = data.position()?;
+
+        let mut info = Self::default();
+
+        // Read all references
+        let mut sections: [Reference; 8] = Default::default();
+        for section in &mut sections {
+            *section = Reference::read(data)?;
+        }
+
+        for section in &mut sections {
+            data.set_position(offset + u64::from(section.offset))?;
+            match section.identifier {
+                Identifier::SOUND_INFO_SECTION => {
+                    // Sound Info
+                    // Load the reference table
+                    let references: Vec<Reference> = Table::read_with_limits(data, limits)?;
+
+                    // Pre-allocate the array with the number of entries
+                    info.sounds = Vec::with_capacity(references.len());
+
+                    for reference in &references {
+                        match reference.identifier {
+                            Identifier::SOUND_INFO => {
+                                data.set_position(offset + u64::from(section.offset + reference.offset))?;
+                                let sound_info = SoundInfo::read(data, platform)?;
+                                info.sounds.push(sound_info);
+                            }
+                            _ => InvalidDataSnafu {
+                                position: data.position()?,
+                                reason: "Unexpected Sound Info Identifier!",
+                            }
+                            .fail()?,
+                        }
+                    }
+                }
+                Identifier::BANK_INFO_SECTION => {}
+                Identifier::PLAYER_INFO_SECTION => {}
+                Identifier::WAVE_ARCHIVE_INFO_SECTION => {}
+                Identifier::SOUND_GROUP_INFO_SECTION => {}
+                Identifier::GROUP_INFO_SECTION => {}
+                Identifier::FILE_INFO_SECTION => {
+                    let references: Vec<Reference> = Table::read_with_limits(data, limits)?;
+                    info.files = Vec::with_capacity(references.len());
+
+                    // Keep a placeholder for anything unexpected, so every file keeps the same ID
+                    for reference in &references {
+                        let location = match reference.identifier {
+                            Identifier::FILE_INFO => {
+                                let start = offset + u64::from(section.offset + reference.offset);
+                                data.set_position(start)?;
+                                Self::read_file_location(data, start)?
+                            }
+                            _ => FileLocation::None,
+                        };
+                        info.files.push(location);
+                    }
+                }
+                Identifier::SOUND_ARCHIVE_PLAYER_INFO => {}
+                _ => InvalidDataSnafu {
+                    position: data.position()?,
+                    reason: "Unexpected Info Section Identifier!",
+                }
+                .fail()?,
+            }
+        }
+
+        Ok(info)
+    }
+
+    fn read_file_location<T: ReadExt + SeekExt>(data: &mut T, start: u64) -> Result<FileLocation> {
+        // Paths are never this long, so anything without a null before this is damaged
+        const MAX_PATH_LENGTH: usize = 0x400;
+
+        let location = Reference::read(data)?;
+        let position = start + u64::from(location.offset);
+        data.set_position(position)?;
+        match location.identifier {
+            Identifier::INTERNAL_FILE_INFO => {
+                let reference = SizedReference::read(data)?;
+                Ok(match reference.offset {
+                    u32::MAX => FileLocation::None,
+                    offset => {
+                        FileLocation::Internal { position, offset, size: reference.size, replacement: None }
+                    }
+                })
+            }
+            Identifier::EXTERNAL_FILE_INFO => {
+                let mut path = Vec::new();
+                loop {
+                    match data.read_u8()? {
+                        0 => break,
+                        byte => path.push(byte),
+                    }
+                    ensure!(
+                        path.len() < MAX_PATH_LENGTH,
+                        InvalidDataSnafu { position, reason: "External file path is too long!" }
+                    );
+                }
+                let path = String::from_utf8(path).map_err(|_| Error::InvalidUtf8)?;
+                // Paths are padded to a multiple of four bytes, which can all be used for a longer path
+                let capacity = ((path.len() + 4) & !3) - 1;
+                Ok(FileLocation::External { position, path, capacity })
+            }
+            _ => Ok(FileLocation::None),
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+
+#[derive(Default, Debug, PartialEq)]
+struct FileBlock {
+    header: SectionHeader,
+}
+
+impl FileBlock {
+    /// Unique identifier that tells us if we're reading a File Block.
+    pub const MAGIC: [u8; 4] = *b"FILE";
+}
+
+//-------------------------------------------------------------------------------------------------
+
+/// Sound archive for any supported platform, see the [module documentation](self) for more information.
+#[derive(Default, Debug, PartialEq)]
+pub struct SoundArchive {
+    platform: Platform,
+    header: BinaryHeader,
+    sections: [SizedReference; 3],
+    strings: StringBlock,
+    info: InfoBlock,
+    files: FileBlock,
+    /// Original file, which everything other than the string block is copied from when writing
+    data: Box<[u8]>,
+    endian: Endian,
+}
+
+/// Binary caFe Sound ARchive, used on the Wii U and Switch
+pub type BFSAR = SoundArchive;
+
+/// Binary Ctr Sound ARchive, used on the 3DS
+pub type BCSAR = SoundArchive;
+
+impl SoundArchive {
+    /// Unique identifier that tells us if we're reading a Wii U or Switch Sound Archive.
+    pub const MAGIC: [u8; 4] = *b"FSAR";
+
+    /// Unique identifier that tells us if we're reading a 3DS Sound Archive.
+    pub const CTR_MAGIC: [u8; 4] = *b"CSAR";
+
+    #[inline]
+    fn read_header<T: ReadExt + SeekExt>(
+        data: &mut T, endian: Option<Endian>,
+    ) -> Result<(BinaryHeader, Platform)> {
+        // Read the header
+        let header = BinaryHeader::read_with_endian(data, endian)?;
+
+        //Now we need to verify that it's what we actually expected, which also tells us the platform
+        let platform = match (header.magic, data.endian()) {
+            (Self::CTR_MAGIC, Endian::Little) => Platform::Ctr,
+            (Self::CTR_MAGIC, Endian::Big) => {
+                InvalidDataSnafu { position: 4u64, reason: "3DS Sound Archives must be little endian!" }
+                    .fail()?
+            }
+            (Self::MAGIC, Endian::Big) => Platform::Cafe,
+            (Self::MAGIC, Endian::Little) => Platform::NX,
+            _ => InvalidMagicSnafu { expected: Self::MAGIC }.fail()?,
+        };
+        ensure!(
+            header.size == 0x40,
+            InvalidDataSnafu { position: data.position()?, reason: "Header size must be 0x40!" }
+        );
+
+        ensure!(
+            data.len()? == u64::from(header.file_size),
+            InvalidDataSnafu { position: data.position()?, reason: "Unexpected file size!" }
+        );
+
+        ensure!(
+            header.num_sections == 3,
+            InvalidDataSnafu { position: data.position()?, reason: "Unexpected section count!" }
+        );
+
+        Ok((header, platform))
+    }
+
+    /// Guesses the byte order of a sound archive from its header size, which is always 0x40, for archives
+    /// with a damaged Byte Order Mark.
+    #[must_use]
+    #[inline]
+    pub fn guess_endian(data: &[u8]) -> Option<Endian> {
+        match data.get(6..8)? {
+            [0x40, 0x00] => Some(Endian::Little),
+            [0x00, 0x40] => Some(Endian::Big),
+            _ => None,
+        }
+    }
+
+    /// Reads a sound archive from a file, for any [`Platform`].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn open<P: AsRef<Path>>(input: P) -> Result<Self> {
+        Self::open_with_limits(input, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Reads a sound archive from a file, returning an error instead of reading a larger file, or more
+    /// sounds, files, or strings than `limits` allow.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn open_with_limits<P: AsRef<Path>>(input: P, limits: &ResourceLimits) -> Result<Self> {
+        // Check the size before reading the whole file into memory
+        let input = input.as_ref();
+        limits.check_output_size(std::fs::metadata(input)?.len())?;
+        let data = std::fs::read(input)?;
+        Self::load_with_limits(data, limits)
+    }
+
+    /// Reads a sound archive from a file using `endian`, ignoring its Byte Order Mark. See
+    /// [`load_with_endian`](Self::load_with_endian) for more information.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn open_with_endian<P: AsRef<Path>>(input: P, endian: Endian) -> Result<Self> {
+        let data = std::fs::read(input)?;
+        Self::load_with_endian(data, endian)
+    }
+
+    /// Reads a sound archive that's already in memory, for any [`Platform`].
+    #[inline]
+    pub fn load<I: Into<Box<[u8]>>>(input: I) -> Result<Self> {
+        Self::load_with_limits(input, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Reads a sound archive that's already in memory, returning an error instead of keeping a larger
+    /// archive, or reading more sounds, files, or strings than `limits` allow.
+    ///
+    /// Sound archives don't nest, so `max_depth` isn't used.
+    #[inline]
+    pub fn load_with_limits<I: Into<Box<[u8]>>>(input: I, limits: &ResourceLimits) -> Result<Self> {
+        let mut data = DataCursor::new(input, Endian::Big);
+        limits.check_output_size(data.len()?)?;
+        Self::read(data, None, limits)
+    }
+
+    /// Reads a sound archive that's already in memory using `endian`, ignoring its Byte Order Mark. This is
+    /// meant for archives with a damaged Byte Order Mark, where [`guess_endian`](Self::guess_endian) can be
+    /// used to find the right byte order.
+    #[inline]
+    pub fn load_with_endian<I: Into<Box<[u8]>>>(input: I, endian: Endian) -> Result<Self> {
+        Self::read(
+            DataCursor::new(input, endian),
+            Some(endian),
+            &ResourceLimits::UNLIMITED,
+        )
+    }
+
+    fn read(mut data: DataCursor, endian: Option<Endian>, limits: &ResourceLimits) -> Result<Self> {
+        // Read the file header
+        let (header, platform) = Self::read_header(&mut data, endian)?;
+
+        // Read the references to all sections
+        let mut sections: [SizedReference; 3] = Default::default();
+        for section in &mut sections {
+            *section = SizedReference::read(&mut data)?;
+        }
+
+        // Align to a 32-byte boundary
+        let position = data.position()?;
+        data.set_position((position + 31) & !31)?;
+
+        // Then read all the section data
+        let mut strings = StringBlock::default();
+        let mut info = InfoBlock::default();
+        for section in &sections {
+            data.set_position(section.offset.into())?;
+
+            match section.identifier {
+                Identifier::STRING_BLOCK => {
+                    strings = StringBlock::read(&mut data, limits)?;
+                }
+                Identifier::INFO_BLOCK => {
+                    info = InfoBlock::read(&mut data, platform, limits)?;
+                }
+                Identifier::FILE_BLOCK => {}
+                _ => InvalidDataSnafu {
+                    position: data.position()?,
+                    reason: "Unexpected Sound Archive Section!",
+                }
+                .fail()?,
+            }
+        }
+
+        let endian = data.endian();
+        Ok(Self {
+            platform,
+            header,
+            sections,
+            strings,
+            info,
+            files: FileBlock::default(),
+            data: data.into_inner(),
+            endian,
+        })
+    }
+
+    /// Returns which console the archive was built for.
+    #[must_use]
+    #[inline]
+    pub const fn platform(&self) -> Platform {
+        self.platform
+    }
+
+    /// Returns the version of the archive's format.
+    #[must_use]
+    #[inline]
+    pub const fn version(&self) -> Version {
+        self.header.version
+    }
+
+    /// Returns the generation of NintendoWare that built the archive, or [`None`] if its version doesn't
+    /// match any known generation.
+    #[must_use]
+    #[inline]
+    pub const fn generation(&self) -> Option<Generation> {
+        Self::classify(self.platform, self.header.version)
+    }
+
+    /// Returns the generation of NintendoWare that builds archives with this version for `platform`, see the
+    /// [`generation`](crate::generation) module for the known versions.
+    #[inline]
+    const fn classify(platform: Platform, version: Version) -> Option<Generation> {
+        match (platform, version.major) {
+            (Platform::Ctr, 2) => Some(Generation::NW4C),
+            (Platform::Cafe, 0) => Some(Generation::NW4F),
+            (Platform::NX, 0) => Some(Generation::NX),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of sounds in the archive.
+    #[must_use]
+    #[inline]
+    pub fn sound_count(&self) -> usize {
+        self.info.sounds.len()
+    }
+
+    /// Returns the name of a sound, without its null terminator, or [`None`] if it doesn't have one.
+    fn sound_name(&self, sound: &SoundInfo) -> Option<&str> {
+        let name = self.strings.table.get(sound.string_id as usize)?;
+        Some(name.trim_end_matches('\0'))
+    }
+
+    /// Returns the name of every item in the archive (sounds, banks, players, groups, and so on), in the
+    /// order they're stored in the string block.
+    #[inline]
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.strings.table.iter().map(|name| name.trim_end_matches('\0'))
+    }
+
+    /// Looks up an item by name using the archive's Patricia tree, the same way the game does, and returns
+    /// its item ID, where the highest byte is the type of item and the rest is its index.
+    #[must_use]
+    pub fn find(&self, name: &str) -> Option<u32> {
+        let node = self.strings.tree.get_node(name).ok()?;
+        let found = self.strings.table.get(node.string_id as usize)?;
+        (found.trim_end_matches('\0') == name).then_some(node.item_id)
+    }
+
+    /// Renames an item, see the [module documentation](self#renaming) for more information.
+    ///
+    /// # Errors
+    /// Returns [`NodeNotFound`](Error::NodeNotFound) if no item is named `name`,
+    /// [`InvalidName`](Error::InvalidName) if `new_name` is empty or contains a null, or
+    /// [`DuplicateName`](Error::DuplicateName) if another item is already named `new_name`.
+    pub fn rename(&mut self, name: &str, new_name: &str) -> Result<()> {
+        ensure!(
+            !new_name.is_empty() && !new_name.contains('\0'),
+            InvalidNameSnafu { name: new_name }
+        );
+        let index = self.names().position(|found| found == name).ok_or(Error::NodeNotFound)?;
+        if name == new_name {
+            return Ok(());
+        }
+        ensure!(
+            !self.names().any(|found| found == new_name),
+            DuplicateNameSnafu { name: new_name }
+        );
+
+        let new_name = format!("{new_name}\0");
+        #[cfg(not(feature = "arena"))]
+        {
+            self.strings.table[index] = new_name;
+        }
+        #[cfg(feature = "arena")]
+        self.strings.table.replace(index, &new_name);
+        Ok(())
+    }
+
+    /// Returns the number of files in the archive, which includes files that are stored elsewhere.
+    #[must_use]
+    #[inline]
+    pub fn file_count(&self) -> usize {
+        self.info.files.len()
+    }
+
+    /// Returns the data of a file stored inside the archive, or its replacement if it's been replaced.
+    /// Returns [`None`] if the file is stored elsewhere.
+    #[must_use]
+    pub fn file(&self, id: u32) -> Option<&[u8]> {
+        match self.info.files.get(id as usize)? {
+            FileLocation::Internal { replacement: Some(data), .. } => Some(data),
+            FileLocation::Internal { offset, size, .. } => {
+                let body = self.section(Identifier::FILE_BLOCK)?.offset as usize + 8;
+                let start = body + *offset as usize;
+                self.data.get(start..start + *size as usize)
+            }
+            FileLocation::External { .. } | FileLocation::None => None,
+        }
+    }
+
+    /// Returns the path of a file stored outside of the archive, such as a stream, relative to the archive.
+    #[must_use]
+    pub fn external_path(&self, id: u32) -> Option<&str> {
+        match self.info.files.get(id as usize)? {
+            FileLocation::External { path, .. } => Some(path),
+            FileLocation::Internal { .. } | FileLocation::None => None,
+        }
+    }
+
+    /// Replaces the data of a file stored inside the archive, such as a wave archive, see the
+    /// [module documentation](self#rebuilding) for more information.
+    ///
+    /// # Errors
+    /// Returns [`InvalidFileId`](Error::InvalidFileId) if the file isn't stored inside the archive.
+    pub fn replace_file<I: Into<Box<[u8]>>>(&mut self, id: u32, data: I) -> Result<()> {
+        match self.info.files.get_mut(id as usize) {
+            Some(FileLocation::Internal { replacement, .. }) => {
+                *replacement = Some(data.into());
+                Ok(())
+            }
+            _ => InvalidFileIdSnafu { id }.fail(),
+        }
+    }
+
+    /// Changes the path of a file stored outside of the archive, such as a stream. The info block isn't
+    /// rebuilt, so the new path can only use the space of the old one, including its padding.
+    ///
+    /// # Errors
+    /// Returns [`InvalidFileId`](Error::InvalidFileId) if the file isn't stored outside of the archive,
+    /// [`InvalidName`](Error::InvalidName) if `path` is empty or contains a null, or
+    /// [`PathTooLong`](Error::PathTooLong) if it doesn't fit.
+    pub fn set_external_path(&mut self, id: u32, new_path: &str) -> Result<()> {
+        ensure!(
+            !new_path.is_empty() && !new_path.contains('\0'),
+            InvalidNameSnafu { name: new_path }
+        );
+        match self.info.files.get_mut(id as usize) {
+            Some(FileLocation::External { path, capacity, .. }) => {
+                ensure!(
+                    new_path.len() <= *capacity,
+                    PathTooLongSnafu { path: new_path, capacity: *capacity }
+                );
+                *path = new_path.to_owned();
+                Ok(())
+            }
+            _ => InvalidFileIdSnafu { id }.fail(),
+        }
+    }
+
+    /// Converts a value to bytes using the archive's byte order.
+    #[inline]
+    fn u32_bytes(&self, value: u32) -> [u8; 4] {
+        match self.endian {
+            Endian::Little => value.to_le_bytes(),
+            Endian::Big => value.to_be_bytes(),
+        }
+    }
+
+    /// Returns the header's reference to a block.
+    fn section(&self, identifier: u16) -> Option<&SizedReference> {
+        self.sections.iter().find(|section| section.identifier == identifier)
+    }
+
+    /// Writes the archive back out with any changes, see the [module documentation](self#rebuilding) for
+    /// more information.
+    ///
+    /// # Errors
+    /// Returns [`InvalidData`](Error::InvalidData) if a file was replaced, but the original file block is
+    /// truncated.
+    ///
+    /// # Examples
+    /// ```
+    /// use orthrus_core::testing;
+    /// use orthrus_nintendoware::prelude::*;
+    ///
+    /// // Archives without any changes are written back exactly the same
+    /// let data = orthrus_tools::corpus::bfsar(3);
+    /// let parse = |data: &[u8]| SoundArchive::load(data);
+    /// let result = testing::round_trip(&data, parse, SoundArchive::to_bytes)?;
+    /// assert!(result.byte_identical);
+    /// # Ok::<(), testing::Divergence>(())
+    /// ```
+    pub fn to_bytes(&self) -> Result<Box<[u8]>> {
+        // Blocks are written back in the same order, along with anything between them
+        let mut order: Vec<usize> = (0..self.sections.len()).collect();
+        order.sort_by_key(|&index| self.sections[index].offset);
+        let mut offsets = [0u32; 3];
+        let mut sizes = [0u32; 3];
+        let mut output = Vec::with_capacity(self.data.len());
+        let mut end = 0;
+        for index in order {
+            let section = &self.sections[index];
+            let start = (section.offset as usize).min(self.data.len());
+            output.extend_from_slice(self.data.get(end..start).unwrap_or_default());
+            end = (section.offset as usize + section.size as usize).min(self.data.len());
+            let original = self.data.get(start..end).unwrap_or_default();
+
+            let block = match section.identifier {
+                Identifier::STRING_BLOCK => self.strings.to_bytes(self.endian, section.size),
+                Identifier::INFO_BLOCK => self.info_block(section, original),
+                Identifier::FILE_BLOCK => self.file_block(section, original)?,
+                _ => original.to_vec(),
+            };
+            offsets[index] = output.len() as u32;
+            sizes[index] = block.len() as u32;
+            output.extend(block);
+        }
+        output.extend_from_slice(self.data.get(end..).unwrap_or_default());
+
+        // Everything inside of a block is relative to its start, so only the header needs updating
+        let file_size = output.len() as u32;
+        output[0x0C..0x10].copy_from_slice(&self.u32_bytes(file_size));
+        for (index, (offset, size)) in offsets.into_iter().zip(sizes).enumerate() {
+            let position = 0x14 + index * 12;
+            output[position + 4..position + 8].copy_from_slice(&self.u32_bytes(offset));
+            output[position + 8..position + 12].copy_from_slice(&self.u32_bytes(size));
+        }
+        Ok(output.into_boxed_slice())
+    }
+
+    /// Returns the file block's layout, as the new offset of every file stored inside the archive, along with
+    /// the size of the block. Files are kept in the same order, aligned to 0x20 bytes, and files that share
+    /// data keep sharing it unless one of them is replaced.
+    fn file_layout(&self, section: &SizedReference) -> (Vec<Option<u32>>, u32) {
+        let body = section.offset as usize + 8;
+        let mut files: Vec<_> = self
+            .info
+            .files
+            .iter()
+            .enumerate()
+            .filter_map(|(id, file)| match file {
+                FileLocation::Internal { offset, .. } => Some((*offset, id)),
+                FileLocation::External { .. } | FileLocation::None => None,
+            })
+            .collect();
+        files.sort_unstable();
+
+        // Anything before the first file is kept as-is, which is normally just padding
+        let mut layout = vec![None; self.info.files.len()];
+        let mut position = files.first().map_or(0, |&(offset, _)| offset as usize);
+        let mut shared = Vec::new();
+        for (offset, id) in files {
+            let FileLocation::Internal { size, replacement, .. } = &self.info.files[id] else {
+                continue;
+            };
+            if replacement.is_none() {
+                if let Some(&(_, new_offset)) =
+                    shared.iter().find(|&&(original, _)| original == (offset, *size))
+                {
+                    layout[id] = Some(new_offset);
+                    continue;
+                }
+            }
+            position += (0usize.wrapping_sub(body + position)) & 0x1F;
+            layout[id] = Some(position as u32);
+            if replacement.is_none() {
+                shared.push(((offset, *size), position as u32));
+            }
+            position += replacement.as_ref().map_or(*size as usize, |data| data.len());
+        }
+        (layout, position as u32)
+    }
+
+    /// Rebuilds the file block if any of its files have been replaced.
+    fn file_block(&self, section: &SizedReference, original: &[u8]) -> Result<Vec<u8>> {
+        let replaced = self
+            .info
+            .files
+            .iter()
+            .any(|file| matches!(file, FileLocation::Internal { replacement: Some(_), .. }));
+        if !replaced {
+            return Ok(original.to_vec());
+        }
+
+        let (layout, length) = self.file_layout(section);
+        let mut block = original.get(..8).unwrap_or_default().to_vec();
+        block.resize(8 + length as usize, 0);
+        let first = layout.iter().flatten().min().map_or(0, |&offset| offset as usize);
+        let padding = original.get(8..8 + first).context(InvalidDataSnafu {
+            position: u64::from(section.offset) + 8,
+            reason: "File Block Truncated",
+        })?;
+        block[8..8 + first].copy_from_slice(padding);
+        for (id, offset) in layout.iter().enumerate() {
+            if let (Some(offset), Some(data)) = (offset, self.file(id as u32)) {
+                let start = 8 + *offset as usize;
+                block[start..start + data.len()].copy_from_slice(data);
+            }
+        }
+
+        // Pad the block so that everything after it stays aligned
+        let size = block.len() as u32 + (section.size.wrapping_sub(block.len() as u32) & 0x1F);
+        block.resize(size as usize, 0);
+        block[4..8].copy_from_slice(&self.u32_bytes(size));
+        Ok(block)
+    }
+
+    /// Copies the info block, pointing every file at where it's stored in the new file block.
+    fn info_block(&self, section: &SizedReference, original: &[u8]) -> Vec<u8> {
+        let mut block = original.to_vec();
+        let layout = match self.section(Identifier::FILE_BLOCK) {
+            Some(file_block) => self.file_layout(file_block).0,
+            None => Vec::new(),
+        };
+        for (id, file) in self.info.files.iter().enumerate() {
+            match file {
+                FileLocation::Internal { position, size, replacement, .. } => {
+                    let start = *position as usize - section.offset as usize;
+                    let Some(&Some(offset)) = layout.get(id) else {
+                        continue;
+                    };
+                    let size = replacement.as_ref().map_or(*size, |data| data.len() as u32);
+                    if let Some(reference) = block.get_mut(start + 4..start + 12) {
+                        reference[..4].copy_from_slice(&self.u32_bytes(offset));
+                        reference[4..].copy_from_slice(&self.u32_bytes(size));
+                    }
+                }
+                FileLocation::External { position, path, capacity } => {
+                    let start = *position as usize - section.offset as usize;
+                    if let Some(field) = block.get_mut(start..start + capacity + 1) {
+                        field.fill(0);
+                        field[..path.len()].copy_from_slice(path.as_bytes());
+                    }
+                }
+                FileLocation::None => {}
+            }
+        }
+        block
+    }
+
+    /// Writes the metadata of every sound as pretty-printed JSON, see the [module documentation](self#json)
+    /// for the layout.
+    ///
+    /// # Errors
+    /// Returns [`Json`](Error::Json) if the metadata can't be written as JSON.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String> {
+        #[derive(serde::Serialize)]
+        struct Sound<'a> {
+            name: Option<&'a str>,
+            #[serde(flatten)]
+            info: &'a SoundInfo,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Archive<'a> {
+            platform: Platform,
+            version: Version,
+            generation: Option<Generation>,
+            sounds: Vec<Sound<'a>>,
+        }
+
+        let sounds =
+            self.info.sounds.iter().map(|info| Sound { name: self.sound_name(info), info }).collect();
+        let archive = Archive {
+            platform: self.platform,
+            version: self.header.version,
+            generation: self.generation(),
+            sounds,
+        };
+        serde_json::to_string_pretty(&archive).context(JsonSnafu)
+    }
+}
+
+#[cfg(feature = "identify")]
+impl FileIdentifier for SoundArchive {
+    fn identify(data: &[u8]) -> Option<FileInfo> {
+        // Still report archives with a damaged Byte Order Mark, as long as the header makes sense
+        let (endian, guessed) = match data.get(4..6)? {
+            [0xFE, 0xFF] => (Endian::Big, false),
+            [0xFF, 0xFE] => (Endian::Little, false),
+            _ => (Self::guess_endian(data)?, true),
+        };
+        let mut cursor = DataCursorRef::new(data, endian);
+        let (header, platform) = Self::read_header(&mut cursor, Some(endian)).ok()?;
+
+        let kind = match platform {
+            Platform::Ctr => "BCSAR",
+            Platform::Cafe | Platform::NX => "BFSAR",
+        };
+        let endian = match endian {
+            Endian::Little => "little endian",
+            Endian::Big => "big endian",
+        };
+        let mut info = format!(
+            "NintendoWare {platform} Sound Archive ({kind}) {} ({}), {endian}",
+            header.version,
+            crate::generation::describe(Self::classify(platform, header.version))
+        );
+        if guessed {
+            info += " (guessed from the header, the Byte Order Mark is damaged)";
+        }
+        Some(FileInfo::new(info, None))
+    }
+}
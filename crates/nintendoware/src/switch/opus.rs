@@ -0,0 +1,251 @@
+//! Adds support for the Opus stream wrapper used by the Nintendo Switch, which many newer titles use for
+//! voice lines and music, either as standalone `.opus` files or embedded inside of other containers.
+//!
+//! Despite the extension, these aren't Ogg Opus files, but raw Opus packets with a small header in front. The
+//! header and every frame can be read without any extra dependencies, while decoding the packets into PCM
+//! samples requires the `opus` feature, which links against the system's libopus.
+//!
+//! # Format
+//! All values are little endian, except for the frame headers. The file is split into chunks, which each
+//! start with a `u32` type (with the top bit set) and a `u32` size.
+//!
+//! ## Header
+//! | Offset | Field          | Type | Notes                                             |
+//! |--------|----------------|------|---------------------------------------------------|
+//! | 0x00   | Chunk Type     | u32  | Always 0x80000001                                 |
+//! | 0x04   | Chunk Size     | u32  | Size of the rest of the header                    |
+//! | 0x08   | Version        | u8   | Always 0                                          |
+//! | 0x09   | Channel Count  | u8   |                                                   |
+//! | 0x0A   | Frame Size     | u16  | Size of every frame if constant, otherwise 0      |
+//! | 0x0C   | Sample Rate    | u32  |                                                   |
+//! | 0x10   | Data Offset    | u32  | Offset of the data chunk                          |
+//! | 0x14   | Frame Offset   | u32  | Unused                                            |
+//! | 0x18   | Context Offset | u32  | Offset of an optional context chunk               |
+//! | 0x1C   | Pre-Skip       | u16  | Number of samples to discard from the start       |
+//!
+//! ## Data
+//! The data chunk (type 0x80000004) holds every frame back-to-back, each of which is a big endian `u32`
+//! packet size, the big endian `u32` final range of the encoder (used for verifying the decoder), and
+//! then the Opus packet itself.
+//!
+//! # Usage
+//! ```
+//! use orthrus_nintendoware::prelude::*;
+//!
+//! let stream = Switch::OpusStream::load(orthrus_tools::corpus::opus(3))?;
+//! println!("{} channel(s) at {}Hz", stream.channel_count, stream.sample_rate);
+//! for packet in stream.packets() {
+//!     assert!(!packet.is_empty());
+//! }
+//! # Ok::<(), orthrus_nintendoware::error::Error>(())
+//! ```
+//!
+//! With the `opus` feature, [`decode`](OpusStream::decode) converts the whole stream to interleaved 16-bit
+//! PCM samples:
+//!
+//! ```no_run
+//! # #[cfg(feature = "opus")]
+//! # fn main() -> Result<(), orthrus_nintendoware::error::Error> {
+//! use orthrus_nintendoware::prelude::*;
+//!
+//! let stream = Switch::OpusStream::open("Voice_Hello.opus")?;
+//! let samples = stream.decode()?;
+//! println!("{} samples per channel", samples.len() / usize::from(stream.channel_count));
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "opus"))]
+//! # fn main() {}
+//! ```
+
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use orthrus_core::data::EndianExt;
+use orthrus_core::prelude::*;
+use snafu::prelude::*;
+
+use crate::error::*;
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
+/// Nintendo Switch Opus stream
+///
+/// See the [module documentation](self) for more information.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpusStream {
+    /// Version of the format
+    pub version: u8,
+    /// Number of channels, interleaved in each packet
+    pub channel_count: u8,
+    /// Size of every frame if they're all the same size, otherwise 0
+    pub frame_size: u16,
+    /// Number of samples per second
+    pub sample_rate: u32,
+    /// Number of samples at the start of the stream that should be discarded after decoding
+    pub pre_skip: u16,
+    packets: Vec<Range<usize>>,
+    data: Box<[u8]>,
+}
+
+impl OpusStream {
+    /// Chunk type of the data chunk.
+    pub const DATA_CHUNK: u32 = 0x8000_0004;
+    /// Chunk type of the header, which tells us if we're reading a Switch Opus file.
+    pub const HEADER_CHUNK: u32 = 0x8000_0001;
+    /// Size of the header in front of each packet, in bytes.
+    pub const FRAME_HEADER_SIZE: usize = 8;
+
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn open<P: AsRef<Path>>(input: P) -> Result<Self> {
+        let data = std::fs::read(input)?;
+        Self::load(data)
+    }
+
+    /// Reads the header, returning the stream without any packets.
+    fn read_header<T: ReadExt + SeekExt>(data: &mut T) -> Result<(Self, u64)> {
+        let chunk = data.read_u32()?;
+        ensure!(
+            chunk == Self::HEADER_CHUNK,
+            InvalidMagicSnafu { expected: Self::HEADER_CHUNK.to_le_bytes() }
+        );
+        let _size = data.read_u32()?;
+        let version = data.read_u8()?;
+        let channel_count = data.read_u8()?;
+        let frame_size = data.read_u16()?;
+        let sample_rate = data.read_u32()?;
+        let data_offset = data.read_u32()?;
+        let _frame_offset = data.read_u32()?;
+        let _context_offset = data.read_u32()?;
+        let pre_skip = data.read_u16()?;
+
+        ensure!(
+            channel_count > 0 && sample_rate > 0,
+            InvalidDataSnafu { position: 0x09u64, reason: "Stream Has No Channels Or Sample Rate" }
+        );
+
+        let stream = Self {
+            version,
+            channel_count,
+            frame_size,
+            sample_rate,
+            pre_skip,
+            packets: Vec::new(),
+            data: Box::default(),
+        };
+        Ok((stream, data_offset.into()))
+    }
+
+    /// Loads a Switch Opus file, and finds the location of every packet.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if this isn't a Switch Opus file, or
+    /// [`InvalidData`](Error::InvalidData) if the data chunk or any of its frames are out of bounds.
+    pub fn load<I: Into<Box<[u8]>>>(input: I) -> Result<Self> {
+        let mut data = DataCursor::new(input, Endian::Little);
+        let (stream, data_offset) = Self::read_header(&mut data)?;
+
+        data.set_position(data_offset)?;
+        let chunk = data.read_u32()?;
+        ensure!(
+            chunk == Self::DATA_CHUNK,
+            InvalidMagicSnafu { expected: Self::DATA_CHUNK.to_le_bytes() }
+        );
+        let size = u64::from(data.read_u32()?);
+        let start = data_offset + 8;
+        ensure!(
+            start + size <= data.len()?,
+            InvalidDataSnafu { position: data_offset + 4, reason: "Data Chunk Out Of Bounds" }
+        );
+
+        // Frame headers are big endian, unlike the rest of the file
+        data.set_endian(Endian::Big);
+        let mut packets = Vec::new();
+        let mut position = start;
+        while position < start + size {
+            data.set_position(position)?;
+            let length = u64::from(data.read_u32()?);
+            let _final_range = data.read_u32()?;
+            let packet = position + Self::FRAME_HEADER_SIZE as u64;
+            ensure!(
+                length > 0 && packet + length <= start + size,
+                InvalidDataSnafu { position, reason: "Invalid Packet Size" }
+            );
+            packets.push(packet as usize..(packet + length) as usize);
+            position = packet + length;
+        }
+
+        Ok(Self { packets, data: data.into_inner(), ..stream })
+    }
+
+    /// Returns every Opus packet in the stream, in playback order.
+    #[inline]
+    pub fn packets(&self) -> impl Iterator<Item = &[u8]> {
+        self.packets.iter().map(|packet| &self.data[packet.clone()])
+    }
+
+    /// Returns the number of Opus packets in the stream.
+    #[must_use]
+    #[inline]
+    pub fn packet_count(&self) -> usize {
+        self.packets.len()
+    }
+
+    /// Decodes the whole stream into interleaved 16-bit PCM samples, with the
+    /// [`pre_skip`](Self::pre_skip) samples already removed.
+    ///
+    /// # Errors
+    /// Returns [`Opus`](Error::Opus) if the stream has more than two channels, uses a sample rate that Opus
+    /// doesn't support, or if any packet can't be decoded.
+    #[cfg(feature = "opus")]
+    pub fn decode(&self) -> Result<Vec<i16>> {
+        use audiopus::coder::Decoder;
+        use audiopus::packet::Packet;
+        use audiopus::{Channels, MutSignals, SampleRate};
+
+        /// Longest duration of a single packet, which is 120ms at 48kHz.
+        const MAX_PACKET_SAMPLES: usize = 5760;
+
+        let channels = usize::from(self.channel_count);
+        let mut decoder = Decoder::new(
+            SampleRate::try_from(self.sample_rate as i32).context(OpusSnafu)?,
+            Channels::try_from(i32::from(self.channel_count)).context(OpusSnafu)?,
+        )
+        .context(OpusSnafu)?;
+
+        let mut output = Vec::new();
+        let mut buffer = vec![0i16; MAX_PACKET_SAMPLES * channels];
+        for packet in self.packets() {
+            let packet = Packet::try_from(packet).context(OpusSnafu)?;
+            let signals = MutSignals::try_from(&mut buffer[..]).context(OpusSnafu)?;
+            let samples = decoder.decode(Some(packet), signals, false).context(OpusSnafu)?;
+            output.extend_from_slice(&buffer[..samples * channels]);
+        }
+
+        let skip = (usize::from(self.pre_skip) * channels).min(output.len());
+        output.drain(..skip);
+        Ok(output)
+    }
+}
+
+#[cfg(feature = "identify")]
+impl FileIdentifier for OpusStream {
+    fn identify(data: &[u8]) -> Option<FileInfo> {
+        let (stream, _) = Self::read_header(&mut DataCursorRef::new(data, Endian::Little)).ok()?;
+        let info = format!(
+            "Nintendo Switch Opus stream, {} channel{}, {}Hz",
+            stream.channel_count,
+            if stream.channel_count == 1 { "" } else { "s" },
+            stream.sample_rate
+        );
+        Some(FileInfo::new(info, None))
+    }
+
+    fn identify_deep(data: &[u8]) -> Option<FileInfo> {
+        let mut info = Self::identify(data)?;
+        let stream = Self::load(data).ok()?;
+        info.info += &format!(", {} packets", stream.packet_count());
+        Some(info)
+    }
+}
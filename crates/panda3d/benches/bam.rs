@@ -0,0 +1,39 @@
+//! Benchmarks for loading BAM files with many nodes, where most of the time goes to small allocations.
+//!
+//! Inputs are generated using [`orthrus_tools::corpus`], so no game files are required. Run with `cargo bench
+//! -p orthrus-panda3d`, and again with `--features arena` to compare against storing lists in shared buffers.
+
+#![allow(unused_crate_dependencies)]
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use orthrus_panda3d::prelude::*;
+use orthrus_tools::corpus;
+
+fn load(c: &mut Criterion) {
+    let mut group = c.benchmark_group("BinaryAsset/load");
+    for count in [100, 1000, 10000] {
+        let data = corpus::bam_with_nodes(count);
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &data, |b, data| {
+            b.iter(|| black_box(BinaryAsset::load(data.clone()).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+fn lookups(c: &mut Criterion) {
+    let asset = BinaryAsset::load(corpus::bam_with_nodes(10000)).unwrap();
+    let mut group = c.benchmark_group("BinaryAsset/lookup");
+    group.bench_function("find_by_name", |b| {
+        b.iter(|| black_box(asset.find_by_name(black_box("node5000"))))
+    });
+    group.bench_function("parents", |b| {
+        b.iter(|| (0..asset.nodes.len()).map(|id| asset.parents(id).len()).sum::<usize>())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, load, lookups);
+criterion_main!(benches);
@@ -12,8 +12,129 @@
 //! It is used to represent Panda3D's internal scene graph hierarchy in a binary file format, as
 //! compared to .egg which is meant to be human-readable and editable by other programs.
 //!
+//! # Custom Object Types
+//! Games often add their own object types on top of the ones built into Panda3D. Handlers for these can be
+//! registered using [`LoadOptions::register`], and any type without a handler is kept as a raw
+//! [`CustomObject`] instead of failing to load, which can be found using [`BinaryAsset::custom_objects`].
+//!
+//! ```no_run
+//! use orthrus_core::prelude::*;
+//! use orthrus_panda3d::prelude::*;
+//!
+//! // A game-specific type that stores a single ID
+//! fn read_prop(data: &mut DataCursorRef<'_>, _minor_version: u16) -> Result<bam::ObjectValue, bam::Error> {
+//!     Ok(Box::new(data.read_u32()?))
+//! }
+//!
+//! let options = bam::LoadOptions::new().register("PropNode", read_prop);
+//! let asset = BinaryAsset::load_with_options(std::fs::read("prop.bam")?, &options)?;
+//! for (id, object) in asset.custom_objects() {
+//!     match object.value.as_ref().and_then(|value| value.downcast_ref::<u32>()) {
+//!         Some(prop_id) => println!("Object {id} is prop {prop_id}"),
+//!         None => println!("Object {id} is an unknown {} ({} bytes)", object.type_name, object.data.len()),
+//!     }
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! # Damaged Files
+//! By default, any object that fails to parse stops loading with an error. Using [`LoadMode::Lenient`],
+//! objects that fail to parse are replaced with a placeholder [`CustomObject`] containing their raw data, and
+//! a truncated file returns every object read before the end. Each error is recorded as an [`ObjectError`],
+//! see [`BinaryAsset::errors`].
+//!
+//! ```no_run
+//! use orthrus_panda3d::prelude::*;
+//!
+//! let (asset, errors) = BinaryAsset::load_lenient(std::fs::read("damaged.bam")?)?;
+//! for error in errors {
+//!     println!("{error}");
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! # Lookups
+//! Object IDs are grouped by name and by type when loading, so that converters can find objects without
+//! scanning every object, see [`BinaryAsset::find_by_name`] and [`BinaryAsset::find_by_type`]. The parents of
+//! each node are also recorded, since Panda3D allows instancing a node by attaching it to more than one
+//! parent, see [`BinaryAsset::parents`] and [`BinaryAsset::instances`]. A loaded `BinaryAsset` is never
+//! modified afterwards, so it can be shared between threads using an [`Arc`](std::sync::Arc) when converting
+//! many objects at once.
+//!
+//! This uses a small generated model from `orthrus_tools::corpus` in place of real game data, which has a
+//! `ModelRoot` named "root" with a `PandaNode` child for each file:
+//!
+//! ```
+//! use orthrus_panda3d::prelude::*;
+//!
+//! let asset = BinaryAsset::load(orthrus_tools::corpus::bam(2))?;
+//! let root = asset.find_by_name("root")[0];
+//! assert_eq!(asset.find_by_type("ModelRoot"), [root]);
+//! assert_eq!(asset.find_by_type("PandaNode").len(), 3);
+//! for id in asset.find_by_name("node1") {
+//!     assert_eq!(asset.parents(*id), [root]);
+//! }
+//! // Each node only has a single parent, so nothing is instanced
+//! assert!(asset.instances().is_empty());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Fonts that were converted to a model, such as with `egg-mkfont`, store each character as a node named
+//! after its character code. [`BinaryAsset::glyphs`] finds them the same way Panda3D's `StaticTextFont`
+//! does, returning the `Geom`s that make up each glyph:
+//!
+//! ```no_run
+//! use orthrus_panda3d::prelude::*;
+//!
+//! let asset = BinaryAsset::open("phase_3/models/fonts/ImpressBT.bam")?;
+//! for glyph in asset.glyphs() {
+//!     println!("{:?}: {} Geom(s)", glyph.character, glyph.geoms.len());
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Animations are stored as an `AnimBundle` with a hierarchy of channels below it, one for each joint of the
+//! character it animates. [`BinaryAsset::animations`] returns each of them along with how many values each
+//! joint stores, which shows which joints actually move:
+//!
+//! ```no_run
+//! use orthrus_panda3d::prelude::*;
+//!
+//! let asset = BinaryAsset::open("phase_3/models/char/mickey-walk.bam")?;
+//! for animation in asset.animations() {
+//!     let animated = animation.joints.iter().filter(|joint| joint.is_animated()).count();
+//!     println!("{}: {} of {} joints animated", animation.name, animated, animation.joints.len());
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! # JSON
+//! With the `serde` feature, [`BinaryAsset::to_json`] writes every object that was read as JSON, for
+//! debugging or for tools that want to use BAM data without parsing it themselves. The output contains the
+//! file's `version`, `endian`, and `use_double` settings, every object as an `id`, its `type` as named in the
+//! file, and its `data`, along with any shared `arrays` and any `errors` recorded while loading. Object IDs
+//! and pointers to other objects start at zero, so they're one less than in the file itself.
+//!
+//! Vertex data, texture images, and the raw data of custom objects can make up most of a file, so
+//! [`BufferMode`] controls whether they're included as base64 or only as their size.
+//!
+//! ```no_run
+//! # #[cfg(feature = "serde")]
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use orthrus_panda3d::prelude::*;
+//!
+//! let asset = BinaryAsset::open("char.bam")?;
+//! std::fs::write("char.json", asset.to_json(bam::BufferMode::Strip)?)?;
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "serde"))]
+//! # fn main() {}
+//! ```
+//!
 //! # Revisions
 
+use core::any::Any;
+use core::ops::Range;
 #[cfg(feature = "std")]
 use std::{io::prelude::*, path::Path};
 
@@ -24,7 +145,7 @@ use orthrus_core::prelude::*;
 use snafu::prelude::*;
 
 use crate::common::*;
-use crate::nodes::dispatch::{NodeStorage, StoredType};
+use crate::nodes::dispatch::{NodeRef, NodeStorage, StoredType};
 use crate::nodes::prelude::*;
 
 /// Error conditions for when working with Multifile archives.
@@ -42,6 +163,11 @@ pub enum Error {
     #[snafu(display("Decoding Error {source}"))]
     DataError { source: DataError },
 
+    /// Thrown if objects can't be written as JSON, see [`BinaryAsset::to_json`].
+    #[cfg(feature = "serde")]
+    #[snafu(display("JSON Error {source}"))]
+    JsonError { source: serde_json::Error },
+
     /// Thrown if trying to read the file out of its current bounds.
     #[snafu(display("Reached the end of the current stream!"))]
     EndOfFile,
@@ -61,6 +187,44 @@ pub enum Error {
     /// Thrown if unable to downcast to a specific type.
     #[snafu(display("Node is not of type {type_name}"))]
     InvalidType { type_name: &'static str },
+
+    /// Thrown if the file has more objects or deeper nesting than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+
+    /// Thrown if an object is missing a pointer to another object that it can't be used without.
+    #[snafu(display("Missing required pointer to {target}!"))]
+    NullPointer { target: &'static str },
+
+    /// Thrown if an object contains values that Panda3D would never write.
+    #[snafu(display("Invalid object data: {reason}!"))]
+    InvalidData { reason: &'static str },
+
+    /// Thrown if the file uses a feature that hasn't been implemented yet, usually from an older BAM version.
+    #[snafu(display("Not supported yet: {reason}!"))]
+    Unsupported { reason: &'static str },
+
+    /// Thrown if an object fails to parse when loading in [`LoadMode::Strict`], along with which object it
+    /// was. [`LoadMode::Lenient`] records the same details in an [`ObjectError`] instead.
+    #[snafu(display("Object {object_id} ({type_name}): {source}"))]
+    InvalidObject {
+        object_id: usize,
+        type_name: String,
+        source: Box<Error>,
+    },
+
+    /// Thrown by the Bevy loader when a diagnostic is reported in one of the kinds listed in
+    /// `LoadSettings::fatal_diagnostics`.
+    #[cfg(feature = "bevy")]
+    #[snafu(display("{code}: {message}"))]
+    FatalDiagnostic { code: &'static str, message: String },
+}
+
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Self::LimitExceeded { source }
+    }
 }
 
 impl From<core::fmt::Error> for Error {
@@ -85,7 +249,7 @@ impl From<DataError> for Error {
             DataError::Io { source } => Error::FileError { source },
             DataError::EndOfFile => Error::EndOfFile,
             DataError::InvalidString { source } => Error::InvalidString { source },
-            _ => todo!(),
+            source => Error::DataError { source },
         }
     }
 }
@@ -104,6 +268,186 @@ impl From<std::string::FromUtf8Error> for Error {
     }
 }
 
+/// Value returned by an [`ObjectHandler`], which can be downcast back to its original type.
+pub type ObjectValue = Box<dyn Any + Send + Sync>;
+
+/// Parses an object type that isn't built-in, given a cursor over the object's data (using the file's
+/// endianness) and the file's minor version.
+///
+/// Object IDs are either 16-bit or 32-bit depending on how many objects the file has, so pointers to other
+/// objects can't be reliably resolved by a handler yet.
+pub type ObjectHandler = fn(&mut DataCursorRef<'_>, u16) -> Result<ObjectValue, Error>;
+
+/// How a [`BinaryAsset`] handles objects that fail to parse, see [`LoadOptions::mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoadMode {
+    /// Stop loading and return the first error
+    #[default]
+    Strict,
+    /// Record each error and keep loading, see the [module documentation](self#damaged-files)
+    Lenient,
+}
+
+/// How [`BinaryAsset::to_json`] writes large byte buffers, such as vertex data and texture images.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BufferMode {
+    /// Write each buffer as its `size` and its data as a `base64` string, so that nothing is left out
+    #[default]
+    Base64,
+    /// Only write the `size` of each buffer, which keeps the output small enough to read through
+    Strip,
+}
+
+/// Options for loading a [`BinaryAsset`], see [`load_with_options`](BinaryAsset::load_with_options).
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct LoadOptions {
+    limits: ResourceLimits,
+    mode: LoadMode,
+    handlers: HashMap<String, ObjectHandler>,
+}
+
+impl LoadOptions {
+    /// Creates new options without any limits or custom handlers.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            limits: ResourceLimits::UNLIMITED,
+            mode: LoadMode::Strict,
+            handlers: HashMap::new(),
+        }
+    }
+
+    /// Sets how objects that fail to parse are handled. Limits are always enforced, regardless of `mode`.
+    #[inline]
+    pub fn mode(mut self, mode: LoadMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Returns an error instead of reading more objects (`max_entries`) or nesting deeper (`max_depth`) than
+    /// `limits` allow.
+    #[inline]
+    pub fn limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Registers a handler for objects of the type named `type_name`, which takes priority over the built-in
+    /// types. See the [module documentation](self#custom-object-types) for more information.
+    #[inline]
+    pub fn register<N: Into<String>>(mut self, type_name: N, handler: ObjectHandler) -> Self {
+        self.handlers.insert(type_name.into(), handler);
+        self
+    }
+}
+
+impl Default for LoadOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An object whose type isn't built-in, either parsed by a registered [`ObjectHandler`] or kept as raw data.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct CustomObject {
+    /// Name of the object's type
+    pub type_name: String,
+    /// Data of the object, not including its type handle and object ID
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::nodes::serialize::buffer"))]
+    pub data: Box<[u8]>,
+    /// Value returned by the registered [`ObjectHandler`], if any
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub value: Option<ObjectValue>,
+}
+
+/// An error that was recorded while loading in [`LoadMode::Lenient`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ObjectError {
+    /// ID of the placeholder [`CustomObject`], or [`None`] if loading stopped early, such as for a truncated
+    /// file
+    pub object_id: Option<usize>,
+    /// Name of the object's type, if known
+    pub type_name: Option<String>,
+    /// The error that was encountered
+    pub source: Error,
+}
+
+impl core::fmt::Display for ObjectError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match (self.object_id, &self.type_name) {
+            (Some(id), Some(type_name)) => write!(f, "Object {id} ({type_name}): {}", self.source),
+            (Some(id), None) => write!(f, "Object {id}: {}", self.source),
+            _ => write!(f, "Stopped loading early: {}", self.source),
+        }
+    }
+}
+
+/// A glyph of a font that was written out as a model, such as by `egg-mkfont`, see
+/// [`BinaryAsset::glyphs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Glyph {
+    /// Character drawn by the glyph
+    pub character: char,
+    /// ID of the node named after the character
+    pub node: usize,
+    /// IDs of every `Geom` that draws the glyph, in the order they were found
+    pub geoms: Vec<usize>,
+    /// ID of the `Geom` holding the single point that marks where the next glyph starts, if there is one
+    pub advance: Option<usize>,
+}
+
+/// An animation stored in an `AnimBundle`, see [`BinaryAsset::animations`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Animation {
+    /// ID of the `AnimBundle`
+    pub id: usize,
+    /// Name of the animation, which usually matches the character it was made for
+    pub name: String,
+    /// Frames per second the animation was made to be played at
+    pub fps: f32,
+    /// Number of frames in the animation
+    pub num_frames: u16,
+    /// Every joint with a channel in this animation, in the same order as its hierarchy
+    pub joints: Vec<AnimatedJoint>,
+}
+
+/// A joint that's part of an [`Animation`], stored in an `AnimChannelMatrixXfmTable`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AnimatedJoint {
+    /// ID of the `AnimChannelMatrixXfmTable`
+    pub id: usize,
+    /// Name of the joint, which matches the `CharacterJoint` it animates
+    pub name: String,
+    /// Number of joints this one is nested inside of
+    pub depth: usize,
+    /// Number of values stored for each channel, in the order of [`AnimatedJoint::CHANNELS`]. Channels
+    /// without any values use their default, and channels with a single value hold it for the whole
+    /// animation.
+    pub table_sizes: [usize; 12],
+}
+
+impl AnimatedJoint {
+    /// Letters Panda3D uses for each channel, which are scale (`ijk`), shear (`abc`), rotation (`hpr`), then
+    /// position (`xyz`).
+    pub const CHANNELS: [char; 12] = ['i', 'j', 'k', 'a', 'b', 'c', 'h', 'p', 'r', 'x', 'y', 'z'];
+
+    /// Returns whether any channel changes during the animation, instead of every channel being constant.
+    #[must_use]
+    #[inline]
+    pub fn is_animated(&self) -> bool {
+        self.table_sizes.iter().any(|&size| size > 1)
+    }
+}
+
 #[derive(Debug, Default)]
 pub(crate) struct Header {
     pub(crate) version: Version,
@@ -159,6 +503,114 @@ impl Default for ObjectsLeft {
     }
 }
 
+/// Pointer to Array data, indexed by its ID. With the `arena` feature, every array is stored in one buffer.
+#[cfg(not(feature = "arena"))]
+pub(crate) type Arrays = Vec<Vec<u32>>;
+/// Pointer to Array data, indexed by its ID. With the `arena` feature, every array is stored in one buffer.
+#[cfg(feature = "arena")]
+pub(crate) type Arrays = orthrus_core::arena::SliceArena<u32>;
+
+/// Object IDs grouped by a key, such as every object with the same name. With the `arena` feature, the IDs
+/// are collected as they're added and grouped into a single buffer by [`finish`](Self::finish), instead of
+/// giving every key its own `Vec`.
+#[derive(Debug, Default)]
+pub(crate) struct IdLookup<K> {
+    #[cfg(not(feature = "arena"))]
+    lists: HashMap<K, Vec<usize>>,
+    /// Index of the IDs for each key in `ids`
+    #[cfg(feature = "arena")]
+    lists: HashMap<K, usize>,
+    #[cfg(feature = "arena")]
+    ids: orthrus_core::arena::SliceArena<usize>,
+    /// IDs that have been added since the last call to `finish`
+    #[cfg(feature = "arena")]
+    pending: Vec<(K, usize)>,
+}
+
+impl<K: core::hash::Hash + Eq + Ord + Default> IdLookup<K> {
+    /// Adds `id` to the IDs for `key`, unless it was the last ID added for that key.
+    #[cfg(not(feature = "arena"))]
+    fn insert(&mut self, key: K, id: usize) {
+        let ids = self.lists.entry(key).or_default();
+        if ids.last() != Some(&id) {
+            ids.push(id);
+        }
+    }
+
+    /// Adds `id` to the IDs for `key`, unless it was the last ID added for that key.
+    #[cfg(feature = "arena")]
+    #[inline]
+    fn insert(&mut self, key: K, id: usize) {
+        self.pending.push((key, id));
+    }
+
+    /// Makes every added ID available to [`get`](Self::get) and [`iter`](Self::iter).
+    #[cfg(not(feature = "arena"))]
+    #[inline]
+    fn finish(&mut self) {}
+
+    /// Makes every added ID available to [`get`](Self::get) and [`iter`](Self::iter).
+    #[cfg(feature = "arena")]
+    fn finish(&mut self) {
+        let mut pending = core::mem::take(&mut self.pending);
+        // A stable sort keeps the IDs for each key in the order they were added
+        pending.sort_by(|(a, _), (b, _)| a.cmp(b));
+        pending.dedup();
+
+        let mut start = 0;
+        while start < pending.len() {
+            let key = &pending[start].0;
+            let end = start + pending[start..].iter().take_while(|(other, _)| other == key).count();
+            let added = pending[start..end].iter().map(|&(_, id)| id);
+            match self.lists.get(key) {
+                Some(&index) => {
+                    let ids: Vec<usize> = self.ids[index].iter().copied().chain(added).collect();
+                    self.ids.replace(index, ids);
+                }
+                None => {
+                    let index = self.ids.push(added);
+                    self.lists.insert(core::mem::take(&mut pending[start].0), index);
+                }
+            }
+            start = end;
+        }
+    }
+
+    /// Returns the IDs for `key`, in the order they were added.
+    #[cfg(not(feature = "arena"))]
+    #[inline]
+    fn get<Q: core::hash::Hash + Eq + ?Sized>(&self, key: &Q) -> &[usize]
+    where
+        K: core::borrow::Borrow<Q>,
+    {
+        self.lists.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the IDs for `key`, in the order they were added.
+    #[cfg(feature = "arena")]
+    #[inline]
+    fn get<Q: core::hash::Hash + Eq + ?Sized>(&self, key: &Q) -> &[usize]
+    where
+        K: core::borrow::Borrow<Q>,
+    {
+        self.lists.get(key).map_or(&[], |&index| &self.ids[index])
+    }
+
+    /// Returns every key along with its IDs, in an arbitrary order.
+    #[cfg(not(feature = "arena"))]
+    #[inline]
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&K, &[usize])> {
+        self.lists.iter().map(|(key, ids)| (key, ids.as_slice()))
+    }
+
+    /// Returns every key along with its IDs, in an arbitrary order.
+    #[cfg(feature = "arena")]
+    #[inline]
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&K, &[usize])> {
+        self.lists.iter().map(|(key, &index)| (key, &self.ids[index]))
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct BinaryAsset {
     /// Holds all BAM metadata needed for parsing
@@ -171,9 +623,25 @@ pub struct BinaryAsset {
     pub(crate) long_pta_id: bool,
     pub(crate) type_registry: HashMap<u16, String>,
     pub nodes: NodeStorage,
-    pub(crate) arrays: Vec<Vec<u32>>,
+    pub(crate) arrays: Arrays,
+    /// Errors recorded when loading in [`LoadMode::Lenient`]
+    pub(crate) errors: Vec<ObjectError>,
+    /// Object IDs for each name, built once loading is finished
+    pub(crate) names: IdLookup<String>,
+    /// Object IDs for each type, as named in the file
+    pub(crate) types: IdLookup<String>,
+    /// Object IDs of every node that has each node as a child, built once loading is finished
+    pub(crate) parents: IdLookup<usize>,
+    /// Where the data of each object is stored in the input, see [`object_range`](Self::object_range)
+    pub(crate) ranges: Vec<Range<usize>>,
 }
 
+// Converters share a single BinaryAsset between threads, so make sure it stays thread-safe
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<BinaryAsset>();
+};
+
 impl BinaryAsset {
     /// Latest revision of the BAM format. For more info, see [here](self#revisions).
     pub const CURRENT_VERSION: Version = Version { major: 6, minor: 45 };
@@ -187,6 +655,28 @@ impl BinaryAsset {
         self.header.version.minor
     }
 
+    /// Returns the BAM format version the file was written with. For more info, see [here](self#revisions).
+    #[must_use]
+    #[inline]
+    pub const fn version(&self) -> Version {
+        self.header.version
+    }
+
+    /// Returns the byte order used by every object in the file. The header itself is always little-endian.
+    #[must_use]
+    #[inline]
+    pub const fn endian(&self) -> Endian {
+        self.header.endian
+    }
+
+    /// Returns whether floating-point values are stored as doubles instead of floats, which is only possible
+    /// starting with 6.27.
+    #[must_use]
+    #[inline]
+    pub const fn use_double(&self) -> bool {
+        self.header.use_double
+    }
+
     #[cfg(feature = "std")]
     #[inline]
     pub fn open<P: AsRef<Path>>(input: P) -> Result<Self, self::Error> {
@@ -196,7 +686,77 @@ impl BinaryAsset {
 
     #[inline]
     pub fn load<I: Into<Box<[u8]>>>(input: I) -> Result<Self, self::Error> {
-        let mut data = DataCursor::new(input, Endian::Little);
+        Self::load_with_options(input, &LoadOptions::new())
+    }
+
+    /// Parses the given data into a new `BinaryAsset`, returning an error instead of reading more objects
+    /// (`max_entries`) or nesting deeper (`max_depth`) than `limits` allow.
+    #[inline]
+    pub fn load_with_limits<I: Into<Box<[u8]>>>(
+        input: I, limits: &ResourceLimits,
+    ) -> Result<Self, self::Error> {
+        Self::load_with_options(input, &LoadOptions::new().limits(*limits))
+    }
+
+    /// Parses the given data into a new `BinaryAsset`, replacing any objects that fail to parse with
+    /// placeholders, and returns it along with every error that was encountered. See the
+    /// [module documentation](self#damaged-files) for more information.
+    ///
+    /// # Errors
+    /// Returns an error if the header is invalid, since nothing can be recovered from the file.
+    #[inline]
+    pub fn load_lenient<I: Into<Box<[u8]>>>(input: I) -> Result<(Self, Vec<ObjectError>), self::Error> {
+        let mut asset = Self::load_with_options(input, &LoadOptions::new().mode(LoadMode::Lenient))?;
+        let errors = core::mem::take(&mut asset.errors);
+        Ok((asset, errors))
+    }
+
+    /// Parses the given data into a new `BinaryAsset`, using `options` for limits, custom object types, and
+    /// how to handle objects that fail to parse.
+    #[inline]
+    pub fn load_with_options<I: Into<Box<[u8]>>>(
+        input: I, options: &LoadOptions,
+    ) -> Result<Self, self::Error> {
+        Self::load_from(&mut DataCursor::new(input, Endian::Little), options)
+    }
+
+    /// Parses a borrowed slice into a new `BinaryAsset`, without copying it first. This is useful for files
+    /// that are already in memory, such as uncompressed files inside of a
+    /// [`Multifile`](crate::multifile::Multifile).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use orthrus_core::prelude::*;
+    /// use orthrus_panda3d::prelude::*;
+    ///
+    /// let multifile = Multifile::open("phase_3.mf", 0)?;
+    /// let reader = multifile.reader("phase_3/models/gui/dialog_box_gui.bam")?;
+    /// let asset = match reader.as_slice() {
+    ///     Some(data) => BinaryAsset::load_borrowed(data)?,
+    ///     None => {
+    ///         let mut stream = DataStream::new(reader, Endian::Little);
+    ///         BinaryAsset::load_from(&mut stream, &bam::LoadOptions::new())?
+    ///     }
+    /// };
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn load_borrowed(input: &[u8]) -> Result<Self, self::Error> {
+        Self::load_from(
+            &mut DataCursorRef::new(input, Endian::Little),
+            &LoadOptions::new(),
+        )
+    }
+
+    /// Parses a new `BinaryAsset` from the current position of any reader, using `options` for limits, custom
+    /// object types, and how to handle objects that fail to parse.
+    ///
+    /// Readers that can lend out their data (such as a [`DataCursorRef`]) are parsed without any copies,
+    /// while other readers (such as a [`DataStream`]) are read one datagram at a time.
+    pub fn load_from<T: ReadExt + SeekExt>(data: &mut T, options: &LoadOptions) -> Result<Self, self::Error> {
+        let limits = &options.limits;
+        let mut buffer = Vec::new();
+        data.set_endian(Endian::Little);
 
         // Read the magic and make sure we're actually parsing a BAM file
         let mut magic = [0u8; 6];
@@ -204,7 +764,7 @@ impl BinaryAsset {
         ensure!(magic == Self::MAGIC, InvalidMagicSnafu { expected: Self::MAGIC });
 
         // The first datagram is always the header data
-        let mut datagram = Datagram::new(&mut data, Endian::Little, false)?;
+        let mut datagram = Datagram::new(data, &mut buffer, Endian::Little, false)?;
         let header = Header::create(&mut datagram)?;
         ensure!(
             header.version.major == Self::CURRENT_VERSION.major
@@ -223,22 +783,28 @@ impl BinaryAsset {
             type_registry: HashMap::new(),
             objects_left,
             nodes: NodeStorage::new(),
-            arrays: Vec::new(),
             ..Default::default()
         };
 
         // Read the initial object
-        datagram = Datagram::new(&mut data, bamfile.header.endian, bamfile.header.use_double)?;
-        bamfile.read_object(&mut datagram)?;
+        let mut num_objects = 1u64;
+        if let Err(source) = bamfile.read_next(data, &mut buffer, options) {
+            bamfile.recover(source, options)?;
+            bamfile.build_lookups();
+            return Ok(bamfile);
+        }
 
         loop {
             //println!("Reading datagram at {:X}", data.position()?);
             match bamfile.objects_left {
                 ObjectsLeft::ObjectCount { mut num_extra_objects } => {
                     if num_extra_objects > 0 {
-                        datagram =
-                            Datagram::new(&mut data, bamfile.header.endian, bamfile.header.use_double)?;
-                        bamfile.read_object(&mut datagram)?;
+                        num_objects += 1;
+                        limits.check_entries(num_objects)?;
+                        if let Err(source) = bamfile.read_next(data, &mut buffer, options) {
+                            bamfile.recover(source, options)?;
+                            break;
+                        }
                         num_extra_objects -= 1;
                         bamfile.objects_left = ObjectsLeft::ObjectCount { num_extra_objects }
                     } else {
@@ -246,10 +812,14 @@ impl BinaryAsset {
                     }
                 }
                 ObjectsLeft::NestingLevel { nesting_level } => {
+                    limits.check_depth(nesting_level.unsigned_abs())?;
                     if nesting_level > 0 {
-                        datagram =
-                            Datagram::new(&mut data, bamfile.header.endian, bamfile.header.use_double)?;
-                        bamfile.read_object(&mut datagram)?;
+                        num_objects += 1;
+                        limits.check_entries(num_objects)?;
+                        if let Err(source) = bamfile.read_next(data, &mut buffer, options) {
+                            bamfile.recover(source, options)?;
+                            break;
+                        }
                     } else {
                         break;
                     }
@@ -257,10 +827,310 @@ impl BinaryAsset {
             }
         }
 
+        bamfile.build_lookups();
         Ok(bamfile)
     }
 
-    fn read_object(&mut self, data: &mut Datagram) -> Result<(), self::Error> {
+    /// Groups every named object by its name, and records the parents of every node, see
+    /// [`find_by_name`](Self::find_by_name) and [`parents`](Self::parents).
+    fn build_lookups(&mut self) {
+        for id in 0..self.nodes.len() {
+            let Some(node) = self.nodes.get(id) else {
+                continue;
+            };
+            if let Some(name) = node.name() {
+                self.names.insert(name.to_owned(), id);
+            }
+            // Stashed children are still attached to their parent, they just aren't rendered
+            let children = node.panda_node().map(|node| node.child_refs.iter().chain(&node.stashed_refs));
+            for &(child, _) in children.into_iter().flatten() {
+                self.parents.insert(child as usize, id);
+            }
+        }
+        self.names.finish();
+        self.types.finish();
+        self.parents.finish();
+    }
+
+    /// Returns the IDs of every object with the given name, in the order they were read.
+    #[must_use]
+    #[inline]
+    pub fn find_by_name(&self, name: &str) -> &[usize] {
+        self.names.get(name)
+    }
+
+    /// Returns the IDs of every object with the given type, in the order they were read. Types are named the
+    /// same way as in Panda3D, such as `GeomNode` or `CharacterJoint`, including custom object types.
+    #[must_use]
+    #[inline]
+    pub fn find_by_type(&self, type_name: &str) -> &[usize] {
+        self.types.get(type_name)
+    }
+
+    /// Returns where the data of object `id` is stored in the input it was loaded from, not including its
+    /// type handle and object ID. This is used for editing objects in place, see
+    /// [`Patch`](crate::bam_patch::Patch).
+    #[must_use]
+    #[inline]
+    pub fn object_range(&self, id: usize) -> Option<Range<usize>> {
+        self.ranges.get(id).cloned()
+    }
+
+    /// Returns the IDs of every node that has the node `id` as a child (including stashed children), in the
+    /// order they were read.
+    #[must_use]
+    #[inline]
+    pub fn parents(&self, id: usize) -> &[usize] {
+        self.parents.get(&id)
+    }
+
+    /// Returns whether the node `id` is instanced, which means that it's attached to more than one parent so
+    /// that the same subtree is shown in multiple places.
+    #[must_use]
+    #[inline]
+    pub fn is_instanced(&self, id: usize) -> bool {
+        self.parents(id).len() > 1
+    }
+
+    /// Returns the ID of every instanced node along with its parents, sorted by ID. See
+    /// [`is_instanced`](Self::is_instanced).
+    #[must_use]
+    pub fn instances(&self) -> Vec<(usize, &[usize])> {
+        let mut instances: Vec<_> = self
+            .parents
+            .iter()
+            .filter(|(_, parents)| parents.len() > 1)
+            .map(|(&id, parents)| (id, parents))
+            .collect();
+        instances.sort_unstable_by_key(|&(id, _)| id);
+        instances
+    }
+
+    /// Returns the filename of every image loaded by a `Texture` in this file, including separate alpha
+    /// images, without duplicates and in the order they were read. Filenames are returned as stored, which is
+    /// usually relative to Panda3D's model path.
+    #[must_use]
+    pub fn texture_filenames(&self) -> Vec<&str> {
+        let mut filenames = Vec::new();
+        for &id in self.find_by_type("Texture") {
+            let Some(texture) = self.nodes.get_as::<Texture>(id) else {
+                continue;
+            };
+            // Textures that only store their image inline don't have a filename
+            for filename in [texture.filename.as_str(), texture.alpha_filename.as_str()] {
+                if !filename.is_empty() && !filenames.contains(&filename) {
+                    filenames.push(filename);
+                }
+            }
+        }
+        filenames
+    }
+
+    /// Returns every glyph stored in this file, sorted by character. This follows Panda3D's `StaticTextFont`,
+    /// which searches from each root node for nodes named after a character code, such as `65` for `A`. The
+    /// `GeomNode`s anywhere below each of those make up the glyph.
+    #[must_use]
+    pub fn glyphs(&self) -> Vec<Glyph> {
+        let mut glyphs = Vec::new();
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack: Vec<usize> =
+            (0..self.nodes.len()).filter(|&id| self.parents(id).is_empty()).rev().collect();
+        while let Some(id) = stack.pop() {
+            let node = self.nodes.get(id);
+            let Some(node) = node.as_ref().and_then(NodeRef::panda_node) else {
+                continue;
+            };
+            if core::mem::replace(&mut visited[id], true) {
+                continue;
+            }
+
+            let is_glyph = !node.name.is_empty() && node.name.bytes().all(|byte| byte.is_ascii_digit());
+            match is_glyph {
+                true => {
+                    // Numbers that aren't valid characters can't be drawn either, so skip the whole subtree
+                    let character = node.name.parse().ok().and_then(char::from_u32);
+                    if let Some(character) = character {
+                        let mut glyph = Glyph { character, node: id, geoms: Vec::new(), advance: None };
+                        self.collect_glyph(id, &mut glyph, &mut visited);
+                        glyphs.push(glyph);
+                    }
+                }
+                false => stack.extend(node.child_refs.iter().rev().map(|&(child, _)| child as usize)),
+            }
+        }
+        glyphs.sort_by_key(|glyph| glyph.character);
+        glyphs
+    }
+
+    /// Adds every `Geom` below the node `id` to `glyph`, splitting out the point that marks its advance.
+    fn collect_glyph(&self, id: usize, glyph: &mut Glyph, visited: &mut [bool]) {
+        if let Some(geom_node) = self.nodes.get_as::<GeomNode>(id) {
+            for &(geom_ref, _) in &geom_node.geom_refs {
+                let geom_ref = geom_ref as usize;
+                // GeomPoints aren't parsed, so they're stored as a CustomObject
+                let is_point = self.nodes.get_as::<Geom>(geom_ref).is_some_and(|geom| {
+                    !geom.primitive_refs.is_empty()
+                        && geom.primitive_refs.iter().all(|&primitive| {
+                            self.nodes
+                                .get_as::<CustomObject>(primitive as usize)
+                                .is_some_and(|object| object.type_name == "GeomPoints")
+                        })
+                });
+                match is_point {
+                    true => glyph.advance = glyph.advance.or(Some(geom_ref)),
+                    false => glyph.geoms.push(geom_ref),
+                }
+            }
+        }
+
+        let node = self.nodes.get(id);
+        let Some(node) = node.as_ref().and_then(NodeRef::panda_node) else {
+            return;
+        };
+        for &(child, _) in &node.child_refs {
+            let child = child as usize;
+            if child < visited.len() && !core::mem::replace(&mut visited[child], true) {
+                self.collect_glyph(child, glyph, visited);
+            }
+        }
+    }
+
+    /// Returns every animation stored in this file, in the order they were read. Joints are found the same
+    /// way Panda3D binds an animation to a character, by walking the hierarchy below each `AnimBundle`, so
+    /// groups such as `<skeleton>` are skipped and only the joints inside of them are returned.
+    #[must_use]
+    pub fn animations(&self) -> Vec<Animation> {
+        let mut animations = Vec::new();
+        for &id in self.find_by_type("AnimBundle") {
+            let Some(bundle) = self.nodes.get_as::<AnimBundle>(id) else {
+                continue;
+            };
+            let mut animation = Animation {
+                id,
+                name: bundle.name.clone(),
+                fps: bundle.fps,
+                num_frames: bundle.num_frames,
+                joints: Vec::new(),
+            };
+
+            let mut visited = vec![false; self.nodes.len()];
+            visited[id] = true;
+            let mut stack: Vec<(usize, usize)> =
+                bundle.child_refs.iter().rev().map(|&child| (child as usize, 0)).collect();
+            while let Some((id, depth)) = stack.pop() {
+                if id >= visited.len() || core::mem::replace(&mut visited[id], true) {
+                    continue;
+                }
+                let (group, child_depth) = match self.nodes.get(id) {
+                    Some(NodeRef::AnimGroup(group)) => (group, depth),
+                    Some(NodeRef::AnimChannelMatrixXfmTable(table)) => {
+                        animation.joints.push(AnimatedJoint {
+                            id,
+                            name: table.name.clone(),
+                            depth,
+                            table_sizes: table.tables.each_ref().map(Vec::len),
+                        });
+                        (&table.inner.inner, depth + 1)
+                    }
+                    _ => continue,
+                };
+                stack.extend(group.child_refs.iter().rev().map(|&child| (child as usize, child_depth)));
+            }
+            animations.push(animation);
+        }
+        animations
+    }
+
+    /// Returns every error recorded when loading in [`LoadMode::Lenient`].
+    #[must_use]
+    #[inline]
+    pub fn errors(&self) -> &[ObjectError] {
+        &self.errors
+    }
+
+    /// Returns `source` when loading in [`LoadMode::Strict`], otherwise records it so loading can stop early
+    /// with every object read so far.
+    fn recover(&mut self, source: Error, options: &LoadOptions) -> Result<(), Error> {
+        match options.mode {
+            LoadMode::Strict => Err(source),
+            LoadMode::Lenient => {
+                self.errors.push(ObjectError { object_id: None, type_name: None, source });
+                Ok(())
+            }
+        }
+    }
+
+    /// Reads the next datagram, and the object inside of it.
+    fn read_next<T: ReadExt + SeekExt>(
+        &mut self, data: &mut T, buffer: &mut Vec<u8>, options: &LoadOptions,
+    ) -> Result<(), Error> {
+        // Skip past the datagram's length, so object ranges point at the data itself
+        let offset = data.position()? as usize + 4;
+        let mut datagram = Datagram::new(data, buffer, self.header.endian, self.header.use_double)?;
+        self.read_object(&mut datagram, offset, options)
+    }
+
+    /// Returns every [`CustomObject`] along with its object ID, in the order they were read.
+    pub fn custom_objects(&self) -> impl Iterator<Item = (usize, &CustomObject)> {
+        (0..self.nodes.len()).filter_map(|id| Some((id, self.nodes.get_as::<CustomObject>(id)?)))
+    }
+
+    /// Writes every object as pretty-printed JSON, see the [module documentation](self#json) for the layout.
+    /// `buffers` controls whether large byte buffers are included or only their size.
+    ///
+    /// # Errors
+    /// Returns [`JsonError`](Error::JsonError) if an object can't be written as JSON.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self, buffers: BufferMode) -> Result<String, self::Error> {
+        #[derive(serde::Serialize)]
+        struct Object<'a> {
+            id: usize,
+            #[serde(rename = "type")]
+            type_name: &'a str,
+            data: crate::nodes::dispatch::NodeRef<'a>,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Asset<'a> {
+            version: Version,
+            endian: &'static str,
+            use_double: bool,
+            objects: Vec<Object<'a>>,
+            arrays: Vec<&'a [u32]>,
+            errors: Vec<String>,
+        }
+
+        // Use the type names from the file, since some types share the same parser
+        let mut type_names = vec![""; self.nodes.len()];
+        for (type_name, ids) in self.types.iter() {
+            for &id in ids {
+                type_names[id] = type_name;
+            }
+        }
+        let objects = type_names
+            .iter()
+            .enumerate()
+            .filter_map(|(id, type_name)| Some(Object { id, type_name, data: self.nodes.get(id)? }))
+            .collect();
+
+        let asset = Asset {
+            version: self.header.version,
+            endian: match self.header.endian {
+                Endian::Little => "little",
+                Endian::Big => "big",
+            },
+            use_double: self.header.use_double,
+            objects,
+            arrays: (0..self.arrays.len()).map(|index| &self.arrays[index][..]).collect(),
+            errors: self.errors.iter().map(ToString::to_string).collect(),
+        };
+        crate::nodes::serialize::with_buffer_mode(buffers, || serde_json::to_string_pretty(&asset))
+            .context(JsonSnafu)
+    }
+
+    fn read_object(
+        &mut self, data: &mut Datagram, offset: usize, options: &LoadOptions,
+    ) -> Result<(), self::Error> {
         // If we're reading a file 6.21 or newer, control flow codes are in the data stream, so
         // match against the enum variant
         if let ObjectsLeft::NestingLevel { ref mut nesting_level } = self.objects_left {
@@ -274,8 +1144,8 @@ impl BinaryAsset {
                     return Ok(());
                 }
                 ObjectCode::Adjunct => {}
-                _ => {
-                    todo!("Remove and FileData are unimplemented, need a test case, pls message me.")
+                ObjectCode::Remove | ObjectCode::FileData => {
+                    return UnsupportedSnafu { reason: "Remove and FileData object codes" }.fail();
                 }
             }
         }
@@ -294,9 +1164,29 @@ impl BinaryAsset {
         if type_handle != 0 {
             // Now we need to read the data of the associated type using the "fillin" functions
             // For now I'm combining them into a single function
-            let type_name = self.type_registry.get_mut(&type_handle).expect("a").to_owned();
+            let type_name = self.type_registry[&type_handle].clone();
             //println!("Filling in {} from {:#X}", type_name, data.position()?);
-            block_on(self.fillin(data, &type_name))?;
+            let start = data.position()?;
+            let id = self.nodes.len();
+            let result = match options.handlers.get(&type_name) {
+                Some(handler) => self.create_custom(data, &type_name, Some(*handler)),
+                None => block_on(self.fillin(data, &type_name)),
+            };
+            if let Err(source) = result {
+                if options.mode == LoadMode::Strict {
+                    return Err(Error::InvalidObject { object_id: id, type_name, source: Box::new(source) });
+                }
+                // Keep the object's raw data as a placeholder, so that the IDs of later objects still line up
+                self.errors.push(ObjectError {
+                    object_id: Some(self.nodes.len()),
+                    type_name: Some(type_name.clone()),
+                    source,
+                });
+                data.set_position(start)?;
+                self.create_custom(data, &type_name, None)?;
+            }
+            self.types.insert(type_name, id);
+            self.ranges.push(offset + start as usize..offset + data.len()? as usize);
         }
         if data.position()? != data.len()? {
             println!(
@@ -354,6 +1244,14 @@ impl BinaryAsset {
         Ok(pta_id)
     }
 
+    /// Reads a pointer to another object that can't be NULL, where `target` is the type of object it points
+    /// to.
+    pub(crate) fn read_required_pointer(
+        &mut self, data: &mut Datagram, target: &'static str,
+    ) -> Result<u32, self::Error> {
+        self.read_pointer(data)?.context(NullPointerSnafu { target })
+    }
+
     pub(crate) fn read_pointer(&mut self, data: &mut Datagram) -> Result<Option<u32>, self::Error> {
         let object_id = self.read_object_id(data)?;
         //println!("Object ID ptrto {}", object_id);
@@ -408,17 +1306,36 @@ impl BinaryAsset {
             "PartGroup" => self.create_node::<PartGroup>(data),
             "RenderEffects" => self.create_node::<RenderEffects>(data),
             "RenderState" => self.create_node::<RenderState>(data),
+            "SheetNode" => self.create_node::<SheetNode>(data),
+            "TextNode" => self.create_node::<TextNode>(data),
             "Texture" => self.create_node::<Texture>(data),
             "TextureAttrib" => self.create_node::<TextureAttrib>(data),
             "TextureStage" => self.create_node::<TextureStage>(data),
             "TransformBlendTable" => self.create_node::<TransformBlendTable>(data),
             "TransformState" => self.create_node::<TransformState>(data),
+            "TransformTable" => self.create_node::<TransformTable>(data),
             "TransparencyAttrib" => self.create_node::<TransparencyAttrib>(data),
             "UserVertexTransform" => self.create_node::<UserVertexTransform>(data),
-            _ => todo!("{type_name}"),
+            _ => self.create_custom(data, type_name, None),
         }
     }
 
+    /// Stores an object that isn't built-in, parsing it first if there's a handler for it.
+    fn create_custom(
+        &mut self, data: &mut Datagram<'_>, type_name: &str, handler: Option<ObjectHandler>,
+    ) -> Result<(), Error> {
+        let data: Box<[u8]> = data.remaining_slice()?.into();
+        let value = match handler {
+            Some(handler) => {
+                let mut cursor = DataCursorRef::new(&data, self.header.endian);
+                Some(handler(&mut cursor, self.header.version.minor)?)
+            }
+            None => None,
+        };
+        self.nodes.push(CustomObject { type_name: type_name.to_owned(), data, value });
+        Ok(())
+    }
+
     fn create_node<T: Node + StoredType>(&mut self, data: &mut Datagram<'_>) -> Result<(), Error> {
         let node = T::create(self, data)?;
         //println!("{:#?}", node);
@@ -492,6 +1409,16 @@ impl GraphWriter {
     }
 }
 
+#[cfg(feature = "std")]
+impl GraphDisplay for CustomObject {
+    fn write_data(
+        &self, label: &mut impl core::fmt::Write, _connections: &mut Vec<u32>, _is_root: bool,
+    ) -> Result<(), Error> {
+        write!(label, "{{{}|size: {:#X}}}", self.type_name, self.data.len())?;
+        Ok(())
+    }
+}
+
 // TODO: stuff I can already see, it would be nice to add labels to connections (&mut Vec<(u32, &'static
 // str)>), and it would be nice to have read access to NodeStorage so we can get std::any::type_name() for
 // NodePath
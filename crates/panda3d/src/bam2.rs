@@ -15,6 +15,10 @@ pub enum Error {
     #[snafu(display("Reached the end of the current stream!"))]
     EndOfFile,
 
+    /// Thrown if a [`DataError`] other than EndOfFile is encountered.
+    #[snafu(display("Decoding Error {source}"))]
+    DataError { source: DataError },
+
     /// Thrown if the header contains a magic number other than "pmf\0\n\r".
     #[snafu(display("Invalid Magic! Expected {:?}.", BinaryAsset::MAGIC))]
     InvalidMagic,
@@ -25,7 +29,7 @@ impl From<DataError> for Error {
     fn from(error: DataError) -> Self {
         match error {
             DataError::EndOfFile => Self::EndOfFile,
-            _ => todo!(),
+            source => Self::DataError { source },
         }
     }
 }
@@ -0,0 +1,436 @@
+//! Adds support for editing a BAM file in place, without needing to write the whole file back out.
+//!
+//! Writing BAM files isn't supported yet, but many quick fixes to a model only need to change a value without
+//! changing its size, such as pointing a texture at a renamed image or nudging a transform. A [`Patch`] is a
+//! list of these edits, which are checked against the parsed [`BinaryAsset`] and then written directly into
+//! the original data using [`BinaryAsset::object_range`].
+//!
+//! # Format
+//! Patches are plain text, with one edit per line in the form `<object> <field> = <value>`. Blank lines and
+//! lines starting with `#` are ignored.
+//!
+//! Objects are either an object ID (starting at zero, the same as in [`BinaryAsset::to_json`]), or the name
+//! of a single object, which can be quoted if it contains spaces. The following fields are supported:
+//!
+//! | Field | Objects | Value |
+//! |-------|---------|-------|
+//! | `name` | Any node, or a `Texture` | String |
+//! | `filename`, `alpha_filename` | `Texture` | String |
+//! | `pos.x`, `pos.y`, `pos.z` | `TransformState` stored as components | Number |
+//! | `hpr.h`, `hpr.p`, `hpr.r` | `TransformState` stored as components with a rotation | Number |
+//! | `quat.0` to `quat.3` | `TransformState` stored as components with a quaternion | Number |
+//! | `scale.x`, `scale.y`, `scale.z` | `TransformState` stored as components | Number |
+//! | `shear.x`, `shear.y`, `shear.z` | `TransformState` stored as components | Number |
+//! | `matrix.0` to `matrix.15` | `TransformState` stored as a matrix, in the order it's stored | Number |
+//!
+//! Strings are written in double quotes, with `\"` and `\\` as escapes, and must be the same length in bytes
+//! as the value they replace. Numbers are written using the file's precision.
+//!
+//! ```text
+//! # Point the chest at the fixed texture, and raise it a little
+//! chest_tex filename = "maps/chest_fix.png"
+//! 12 pos.z = 1.5
+//! ```
+//!
+//! # Usage
+//! This uses a small generated model from `orthrus_tools::corpus` in place of real game data, which has a
+//! `ModelRoot` named "root" with a `PandaNode` child for each file:
+//!
+//! ```
+//! use orthrus_panda3d::prelude::*;
+//!
+//! let mut data = orthrus_tools::corpus::bam(2);
+//! let asset = BinaryAsset::load(data.clone())?;
+//!
+//! let patch = Patch::parse("node1 name = \"chest\"")?;
+//! patch.apply(&asset, &mut data)?;
+//! assert_eq!(BinaryAsset::load(data.clone())?.find_by_name("chest").len(), 1);
+//!
+//! // Anything that would change the size of the file is rejected, without changing the data
+//! let patch = Patch::parse("root name = \"scene\"")?;
+//! assert!(patch.apply(&asset, &mut data).is_err());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use core::fmt;
+use core::ops::Range;
+
+use orthrus_core::prelude::*;
+use snafu::prelude::*;
+
+use crate::bam::BinaryAsset;
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
+/// Error conditions for when parsing or applying a [`Patch`].
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown if a line of the patch can't be parsed.
+    #[snafu(display("Line {line}: {reason}!"))]
+    InvalidSyntax { line: usize, reason: &'static str },
+
+    /// Thrown if an edit's object doesn't exist, or if its name doesn't match exactly one object.
+    #[snafu(display("Line {line}: unable to find a single object named {object}!"))]
+    ObjectNotFound { line: usize, object: String },
+
+    /// Thrown if an edit's object doesn't have the field, such as a texture path on a node.
+    #[snafu(display("Line {line}: object {id} ({type_name}) doesn't store {field}!"))]
+    InvalidField {
+        line: usize,
+        id: usize,
+        type_name: String,
+        field: Field,
+    },
+
+    /// Thrown if a new string isn't the same length as the one it replaces.
+    #[snafu(display("Line {line}: {field} is {expected} bytes long, but the new value is {found} bytes!"))]
+    LengthMismatch {
+        line: usize,
+        field: Field,
+        expected: usize,
+        found: usize,
+    },
+
+    /// Thrown if the data doesn't match the [`BinaryAsset`] it was loaded as.
+    #[snafu(display("Line {line}: the data doesn't match the parsed file!"))]
+    DataMismatch { line: usize },
+}
+
+/// Object that an [`Edit`] applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// Object ID, starting at zero
+    Id(usize),
+    /// Name of a single object
+    Name(String),
+}
+
+/// Field that an [`Edit`] changes, see the [module documentation](self#format) for which objects have each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Field {
+    /// Name of a node or `Texture`
+    Name,
+    /// Image filename of a `Texture`
+    Filename,
+    /// Alpha image filename of a `Texture`
+    AlphaFilename,
+    /// Component of a `TransformState`'s position
+    Position(usize),
+    /// Component of a `TransformState`'s rotation, as heading, pitch, and roll
+    Rotation(usize),
+    /// Component of a `TransformState`'s quaternion, in the order it's stored
+    Quaternion(usize),
+    /// Component of a `TransformState`'s scale
+    Scale(usize),
+    /// Component of a `TransformState`'s shear
+    Shear(usize),
+    /// Element of a `TransformState`'s matrix, in the order it's stored
+    Matrix(usize),
+}
+
+impl Field {
+    /// Parses a field name, such as `filename` or `pos.x`.
+    fn parse(name: &str) -> Option<Self> {
+        let component = |index: &str, names: [&str; 3]| names.iter().position(|name| *name == index);
+        let element = |index: &str, count: usize| index.parse().ok().filter(|&index| index < count);
+        match name.split_once('.') {
+            None => match name {
+                "name" => Some(Self::Name),
+                "filename" => Some(Self::Filename),
+                "alpha_filename" => Some(Self::AlphaFilename),
+                _ => None,
+            },
+            Some(("pos", index)) => component(index, ["x", "y", "z"]).map(Self::Position),
+            Some(("hpr", index)) => component(index, ["h", "p", "r"]).map(Self::Rotation),
+            Some(("quat", index)) => element(index, 4).map(Self::Quaternion),
+            Some(("scale", index)) => component(index, ["x", "y", "z"]).map(Self::Scale),
+            Some(("shear", index)) => component(index, ["x", "y", "z"]).map(Self::Shear),
+            Some(("matrix", index)) => element(index, 16).map(Self::Matrix),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the component or element index is in bounds.
+    #[inline]
+    const fn is_valid(self) -> bool {
+        match self {
+            Self::Name | Self::Filename | Self::AlphaFilename => true,
+            Self::Position(index) | Self::Rotation(index) | Self::Scale(index) | Self::Shear(index) => {
+                index < 3
+            }
+            Self::Quaternion(index) => index < 4,
+            Self::Matrix(index) => index < 16,
+        }
+    }
+
+    /// Returns whether the field is a string, rather than a number.
+    #[inline]
+    const fn is_string(self) -> bool {
+        matches!(self, Self::Name | Self::Filename | Self::AlphaFilename)
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const XYZ: [&str; 3] = ["x", "y", "z"];
+        let component = |names: [&'static str; 3], index: usize| names.get(index).copied().unwrap_or("?");
+        match *self {
+            Self::Name => write!(f, "name"),
+            Self::Filename => write!(f, "filename"),
+            Self::AlphaFilename => write!(f, "alpha_filename"),
+            Self::Position(index) => write!(f, "pos.{}", component(XYZ, index)),
+            Self::Rotation(index) => write!(f, "hpr.{}", component(["h", "p", "r"], index)),
+            Self::Quaternion(index) => write!(f, "quat.{index}"),
+            Self::Scale(index) => write!(f, "scale.{}", component(XYZ, index)),
+            Self::Shear(index) => write!(f, "shear.{}", component(XYZ, index)),
+            Self::Matrix(index) => write!(f, "matrix.{index}"),
+        }
+    }
+}
+
+/// New value of a [`Field`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// Replaces a string, which must be the same length
+    String(String),
+    /// Replaces a number, which is written using the file's precision
+    Number(f64),
+}
+
+/// A single change to a [`BinaryAsset`], see [`Patch`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Edit {
+    /// Line of the patch the edit came from, for error messages
+    pub line: usize,
+    /// Object to change
+    pub object: Target,
+    /// Field of the object to change
+    pub field: Field,
+    /// Value to write
+    pub value: Value,
+}
+
+/// A list of edits that can be written directly into a BAM file, see the [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq)]
+#[must_use]
+pub struct Patch {
+    /// Every edit, in the order they're applied
+    pub edits: Vec<Edit>,
+}
+
+/// `TransformState` flags that determine which fields are stored, see `TransformFlags`.
+const COMPONENTS_GIVEN: u32 = 0x8;
+const MATRIX_KNOWN: u32 = 0x40;
+const QUATERNION_GIVEN: u32 = 0x100;
+
+impl Patch {
+    /// Parses a patch, see the [module documentation](self#format) for the format.
+    ///
+    /// # Errors
+    /// Returns [`InvalidSyntax`](Error::InvalidSyntax) if a line can't be parsed, or if a value is the wrong
+    /// kind for its field.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut edits = Vec::new();
+        for (index, source) in text.lines().enumerate() {
+            let line = index + 1;
+            let source = source.trim();
+            if source.is_empty() || source.starts_with('#') {
+                continue;
+            }
+
+            let (object, rest) =
+                read_token(source).context(InvalidSyntaxSnafu { line, reason: "missing object" })?;
+            let (field, rest) = rest
+                .trim_start()
+                .split_once('=')
+                .context(InvalidSyntaxSnafu { line, reason: "expected `<object> <field> = <value>`" })?;
+            let field =
+                Field::parse(field.trim()).context(InvalidSyntaxSnafu { line, reason: "unknown field" })?;
+            let value = rest.trim();
+
+            let object = match object.parse() {
+                Ok(id) if !source.starts_with('"') => Target::Id(id),
+                _ => Target::Name(object),
+            };
+            let value = match field.is_string() {
+                true => {
+                    let (value, rest) = read_token(value)
+                        .filter(|_| value.starts_with('"'))
+                        .context(InvalidSyntaxSnafu { line, reason: "expected a quoted string" })?;
+                    ensure!(
+                        rest.trim().is_empty(),
+                        InvalidSyntaxSnafu { line, reason: "unexpected text after value" }
+                    );
+                    Value::String(value)
+                }
+                false => {
+                    let value: f64 = value
+                        .parse()
+                        .ok()
+                        .filter(|value: &f64| value.is_finite())
+                        .context(InvalidSyntaxSnafu { line, reason: "expected a finite number" })?;
+                    Value::Number(value)
+                }
+            };
+            edits.push(Edit { line, object, field, value });
+        }
+        Ok(Self { edits })
+    }
+
+    /// Writes every edit into `data`, which must be the same data that `asset` was loaded from. Every edit is
+    /// checked before anything is written, so `data` is left unchanged if any of them are invalid.
+    ///
+    /// # Errors
+    /// Returns [`ObjectNotFound`](Error::ObjectNotFound) if an object doesn't exist,
+    /// [`InvalidField`](Error::InvalidField) if an object doesn't store a field,
+    /// [`LengthMismatch`](Error::LengthMismatch) if a string would change length, or
+    /// [`DataMismatch`](Error::DataMismatch) if `data` isn't what `asset` was loaded from.
+    pub fn apply(&self, asset: &BinaryAsset, data: &mut [u8]) -> Result<(), Error> {
+        let mut writes = Vec::with_capacity(self.edits.len());
+        for edit in &self.edits {
+            writes.push(edit.resolve(asset, data)?);
+        }
+        for (offset, bytes) in writes {
+            data[offset..offset + bytes.len()].copy_from_slice(&bytes);
+        }
+        Ok(())
+    }
+}
+
+impl Edit {
+    /// Checks the edit against `asset` and `data`, and returns where to write the new value and its bytes.
+    fn resolve(&self, asset: &BinaryAsset, data: &[u8]) -> Result<(usize, Vec<u8>), Error> {
+        let line = self.line;
+        let id = match &self.object {
+            Target::Id(id) => Some(*id).filter(|&id| id < asset.nodes.len()),
+            Target::Name(name) => match asset.find_by_name(name) {
+                [id] => Some(*id),
+                _ => None,
+            },
+        };
+        let id = id.with_context(|| ObjectNotFoundSnafu { line, object: self.object.to_string() })?;
+        let range = asset.object_range(id).context(DataMismatchSnafu { line })?;
+        let object = data.get(range.clone()).context(DataMismatchSnafu { line })?;
+
+        let type_name = asset
+            .types
+            .iter()
+            .find(|(_, ids)| ids.contains(&id))
+            .map_or("", |(type_name, _)| type_name.as_str());
+        let invalid_field =
+            || InvalidFieldSnafu { line, id, type_name: type_name.to_owned(), field: self.field };
+        let endian = asset.endian();
+        ensure!(self.field.is_valid(), invalid_field());
+
+        match (&self.value, self.field) {
+            (Value::String(value), field) => {
+                // Nodes and Textures both start with their name, and Textures follow it with their filenames
+                let is_node = asset.nodes.get(id).is_some_and(|node| node.panda_node().is_some());
+                let index = match (field, type_name) {
+                    (Field::Name, "Texture") => 0,
+                    (Field::Name, _) if is_node => 0,
+                    (Field::Filename, "Texture") => 1,
+                    (Field::AlphaFilename, "Texture") => 2,
+                    _ => return invalid_field().fail(),
+                };
+                let string = string_range(object, endian, index).context(DataMismatchSnafu { line })?;
+                ensure!(
+                    string.len() == value.len(),
+                    LengthMismatchSnafu { line, field, expected: string.len(), found: value.len() }
+                );
+                Ok((range.start + string.start, value.as_bytes().to_vec()))
+            }
+            (Value::Number(value), field) => {
+                ensure!(type_name == "TransformState", invalid_field());
+                let flags = read_u32(object, endian).context(DataMismatchSnafu { line })?;
+                let components = flags & COMPONENTS_GIVEN != 0;
+                let rotation_size = match flags & QUATERNION_GIVEN != 0 {
+                    true => 4,
+                    false => 3,
+                };
+                // Components are stored as position, rotation, scale, then shear, followed by the matrix
+                let index = match field {
+                    Field::Position(index) if components => index,
+                    Field::Rotation(index) if components && rotation_size == 3 => 3 + index,
+                    Field::Quaternion(index) if components && rotation_size == 4 => 3 + index,
+                    Field::Scale(index) if components => 3 + rotation_size + index,
+                    Field::Shear(index) if components => 6 + rotation_size + index,
+                    Field::Matrix(index) if flags & MATRIX_KNOWN != 0 => match components {
+                        true => 9 + rotation_size + index,
+                        false => index,
+                    },
+                    _ => return invalid_field().fail(),
+                };
+
+                let bytes = match (asset.use_double(), endian) {
+                    (true, Endian::Little) => value.to_le_bytes().to_vec(),
+                    (true, Endian::Big) => value.to_be_bytes().to_vec(),
+                    (false, Endian::Little) => (*value as f32).to_le_bytes().to_vec(),
+                    (false, Endian::Big) => (*value as f32).to_be_bytes().to_vec(),
+                };
+                let offset = 4 + index * bytes.len();
+                ensure!(offset + bytes.len() <= object.len(), DataMismatchSnafu { line });
+                Ok((range.start + offset, bytes))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Id(id) => write!(f, "{id}"),
+            Self::Name(name) => write!(f, "{name:?}"),
+        }
+    }
+}
+
+/// Reads the first word of `text`, or a quoted string with escapes, and returns it along with the rest.
+fn read_token(text: &str) -> Option<(String, &str)> {
+    let Some(quoted) = text.strip_prefix('"') else {
+        let end = text.find(char::is_whitespace).unwrap_or(text.len());
+        return (end != 0).then(|| (text[..end].to_owned(), &text[end..]));
+    };
+    let mut token = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((index, char)) = chars.next() {
+        match char {
+            '"' => return Some((token, &quoted[index + 1..])),
+            '\\' => token.push(chars.next()?.1),
+            _ => token.push(char),
+        }
+    }
+    None
+}
+
+/// Returns where the contents of the string at `index` are, in an object that starts with strings.
+fn string_range(object: &[u8], endian: Endian, index: usize) -> Option<Range<usize>> {
+    let mut position = 0;
+    for _ in 0..index {
+        position += 2 + usize::from(read_u16(object.get(position..)?, endian)?);
+    }
+    let length = usize::from(read_u16(object.get(position..)?, endian)?);
+    let range = position + 2..position + 2 + length;
+    (range.end <= object.len()).then_some(range)
+}
+
+#[inline]
+fn read_u16(data: &[u8], endian: Endian) -> Option<u16> {
+    let bytes = data.get(..2)?.try_into().ok()?;
+    Some(match endian {
+        Endian::Little => u16::from_le_bytes(bytes),
+        Endian::Big => u16::from_be_bytes(bytes),
+    })
+}
+
+#[inline]
+fn read_u32(data: &[u8], endian: Endian) -> Option<u32> {
+    let bytes = data.get(..4)?.try_into().ok()?;
+    Some(match endian {
+        Endian::Little => u32::from_le_bytes(bytes),
+        Endian::Big => u32::from_be_bytes(bytes),
+    })
+}
@@ -7,6 +7,7 @@
 //! Character node is designed to be a high level animatable node that multiple meshes attach to, as well
 //! as a singular (TODO: check) PartBundle that holds all skinning data
 
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 
 use bevy_internal::animation::{animated_field, AnimationTarget, AnimationTargetId};
@@ -27,11 +28,13 @@ use bevy_internal::render::render_resource::{
 use bevy_internal::tasks::block_on;
 use hashbrown::HashMap;
 use orthrus_core::prelude::*;
+use orthrus_texture::bc;
 use serde::{Deserialize, Serialize};
 use smallvec::{smallvec, SmallVec};
 use snafu::prelude::*;
 
 use crate::bevy_sgi::SgiImageLoader;
+use crate::mesh::{strips_to_list, weld};
 use crate::nodes::color_attrib::ColorType;
 use crate::nodes::cull_face_attrib::CullMode;
 use crate::nodes::dispatch::NodeRef;
@@ -39,11 +42,28 @@ use crate::nodes::model_node::PreserveTransform;
 use crate::nodes::part_bundle::BlendType;
 use crate::nodes::prelude::*;
 use crate::nodes::sampler_state::{FilterType, WrapMode};
+use crate::nodes::texture::Format;
+use crate::nodes::texture_stage::Mode;
 use crate::nodes::transform_blend::TransformEntry;
 use crate::nodes::transform_state::TransformFlags;
 use crate::nodes::transparency_attrib::TransparencyMode;
 use crate::prelude::*;
 
+/// Reports a [`Diagnostic`] for anything we can't convert, which is both logged and collected into
+/// [`Panda3DAsset::diagnostics`].
+macro_rules! report {
+    ($loader:expr, $kind:ident, name: $code:literal, node: $node:expr, $($message:tt)+) => {{
+        let message = format!($($message)+);
+        warn!(name: $code, target: "Panda3DLoader", "{}", message);
+        $loader.assets.diagnostics.push(Diagnostic {
+            kind: DiagnosticKind::$kind,
+            code: $code,
+            node: $node,
+            message,
+        });
+    }};
+}
+
 // TODO on this whole file, try to reduce nesting, should be able to create an internal Error type, return
 // result and error if we encounter unexpected data, instead of the current stupid if let Some() spam.
 
@@ -70,6 +90,10 @@ impl From<DataError> for Panda3DError {
     }
 }
 
+/// Joint indices and weights of every vertex in a mesh, for [`Mesh::ATTRIBUTE_JOINT_INDEX`] and
+/// [`Mesh::ATTRIBUTE_JOINT_WEIGHT`].
+type JointAttributes = (Vec<[u16; 4]>, Vec<[f32; 4]>);
+
 #[derive(Debug, Default, Clone, Copy)]
 struct Effects {
     is_billboard: bool,
@@ -77,14 +101,17 @@ struct Effects {
 }
 
 impl Effects {
-    async fn new(assets: &BinaryAsset, parent: Option<&Effects>, node_index: usize) -> Self {
+    async fn new(
+        assets: &BinaryAsset, loader: &mut AssetLoaderData<'_, '_>, parent: Option<&Effects>,
+        node_index: usize,
+    ) -> Self {
         let mut result = match parent {
             Some(effects) => *effects,
             None => Self::default(),
         };
 
         let Some(effects) = assets.nodes.get_as::<RenderEffects>(node_index) else {
-            warn!(name: "not_a_render_effects", target: "Panda3DLoader",
+            report!(loader, UnknownNode, name: "not_a_render_effects", node: Some(node_index),
                 "Tried to access node {}, but it's not a RenderEffects, ignoring.", node_index);
             return result;
         };
@@ -99,12 +126,12 @@ impl Effects {
                     // setup
                     NodeRef::CharacterJointEffect(_) => {}
                     _ => {
-                        warn!(name: "unknown_render_effect", target: "Panda3DLoader",
+                        report!(loader, UnknownNode, name: "unknown_render_effect", node: Some(*effect as usize),
                             "Unknown RenderEffects: node {}, ignoring.", effect)
                     }
                 },
                 None => {
-                    warn!(name: "unexpected_node_index", target: "Panda3DLoader",
+                    report!(loader, UnknownNode, name: "unexpected_node_index", node: Some(*effect as usize),
                         "Tried to access node {}, but it doesn't exist, ignoring.", effect)
                 }
             }
@@ -134,19 +161,18 @@ impl BinaryAsset {
                 // This can either be a ModelNode or a ModelRoot, either way we need to spawn a new node to
                 // attach stuff to.
                 let (entity, effects) =
-                    self.handle_panda_node(loader.world, parent, effects, net_nodes, node, node_index).await;
+                    self.handle_panda_node(loader, parent, effects, net_nodes, node, node_index).await;
 
                 // TODO: handle transform: Local correctly?
                 if node.attributes != 0 {
-                    warn!(name: "model_node_attribs_unhandled", target: "Panda3DLoader",
+                    report!(loader, UnhandledAttrib, name: "model_node_attribs_unhandled", node: Some(node_index),
                         "ModelNode {} has attributes attached that we don't handle, please fix!", node_index);
                 }
 
-                for child_ref in &node.child_refs {
-                    if child_ref.1 != 0 {
-                        warn!(name: "nonzero_node_sort", target: "Panda3DLoader",
-                            "Node {} has a child with non-zero sort order, please fix!", node_index);
-                    }
+                let children = node.sorted_children();
+                let child_refs =
+                    self.flatten_children(loader, entity, joint_data, net_nodes, &children).await;
+                for child_ref in child_refs {
                     Box::pin(self.recurse_nodes(
                         loader,
                         Some(entity),
@@ -161,13 +187,38 @@ impl BinaryAsset {
             Some(NodeRef::PandaNode(node)) => {
                 // This is just a plain ol' node, so just process its data and explore all children.
                 let (entity, effects) =
-                    self.handle_panda_node(loader.world, parent, effects, net_nodes, node, node_index).await;
+                    self.handle_panda_node(loader, parent, effects, net_nodes, node, node_index).await;
 
-                for child_ref in &node.child_refs {
-                    if child_ref.1 != 0 {
-                        warn!(name: "nonzero_node_sort", target: "Panda3DLoader",
-                            "Node {} has a child with non-zero sort order, please fix!", node_index);
-                    }
+                let children = node.sorted_children();
+                let child_refs =
+                    self.flatten_children(loader, entity, joint_data, net_nodes, &children).await;
+                for child_ref in child_refs {
+                    Box::pin(self.recurse_nodes(
+                        loader,
+                        Some(entity),
+                        Some(&effects),
+                        joint_data,
+                        net_nodes,
+                        child_ref.0 as usize,
+                    ))
+                    .await;
+                }
+            }
+            Some(
+                NodeRef::SheetNode(SheetNode { inner: node }) | NodeRef::TextNode(TextNode { inner: node }),
+            ) => {
+                // These generate their geometry at runtime, which Panda3D doesn't store, so treat them like a
+                // plain node. Fonts and GUI models are the usual source of these.
+                report!(loader, SkippedFeature, name: "generated_node_skipped", node: Some(node_index),
+                    "Node {} is a {}, which generates its geometry at runtime, only converting its children.",
+                    node_index, self.nodes.get(node_index).map_or("node", |node| node.type_name()));
+                let (entity, effects) =
+                    self.handle_panda_node(loader, parent, effects, net_nodes, node, node_index).await;
+
+                let children = node.sorted_children();
+                let child_refs =
+                    self.flatten_children(loader, entity, joint_data, net_nodes, &children).await;
+                for child_ref in child_refs {
                     Box::pin(self.recurse_nodes(
                         loader,
                         Some(entity),
@@ -183,24 +234,21 @@ impl BinaryAsset {
                 // Characters are helper nodes that group together multiple meshes together with
                 // animation data. TODO: add a marker Component?
                 let (entity, effects) =
-                    self.handle_panda_node(loader.world, parent, effects, net_nodes, node, node_index).await;
+                    self.handle_panda_node(loader, parent, effects, net_nodes, node, node_index).await;
 
-                if node.bundle_refs.len() != 1 {
-                    warn!(name: "unexpected_character_node", target: "Panda3DLoader",
-                        "Character Node {} has more than one associated CharacterJointBundle, ignoring.", node_index);
-                }
-
-                // First, let's process the `CharacterJointBundle` into [`SkinnedMesh`] data, as well as any
-                // net nodes we spawned to add an [`AnimationTarget`]. TODO: make a
-                // non-recursive function to simplify this mess?
+                // First, let's process each `CharacterJointBundle` into [`SkinnedMesh`] data, as well as any
+                // net nodes we spawned to add an [`AnimationTarget`]. Multi-part Characters have one bundle per
+                // part, which all share the same mesh data. TODO: make a non-recursive function to simplify
+                // this mess?
                 let mut net_nodes = BTreeMap::new();
-                let (inverse_bindposes, joints) = self.convert_joint_bundle(
-                    loader,
-                    entity,
-                    None,
-                    &mut net_nodes,
-                    node.bundle_refs[0] as usize,
-                );
+                let mut inverse_bindposes = Vec::new();
+                let mut joints = Vec::new();
+                for bundle_ref in &node.bundle_refs {
+                    let (bundle_inverse_bindposes, bundle_joints) =
+                        self.convert_joint_bundle(loader, entity, None, &mut net_nodes, *bundle_ref as usize);
+                    inverse_bindposes.extend(bundle_inverse_bindposes);
+                    joints.extend(bundle_joints);
+                }
 
                 // TODO: migrate to bevy_gltf's new enum-based system so this is less dumb
                 let label = format!("Bindpose{}", loader.assets.bindposes.len());
@@ -213,11 +261,7 @@ impl BinaryAsset {
                 let skinned_mesh = SkinnedMesh { inverse_bindposes, joints };
 
                 // Then, we need to process all child nodes
-                for child_ref in &node.child_refs {
-                    if child_ref.1 != 0 {
-                        warn!(name: "nonzero_node_sort", target: "Panda3DLoader",
-                            "Node {} has a child with non-zero sort order, please fix!", node_index);
-                    }
+                for child_ref in &node.sorted_children() {
                     Box::pin(self.recurse_nodes(
                         loader,
                         Some(entity),
@@ -241,7 +285,7 @@ impl BinaryAsset {
                     || !node.child_refs.is_empty()
                     || !node.stashed_refs.is_empty()
                 {
-                    warn!(name: "unhandled_node_attribs", target: "Panda3DLoader",
+                    report!(loader, UnhandledAttrib, name: "unhandled_node_attribs", node: Some(node_index),
                         "PandaNode attribs attached to node {} are non-zero! Please fix.", node_index);
                 }
 
@@ -250,7 +294,7 @@ impl BinaryAsset {
             Some(NodeRef::GeomNode(node)) => {
                 // We need to create and attach actual mesh data to this node.
                 let (entity, effects) =
-                    self.handle_panda_node(loader.world, parent, effects, net_nodes, node, node_index).await;
+                    self.handle_panda_node(loader, parent, effects, net_nodes, node, node_index).await;
 
                 //TODO handle tags, collide_mask?
 
@@ -266,11 +310,10 @@ impl BinaryAsset {
                 }
 
                 // Then, we need to process all child nodes
-                for child_ref in &node.child_refs {
-                    if child_ref.1 != 0 {
-                        warn!(name: "nonzero_node_sort", target: "Panda3DLoader",
-                            "Node {} has a child with non-zero sort order, please fix!", node_index);
-                    }
+                let children = node.sorted_children();
+                let child_refs =
+                    self.flatten_children(loader, entity, joint_data, net_nodes, &children).await;
+                for child_ref in child_refs {
                     Box::pin(self.recurse_nodes(
                         loader,
                         Some(entity),
@@ -282,9 +325,10 @@ impl BinaryAsset {
                     .await;
                 }
             }
-            Some(node) => println!("Unexpected node {:?} in recurse_nodes", node),
+            Some(node) => report!(loader, UnknownNode, name: "unexpected_node", node: Some(node_index),
+                "Node {} is a {}, which isn't expected in recurse_nodes, ignoring.", node_index, node.type_name()),
             None => {
-                warn!(name: "unexpected_node_index", target: "Panda3DLoader",
+                report!(loader, UnknownNode, name: "unexpected_node_index", node: Some(node_index),
                     "Tried to access node {}, but it doesn't exist, ignoring.", node_index);
             }
         }
@@ -292,7 +336,7 @@ impl BinaryAsset {
 
     /// Constructs a [`Transform`] from a given `TransformState`. Used for any node that inherits from
     /// `PandaNode`.
-    fn handle_transform_state(&self, node_index: usize) -> Transform {
+    fn handle_transform_state(&self, loader: &mut AssetLoaderData<'_, '_>, node_index: usize) -> Transform {
         if let Some(node) = self.nodes.get_as::<TransformState>(node_index) {
             if node.flags.contains(TransformFlags::Identity) {
                 Transform::default()
@@ -310,44 +354,66 @@ impl BinaryAsset {
                 };
                 let scale = node.scale;
                 if node.shear != Vec3::ZERO {
-                    warn!(name: "shear_transform_unimplemented", target: "Panda3DLoader",
+                    report!(loader, SkippedFeature, name: "shear_transform_unimplemented", node: Some(node_index),
                         "Detected a non-zero shear on node {}, which is currently unsupported, ignoring.", node_index);
                 }
                 Transform::from_translation(translation).with_rotation(rotation).with_scale(scale)
             } else {
-                warn!(name: "unexpected_transform_state", target: "Panda3DLoader",
+                report!(loader, InvalidData, name: "unexpected_transform_state", node: Some(node_index),
                     "Potentially malformed TransformState: node {}, ignoring.", node_index);
                 Transform::default()
             }
         } else {
-            warn!(name: "not_a_transform_state", target: "Panda3DLoader",
+            report!(loader, UnknownNode, name: "not_a_transform_state", node: Some(node_index),
                 "Tried to access node {}, but it's not a TransformState, ignoring.", node_index);
             Transform::default()
         }
     }
 
+    /// Converts a joint's local matrix into a [`Transform`] using the configured [`JointDecomposition`], and
+    /// reports the joint if the conversion is lossy.
+    fn convert_joint_transform(loader: &mut AssetLoaderData<'_, '_>, name: &str, matrix: Mat4) -> Transform {
+        let epsilon = loader.settings.decomposition_epsilon;
+        let (transform, shear) = match loader.settings.joint_decomposition {
+            JointDecomposition::Matrix => (Transform::from_matrix(matrix), Vec3::ZERO),
+            JointDecomposition::Hpr => decompose_hpr(matrix, epsilon),
+        };
+
+        // Bevy can't represent shear, so compare against the original to see how much was lost
+        let error = (transform.compute_matrix() - matrix)
+            .to_cols_array()
+            .into_iter()
+            .fold(0.0f32, |error, difference| error.max(difference.abs()));
+        if error > epsilon {
+            report!(loader, Lossy, name: "lossy_joint_transform", node: None,
+                "Joint {} can't be represented exactly (shear {}, max error {}), it may deform incorrectly.", name, shear, error);
+            loader.assets.lossy_joints.push(LossyJoint { name: name.to_owned(), shear, error });
+        }
+        transform
+    }
+
     /// Handles all data relevant to `PandaNode` entities, and spawns a new object into the world.
     async fn handle_panda_node(
-        &self, world: &mut World, parent: Option<Entity>, effects: Option<&Effects>,
+        &self, loader: &mut AssetLoaderData<'_, '_>, parent: Option<Entity>, effects: Option<&Effects>,
         net_nodes: Option<&BTreeMap<usize, Entity>>, node: &PandaNode, node_index: usize,
     ) -> (Entity, Effects) {
         // TODO: We don't current handle RenderState, for now, grab it and check if it's empty
         if let Some(render_state) = self.nodes.get_as::<RenderState>(node.state_ref as usize) {
             if !render_state.attrib_refs.is_empty() {
-                warn!(name: "unhandled_render_state", target: "Panda3DLoader",
+                report!(loader, UnhandledAttrib, name: "unhandled_render_state", node: Some(node_index),
                     "Non-empty RenderState attached to node {} being ignored! Please fix.", node_index);
             }
         } else {
-            warn!(name: "not_a_render_state", target: "Panda3DLoader",
+            report!(loader, UnknownNode, name: "not_a_render_state", node: Some(node.state_ref as usize),
                 "Tried to access node {}, but it's not a RenderState, ignoring.", node.state_ref);
         }
 
         // Handle our Transform so we can spawn a new entity
-        let transform = self.handle_transform_state(node.transform_ref as usize);
+        let transform = self.handle_transform_state(loader, node.transform_ref as usize);
 
         // We only see what data is attached to a RenderEffects so we can pass it down to child nodes, TODO:
         // figure out proper inheritance
-        let effects = Effects::new(self, effects, node.effects_ref as usize).await;
+        let effects = Effects::new(self, loader, effects, node.effects_ref as usize).await;
 
         // Check all of the parameters I've been ignoring, warn if any of them aren't the default, TODO
         if node.draw_control_mask != 0
@@ -356,24 +422,27 @@ impl BinaryAsset {
             || node.bounds_type != BoundsType::Default
             || !node.tag_data.is_empty()
         {
-            warn!(name: "unhandled_node_attribs", target: "Panda3DLoader",
+            report!(loader, UnhandledAttrib, name: "unhandled_node_attribs", node: Some(node_index),
                 "PandaNode attribs attached to node {} are non-zero! Please fix.", node_index);
         }
         if !node.stashed_refs.is_empty() {
-            warn!(name: "unexpected_stashed_refs", target: "Panda3DLoader",
+            report!(loader, SkippedFeature, name: "unexpected_stashed_refs", node: Some(node_index),
                 "Node {} has stashed nodes, but this loader doesn't support those. Please fix!", node_index);
         }
 
         // Finally, let's check if we've already spawned a node to add an AnimationTarget previously. If it
-        // isn't in the lookup, then let's spawn a new one.
-        let entity =
-            net_nodes.and_then(|node_lookup| node_lookup.get(&node_index).copied()).unwrap_or_else(|| {
-                world.spawn((transform, Visibility::default(), Name::new(node.name.clone()))).id()
+        // isn't in the lookup, or it's an instance and the node was already parented to another instance, then
+        // let's spawn a new one.
+        let entity = net_nodes
+            .and_then(|node_lookup| node_lookup.get(&node_index).copied())
+            .filter(|&entity| loader.world.get::<Parent>(entity).is_none())
+            .unwrap_or_else(|| {
+                loader.world.spawn((transform, Visibility::default(), Name::new(node.name.clone()))).id()
             });
 
         // Even if the node was already created, it wasn't parented, so parent it now.
         if let Some(parent) = parent {
-            world.entity_mut(parent).add_child(entity);
+            loader.world.entity_mut(parent).add_child(entity);
         }
 
         (entity, effects)
@@ -396,89 +465,82 @@ impl BinaryAsset {
                 // (the Character) Entity so our AnimationPath includes it, which may otherwise have
                 // unexpected side effects when trying to play animations on nodes that share a name.
 
-                // Let's start by validating the PartBundle, which should share the same name as the Character
-                // above us.
-                if node.anim_preload_ref.is_some()
-                    || node.blend_type != BlendType::NormalizedLinear
-                    || node.anim_blend_flag
-                    || node.frame_blend_flag
-                {
-                    warn!(name: "unhandled_part_bundle", target: "Panda3DLoader",
-                        "PartBundle attribs on node {} are unhandled, please fix!", node_index);
-                }
-                // TODO: if we find an instance where this isn't the case, we'll need to spawn a node
-                // separately to store each PartGroup, but for now this isn't an issue.
-                if node.child_refs.len() != 1 {
-                    warn!(name: "unexpected_part_bundle", target: "Panda3DLoader",
-                        "Unexpected number of child nodes on PartBundle node {}, ignoring.", node_index);
+                // Panda3D's blending options don't map directly onto Bevy's AnimationGraph, so expose them on
+                // the AnimationPlayer's Entity, which we need to attach to our parent (the Character).
+                if node.anim_preload_ref.is_some() {
+                    report!(loader, SkippedFeature, name: "unhandled_part_bundle", node: Some(node_index),
+                        "PartBundle node {} has preloaded animations, which are unhandled, ignoring.", node_index);
                 }
-
-                // Let's also grab the PartGroup so we can make sure it's what we expect.
-                let Some(part_group) = self.nodes.get_as::<PartGroup>(node.child_refs[0] as usize) else {
-                    warn!(name: "not_a_part_group", target: "Panda3DLoader",
-                        "Tried to get node {}, but it wasn't a PartGroup. Unable to create joints, returning.", node.child_refs[0]);
-                    return (inverse_bindposes, joints);
+                let blend = PartBundleBlend {
+                    blend_type: node.blend_type.into(),
+                    anim_blend: node.anim_blend_flag,
+                    frame_blend: node.frame_blend_flag,
                 };
-
-                if part_group.name != "<skeleton>" {
-                    warn!(name: "unexpected_part_group_name", target: "Panda3DLoader",
-                        "Encountered a PartGroup that wasn't named <skeleton>, node {}. This model may not be imported correctly.", node.child_refs[0]);
+                // Characters with multiple bundles share a single AnimationPlayer
+                if !loader.world.entity(parent).contains::<AnimationPlayer>() {
+                    loader.world.entity_mut(parent).insert(AnimationPlayer::default());
+                    loader.assets.animators.push(parent);
                 }
+                loader.world.entity_mut(parent).insert(blend);
+
+                // Each PartGroup gets its own node, which is usually just "<skeleton>", but can also include
+                // "morph" for morph sliders, or any other group. Its animation tables use the same path.
+                for group_ref in &node.child_refs {
+                    let Some(part_group) = self.nodes.get_as::<PartGroup>(*group_ref as usize) else {
+                        report!(loader, UnknownNode, name: "not_a_part_group", node: Some(*group_ref as usize),
+                            "Tried to get node {}, but it wasn't a PartGroup. Unable to create its joints, ignoring.", group_ref);
+                        continue;
+                    };
 
-                // We first need to create a new AnimationPlayer and attach it to our parent. It cannot have
-                // animation tables assigned to itself, so we'll only add an AnimationTarget to the skeleton
-                // on down.
-                loader.world.entity_mut(parent).insert(AnimationPlayer::default());
-                loader.assets.animators.push(parent);
+                    if part_group.name == "morph" {
+                        if !part_group.child_refs.is_empty() {
+                            report!(loader, SkippedFeature, name: "morph_sliders_unimplemented", node: Some(node_index),
+                                "PartBundle node {} has morph sliders, but they're currently unimplemented, ignoring.", node_index);
+                        }
+                        continue;
+                    }
 
-                let parent_name = Name::new(node.name.clone());
-                let name = Name::new(part_group.name.clone());
-                let animation_context =
-                    AnimationContext { root: parent, path: smallvec![parent_name, name.clone()] };
+                    let parent_name = Name::new(node.name.clone());
+                    let name = Name::new(part_group.name.clone());
+                    let animation_context =
+                        AnimationContext { root: parent, path: smallvec![parent_name, name.clone()] };
 
-                let skeleton = loader
-                    .world
-                    .spawn((
-                        AnimationTarget {
-                            id: AnimationTargetId::from_names(animation_context.path.iter()),
-                            player: animation_context.root,
-                        },
-                        Transform::from_matrix(node.root_transform),
-                        Visibility::default(),
-                        name.clone(),
-                    ))
-                    .id();
+                    let id = loader.joint_target(&animation_context.path);
+                    let group = loader
+                        .world
+                        .spawn((
+                            AnimationTarget { id, player: animation_context.root },
+                            Transform::from_matrix(node.root_transform),
+                            Visibility::default(),
+                            name.clone(),
+                        ))
+                        .id();
 
-                // Make sure to parent it correctly
-                loader.world.entity_mut(parent).add_child(skeleton);
+                    // Make sure to parent it correctly
+                    loader.world.entity_mut(parent).add_child(group);
 
-                inverse_bindposes.push(node.root_transform.inverse());
-                joints.push(skeleton);
+                    inverse_bindposes.push(node.root_transform.inverse());
+                    joints.push(group);
 
-                for child_ref in &part_group.child_refs {
-                    let (child_inverse_bindposes, child_joints) = self.convert_joint_bundle(
-                        loader,
-                        skeleton,
-                        Some(animation_context.clone()),
-                        net_nodes,
-                        *child_ref as usize,
-                    );
-                    inverse_bindposes.extend(child_inverse_bindposes);
-                    joints.extend(child_joints);
+                    for child_ref in &part_group.child_refs {
+                        let (child_inverse_bindposes, child_joints) = self.convert_joint_bundle(
+                            loader,
+                            group,
+                            Some(animation_context.clone()),
+                            net_nodes,
+                            *child_ref as usize,
+                        );
+                        inverse_bindposes.extend(child_inverse_bindposes);
+                        joints.extend(child_joints);
+                    }
                 }
             }
             Some(NodeRef::CharacterJoint(node)) => {
                 // We're at an actual skeletal joint.
 
                 let name = Name::new(node.name.clone());
-                let joint = loader
-                    .world
-                    .spawn((
-                        Transform::from_matrix(node.default_value),
-                        Visibility::default(),
-                        name.clone(),
-                    ))
-                    .id();
+                let transform = Self::convert_joint_transform(loader, &node.name, node.default_value);
+                let joint = loader.world.spawn((transform, Visibility::default(), name.clone())).id();
 
                 // Make sure to parent it correctly
                 loader.world.entity_mut(parent).add_child(joint);
@@ -490,16 +552,14 @@ impl BinaryAsset {
                 let mut animation_context = animation_context.unwrap();
                 animation_context.path.push(name);
                 println!("Joint {:?}", animation_context.path);
-                loader.world.entity_mut(joint).insert(AnimationTarget {
-                    id: AnimationTargetId::from_names(animation_context.path.iter()),
-                    player: animation_context.root,
-                });
+                let id = loader.joint_target(&animation_context.path);
+                loader.world.entity_mut(joint).insert(AnimationTarget { id, player: animation_context.root });
 
                 // Check any net transform nodes and try to create them. Theoretically, only ModelNode has the
                 // parameter needed to support a transform: Net, so let's just get the node as that.
                 for net_node_ref in &node.net_node_refs {
                     let Some(node) = self.nodes.get_as::<ModelNode>(*net_node_ref as usize) else {
-                        warn!(name: "not_a_model_node", target: "Panda3DLoader",
+                        report!(loader, UnknownNode, name: "not_a_model_node", node: Some(*net_node_ref as usize),
                             "Tried to get node {} when trying to construct Net Transforms, but it wasn't a ModelNode, ignoring.", *net_node_ref);
                         continue;
                     };
@@ -507,20 +567,18 @@ impl BinaryAsset {
                     // doesn't have a mesh. We'll handle its effects and etc once we encounter it normally
                     // in the tree.
                     let name = Name::new(node.name.clone());
-                    let transform = self.handle_transform_state(node.transform_ref as usize);
+                    let transform = self.handle_transform_state(loader, node.transform_ref as usize);
                     // Make sure we don't pollute our parent's context
                     let mut animation_context = animation_context.clone();
                     animation_context.path.push(name.clone());
+                    let id = loader.joint_target(&animation_context.path);
                     let net_node = loader
                         .world
                         .spawn((
                             transform,
                             Visibility::default(),
                             name,
-                            AnimationTarget {
-                                id: AnimationTargetId::from_names(animation_context.path.iter()),
-                                player: animation_context.root,
-                            },
+                            AnimationTarget { id, player: animation_context.root },
                         ))
                         .id();
 
@@ -539,9 +597,10 @@ impl BinaryAsset {
                     joints.extend(child_joints);
                 }
             }
-            Some(node) => println!("Unexpected node {:?} in convert_joint_bundle", node),
+            Some(node) => report!(loader, UnknownNode, name: "unexpected_node", node: Some(node_index),
+                "Node {} is a {}, which isn't expected in convert_joint_bundle, ignoring.", node_index, node.type_name()),
             None => {
-                warn!(name: "unexpected_node_index", target: "Panda3DLoader",
+                report!(loader, UnknownNode, name: "unexpected_node_index", node: Some(node_index),
                     "Tried to access node {}, but it doesn't exist, ignoring.", node_index);
             }
         }
@@ -554,18 +613,40 @@ impl BinaryAsset {
         render_ref: usize, parent: Entity,
     ) {
         let Some(geom_node) = self.nodes.get_as::<Geom>(geom_ref) else {
-            warn!(name: "invalid_geom_node", target: "Panda3DLoader",
+            report!(loader, UnknownNode, name: "invalid_geom_node", node: Some(geom_ref),
                 "Tried to load node {}, but it wasn't a Geom, returning.", geom_ref);
             return;
         };
         let Some(render_state) = self.nodes.get_as::<RenderState>(render_ref) else {
-            warn!(name: "invalid_geom_node", target: "Panda3DLoader",
+            report!(loader, UnknownNode, name: "invalid_geom_node", node: Some(render_ref),
                 "Tried to load node {}, but it wasn't a RenderState, returning.", render_ref);
             return;
         };
+        // GeomPoints aren't parsed, and are only used by fonts to mark the advance of each glyph
+        let is_point = !geom_node.primitive_refs.is_empty()
+            && geom_node.primitive_refs.iter().all(|&primitive| {
+                self.nodes
+                    .get_as::<CustomObject>(primitive as usize)
+                    .is_some_and(|object| object.type_name == "GeomPoints")
+            });
+        if is_point {
+            report!(loader, SkippedFeature, name: "geom_points_skipped", node: Some(geom_ref),
+                "Geom {} only contains points, which aren't converted, ignoring.", geom_ref);
+            return;
+        }
 
         let entity = loader.world.spawn((Transform::default(), Visibility::default())).id();
         loader.world.entity_mut(parent).add_child(entity);
+        if let Some(bin) = self.cull_bin(render_state) {
+            loader.world.entity_mut(entity).insert(bin);
+        }
+
+        // Instances of the same Geom can reuse its assets, unless it's skinned to a different set of joints
+        let shared = loader.settings.instancing == InstanceMode::Share && joint_data.is_none();
+        if let Some((mesh, material)) = loader.geom_cache.get(&(geom_ref, render_ref)).filter(|_| shared) {
+            loader.world.entity_mut(entity).insert((Mesh3d(mesh.clone()), MeshMaterial3d(material.clone())));
+            return;
+        }
 
         // Now, let's create a Material.
         let label = format!("Material{}", loader.assets.materials.len());
@@ -574,23 +655,37 @@ impl BinaryAsset {
         let material = loader.context.add_labeled_asset(label, material);
         loader.assets.materials.push(material.clone());
 
-        // TODO: remove unwrap
         let label = format!("Mesh{}", loader.assets.meshes.len());
-        let mesh = self.create_mesh(loader, joint_data, entity, geom_ref, geom_node).unwrap();
+        let mut mesh = match self.create_mesh(loader, joint_data, entity, geom_ref, geom_node) {
+            Ok(mesh) => mesh,
+            Err(error) => {
+                report!(loader, InvalidData, name: "invalid_geom_node", node: Some(geom_ref),
+                    "Unable to create a mesh for node {}: {}, ignoring.", geom_ref, error);
+                return;
+            }
+        };
+        if loader.settings.optimize_meshes {
+            mesh = self.optimize_mesh(loader, mesh, geom_node);
+        }
         let mesh = loader.context.add_labeled_asset(label, mesh);
         loader.assets.meshes.push(mesh.clone());
 
+        if shared {
+            loader.geom_cache.insert((geom_ref, render_ref), (mesh.clone(), material.clone()));
+        }
         loader.world.entity_mut(entity).insert((Mesh3d(mesh), MeshMaterial3d(material)));
     }
 
-    fn convert_wrap_mode(&self, mode: WrapMode, node_index: usize) -> ImageAddressMode {
+    fn convert_wrap_mode(
+        &self, loader: &mut AssetLoaderData<'_, '_>, mode: WrapMode, node_index: usize,
+    ) -> ImageAddressMode {
         match mode {
             WrapMode::Clamp => ImageAddressMode::ClampToEdge,
             WrapMode::Repeat => ImageAddressMode::Repeat,
             WrapMode::Mirror => ImageAddressMode::MirrorRepeat,
             WrapMode::BorderColor => ImageAddressMode::ClampToBorder,
             _ => {
-                warn!(name: "unexpected_wrap_mode", target: "Panda3DLoader",
+                report!(loader, UnhandledAttrib, name: "unexpected_wrap_mode", node: Some(node_index),
                     "Unsupported WrapMode encountered on node {}", node_index);
                 ImageAddressMode::default()
             }
@@ -630,7 +725,7 @@ impl BinaryAsset {
 
         for attrib_ref in &render_state.attrib_refs {
             if attrib_ref.1 != 0 {
-                warn!(name: "nonzero_override", target: "Panda3DLoader",
+                report!(loader, UnhandledAttrib, name: "nonzero_override", node: Some(attrib_ref.0 as usize),
                     "Node {} has a non-zero override value, please fix!", attrib_ref.0);
             }
             match self.nodes.get(attrib_ref.0 as usize) {
@@ -640,7 +735,7 @@ impl BinaryAsset {
                         || !attrib.off_stage_refs.is_empty()
                         || attrib.on_stages.len() != 1
                     {
-                        warn!(name: "unexpected_texture_attrib", target: "Panda3DLoader",
+                        report!(loader, UnhandledAttrib, name: "unexpected_texture_attrib", node: Some(attrib_ref.0 as usize),
                             "Creating a Texture using node {}, but it has unexpected on/off nodes, ignoring.", attrib_ref.0);
                         if attrib.on_stages.is_empty() {
                             continue;
@@ -653,7 +748,7 @@ impl BinaryAsset {
                         || stage_node.priority != 0
                         || stage_node.implicit_sort != 1
                     {
-                        warn!(name: "unexpected_stage_node", target: "Panda3DLoader",
+                        report!(loader, UnhandledAttrib, name: "unexpected_stage_node", node: Some(attrib_ref.0 as usize),
                             "Encountered unexpected StageNode data on node {}, ignoring.", attrib_ref.0);
                     }
 
@@ -661,12 +756,12 @@ impl BinaryAsset {
                     let Some(texture_stage) =
                         self.nodes.get_as::<TextureStage>(stage_node.texture_stage_ref as usize)
                     else {
-                        warn!(name: "not_a_texture_stage", target: "Panda3DLoader",
+                        report!(loader, UnknownNode, name: "not_a_texture_stage", node: Some(stage_node.texture_stage_ref as usize),
                             "Tried to get node {}, but it wasn't a TextureStage, ignoring.", stage_node.texture_stage_ref);
                         continue;
                     };
                     if *texture_stage != TextureStage::default() {
-                        warn!(name: "unhandled_texture_stage", target: "Panda3DLoader",
+                        report!(loader, UnhandledAttrib, name: "unhandled_texture_stage", node: Some(stage_node.texture_stage_ref as usize),
                             "TextureStage Node {} is not the default, please fix!", stage_node.texture_stage_ref);
                     }
 
@@ -677,7 +772,7 @@ impl BinaryAsset {
                         loader.assets.textures[*image_id].clone()
                     } else {
                         let Some(texture) = self.nodes.get_as::<Texture>(texture_ref) else {
-                            warn!(name: "not_a_texture", target: "Panda3DLoader",
+                            report!(loader, UnknownNode, name: "not_a_texture", node: Some(texture_ref),
                                 "Tried to get node {}, but it wasn't a Texture, ignoring.", texture_ref);
                             continue;
                         };
@@ -694,7 +789,7 @@ impl BinaryAsset {
                         {
                             Ok(image) => image.take(),
                             Err(error) => {
-                                warn!(name: "image_file_error", target: "Panda3DLoader",
+                                report!(loader, InvalidData, name: "image_file_error", node: Some(texture_ref),
                                     "Tried to load file {}, got back error {}", texture.filename, error);
                                 continue;
                             }
@@ -712,7 +807,7 @@ impl BinaryAsset {
                                 {
                                     Ok(image) => image.take(),
                                     Err(error) => {
-                                        warn!(name: "image_file_error", target: "Panda3DLoader",
+                                        report!(loader, InvalidData, name: "image_file_error", node: Some(texture_ref),
                                             "Tried to load file {}, got back error {}", texture.alpha_filename, error);
                                         continue;
                                     }
@@ -725,52 +820,73 @@ impl BinaryAsset {
                         // If an alpha texture exists, then we need to merge the two into a single Image.
                         // TODO: enforce texture.format?
                         let mut image = if let Some(alpha_image) = alpha_image {
-                            // Image.convert has very limited support, so use a match to filter out the couple
-                            // we care about, and convert to RGBA
-                            let mut rgb_image = match rgb_image.texture_descriptor.format {
-                                TextureFormat::R8Unorm | TextureFormat::Rg8Unorm => {
-                                    rgb_image.convert(TextureFormat::Rgba8UnormSrgb).unwrap()
-                                }
-                                TextureFormat::Rgba8UnormSrgb => rgb_image.clone(),
-                                _ => {
-                                    warn!(name: "combine_alpha_no_convert", target: "Panda3DLoader",
-                                        "Material {} has a separate alpha channel, but the RGB file {} was not in a supported format! Ignoring.", texture_ref, texture.filename);
-                                    continue;
-                                }
+                            // Image.convert has very limited support, so decode both images to RGBA ourselves
+                            let Some(mut rgba) = to_rgba8(&rgb_image) else {
+                                report!(loader, SkippedFeature, name: "combine_alpha_no_convert", node: Some(texture_ref),
+                                    "Material {} has a separate alpha channel, but the RGB file {} was not in a supported format! Ignoring.", texture_ref, texture.filename);
+                                continue;
+                            };
+                            let Some(alpha) = to_rgba8(&alpha_image) else {
+                                report!(loader, SkippedFeature, name: "unsupported_alpha_image", node: Some(texture_ref),
+                                    "Trying to merge alpha texture {}, but it's not in a supported format! Ignoring.", texture.alpha_filename);
+                                continue;
                             };
 
-                            // The only supported format right now is R8, theoretically we could support any
-                            // kind of Rgba8 and just grab the alpha from that, TODO?
-                            match alpha_image.texture_descriptor.format {
-                                TextureFormat::R8Unorm => (),
-                                _ => {
-                                    warn!(name: "unsupported_alpha_image", target: "Panda3DLoader",
-                                        "Trying to merge alpha texture {}, but it's not in a supported format! Ignoring.", texture.alpha_filename);
+                            // Panda3D uses the grey level of the alpha image, which is just the value for
+                            // greyscale images
+                            let alpha: Vec<u8> = alpha
+                                .chunks_exact(4)
+                                .map(|pixel| {
+                                    let [r, g, b] = [pixel[0], pixel[1], pixel[2]].map(u32::from);
+                                    ((r * 299 + g * 587 + b * 114 + 500) / 1000) as u8
+                                })
+                                .collect();
+
+                            let size = (rgb_image.width(), rgb_image.height());
+                            let alpha_size = (alpha_image.width(), alpha_image.height());
+                            let alpha = match (alpha_size == size, loader.settings.resize_alpha) {
+                                (true, _) => alpha,
+                                (false, true) => resize_channel(&alpha, alpha_size, size),
+                                (false, false) => {
+                                    report!(loader, SkippedFeature, name: "alpha_size_mismatch", node: Some(texture_ref),
+                                        "Alpha texture {} is {:?}, but the RGB file {} is {:?}! Ignoring.", texture.alpha_filename, alpha_size, texture.filename, size);
                                     continue;
                                 }
-                            }
+                            };
 
                             // For the entire image, replace the alpha u8 with the one from alpha image
-                            let height = rgb_image.texture_descriptor.size.height;
-                            let width = rgb_image.texture_descriptor.size.width;
-                            for y in 0..height {
-                                for x in 0..width {
-                                    let alpha_pixel = alpha_image.data[(y * width + x) as usize];
-                                    rgb_image.data[((y * width + x) * 4) as usize + 3] = alpha_pixel;
-                                }
+                            for (pixel, alpha) in rgba.chunks_exact_mut(4).zip(alpha) {
+                                pixel[3] = alpha;
                             }
+                            let mut rgb_image = rgb_image;
+                            rgb_image.data = rgba;
+                            rgb_image.texture_descriptor.format = TextureFormat::Rgba8UnormSrgb;
+                            // Only the first mip level was decoded, so let Bevy regenerate the rest
+                            rgb_image.texture_descriptor.mip_level_count = 1;
                             rgb_image
                         } else {
                             rgb_image
                         };
 
+                        // Bevy's image loaders default to sRGB, which shades data textures incorrectly
+                        let color_space = loader.settings.texture_color_space;
+                        let format = image.texture_descriptor.format;
+                        image.texture_descriptor.format =
+                            match color_space.is_srgb(texture.format, texture_stage.mode) {
+                                true => format.add_srgb_suffix(),
+                                false => format.remove_srgb_suffix(),
+                            };
+
                         // Now that we have this new image, we need to configure its properties
                         let descriptor = image.sampler.get_or_init_descriptor();
                         descriptor.label = Some(texture.name.clone());
 
-                        descriptor.address_mode_u = self.convert_wrap_mode(texture.wrap_u, texture_ref);
-                        descriptor.address_mode_v = self.convert_wrap_mode(texture.wrap_v, texture_ref);
-                        descriptor.address_mode_w = self.convert_wrap_mode(texture.wrap_w, texture_ref);
+                        descriptor.address_mode_u =
+                            self.convert_wrap_mode(loader, texture.wrap_u, texture_ref);
+                        descriptor.address_mode_v =
+                            self.convert_wrap_mode(loader, texture.wrap_v, texture_ref);
+                        descriptor.address_mode_w =
+                            self.convert_wrap_mode(loader, texture.wrap_w, texture_ref);
 
                         descriptor.mag_filter = self.convert_image_filter(texture.mag_filter, false);
                         descriptor.min_filter = self.convert_image_filter(texture.min_filter, false);
@@ -810,7 +926,7 @@ impl BinaryAsset {
                         TransparencyMode::Binary => AlphaMode::Mask(0.5),
                         TransparencyMode::Dual => AlphaMode::AlphaToCoverage,
                         _ => {
-                            warn!(name: "multisample_transparency", target: "Panda3DLoader",
+                            report!(loader, SkippedFeature, name: "multisample_transparency", node: Some(attrib_ref.0 as usize),
                                 "Encountered Multisample TransparencyAttrib on node {}, ignoring.", attrib_ref.0);
                             AlphaMode::Opaque
                         }
@@ -837,12 +953,23 @@ impl BinaryAsset {
                 Some(NodeRef::DepthWriteAttrib(attrib)) => {
                     material.extension.depth_write_enabled = attrib.depth_write_enabled();
                 }
-                Some(NodeRef::CullBinAttrib(_)) => {
-                    // TODO: actually handle this? There's not much we can do about pipelining in this loader.
+                Some(NodeRef::CullBinAttrib(attrib)) => {
+                    // Bevy can't draw bins in order, so approximate it by biasing their depth instead
+                    let bin = CullBin::from(attrib);
+                    if matches!(bin, CullBin::Background(_)) {
+                        material.extension.depth_write_enabled = false;
+                    }
+                    // The material's own depth bias is only used to sort transparent meshes, since Bevy
+                    // drops negative values when building its pipeline
+                    material.base.depth_bias = bin.depth_bias() as f32;
+                    material.extension.depth_bias = bin.depth_bias();
+                }
+                Some(node) => {
+                    report!(loader, UnknownNode, name: "unexpected_node", node: Some(attrib_ref.0 as usize),
+                        "Node {} is a {}, which isn't expected in create_material, ignoring.", attrib_ref.0 as usize, node.type_name());
                 }
-                Some(node) => println!("Unexpected node {:?} in create_material", node),
                 None => {
-                    warn!(name: "unexpected_node_index", target: "Panda3DLoader",
+                    report!(loader, UnknownNode, name: "unexpected_node_index", node: Some(attrib_ref.0 as usize),
                         "Tried to access node {}, but it doesn't exist, ignoring.", attrib_ref.0);
                 }
             }
@@ -856,78 +983,359 @@ impl BinaryAsset {
         material
     }
 
+    /// Returns the [`CullBin`] that a RenderState assigns its meshes to, if it has a CullBinAttrib.
+    fn cull_bin(&self, render_state: &RenderState) -> Option<CullBin> {
+        render_state
+            .attrib_refs
+            .iter()
+            .find_map(|attrib_ref| self.nodes.get_as::<CullBinAttrib>(attrib_ref.0 as usize))
+            .map(CullBin::from)
+    }
+
     fn convert_blend_entry(&self, entry: &TransformEntry, lookup: &HashMap<u32, u16>) -> Option<(u16, f32)> {
         lookup.get(&entry.transform_ref).map(|&joint_id| (joint_id, entry.weight))
     }
 
     fn process_blend(&self, blend: &TransformBlend, lookup: &HashMap<u32, u16>) -> ([u16; 4], [f32; 4]) {
-        let mut indices = [0u16; 4];
-        let mut weights = [0f32; 4];
-
-        // First sort entries by weight
         let mut entries: Vec<_> =
             blend.entries.iter().filter_map(|entry| self.convert_blend_entry(entry, lookup)).collect();
-        entries.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-
-        // Take first 4 entries after sorting
-        for (i, &(joint_id, weight)) in entries.iter().take(4).enumerate() {
-            indices[i] = joint_id;
-            weights[i] = weight;
-        }
-
-        // Normalize weights
-        let total: f32 = weights.iter().sum();
-        if total > 0.0 {
-            weights.iter_mut().for_each(|w| *w /= total);
-        }
-
-        (indices, weights)
+        strongest_weights(&mut entries)
     }
 
+    /// Maps every VertexTransform in `transform_refs` to the index of its joint in `joint_data`.
     fn build_joint_lookup(
-        &self, blend_table: &TransformBlendTable, world: &World, joint_data: Option<&SkinnedMesh>,
+        &self, loader: &mut AssetLoaderData<'_, '_>, transform_refs: impl IntoIterator<Item = u32>,
+        joint_data: Option<&SkinnedMesh>,
     ) -> Option<HashMap<u32, u16>> {
         let mut lookup = HashMap::new();
         let joint_data = joint_data?;
 
-        for transform in &blend_table.blends {
-            for entry in &transform.entries {
-                if lookup.contains_key(&entry.transform_ref) {
+        for transform_ref in transform_refs {
+            if lookup.contains_key(&transform_ref) {
+                continue;
+            }
+
+            // Get the joint vertex transform
+            let vertex_transform = match self.nodes.get_as::<JointVertexTransform>(transform_ref as usize) {
+                Some(node) => node,
+                None => {
+                    report!(loader, UnknownNode, name: "not_a_joint_vertex_transform", node: Some(transform_ref as usize),
+                        "Expected JointVertexTransform for node {}, ignoring.", transform_ref);
                     continue;
                 }
+            };
 
-                // Get the joint vertex transform
-                let vertex_transform =
-                    match self.nodes.get_as::<JointVertexTransform>(entry.transform_ref as usize) {
-                        Some(node) => node,
-                        None => {
-                            warn!(name: "not_a_joint_vertex_transform", target: "Panda3DLoader",
-                            "Expected JointVertexTransform for node {}, ignoring.", entry.transform_ref);
-                            continue;
-                        }
-                    };
+            // Get the character joint
+            let joint = match self.nodes.get_as::<CharacterJoint>(vertex_transform.joint_ref as usize) {
+                Some(node) => node,
+                None => {
+                    report!(loader, UnknownNode, name: "not_a_character_joint", node: Some(vertex_transform.joint_ref as usize),
+                        "Expected CharacterJoint for node {}, ignoring.", vertex_transform.joint_ref);
+                    continue;
+                }
+            };
 
-                // Get the character joint
-                let joint = match self.nodes.get_as::<CharacterJoint>(vertex_transform.joint_ref as usize) {
-                    Some(node) => node,
-                    None => {
-                        warn!(name: "not_a_character_joint", target: "Panda3DLoader",
-                            "Expected CharacterJoint for node {}, ignoring.", vertex_transform.joint_ref);
-                        continue;
-                    }
+            // Find matching joint in joint_data
+            for (joint_id, &entity) in joint_data.joints.iter().enumerate() {
+                if **loader.world.entity(entity).get::<Name>().unwrap() == *joint.name {
+                    lookup.insert(transform_ref, joint_id as u16);
+                    break;
+                }
+            }
+        }
+
+        Some(lookup)
+    }
+
+    /// Returns the array data, array format, and column of the first column called `name` in any of a
+    /// GeomVertexData's arrays.
+    fn find_column(
+        &self, vertex_data: &GeomVertexData, name: &str,
+    ) -> Option<(&GeomVertexArrayData, &GeomVertexArrayFormat, &GeomVertexColumn)> {
+        vertex_data.array_refs.iter().find_map(|&array_ref| {
+            let array_data = self.nodes.get_as::<GeomVertexArrayData>(array_ref as usize)?;
+            let array_format =
+                self.nodes.get_as::<GeomVertexArrayFormat>(array_data.array_format_ref as usize)?;
+            let column = array_format.columns.iter().find(|column| {
+                self.nodes
+                    .get_as::<InternalName>(column.name_ref as usize)
+                    .is_some_and(|internal_name| internal_name.name == name)
+            })?;
+            Some((array_data, array_format, column))
+        })
+    }
+
+    /// Reads the joint indices and weights of every vertex from the transform_index and transform_weight
+    /// columns used by hardware skinning, where each index points into a TransformTable.
+    ///
+    /// Returns `None` if the columns are missing or can't be read, after reporting why.
+    fn read_hardware_blends(
+        &self, loader: &mut AssetLoaderData<'_, '_>, data_ref: usize, vertex_data: &GeomVertexData,
+        spec: &GeomVertexAnimationSpec, table: &TransformTable, lookup: &HashMap<u32, u16>,
+    ) -> Result<Option<JointAttributes>, Panda3DError> {
+        let Some((weight_data, weight_format, weight_column)) =
+            self.find_column(vertex_data, "transform_weight")
+        else {
+            report!(loader, InvalidData, name: "missing_transform_weight", node: Some(data_ref),
+                "Vertex Data {} uses hardware animation but has no transform_weight column, ignoring.", data_ref);
+            return Ok(None);
+        };
+        if !matches!(
+            weight_column.numeric_type,
+            NumericType::F32 | NumericType::F64 | NumericType::StdFloat
+        ) {
+            report!(loader, InvalidData, name: "unexpected_transform_weight_type", node: Some(data_ref),
+                "Tried to parse transform weights on node {}, but encountered unexpected data, ignoring.", data_ref);
+            return Ok(None);
+        }
+
+        // Without indices, each vertex is blended between the first num_transforms entries of the table
+        let (mut index_data, num_transforms) = match spec.indexed_transforms {
+            true => match self.find_column(vertex_data, "transform_index") {
+                Some((array_data, array_format, column))
+                    if matches!(column.numeric_type, NumericType::U8 | NumericType::U16) =>
+                {
+                    let data = DataCursorRef::new(&array_data.buffer, Endian::Little);
+                    (
+                        Some((data, array_format, column)),
+                        usize::from(column.num_components),
+                    )
+                }
+                _ => {
+                    report!(loader, InvalidData, name: "unexpected_transform_index", node: Some(data_ref),
+                        "Vertex Data {} has indexed transforms but no usable transform_index column, ignoring.", data_ref);
+                    return Ok(None);
+                }
+            },
+            false => (None, usize::from(spec.num_transforms)),
+        };
+
+        let num_vertices = weight_data.buffer.len() / usize::from(weight_format.stride);
+        let mut data = DataCursorRef::new(&weight_data.buffer, Endian::Little);
+        let mut joint_indices = Vec::with_capacity(num_vertices);
+        let mut joint_weights = Vec::with_capacity(num_vertices);
+        let mut weights: SmallVec<[f32; 4]> = SmallVec::new();
+        let mut entries = Vec::with_capacity(num_transforms);
+        for n in 0..num_vertices as u64 {
+            // We have a stride to worry about
+            data.set_position(u64::from(weight_format.stride) * n + u64::from(weight_column.start))?;
+            weights.clear();
+            for _ in 0..weight_column.num_components {
+                weights.push(read_component(&mut data, weight_column.numeric_type)?);
+            }
+
+            // Exporters can leave out the last weight, since it's always whatever the others don't add up to
+            if weights.len() < num_transforms {
+                weights.push((1.0 - weights.iter().sum::<f32>()).max(0.0));
+            }
+
+            if let Some((data, array_format, column)) = &mut index_data {
+                data.set_position(u64::from(array_format.stride) * n + u64::from(column.start))?;
+            }
+            entries.clear();
+            for (slot, &weight) in weights.iter().enumerate() {
+                let table_index = match &mut index_data {
+                    Some((data, _, column)) => read_index(data, column.numeric_type)?,
+                    None => slot as u16,
                 };
+                let joint_id = table.transform_refs.get(usize::from(table_index)).and_then(|r| lookup.get(r));
+                if let Some(&joint_id) = joint_id {
+                    entries.push((joint_id, weight));
+                }
+            }
 
-                // Find matching joint in joint_data
-                for (joint_id, &entity) in joint_data.joints.iter().enumerate() {
-                    if **world.entity(entity).get::<Name>().unwrap() == *joint.name {
-                        lookup.insert(entry.transform_ref, joint_id as u16);
-                        break;
-                    }
+            let (indices, weights) = strongest_weights(&mut entries);
+            joint_indices.push(indices);
+            joint_weights.push(weights);
+        }
+
+        Ok(Some((joint_indices, joint_weights)))
+    }
+
+    /// Returns whether a node and all of its children can be merged into their parent, which is only the case
+    /// if none of them are animated, have effects or tags, or are ModelNodes that want to be kept.
+    fn is_static(&self, net_nodes: Option<&BTreeMap<usize, Entity>>, node_index: usize) -> bool {
+        let node: &PandaNode = match self.nodes.get(node_index) {
+            Some(NodeRef::ModelNode(node)) if node.transform == PreserveTransform::None => node,
+            Some(NodeRef::PandaNode(node)) => node,
+            Some(NodeRef::GeomNode(node)) => node,
+            _ => return false,
+        };
+        let has_effects = self
+            .nodes
+            .get_as::<RenderEffects>(node.effects_ref as usize)
+            .is_none_or(|effects| !effects.effect_refs.is_empty());
+
+        !has_effects
+            && node.tag_data.is_empty()
+            && !net_nodes.is_some_and(|net_nodes| net_nodes.contains_key(&node_index))
+            && node.child_refs.iter().all(|child_ref| self.is_static(net_nodes, child_ref.0 as usize))
+    }
+
+    /// Collects every Geom in a static subtree, along with its RenderState and its transform relative to the
+    /// subtree's parent.
+    fn collect_geoms(
+        &self, loader: &mut AssetLoaderData<'_, '_>, node_index: usize, parent: Mat4,
+        geoms: &mut Vec<(usize, usize, Mat4)>,
+    ) {
+        let node: &PandaNode = match self.nodes.get(node_index) {
+            Some(NodeRef::ModelNode(node)) => node,
+            Some(NodeRef::PandaNode(node)) => node,
+            Some(NodeRef::GeomNode(node)) => {
+                let transform = parent
+                    * self.handle_transform_state(loader, node.transform_ref as usize).compute_matrix();
+                geoms.extend(
+                    node.geom_refs.iter().map(|(geom, state)| (*geom as usize, *state as usize, transform)),
+                );
+                node
+            }
+            _ => return,
+        };
+        let transform =
+            parent * self.handle_transform_state(loader, node.transform_ref as usize).compute_matrix();
+        for child_ref in &node.sorted_children() {
+            self.collect_geoms(loader, child_ref.0 as usize, transform, geoms);
+        }
+    }
+
+    /// Merges every static child of `parent` into a single mesh per RenderState, mirroring Panda3D's
+    /// `flatten_strong`, and returns the children that still need to be converted. See
+    /// [`LoadSettings::flatten_static`].
+    ///
+    /// `child_refs` should already be sorted with [`PandaNode::sorted_children`]. Only children with the default
+    /// sort value are merged, so that any others keep their place relative to their siblings.
+    async fn flatten_children<'a>(
+        &self, loader: &mut AssetLoaderData<'_, '_>, parent: Entity, joint_data: Option<&SkinnedMesh>,
+        net_nodes: Option<&BTreeMap<usize, Entity>>, child_refs: &'a [(u32, i32)],
+    ) -> Vec<&'a (u32, i32)> {
+        // Skinned meshes are deformed by their joints, so they can't be baked
+        if !loader.settings.flatten_static || joint_data.is_some() {
+            return child_refs.iter().collect();
+        }
+
+        let (flattened, remaining): (Vec<&(u32, i32)>, Vec<_>) = child_refs
+            .iter()
+            .partition(|child_ref| child_ref.1 == 0 && self.is_static(net_nodes, child_ref.0 as usize));
+        let mut geoms = Vec::new();
+        for child_ref in flattened {
+            self.collect_geoms(loader, child_ref.0 as usize, Mat4::IDENTITY, &mut geoms);
+        }
+
+        // Group every mesh by its RenderState, starting a new mesh if one doesn't have the same attributes
+        let mut groups: BTreeMap<usize, Vec<Mesh>> = BTreeMap::new();
+        for (geom_ref, render_ref, transform) in geoms {
+            let Some(geom_node) = self.nodes.get_as::<Geom>(geom_ref) else {
+                report!(loader, UnknownNode, name: "invalid_geom_node", node: Some(geom_ref),
+                    "Tried to load node {}, but it wasn't a Geom, returning.", geom_ref);
+                continue;
+            };
+            let mesh = match self.create_mesh(loader, None, parent, geom_ref, geom_node) {
+                Ok(mesh) => self.optimize_mesh(loader, mesh, geom_node),
+                Err(error) => {
+                    report!(loader, InvalidData, name: "invalid_geom_node", node: Some(geom_ref),
+                        "Unable to create a mesh for node {}: {}, ignoring.", geom_ref, error);
+                    continue;
                 }
+            };
+            let meshes = groups.entry(render_ref).or_default();
+            if meshes.last_mut().is_some_and(|merged| append_mesh(merged, &mesh, transform)) {
+                continue;
+            }
+
+            let mut merged = Mesh::new(PrimitiveTopology::TriangleList, mesh.asset_usage);
+            merged.insert_indices(Indices::U32(Vec::new()));
+            for (attribute, values) in mesh.attributes() {
+                merged.insert_attribute(*attribute, select_vertices(values, &[]));
+            }
+            // Only meshes that failed to convert to a triangle list can end up here
+            if !append_mesh(&mut merged, &mesh, transform) {
+                report!(loader, SkippedFeature, name: "unflattened_mesh", node: Some(geom_ref),
+                    "Geom {} couldn't be converted to a triangle list for flattening, ignoring.", geom_ref);
+                continue;
             }
+            meshes.push(merged);
         }
 
-        Some(lookup)
+        for (render_ref, meshes) in groups {
+            let Some(render_state) = self.nodes.get_as::<RenderState>(render_ref) else {
+                report!(loader, UnknownNode, name: "invalid_geom_node", node: Some(render_ref),
+                    "Tried to load node {}, but it wasn't a RenderState, returning.", render_ref);
+                continue;
+            };
+            let label = format!("Material{}", loader.assets.materials.len());
+            let material = self.create_material(loader, render_state).await;
+            let material = loader.context.add_labeled_asset(label, material);
+            loader.assets.materials.push(material.clone());
+            let bin = self.cull_bin(render_state);
+
+            for mesh in meshes {
+                let label = format!("Mesh{}", loader.assets.meshes.len());
+                let mesh = loader.context.add_labeled_asset(label, mesh);
+                loader.assets.meshes.push(mesh.clone());
+
+                let entity = loader
+                    .world
+                    .spawn((
+                        Transform::default(),
+                        Visibility::default(),
+                        Mesh3d(mesh),
+                        MeshMaterial3d(material.clone()),
+                    ))
+                    .id();
+                loader.world.entity_mut(parent).add_child(entity);
+                if let Some(bin) = bin.clone() {
+                    loader.world.entity_mut(entity).insert(bin);
+                }
+            }
+        }
+        remaining
+    }
+
+    /// Converts a mesh into an indexed triangle list with any duplicate vertices merged, see
+    /// [`LoadSettings::optimize_meshes`].
+    fn optimize_mesh(&self, loader: &mut AssetLoaderData<'_, '_>, mesh: Mesh, geom_node: &Geom) -> Mesh {
+        let Some(indices) = mesh.indices() else {
+            return mesh;
+        };
+        let indices: Vec<u32> = indices.iter().map(|index| index as u32).collect();
+        let mut indices = match mesh.primitive_topology() {
+            PrimitiveTopology::TriangleStrip => {
+                // Strips are joined by unused vertices, so we need the ends to know which ones to skip
+                let ends = geom_node
+                    .primitive_refs
+                    .first()
+                    .and_then(|node_index| self.nodes.get_as::<GeomPrimitive>(*node_index as usize))
+                    .and_then(|primitive| primitive.ends_ref)
+                    .and_then(|ends_ref| self.arrays.get(ends_ref as usize))
+                    .map_or(&[][..], |ends| ends);
+                strips_to_list(&indices, ends)
+            }
+            PrimitiveTopology::TriangleList => indices,
+            _ => return mesh,
+        };
+
+        let vertex_count = mesh.count_vertices();
+        if vertex_count == 0 || indices.iter().any(|index| *index as usize >= vertex_count) {
+            report!(loader, InvalidData, name: "invalid_mesh_indices", node: Some(geom_node.data_ref as usize),
+                "Geom {} has indices past the end of its vertices, skipping optimization.", geom_node.data_ref);
+            return mesh;
+        }
+        let kept = weld(
+            &mesh.create_packed_vertex_buffer_data(),
+            mesh.get_vertex_size() as usize,
+            &mut indices,
+        );
+
+        let mut optimized = Mesh::new(PrimitiveTopology::TriangleList, mesh.asset_usage);
+        for (attribute, values) in mesh.attributes() {
+            optimized.insert_attribute(*attribute, select_vertices(values, &kept));
+        }
+        // Most meshes are small enough to use 16-bit indices after welding, which halves their size
+        optimized.insert_indices(match kept.len() <= usize::from(u16::MAX) + 1 {
+            true => Indices::U16(indices.into_iter().map(|index| index as u16).collect()),
+            false => Indices::U32(indices),
+        });
+        optimized
     }
 
     fn create_mesh(
@@ -937,11 +1345,11 @@ impl BinaryAsset {
         // We already handle primitive_type by what type the node_ref is, and we theoretically account for
         // Smooth shading because the mesh already has flat normals calculated. TODO: verify this?
         if geom_node.primitive_refs.len() != 1 {
-            warn!(name: "too_many_primitives", target: "Panda3DLoader",
+            report!(loader, SkippedFeature, name: "too_many_primitives", node: Some(geom_ref),
                 "More than one primitive is attached to node {}, please fix!", geom_ref);
         }
         if geom_node.bounds_type != BoundsType::Default {
-            warn!(name: "bounds_type_unhandled", target: "Panda3DLoader",
+            report!(loader, UnhandledAttrib, name: "bounds_type_unhandled", node: Some(geom_ref),
                 "Geom node {} has a unique BoundsType that isn't being handled, ignoring.", geom_ref);
         }
 
@@ -971,7 +1379,7 @@ impl BinaryAsset {
         } else if geom_node.geom_rendering.is_empty() {
             PrimitiveTopology::TriangleList
         } else {
-            warn!(name: "unexpected_rendering_flags", target: "Panda3DLoader",
+            report!(loader, UnhandledAttrib, name: "unexpected_rendering_flags", node: Some(geom_ref),
                 "Unknown geometry rendering type: {:?}, defaulting to TriangleList", geom_node.geom_rendering);
             PrimitiveTopology::TriangleList
         };
@@ -994,7 +1402,7 @@ impl BinaryAsset {
                     .context(WrongNodeSnafu { node_index, node_type: "GeomVertexArrayFormat" })?;
 
                 if array_format.num_columns != 1 {
-                    warn!(name: "too_many_array_columns", target: "Panda3DLoader",
+                    report!(loader, UnhandledAttrib, name: "too_many_array_columns", node: Some(array_data.array_format_ref as usize),
                         "Too many columns in GeomVertexArrayFormat {}, ignoring.", array_data.array_format_ref as usize);
                 }
 
@@ -1073,10 +1481,10 @@ impl BinaryAsset {
                     // Note: this can be 4D homogenous space, if it is we just ignore the 4th float which is
                     // 1.0.
                     if (column.num_components != 3 && column.num_components != 4)
-                        || column.numeric_type != NumericType::F32
+                        || !matches!(column.numeric_type, NumericType::F32 | NumericType::F64)
                         || column.contents != Contents::Point
                     {
-                        warn!(name: "unexpected_vertex_type", target: "Panda3DLoader",
+                        report!(loader, InvalidData, name: "unexpected_vertex_type", node: Some(vertex_data.array_refs[0] as usize),
                             "Tried to parse vertex data on node {}, but encountered unexpected data, ignoring.", vertex_data.array_refs[0]);
                         continue;
                     }
@@ -1085,16 +1493,20 @@ impl BinaryAsset {
                     for n in 0..num_primitives {
                         // We have a stride to worry about
                         data.set_position(u64::from(column.start) + u64::from(array_format.stride) * n)?;
-                        vertex_data.push([data.read_f32()?, data.read_f32()?, data.read_f32()?]);
+                        vertex_data.push([
+                            read_component(&mut data, column.numeric_type)?,
+                            read_component(&mut data, column.numeric_type)?,
+                            read_component(&mut data, column.numeric_type)?,
+                        ]);
                     }
                     mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertex_data);
                 }
                 "texcoord" => {
                     if column.num_components != 2
-                        || column.numeric_type != NumericType::F32
+                        || !matches!(column.numeric_type, NumericType::F32 | NumericType::F64)
                         || column.contents != Contents::TexCoord
                     {
-                        warn!(name: "unexpected_texcoord_type", target: "Panda3DLoader",
+                        report!(loader, InvalidData, name: "unexpected_texcoord_type", node: Some(vertex_data.array_refs[0] as usize),
                             "Tried to parse texcoord data on node {}, but encountered unexpected data, ignoring.", vertex_data.array_refs[0]);
                         continue;
                     }
@@ -1105,20 +1517,62 @@ impl BinaryAsset {
                         data.set_position(u64::from(array_format.stride) * n + u64::from(column.start))?;
 
                         // Panda3D stores flipped Y values to support OpenGL, so we do 1.0 - value.
-                        texcoord_data.push([data.read_f32()?, 1.0 - data.read_f32()?]);
+                        texcoord_data.push([
+                            read_component(&mut data, column.numeric_type)?,
+                            1.0 - read_component(&mut data, column.numeric_type)?,
+                        ]);
                     }
                     mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, texcoord_data);
                 }
-                _ => warn!(name: "unexpected_column_type", target: "Panda3DLoader",
+                // These are read along with the TransformTable below
+                "transform_index" | "transform_weight"
+                    if vertex_format.animation_type == AnimationType::Hardware => {}
+                _ => report!(loader, UnhandledAttrib, name: "unexpected_column_type", node: Some(node_index),
                     "Unexpected Column Type Encountered: {}, ignoring.", internal_name.name),
             }
         }
 
         // Now that we've handled base data, let's check all other tables.
         let mut tables_read = 1;
-        if let Some(_node_index) = vertex_data.transform_table_ref {
-            warn!(name: "unsupported_transform_table", target: "Panda3DLoader",
-                "Vertex Data {} has a TransformTable, please fix!", geom_node.data_ref);
+        if let Some(node_index) = vertex_data.transform_table_ref {
+            // Hardware skinning stores which transforms affect each vertex in its own columns, instead of
+            // pointing into a TransformBlendTable.
+            if vertex_format.animation_type == AnimationType::Hardware {
+                let table =
+                    self.nodes.get_as::<TransformTable>(node_index as usize).context(WrongNodeSnafu {
+                        node_index: node_index as usize,
+                        node_type: "TransformTable",
+                    })?;
+
+                let Some(lookup) =
+                    self.build_joint_lookup(loader, table.transform_refs.iter().copied(), joint_data)
+                else {
+                    report!(loader, InvalidData, name: "joint_data_missing", node: Some(geom_ref),
+                        "No joint data available for mesh with transform table, ignoring.");
+                    return Ok(mesh);
+                };
+
+                let data_ref = geom_node.data_ref as usize;
+                let spec = &vertex_format.animation;
+                if let Some((joint_indices, joint_weights)) =
+                    self.read_hardware_blends(loader, data_ref, vertex_data, spec, table, &lookup)?
+                {
+                    mesh.insert_attribute(
+                        Mesh::ATTRIBUTE_JOINT_INDEX,
+                        VertexAttributeValues::Uint16x4(joint_indices),
+                    );
+                    mesh.insert_attribute(
+                        Mesh::ATTRIBUTE_JOINT_WEIGHT,
+                        VertexAttributeValues::Float32x4(joint_weights),
+                    );
+                    if let Some(joint_data) = joint_data {
+                        loader.world.entity_mut(entity).insert(joint_data.clone());
+                    }
+                }
+            } else {
+                report!(loader, SkippedFeature, name: "unsupported_transform_table", node: Some(geom_node.data_ref as usize),
+                    "Vertex Data {} has a TransformTable without hardware animation, please fix!", geom_node.data_ref);
+            }
             tables_read += 1;
         }
 
@@ -1138,8 +1592,12 @@ impl BinaryAsset {
             //
             // We have to walk the TransformBlendTable twice, but the number of joints is less than the number
             // of blend combinations, so this should overall save time.
-            let Some(lookup) = self.build_joint_lookup(blend_table, loader.world, joint_data) else {
-                warn!(name: "joint_data_missing", target: "Panda3DLoader",
+            let transform_refs = blend_table
+                .blends
+                .iter()
+                .flat_map(|blend| blend.entries.iter().map(|entry| entry.transform_ref));
+            let Some(lookup) = self.build_joint_lookup(loader, transform_refs, joint_data) else {
+                report!(loader, InvalidData, name: "joint_data_missing", node: Some(geom_ref),
                     "No joint data available for mesh with blend table, ignoring.");
                 return Ok(mesh);
             };
@@ -1206,51 +1664,54 @@ impl BinaryAsset {
         match self.nodes.get(node_index) {
             Some(NodeRef::AnimBundle(node)) => {
                 // We're at the base of an AnimBundle, so let's start building an AnimationClip
+                if !is_valid_fps(node.fps) {
+                    report!(loader, InvalidData, name: "invalid_anim_fps", node: Some(node_index),
+                        "AnimBundle node {} has an invalid frame rate of {}, unable to make animation!", node_index, node.fps);
+                    return;
+                }
+                if let Some(fps) = loader.settings.animation_fps.filter(|&fps| !is_valid_fps(fps)) {
+                    report!(loader, InvalidData, name: "invalid_animation_fps_setting", node: Some(node_index),
+                        "LoadSettings::animation_fps is {}, which isn't a valid frame rate, keeping the original frame rate of {}.", fps, node.fps);
+                }
                 let mut animation = AnimationClip::default();
                 animation.set_duration(f32::from(node.num_frames) / node.fps);
 
-                // Let's also pull up the AnimGroups, since we know what they look like
-                if node.child_refs.len() != 2 {
-                    warn!(name: "unexpected_anim_bundle", target: "Panda3DLoader",
-                        "Unexpected number of child nodes on Node {}, unable to make animation!", node_index);
+                // Each AnimGroup matches a PartGroup in the PartBundle, usually "<skeleton>" followed by "morph"
+                if node.child_refs.is_empty() {
+                    report!(loader, InvalidData, name: "unexpected_anim_bundle", node: Some(node_index),
+                        "AnimBundle node {} has no AnimGroups, unable to make animation!", node_index);
                     return;
                 }
 
-                // Then, let's process skeleton/transform animation data
-                let Some(skeleton) = self.nodes.get_as::<AnimGroup>(node.child_refs[0] as usize) else {
-                    warn!(name: "not_an_anim_group", target: "Panda3DLoader",
-                        "Tried to acquire node {}, but it wasn't an AnimGroup! Unable to make animation, returning.", node.child_refs[0]);
-                    return;
-                };
-                if skeleton.name != "<skeleton>" {
-                    warn!(name: "unexpected_anim_group", target: "Panda3DLoader",
-                        "Expected node {} to have <skeleton> as a name but it didn't, ignoring.", node.child_refs[0]);
-                }
+                for group_ref in &node.child_refs {
+                    let Some(group) = self.nodes.get_as::<AnimGroup>(*group_ref as usize) else {
+                        report!(loader, UnknownNode, name: "not_an_anim_group", node: Some(*group_ref as usize),
+                            "Tried to acquire node {}, but it wasn't an AnimGroup! Unable to animate it, ignoring.", group_ref);
+                        continue;
+                    };
 
-                let animation_context = AnimationContext {
-                    root: Entity::PLACEHOLDER,
-                    path: smallvec![Name::new(node.name.clone()), Name::new(skeleton.name.clone())],
-                };
+                    if group.name == "morph" {
+                        if !group.child_refs.is_empty() {
+                            report!(loader, SkippedFeature, name: "morph_anims_unimplemented", node: Some(node_index),
+                                "Node {} has Morph Target Animations, but they're currently unimplemented, please fix!", node_index);
+                        }
+                        continue;
+                    }
 
-                for child_ref in &skeleton.child_refs {
-                    self.convert_anim_bundle(
-                        loader,
-                        Some(&mut animation),
-                        Some(animation_context.clone()),
-                        Some((node.num_frames as usize, node.fps)),
-                        *child_ref as usize,
-                    );
-                }
+                    let animation_context = AnimationContext {
+                        root: Entity::PLACEHOLDER,
+                        path: smallvec![Name::new(node.name.clone()), Name::new(group.name.clone())],
+                    };
 
-                // Finally, let's process morph target animations
-                let Some(morph) = self.nodes.get_as::<AnimGroup>(node.child_refs[1] as usize) else {
-                    warn!(name: "not_an_anim_group", target: "Panda3DLoader",
-                        "Tried to acquire node {}, but it wasn't an AnimGroup! Unable to make animation, returning.", node.child_refs[1]);
-                    return;
-                };
-                if !morph.child_refs.is_empty() {
-                    warn!(name: "morph_anims_unimplemented", target: "Panda3DLoader",
-                        "Node {} has Morph Target Animations, but they're currently unimplemented, please fix!", node_index);
+                    for child_ref in &group.child_refs {
+                        self.convert_anim_bundle(
+                            loader,
+                            Some(&mut animation),
+                            Some(animation_context.clone()),
+                            Some((node.num_frames as usize, node.fps)),
+                            *child_ref as usize,
+                        );
+                    }
                 }
 
                 let label = format!("Animation{}", loader.assets.animations.len());
@@ -1264,18 +1725,67 @@ impl BinaryAsset {
 
                     println!("Animation {:?}", animation_context.path);
 
-                    let anim_target_id = AnimationTargetId::from_names(animation_context.path.iter());
+                    let anim_target_id = loader.animation_target(&animation_context.path);
+                    loader
+                        .assets
+                        .animation_paths
+                        .entry(anim_target_id)
+                        .or_insert_with(|| animation_context.path.to_vec());
 
                     let (num_frames, fps) = frame_data.unwrap();
-                    let frame_times = (0..num_frames).map(|i| i as f32 / fps);
+                    let settings = loader.settings;
+                    // Invalid frame rates were already reported when the AnimBundle was reached
+                    let target_fps = settings.animation_fps.filter(|&fps| is_valid_fps(fps));
+                    let sample_fps = target_fps.unwrap_or(fps);
+                    let Ok(domain) = Interval::new(0.0, num_frames.saturating_sub(1) as f32 / fps) else {
+                        report!(loader, InvalidData, name: "invalid_anim_duration", node: Some(node_index),
+                            "Node {} has {} frames at {} fps, which isn't a valid duration, skipping it.", node_index, num_frames, fps);
+                        return;
+                    };
 
                     // Let's just check shear now since it's easier
                     if !node.tables[3].is_empty() || !node.tables[4].is_empty() || !node.tables[5].is_empty()
                     {
-                        warn!(name: "shear_animation_unsupported", target: "Panda3DLoader",
+                        report!(loader, SkippedFeature, name: "shear_animation_unsupported", node: Some(node_index),
                             "Shear animation detected on node {}, currently unsupported.", node_index);
                     }
 
+                    // Each component has a different type, so this can't easily be a function
+                    macro_rules! add_curve {
+                        ($field:expr, $values:expr, $interpolate:expr) => {{
+                            let values = match target_fps {
+                                Some(target_fps) if target_fps != fps => {
+                                    resample(&$values, fps, target_fps, $interpolate)
+                                }
+                                _ => $values,
+                            };
+                            let keyframes: Vec<_> = values
+                                .into_iter()
+                                .enumerate()
+                                .map(|(i, value)| (i as f32 / sample_fps, value))
+                                .collect();
+                            let keyframes = match settings.strip_constant_channels {
+                                true => strip_keyframes(keyframes),
+                                false => keyframes,
+                            };
+                            match keyframes.as_slice() {
+                                [] => {}
+                                [(_, value)] => animation.add_curve_to_target(
+                                    anim_target_id,
+                                    AnimatableCurve::new($field, ConstantCurve::new(domain, *value)),
+                                ),
+                                _ => match UnevenSampleAutoCurve::new(keyframes) {
+                                    Ok(curve) => animation
+                                        .add_curve_to_target(anim_target_id, AnimatableCurve::new($field, curve)),
+                                    Err(error) => {
+                                        report!(loader, InvalidData, name: "invalid_anim_keyframes", node: Some(node_index),
+                                            "Unable to make an animation curve for node {}: {}", node_index, error);
+                                    }
+                                },
+                            }
+                        }};
+                    }
+
                     for n in [0, 2, 3] {
                         let default = match n {
                             0 => 1.0, // Scale
@@ -1290,66 +1800,50 @@ impl BinaryAsset {
                             expand_channel_data(&node.tables[n * 3 + 2], default, num_frames),
                         ];
 
-                        if !channels[0].is_empty() || !channels[1].is_empty() || !channels[2].is_empty() {
-                            match n {
-                                0 => {
-                                    // Scale
-                                    let scale_values: Vec<Vec3> = (0..num_frames)
-                                        .map(|i| Vec3::new(channels[0][i], channels[1][i], channels[2][i]))
-                                        .collect();
-
-                                    animation.add_curve_to_target(
-                                        anim_target_id,
-                                        AnimatableCurve::new(
-                                            animated_field!(Transform::scale),
-                                            UnevenSampleAutoCurve::new(frame_times.clone().zip(scale_values))
-                                                .unwrap(),
-                                        ),
-                                    );
-                                }
-                                2 => {
-                                    // Rotation
-                                    let rotation_values: Vec<Quat> = (0..num_frames)
-                                        .map(|i| {
-                                            Quat::from_euler(
-                                                EulerRot::ZXY,
-                                                channels[0][i].to_radians(), // heading
-                                                channels[1][i].to_radians(), // pitch
-                                                channels[2][i].to_radians(), // roll
-                                            )
-                                        })
-                                        .collect();
-
-                                    animation.add_curve_to_target(
-                                        anim_target_id,
-                                        AnimatableCurve::new(
-                                            animated_field!(Transform::rotation),
-                                            UnevenSampleAutoCurve::new(
-                                                frame_times.clone().zip(rotation_values),
-                                            )
-                                            .unwrap(),
-                                        ),
-                                    );
-                                }
-                                3 => {
-                                    // Translation
-                                    let translation_values: Vec<Vec3> = (0..num_frames)
-                                        .map(|i| Vec3::new(channels[0][i], channels[1][i], channels[2][i]))
-                                        .collect();
-
-                                    animation.add_curve_to_target(
-                                        anim_target_id,
-                                        AnimatableCurve::new(
-                                            animated_field!(Transform::translation),
-                                            UnevenSampleAutoCurve::new(
-                                                frame_times.clone().zip(translation_values),
-                                            )
-                                            .unwrap(),
-                                        ),
-                                    );
-                                }
-                                _ => unreachable!(),
+                        match n {
+                            0 => {
+                                // Scale
+                                let scale_values: Vec<Vec3> = (0..num_frames)
+                                    .map(|i| Vec3::new(channels[0][i], channels[1][i], channels[2][i]))
+                                    .collect();
+                                add_curve!(animated_field!(Transform::scale), scale_values, Vec3::lerp);
+                            }
+                            2 => {
+                                // Rotation, optionally rounded so that more keyframes can be stripped
+                                let quantize = |degrees: f32| match settings.rotation_precision {
+                                    Some(precision) if precision > 0.0 => {
+                                        (degrees / precision).round() * precision
+                                    }
+                                    _ => degrees,
+                                };
+                                let rotation_values: Vec<Quat> = (0..num_frames)
+                                    .map(|i| {
+                                        Quat::from_euler(
+                                            EulerRot::ZXY,
+                                            quantize(channels[0][i]).to_radians(), // heading
+                                            quantize(channels[1][i]).to_radians(), // pitch
+                                            quantize(channels[2][i]).to_radians(), // roll
+                                        )
+                                    })
+                                    .collect();
+                                add_curve!(
+                                    animated_field!(Transform::rotation),
+                                    rotation_values,
+                                    Quat::slerp
+                                );
+                            }
+                            3 => {
+                                // Translation
+                                let translation_values: Vec<Vec3> = (0..num_frames)
+                                    .map(|i| Vec3::new(channels[0][i], channels[1][i], channels[2][i]))
+                                    .collect();
+                                add_curve!(
+                                    animated_field!(Transform::translation),
+                                    translation_values,
+                                    Vec3::lerp
+                                );
                             }
+                            _ => unreachable!(),
                         }
                     }
 
@@ -1364,17 +1858,798 @@ impl BinaryAsset {
                     }
                 }
             }
-            Some(node) => println!("Unexpected node {:?} in convert_anim_bundle", node),
+            Some(node) => report!(loader, UnknownNode, name: "unexpected_node", node: Some(node_index),
+                "Node {} is a {}, which isn't expected in convert_anim_bundle, ignoring.", node_index, node.type_name()),
+            None => {
+                report!(loader, UnknownNode, name: "unexpected_node_index", node: Some(node_index),
+                    "Tried to access node {}, but it doesn't exist, ignoring.", node_index);
+            }
+        }
+    }
+
+    /// Converts every AnimBundle in a file loaded through [`LoadSettings::animations`], without spawning any
+    /// of its nodes, since only the animations are used.
+    fn convert_animations(&self, loader: &mut AssetLoaderData<'_, '_>, node_index: usize) {
+        let child_refs = match self.nodes.get(node_index) {
+            Some(NodeRef::ModelNode(node)) => &node.child_refs,
+            Some(NodeRef::PandaNode(node)) => &node.child_refs,
+            Some(NodeRef::AnimBundleNode(node)) => {
+                self.convert_anim_bundle(loader, None, None, None, node.anim_bundle_ref as usize);
+                return;
+            }
+            Some(_) => {
+                report!(loader, UnknownNode, name: "unexpected_animation_node", node: Some(node_index),
+                    "Node {} in an animation file isn't an AnimBundleNode, ignoring.", node_index);
+                return;
+            }
             None => {
-                warn!(name: "unexpected_node_index", target: "Panda3DLoader",
+                report!(loader, UnknownNode, name: "unexpected_node_index", node: Some(node_index),
                     "Tried to access node {}, but it doesn't exist, ignoring.", node_index);
+                return;
             }
+        };
+
+        for child_ref in child_refs {
+            self.convert_animations(loader, child_ref.0 as usize);
         }
     }
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
-pub struct LoadSettings {}
+/// Reads a single floating-point vertex component, converting doubles down to floats since that's all Bevy
+/// supports.
+#[inline]
+fn read_component(data: &mut DataCursorRef<'_>, numeric_type: NumericType) -> Result<f32, DataError> {
+    match numeric_type {
+        NumericType::F64 => Ok(data.read_f64()? as f32),
+        _ => data.read_f32(),
+    }
+}
+
+/// Reads a single integer vertex component, such as an index into a TransformTable.
+#[inline]
+fn read_index(data: &mut DataCursorRef<'_>, numeric_type: NumericType) -> Result<u16, DataError> {
+    match numeric_type {
+        NumericType::U8 => Ok(u16::from(data.read_u8()?)),
+        _ => data.read_u16(),
+    }
+}
+
+/// Keeps the four joints with the largest weights, since that's all Bevy supports, and normalizes their
+/// weights so they still add up to 1.
+fn strongest_weights(entries: &mut [(u16, f32)]) -> ([u16; 4], [f32; 4]) {
+    let mut indices = [0u16; 4];
+    let mut weights = [0f32; 4];
+
+    // First sort entries by weight
+    entries.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    // Take first 4 entries after sorting
+    for (i, &(joint_id, weight)) in entries.iter().take(4).enumerate() {
+        indices[i] = joint_id;
+        weights[i] = weight;
+    }
+
+    // Normalize weights
+    let total: f32 = weights.iter().sum();
+    if total > 0.0 {
+        weights.iter_mut().for_each(|w| *w /= total);
+    }
+
+    (indices, weights)
+}
+
+/// Calls `$macro` with the name of every [`VertexAttributeValues`] variant, since Bevy doesn't provide a
+/// generic way to rearrange or combine vertices.
+macro_rules! with_vertex_formats {
+    ($macro:ident) => {
+        $macro!(
+            Float32, Sint32, Uint32, Float32x2, Sint32x2, Uint32x2, Float32x3, Sint32x3, Uint32x3, Float32x4,
+            Sint32x4, Uint32x4, Sint16x2, Snorm16x2, Uint16x2, Unorm16x2, Sint16x4, Snorm16x4, Uint16x4,
+            Unorm16x4, Sint8x2, Snorm8x2, Uint8x2, Unorm8x2, Sint8x4, Snorm8x4, Uint8x4, Unorm8x4
+        )
+    };
+}
+
+/// Returns only the given vertices of an attribute, in order.
+fn select_vertices(values: &VertexAttributeValues, kept: &[u32]) -> VertexAttributeValues {
+    macro_rules! select {
+        ($($variant:ident),*) => {
+            match values {
+                $(VertexAttributeValues::$variant(values) => {
+                    VertexAttributeValues::$variant(kept.iter().map(|index| values[*index as usize]).collect())
+                })*
+            }
+        };
+    }
+    with_vertex_formats!(select)
+}
+
+/// Appends every vertex of `source` to `target`, returning `false` if they aren't the same type.
+fn append_vertices(target: &mut VertexAttributeValues, source: &VertexAttributeValues) -> bool {
+    macro_rules! append {
+        ($($variant:ident),*) => {
+            match (target, source) {
+                $((VertexAttributeValues::$variant(target), VertexAttributeValues::$variant(source)) => {
+                    target.extend_from_slice(source);
+                    true
+                })*
+                _ => false,
+            }
+        };
+    }
+    with_vertex_formats!(append)
+}
+
+/// Appends every vertex and triangle of `source` to `target` after applying `transform` to them, returning
+/// `false` without changing `target` if either one isn't an indexed triangle list, or they don't have the
+/// same attributes.
+fn append_mesh(target: &mut Mesh, source: &Mesh, transform: Mat4) -> bool {
+    let layout = |mesh: &Mesh| -> Vec<_> {
+        mesh.attributes().map(|(attribute, _)| (attribute.id, attribute.format)).collect()
+    };
+    if target.primitive_topology() != PrimitiveTopology::TriangleList
+        || source.primitive_topology() != PrimitiveTopology::TriangleList
+        || layout(target) != layout(source)
+    {
+        return false;
+    }
+    let (Some(target_indices), Some(source_indices)) = (target.indices(), source.indices()) else {
+        return false;
+    };
+
+    let offset = target.count_vertices() as u32;
+    let mut indices: Vec<u32> = target_indices.iter().map(|index| index as u32).collect();
+    let start = indices.len();
+    indices.extend(source_indices.iter().map(|index| index as u32 + offset));
+    // Mirroring flips the winding order of every triangle, so flip them back
+    if transform.determinant() < 0.0 {
+        for triangle in indices[start..].chunks_exact_mut(3) {
+            triangle.swap(0, 1);
+        }
+    }
+
+    let normal_matrix = Mat3::from_mat4(transform).inverse().transpose();
+    for (attribute, values) in source.attributes() {
+        let mut values = values.clone();
+        if let VertexAttributeValues::Float32x3(vectors) = &mut values {
+            if attribute.id == Mesh::ATTRIBUTE_POSITION.id {
+                for vector in vectors {
+                    *vector = transform.transform_point3(Vec3::from(*vector)).to_array();
+                }
+            } else if attribute.id == Mesh::ATTRIBUTE_NORMAL.id {
+                for vector in vectors {
+                    *vector = (normal_matrix * Vec3::from(*vector)).normalize_or_zero().to_array();
+                }
+            }
+        }
+        if let Some(target) = target.attribute_mut(attribute.id) {
+            append_vertices(target, &values);
+        }
+    }
+    target.insert_indices(Indices::U32(indices));
+    true
+}
+
+/// Converts the first mip level of an image into tightly packed RGBA8 data, decompressing it if needed, or
+/// returns [`None`] if the format isn't supported.
+fn to_rgba8(image: &Image) -> Option<Vec<u8>> {
+    // 16-bit images are stored in native byte order, so only keep the high byte of each channel
+    fn high(channel: &[u8]) -> u8 {
+        (u16::from_ne_bytes([channel[0], channel[1]]) >> 8) as u8
+    }
+
+    let (width, height) = (image.width(), image.height());
+    let pixels = width as usize * height as usize;
+    let data = &image.data;
+    let rgba: Vec<u8> = match image.texture_descriptor.format {
+        TextureFormat::R8Unorm => data.iter().take(pixels).flat_map(|&l| [l, l, l, 0xFF]).collect(),
+        TextureFormat::Rg8Unorm => {
+            data.chunks_exact(2).take(pixels).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect()
+        }
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => data.get(..pixels * 4)?.to_vec(),
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => {
+            data.chunks_exact(4).take(pixels).flat_map(|p| [p[2], p[1], p[0], p[3]]).collect()
+        }
+        TextureFormat::R16Unorm => {
+            data.chunks_exact(2).take(pixels).flat_map(|p| [high(p); 3].into_iter().chain([0xFF])).collect()
+        }
+        TextureFormat::Rg16Unorm => data
+            .chunks_exact(4)
+            .take(pixels)
+            .flat_map(|p| [high(p), high(p), high(p), high(&p[2..])])
+            .collect(),
+        TextureFormat::Rgba16Unorm => data
+            .chunks_exact(8)
+            .take(pixels)
+            .flat_map(|p| [high(p), high(&p[2..]), high(&p[4..]), high(&p[6..])])
+            .collect(),
+        TextureFormat::Bc1RgbaUnorm | TextureFormat::Bc1RgbaUnormSrgb => {
+            bc::decode(data, width, height, bc::Format::BC1).ok()?.into_vec()
+        }
+        TextureFormat::Bc3RgbaUnorm | TextureFormat::Bc3RgbaUnormSrgb => {
+            bc::decode(data, width, height, bc::Format::BC3).ok()?.into_vec()
+        }
+        _ => return None,
+    };
+    (rgba.len() == pixels * 4).then_some(rgba)
+}
+
+/// Resizes a single channel image, averaging every source pixel that each new pixel covers.
+fn resize_channel(data: &[u8], (width, height): (u32, u32), (new_width, new_height): (u32, u32)) -> Vec<u8> {
+    // Always cover at least one source pixel, so this also works for upscaling
+    let span = |index: u32, old: u32, new: u32| {
+        let start = (u64::from(index) * u64::from(old) / u64::from(new)) as u32;
+        let end = (u64::from(index + 1) * u64::from(old)).div_ceil(u64::from(new)) as u32;
+        start..end.max(start + 1)
+    };
+
+    let mut output = Vec::with_capacity(new_width as usize * new_height as usize);
+    for y in 0..new_height {
+        let rows = span(y, height, new_height);
+        for x in 0..new_width {
+            let columns = span(x, width, new_width);
+            let (mut sum, mut count) = (0u32, 0u32);
+            for row in rows.clone() {
+                for column in columns.clone() {
+                    sum += u32::from(data[(row * width + column) as usize]);
+                    count += 1;
+                }
+            }
+            output.push(((sum + count / 2) / count) as u8);
+        }
+    }
+    output
+}
+
+/// Decomposes a matrix the same way as Panda3D, into a scale, shear, HPR rotation and translation, returning
+/// the [`Transform`] without the shear, along with the shear as (XY, XZ, YZ).
+fn decompose_hpr(matrix: Mat4, epsilon: f32) -> (Transform, Vec3) {
+    let translation = matrix.w_axis.truncate();
+    let (x, y, z) = (
+        matrix.x_axis.truncate(),
+        matrix.y_axis.truncate(),
+        matrix.z_axis.truncate(),
+    );
+
+    // Orthogonalize each axis against the previous ones, keeping track of the shear between them
+    let scale_x = x.length();
+    if scale_x < epsilon {
+        return (Transform::from_matrix(matrix), Vec3::ZERO);
+    }
+    let x = x / scale_x;
+    let shear_xy = x.dot(y);
+    let y = y - x * shear_xy;
+    let scale_y = y.length();
+    if scale_y < epsilon {
+        return (Transform::from_matrix(matrix), Vec3::ZERO);
+    }
+    let y = y / scale_y;
+    let (shear_xz, shear_yz) = (x.dot(z), y.dot(z));
+    let z = z - x * shear_xz - y * shear_yz;
+    let mut scale_z = z.length();
+    if scale_z < epsilon {
+        return (Transform::from_matrix(matrix), Vec3::ZERO);
+    }
+    let mut z = z / scale_z;
+    // Mirrored matrices are represented with a negative Z scale
+    if x.cross(y).dot(z) < 0.0 {
+        scale_z = -scale_z;
+        z = -z;
+    }
+    let shear = Vec3::new(shear_xy / scale_y, shear_xz / scale_z, shear_yz / scale_z);
+
+    // Use the same HPR order as animation channels, and snap angles that are nearly zero like Panda3D does,
+    // so joints don't pick up tiny rotations from rounding errors
+    let (heading, pitch, roll) = Quat::from_mat3(&Mat3::from_cols(x, y, z)).to_euler(EulerRot::ZXY);
+    let snap = |angle: f32| match angle.abs() < epsilon {
+        true => 0.0,
+        false => angle,
+    };
+    let rotation = Quat::from_euler(EulerRot::ZXY, snap(heading), snap(pitch), snap(roll));
+
+    (
+        Transform { translation, rotation, scale: Vec3::new(scale_x, scale_y, scale_z) },
+        shear,
+    )
+}
+
+/// Returns whether `fps` can be used as a frame rate, which has to be finite and positive.
+#[inline]
+fn is_valid_fps(fps: f32) -> bool {
+    fps.is_finite() && fps > 0.0
+}
+
+/// Resamples evenly spaced `values` from `fps` to `target_fps`, using `interpolate` between frames.
+fn resample<T: Copy>(values: &[T], fps: f32, target_fps: f32, interpolate: fn(T, T, f32) -> T) -> Vec<T> {
+    let Some(last) = values.len().checked_sub(1) else {
+        return Vec::new();
+    };
+    // Keep the same length, rounding slightly so that the last frame isn't dropped due to rounding errors
+    let count = (last as f32 / fps * target_fps + 1e-3).floor() as usize + 1;
+    (0..count)
+        .map(|i| {
+            let frame = i as f32 / target_fps * fps;
+            let index = (frame.floor() as usize).min(last);
+            interpolate(values[index], values[(index + 1).min(last)], frame - index as f32)
+        })
+        .collect()
+}
+
+/// Removes keyframes that are the same as both of their neighbors, which doesn't change the animation. If
+/// every keyframe is the same, only the first one is kept.
+fn strip_keyframes<T: Copy + PartialEq>(keyframes: Vec<(f32, T)>) -> Vec<(f32, T)> {
+    if keyframes.windows(2).all(|pair| pair[0].1 == pair[1].1) {
+        return keyframes.into_iter().take(1).collect();
+    }
+    let mut stripped = Vec::with_capacity(keyframes.len());
+    for (i, keyframe) in keyframes.iter().enumerate() {
+        let same_as_neighbors = i > 0
+            && i + 1 < keyframes.len()
+            && keyframes[i - 1].1 == keyframe.1
+            && keyframes[i + 1].1 == keyframe.1;
+        if !same_as_neighbors {
+            stripped.push(*keyframe);
+        }
+    }
+    stripped
+}
+
+/// How joint matrices are converted into a [`Transform`], see [`LoadSettings::joint_decomposition`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JointDecomposition {
+    /// Decompose each matrix directly, which assumes that it has no shear
+    #[default]
+    Matrix,
+    /// Decompose each matrix using Panda3D's conventions, into a scale, shear, HPR rotation and translation,
+    /// then drop the shear, which keeps the rotation stable for sheared joints
+    Hpr,
+}
+
+/// How nodes that are attached to more than one parent are loaded, see [`LoadSettings::instancing`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InstanceMode {
+    /// Give every instance its own copy of each mesh and material, so that they can be changed separately
+    #[default]
+    Duplicate,
+    /// Share meshes and materials between every instance, which uses less memory and allows them to be
+    /// batched together. Skinned meshes are always duplicated, since each Character has its own joints
+    Share,
+}
+
+/// How the color data of loaded textures is interpreted, see [`LoadSettings::texture_color_space`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextureColorSpace {
+    /// Use each Texture's format and how its TextureStage uses it: normal, gloss, and height maps, along with
+    /// formats that don't store color such as depth or integers, are linear, and everything else is sRGB
+    #[default]
+    Auto,
+    /// Treat every texture as sRGB
+    Srgb,
+    /// Treat every texture as linear
+    Linear,
+}
+
+impl TextureColorSpace {
+    /// Returns whether a texture with the given format, used by a TextureStage with the given mode, is sRGB.
+    fn is_srgb(self, format: Format, mode: Mode) -> bool {
+        match self {
+            Self::Srgb => true,
+            Self::Linear => false,
+            Self::Auto => match format {
+                Format::SRGB | Format::SRGBAlpha | Format::SLuminance | Format::SLuminanceAlpha => true,
+                Format::DepthStencil
+                | Format::DepthComponent
+                | Format::DepthComponent16
+                | Format::DepthComponent24
+                | Format::DepthComponent32
+                | Format::R16
+                | Format::RG16
+                | Format::RGB16
+                | Format::RGBA16
+                | Format::R32
+                | Format::RG32
+                | Format::RGB32
+                | Format::RGBA32
+                | Format::R32I
+                | Format::R8I
+                | Format::RG8I
+                | Format::RGB8I
+                | Format::RGBA8I
+                | Format::R16I
+                | Format::RG16I
+                | Format::RGB16I
+                | Format::RGBA16I
+                | Format::RG32I
+                | Format::RGB32I
+                | Format::RGBA32I
+                | Format::R11G11B10
+                | Format::RGB9E5 => false,
+                _ => !matches!(
+                    mode,
+                    Mode::Normal
+                        | Mode::NormalHeight
+                        | Mode::NormalGloss
+                        | Mode::Gloss
+                        | Mode::Height
+                        | Mode::Selector
+                ),
+            },
+        }
+    }
+}
+
+/// A joint whose matrix couldn't be represented exactly as a [`Transform`], see
+/// [`Panda3DAsset::lossy_joints`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LossyJoint {
+    /// Name of the joint
+    pub name: String,
+    /// Shear that was dropped, as (XY, XZ, YZ), or zero when using [`JointDecomposition::Matrix`]
+    pub shear: Vec3,
+    /// Largest difference between any element of the original and converted matrices
+    pub error: f32,
+}
+
+/// Category of a [`Diagnostic`], which can be made fatal using [`LoadSettings::fatal_diagnostics`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum DiagnosticKind {
+    /// An attribute or setting on a node that isn't converted, so the node may look different
+    UnhandledAttrib,
+    /// A node that isn't the type we expected, or that we don't know how to handle
+    UnknownNode,
+    /// A feature that Panda3D supports but isn't implemented yet, which is skipped
+    SkippedFeature,
+    /// Data that was converted, but may have lost precision
+    Lossy,
+    /// Data that's invalid or missing, so whatever uses it is skipped
+    InvalidData,
+}
+
+/// Something that couldn't be loaded exactly, see [`Panda3DAsset::diagnostics`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct Diagnostic {
+    /// Category of the diagnostic
+    pub kind: DiagnosticKind,
+    /// Stable identifier for what went wrong, such as `unhandled_render_state`, which is also used as the
+    /// name of the logged event
+    pub code: &'static str,
+    /// Index of the node that caused it, if there is one
+    pub node: Option<usize>,
+    /// Human-readable description
+    pub message: String,
+}
+
+impl core::fmt::Display for Diagnostic {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+/// All [`Diagnostic`]s reported while loading an asset, in the order they were found.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct Diagnostics {
+    entries: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    #[inline]
+    fn push(&mut self, diagnostic: Diagnostic) {
+        self.entries.push(diagnostic);
+    }
+
+    /// Returns an iterator over every diagnostic.
+    #[inline]
+    pub fn iter(&self) -> core::slice::Iter<'_, Diagnostic> {
+        self.entries.iter()
+    }
+
+    /// Returns the total number of diagnostics.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the asset loaded without any diagnostics.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of diagnostics of the given kind.
+    #[must_use]
+    #[inline]
+    pub fn count(&self, kind: DiagnosticKind) -> usize {
+        self.of_kind(kind).count()
+    }
+
+    /// Returns the number of diagnostics of each kind, leaving out any kinds that weren't reported.
+    #[must_use]
+    pub fn counts(&self) -> BTreeMap<DiagnosticKind, usize> {
+        let mut counts = BTreeMap::new();
+        for diagnostic in &self.entries {
+            *counts.entry(diagnostic.kind).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Returns an iterator over every diagnostic of the given kind.
+    #[inline]
+    pub fn of_kind(&self, kind: DiagnosticKind) -> impl Iterator<Item = &Diagnostic> {
+        self.entries.iter().filter(move |diagnostic| diagnostic.kind == kind)
+    }
+
+    /// Checks that none of the diagnostics are one of the `fatal` kinds.
+    ///
+    /// # Errors
+    /// Returns [`FatalDiagnostic`](bam::Error::FatalDiagnostic) for the first diagnostic that is.
+    pub fn check(&self, fatal: &[DiagnosticKind]) -> Result<(), bam::Error> {
+        match self.entries.iter().find(|diagnostic| fatal.contains(&diagnostic.kind)) {
+            Some(diagnostic) => Err(bam::Error::FatalDiagnostic {
+                code: diagnostic.code,
+                message: diagnostic.message.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a Diagnostic;
+    type IntoIter = core::slice::Iter<'a, Diagnostic>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// How a [`PartBundle`](https://docs.panda3d.org/1.10/python/reference/panda3d.core.PartBundle) blends
+/// between multiple animations that are playing at once.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PartBlendType {
+    /// Matrices are blended linearly, which may introduce scale and shear
+    Linear,
+    /// Matrices are blended linearly, then normalized to remove any scale and shear
+    #[default]
+    NormalizedLinear,
+    /// Each component (position, rotation, scale, shear) is blended separately
+    Componentwise,
+    /// Each component is blended separately, with rotations blended as quaternions
+    ComponentwiseQuat,
+}
+
+impl From<BlendType> for PartBlendType {
+    #[inline]
+    fn from(value: BlendType) -> Self {
+        match value {
+            BlendType::Linear => Self::Linear,
+            BlendType::NormalizedLinear => Self::NormalizedLinear,
+            BlendType::Componentwise => Self::Componentwise,
+            BlendType::ComponentwiseQuat => Self::ComponentwiseQuat,
+        }
+    }
+}
+
+/// Blending configuration of a Character's PartBundle, attached alongside its [`AnimationPlayer`].
+///
+/// Bevy always blends animations based on the weights in an [`AnimationGraph`], so this is only informational,
+/// for setups that need to mimic Panda3D, such as a half-body animation playing on top of another.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PartBundleBlend {
+    /// How multiple animations are blended together
+    pub blend_type: PartBlendType,
+    /// Whether multiple animations are allowed to play at once, instead of only the most recent one
+    pub anim_blend: bool,
+    /// Whether animations should interpolate between frames, instead of snapping to the nearest frame
+    pub frame_blend: bool,
+}
+
+/// Constant depth bias given to meshes in the `background` bin, which is large enough to push them behind
+/// everything else, since Bevy can't draw them before the rest of the scene like Panda3D does.
+const BACKGROUND_DEPTH_BIAS: i32 = -(1 << 24);
+
+/// [Cull bin](https://docs.panda3d.org/1.10/python/programming/rendering-process/how-to-control-render-order)
+/// that a mesh was assigned to with a CullBinAttrib, attached alongside its material.
+///
+/// Panda3D draws each bin one after another, but Bevy only sorts meshes by depth, so the loader approximates
+/// this with a depth bias on the material (see [`depth_bias`](Self::depth_bias)), which keeps skyboxes behind
+/// the scene and stops overlapping planes from z-fighting. This component allows any system that needs the
+/// exact ordering, such as one that assigns render layers or cameras, to look it up.
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub enum CullBin {
+    /// Drawn first, in draw order, without writing depth, such as skyboxes
+    Background(i32),
+    /// Drawn sorted by state, which is where meshes go by default
+    Opaque,
+    /// Drawn back to front, which is where transparent meshes go by default
+    Transparent,
+    /// Drawn in draw order, such as decals or UI elements on top of each other
+    Fixed(i32),
+    /// Drawn in whatever order they're encountered
+    Unsorted,
+    /// Any bin defined by the game itself, which is usually sorted in draw order
+    Other { name: String, draw_order: i32 },
+}
+
+impl CullBin {
+    /// Returns the constant depth bias given to materials in this bin, where meshes with a larger bias are
+    /// drawn on top of ones with a smaller bias at the same depth.
+    #[must_use]
+    #[inline]
+    pub fn depth_bias(&self) -> i32 {
+        match self {
+            Self::Background(draw_order) => BACKGROUND_DEPTH_BIAS.saturating_add(*draw_order),
+            Self::Opaque | Self::Transparent | Self::Unsorted => 0,
+            Self::Fixed(draw_order) | Self::Other { draw_order, .. } => *draw_order,
+        }
+    }
+}
+
+impl From<&CullBinAttrib> for CullBin {
+    #[inline]
+    fn from(attrib: &CullBinAttrib) -> Self {
+        match attrib.bin_name.as_str() {
+            "background" => Self::Background(attrib.draw_order),
+            "opaque" => Self::Opaque,
+            "transparent" => Self::Transparent,
+            "fixed" => Self::Fixed(attrib.draw_order),
+            "unsorted" => Self::Unsorted,
+            name => Self::Other { name: name.to_string(), draw_order: attrib.draw_order },
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoadSettings {
+    /// How joint matrices are converted into a [`Transform`]
+    pub joint_decomposition: JointDecomposition,
+    /// Whether loaded textures are sRGB or linear, which only changes how they're sampled, not their data
+    pub texture_color_space: TextureColorSpace,
+    /// Whether separate alpha images that are a different size than their color image are resized to match,
+    /// instead of being ignored
+    pub resize_alpha: bool,
+    /// Whether to convert triangle strips into lists and merge duplicate vertices, which is faster to render
+    /// for models made of many small strips, such as environments
+    pub optimize_meshes: bool,
+    /// Whether to merge static subtrees (ones without any animations, effects, or tags) into a single mesh per
+    /// RenderState, like Panda3D's `flatten_strong`, which greatly reduces the number of entities and draw
+    /// calls for large scenes such as Toontown streets. Merged nodes lose their names and transforms.
+    pub flatten_static: bool,
+    /// How nodes that are instanced (attached to more than one parent, see [`BinaryAsset::instances`]) are
+    /// loaded. Entities can only have one parent, so each instance always gets its own copy of the subtree's
+    /// entities, but their meshes and materials can be shared
+    pub instancing: InstanceMode,
+    /// Largest difference between the original and converted joint matrices that isn't reported as lossy,
+    /// which is also used to snap nearly-zero angles when using [`JointDecomposition::Hpr`]
+    pub decomposition_epsilon: f32,
+    /// Frame rate to resample animations to, or [`None`] to keep each animation's own frame rate. Frame rates
+    /// that aren't finite and positive are reported and ignored
+    pub animation_fps: Option<f32>,
+    /// Whether to store channels that never change as a single value, and drop keyframes that are the same as
+    /// both of their neighbors
+    pub strip_constant_channels: bool,
+    /// Rounds animated rotations to a multiple of this many degrees, which allows more keyframes to be
+    /// stripped, or [`None`] to keep them as-is
+    pub rotation_precision: Option<f32>,
+    /// Asset paths of separate animation files to load along with the model, such as Toontown's `-walk.bam`
+    /// files. Their animations are bound to the model's joints by name, and are added to
+    /// [`Panda3DAsset::animations`] after any animations in the model itself, in the same order.
+    pub animations: Vec<String>,
+    /// Kinds of [`Diagnostic`] that fail loading with [`FatalDiagnostic`](bam::Error::FatalDiagnostic),
+    /// instead of only being reported in [`Panda3DAsset::diagnostics`]
+    pub fatal_diagnostics: Vec<DiagnosticKind>,
+}
+
+impl Default for LoadSettings {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            joint_decomposition: JointDecomposition::Matrix,
+            texture_color_space: TextureColorSpace::Auto,
+            resize_alpha: true,
+            optimize_meshes: false,
+            flatten_static: false,
+            instancing: InstanceMode::Duplicate,
+            decomposition_epsilon: 1e-4,
+            animation_fps: None,
+            strip_constant_channels: false,
+            rotation_precision: None,
+            animations: Vec::new(),
+            fatal_diagnostics: Vec::new(),
+        }
+    }
+}
+
+/// Rewrites the paths that [`AnimationTargetId`]s are created from, so that animations made for one skeleton
+/// can be bound to another, such as a different Toon body type whose joints are named differently.
+///
+/// Each path first has its leading names stripped, then has every name that has an alias renamed, and then
+/// has the new prefix added. For example, stripping 1 name and adding the model's PartBundle name as the
+/// prefix replaces the AnimBundle name, which is how [`LoadSettings::animations`] binds animations.
+///
+/// Use [`apply`](Self::apply) along with [`Panda3DAsset::animation_paths`] to rebind a loaded
+/// [`AnimationClip`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TargetRemap {
+    strip: usize,
+    prefix: Vec<Name>,
+    aliases: HashMap<Name, Name>,
+}
+
+impl TargetRemap {
+    /// Creates a new remap that leaves every path unchanged.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes the first `count` names from each path, such as the AnimBundle name.
+    #[must_use]
+    #[inline]
+    pub fn strip_prefix(mut self, count: usize) -> Self {
+        self.strip = count;
+        self
+    }
+
+    /// Adds `names` to the start of each path, after any names have been stripped.
+    #[must_use]
+    #[inline]
+    pub fn prefix<I, N>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = N>,
+        N: Into<Cow<'static, str>>,
+    {
+        self.prefix = names.into_iter().map(Name::new).collect();
+        self
+    }
+
+    /// Renames every joint called `from` to `to`, anywhere in the path.
+    #[must_use]
+    #[inline]
+    pub fn alias<F, T>(mut self, from: F, to: T) -> Self
+    where
+        F: Into<Cow<'static, str>>,
+        T: Into<Cow<'static, str>>,
+    {
+        self.aliases.insert(Name::new(from), Name::new(to));
+        self
+    }
+
+    /// Returns the rewritten version of `path`.
+    #[must_use]
+    pub fn path(&self, path: &[Name]) -> Vec<Name> {
+        let names = path.iter().skip(self.strip).map(|name| self.aliases.get(name).unwrap_or(name));
+        self.prefix.iter().chain(names).cloned().collect()
+    }
+
+    /// Returns the [`AnimationTargetId`] for the rewritten version of `path`.
+    #[must_use]
+    #[inline]
+    pub fn target_id(&self, path: &[Name]) -> AnimationTargetId {
+        AnimationTargetId::from_names(self.path(path).iter())
+    }
+
+    /// Rebinds every curve in `clip` to its rewritten target, using `paths` to look up the path each target
+    /// was created from, and returns how many targets were rebound. Targets without a known path are left
+    /// unchanged.
+    pub fn apply(&self, clip: &mut AnimationClip, paths: &HashMap<AnimationTargetId, Vec<Name>>) -> usize {
+        let targets: Vec<_> = clip.curves().keys().copied().filter(|id| paths.contains_key(id)).collect();
+        let curves: Vec<_> = targets
+            .iter()
+            .filter_map(|id| Some((self.target_id(&paths[id]), clip.curves_mut().remove(id)?)))
+            .collect();
+        // Only reinsert once every old target is removed, in case one target is renamed to another
+        for (id, curves) in curves {
+            clip.curves_mut().entry(id).or_default().extend(curves);
+        }
+        targets.len()
+    }
+}
 
 #[derive(Debug, Default)]
 pub struct Panda3DLoader;
@@ -1389,14 +2664,50 @@ pub struct Panda3DAsset {
     /// All entities that have an AnimationPlayer attached
     pub animators: Vec<Entity>,
     pub animations: Vec<Handle<AnimationClip>>,
+    /// Path of every target in [`animations`](Self::animations) as it was stored in the file, which is needed
+    /// to rebind them using a [`TargetRemap`]
+    pub animation_paths: HashMap<AnimationTargetId, Vec<Name>>,
+    /// Joints that couldn't be converted exactly, which may deform incorrectly
+    pub lossy_joints: Vec<LossyJoint>,
+    /// Whether the file stored floating-point values as doubles, which have been converted to floats and may
+    /// have lost precision
+    pub double_precision: bool,
+    /// Everything that couldn't be loaded exactly, such as unhandled attributes, unknown nodes, and skipped
+    /// features, which can be checked instead of reading through the logs
+    pub diagnostics: Diagnostics,
 }
 
 struct AssetLoaderData<'loader, 'context> {
     world: &'loader mut World,
     context: &'loader mut LoadContext<'context>,
     assets: &'loader mut Panda3DAsset,
+    settings: &'loader LoadSettings,
     // Stores all Texture NodeIDs and their Image# so we don't try to load image files twice
     image_cache: HashMap<usize, usize>,
+    // Stores the assets created for each Geom and RenderState, so instances can share them
+    geom_cache: HashMap<(usize, usize), (Handle<Mesh>, Handle<Panda3DMaterial>)>,
+    // Stores the AnimationTargetId of every joint by its path without the PartBundle name, since AnimBundles
+    // are usually named differently, especially when they come from a separate file
+    joint_targets: HashMap<Vec<Name>, AnimationTargetId>,
+}
+
+impl AssetLoaderData<'_, '_> {
+    /// Returns the [`AnimationTargetId`] for the joint at `path`, and remembers it for binding animations.
+    fn joint_target(&mut self, path: &[Name]) -> AnimationTargetId {
+        let id = AnimationTargetId::from_names(path.iter());
+        // Multi-part Characters can reuse paths in each part, in which case the first part wins
+        self.joint_targets.entry(path[1..].to_vec()).or_insert(id);
+        id
+    }
+
+    /// Returns the [`AnimationTargetId`] for the animation channel at `path`, which is the matching joint if
+    /// one has been loaded, since Panda3D binds animations to joints by name regardless of the bundle name.
+    fn animation_target(&self, path: &[Name]) -> AnimationTargetId {
+        match self.joint_targets.get(&path[1..]) {
+            Some(id) => *id,
+            None => AnimationTargetId::from_names(path.iter()),
+        }
+    }
 }
 
 impl AssetLoader for Panda3DLoader {
@@ -1405,7 +2716,7 @@ impl AssetLoader for Panda3DLoader {
     type Settings = LoadSettings;
 
     async fn load(
-        &self, reader: &mut dyn Reader, _settings: &Self::Settings, load_context: &mut LoadContext<'_>,
+        &self, reader: &mut dyn Reader, settings: &Self::Settings, load_context: &mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
         // let start_time = bevy_internal::utils::Instant::now();
 
@@ -1417,19 +2728,35 @@ impl AssetLoader for Panda3DLoader {
         let bam = BinaryAsset::load(bytes)?;
 
         // Now we need to post-process it into a scene the user can actually spawn
-        let mut assets = Self::Asset::default();
+        let mut assets = Self::Asset { double_precision: bam.use_double(), ..Default::default() };
         let mut world = World::default();
 
         let mut loader = AssetLoaderData {
             world: &mut world,
             context: load_context,
             assets: &mut assets,
+            settings,
             image_cache: HashMap::new(),
+            geom_cache: HashMap::new(),
+            joint_targets: HashMap::new(),
         };
 
+        if bam.use_double() {
+            report!(loader, Lossy, name: "double_precision", node: None,
+                "BAM file uses double-precision floats, which will be converted to single-precision.");
+        }
+
+        let instances = bam.instances().len();
+        if instances > 0 {
+            debug!(name: "instanced_nodes", target: "Panda3DLoader",
+                "{} nodes are instanced, loading them using {:?}.", instances, settings.instancing);
+        }
+
         // Let's first pull out the root node, since it's a placeholder.
         let Some(root_node) = bam.nodes.get_as::<ModelNode>(0) else {
-            warn!(name: "not_a_model_node", target: "Panda3DLoader", "Root Node isn't a ModelNode! Aborting loading.");
+            report!(loader, UnknownNode, name: "not_a_model_node", node: Some(0),
+                "Root Node isn't a ModelNode! Aborting loading.");
+            loader.assets.diagnostics.check(&settings.fatal_diagnostics)?;
             return Ok(assets);
         };
 
@@ -1441,7 +2768,8 @@ impl AssetLoader for Panda3DLoader {
             || root_node.attributes != 0
             || root_node.child_refs.len() != 1
         {
-            warn!(name: "unexpected_root_node", target: "Panda3DLoader", "Root Node doesn't have default parameters! May not be loaded correctly.");
+            report!(loader, UnhandledAttrib, name: "unexpected_root_node", node: Some(0),
+                "Root Node doesn't have default parameters! May not be loaded correctly.");
         }
 
         block_on(bam.recurse_nodes(
@@ -1453,8 +2781,16 @@ impl AssetLoader for Panda3DLoader {
             root_node.child_refs[0].0 as usize,
         ));
 
+        // Animations are stored in separate files, which only make sense once the model's joints are known
+        for path in &settings.animations {
+            let bytes =
+                loader.context.read_asset_bytes(path.as_str()).await.map_err(std::io::Error::other)?;
+            BinaryAsset::load(bytes)?.convert_animations(&mut loader, 0);
+        }
+
         assets.scene = load_context.add_labeled_asset("Scene0".to_string(), Scene::new(world));
 
+        assets.diagnostics.check(&settings.fatal_diagnostics)?;
         Ok(assets)
     }
 
@@ -1479,17 +2815,19 @@ impl Plugin for Panda3DPlugin {
 pub struct Panda3DExtension {
     depth_write_enabled: bool,
     decal_effect: bool,
+    depth_bias: i32,
 }
 
 #[derive(Eq, PartialEq, Hash, Clone)]
 pub struct Panda3DExtensionKey {
     depth_write_enabled: bool,
     decal_effect: bool,
+    depth_bias: i32,
 }
 
 impl Default for Panda3DExtension {
     fn default() -> Self {
-        Self { depth_write_enabled: true, decal_effect: false }
+        Self { depth_write_enabled: true, decal_effect: false, depth_bias: 0 }
     }
 }
 
@@ -1500,9 +2838,10 @@ impl MaterialExtension for Panda3DExtension {
     ) -> Result<(), SpecializedMeshPipelineError> {
         if let Some(depth_stencil) = descriptor.depth_stencil.as_mut() {
             depth_stencil.depth_write_enabled = key.bind_group_data.depth_write_enabled;
+            depth_stencil.bias.constant = key.bind_group_data.depth_bias;
             if key.bind_group_data.decal_effect {
                 //TODO: tweak these more if they give any trouble
-                depth_stencil.bias.constant = 1;
+                depth_stencil.bias.constant = depth_stencil.bias.constant.saturating_add(1);
                 depth_stencil.bias.slope_scale = 0.5;
                 depth_stencil.depth_write_enabled = false;
             }
@@ -1516,6 +2855,7 @@ impl From<&Panda3DExtension> for Panda3DExtensionKey {
         Self {
             depth_write_enabled: extension.depth_write_enabled,
             decal_effect: extension.decal_effect,
+            depth_bias: extension.depth_bias,
         }
     }
 }
@@ -0,0 +1,142 @@
+//! Adds an [`AssetReader`] that loads assets straight out of a [`MultifileSet`], so that models and their
+//! textures can be loaded from a game's Multifiles without having to extract them first.
+//!
+//! Models reference their textures using paths such as `phase_3/maps/avatar_palette_1lla_1.jpg`, which are
+//! loaded from the default asset source. Because of this, the reader is meant to replace the default source,
+//! falling back to the regular `assets` folder for anything that isn't in a Multifile. This matches
+//! Panda3D, which mounts its Multifiles over the real filesystem.
+//!
+//! ```no_run
+//! use bevy_internal::asset::io::AssetSourceId;
+//! use bevy_internal::asset::AssetApp;
+//! use bevy_internal::prelude::*;
+//! use orthrus_panda3d::bevy_multifile::MultifileAssetReader;
+//! use orthrus_panda3d::prelude::*;
+//!
+//! let mut set = MultifileSet::new();
+//! for phase in ["3", "3.5", "4", "5", "5.5", "6", "7", "8", "9", "10", "11", "12", "13"] {
+//!     set.open(format!("phase_{phase}.mf"), "/")?;
+//! }
+//!
+//! // Asset sources have to be registered before the AssetPlugin is added
+//! let mut app = App::new();
+//! app.register_asset_source(AssetSourceId::Default, MultifileAssetReader::source(set, "assets"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use bevy_internal::asset::io::{
+    AssetReader, AssetReaderError, AssetSource, AssetSourceBuilder, ErasedAssetReader, PathStream, Reader,
+    VecReader,
+};
+use bevy_tasks::futures_lite::{stream, StreamExt};
+
+use crate::multifile::Error;
+use crate::multifile_set::MultifileSet;
+
+/// Reads assets from a [`MultifileSet`], optionally falling back to another [`AssetReader`] for any paths
+/// that aren't in it.
+///
+/// See the [module documentation](self) for more information.
+pub struct MultifileAssetReader {
+    set: Arc<MultifileSet>,
+    fallback: Option<Box<dyn ErasedAssetReader>>,
+}
+
+impl MultifileAssetReader {
+    /// Creates a new reader that only reads from `set`.
+    #[must_use]
+    #[inline]
+    pub fn new<S: Into<Arc<MultifileSet>>>(set: S) -> Self {
+        Self { set: set.into(), fallback: None }
+    }
+
+    /// Reads any paths that aren't in the [`MultifileSet`] using `fallback` instead.
+    #[must_use]
+    #[inline]
+    pub fn with_fallback(mut self, fallback: Box<dyn ErasedAssetReader>) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    /// Returns an [`AssetSourceBuilder`] that reads from `set`, falling back to the platform's default reader
+    /// for the folder at `path`. Register it as [`AssetSourceId::Default`] to load everything through it.
+    ///
+    /// [`AssetSourceId::Default`]: bevy_internal::asset::io::AssetSourceId::Default
+    #[must_use]
+    pub fn source<S: Into<Arc<MultifileSet>>>(set: S, path: &str) -> AssetSourceBuilder {
+        let set = set.into();
+        let mut fallback = AssetSource::get_default_reader(path.to_owned());
+        AssetSource::build().with_reader(move || Box::new(Self::new(set.clone()).with_fallback(fallback())))
+    }
+
+    /// Returns the decompressed contents of `path`, or [`None`] if it isn't in the [`MultifileSet`].
+    fn read_bytes(&self, path: &Path) -> Option<Result<Vec<u8>, AssetReaderError>> {
+        let name = path.to_str()?;
+        let mut reader = match self.set.reader(name) {
+            Ok(reader) => reader,
+            Err(Error::FileNotFound { .. }) => return None,
+            Err(error) => return Some(Err(std::io::Error::other(error).into())),
+        };
+        let mut bytes = Vec::with_capacity(usize::try_from(reader.len()).unwrap_or_default());
+        Some(reader.read_to_end(&mut bytes).map(|_| bytes).map_err(Into::into))
+    }
+}
+
+impl AssetReader for MultifileAssetReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<Box<dyn Reader + 'a>, AssetReaderError> {
+        if let Some(bytes) = self.read_bytes(path) {
+            return Ok(Box::new(VecReader::new(bytes?)));
+        }
+        match &self.fallback {
+            Some(fallback) => fallback.read(path).await,
+            None => Err(AssetReaderError::NotFound(path.to_owned())),
+        }
+    }
+
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<Box<dyn Reader + 'a>, AssetReaderError> {
+        // Meta files are stored next to their asset, the same as on disk
+        let mut meta_path = path.as_os_str().to_owned();
+        meta_path.push(".meta");
+        let meta_path = PathBuf::from(meta_path);
+        if let Some(bytes) = self.read_bytes(&meta_path) {
+            return Ok(Box::new(VecReader::new(bytes?)));
+        }
+        match &self.fallback {
+            Some(fallback) => fallback.read_meta(path).await,
+            None => Err(AssetReaderError::NotFound(meta_path)),
+        }
+    }
+
+    async fn read_directory<'a>(&'a self, path: &'a Path) -> Result<Box<PathStream>, AssetReaderError> {
+        let mut entries: Vec<PathBuf> = match path.to_str() {
+            Some(name) => self.set.read_dir(name).into_iter().map(|entry| path.join(entry)).collect(),
+            None => Vec::new(),
+        };
+        if let Some(fallback) = &self.fallback {
+            if let Ok(stream) = fallback.read_directory(path).await {
+                entries.extend(stream.collect::<Vec<_>>().await);
+            }
+        }
+        if entries.is_empty() {
+            return Err(AssetReaderError::NotFound(path.to_owned()));
+        }
+
+        entries.sort_unstable();
+        entries.dedup();
+        Ok(Box::new(stream::iter(entries)))
+    }
+
+    async fn is_directory<'a>(&'a self, path: &'a Path) -> Result<bool, AssetReaderError> {
+        if path.to_str().is_some_and(|name| self.set.is_directory(name)) {
+            return Ok(true);
+        }
+        match &self.fallback {
+            Some(fallback) => fallback.is_directory(path).await,
+            None => Ok(false),
+        }
+    }
+}
@@ -7,6 +7,7 @@ use orthrus_core::prelude::*;
 
 /// This struct is mainly for readability in place of an unnamed tuple
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Version {
     pub major: u16,
     pub minor: u16,
@@ -27,14 +28,19 @@ pub struct Datagram<'a> {
 }
 
 impl<'a> Datagram<'a> {
+    /// Reads the next datagram. Readers that can't lend out their data (such as a [`DataStream`]) store it
+    /// in `buffer` instead, so it lives as long as the datagram.
     #[inline]
     pub(crate) fn new<T: ReadExt>(
-        data: &'a mut T, endian: Endian, float_type: bool,
+        data: &'a mut T, buffer: &'a mut Vec<u8>, endian: Endian, float_type: bool,
     ) -> Result<Self, DataError> {
         let length = data.read_u32()? as usize;
         let data = match data.read_slice(length)? {
             Cow::Borrowed(data) => data,
-            Cow::Owned(_) => todo!(),
+            Cow::Owned(data) => {
+                *buffer = data;
+                buffer.as_slice()
+            }
         };
         Ok(Self { cursor: DataCursorRef::new(data, endian), float_type })
     }
@@ -0,0 +1,473 @@
+//! Builds a cross-reference of which textures are used by which models across an entire asset tree, such as
+//! every phase of Toontown, to find textures that nothing uses and textures that models use but don't exist.
+//!
+//! Files are mounted the same way Panda3D's virtual file system mounts them: every [`Multifile`] is mounted
+//! at the root of the tree, so its [`Subfile`](crate::subfile::Subfile)s keep their stored paths (such as
+//! `phase_3/maps/...`), and loose files use their path relative to the root. A texture filename is looked up
+//! from the root first, and then relative to the directory of the model that uses it.
+//!
+//! Only `Texture` objects are tracked, since materials aren't parsed yet.
+//!
+//! # Usage
+//! ```no_run
+//! use orthrus_panda3d::crossref::CrossReference;
+//!
+//! let crossref = CrossReference::scan("Toontown Rewritten")?;
+//! for reference in crossref.references().iter().filter(|reference| !reference.found) {
+//!     println!("{} uses {}, which doesn't exist", reference.model, reference.texture);
+//! }
+//! std::fs::write("textures.json", crossref.to_json())?;
+//! std::fs::write("textures.dot", crossref.to_dot())?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use snafu::prelude::*;
+
+use crate::bam::{self, BinaryAsset, ObjectError};
+use crate::multifile::{self, Multifile};
+use crate::subfile::Flags;
+
+/// Error conditions for when building a cross-reference.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown if unable to read a file or directory.
+    #[snafu(display("Filesystem Error {}", source))]
+    FileError { source: std::io::Error },
+
+    /// Thrown if a Multifile can't be mounted, or one of its Subfiles can't be read.
+    #[snafu(display("{source}"))]
+    Multifile { source: multifile::Error },
+
+    /// Thrown if a model doesn't have a valid BAM header.
+    #[snafu(display("{source}"))]
+    Model { source: bam::Error },
+
+    /// Thrown if an object inside of a model failed to parse. The rest of the model is still used.
+    #[snafu(display("{error}"))]
+    Object { error: ObjectError },
+}
+type Result<T> = core::result::Result<T, Error>;
+
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(source: std::io::Error) -> Self {
+        Error::FileError { source }
+    }
+}
+
+impl From<multifile::Error> for Error {
+    #[inline]
+    fn from(source: multifile::Error) -> Self {
+        Error::Multifile { source }
+    }
+}
+
+impl From<bam::Error> for Error {
+    #[inline]
+    fn from(source: bam::Error) -> Self {
+        Error::Model { source }
+    }
+}
+
+/// File extensions that Panda3D can load as a texture image.
+const TEXTURE_EXTENSIONS: &[&str] = &[
+    "bmp", "bw", "dds", "exr", "gif", "hdr", "jpeg", "jpg", "ktx", "pgm", "png", "pnm", "ppm", "rgb", "rgba",
+    "sgi", "tga", "tif", "tiff", "txo",
+];
+
+/// Returns whether `path` has the extension of a texture image.
+fn is_texture(path: &str) -> bool {
+    path.rsplit_once('.').is_some_and(|(_, extension)| {
+        TEXTURE_EXTENSIONS.iter().any(|known| extension.eq_ignore_ascii_case(known))
+    })
+}
+
+/// Converts `path` into the form used for lookups, which uses forward slashes without any leading slash,
+/// `.`, or `..` components.
+fn normalize(path: &str) -> String {
+    let mut components = Vec::new();
+    for component in path.split(['/', '\\']) {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            _ => components.push(component),
+        }
+    }
+    components.join("/")
+}
+
+/// A texture used by a model, see [`CrossReference::references`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference<'a> {
+    /// Path of the model
+    pub model: &'a str,
+    /// Path of the texture, after looking it up. If it wasn't found, this is the filename stored in the model
+    pub texture: String,
+    /// Whether the texture exists in the tree
+    pub found: bool,
+}
+
+/// Textures used by the models in an asset tree, see the [module documentation](self) for more information.
+#[derive(Debug, Default)]
+pub struct CrossReference {
+    /// Path of every file in the tree
+    files: BTreeSet<String>,
+    /// Texture filenames used by each model, as stored in the model
+    models: BTreeMap<String, Vec<String>>,
+    /// Problems encountered while reading each file
+    errors: Vec<(String, Error)>,
+}
+
+impl CrossReference {
+    /// Creates an empty cross-reference, for adding files to manually.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a cross-reference of every file inside of `root`, see
+    /// [`add_directory`](Self::add_directory).
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to read the contents of `root`.
+    #[inline]
+    pub fn scan<P: AsRef<Path>>(root: P) -> Result<Self> {
+        let mut crossref = Self::new();
+        crossref.add_directory(root)?;
+        Ok(crossref)
+    }
+
+    /// Adds every file inside of `root` and its subdirectories, mounting any Multifiles at the root of the
+    /// tree. Files that can't be read or parsed are recorded in [`errors`](Self::errors) instead of stopping
+    /// the scan.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to read the contents of `root`.
+    pub fn add_directory<P: AsRef<Path>>(&mut self, root: P) -> Result<()> {
+        let root = root.as_ref();
+        let mut pending = vec![root.to_path_buf()];
+        let mut first = true;
+        while let Some(directory) = pending.pop() {
+            let entries =
+                match std::fs::read_dir(&directory).and_then(Iterator::collect::<std::io::Result<Vec<_>>>) {
+                    Ok(entries) => entries,
+                    // Only the root is required, anything else is recorded like any other unreadable file
+                    Err(source) if first => return Err(source.into()),
+                    Err(source) => {
+                        self.errors.push((directory.to_string_lossy().into_owned(), source.into()));
+                        continue;
+                    }
+                };
+            first = false;
+
+            let mut entries: Vec<_> = entries.into_iter().map(|entry| entry.path()).collect();
+            entries.sort();
+            let mut directories = Vec::new();
+            for path in entries {
+                if path.is_dir() {
+                    directories.push(path);
+                    continue;
+                }
+                let name = normalize(&path.strip_prefix(root).unwrap_or(&path).to_string_lossy());
+                let is_multifile =
+                    path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("mf"));
+                match is_multifile {
+                    true => match Multifile::open(&path, 0) {
+                        Ok(multifile) => self.add_multifile(&multifile),
+                        Err(source) => self.errors.push((name, source.into())),
+                    },
+                    false if name.to_ascii_lowercase().ends_with(".bam") => match std::fs::read(&path) {
+                        Ok(data) => self.add_model_data(&name, data),
+                        Err(source) => self.errors.push((name, source.into())),
+                    },
+                    false => self.add_file(&name),
+                }
+            }
+            // Directories are popped from the end, so push them in reverse to visit them in order
+            pending.extend(directories.into_iter().rev());
+        }
+        Ok(())
+    }
+
+    /// Adds every [`Subfile`](crate::subfile::Subfile) of `multifile`, parsing any models along the way.
+    /// Subfiles that can't be read or parsed are recorded in [`errors`](Self::errors).
+    pub fn add_multifile(&mut self, multifile: &Multifile) {
+        for subfile in multifile.subfiles() {
+            if subfile.flags.intersects(Flags::Signature | Flags::Deleted) {
+                continue;
+            }
+            let name = normalize(subfile.name());
+            if !name.to_ascii_lowercase().ends_with(".bam") {
+                self.add_file(&name);
+                continue;
+            }
+
+            let mut data = Vec::with_capacity(subfile.length() as usize);
+            let result = multifile
+                .reader(subfile.name())
+                .map_err(Error::from)
+                .and_then(|mut reader| Ok(std::io::Read::read_to_end(&mut reader, &mut data)?));
+            match result {
+                Ok(_) => self.add_model_data(&name, data),
+                Err(error) => {
+                    // Still count the model as part of the tree, even though its textures are unknown
+                    self.add_file(&name);
+                    self.errors.push((name, error));
+                }
+            }
+        }
+    }
+
+    /// Parses `data` as a model, recording any objects that fail to parse.
+    fn add_model_data(&mut self, path: &str, data: Vec<u8>) {
+        match BinaryAsset::load_lenient(data) {
+            Ok((asset, errors)) => {
+                self.add_model(path, &asset);
+                self.errors
+                    .extend(errors.into_iter().map(|error| (path.to_owned(), Error::Object { error })));
+            }
+            Err(source) => {
+                self.add_file(path);
+                self.errors.push((path.to_owned(), source.into()));
+            }
+        }
+    }
+
+    /// Adds a file that exists in the tree at `path`, which is relative to the root.
+    #[inline]
+    pub fn add_file(&mut self, path: &str) {
+        self.files.insert(normalize(path));
+    }
+
+    /// Adds a model that exists in the tree at `path`, which is relative to the root, along with every
+    /// texture it uses.
+    pub fn add_model(&mut self, path: &str, asset: &BinaryAsset) {
+        let path = normalize(path);
+        let textures = asset.texture_filenames().into_iter().map(str::to_owned).collect();
+        self.files.insert(path.clone());
+        self.models.insert(path, textures);
+    }
+
+    /// Returns the path of every file in the tree, in sorted order.
+    #[inline]
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.files.iter().map(String::as_str)
+    }
+
+    /// Returns the path of every model in the tree along with the texture filenames it uses, as they're
+    /// stored in the model.
+    #[inline]
+    pub fn models(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.models.iter().map(|(path, textures)| (path.as_str(), textures.as_slice()))
+    }
+
+    /// Returns every file that couldn't be read or parsed, along with the reason why.
+    #[must_use]
+    #[inline]
+    pub fn errors(&self) -> &[(String, Error)] {
+        &self.errors
+    }
+
+    /// Looks up `filename` as used by `model`, returning the path of the texture and whether it was found.
+    fn resolve(&self, model: &str, filename: &str) -> (String, bool) {
+        let path = normalize(filename);
+        if self.files.contains(&path) {
+            return (path, true);
+        }
+        if let Some((directory, _)) = model.rsplit_once('/') {
+            let relative = normalize(&format!("{directory}/{filename}"));
+            if self.files.contains(&relative) {
+                return (relative, true);
+            }
+        }
+        (path, false)
+    }
+
+    /// Returns every texture used by every model, sorted by model and then in the order the model uses
+    /// them.
+    #[must_use]
+    pub fn references(&self) -> Vec<Reference<'_>> {
+        let mut references = Vec::new();
+        for (model, textures) in &self.models {
+            for filename in textures {
+                let (texture, found) = self.resolve(model, filename);
+                references.push(Reference { model, texture, found });
+            }
+        }
+        references
+    }
+
+    /// Returns every texture in the tree along with the models that use it, including textures that aren't
+    /// used by anything.
+    #[must_use]
+    pub fn texture_users(&self) -> BTreeMap<&str, Vec<&str>> {
+        let mut users: BTreeMap<&str, Vec<&str>> =
+            self.files().filter(|path| is_texture(path)).map(|path| (path, Vec::new())).collect();
+        for reference in self.references().into_iter().filter(|reference| reference.found) {
+            let Some(models) = users.get_mut(reference.texture.as_str()) else {
+                continue;
+            };
+            // References are sorted by model, so duplicates are always next to each other
+            if models.last() != Some(&reference.model) {
+                models.push(reference.model);
+            }
+        }
+        users
+    }
+
+    /// Returns every texture in the tree that isn't used by any model.
+    #[must_use]
+    pub fn unreferenced(&self) -> Vec<&str> {
+        self.texture_users()
+            .into_iter()
+            .filter(|(_, models)| models.is_empty())
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    /// Returns every texture that's used by a model, but doesn't exist in the tree.
+    #[must_use]
+    #[inline]
+    pub fn missing(&self) -> Vec<Reference<'_>> {
+        self.references().into_iter().filter(|reference| !reference.found).collect()
+    }
+
+    /// Writes the cross-reference as a JSON object, containing:
+    /// * `models`: each model and the textures it uses
+    /// * `textures`: each texture in the tree and the models that use it
+    /// * `unreferenced`: textures that aren't used by any model
+    /// * `missing`: each model and texture where the texture doesn't exist
+    /// * `errors`: each file that couldn't be read or parsed, and why
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let references = self.references();
+        let users = self.texture_users();
+
+        let mut models: BTreeMap<&str, Vec<&str>> =
+            self.models.keys().map(|model| (model.as_str(), Vec::new())).collect();
+        for reference in &references {
+            if let Some(textures) = models.get_mut(reference.model) {
+                textures.push(&reference.texture);
+            }
+        }
+        let missing = references.iter().filter(|reference| !reference.found).map(|reference| {
+            format!(
+                "{{\"model\": {}, \"texture\": {}}}",
+                json_string(reference.model),
+                json_string(&reference.texture)
+            )
+        });
+        let errors = self.errors.iter().map(|(path, error)| {
+            format!(
+                "{{\"path\": {}, \"error\": {}}}",
+                json_string(path),
+                json_string(&error.to_string())
+            )
+        });
+        let unreferenced =
+            users.iter().filter(|(_, models)| models.is_empty()).map(|(path, _)| json_string(path));
+
+        let mut json = String::from("{\n");
+        json_map(&mut json, "models", &models);
+        json.push_str(",\n");
+        json_map(&mut json, "textures", &users);
+        json.push_str(",\n");
+        json_list(&mut json, "unreferenced", unreferenced);
+        json.push_str(",\n");
+        json_list(&mut json, "missing", missing);
+        json.push_str(",\n");
+        json_list(&mut json, "errors", errors);
+        json.push_str("\n}\n");
+        json
+    }
+
+    /// Writes the cross-reference as a Graphviz graph, with an edge from each model to the textures it uses.
+    /// Missing textures are drawn in red, and unreferenced textures in gray.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph \"textures\" {\n");
+        dot.push_str("    graph [rankdir=LR]\n");
+        dot.push_str("    node [shape=box, style=rounded, fontname=\"Consolas\"]\n\n");
+        for model in self.models.keys() {
+            dot.push_str(&format!("    {} [shape=component];\n", json_string(model)));
+        }
+        for path in self.unreferenced() {
+            dot.push_str(&format!(
+                "    {} [color=gray, fontcolor=gray];\n",
+                json_string(path)
+            ));
+        }
+        for reference in self.references() {
+            let (model, texture) = (json_string(reference.model), json_string(&reference.texture));
+            match reference.found {
+                true => dot.push_str(&format!("    {model} -> {texture};\n")),
+                false => {
+                    dot.push_str(&format!(
+                        "    {texture} [color=red, fontcolor=red, style=dashed];\n"
+                    ));
+                    dot.push_str(&format!("    {model} -> {texture} [color=red];\n"));
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Quotes and escapes `value` as a JSON string, which is also a valid Graphviz ID.
+fn json_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Writes `key` and an object mapping each path to a list of paths.
+fn json_map(json: &mut String, key: &str, map: &BTreeMap<&str, Vec<&str>>) {
+    let entries = map.iter().map(|(path, values)| {
+        let values: Vec<_> = values.iter().map(|value| json_string(value)).collect();
+        format!("{}: [{}]", json_string(path), values.join(", "))
+    });
+    json_entries(json, key, ('{', '}'), entries);
+}
+
+/// Writes `key` and a list of values that have already been converted to JSON.
+fn json_list(json: &mut String, key: &str, values: impl Iterator<Item = String>) {
+    json_entries(json, key, ('[', ']'), values);
+}
+
+/// Writes `key` and a list or object, with each entry on its own line.
+fn json_entries(
+    json: &mut String, key: &str, (open, close): (char, char), entries: impl Iterator<Item = String>,
+) {
+    json.push_str(&format!("  {}: {open}", json_string(key)));
+    let mut empty = true;
+    for entry in entries {
+        json.push_str(match empty {
+            true => "\n    ",
+            false => ",\n    ",
+        });
+        json.push_str(&entry);
+        empty = false;
+    }
+    if !empty {
+        json.push_str("\n  ");
+    }
+    json.push(close);
+}
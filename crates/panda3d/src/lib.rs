@@ -13,14 +13,21 @@ mod no_std {
 }
 
 pub mod multifile;
+pub mod multifile_set;
 pub mod subfile;
 
 pub mod bam;
+pub mod bam_patch;
+#[cfg(feature = "std")]
+pub mod crossref;
+pub mod mesh;
 //#[cfg(feature = "bevy")]
 //pub mod bevy;
 #[cfg(feature = "bevy")]
 pub mod bevy2;
 #[cfg(feature = "bevy")]
+pub mod bevy_multifile;
+#[cfg(feature = "bevy")]
 pub mod bevy_sgi;
 
 pub mod common;
@@ -31,3 +38,9 @@ mod nodes;
 pub mod bam2;
 
 pub mod multifile2;
+
+// Dev-dependencies are only used by doctests and benchmarks, but are still passed to the unit test target
+#[cfg(test)]
+use criterion as _;
+#[cfg(test)]
+use orthrus_tools as _;
@@ -0,0 +1,130 @@
+//! Engine-agnostic post-processing for triangle meshes, to turn Panda3D's geometry into something that's
+//! cheaper to render on modern GPUs.
+//!
+//! BAM environments are often stored as hundreds of small triangle strips, each with their own copy of any
+//! shared vertices. [`strips_to_list`] converts them into a plain triangle list, [`weld`] merges vertices
+//! that are exactly the same, and [`interleave`] packs separate vertex attributes into a single buffer.
+//!
+//! # Usage
+//! ```
+//! use orthrus_panda3d::mesh;
+//!
+//! // Two strips, separated by the two unused vertices that Panda3D places between them
+//! let strips = [0, 1, 2, 3, 3, 4, 4, 5, 6];
+//! let mut indices = mesh::strips_to_list(&strips, &[4, 9]);
+//! assert_eq!(indices, [0, 1, 2, 2, 1, 3, 4, 5, 6]);
+//!
+//! // Vertices 3 and 6 are at the same position, so they can share an index
+//! let positions: [[u8; 2]; 7] = [[0, 0], [1, 0], [0, 1], [1, 1], [2, 0], [2, 1], [1, 1]];
+//! let colors = [0xFFu8; 7];
+//! let (vertices, stride) = mesh::interleave(&[(positions.as_flattened(), 2), (&colors, 1)]);
+//! assert_eq!(stride, 3);
+//!
+//! let kept = mesh::weld(&vertices, stride, &mut indices);
+//! assert_eq!(kept, [0, 1, 2, 3, 4, 5]);
+//! assert_eq!(indices, [0, 1, 2, 2, 1, 3, 4, 5, 3]);
+//! ```
+
+use hashbrown::HashMap;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
+/// Number of vertices between each strip that aren't part of either, which Panda3D uses to join the strips
+/// together with degenerate triangles.
+const UNUSED_STRIP_VERTICES: usize = 2;
+
+/// Converts triangle strips into a triangle list, keeping the winding order of every triangle and dropping
+/// any degenerate triangles.
+///
+/// `ends` holds where each strip ends, as stored by Panda3D's `GeomTristrips`, or can be empty if `strips` is
+/// a single strip.
+#[must_use]
+pub fn strips_to_list(strips: &[u32], ends: &[u32]) -> Vec<u32> {
+    let mut output = Vec::with_capacity(strips.len().saturating_sub(2) * 3);
+    let mut convert = |strip: &[u32]| {
+        for (index, triangle) in strip.windows(3).enumerate() {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]];
+            if a == b || b == c || a == c {
+                continue;
+            }
+            // Every other triangle in a strip is flipped, so swap it back to keep the same facing
+            match index % 2 {
+                0 => output.extend_from_slice(&[a, b, c]),
+                _ => output.extend_from_slice(&[b, a, c]),
+            }
+        }
+    };
+
+    match ends.is_empty() {
+        true => convert(strips),
+        false => {
+            let mut start = 0;
+            for &end in ends {
+                let end = (end as usize).min(strips.len());
+                if let Some(strip) = strips.get(start..end) {
+                    convert(strip);
+                }
+                start = end + UNUSED_STRIP_VERTICES;
+            }
+        }
+    }
+    output
+}
+
+/// Merges vertices that are exactly the same, given a buffer of vertices that are each `stride` bytes, and
+/// rewrites `indices` to refer to the merged vertices.
+///
+/// Returns which of the original vertices each merged vertex is, in order, which can be used to rebuild
+/// any vertex attributes that are stored separately.
+///
+/// # Panics
+/// Panics if `stride` is zero, or if any index is past the end of `vertices`.
+#[must_use]
+pub fn weld(vertices: &[u8], stride: usize, indices: &mut [u32]) -> Vec<u32> {
+    let mut lookup: HashMap<&[u8], u32> = HashMap::new();
+    let mut kept = Vec::new();
+    let remap: Vec<u32> = vertices
+        .chunks_exact(stride)
+        .enumerate()
+        .map(|(index, vertex)| {
+            *lookup.entry(vertex).or_insert_with(|| {
+                kept.push(index as u32);
+                kept.len() as u32 - 1
+            })
+        })
+        .collect();
+
+    for index in indices {
+        *index = remap[*index as usize];
+    }
+    kept
+}
+
+/// Packs separate vertex attributes into a single buffer, where each attribute is given as its data and the
+/// size of a single element in bytes. Returns the buffer and the size of each vertex.
+///
+/// The number of vertices is taken from the longest attribute, and any shorter ones are padded with zeroes.
+#[must_use]
+pub fn interleave(attributes: &[(&[u8], usize)]) -> (Vec<u8>, usize) {
+    let stride: usize = attributes.iter().map(|(_, size)| size).sum();
+    let count = attributes
+        .iter()
+        .filter(|(_, size)| *size != 0)
+        .map(|(data, size)| data.len().div_ceil(*size))
+        .max()
+        .unwrap_or(0);
+
+    let mut output = vec![0u8; stride * count];
+    let mut offset = 0;
+    for (data, size) in attributes {
+        if *size == 0 {
+            continue;
+        }
+        for (vertex, element) in output.chunks_exact_mut(stride).zip(data.chunks(*size)) {
+            vertex[offset..offset + element.len()].copy_from_slice(element);
+        }
+        offset += size;
+    }
+    (output, stride)
+}
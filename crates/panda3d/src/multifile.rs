@@ -87,6 +87,13 @@
 //! Once created, the following functions can be used to manipulate the archive:
 //!
 //! * [`extract_all`](Multifile::extract_all): Save all contained [`Subfile`]s to a given folder
+//! * [`reader`](Multifile::reader): Stream the contents of a single [`Subfile`], decompressing it if needed
+//! * [`extract_all_parallel`](Multifile::extract_all_parallel): Save all contained [`Subfile`]s using every
+//!   core, while still reporting them in a deterministic order
+//!
+//! Each of the extraction functions also has an `_into` version, which takes an [`Extraction`] in place of
+//! the output folder so that [`Subfile`]s can be staged before they're moved into place, or only listed
+//! during a dry run.
 //!
 //! ## Stateless Functions
 //! These functions can be used without having to first create a Multifile, used for the
@@ -96,9 +103,27 @@
 //!   [`Subfile`]s to a given folder
 //! * [`extract_from`](Multifile::extract_from): Reads the provided Multifile, and saves all [`Subfile`]s to a
 //!   given folder
+//!
+//! ## Example
+//! Listing the contents of a Multifile, using a small generated one from `orthrus_tools::corpus` in place of
+//! real game data:
+//!
+//! ```
+//! use orthrus_panda3d::prelude::*;
+//!
+//! let multifile = Multifile::load(orthrus_tools::corpus::multifile(3), 0)?;
+//! for subfile in multifile.subfiles() {
+//!     println!("{}", subfile.name());
+//! }
+//! assert_eq!(multifile.subfiles().len(), 4);
+//! assert_eq!(multifile.subfiles()[0].name(), "file0.bin");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
 
 #[cfg(feature = "std")]
 use std::path::Path;
+#[cfg(feature = "std")]
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 use orthrus_core::prelude::*;
 use snafu::prelude::*;
@@ -125,22 +150,69 @@ pub enum Error {
     #[snafu(display("Invalid Magic! Expected {:?}.", Multifile::MAGIC))]
     InvalidMagic,
     /// Thrown if the header version is too new to be supported.
-    #[snafu(display("Unknown Multifile Version! Expected >= v{}.", Multifile::CURRENT_VERSION))]
-    UnknownVersion,
+    #[snafu(display(
+        "Unsupported Multifile version v{version}! Only v{}.0 to v{} are supported.",
+        Multifile::CURRENT_VERSION.major,
+        Multifile::CURRENT_VERSION
+    ))]
+    UnknownVersion { version: Version },
+    /// Thrown if the archive is larger than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+    /// Thrown if trying to read a [`Subfile`] that doesn't exist.
+    #[snafu(display("Unable to find {path} in the Multifile!"))]
+    FileNotFound { path: String },
+    /// Thrown if trying to read an encrypted [`Subfile`], which isn't supported yet.
+    #[snafu(display("Unable to read {path}, encrypted Subfiles aren't supported yet!"))]
+    Encrypted { path: String },
+    /// Thrown if a compressed [`Subfile`]'s data is invalid or truncated.
+    #[snafu(display("Unable to decompress {path}, its data is invalid or truncated!"))]
+    InvalidData { path: String },
+    /// Thrown if an extracted file collides with another, see [`OverwritePolicy::Error`].
+    #[cfg(feature = "std")]
+    #[snafu(display("File already exists!"))]
+    AlreadyExists,
+    /// Thrown if extraction was cancelled, see [`Extraction::cancellation`].
+    #[cfg(feature = "std")]
+    #[snafu(display("Extraction was cancelled!"))]
+    Cancelled,
+    /// Thrown if any other filesystem error occurs.
+    #[cfg(feature = "std")]
+    #[snafu(display("Filesystem Error {source}"))]
+    FileError { source: std::io::Error },
+    /// Thrown if a [`Subfile`]'s name can't safely be extracted, see [`PathOptions`].
+    #[cfg(feature = "std")]
+    #[snafu(display("{source}"))]
+    InvalidPath { source: PathError },
 }
 pub(crate) type Result<T> = core::result::Result<T, Error>;
 
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Self::LimitExceeded { source }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<PathError> for Error {
+    #[inline]
+    fn from(source: PathError) -> Self {
+        Self::InvalidPath { source }
+    }
+}
+
 #[cfg(feature = "std")]
 impl From<std::io::Error> for Error {
     #[inline]
     fn from(error: std::io::Error) -> Self {
         match error.kind() {
+            _ if Cancelled::is(&error) => Self::Cancelled,
             std::io::ErrorKind::NotFound => Self::NotFound,
             std::io::ErrorKind::UnexpectedEof => Self::EndOfFile,
             std::io::ErrorKind::PermissionDenied => Self::PermissionDenied,
-            kind => {
-                panic!("Unexpected std::io::error: {kind}! Something has gone horribly wrong")
-            }
+            std::io::ErrorKind::AlreadyExists => Self::AlreadyExists,
+            _ => Self::FileError { source: error },
         }
     }
 }
@@ -155,10 +227,33 @@ impl From<DataError> for Error {
     }
 }
 
-struct Header {
-    version: Version,
-    scale_factor: u32,
-    timestamp: u32,
+/// Metadata stored in the Multifile header, which can be read without parsing any [`Subfile`]s using
+/// [`Multifile::read_info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Header {
+    /// Revision of the Multifile format, see [here](self#revisions)
+    pub version: Version,
+    /// Amount that all offsets are multiplied by, to support archives larger than 4 GiB
+    pub scale_factor: u32,
+    /// Unix timestamp of when the Multifile was last modified, or zero before revision 1.1
+    pub timestamp: u32,
+}
+
+impl Header {
+    /// Returns whether offsets are scaled past 32 bits, which is only needed for archives larger than 4 GiB.
+    #[must_use]
+    #[inline]
+    pub const fn large_offsets(&self) -> bool {
+        self.scale_factor > 1
+    }
+
+    /// Returns the largest offset that can be stored using this header's scale factor.
+    #[must_use]
+    #[inline]
+    pub const fn max_offset(&self) -> u64 {
+        u32::MAX as u64 * self.scale_factor as u64
+    }
 }
 
 // The current least terrible way to implement state in this system is to just store the entire
@@ -173,8 +268,7 @@ struct Header {
 pub struct Multifile {
     data: DataCursor,
     files: Vec<Subfile>,
-    version: Version,
-    timestamp: u32,
+    header: Header,
 }
 
 impl Multifile {
@@ -224,7 +318,7 @@ impl Multifile {
         let version = Version { major: data.read_u16()?, minor: data.read_u16()? };
         ensure!(
             Self::CURRENT_VERSION.major == version.major && Self::CURRENT_VERSION.minor >= version.minor,
-            UnknownVersionSnafu
+            UnknownVersionSnafu { version }
         );
 
         let scale_factor = data.read_u32()?;
@@ -237,12 +331,87 @@ impl Multifile {
         Ok(Header { version, scale_factor, timestamp })
     }
 
+    /// Reads only the Multifile header, skipping any header prefix, without parsing any [`Subfile`]s.
+    ///
+    /// This is useful for checking whether a Multifile is supported before loading it.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the magic number does not match a
+    /// Multifile, [`UnknownVersion`](Error::UnknownVersion) if the Multifile version is too
+    /// new to be supported, or [`EndOfFile`](Error::EndOfFile) if trying to read out of bounds.
+    #[inline]
+    pub fn read_info(input: &[u8], offset: u64) -> Result<Header> {
+        let mut data = DataCursorRef::new(input, Endian::Little);
+        data.set_position(offset)?;
+        data.set_position(Self::parse_header_prefix(&data) as u64)?;
+        Self::read_header(&mut data)
+    }
+
+    /// Returns the metadata from the Multifile header.
+    #[must_use]
+    #[inline]
+    pub const fn header(&self) -> Header {
+        self.header
+    }
+
     /// Returns the number of [`Subfile`]s currently stored in the Multifile.
     #[inline]
     pub fn count(&mut self) -> usize {
         self.files.len()
     }
 
+    /// Returns all [`Subfile`]s stored in the Multifile, in the order they're stored.
+    #[must_use]
+    #[inline]
+    pub fn subfiles(&self) -> &[Subfile] {
+        &self.files
+    }
+
+    /// Returns the [`Subfile`] at `path`, or [`None`] if there isn't one or it's been deleted.
+    #[must_use]
+    #[inline]
+    pub fn find(&self, path: &str) -> Option<&Subfile> {
+        self.files.iter().find(|subfile| subfile.filename == path && !subfile.is_deleted())
+    }
+
+    /// Returns a [`SubfileReader`] that streams the contents of the [`Subfile`] at `path`, decompressing it
+    /// on the fly if needed.
+    ///
+    /// # Errors
+    /// Returns [`FileNotFound`](Error::FileNotFound) if there's no [`Subfile`] at `path`,
+    /// [`Encrypted`](Error::Encrypted) if the [`Subfile`] is encrypted, or [`EndOfFile`](Error::EndOfFile) if
+    /// its data is out of bounds.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::io::Read;
+    ///
+    /// use orthrus_panda3d::prelude::*;
+    ///
+    /// let multifile = Multifile::open("phase_3.mf", 0)?;
+    /// let mut reader = multifile.reader("phase_3/models/gui/dialog_box_gui.bam")?;
+    /// let mut header = [0u8; 6];
+    /// reader.read_exact(&mut header)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn reader(&self, path: &str) -> Result<SubfileReader<'_>> {
+        let subfile = self.find(path).context(FileNotFoundSnafu { path })?;
+        ensure!(!subfile.is_encrypted(), EncryptedSnafu { path });
+        self.subfile_reader(subfile)
+    }
+
+    /// Returns a [`SubfileReader`] over the stored data of `subfile`.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn subfile_reader<'a>(&'a self, subfile: &Subfile) -> Result<SubfileReader<'a>> {
+        let start = usize::try_from(subfile.offset).map_err(|_| Error::EndOfFile)?;
+        let end = start.checked_add(subfile.data_length as usize).context(EndOfFileSnafu)?;
+        let data = self.data.get(start..end).context(EndOfFileSnafu)?;
+        Ok(SubfileReader::new(data, subfile))
+    }
+
     /// Opens a file on disk, loads its contents, and parses it into a new instance of
     /// Multifile. The returned instance can then be used for further operations.
     ///
@@ -253,8 +422,23 @@ impl Multifile {
     #[cfg(feature = "std")]
     #[inline]
     pub fn open<P: AsRef<Path>>(input: P, offset: u64) -> Result<Self> {
+        Self::open_with_limits(input, offset, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Opens a file on disk the same way as [`open`](Self::open), returning an error instead of reading a
+    /// larger file, or more [`Subfile`]s or more total data than `limits` allow.
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the file is larger than the limits allow, or any
+    /// error from [`load_with_limits`](Self::load_with_limits).
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn open_with_limits<P: AsRef<Path>>(input: P, offset: u64, limits: &ResourceLimits) -> Result<Self> {
+        // Check the size before reading the whole file into memory
+        let input = input.as_ref();
+        limits.check_output_size(std::fs::metadata(input)?.len())?;
         let data = std::fs::read(input)?;
-        Self::load(data, offset)
+        Self::load_with_limits(data, offset, limits)
     }
 
     /// Loads the data from the given file and parses it into a new instance of Multifile. The
@@ -266,34 +450,111 @@ impl Multifile {
     /// new to be supported, or [`EndOfFile`](Error::EndOfFile) if trying to read out of bounds.
     #[inline]
     pub fn load<I: Into<Box<[u8]>>>(input: I, offset: u64) -> Result<Self> {
+        Self::load_with_limits(input, offset, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Loads the data from the given file and parses it into a new instance of Multifile, returning an error
+    /// instead of reading more [`Subfile`]s or more total data than `limits` allow.
+    ///
+    /// Since the [`Subfile`] index is a linked list, this also protects against malformed archives that
+    /// loop back on themselves.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the magic number does not match a
+    /// Multifile, [`UnknownVersion`](Error::UnknownVersion) if the Multifile version is too
+    /// new to be supported, [`EndOfFile`](Error::EndOfFile) if trying to read out of bounds, or
+    /// [`LimitExceeded`](Error::LimitExceeded) if the archive is larger than the limits allow.
+    #[inline]
+    pub fn load_with_limits<I: Into<Box<[u8]>>>(
+        input: I, offset: u64, limits: &ResourceLimits,
+    ) -> Result<Self> {
         let mut data = DataCursor::new(input, Endian::Little);
         data.set_position(offset)?;
         data.set_position(Self::parse_header_prefix(&data) as u64)?;
 
         let header = Self::read_header(&mut data)?;
-        let mut multifile = Self {
-            data,
-            files: Vec::new(),
-            version: header.version,
-            timestamp: header.timestamp,
-        };
+        let files = Self::read_index(&mut data, header, limits)?;
+        Ok(Self { data, files, header })
+    }
 
+    /// Reads every [`Subfile`] in the index, which starts right after the header.
+    fn read_index<T: ReadExt + SeekExt>(
+        data: &mut T, header: Header, limits: &ResourceLimits,
+    ) -> Result<Vec<Subfile>> {
         // Loop through each Subfile, using next_index as a linked list
-        let mut next_index = multifile.data.read_u32()? * header.scale_factor;
+        let scale_factor = u64::from(header.scale_factor);
+        let mut files = Vec::new();
+        let mut next_index = u64::from(data.read_u32()?) * scale_factor;
+        let mut total_size = 0u64;
         while next_index != 0 {
-            let mut subfile = Subfile::load(&mut multifile.data, header.version)?;
-            subfile.offset *= header.scale_factor;
+            let mut subfile = Subfile::load(data, header.version)?;
+            subfile.offset *= scale_factor;
             if subfile.timestamp == 0 {
                 subfile.timestamp = header.timestamp;
             }
 
-            multifile.files.push(subfile);
+            limits.check_entries(files.len() as u64 + 1)?;
+            total_size += u64::from(subfile.length);
+            limits.check_output_size(total_size)?;
 
-            multifile.data.set_position(next_index.into())?;
-            next_index = multifile.data.read_u32()? * header.scale_factor;
+            files.push(subfile);
+
+            data.set_position(next_index)?;
+            next_index = u64::from(data.read_u32()?) * scale_factor;
         }
+        Ok(files)
+    }
+
+    /// Reads only the header and index of a Multifile from any stream, without reading the data of any
+    /// [`Subfile`]. This is useful for listing the contents of an archive that's too large to load, or that's
+    /// being read over a network, since only the index has to be read.
+    ///
+    /// Any header prefix has to fit within the first 4KiB after `offset`, which is much larger than the
+    /// single line that Panda3D writes.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the magic number does not match a
+    /// Multifile, [`UnknownVersion`](Error::UnknownVersion) if the Multifile version is too
+    /// new to be supported, or [`EndOfFile`](Error::EndOfFile) if trying to read out of bounds.
+    ///
+    /// # Examples
+    /// ```
+    /// use orthrus_core::prelude::*;
+    /// use orthrus_panda3d::prelude::*;
+    ///
+    /// let data = orthrus_tools::corpus::multifile(1);
+    /// let subfiles = Multifile::list_from(&mut DataCursorRef::new(&data, Endian::Little), 0)?;
+    ///
+    /// let multifile = Multifile::load(data, 0)?;
+    /// assert_eq!(subfiles.len(), multifile.subfiles().len());
+    /// for (listed, loaded) in subfiles.iter().zip(multifile.subfiles()) {
+    ///     assert_eq!(listed.name(), loaded.name());
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn list_from<T: ReadExt + SeekExt>(input: &mut T, offset: u64) -> Result<Vec<Subfile>> {
+        Self::list_from_with_limits(input, offset, &ResourceLimits::UNLIMITED)
+    }
 
-        Ok(multifile)
+    /// Same as [`list_from`](Self::list_from), but returns an error instead of reading more [`Subfile`]s or
+    /// more total data than `limits` allow.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if the magic number does not match a
+    /// Multifile, [`UnknownVersion`](Error::UnknownVersion) if the Multifile version is too
+    /// new to be supported, [`EndOfFile`](Error::EndOfFile) if trying to read out of bounds, or
+    /// [`LimitExceeded`](Error::LimitExceeded) if the archive is larger than the limits allow.
+    pub fn list_from_with_limits<T: ReadExt + SeekExt>(
+        input: &mut T, offset: u64, limits: &ResourceLimits,
+    ) -> Result<Vec<Subfile>> {
+        let mut prefix = [0u8; 0x1000];
+        input.set_position(offset)?;
+        let length = input.read_length(&mut prefix)?;
+        input.set_position(offset + Self::parse_header_prefix(&prefix[..length]) as u64)?;
+
+        let header = Self::read_header(input)?;
+        Self::read_index(input, header, limits)
     }
 
     /// Saves all [`Subfile`]s to disk. For use without having to [`open`](Self::open) or
@@ -308,17 +569,150 @@ impl Multifile {
     #[inline]
     #[cfg(feature = "std")]
     pub fn extract_all<P: AsRef<Path>>(&mut self, output: P) -> Result<usize> {
+        self.extract_all_with_options(output, &PathOptions::DEFAULT)
+    }
+
+    /// Saves all [`Subfile`]s to disk, using `options` to control how [`Subfile`] names are sanitized.
+    ///
+    /// # Errors
+    /// Returns [`InvalidPath`](Error::InvalidPath) if a [`Subfile`] name is unsafe, or any error from
+    /// [`extract_all`](Self::extract_all).
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn extract_all_with_options<P: AsRef<Path>>(
+        &mut self, output: P, options: &PathOptions,
+    ) -> Result<usize> {
+        self.extract_all_into(&Extraction::direct(output.as_ref()), options)
+    }
+
+    /// Saves all [`Subfile`]s to wherever `extraction` puts them, such as a staging folder or nowhere at all
+    /// for a dry run, see [`Extraction`]. The [`Extraction`] still needs to be committed afterwards.
+    ///
+    /// # Errors
+    /// Returns the same errors as [`extract_all_with_options`](Self::extract_all_with_options).
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn extract_all_into(&mut self, extraction: &Extraction, options: &PathOptions) -> Result<usize> {
         let mut saved_files = 0;
         for subfile in &mut self.files {
             if !subfile.flags.intersects(Flags::Signature | Flags::Compressed | Flags::Encrypted) {
-                self.data.set_position(subfile.offset.into())?;
-                subfile.write_file(&self.data.read_slice(subfile.length as usize)?, &output)?;
+                self.data.set_position(subfile.offset)?;
+                subfile.write_file(
+                    &*self.data.read_slice(subfile.length as usize)?,
+                    extraction,
+                    options,
+                )?;
                 saved_files += 1;
             }
         }
         Ok(saved_files)
     }
 
+    /// Saves all [`Subfile`]s to disk on worker threads, one per available core, decompressing any
+    /// compressed [`Subfile`]s along the way. Signatures, along with encrypted and deleted [`Subfile`]s, are
+    /// skipped. Returns how many [`Subfile`]s were saved.
+    ///
+    /// [`Subfile`]s can finish in any order, but `on_extracted` is always called in the order they're stored
+    /// in the Multifile, along with each one's index in [`subfiles`](Self::subfiles), so that progress output
+    /// and manifests are the same on every run.
+    ///
+    /// # Errors
+    /// Returns the first error in archive order, after every [`Subfile`] before it has been reported. This
+    /// is [`InvalidPath`](Error::InvalidPath) if a [`Subfile`] name is unsafe,
+    /// [`InvalidData`](Error::InvalidData) if a compressed [`Subfile`] can't be decompressed, or any error
+    /// from [`extract_all`](Self::extract_all). Once an error is found, no new [`Subfile`]s are started.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use orthrus_core::prelude::*;
+    /// use orthrus_panda3d::prelude::*;
+    ///
+    /// let multifile = Multifile::open("phase_3.mf", 0)?;
+    /// let total = multifile.subfiles().len();
+    /// multifile.extract_all_parallel("phase_3", &PathOptions::DEFAULT, |index, subfile| {
+    ///     println!("[{}/{total}] {}", index + 1, subfile.name());
+    /// })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn extract_all_parallel<P, F>(
+        &self, output: P, options: &PathOptions, on_extracted: F,
+    ) -> Result<usize>
+    where
+        P: AsRef<Path>,
+        F: FnMut(usize, &Subfile),
+    {
+        self.extract_all_parallel_into(&Extraction::direct(output.as_ref()), options, on_extracted)
+    }
+
+    /// Saves all [`Subfile`]s on worker threads to wherever `extraction` puts them, such as a staging folder
+    /// or nowhere at all for a dry run, see [`Extraction`]. The [`Extraction`] still needs to be committed
+    /// afterwards.
+    ///
+    /// # Errors
+    /// Returns the same errors as [`extract_all_parallel`](Self::extract_all_parallel).
+    #[cfg(feature = "std")]
+    pub fn extract_all_parallel_into<F>(
+        &self, extraction: &Extraction, options: &PathOptions, mut on_extracted: F,
+    ) -> Result<usize>
+    where
+        F: FnMut(usize, &Subfile),
+    {
+        let pending: Vec<usize> = (0..self.files.len())
+            .filter(|&index| {
+                !self.files[index].flags.intersects(Flags::Signature | Flags::Encrypted | Flags::Deleted)
+            })
+            .collect();
+        let threads =
+            std::thread::available_parallelism().map_or(1, core::num::NonZeroUsize::get).min(pending.len());
+
+        // Workers take the next Subfile from a shared counter, and stop taking new ones after any error
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::scope(|scope| {
+            for _ in 0..threads {
+                let sender = sender.clone();
+                let (pending, next, failed) = (&pending, &next, &failed);
+                scope.spawn(move || {
+                    while !failed.load(Ordering::Relaxed) {
+                        let slot = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(&index) = pending.get(slot) else {
+                            break;
+                        };
+                        let subfile = &self.files[index];
+                        let result = self
+                            .subfile_reader(subfile)
+                            .and_then(|reader| subfile.write_file(reader, extraction, options));
+                        if result.is_err() {
+                            failed.store(true, Ordering::Relaxed);
+                        }
+                        // The receiver is only gone if an earlier Subfile already failed
+                        if sender.send((slot, result)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(sender);
+
+            // Hold onto results that arrive early until every Subfile before them has been reported
+            let mut finished: Vec<Option<Result<()>>> = pending.iter().map(|_| None).collect();
+            let mut reported = 0;
+            for (slot, result) in receiver {
+                finished[slot] = Some(result);
+                while let Some(result) = finished.get_mut(reported).and_then(Option::take) {
+                    result?;
+                    let index = pending[reported];
+                    on_extracted(index, &self.files[index]);
+                    reported += 1;
+                }
+            }
+            Ok(reported)
+        })
+    }
+
     /// Loads a Multifile from disk and extracts all [`Subfile`]s. For use with other functions,
     /// see [`extract`](Self::extract_all).
     ///
@@ -329,7 +723,7 @@ impl Multifile {
     /// failing to create a file to write to (see [`write`](std::fs::write)).
     #[cfg(feature = "std")]
     #[inline]
-    pub fn extract_from_path<P: AsRef<Path>>(input: P, output: P, offset: u64) -> Result<()> {
+    pub fn extract_from_path<P: AsRef<Path>, O: AsRef<Path>>(input: P, output: O, offset: u64) -> Result<()> {
         let data = std::fs::read(input)?;
         Self::extract_from(&data, output, offset)?;
         Ok(())
@@ -346,6 +740,33 @@ impl Multifile {
     #[cfg(feature = "std")]
     #[inline]
     pub fn extract_from<P: AsRef<Path>>(input: &[u8], output: P, offset: u64) -> Result<()> {
+        Self::extract_from_with_options(input, output, offset, &PathOptions::DEFAULT)
+    }
+
+    /// Extracts all [`Subfile`]s from the given Multifile, using `options` to control how [`Subfile`] names
+    /// are sanitized.
+    ///
+    /// # Errors
+    /// Returns [`InvalidPath`](Error::InvalidPath) if a [`Subfile`] name is unsafe, or any error from
+    /// [`extract_from`](Self::extract_from).
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn extract_from_with_options<P: AsRef<Path>>(
+        input: &[u8], output: P, offset: u64, options: &PathOptions,
+    ) -> Result<()> {
+        Self::extract_from_into(input, &Extraction::direct(output.as_ref()), offset, options)
+    }
+
+    /// Extracts all [`Subfile`]s from the given Multifile to wherever `extraction` puts them, such as a
+    /// staging folder or nowhere at all for a dry run, see [`Extraction`]. The [`Extraction`] still needs to
+    /// be committed afterwards.
+    ///
+    /// # Errors
+    /// Returns the same errors as [`extract_from_with_options`](Self::extract_from_with_options).
+    #[cfg(feature = "std")]
+    pub fn extract_from_into(
+        input: &[u8], extraction: &Extraction, offset: u64, options: &PathOptions,
+    ) -> Result<()> {
         //Use a DataCursorRef internally because it makes reading structured data a lot easier
         let mut data = DataCursorRef::new(input, Endian::Little);
         data.set_position(offset)?;
@@ -354,25 +775,26 @@ impl Multifile {
         let header = Self::read_header(&mut data)?;
 
         // Loop through each Subfile, using next_index as a linked list
-        let mut next_index = data.read_u32()? * header.scale_factor;
+        let scale_factor = u64::from(header.scale_factor);
+        let mut next_index = u64::from(data.read_u32()?) * scale_factor;
         while next_index != 0 {
             let mut subfile = Subfile::load(&mut data, header.version)?;
-            subfile.offset *= header.scale_factor;
+            subfile.offset *= scale_factor;
             if subfile.timestamp == 0 {
                 subfile.timestamp = header.timestamp;
             }
 
-            data.set_position(subfile.offset.into())?;
+            data.set_position(subfile.offset)?;
             if !subfile.flags.contains(Flags::Signature) {
-                subfile.write_file(&data.read_slice(subfile.length as usize)?, &output)?;
+                subfile.write_file(&*data.read_slice(subfile.length as usize)?, extraction, options)?;
             } /* else if cfg!(signature) {
                   println!("{:?}", subfile);
                   data.set_position(subfile.offset as usize);
                   Self::check_signatures(data.get_slice(subfile.length as usize)?)?;
               }*/
 
-            data.set_position(next_index.into())?;
-            next_index = data.read_u32()? * header.scale_factor;
+            data.set_position(next_index)?;
+            next_index = u64::from(data.read_u32()?) * scale_factor;
         }
 
         Ok(())
@@ -411,47 +833,53 @@ impl Multifile {
 #[cfg(feature = "identify")]
 impl FileIdentifier for Multifile {
     fn identify(data: &[u8]) -> Option<FileInfo> {
-        let multifile = Self::load(data, 0).ok()?;
-        let (num_compressed, num_encrypted) = multifile.files.iter().fold((0, 0), |(comp, enc), subfile| {
-            let is_compressed = subfile.flags.contains(Flags::Compressed) as usize;
-            let is_encrypted = subfile.flags.contains(Flags::Encrypted) as usize;
-            (comp + is_compressed, enc + is_encrypted)
-        });
+        // Loading copies the data, so check the magic first to keep scanning large files fast
+        if !data[Self::parse_header_prefix(data)..].starts_with(&Self::MAGIC) {
+            return None;
+        }
+        // Still report Multifiles we can't parse, rather than leaving them as unidentified data
+        let multifile = match Self::load(data, 0) {
+            Ok(multifile) => multifile,
+            Err(Error::UnknownVersion { version }) => {
+                let info = format!("Panda3D Multifile archive v{version} (unsupported version).");
+                return Some(FileInfo::new(info, None));
+            }
+            Err(_) => return None,
+        };
+        let header = multifile.header;
+        let count =
+            |flag: Flags| multifile.files.iter().filter(|subfile| subfile.flags.contains(flag)).count();
 
         //u32 will always be inside i64::MAX, so we can unwrap. We'll worry about it in 2106.
-        let timestamp = time::format_timestamp(multifile.timestamp.into()).unwrap();
+        let timestamp = time::format_timestamp(header.timestamp.into()).unwrap();
 
         let mut info = format!(
             "Panda3D Multifile archive v{}, modified {}, file count: {}",
-            multifile.version,
+            header.version,
             timestamp,
             multifile.files.len()
         );
 
         //Manually build additional details
-        let details = format!(
-            "{}{}{}",
-            if num_compressed > 0 {
-                format!("{num_compressed} compressed")
-            } else {
-                String::new()
-            },
-            if num_compressed > 0 && num_encrypted > 0 {
-                ", "
-            } else {
-                ""
-            },
-            if num_encrypted > 0 {
-                format!("{num_encrypted} encrypted")
-            } else {
-                String::new()
+        let mut details = Vec::new();
+        for (flag, name) in [
+            (Flags::Compressed, "compressed"),
+            (Flags::Encrypted, "encrypted"),
+            (Flags::Signature, "signed"),
+        ] {
+            match count(flag) {
+                0 => {}
+                num => details.push(format!("{num} {name}")),
             }
-        );
+        }
+        if header.large_offsets() {
+            details.push(format!("scale factor {}, large offsets", header.scale_factor));
+        }
 
         if details.is_empty() {
             info.push('.');
         } else {
-            info.push_str(&format!(" ({details})."));
+            info.push_str(&format!(" ({}).", details.join(", ")));
         }
 
         Some(FileInfo::new(info, None))
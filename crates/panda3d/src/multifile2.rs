@@ -2,12 +2,16 @@
 use std::{
     collections::BTreeMap,
     fs::File,
-    io::{BufReader, Write},
-    path::{Path, PathBuf},
+    io::{BufReader, Read, Seek, Write},
+    path::Path,
     time::{Duration, SystemTime},
 };
 
 use bitflags::bitflags;
+#[cfg(feature = "async")]
+use futures_lite::{AsyncRead, AsyncReadExt};
+#[cfg(feature = "std")]
+use orthrus_core::prelude::util::Replacement;
 use orthrus_core::prelude::*;
 use snafu::prelude::*;
 
@@ -31,8 +35,32 @@ pub enum Error {
     InvalidMagic,
 
     /// Thrown if the header version is too new to be supported.
-    #[snafu(display("Unknown Multifile Version! Expected >= v{}.", Multifile::CURRENT_VERSION))]
-    UnknownVersion,
+    #[snafu(display(
+        "Unsupported Multifile version v{version}! Only v{}.0 to v{} are supported.",
+        Multifile::CURRENT_VERSION.major,
+        Multifile::CURRENT_VERSION
+    ))]
+    UnknownVersion { version: Version },
+
+    /// Thrown if the archive is larger than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+
+    /// Thrown if trying to access a Subfile that isn't in the archive.
+    #[snafu(display("Unable to find {path} in the Multifile!"))]
+    FileNotFound { path: String },
+
+    /// Thrown if a Subfile's name can't safely be extracted, see [`PathOptions`].
+    #[cfg(feature = "std")]
+    #[snafu(display("{source}"))]
+    InvalidPath { source: PathError },
+}
+
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Self::LimitExceeded { source }
+    }
 }
 
 impl From<DataError> for Error {
@@ -55,6 +83,14 @@ impl From<std::io::Error> for Error {
     }
 }
 
+#[cfg(feature = "std")]
+impl From<PathError> for Error {
+    #[inline]
+    fn from(source: PathError) -> Self {
+        Error::InvalidPath { source }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Version {
     major: u16,
@@ -137,7 +173,7 @@ impl Multifile {
         let version = Version { major: data.read_u16()?, minor: data.read_u16()? };
         ensure!(
             Self::CURRENT_VERSION.major == version.major && Self::CURRENT_VERSION.minor >= version.minor,
-            UnknownVersionSnafu
+            UnknownVersionSnafu { version }
         );
 
         let scale_factor = data.read_u32()?;
@@ -165,15 +201,43 @@ impl Multifile {
         Multifile::load(data, offset)
     }
 
+    /// Asynchronously reads a file on disk and parses it into a new `Multifile` instance. File I/O runs on a
+    /// background thread pool, so this works with any async runtime.
+    #[inline]
+    #[cfg(feature = "async")]
+    pub async fn open_async<P: AsRef<Path>>(path: P, offset: u64) -> Result<Self, self::Error> {
+        let data = async_fs::read(path).await?;
+        Multifile::load(data.into_boxed_slice(), offset)
+    }
+
+    /// Asynchronously reads all of `reader`, then parses it into a new `Multifile` instance. Parsing only
+    /// happens once all data is in memory, so it never blocks on I/O.
+    #[inline]
+    #[cfg(feature = "async")]
+    pub async fn load_async<R: AsyncRead + Unpin>(mut reader: R, offset: u64) -> Result<Self, self::Error> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).await?;
+        Multifile::load(data.into_boxed_slice(), offset)
+    }
+
     /// Loads the data from a given input and parses it into a new `Multifile` instance. The instance can then
     /// be used for further operations.
     #[inline]
     pub fn load<T: IntoDataStream>(input: T, offset: u64) -> Result<Self, self::Error> {
+        Self::load_with_limits(input, offset, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Loads the data from a given input and parses it into a new `Multifile` instance, returning an error
+    /// instead of reading more Subfiles or more total data than `limits` allow.
+    #[inline]
+    pub fn load_with_limits<T: IntoDataStream>(
+        input: T, offset: u64, limits: &ResourceLimits,
+    ) -> Result<Self, self::Error> {
         let mut data = input.into_stream(Endian::Little);
         data.set_position(offset)?;
         let header_size = Self::parse_header_prefix(&mut data)?;
         data.set_position(header_size)?;
-        let metadata = Self::load_metadata(&mut data)?;
+        let metadata = Self::load_metadata(&mut data, limits)?;
 
         // Now, let's actually build our sorted list of files (ideally, this will already be sorted inside
         // the Multifile)
@@ -199,7 +263,9 @@ impl Multifile {
     ///
     /// This assumes that the input data is already at the start of a Multifile, i.e. we've already skipped
     /// any potential header prefix or offset.
-    fn load_metadata<T: ReadExt + SeekExt>(data: &mut T) -> Result<Metadata, self::Error> {
+    fn load_metadata<T: ReadExt + SeekExt>(
+        data: &mut T, limits: &ResourceLimits,
+    ) -> Result<Metadata, self::Error> {
         let header = Multifile::read_header(data)?;
 
         // This is designed to work with an "optimized" Multifile. This means that all Subfile metadata is at
@@ -207,13 +273,19 @@ impl Multifile {
         // we accumulate all metadata, and then parse it into a BTreeMap to optimize cache efficiency.
         let mut files = Vec::new();
 
-        let mut next_index = data.read_u32()? * header.scale_factor;
+        let scale_factor = u64::from(header.scale_factor);
+        let mut next_index = u64::from(data.read_u32()?) * scale_factor;
+        let mut total_size = 0u64;
         while next_index != 0 {
-            let subfile = SubfileHeader::load(data, header.version)?;
+            let mut subfile = SubfileHeader::load(data, header.version)?;
+            subfile.offset *= scale_factor;
+            limits.check_entries(files.len() as u64 + 1)?;
+            total_size += u64::from(subfile.length);
+            limits.check_output_size(total_size)?;
             files.push(subfile);
 
-            data.set_position(next_index.into())?;
-            next_index = data.read_u32()? * header.scale_factor;
+            data.set_position(next_index)?;
+            next_index = u64::from(data.read_u32()?) * scale_factor;
         }
 
         Ok(Metadata { header, files })
@@ -223,7 +295,25 @@ impl Multifile {
     #[inline]
     #[cfg(feature = "std")]
     pub fn extract_all<P: AsRef<Path>>(&mut self, output: P) -> Result<usize, self::Error> {
-        let output = PathBuf::from(output.as_ref());
+        self.extract_all_with_options(output, &PathOptions::DEFAULT)
+    }
+
+    /// Extracts all non-special Subfiles to the specified output directory, using `options` to control how
+    /// Subfile names are sanitized.
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn extract_all_with_options<P: AsRef<Path>>(
+        &mut self, output: P, options: &PathOptions,
+    ) -> Result<usize, self::Error> {
+        self.extract_all_into(&Extraction::direct(output.as_ref()), options)
+    }
+
+    /// Extracts all non-special Subfiles to wherever `extraction` puts them, such as a staging folder or
+    /// nowhere at all for a dry run. The [`Extraction`] still needs to be committed afterwards.
+    #[cfg(feature = "std")]
+    pub fn extract_all_into(
+        &mut self, extraction: &Extraction, options: &PathOptions,
+    ) -> Result<usize, self::Error> {
         let mut saved_files = 0;
         for subfile in &self.files {
             if !subfile
@@ -231,7 +321,11 @@ impl Multifile {
                 .attributes
                 .intersects(Attributes::Signature | Attributes::Compressed | Attributes::Encrypted)
             {
-                let path = output.join(subfile.0);
+                let path = options.sanitize(subfile.0)?;
+                let Some(path) = extraction.prepare(path, subfile.1.data.len() as u64)? else {
+                    saved_files += 1;
+                    continue;
+                };
 
                 if let Some(dir) = path.parent() {
                     std::fs::create_dir_all(dir)?;
@@ -252,15 +346,100 @@ impl Multifile {
         Ok(saved_files)
     }
 
+    /// Asynchronously extracts all non-special Subfiles to the specified output directory, see
+    /// [`extract_all`](Self::extract_all).
+    #[inline]
+    #[cfg(feature = "async")]
+    pub async fn extract_all_async<P: AsRef<Path>>(&self, output: P) -> Result<usize, self::Error> {
+        self.extract_all_with_options_async(output, &PathOptions::DEFAULT).await
+    }
+
+    /// Asynchronously extracts all non-special Subfiles to the specified output directory, using `options` to
+    /// control how Subfile names are sanitized. Files are written one at a time on a background thread pool,
+    /// so this works with any async runtime.
+    #[cfg(feature = "async")]
+    #[inline]
+    pub async fn extract_all_with_options_async<P: AsRef<Path>>(
+        &self, output: P, options: &PathOptions,
+    ) -> Result<usize, self::Error> {
+        self.extract_all_into_async(&Extraction::direct(output.as_ref()), options).await
+    }
+
+    /// Asynchronously extracts all non-special Subfiles to wherever `extraction` puts them, see
+    /// [`extract_all_into`](Self::extract_all_into).
+    #[cfg(feature = "async")]
+    pub async fn extract_all_into_async(
+        &self, extraction: &Extraction, options: &PathOptions,
+    ) -> Result<usize, self::Error> {
+        let mut saved_files = 0;
+        for (name, subfile) in &self.files {
+            if subfile
+                .attributes
+                .intersects(Attributes::Signature | Attributes::Compressed | Attributes::Encrypted)
+            {
+                continue;
+            }
+            let Some(path) = extraction.prepare(options.sanitize(name)?, subfile.data.len() as u64)? else {
+                saved_files += 1;
+                continue;
+            };
+
+            if let Some(dir) = path.parent() {
+                async_fs::create_dir_all(dir).await?;
+            }
+            async_fs::write(&path, &subfile.data).await?;
+
+            // async-fs has no way to set timestamps, so reopen the file on the thread pool instead
+            if subfile.timestamp != 0 {
+                let timestamp = Duration::from_secs(subfile.timestamp.into());
+                if let Some(modified) = SystemTime::UNIX_EPOCH.checked_add(timestamp) {
+                    let file = move || File::options().write(true).open(path)?.set_modified(modified);
+                    blocking::unblock(file).await?;
+                }
+            }
+
+            saved_files += 1;
+        }
+        Ok(saved_files)
+    }
+
+    /// Extracts all non-special Subfiles from a Multifile on disk, without loading the whole Multifile into
+    /// memory.
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn extract_from_file<P: AsRef<Path>, O: AsRef<Path>>(
+        input: P, output: O,
+    ) -> Result<usize, self::Error> {
+        Self::extract_from_file_with_options(input, output, &PathOptions::DEFAULT)
+    }
+
+    /// Extracts all non-special Subfiles from a Multifile on disk, using `options` to control how Subfile
+    /// names are sanitized.
     #[inline]
     #[cfg(feature = "std")]
-    pub fn extract_from_file<P: AsRef<Path>>(input: P, output: P) -> Result<usize, self::Error> {
+    pub fn extract_from_file_with_options<P: AsRef<Path>, O: AsRef<Path>>(
+        input: P, output: O, options: &PathOptions,
+    ) -> Result<usize, self::Error> {
+        Self::extract_from_file_into(
+            input,
+            &Extraction::direct(output.as_ref()),
+            options,
+            &ResourceLimits::UNLIMITED,
+        )
+    }
+
+    /// Extracts all non-special Subfiles from a Multifile on disk to wherever `extraction` puts them, such as
+    /// a staging folder or nowhere at all for a dry run, returning an error instead of extracting more
+    /// Subfiles, or more data, than `limits` allow. The [`Extraction`] still needs to be committed afterwards.
+    #[cfg(feature = "std")]
+    pub fn extract_from_file_into<P: AsRef<Path>>(
+        input: P, extraction: &Extraction, options: &PathOptions, limits: &ResourceLimits,
+    ) -> Result<usize, self::Error> {
         let input = BufReader::new(File::open(input.as_ref())?);
         let mut data = DataStream::new(input, Endian::Little);
-        let output = PathBuf::from(output.as_ref());
 
         // Load all metadata (hopefully at the beginning of the file so our BufReader isn't getting thrashed)
-        let metadata = Self::load_metadata(&mut data)?;
+        let metadata = Self::load_metadata(&mut data, limits)?;
 
         // Now, let's actually extract to the filesystem
         let mut saved_files = 0;
@@ -281,13 +460,17 @@ impl Multifile {
                 .attributes
                 .intersects(Attributes::Signature | Attributes::Compressed | Attributes::Encrypted)
             {
-                let path = output.join(header.filename);
+                let path = options.sanitize(&header.filename)?;
+                let Some(path) = extraction.prepare(path, header.length.into())? else {
+                    saved_files += 1;
+                    continue;
+                };
 
                 if let Some(dir) = path.parent() {
                     std::fs::create_dir_all(dir)?;
                 }
 
-                data.set_position(header.offset.into())?;
+                data.set_position(header.offset)?;
 
                 let mut file = File::create(path)?;
                 file.write_all(&data.read_slice(header.length as usize)?)?;
@@ -304,6 +487,51 @@ impl Multifile {
 
         Ok(saved_files)
     }
+
+    /// Overwrites a single Subfile without rewriting the rest of the Multifile, as long as `contents` is no
+    /// larger than the original Subfile. Any leftover space is filled with zeroes, and the Subfile's length is
+    /// updated to match.
+    ///
+    /// Compressed and encrypted Subfiles have a larger index entry than plain ones, so replacing them always
+    /// requires a rebuild.
+    ///
+    /// # Errors
+    /// Returns [`FileNotFound`](Error::FileNotFound) if the Multifile doesn't contain `path`, or
+    /// [`FileError`](Error::FileError) if unable to write to the input.
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn replace_in_place<T: Read + Write + Seek>(
+        input: T, path: &str, contents: &[u8],
+    ) -> Result<Replacement, self::Error> {
+        let mut data = DataStream::new(input, Endian::Little);
+        let header_size = Self::parse_header_prefix(&mut data)?;
+        data.set_position(header_size)?;
+        let metadata = Self::load_metadata(&mut data, &ResourceLimits::UNLIMITED)?;
+
+        let subfile = metadata
+            .files
+            .iter()
+            .find(|subfile| subfile.filename == path && !subfile.attributes.contains(Attributes::Deleted))
+            .context(FileNotFoundSnafu { path })?;
+
+        let length = contents.len() as u32;
+        if subfile.attributes.intersects(Attributes::Compressed | Attributes::Encrypted)
+            || contents.len() > subfile.length as usize
+        {
+            return Ok(Replacement::RebuildRequired);
+        }
+
+        data.set_position(subfile.offset)?;
+        data.write_all(contents)?;
+        std::io::copy(
+            &mut std::io::repeat(0).take(u64::from(subfile.length - length)),
+            &mut *data,
+        )?;
+
+        data.set_position(subfile.position + 4)?;
+        data.write_u32(length)?;
+        Ok(Replacement::InPlace)
+    }
 }
 
 bitflags! {
@@ -321,7 +549,10 @@ bitflags! {
 
 #[derive(Debug)]
 struct SubfileHeader {
-    offset: u32,
+    /// Where this header is stored in the Multifile, so it can be edited in-place.
+    position: u64,
+    /// Offset to the Subfile's data, already multiplied by the scale factor.
+    offset: u64,
     length: u32,
     attributes: Attributes,
     original_length: u32,
@@ -331,8 +562,9 @@ struct SubfileHeader {
 
 impl SubfileHeader {
     #[inline]
-    fn load<T: ReadExt>(data: &mut T, version: Version) -> Result<Self, self::Error> {
-        let offset = data.read_u32()?;
+    fn load<T: ReadExt + SeekExt>(data: &mut T, version: Version) -> Result<Self, self::Error> {
+        let position = data.position()?;
+        let offset = u64::from(data.read_u32()?);
         let length = data.read_u32()?;
         let attributes = Attributes::from_bits_truncate(data.read_u16()?);
 
@@ -352,7 +584,15 @@ impl SubfileHeader {
             filename.push((255 - *c).into());
         }
 
-        Ok(SubfileHeader { offset, length, attributes, original_length, timestamp, filename })
+        Ok(SubfileHeader {
+            position,
+            offset,
+            length,
+            attributes,
+            original_length,
+            timestamp,
+            filename,
+        })
     }
 }
 
@@ -368,7 +608,7 @@ struct Subfile {
 impl Subfile {
     #[inline]
     fn load<T: ReadExt + SeekExt>(data: &mut T, header: &SubfileHeader) -> Result<Self, self::Error> {
-        data.set_position(header.offset.into())?;
+        data.set_position(header.offset)?;
         Ok(Subfile {
             attributes: header.attributes,
             original_length: header.original_length,
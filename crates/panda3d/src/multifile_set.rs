@@ -0,0 +1,239 @@
+//! Adds support for mounting multiple Multifiles into a single namespace, the same way that Panda3D's
+//! `VirtualFileSystem` does.
+//!
+//! Games usually split their assets across several Multifiles that are all mounted at once, such as
+//! Toontown's `phase_3.mf` through `phase_13.mf`. A [`MultifileSet`] resolves a path across all of them, so
+//! callers don't need to know which Multifile a file came from.
+//!
+//! # Mounting
+//! Each Multifile is mounted at a mount point, which is prepended to the names of all its [`Subfile`]s. An
+//! empty mount point (or `/`) mounts the Multifile at the root, which is what Toontown does, since its
+//! [`Subfile`] names already start with their phase folder.
+//!
+//! When more than one Multifile contains the same path, the one mounted **last** takes precedence, matching
+//! Panda3D. This allows patches to be mounted on top of the original files.
+//!
+//! Paths are compared after removing any empty, `.`, and `..` components, so `/phase_3//models/../maps` and
+//! `phase_3/maps` are the same path. Like Panda3D, paths are case-sensitive.
+//!
+//! # Usage
+//! * [`mount`](MultifileSet::mount): Mount an already loaded [`Multifile`]
+//! * [`open`](MultifileSet::open): Load a Multifile from disk and mount it, or
+//!   [`open_with_limits`](MultifileSet::open_with_limits) for untrusted files
+//! * [`find`](MultifileSet::find): Returns the [`Subfile`] a path resolves to
+//! * [`reader`](MultifileSet::reader): Stream the contents of a path, decompressing it if needed
+//! * [`files`](MultifileSet::files): Returns every path in the set, along with the [`Subfile`] it resolves to
+//! * [`read_dir`](MultifileSet::read_dir) and [`is_directory`](MultifileSet::is_directory): Browse the set
+//!   as a directory tree
+//!
+//! ## Example
+//! Mounting two Multifiles that share some paths, using small generated ones from `orthrus_tools::corpus` in
+//! place of real game data:
+//!
+//! ```
+//! use orthrus_panda3d::prelude::*;
+//!
+//! let mut set = MultifileSet::new();
+//! set.mount(Multifile::load(orthrus_tools::corpus::multifile(3), 0)?, "phase_3");
+//! set.mount(Multifile::load(orthrus_tools::corpus::multifile(1), 0)?, "phase_3");
+//!
+//! // file0.bin and file1.bin are in both, so they come from the second Multifile
+//! let patch = Multifile::load(orthrus_tools::corpus::multifile(1), 0)?;
+//! let subfile = set.find("/phase_3/file1.bin").unwrap();
+//! assert_eq!(subfile.length(), patch.subfiles()[1].length());
+//!
+//! assert_eq!(set.files().len(), 4);
+//! assert_eq!(set.read_dir("phase_3").len(), 4);
+//! assert!(set.is_directory("phase_3"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+#[cfg(feature = "std")]
+use orthrus_core::prelude::*;
+
+use crate::multifile::Multifile;
+#[cfg(feature = "std")]
+use crate::multifile::{Error, Result};
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+use crate::subfile::*;
+
+/// A set of [`Multifile`]s mounted into a single namespace, where later mounts take precedence.
+///
+/// See the [module documentation](self) for more information.
+#[derive(Debug, Default)]
+pub struct MultifileSet {
+    mounts: Vec<(String, Multifile)>,
+}
+
+impl MultifileSet {
+    /// Creates a new set without any Multifiles mounted.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self { mounts: Vec::new() }
+    }
+
+    /// Splits a path into its components, ignoring empty and `.` components and resolving any `..`.
+    fn normalize(path: &str) -> Vec<&str> {
+        let mut components = Vec::new();
+        for component in path.split(['/', '\\']) {
+            match component {
+                "" | "." => {}
+                ".." => {
+                    components.pop();
+                }
+                component => components.push(component),
+            }
+        }
+        components
+    }
+
+    /// Mounts `multifile` at `mount_point`, taking precedence over every Multifile mounted before it.
+    #[inline]
+    pub fn mount(&mut self, multifile: Multifile, mount_point: &str) {
+        self.mounts.push((Self::normalize(mount_point).join("/"), multifile));
+    }
+
+    /// Loads a Multifile from disk and mounts it at `mount_point`, see [`mount`](Self::mount).
+    ///
+    /// # Errors
+    /// Returns any error from [`Multifile::open`].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn open<P: AsRef<Path>>(&mut self, input: P, mount_point: &str) -> Result<()> {
+        self.open_with_limits(input, mount_point, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Loads a Multifile from disk the same way as [`open`](Self::open), returning an error instead of
+    /// reading a larger file, or more [`Subfile`]s or more total data than `limits` allow.
+    ///
+    /// The limits apply to each Multifile separately, and nothing is mounted if they're exceeded.
+    ///
+    /// # Errors
+    /// Returns any error from [`Multifile::open_with_limits`].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn open_with_limits<P: AsRef<Path>>(
+        &mut self, input: P, mount_point: &str, limits: &ResourceLimits,
+    ) -> Result<()> {
+        self.mount(Multifile::open_with_limits(input, 0, limits)?, mount_point);
+        Ok(())
+    }
+
+    /// Returns the number of mounted Multifiles.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.mounts.len()
+    }
+
+    /// Returns whether no Multifiles are mounted.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.mounts.is_empty()
+    }
+
+    /// Returns every mounted Multifile along with its mount point, in the order they were mounted.
+    #[inline]
+    pub fn mounts(&self) -> impl Iterator<Item = (&str, &Multifile)> {
+        self.mounts.iter().map(|(mount_point, multifile)| (mount_point.as_str(), multifile))
+    }
+
+    /// Returns the part of `path` that's inside of `mount_point`, or [`None`] if it's outside of it.
+    fn strip_mount_point<'a>(path: &'a str, mount_point: &str) -> Option<&'a str> {
+        match mount_point.is_empty() {
+            true => Some(path),
+            false => path.strip_prefix(mount_point)?.strip_prefix('/'),
+        }
+    }
+
+    /// Returns the Multifile that `path` resolves to, along with its [`Subfile`].
+    fn resolve(&self, path: &str) -> Option<(&Multifile, &Subfile)> {
+        let path = Self::normalize(path).join("/");
+        self.mounts.iter().rev().find_map(|(mount_point, multifile)| {
+            let name = Self::strip_mount_point(&path, mount_point)?;
+            Some((multifile, multifile.find(name)?))
+        })
+    }
+
+    /// Returns the [`Subfile`] that `path` resolves to, from the last mounted Multifile that contains it.
+    #[must_use]
+    #[inline]
+    pub fn find(&self, path: &str) -> Option<&Subfile> {
+        self.resolve(path).map(|(_, subfile)| subfile)
+    }
+
+    /// Returns whether `path` resolves to a [`Subfile`] in any mounted Multifile.
+    #[must_use]
+    #[inline]
+    pub fn contains(&self, path: &str) -> bool {
+        self.resolve(path).is_some()
+    }
+
+    /// Returns a [`SubfileReader`] that streams the contents of `path`, from the last mounted Multifile that
+    /// contains it.
+    ///
+    /// # Errors
+    /// Returns [`FileNotFound`](Error::FileNotFound) if no mounted Multifile contains `path`, or any error
+    /// from [`Multifile::reader`].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn reader(&self, path: &str) -> Result<SubfileReader<'_>> {
+        let (multifile, subfile) =
+            self.resolve(path).ok_or_else(|| Error::FileNotFound { path: path.to_owned() })?;
+        multifile.reader(subfile.name())
+    }
+
+    /// Returns every path in the set along with the [`Subfile`] it resolves to, sorted by path. Paths that
+    /// are in more than one Multifile are only included once.
+    #[must_use]
+    pub fn files(&self) -> BTreeMap<String, &Subfile> {
+        let mut files = BTreeMap::new();
+        // Insert in mount order, so that later mounts replace earlier ones
+        for (mount_point, multifile) in &self.mounts {
+            for subfile in multifile.subfiles().iter().filter(|subfile| !subfile.is_deleted()) {
+                let mut path = mount_point.clone();
+                for component in Self::normalize(subfile.name()) {
+                    if !path.is_empty() {
+                        path.push('/');
+                    }
+                    path.push_str(component);
+                }
+                files.insert(path, subfile);
+            }
+        }
+        files
+    }
+
+    /// Returns the names of every file and directory directly inside of the directory at `path`, sorted by
+    /// name. Returns an empty list if `path` isn't a directory.
+    #[must_use]
+    pub fn read_dir(&self, path: &str) -> Vec<String> {
+        let path = Self::normalize(path).join("/");
+        let mut entries: Vec<String> = self
+            .files()
+            .into_keys()
+            .filter_map(|file| {
+                let name = Self::strip_mount_point(&file, &path)?;
+                Some(name.split('/').next().unwrap_or(name).to_owned())
+            })
+            .collect();
+        entries.sort_unstable();
+        entries.dedup();
+        entries
+    }
+
+    /// Returns whether `path` is a directory, which is the case if any path in the set is inside of it.
+    #[must_use]
+    pub fn is_directory(&self, path: &str) -> bool {
+        let path = Self::normalize(path).join("/");
+        self.files().keys().any(|file| Self::strip_mount_point(file, &path).is_some())
+    }
+}
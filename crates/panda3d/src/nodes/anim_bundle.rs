@@ -3,6 +3,7 @@ use core::ops::{Deref, DerefMut};
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct AnimBundle {
     pub inner: AnimGroup,
@@ -3,6 +3,7 @@ use core::ops::{Deref, DerefMut};
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct AnimBundleNode {
     pub inner: PandaNode,
@@ -13,7 +14,7 @@ impl Node for AnimBundleNode {
     #[inline]
     fn create(loader: &mut BinaryAsset, data: &mut Datagram) -> Result<Self, bam::Error> {
         let inner = PandaNode::create(loader, data)?;
-        let anim_bundle_ref = loader.read_pointer(data)?.unwrap();
+        let anim_bundle_ref = loader.read_required_pointer(data, "AnimBundle")?;
         Ok(Self { inner, anim_bundle_ref })
     }
 }
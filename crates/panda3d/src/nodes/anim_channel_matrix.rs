@@ -4,6 +4,7 @@ use super::prelude::*;
 
 // This is technically a generic but I don't feel like making one
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct AnimChannelMatrix {
     pub inner: AnimGroup,
@@ -6,6 +6,7 @@ const NUM_MATRIX_COMPONENTS: usize = 12;
 
 // TODO: re-type this from f32 once we make read_float generic
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct AnimChannelMatrixXfmTable {
     pub inner: AnimChannelMatrix,
@@ -31,10 +32,10 @@ impl Node for AnimChannelMatrixXfmTable {
             }
 
             if !new_hpr {
-                unimplemented!("Haven't implemented old HPR translation in AnimChannelMatrixXfmTable");
+                return Err(bam::Error::Unsupported { reason: "old HPR rotations" });
             }
         } else {
-            unimplemented!("Haven't implemented FFT decompression in AnimChannelMatrixXfmTable");
+            return Err(bam::Error::Unsupported { reason: "FFT-compressed tables" });
         }
 
         Ok(Self { inner, tables })
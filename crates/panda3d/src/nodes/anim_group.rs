@@ -1,6 +1,7 @@
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct AnimGroup {
     pub name: String,
@@ -12,11 +13,11 @@ impl Node for AnimGroup {
     #[inline]
     fn create(loader: &mut BinaryAsset, data: &mut Datagram) -> Result<Self, bam::Error> {
         let name = data.read_string()?;
-        let root_ref = loader.read_pointer(data)?.unwrap();
+        let root_ref = loader.read_required_pointer(data, "root AnimBundle")?;
         let num_children = data.read_u16()?;
         let mut child_refs = Vec::with_capacity(num_children as usize);
         for _ in 0..num_children {
-            let child_ref = loader.read_pointer(data)?.unwrap();
+            let child_ref = loader.read_required_pointer(data, "child AnimGroup")?;
             child_refs.push(child_ref);
         }
         Ok(Self { name, root_ref, child_refs })
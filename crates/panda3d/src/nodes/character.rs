@@ -3,6 +3,7 @@ use core::ops::{Deref, DerefMut};
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct Character {
     pub inner: PartBundleNode,
@@ -18,7 +19,7 @@ impl Node for Character {
         let temp_num_parts = data.read_u16()?;
         let mut temp_part_refs = Vec::with_capacity(temp_num_parts as usize);
         for _ in 0..temp_num_parts {
-            temp_part_refs.push(loader.read_pointer(data)?.unwrap());
+            temp_part_refs.push(loader.read_required_pointer(data, "PartGroup")?);
         }
 
         Ok(Self { inner, temp_part_refs })
@@ -3,6 +3,7 @@ use core::ops::{Deref, DerefMut};
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct CharacterJoint {
     pub inner: MovingPartMatrix,
@@ -25,13 +26,13 @@ impl Node for CharacterJoint {
         let num_net_nodes = data.read_u16()?;
         let mut net_node_refs = Vec::with_capacity(num_net_nodes as usize);
         for _ in 0..num_net_nodes {
-            net_node_refs.push(loader.read_pointer(data)?.unwrap());
+            net_node_refs.push(loader.read_required_pointer(data, "net transform PandaNode")?);
         }
 
         let num_local_nodes = data.read_u16()?;
         let mut local_node_refs = Vec::with_capacity(num_local_nodes as usize);
         for _ in 0..num_local_nodes {
-            local_node_refs.push(loader.read_pointer(data)?.unwrap());
+            local_node_refs.push(loader.read_required_pointer(data, "local transform PandaNode")?);
         }
 
         let initial_net_transform_inverse = Mat4::read(data)?;
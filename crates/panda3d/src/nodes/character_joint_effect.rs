@@ -3,6 +3,7 @@ use core::ops::{Deref, DerefMut};
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub(crate) struct CharacterJointEffect {
     pub character_ref: u32,
 }
@@ -10,7 +11,7 @@ pub(crate) struct CharacterJointEffect {
 impl Node for CharacterJointEffect {
     #[inline]
     fn create(loader: &mut BinaryAsset, data: &mut Datagram) -> Result<Self, bam::Error> {
-        Ok(Self { character_ref: loader.read_pointer(data)?.unwrap() })
+        Ok(Self { character_ref: loader.read_required_pointer(data, "Character")? })
     }
 }
 
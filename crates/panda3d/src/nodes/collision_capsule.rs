@@ -5,6 +5,7 @@ use bevy_transform::prelude::*;
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct CollisionCapsule {
     pub inner: CollisionSolid,
@@ -3,6 +3,7 @@ use core::ops::{Deref, DerefMut};
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct CollisionNode {
     pub inner: PandaNode,
@@ -22,7 +23,7 @@ impl Node for CollisionNode {
         };
         let mut solid_refs = Vec::with_capacity(num_solids as usize);
         for _ in 0..num_solids {
-            solid_refs.push(loader.read_pointer(data)?.unwrap());
+            solid_refs.push(loader.read_required_pointer(data, "CollisionSolid")?);
         }
 
         let collide_mask = data.read_u32()?;
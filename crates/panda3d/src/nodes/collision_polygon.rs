@@ -3,6 +3,7 @@ use core::ops::{Deref, DerefMut};
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct CollisionPolygon {
     pub inner: CollisionPlane,
@@ -25,8 +26,8 @@ impl Node for CollisionPolygon {
         let to_2d_matrix = Mat4::read(data)?;
 
         if loader.get_minor_version() < 13 {
-            unimplemented!("I don't have any BAM files this old, message me - error in CollisionPolygon");
             //TODO: need to wind vertices the other way
+            return Err(bam::Error::Unsupported { reason: "files before BAM 6.13" });
         }
 
         Ok(Self { inner, points, to_2d_matrix })
@@ -3,6 +3,7 @@ use super::prelude::*;
 bitflags! {
     #[repr(transparent)]
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize))]
     pub(crate) struct Flags: u8 {
         const Tangible = 1 << 0;
         const EffectiveNormal = 1 << 1;
@@ -13,6 +14,7 @@ bitflags! {
 }
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct CollisionSolid {
     pub flags: Flags,
@@ -1,6 +1,7 @@
 use super::prelude::*;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum ColorType {
     #[default]
@@ -10,6 +11,7 @@ pub(crate) enum ColorType {
 }
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub(crate) struct ColorAttrib {
     pub color_type: ColorType,
     pub color: Vec4,
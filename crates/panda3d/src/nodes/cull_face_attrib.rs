@@ -1,6 +1,7 @@
 use super::prelude::*;
 
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum CullMode {
     None,
@@ -11,6 +12,7 @@ pub(crate) enum CullMode {
 }
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct CullFaceAttrib {
     pub mode: CullMode,
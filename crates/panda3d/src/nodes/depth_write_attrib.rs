@@ -3,6 +3,7 @@ use core::ops::{Deref, DerefMut};
 use super::prelude::*;
 
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum DepthMode {
     Off,
@@ -11,6 +12,7 @@ pub(crate) enum DepthMode {
 }
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub(crate) struct DepthWriteAttrib {
     pub mode: DepthMode,
 }
@@ -94,6 +94,27 @@ macro_rules! stored_types {
                         )*
                     }
                 }
+
+                /// Returns the name of the node's type, as it's stored in the BAM file.
+                #[allow(dead_code)]
+                pub(crate) fn type_name(&self) -> &'static str {
+                    match self {
+                        $(
+                            NodeRef::$type(_) => stringify!($type),
+                        )*
+                    }
+                }
+            }
+
+            #[cfg(feature = "serde")]
+            impl serde::Serialize for NodeRef<'_> {
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    match self {
+                        $(
+                            NodeRef::$type(node) => node.serialize(serializer),
+                        )*
+                    }
+                }
             }
 
             // Trait for stored types
@@ -141,6 +162,7 @@ stored_types!(
     ColorAttrib,
     CullBinAttrib,
     CullFaceAttrib,
+    CustomObject,
     DecalEffect,
     DepthWriteAttrib,
     Geom,
@@ -159,11 +181,59 @@ stored_types!(
     PartGroup,
     RenderEffects,
     RenderState,
+    SheetNode,
+    TextNode,
     Texture,
     TextureAttrib,
     TextureStage,
     TransformBlendTable,
     TransformState,
+    TransformTable,
     TransparencyAttrib,
     UserVertexTransform,
 );
+
+impl NodeRef<'_> {
+    /// Returns the object's name, for types that have one.
+    pub(crate) fn name(&self) -> Option<&str> {
+        match self {
+            NodeRef::AnimBundle(node) => Some(&node.name),
+            NodeRef::AnimBundleNode(node) => Some(&node.name),
+            NodeRef::AnimChannelMatrixXfmTable(node) => Some(&node.name),
+            NodeRef::AnimGroup(node) => Some(&node.name),
+            NodeRef::Character(node) => Some(&node.name),
+            NodeRef::CharacterJoint(node) => Some(&node.name),
+            NodeRef::CollisionNode(node) => Some(&node.name),
+            NodeRef::GeomNode(node) => Some(&node.name),
+            NodeRef::GeomVertexData(node) => Some(&node.name),
+            NodeRef::InternalName(node) => Some(&node.name),
+            NodeRef::LODNode(node) => Some(&node.name),
+            NodeRef::ModelNode(node) => Some(&node.name),
+            NodeRef::PandaNode(node) => Some(&node.name),
+            NodeRef::PartBundle(node) => Some(&node.name),
+            NodeRef::PartGroup(node) => Some(&node.name),
+            NodeRef::SheetNode(node) => Some(&node.name),
+            NodeRef::TextNode(node) => Some(&node.name),
+            NodeRef::Texture(node) => Some(&node.name),
+            NodeRef::TextureStage(node) => Some(&node.name),
+            _ => None,
+        }
+    }
+
+    /// Returns the object's [`PandaNode`] data, for types that derive from it.
+    pub(crate) fn panda_node(&self) -> Option<&PandaNode> {
+        let node: &PandaNode = match self {
+            NodeRef::AnimBundleNode(node) => node,
+            NodeRef::Character(node) => node,
+            NodeRef::CollisionNode(node) => node,
+            NodeRef::GeomNode(node) => node,
+            NodeRef::LODNode(node) => node,
+            NodeRef::ModelNode(node) => node,
+            NodeRef::PandaNode(node) => node,
+            NodeRef::SheetNode(node) => node,
+            NodeRef::TextNode(node) => node,
+            _ => return None,
+        };
+        Some(node)
+    }
+}
@@ -1,6 +1,7 @@
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct Geom {
     /// Reference to the associated GeomVertexData
@@ -16,12 +17,12 @@ pub(crate) struct Geom {
 impl Node for Geom {
     #[inline]
     fn create(loader: &mut BinaryAsset, data: &mut Datagram) -> Result<Self, bam::Error> {
-        let data_ref = loader.read_pointer(data)?.unwrap();
+        let data_ref = loader.read_required_pointer(data, "GeomVertexData")?;
 
         let num_primitives = data.read_u16()?;
         let mut primitive_refs = Vec::with_capacity(num_primitives as usize);
         for _ in 0..num_primitives {
-            primitive_refs.push(loader.read_pointer(data)?.unwrap());
+            primitive_refs.push(loader.read_required_pointer(data, "GeomPrimitive")?);
         }
 
         let primitive_type = PrimitiveType::from(data.read_u8()?);
@@ -1,6 +1,7 @@
 use super::prelude::*;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum AnimationType {
     #[default]
@@ -13,6 +14,7 @@ pub(crate) enum AnimationType {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum UsageHint {
     // These are ordered from most dynamic to most static.
@@ -33,6 +35,7 @@ pub(crate) enum UsageHint {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum NumericType {
     #[default]
@@ -55,6 +58,7 @@ pub(crate) enum NumericType {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum Contents {
     #[default]
@@ -71,6 +75,7 @@ pub(crate) enum Contents {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum ShadeModel {
     #[default]
@@ -81,6 +86,7 @@ pub(crate) enum ShadeModel {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum PrimitiveType {
     #[default]
@@ -94,6 +100,7 @@ pub(crate) enum PrimitiveType {
 bitflags! {
     #[repr(transparent)]
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize))]
     pub struct GeomRendering: u32 {
         const IndexedPoint         = 1 << 0;
         const IndexedOther         = 1 << 16;
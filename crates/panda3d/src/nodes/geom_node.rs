@@ -3,6 +3,7 @@ use core::ops::{Deref, DerefMut};
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct GeomNode {
     /// ModelNode is a superclass of PandaNode, so we include its data here
@@ -20,8 +21,8 @@ impl Node for GeomNode {
         let num_geoms = data.read_u16()?;
         let mut geom_refs = Vec::with_capacity(num_geoms as usize);
         for _ in 0..num_geoms {
-            let geom_ref = loader.read_pointer(data)?.unwrap(); //Geom
-            let render_ref = loader.read_pointer(data)?.unwrap(); //RenderState
+            let geom_ref = loader.read_required_pointer(data, "Geom")?;
+            let render_ref = loader.read_required_pointer(data, "RenderState")?;
             geom_refs.push((geom_ref, render_ref));
         }
 
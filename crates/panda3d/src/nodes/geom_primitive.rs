@@ -1,6 +1,7 @@
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct GeomPrimitive {
     pub shade_model: ShadeModel,
@@ -30,7 +31,11 @@ impl Node for GeomPrimitive {
             0 => {
                 // If the pointer is zero, that means that it's a NULL, so just store None. We still need to
                 // read an array, but it's empty
-                assert!(data.read_u32()? == 0);
+                if data.read_u32()? != 0 {
+                    return Err(bam::Error::InvalidData {
+                        reason: "vertex ends are NULL, but have a non-empty array",
+                    });
+                }
                 None
             }
             x if x >= loader.arrays.len() as u32 => {
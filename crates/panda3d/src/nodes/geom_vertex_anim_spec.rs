@@ -2,6 +2,7 @@ use super::geom_enums::AnimationType;
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct GeomVertexAnimationSpec {
     pub animation_type: AnimationType,
@@ -3,6 +3,7 @@ use orthrus_core::data::EndianExt;
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct GeomVertexArrayData {
     /// Reference to the associated GeomVertexArrayFormat used to interpret the data
@@ -10,13 +11,14 @@ pub(crate) struct GeomVertexArrayData {
     /// Usage hint on how often the data in question will be modified/rendered
     pub usage_hint: UsageHint,
     /// Raw vertex data, stored as a u8 array and interpreted according to the array format
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::nodes::serialize::buffer"))]
     pub buffer: Vec<u8>,
 }
 
 impl Node for GeomVertexArrayData {
     #[inline]
     fn create(loader: &mut BinaryAsset, data: &mut Datagram) -> Result<Self, bam::Error> {
-        let array_format_ref = loader.read_pointer(data)?.unwrap();
+        let array_format_ref = loader.read_required_pointer(data, "GeomVertexArrayFormat")?;
 
         //Cycler data
         let usage_hint = UsageHint::from(data.read_u8()?);
@@ -30,13 +32,13 @@ impl Node for GeomVertexArrayData {
             }
             false => {
                 let _ptr_to_array = loader.read_pta_id(data)?;
-                unimplemented!("I don't have any <6.8 BAM files - contact me");
+                return Err(bam::Error::Unsupported { reason: "files before BAM 6.8" });
             }
         };
 
         //TODO: byteswap if endianness doesn't match
         if data.endian() != Endian::default() {
-            unimplemented!("Need to byteswap GeomVertexArrayData - non-native endianness");
+            return Err(bam::Error::Unsupported { reason: "non-native byte order" });
         }
 
         Ok(Self { array_format_ref, usage_hint, buffer })
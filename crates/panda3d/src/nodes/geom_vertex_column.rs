@@ -3,6 +3,7 @@ use super::prelude::*;
 pub const VERTEX_COLUMN_ALIGNMENT: u8 = 4;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct GeomVertexColumn {
     pub name_ref: u32,
@@ -25,7 +26,7 @@ impl GeomVertexColumn {
         self.num_values = self.num_components.into();
 
         if self.numeric_type == NumericType::StdFloat {
-            match loader.header.use_double {
+            self.numeric_type = match loader.header.use_double {
                 true => NumericType::F64,
                 false => NumericType::F32,
             };
@@ -74,7 +75,7 @@ impl GeomVertexColumn {
 impl GeomVertexColumn {
     #[inline]
     pub fn create(loader: &mut BinaryAsset, data: &mut Datagram) -> Result<Self, bam::Error> {
-        let name_ref = loader.read_pointer(data)?.unwrap();
+        let name_ref = loader.read_required_pointer(data, "InternalName")?;
         let num_components = data.read_u8()?;
         let numeric_type = NumericType::from(data.read_u8()?);
         let contents = Contents::from(data.read_u8()?);
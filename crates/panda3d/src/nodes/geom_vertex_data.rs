@@ -1,6 +1,7 @@
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct GeomVertexData {
     pub name: String,
@@ -20,13 +21,13 @@ impl Node for GeomVertexData {
         let name = data.read_string()?;
 
         // Cycler data
-        let format_ref = loader.read_pointer(data)?.unwrap();
+        let format_ref = loader.read_required_pointer(data, "GeomVertexFormat")?;
         let usage_hint = UsageHint::from(data.read_u8()?);
 
         let num_arrays = data.read_u16()?;
         let mut array_refs = Vec::with_capacity(num_arrays as usize);
         for _ in 0..num_arrays {
-            array_refs.push(loader.read_pointer(data)?.unwrap());
+            array_refs.push(loader.read_required_pointer(data, "GeomVertexArrayData")?);
         }
 
         let transform_table_ref = loader.read_pointer(data)?;
@@ -3,6 +3,7 @@ use core::ops::{Deref, DerefMut};
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct GeomVertexFormat {
     pub animation: GeomVertexAnimationSpec,
@@ -18,7 +19,7 @@ impl Node for GeomVertexFormat {
         let num_arrays = data.read_u16()?;
         let mut array_refs = Vec::with_capacity(num_arrays as usize);
         for _ in 0..num_arrays {
-            array_refs.push(loader.read_pointer(data)?.unwrap());
+            array_refs.push(loader.read_required_pointer(data, "GeomVertexArrayFormat")?);
         }
 
         Ok(Self { animation, array_refs })
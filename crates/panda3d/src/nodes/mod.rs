@@ -1,6 +1,8 @@
 pub(crate) mod prelude;
 
 pub(crate) mod dispatch;
+#[cfg(feature = "serde")]
+pub(crate) mod serialize;
 pub(crate) mod types;
 
 pub(crate) mod anim_bundle;
@@ -49,12 +51,15 @@ pub(crate) mod part_group;
 pub(crate) mod render_effects;
 pub(crate) mod render_state;
 pub(crate) mod sampler_state;
+pub(crate) mod sheet_node;
 pub(crate) mod sparse_array;
+pub(crate) mod text_node;
 pub(crate) mod texture;
 pub(crate) mod texture_attrib;
 pub(crate) mod texture_stage;
 pub(crate) mod transform_blend;
 pub(crate) mod transform_blend_table;
 pub(crate) mod transform_state;
+pub(crate) mod transform_table;
 pub(crate) mod transparency_attrib;
 pub(crate) mod user_vertex_transform;
@@ -5,6 +5,7 @@ use super::prelude::*;
 /// The PreserveTransform attribute tells us how a flatten operation can affect the transform data
 /// on this node.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum PreserveTransform {
     #[default]
@@ -21,6 +22,7 @@ pub(crate) enum PreserveTransform {
 }
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct ModelNode {
     /// ModelNode is a superclass of a PandaNode, so we include its data here
@@ -4,6 +4,7 @@ use super::prelude::*;
 
 //TODO: This is technically a generic but I don't want to make it a generic right now
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct MovingPartMatrix {
     pub inner: MovingPartBase,
@@ -1,6 +1,7 @@
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct PandaNode {
     pub name: String,
@@ -18,6 +19,10 @@ pub(crate) struct PandaNode {
 
     pub bounds_type: BoundsType,
 
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::nodes::serialize::sorted_map")
+    )]
     pub tag_data: HashMap<String, String>,
 
     /// Reference to all parent nodes (may be derived from PandaNode)
@@ -27,6 +32,18 @@ pub(crate) struct PandaNode {
     pub stashed_refs: Vec<(u32, i32)>,
 }
 
+impl PandaNode {
+    /// Returns all children in the order Panda3D traverses them, sorted by their sort value. The sort is
+    /// stable, so children with the same sort value keep the order they were stored in, which is what decals
+    /// and UI elements rely on to draw on top of their siblings.
+    #[must_use]
+    pub fn sorted_children(&self) -> Vec<(u32, i32)> {
+        let mut children = self.child_refs.clone();
+        children.sort_by_key(|&(_, sort)| sort);
+        children
+    }
+}
+
 impl Node for PandaNode {
     #[inline]
     fn create(loader: &mut BinaryAsset, data: &mut Datagram) -> Result<Self, bam::Error> {
@@ -34,9 +51,9 @@ impl Node for PandaNode {
         let name = data.read_string()?;
 
         // Cycler Data
-        let state_ref = loader.read_pointer(data)?.unwrap();
-        let transform_ref = loader.read_pointer(data)?.unwrap();
-        let effects_ref = loader.read_pointer(data)?.unwrap();
+        let state_ref = loader.read_required_pointer(data, "RenderState")?;
+        let transform_ref = loader.read_required_pointer(data, "TransformState")?;
+        let effects_ref = loader.read_required_pointer(data, "RenderEffects")?;
 
         let draw_control_mask: u32;
         let draw_show_mask: u32;
@@ -79,14 +96,14 @@ impl Node for PandaNode {
         let num_parents = data.read_u16()?;
         let mut parent_refs = Vec::with_capacity(num_parents as usize);
         for _ in 0..num_parents {
-            parent_refs.push(loader.read_pointer(data)?.unwrap());
+            parent_refs.push(loader.read_required_pointer(data, "parent PandaNode")?);
         }
         //TODO: sort parent nodes? They're based on pointer order so they're different per session
 
         let num_children = data.read_u16()?;
         let mut child_refs = Vec::with_capacity(num_children as usize);
         for _ in 0..num_children {
-            let pointer = loader.read_pointer(data)?.unwrap();
+            let pointer = loader.read_required_pointer(data, "child PandaNode")?;
             let sort = data.read_i32()?;
             child_refs.push((pointer, sort));
         }
@@ -94,7 +111,7 @@ impl Node for PandaNode {
         let num_stashed = data.read_u16()?;
         let mut stashed_refs = Vec::with_capacity(num_stashed as usize);
         for _ in 0..num_stashed {
-            let pointer = loader.read_pointer(data)?.unwrap();
+            let pointer = loader.read_required_pointer(data, "stashed PandaNode")?;
             let sort = data.read_i32()?;
             stashed_refs.push((pointer, sort));
         }
@@ -148,7 +165,7 @@ impl GraphDisplay for PandaNode {
             write!(label, "}}")?;
         }
         // Ignore parents, since we should already have made that
-        for child_ref in &self.child_refs {
+        for child_ref in &self.sorted_children() {
             connections.push(child_ref.0);
         }
         for stashed_ref in &self.stashed_refs {
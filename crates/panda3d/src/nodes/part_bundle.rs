@@ -3,6 +3,7 @@ use core::ops::{Deref, DerefMut};
 use super::prelude::*;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum BlendType {
     Linear,
@@ -13,6 +14,7 @@ pub(crate) enum BlendType {
 }
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct PartBundle {
     pub inner: PartGroup,
@@ -35,7 +37,7 @@ impl Node for PartBundle {
 
         // Cycler Data
         if loader.get_minor_version() < 10 {
-            unimplemented!("I don't have any BAM files this old - contact me");
+            return Err(bam::Error::Unsupported { reason: "files before BAM 6.10" });
         }
         let blend_type = BlendType::from(data.read_u8()?);
         let anim_blend_flag = data.read_bool()?;
@@ -43,7 +45,7 @@ impl Node for PartBundle {
         let root_transform = Mat4::read(data)?;
 
         if loader.get_minor_version() == 11 {
-            unimplemented!("We need to handle _modifies_anim_bundles in PartBundle for this file");
+            return Err(bam::Error::Unsupported { reason: "modified AnimBundles from BAM 6.11" });
         }
 
         Ok(Self {
@@ -3,6 +3,7 @@ use core::ops::{Deref, DerefMut};
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct PartBundleNode {
     pub inner: PandaNode,
@@ -20,7 +21,7 @@ impl PartBundleNode {
         };
         let mut bundle_refs = Vec::with_capacity(num_bundles as usize);
         for _ in 0..num_bundles {
-            bundle_refs.push(loader.read_pointer(data)?.unwrap());
+            bundle_refs.push(loader.read_required_pointer(data, "PartBundle")?);
         }
 
         Ok(Self { inner, bundle_refs })
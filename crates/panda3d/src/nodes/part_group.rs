@@ -1,6 +1,7 @@
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct PartGroup {
     pub name: String,
@@ -13,13 +14,13 @@ impl Node for PartGroup {
         let name = data.read_string()?;
 
         if loader.get_minor_version() == 11 {
-            unimplemented!("I don't have a BAM file this old - contact me");
+            return Err(bam::Error::Unsupported { reason: "files from BAM 6.11" });
         }
 
         let num_children = data.read_u16()?;
         let mut child_refs = Vec::with_capacity(num_children as usize);
         for _ in 0..num_children {
-            child_refs.push(loader.read_pointer(data)?.unwrap());
+            child_refs.push(loader.read_required_pointer(data, "child PartGroup")?);
         }
 
         Ok(Self { name, child_refs })
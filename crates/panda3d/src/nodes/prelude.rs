@@ -3,6 +3,7 @@ pub(super) use bitflags::bitflags;
 pub(super) use hashbrown::HashMap;
 pub(super) use num_enum::FromPrimitive;
 pub(super) use orthrus_core::prelude::*;
+pub(crate) use orthrus_core::util::SparseArray;
 
 pub(super) use super::types::DatagramRead;
 pub(super) use crate::bam::BinaryAsset;
@@ -60,13 +61,15 @@ pub(crate) use super::part_group::PartGroup;
 pub(crate) use super::render_effects::RenderEffects;
 pub(crate) use super::render_state::RenderState;
 pub(crate) use super::sampler_state::SamplerState;
-pub(crate) use super::sparse_array::SparseArray;
+pub(crate) use super::sheet_node::SheetNode;
+pub(crate) use super::text_node::TextNode;
 pub(crate) use super::texture::Texture;
 pub(crate) use super::texture_attrib::TextureAttrib;
 pub(crate) use super::texture_stage::TextureStage;
 pub(crate) use super::transform_blend::TransformBlend;
 pub(crate) use super::transform_blend_table::TransformBlendTable;
 pub(crate) use super::transform_state::TransformState;
+pub(crate) use super::transform_table::TransformTable;
 pub(crate) use super::transparency_attrib::TransparencyAttrib;
 pub(crate) use super::user_vertex_transform::UserVertexTransform;
-pub(crate) use crate::bam::GraphDisplay;
+pub(crate) use crate::bam::{CustomObject, GraphDisplay};
@@ -3,6 +3,7 @@ use core::ops::{Deref, DerefMut};
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub(crate) struct RenderEffects {
     /// References to all Effects
     pub effect_refs: Vec<u32>,
@@ -14,7 +15,7 @@ impl Node for RenderEffects {
         let num_effects = data.read_u16()?;
         let mut effect_refs = Vec::with_capacity(num_effects as usize);
         for _ in 0..num_effects {
-            effect_refs.push(loader.read_pointer(data)?.unwrap());
+            effect_refs.push(loader.read_required_pointer(data, "RenderEffect")?);
         }
 
         Ok(Self { effect_refs })
@@ -3,6 +3,7 @@ use core::ops::{Deref, DerefMut};
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub(crate) struct RenderState {
     /// This stores a pointer to each RenderAttrib and its associated override value
     pub attrib_refs: Vec<(u32, i32)>,
@@ -14,7 +15,7 @@ impl Node for RenderState {
         let num_attribs = data.read_u16()?;
         let mut attrib_refs = Vec::with_capacity(num_attribs as usize);
         for _ in 0..num_attribs {
-            let attrib_ref = loader.read_pointer(data)?.unwrap();
+            let attrib_ref = loader.read_required_pointer(data, "RenderAttrib")?;
             let priority = data.read_i32()?;
             attrib_refs.push((attrib_ref, priority));
         }
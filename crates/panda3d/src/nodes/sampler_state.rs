@@ -1,6 +1,7 @@
 use super::prelude::*;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum WrapMode {
     /// Clamp coordinate to [0, 1]
@@ -16,6 +17,7 @@ pub(crate) enum WrapMode {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum FilterType {
     // Both min filter and mag filter
@@ -43,6 +45,7 @@ pub(crate) enum FilterType {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct SamplerState {
     pub wrap_u: WrapMode,
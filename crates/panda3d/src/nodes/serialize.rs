@@ -0,0 +1,104 @@
+//! Serializers for fields that need special handling when dumping objects with [`BinaryAsset::to_json`].
+
+use core::cell::Cell;
+use std::collections::BTreeMap;
+
+use serde::ser::{SerializeSeq, SerializeStruct};
+use serde::{Serialize, Serializer};
+
+use super::prelude::*;
+use crate::bam::BufferMode;
+
+std::thread_local! {
+    /// How buffers are written by the current call to [`BinaryAsset::to_json`], since serde has no way to pass
+    /// options down to each field
+    static BUFFER_MODE: Cell<BufferMode> = const { Cell::new(BufferMode::Base64) };
+}
+
+/// Runs `f` with buffers written using `mode`, restoring the previous mode afterwards.
+pub(crate) fn with_buffer_mode<T>(mode: BufferMode, f: impl FnOnce() -> T) -> T {
+    let previous = BUFFER_MODE.replace(mode);
+    let result = f();
+    BUFFER_MODE.set(previous);
+    result
+}
+
+/// Writes a byte buffer as its size, along with its data if using [`BufferMode::Base64`].
+pub(crate) fn buffer<S: Serializer>(buffer: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    match BUFFER_MODE.get() {
+        BufferMode::Strip => {
+            let mut state = serializer.serialize_struct("Buffer", 1)?;
+            state.serialize_field("size", &buffer.len())?;
+            state.end()
+        }
+        BufferMode::Base64 => {
+            let mut state = serializer.serialize_struct("Buffer", 2)?;
+            state.serialize_field("size", &buffer.len())?;
+            state.serialize_field("base64", &base64(buffer))?;
+            state.end()
+        }
+    }
+}
+
+/// Writes each RAM image of a Texture along with its page size.
+pub(crate) fn ram_images<S: Serializer>(images: &[(u32, Vec<u8>)], serializer: S) -> Result<S::Ok, S::Error> {
+    struct RamImage<'a>(u32, &'a [u8]);
+
+    impl Serialize for RamImage<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("RamImage", 2)?;
+            state.serialize_field("page_size", &self.0)?;
+            state.serialize_field("image", &Buffer(self.1))?;
+            state.end()
+        }
+    }
+
+    let mut seq = serializer.serialize_seq(Some(images.len()))?;
+    for (page_size, image) in images {
+        seq.serialize_element(&RamImage(*page_size, image))?;
+    }
+    seq.end()
+}
+
+/// Wrapper that allows writing a buffer inside of another type, see [`buffer`].
+struct Buffer<'a>(&'a [u8]);
+
+impl Serialize for Buffer<'_> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        buffer(self.0, serializer)
+    }
+}
+
+/// Writes a map sorted by key, so that the output is the same every time.
+pub(crate) fn sorted_map<S: Serializer>(
+    map: &HashMap<String, String>, serializer: S,
+) -> Result<S::Ok, S::Error> {
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+/// Writes a SparseArray the same way as any other node, since it's defined in orthrus-core without serde.
+pub(crate) fn sparse_array<S: Serializer>(array: &SparseArray, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut state = serializer.serialize_struct("SparseArray", 2)?;
+    state.serialize_field("subranges", &array.subranges)?;
+    state.serialize_field("inverse", &array.inverse)?;
+    state.end()
+}
+
+/// Encodes `data` using the standard base64 alphabet, with padding.
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut output = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let value = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            output.push(match i <= chunk.len() {
+                true => ALPHABET[(value >> (18 - i * 6)) as usize & 0x3F] as char,
+                false => '=',
+            });
+        }
+    }
+    output
+}
@@ -0,0 +1,56 @@
+use core::ops::{Deref, DerefMut};
+
+use super::prelude::*;
+
+/// A SheetNode draws a NURBS surface, which is generated at runtime. Panda3D doesn't write the surface to
+/// BAM files yet, so all that's stored is the PandaNode data.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[allow(dead_code)]
+pub(crate) struct SheetNode {
+    /// SheetNode is a superclass of a PandaNode, so we include its data here
+    pub inner: PandaNode,
+}
+
+impl Node for SheetNode {
+    #[inline]
+    fn create(loader: &mut BinaryAsset, data: &mut Datagram) -> Result<Self, bam::Error> {
+        let inner = PandaNode::create(loader, data)?;
+        Ok(Self { inner })
+    }
+}
+
+impl GraphDisplay for SheetNode {
+    fn write_data(
+        &self, label: &mut impl core::fmt::Write, connections: &mut Vec<u32>, is_root: bool,
+    ) -> Result<(), bam::Error> {
+        // Header
+        if is_root {
+            write!(label, "{{SheetNode|")?;
+        }
+
+        self.inner.write_data(label, connections, false)?;
+
+        // Footer
+        if is_root {
+            write!(label, "}}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Deref for SheetNode {
+    type Target = PandaNode;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for SheetNode {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
@@ -0,0 +1,57 @@
+use core::ops::{Deref, DerefMut};
+
+use super::prelude::*;
+
+/// A TextNode generates the geometry for its text at runtime, using the font and properties set in code.
+/// Panda3D doesn't write any of those to BAM files, so all that's stored is the PandaNode data, which is
+/// mostly found in fonts and GUI models that were written out with a TextNode still attached.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[allow(dead_code)]
+pub(crate) struct TextNode {
+    /// TextNode is a superclass of a PandaNode, so we include its data here
+    pub inner: PandaNode,
+}
+
+impl Node for TextNode {
+    #[inline]
+    fn create(loader: &mut BinaryAsset, data: &mut Datagram) -> Result<Self, bam::Error> {
+        let inner = PandaNode::create(loader, data)?;
+        Ok(Self { inner })
+    }
+}
+
+impl GraphDisplay for TextNode {
+    fn write_data(
+        &self, label: &mut impl core::fmt::Write, connections: &mut Vec<u32>, is_root: bool,
+    ) -> Result<(), bam::Error> {
+        // Header
+        if is_root {
+            write!(label, "{{TextNode|")?;
+        }
+
+        self.inner.write_data(label, connections, false)?;
+
+        // Footer
+        if is_root {
+            write!(label, "}}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Deref for TextNode {
+    type Target = PandaNode;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for TextNode {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
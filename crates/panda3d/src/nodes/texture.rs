@@ -6,6 +6,7 @@ use super::prelude::*;
 use super::sampler_state::SamplerState;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum TextureType {
     Texture1D,
@@ -20,6 +21,7 @@ pub(crate) enum TextureType {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 #[allow(clippy::upper_case_acronyms)]
 pub(crate) enum CompressionMode {
@@ -58,6 +60,7 @@ pub(crate) enum CompressionMode {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum QualityLevel {
     #[default]
@@ -68,6 +71,7 @@ pub(crate) enum QualityLevel {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 #[allow(clippy::upper_case_acronyms)]
 pub(crate) enum Format {
@@ -141,6 +145,7 @@ pub(crate) enum Format {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum ComponentType {
     #[default]
@@ -155,6 +160,7 @@ pub(crate) enum ComponentType {
 }
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct Texture {
     pub name: String,
@@ -170,6 +176,7 @@ pub(crate) struct Texture {
 }
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub(crate) struct TextureBody {
     pub default_sampler: SamplerState,
     pub format: Format,
@@ -184,11 +191,13 @@ pub(crate) struct TextureBody {
     pub simple_y_size: u32,
     /// Timestamp of when the image was last modified
     pub simple_image_date_generated: i32,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::nodes::serialize::buffer"))]
     pub image: Vec<u8>,
     pub clear_color: Option<Vec4>,
 }
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct TextureData {
     pub size: UVec3,
@@ -199,6 +208,10 @@ pub(crate) struct TextureData {
     pub ram_image_compression: CompressionMode,
     pub ram_image_count: u8,
     /// Page Size + Image Data
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::nodes::serialize::ram_images")
+    )]
     pub ram_images: Vec<(u32, Vec<u8>)>,
 }
 
@@ -287,7 +300,7 @@ impl Texture {
         for _ in 0..ram_image_count {
             let page_size = match loader.get_minor_version() >= 1 {
                 true => data.read_u32()?,
-                false => todo!("I have no BAM files this old - contact me"),
+                false => return Err(bam::Error::Unsupported { reason: "RAM images before BAM 6.1" }),
             };
             let size = data.read_u32()?;
             let mut image = vec![0u8; size as usize];
@@ -1,6 +1,7 @@
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct StageNode {
     pub sampler: Option<SamplerState>,
@@ -15,8 +16,8 @@ pub(crate) struct StageNode {
 impl StageNode {
     #[inline]
     fn create(loader: &mut BinaryAsset, data: &mut Datagram) -> Result<Self, bam::Error> {
-        let texture_stage_ref = loader.read_pointer(data)?.unwrap();
-        let texture_ref = loader.read_pointer(data)?.unwrap();
+        let texture_stage_ref = loader.read_required_pointer(data, "TextureStage")?;
+        let texture_ref = loader.read_required_pointer(data, "Texture")?;
 
         let implicit_sort = match loader.get_minor_version() >= 15 {
             true => data.read_u16()?,
@@ -73,6 +74,7 @@ impl GraphDisplay for StageNode {
 }
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct TextureAttrib {
     pub off_all_stages: bool,
@@ -89,7 +91,7 @@ impl Node for TextureAttrib {
         let num_off_stages = data.read_u16()?;
         let mut off_stage_refs = Vec::with_capacity(num_off_stages as usize);
         for _ in 0..num_off_stages {
-            let texture_stage_ref = loader.read_pointer(data)?.unwrap();
+            let texture_stage_ref = loader.read_required_pointer(data, "off TextureStage")?;
             off_stage_refs.push(texture_stage_ref);
         }
 
@@ -1,6 +1,7 @@
 use super::prelude::*;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum Mode {
     //fixed-function pipeline
@@ -33,6 +34,7 @@ pub(crate) enum Mode {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum CombineMode {
     #[default]
@@ -48,6 +50,7 @@ pub(crate) enum CombineMode {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum CombineSource {
     #[default]
@@ -61,6 +64,7 @@ pub(crate) enum CombineSource {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub(crate) enum CombineOperand {
     #[default]
@@ -72,6 +76,7 @@ pub(crate) enum CombineOperand {
 }
 
 #[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct CombineConfig {
     pub mode: CombineMode,
@@ -150,6 +155,7 @@ impl GraphDisplay for CombineConfig {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct TextureStage {
     pub name: String,
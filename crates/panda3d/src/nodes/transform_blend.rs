@@ -3,6 +3,7 @@ use core::ops::{Deref, DerefMut};
 use super::prelude::*;
 
 #[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct TransformEntry {
     pub transform_ref: u32,
@@ -12,13 +13,14 @@ pub(crate) struct TransformEntry {
 impl TransformEntry {
     #[inline]
     fn create(loader: &mut BinaryAsset, data: &mut Datagram) -> Result<Self, bam::Error> {
-        let transform_ref = loader.read_pointer(data)?.unwrap();
+        let transform_ref = loader.read_required_pointer(data, "VertexTransform")?;
         let weight = data.read_float()?;
         Ok(Self { transform_ref, weight })
     }
 }
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub(crate) struct TransformBlend {
     pub entries: Vec<TransformEntry>,
 }
@@ -1,9 +1,14 @@
 use super::prelude::*;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(dead_code)]
 pub(crate) struct TransformBlendTable {
     pub blends: Vec<TransformBlend>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::nodes::serialize::sparse_array")
+    )]
     pub rows: SparseArray,
 }
 
@@ -17,9 +22,9 @@ impl Node for TransformBlendTable {
         }
 
         if loader.get_minor_version() < 7 {
-            unimplemented!("I don't have any BAM files this old - message me");
+            return Err(bam::Error::Unsupported { reason: "files before BAM 6.7" });
         }
-        let rows = SparseArray::create(loader, data)?;
+        let rows = SparseArray::read(&mut **data)?;
 
         //There is cdata but it doesn't actually have any BAM data stored
         Ok(Self { blends, rows })
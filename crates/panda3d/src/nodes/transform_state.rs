@@ -2,6 +2,7 @@ use super::prelude::*;
 
 bitflags! {
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize))]
     #[repr(transparent)]
     pub(crate) struct TransformFlags: u32 {
         const Identity = 0x00001;
@@ -26,6 +27,7 @@ bitflags! {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub(crate) struct TransformState {
     pub flags: TransformFlags,
     pub position: Vec3,
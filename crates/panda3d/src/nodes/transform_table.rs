@@ -0,0 +1,43 @@
+use super::prelude::*;
+
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub(crate) struct TransformTable {
+    /// Transforms referenced by index from a hardware-skinned vertex's transform_index column
+    pub transform_refs: Vec<u32>,
+}
+
+impl Node for TransformTable {
+    #[inline]
+    fn create(loader: &mut BinaryAsset, data: &mut Datagram) -> Result<Self, bam::Error> {
+        let num_transforms = data.read_u16()?;
+        let mut transform_refs = Vec::with_capacity(num_transforms as usize);
+        for _ in 0..num_transforms {
+            transform_refs.push(loader.read_required_pointer(data, "VertexTransform")?);
+        }
+
+        //There is cdata but it doesn't actually have any BAM data stored
+        Ok(Self { transform_refs })
+    }
+}
+
+impl GraphDisplay for TransformTable {
+    fn write_data(
+        &self, label: &mut impl core::fmt::Write, connections: &mut Vec<u32>, is_root: bool,
+    ) -> Result<(), bam::Error> {
+        // Header
+        if is_root {
+            write!(label, "{{TransformTable|")?;
+        }
+
+        // Fields
+        write!(label, "num_transforms: {}", self.transform_refs.len())?;
+        connections.extend(&self.transform_refs);
+
+        // Footer
+        if is_root {
+            write!(label, "}}")?;
+        }
+        Ok(())
+    }
+}
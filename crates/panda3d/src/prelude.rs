@@ -10,19 +10,61 @@
 #[doc(inline)]
 pub use crate::multifile::Multifile;
 
-/// Includes [`multifile::Error`] for Result handling.
+/// Includes [`multifile::Error`] for Result handling, and [`multifile::Header`] for archive metadata.
 pub mod multifile {
     #[doc(inline)]
-    pub use crate::multifile::Error;
+    pub use crate::multifile::{Error, Header};
+}
+
+#[doc(inline)]
+pub use crate::multifile_set::MultifileSet;
+
+/// Includes [`subfile::Subfile`] and [`subfile::SubfileReader`], for reading files inside of a Multifile.
+pub mod subfile {
+    #[doc(inline)]
+    pub use crate::subfile::Subfile;
+    #[cfg(feature = "std")]
+    #[doc(inline)]
+    pub use crate::subfile::SubfileReader;
 }
 
 #[doc(inline)]
 pub use crate::bam::BinaryAsset;
 
-/// Includes [`bam::Error`] for Result handling.
+/// Includes [`bam::Error`] for Result handling, [`bam::LoadOptions`] for custom object types and
+/// loading damaged files, [`bam::Glyph`] for fonts, [`bam::Animation`] for animations, and `bam::BufferMode`
+/// for dumping objects to JSON.
 pub mod bam {
+    #[cfg(feature = "serde")]
+    #[doc(inline)]
+    pub use crate::bam::BufferMode;
+    #[doc(inline)]
+    pub use crate::bam::{
+        AnimatedJoint, Animation, CustomObject, Error, Glyph, LoadMode, LoadOptions, ObjectError,
+        ObjectHandler, ObjectValue,
+    };
+}
+
+#[doc(inline)]
+pub use crate::bam_patch::Patch;
+
+/// Includes [`bam_patch::Error`] for Result handling, and [`bam_patch::Edit`] for each change a [`Patch`]
+/// makes.
+pub mod bam_patch {
+    #[doc(inline)]
+    pub use crate::bam_patch::{Edit, Error, Field, Target, Value};
+}
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use crate::crossref::CrossReference;
+
+/// Includes [`crossref::Error`] for Result handling, and [`crossref::Reference`] for each texture a model
+/// uses.
+#[cfg(feature = "std")]
+pub mod crossref {
     #[doc(inline)]
-    pub use crate::bam::Error;
+    pub use crate::crossref::{Error, Reference};
 }
 
 /// Includes [`panda3d::Version`] for file format versions.
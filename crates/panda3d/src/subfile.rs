@@ -1,18 +1,25 @@
 //! Adds support for the Subfile format used inside of Multifiles.
 //!
-//! This module is mainly internal right now, as Subfiles are heavily tied to their associated
-//! [`Multifile`](crate::multifile::Multifile) for the moment.
+//! Subfiles are heavily tied to their associated [`Multifile`](crate::multifile::Multifile), so they can only
+//! be created by loading one. Their contents can be streamed using a [`SubfileReader`], which decompresses
+//! them on the fly if needed, instead of having to buffer the whole file first.
 //!
 //! # Format
 //! Refer to the [Multifile format](crate::multifile#format) for more details.
 
 #[cfg(feature = "std")]
-use std::path::{Path, PathBuf};
+use std::io::{Read, Seek, SeekFrom};
 
 use bitflags::bitflags;
+#[cfg(feature = "std")]
+use miniz_oxide::inflate::stream::{inflate, InflateState};
+#[cfg(feature = "std")]
+use miniz_oxide::{DataFormat, MZFlush, MZStatus};
 use orthrus_core::prelude::*;
 
 use crate::common::Version;
+#[cfg(feature = "std")]
+use crate::multifile::Error;
 use crate::multifile::Result;
 #[cfg(not(feature = "std"))]
 use crate::no_std::*;
@@ -32,12 +39,13 @@ bitflags! {
 }
 
 /// Utility struct for handling Subfile data, for use with
-/// [`Multifile`](crate::multifile::Multifile) archives. Currently only for internal use.
+/// [`Multifile`](crate::multifile::Multifile) archives.
 ///
 /// For more details on the Multifile format, see the [module documentation](self#format).
 #[derive(Default, Debug)]
 pub struct Subfile {
-    pub(crate) offset: u32,
+    pub(crate) offset: u64,
+    pub(crate) data_length: u32,
     pub(crate) length: u32,
     pub(crate) flags: Flags,
     pub(crate) timestamp: u32,
@@ -55,7 +63,7 @@ impl Subfile {
     /// # Errors
     /// Returns [`EndOfFile`] if it tries to read out of bounds.
     pub(crate) fn load<T: ReadExt>(input: &mut T, version: Version) -> Result<Self> {
-        let offset = input.read_u32()?;
+        let offset = u64::from(input.read_u32()?);
         let data_length = input.read_u32()?;
         let flags = Flags::from_bits_truncate(input.read_u16()?);
 
@@ -75,25 +83,232 @@ impl Subfile {
             filename.push((255 - *c).into());
         }
 
-        Ok(Self { offset, length, flags, timestamp, filename })
+        Ok(Self { offset, data_length, length, flags, timestamp, filename })
+    }
+
+    /// Returns the path of the [`Subfile`] inside of the Multifile.
+    #[must_use]
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.filename
+    }
+
+    /// Returns the length of the [`Subfile`]'s contents, after decompression.
+    #[must_use]
+    #[inline]
+    pub const fn length(&self) -> u32 {
+        self.length
     }
 
-    /// Writes the [`Subfile`] data to disk, using the data from the associated [`Multifile`].
+    /// Returns the Unix timestamp of when the [`Subfile`] was last modified.
+    #[must_use]
+    #[inline]
+    pub const fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    /// Returns whether the [`Subfile`] is stored compressed.
+    #[must_use]
+    #[inline]
+    pub const fn is_compressed(&self) -> bool {
+        self.flags.contains(Flags::Compressed)
+    }
+
+    /// Returns whether the [`Subfile`] has been deleted from the Multifile, and should be ignored.
+    #[must_use]
+    #[inline]
+    pub const fn is_deleted(&self) -> bool {
+        self.flags.contains(Flags::Deleted)
+    }
+
+    /// Returns whether the [`Subfile`] is stored encrypted, which isn't supported yet.
+    #[must_use]
+    #[inline]
+    pub const fn is_encrypted(&self) -> bool {
+        self.flags.contains(Flags::Encrypted)
+    }
+
+    /// Writes the [`Subfile`] data to wherever `extraction` puts it, reading it from `data`, which is either
+    /// a slice of the associated [`Multifile`](crate::multifile::Multifile) or a [`SubfileReader`]. Nothing
+    /// is read during a dry run.
     ///
     /// # Errors
-    /// Returns an error if unable to create the necessary directories, or unable to create a file
-    /// to write to. See [`create_dir_all`](std::fs::create_dir_all) and [`write`](std::fs::write).
+    /// Returns an error if the filename is unsafe (see [`PathOptions`]), unable to create the necessary
+    /// directories, or unable to create a file to write to. See [`create_dir_all`](std::fs::create_dir_all)
+    /// and [`create`](std::fs::File::create). Returns [`InvalidData`](crate::multifile::Error::InvalidData)
+    /// if `data` can't be decompressed.
     #[cfg(feature = "std")]
     #[inline]
-    pub(crate) fn write_file<P: AsRef<Path>>(&mut self, data: &[u8], output: P) -> Result<()> {
-        let mut path = PathBuf::from(output.as_ref());
-        path.push(&self.filename);
+    pub(crate) fn write_file<R: Read>(
+        &self, mut data: R, extraction: &Extraction, options: &PathOptions,
+    ) -> Result<()> {
+        let Some(path) = extraction.prepare(options.sanitize(&self.filename)?, self.length.into())? else {
+            return Ok(());
+        };
 
         if let Some(dir) = path.parent() {
             std::fs::create_dir_all(dir)?;
         }
 
-        std::fs::write(path, data)?;
+        let mut file = std::fs::File::create(path)?;
+        std::io::copy(&mut data, &mut file).map_err(|error| match error.kind() {
+            std::io::ErrorKind::InvalidData => Error::InvalidData { path: self.filename.clone() },
+            _ => error.into(),
+        })?;
         Ok(())
     }
 }
+
+/// Streams the contents of a [`Subfile`], borrowing the data from its
+/// [`Multifile`](crate::multifile::Multifile). Created using
+/// [`Multifile::reader`](crate::multifile::Multifile::reader).
+///
+/// Compressed Subfiles are decompressed on the fly. Seeking forwards decompresses up to the new position,
+/// while seeking backwards has to restart decompression from the beginning, so reading in order is fastest.
+#[cfg(feature = "std")]
+pub struct SubfileReader<'a> {
+    data: &'a [u8],
+    length: u64,
+    position: u64,
+    inflater: Option<Inflater>,
+}
+
+/// Decompression state for a compressed [`SubfileReader`].
+#[cfg(feature = "std")]
+struct Inflater {
+    state: Box<InflateState>,
+    consumed: usize,
+}
+
+#[cfg(feature = "std")]
+impl<'a> SubfileReader<'a> {
+    /// Creates a new reader over the stored data of a [`Subfile`].
+    #[inline]
+    pub(crate) fn new(data: &'a [u8], subfile: &Subfile) -> Self {
+        let inflater = subfile
+            .is_compressed()
+            .then(|| Inflater { state: InflateState::new_boxed(DataFormat::Zlib), consumed: 0 });
+        Self { data, length: subfile.length.into(), position: 0, inflater }
+    }
+
+    /// Returns the length of the [`Subfile`]'s contents, after decompression.
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> u64 {
+        self.length
+    }
+
+    /// Returns whether the [`Subfile`] is empty.
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns whether the [`Subfile`] is being decompressed on the fly.
+    #[must_use]
+    #[inline]
+    pub const fn is_compressed(&self) -> bool {
+        self.inflater.is_some()
+    }
+
+    /// Returns the contents of the [`Subfile`] directly from the archive's buffer, or [`None`] if it's
+    /// compressed and has to be read instead.
+    #[must_use]
+    #[inline]
+    pub const fn as_slice(&self) -> Option<&'a [u8]> {
+        match self.inflater {
+            Some(_) => None,
+            None => Some(self.data),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Debug for SubfileReader<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SubfileReader")
+            .field("length", &self.length)
+            .field("position", &self.position)
+            .field("compressed", &self.is_compressed())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Read for SubfileReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // Never return more than the stored length, even if the compressed stream has more
+        let remaining = usize::try_from(self.length.saturating_sub(self.position)).unwrap_or(usize::MAX);
+        let buf_len = buf.len().min(remaining);
+        let buf = &mut buf[..buf_len];
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let written = match &mut self.inflater {
+            None => {
+                let start = self.position as usize;
+                let data =
+                    self.data.get(start..start + buf.len()).ok_or(std::io::ErrorKind::UnexpectedEof)?;
+                buf.copy_from_slice(data);
+                buf.len()
+            }
+            Some(inflater) => loop {
+                let input = &self.data[inflater.consumed..];
+                let result = inflate(&mut inflater.state, input, buf, MZFlush::None);
+                inflater.consumed += result.bytes_consumed;
+                match result.status {
+                    Ok(_) if result.bytes_written != 0 => break result.bytes_written,
+                    Ok(MZStatus::StreamEnd) => return Ok(0),
+                    Ok(_) if result.bytes_consumed != 0 => continue,
+                    _ => {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "Invalid or truncated compressed Subfile data",
+                        ))
+                    }
+                }
+            },
+        };
+        self.position += written as u64;
+        Ok(written)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Seek for SubfileReader<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.length.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        }
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Tried to seek to a negative position",
+            )
+        })?;
+
+        if let Some(inflater) = &mut self.inflater {
+            // Decompressed data can't be revisited, so start over from the beginning
+            if target < self.position {
+                inflater.state.reset(DataFormat::Zlib);
+                inflater.consumed = 0;
+                self.position = 0;
+            }
+        }
+        if self.inflater.is_some() {
+            let mut scratch = [0u8; 0x1000];
+            while self.position < target.min(self.length) {
+                let size = usize::try_from(target - self.position).unwrap_or(usize::MAX).min(scratch.len());
+                if self.read(&mut scratch[..size])? == 0 {
+                    break;
+                }
+            }
+        }
+        self.position = target;
+        Ok(target)
+    }
+}
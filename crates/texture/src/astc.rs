@@ -0,0 +1,746 @@
+//! Adds support for decoding Adaptive Scalable Texture Compression (ASTC), as used on the Nintendo Switch.
+//!
+//! # Format
+//! Every block is 16 bytes, but can cover anywhere from 4x4 to 12x12 pixels, which is fixed per texture.
+//! Blocks are stored in row-major order, and images whose dimensions aren't a multiple of the block size are
+//! padded out to the next full block.
+//!
+//! Each block is a little-endian 128-bit value containing:
+//! * The block mode, which describes the weight grid size, weight precision, and whether there's a second
+//!   weight plane for one of the color channels.
+//! * Up to four partitions, each with its own pair of color endpoints, where the partition that each pixel
+//!   belongs to is chosen by a hash of the pixel position and a 10-bit seed.
+//! * The color endpoints and weights, which are packed using Bounded Integer Sequence Encoding (BISE). The
+//!   weights are stored backwards from the end of the block.
+//!
+//! Only the LDR profile is supported, any blocks that use HDR endpoints (or are otherwise invalid) are
+//! decoded to the error color (magenta), which matches the behavior of hardware decoders.
+//!
+//! # Usage
+//! Decoding outputs tightly packed RGBA8 data.
+//! ```
+//! use orthrus_texture::astc;
+//!
+//! // A single "void-extent" block, which is a constant color (stored as UNORM16)
+//! let mut block = [0xFC, 0xFD, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0, 0, 0, 0, 0];
+//! block[8..].copy_from_slice(&[0x00, 0xFF, 0x00, 0x80, 0x00, 0x00, 0x00, 0xFF]);
+//!
+//! let decoded = astc::decode(&block, 6, 6, 6, 6)?;
+//! assert_eq!(&decoded[..4], &[0xFF, 0x80, 0x00, 0xFF]);
+//! # Ok::<(), astc::Error>(())
+//! ```
+
+use snafu::prelude::*;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
+/// Error conditions for when decoding ASTC textures.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown if the input is too small for the given dimensions and block size.
+    #[snafu(display("Not enough data! Expected {expected:#X} bytes, got {actual:#X}."))]
+    InvalidSize { expected: usize, actual: usize },
+
+    /// Thrown if the block size isn't one that ASTC supports.
+    #[snafu(display("Invalid block size {width}x{height}!"))]
+    InvalidBlockSize { width: u32, height: u32 },
+}
+type Result<T> = core::result::Result<T, Error>;
+
+/// Color used for blocks that are invalid or unsupported.
+const ERROR_COLOR: [u8; 4] = [0xFF, 0x00, 0xFF, 0xFF];
+
+/// All valid BISE ranges, as `(trits, quints, bits)`. The number of levels is `3^trits * 5^quints * 2^bits`.
+const RANGES: [(u32, u32, u32); 21] = [
+    (0, 0, 1),
+    (1, 0, 0),
+    (0, 0, 2),
+    (0, 1, 0),
+    (1, 0, 1),
+    (0, 0, 3),
+    (0, 1, 1),
+    (1, 0, 2),
+    (0, 0, 4),
+    (0, 1, 2),
+    (1, 0, 3),
+    (0, 0, 5),
+    (0, 1, 3),
+    (1, 0, 4),
+    (0, 0, 6),
+    (0, 1, 4),
+    (1, 0, 5),
+    (0, 0, 7),
+    (0, 1, 5),
+    (1, 0, 6),
+    (0, 0, 8),
+];
+
+/// Returns the number of bytes needed to store an image with the given dimensions and block size.
+#[must_use]
+#[inline]
+pub const fn data_size(width: u32, height: u32, block_width: u32, block_height: u32) -> usize {
+    width.div_ceil(block_width) as usize * height.div_ceil(block_height) as usize * 16
+}
+
+#[inline]
+const fn bits(block: u128, start: u32, count: u32) -> u32 {
+    ((block >> start) & ((1 << count) - 1)) as u32
+}
+
+/// Returns the number of bits needed to store `count` values in the given BISE range.
+#[inline]
+const fn ise_size(count: u32, range: usize) -> u32 {
+    let (trits, quints, bits) = RANGES[range];
+    count * bits + (count * 8 * trits).div_ceil(5) + (count * 7 * quints).div_ceil(3)
+}
+
+/// Unpacks the five trits stored in an 8-bit value.
+fn decode_trits(packed: u32) -> [u32; 5] {
+    let bit = |n: u32| (packed >> n) & 1;
+    let (c, t4, t3);
+    match bits(packed.into(), 2, 3) == 7 {
+        true => {
+            c = (bits(packed.into(), 5, 3) << 2) | bits(packed.into(), 0, 2);
+            (t4, t3) = (2, 2);
+        }
+        false => {
+            c = packed & 0x1F;
+            match bits(packed.into(), 5, 2) == 3 {
+                true => (t4, t3) = (2, bit(7)),
+                false => (t4, t3) = (bit(7), bits(packed.into(), 5, 2)),
+            }
+        }
+    }
+
+    let cbit = |n: u32| (c >> n) & 1;
+    let (t2, t1, t0);
+    if c & 3 == 3 {
+        (t2, t1) = (2, cbit(4));
+        t0 = (cbit(3) << 1) | (cbit(2) & !cbit(3) & 1);
+    } else if (c >> 2) & 3 == 3 {
+        (t2, t1) = (2, 2);
+        t0 = c & 3;
+    } else {
+        (t2, t1) = (cbit(4), (c >> 2) & 3);
+        t0 = (cbit(1) << 1) | (cbit(0) & !cbit(1) & 1);
+    }
+    [t0, t1, t2, t3, t4]
+}
+
+/// Unpacks the three quints stored in a 7-bit value.
+fn decode_quints(packed: u32) -> [u32; 3] {
+    let bit = |n: u32| (packed >> n) & 1;
+    if (packed >> 1) & 3 == 3 && (packed >> 5) & 3 == 0 {
+        let q2 = (bit(0) << 2) | ((bit(4) & !bit(0) & 1) << 1) | (bit(3) & !bit(0) & 1);
+        return [4, 4, q2];
+    }
+
+    let (q2, c) = match (packed >> 1) & 3 == 3 {
+        true => (
+            4,
+            (((packed >> 3) & 3) << 3) | ((!(packed >> 5) & 3) << 1) | bit(0),
+        ),
+        false => ((packed >> 5) & 3, packed & 0x1F),
+    };
+    match c & 7 == 5 {
+        true => [(c >> 3) & 3, 4, q2],
+        false => [c & 7, (c >> 3) & 3, q2],
+    }
+}
+
+/// Decodes `count` values in the given BISE range, starting at bit `start`.
+fn decode_ise(block: u128, start: u32, count: usize, range: usize) -> Vec<u32> {
+    let (trits, quints, bit_count) = RANGES[range];
+    let mut values = Vec::with_capacity(count + 4);
+    // Any bits past the end of the sequence (from a partial group of trits/quints) are treated as zero
+    let end = (start + ise_size(count as u32, range)).min(128);
+    let mut position = start;
+    let mut read = |length: u32| {
+        let value = match position < end {
+            true => bits(block, position, length.min(end - position)),
+            false => 0,
+        };
+        position += length;
+        value
+    };
+
+    while values.len() < count {
+        if trits != 0 {
+            // Five values, with the 8 trit bits interleaved between them
+            let mut m = [0; 5];
+            let mut packed = 0;
+            for (n, (value, trit_bits)) in m.iter_mut().zip([2, 2, 1, 2, 1]).enumerate() {
+                *value = read(bit_count);
+                let shift = [0, 2, 4, 5, 7][n];
+                packed |= read(trit_bits) << shift;
+            }
+            for (value, trit) in m.into_iter().zip(decode_trits(packed)) {
+                values.push((trit << bit_count) | value);
+            }
+        } else if quints != 0 {
+            // Three values, with the 7 quint bits interleaved between them
+            let mut m = [0; 3];
+            let mut packed = 0;
+            for (n, (value, quint_bits)) in m.iter_mut().zip([3, 2, 2]).enumerate() {
+                *value = read(bit_count);
+                let shift = [0, 3, 5][n];
+                packed |= read(quint_bits) << shift;
+            }
+            for (value, quint) in m.into_iter().zip(decode_quints(packed)) {
+                values.push((quint << bit_count) | value);
+            }
+        } else {
+            values.push(read(bit_count));
+        }
+    }
+    values.truncate(count);
+    values
+}
+
+/// Converts a color endpoint value in the given BISE range to 0-255.
+fn unquantize_color(value: u32, range: usize) -> u8 {
+    let (trits, quints, bit_count) = RANGES[range];
+    if trits == 0 && quints == 0 {
+        // Replicate the bits until we have all 8
+        let mut result = 0;
+        let mut shift = 8i32 - bit_count as i32;
+        while shift > -(bit_count as i32) {
+            result |= match shift >= 0 {
+                true => value << shift,
+                false => value >> -shift,
+            };
+            shift -= bit_count as i32;
+        }
+        return result as u8;
+    }
+
+    let d = value >> bit_count;
+    let m = value & ((1 << bit_count) - 1);
+    let a = match m & 1 {
+        1 => 0x1FF,
+        _ => 0,
+    };
+    let (b, c) = match (trits, bit_count) {
+        (1, 0) => return (d * 0xFF / 2) as u8,
+        (0, 0) => return (d * 0xFF / 4) as u8,
+        (1, 1) => (0, 204),
+        (0, 1) => (0, 113),
+        (1, 2) => {
+            let b = (m >> 1) & 1;
+            ((b << 8) | (b << 4) | (b << 2) | (b << 1), 93)
+        }
+        (0, 2) => {
+            let b = (m >> 1) & 1;
+            ((b << 8) | (b << 3) | (b << 2), 54)
+        }
+        (1, 3) => {
+            let cb = (m >> 1) & 3;
+            ((cb << 7) | (cb << 2) | cb, 44)
+        }
+        (0, 3) => {
+            let cb = (m >> 1) & 3;
+            ((cb << 7) | (cb << 1) | (cb >> 1), 26)
+        }
+        (1, 4) => {
+            let dcb = (m >> 1) & 7;
+            ((dcb << 6) | dcb, 22)
+        }
+        (0, 4) => {
+            let dcb = (m >> 1) & 7;
+            ((dcb << 6) | (dcb >> 1), 13)
+        }
+        (1, 5) => {
+            let edcb = (m >> 1) & 0xF;
+            ((edcb << 5) | (edcb >> 2), 11)
+        }
+        (0, 5) => {
+            let edcb = (m >> 1) & 0xF;
+            ((edcb << 5) | (edcb >> 3), 6)
+        }
+        _ => {
+            let fedcb = (m >> 1) & 0x1F;
+            ((fedcb << 4) | (fedcb >> 4), 5)
+        }
+    };
+    let t = ((d * c + b) ^ a) & 0x1FF;
+    ((a & 0x80) | (t >> 2)) as u8
+}
+
+/// Converts a weight in the given BISE range to 0-64.
+fn unquantize_weight(value: u32, range: usize) -> u32 {
+    let (trits, quints, bit_count) = RANGES[range];
+    let result = match (trits, quints, bit_count) {
+        (0, 0, _) => {
+            // Replicate the bits until we have 6
+            let mut result = 0;
+            let mut shift = 6i32 - bit_count as i32;
+            while shift > -(bit_count as i32) {
+                result |= match shift >= 0 {
+                    true => value << shift,
+                    false => value >> -shift,
+                };
+                shift -= bit_count as i32;
+            }
+            result & 0x3F
+        }
+        (1, 0, 0) => return value * 32,
+        (0, 1, 0) => return value * 16,
+        _ => {
+            let d = value >> bit_count;
+            let m = value & ((1 << bit_count) - 1);
+            let a = match m & 1 {
+                1 => 0x7F,
+                _ => 0,
+            };
+            let (b, c) = match (trits, bit_count) {
+                (1, 1) => (0, 50),
+                (0, 1) => (0, 28),
+                (1, 2) => {
+                    let b = (m >> 1) & 1;
+                    ((b << 6) | (b << 2) | b, 23)
+                }
+                (0, 2) => {
+                    let b = (m >> 1) & 1;
+                    ((b << 6) | (b << 1), 13)
+                }
+                _ => {
+                    let cb = (m >> 1) & 3;
+                    ((cb << 5) | cb, 11)
+                }
+            };
+            let t = ((d * c + b) ^ a) & 0x7F;
+            (a & 0x20) | (t >> 2)
+        }
+    };
+    match result > 32 {
+        true => result + 1,
+        false => result,
+    }
+}
+
+/// Hashes the partition seed, as defined by the specification.
+const fn hash52(mut p: u32) -> u32 {
+    p ^= p >> 15;
+    p = p.wrapping_sub(p << 17);
+    p = p.wrapping_add(p << 7);
+    p = p.wrapping_add(p << 4);
+    p ^= p >> 5;
+    p = p.wrapping_add(p << 16);
+    p ^= p >> 7;
+    p ^= p >> 3;
+    p ^= p << 6;
+    p ^= p >> 17;
+    p
+}
+
+/// Returns which partition the pixel at `(x, y)` belongs to.
+fn select_partition(seed: u32, mut x: u32, mut y: u32, partitions: u32, small_block: bool) -> usize {
+    if small_block {
+        x <<= 1;
+        y <<= 1;
+    }
+    let seed = seed + (partitions - 1) * 1024;
+    let random = hash52(seed);
+
+    let mut seeds = [
+        random & 0xF,
+        (random >> 4) & 0xF,
+        (random >> 8) & 0xF,
+        (random >> 12) & 0xF,
+        (random >> 16) & 0xF,
+        (random >> 20) & 0xF,
+        (random >> 24) & 0xF,
+        (random >> 28) & 0xF,
+    ];
+    for seed in &mut seeds {
+        *seed *= *seed;
+    }
+
+    let (shift1, shift2) = match seed & 1 {
+        1 => (
+            if seed & 2 != 0 { 4 } else { 5 },
+            if partitions == 3 { 6 } else { 5 },
+        ),
+        _ => (
+            if partitions == 3 { 6 } else { 5 },
+            if seed & 2 != 0 { 4 } else { 5 },
+        ),
+    };
+    for (n, seed) in seeds.iter_mut().enumerate() {
+        *seed >>= match n % 2 {
+            0 => shift1,
+            _ => shift2,
+        };
+    }
+
+    // The Z terms are always zero for 2D textures, so they're skipped here
+    let a = (seeds[0] * x + seeds[1] * y + (random >> 14)) & 0x3F;
+    let b = (seeds[2] * x + seeds[3] * y + (random >> 10)) & 0x3F;
+    let c = match partitions < 3 {
+        true => 0,
+        false => (seeds[4] * x + seeds[5] * y + (random >> 6)) & 0x3F,
+    };
+    let d = match partitions < 4 {
+        true => 0,
+        false => (seeds[6] * x + seeds[7] * y + (random >> 2)) & 0x3F,
+    };
+
+    if a >= b && a >= c && a >= d {
+        0
+    } else if b >= c && b >= d {
+        1
+    } else if c >= d {
+        2
+    } else {
+        3
+    }
+}
+
+/// Moves bits from `b` into `a`, used by the base+offset endpoint modes.
+#[inline]
+const fn bit_transfer_signed(a: i32, b: i32) -> (i32, i32) {
+    let b = (b >> 1) | (a & 0x80);
+    let mut a = (a >> 1) & 0x3F;
+    if a & 0x20 != 0 {
+        a -= 0x40;
+    }
+    (a, b)
+}
+
+#[inline]
+fn blue_contract(color: [i32; 4]) -> [i32; 4] {
+    [
+        (color[0] + color[2]) >> 1,
+        (color[1] + color[2]) >> 1,
+        color[2],
+        color[3],
+    ]
+}
+
+/// Decodes a pair of color endpoints, returning `None` for HDR or reserved modes.
+fn decode_endpoints(mode: u32, v: &[i32]) -> Option<[[u8; 4]; 2]> {
+    let (e0, e1) = match mode {
+        0 => ([v[0], v[0], v[0], 0xFF], [v[1], v[1], v[1], 0xFF]),
+        1 => {
+            let l0 = (v[0] >> 2) | (v[1] & 0xC0);
+            let l1 = l0 + (v[1] & 0x3F);
+            ([l0, l0, l0, 0xFF], [l1, l1, l1, 0xFF])
+        }
+        4 => ([v[0], v[0], v[0], v[2]], [v[1], v[1], v[1], v[3]]),
+        5 => {
+            let (b0, a0) = bit_transfer_signed(v[1], v[0]);
+            let (b2, a2) = bit_transfer_signed(v[3], v[2]);
+            ([a0, a0, a0, a2], [a0 + b0, a0 + b0, a0 + b0, a2 + b2])
+        }
+        6 | 10 => {
+            let alpha = match mode {
+                10 => (v[4], v[5]),
+                _ => (0xFF, 0xFF),
+            };
+            (
+                [
+                    (v[0] * v[3]) >> 8,
+                    (v[1] * v[3]) >> 8,
+                    (v[2] * v[3]) >> 8,
+                    alpha.0,
+                ],
+                [v[0], v[1], v[2], alpha.1],
+            )
+        }
+        8 | 12 => {
+            let alpha = match mode {
+                12 => (v[6], v[7]),
+                _ => (0xFF, 0xFF),
+            };
+            match v[1] + v[3] + v[5] >= v[0] + v[2] + v[4] {
+                true => ([v[0], v[2], v[4], alpha.0], [v[1], v[3], v[5], alpha.1]),
+                false => (
+                    blue_contract([v[1], v[3], v[5], alpha.1]),
+                    blue_contract([v[0], v[2], v[4], alpha.0]),
+                ),
+            }
+        }
+        9 | 13 => {
+            let (b0, a0) = bit_transfer_signed(v[1], v[0]);
+            let (b1, a1) = bit_transfer_signed(v[3], v[2]);
+            let (b2, a2) = bit_transfer_signed(v[5], v[4]);
+            let (b3, a3) = match mode {
+                13 => bit_transfer_signed(v[7], v[6]),
+                _ => (0, 0xFF),
+            };
+            match b0 + b1 + b2 >= 0 {
+                true => ([a0, a1, a2, a3], [a0 + b0, a1 + b1, a2 + b2, a3 + b3]),
+                false => (
+                    blue_contract([a0 + b0, a1 + b1, a2 + b2, a3 + b3]),
+                    blue_contract([a0, a1, a2, a3]),
+                ),
+            }
+        }
+        _ => return None,
+    };
+    Some([
+        e0.map(|c| c.clamp(0, 0xFF) as u8),
+        e1.map(|c| c.clamp(0, 0xFF) as u8),
+    ])
+}
+
+/// Weight grid parameters decoded from the block mode.
+struct BlockMode {
+    width: u32,
+    height: u32,
+    dual_plane: bool,
+    weight_range: usize,
+}
+
+/// Decodes the 11-bit block mode, returning `None` for reserved modes.
+fn decode_block_mode(mode: u32) -> Option<BlockMode> {
+    let bit = |n: u32| (mode >> n) & 1;
+    let a = (mode >> 5) & 3;
+    let b = (mode >> 7) & 3;
+    let (mut high_precision, mut dual_plane) = (bit(9) == 1, bit(10) == 1);
+
+    let (range, width, height) = match mode & 3 {
+        0 => {
+            let range = ((mode >> 1) & 6) | bit(4);
+            let (width, height) = match (mode >> 5) & 0xF {
+                0..=3 => (12, a + 2),
+                4..=7 => (a + 2, 12),
+                0xC => (6, 10),
+                0xD => (10, 6),
+                8..=0xB => {
+                    (high_precision, dual_plane) = (false, false);
+                    (a + 6, ((mode >> 9) & 3) + 6)
+                }
+                _ => return None,
+            };
+            (range, width, height)
+        }
+        _ => {
+            let range = ((mode & 3) << 1) | bit(4);
+            let (width, height) = match (mode >> 2) & 3 {
+                0 => (b + 4, a + 2),
+                1 => (b + 8, a + 2),
+                2 => (a + 2, b + 8),
+                _ => match bit(8) {
+                    0 => (a + 2, (b & 1) + 6),
+                    _ => ((b & 1) + 2, a + 2),
+                },
+            };
+            (range, width, height)
+        }
+    };
+    if range < 2 {
+        return None;
+    }
+
+    // The range bits index into the weight ranges, with high precision selecting the upper half
+    let weight_range = match high_precision {
+        false => [0, 1, 2, 3, 4, 5][range as usize - 2],
+        true => [6, 7, 8, 9, 10, 11][range as usize - 2],
+    };
+    Some(BlockMode { width, height, dual_plane, weight_range })
+}
+
+/// Decodes a single block into RGBA8 pixels, returning `None` if it should use the error color.
+fn decode_block(block: u128, block_width: u32, block_height: u32) -> Option<Vec<[u8; 4]>> {
+    let texel_count = (block_width * block_height) as usize;
+
+    // Void-extent blocks are a single constant color
+    if bits(block, 0, 9) == 0x1FC {
+        if bits(block, 9, 1) == 1 {
+            return None;
+        }
+        let color: [u8; 4] = core::array::from_fn(|n| (bits(block, 64 + n as u32 * 16, 16) >> 8) as u8);
+        return Some(vec![color; texel_count]);
+    }
+
+    let mode = decode_block_mode(bits(block, 0, 11))?;
+    let weights_per_plane = mode.width * mode.height;
+    let weight_count = weights_per_plane * (u32::from(mode.dual_plane) + 1);
+    let weight_bits = ise_size(weight_count, mode.weight_range);
+    if weight_count > 64 || !(24..=96).contains(&weight_bits) {
+        return None;
+    }
+    if mode.width > block_width || mode.height > block_height {
+        return None;
+    }
+
+    let partitions = bits(block, 11, 2) + 1;
+    if partitions == 4 && mode.dual_plane {
+        return None;
+    }
+
+    // Figure out the color endpoint modes, and where the color data ends
+    let mut below_weights = 128 - weight_bits;
+    let mut modes = [0u32; 4];
+    let (color_start, seed) = match partitions {
+        1 => {
+            modes[0] = bits(block, 13, 4);
+            (17, 0)
+        }
+        _ => {
+            let seed = bits(block, 13, 10);
+            let selector = bits(block, 23, 2);
+            match selector {
+                0 => modes = [bits(block, 25, 4); 4],
+                _ => {
+                    // The remaining bits are stored directly below the weights
+                    let extra_bits = 3 * partitions - 4;
+                    below_weights -= extra_bits;
+                    let encoded = bits(block, 23, 6) | (bits(block, below_weights, extra_bits) << 6);
+                    let base_class = selector - 1;
+                    for (n, mode) in modes.iter_mut().take(partitions as usize).enumerate() {
+                        let class = base_class + ((encoded >> (2 + n)) & 1);
+                        let low = (encoded >> (2 + partitions as usize + n * 2)) & 3;
+                        *mode = (class << 2) | low;
+                    }
+                }
+            }
+            (29, seed)
+        }
+    };
+    let modes = &modes[..partitions as usize];
+
+    // The dual plane component selector is stored directly below the weights (and any extra mode bits)
+    let plane_component = match mode.dual_plane {
+        true => {
+            below_weights -= 2;
+            Some(bits(block, below_weights, 2) as usize)
+        }
+        false => None,
+    };
+
+    // Pick the highest precision that fits all of the color values
+    let value_count: u32 = modes.iter().map(|mode| ((mode >> 2) + 1) * 2).sum();
+    if value_count > 18 || below_weights <= color_start {
+        return None;
+    }
+    let available = below_weights - color_start;
+    let color_range = (4..RANGES.len()).rev().find(|&range| ise_size(value_count, range) <= available)?;
+    let values: Vec<i32> = decode_ise(block, color_start, value_count as usize, color_range)
+        .into_iter()
+        .map(|value| i32::from(unquantize_color(value, color_range)))
+        .collect();
+
+    let mut endpoints = Vec::with_capacity(modes.len());
+    let mut offset = 0;
+    for &endpoint_mode in modes {
+        let count = (((endpoint_mode >> 2) + 1) * 2) as usize;
+        endpoints.push(decode_endpoints(endpoint_mode, &values[offset..offset + count])?);
+        offset += count;
+    }
+
+    // Weights are stored in reverse bit order from the end of the block
+    let weights: Vec<u32> = decode_ise(block.reverse_bits(), 0, weight_count as usize, mode.weight_range)
+        .into_iter()
+        .map(|weight| unquantize_weight(weight, mode.weight_range))
+        .collect();
+    let planes = u32::from(mode.dual_plane) + 1;
+
+    // Scale the weight grid up to the block size, using bilinear interpolation
+    let scale_x = (1024 + block_width / 2) / (block_width - 1);
+    let scale_y = (1024 + block_height / 2) / (block_height - 1);
+    let infill = |x: u32, y: u32, plane: u32| -> u32 {
+        let gs = (scale_x * x * (mode.width - 1) + 32) >> 6;
+        let gt = (scale_y * y * (mode.height - 1) + 32) >> 6;
+        let (js, fs) = (gs >> 4, gs & 0xF);
+        let (jt, ft) = (gt >> 4, gt & 0xF);
+        let w11 = (fs * ft + 8) >> 4;
+        let w10 = ft - w11;
+        let w01 = fs - w11;
+        let w00 = 16 - fs - ft + w11;
+
+        let weight = |grid_x: u32, grid_y: u32| -> u32 {
+            let index = (grid_y * mode.width + grid_x) * planes + plane;
+            weights.get(index as usize).copied().unwrap_or(0)
+        };
+        let p00 = weight(js, jt);
+        let p01 = match w01 {
+            0 => 0,
+            _ => weight(js + 1, jt),
+        };
+        let p10 = match w10 {
+            0 => 0,
+            _ => weight(js, jt + 1),
+        };
+        let p11 = match w11 {
+            0 => 0,
+            _ => weight(js + 1, jt + 1),
+        };
+        (p00 * w00 + p01 * w01 + p10 * w10 + p11 * w11 + 8) >> 4
+    };
+
+    let small_block = texel_count < 31;
+    let mut pixels = Vec::with_capacity(texel_count);
+    for y in 0..block_height {
+        for x in 0..block_width {
+            let partition = match partitions {
+                1 => 0,
+                _ => select_partition(seed, x, y, partitions, small_block),
+            };
+            let [e0, e1] = endpoints[partition];
+            let weight0 = infill(x, y, 0);
+            let weight1 = match mode.dual_plane {
+                true => infill(x, y, 1),
+                false => weight0,
+            };
+
+            let color = core::array::from_fn(|n| {
+                let weight = match plane_component == Some(n) {
+                    true => weight1,
+                    false => weight0,
+                };
+                // Endpoints are expanded to 16 bits before interpolating, and the top 8 bits are the result
+                let (c0, c1) = (u32::from(e0[n]) * 0x101, u32::from(e1[n]) * 0x101);
+                (((c0 * (64 - weight) + c1 * weight + 32) >> 6) >> 8) as u8
+            });
+            pixels.push(color);
+        }
+    }
+    Some(pixels)
+}
+
+/// Decodes an ASTC texture into RGBA8 data.
+///
+/// # Errors
+/// Returns [`InvalidBlockSize`](Error::InvalidBlockSize) if the block size isn't between 4x4 and 12x12, or
+/// [`InvalidSize`](Error::InvalidSize) if there's not enough data for the given dimensions.
+pub fn decode(
+    data: &[u8], width: u32, height: u32, block_width: u32, block_height: u32,
+) -> Result<Box<[u8]>> {
+    ensure!(
+        (4..=12).contains(&block_width) && (4..=12).contains(&block_height),
+        InvalidBlockSizeSnafu { width: block_width, height: block_height }
+    );
+    let expected = data_size(width, height, block_width, block_height);
+    ensure!(
+        data.len() >= expected,
+        InvalidSizeSnafu { expected, actual: data.len() }
+    );
+
+    let mut output = vec![0u8; width as usize * height as usize * 4];
+    let blocks_per_row = width.div_ceil(block_width);
+
+    for (index, block) in data[..expected].chunks_exact(16).enumerate() {
+        let block = u128::from_le_bytes(block.try_into().unwrap());
+        let pixels = decode_block(block, block_width, block_height);
+
+        let x = (index as u32 % blocks_per_row) * block_width;
+        let y = (index as u32 / blocks_per_row) * block_height;
+        for n in 0..block_width * block_height {
+            let (pixel_x, pixel_y) = (x + n % block_width, y + n / block_width);
+            if pixel_x < width && pixel_y < height {
+                let color = match &pixels {
+                    Some(pixels) => pixels[n as usize],
+                    None => ERROR_COLOR,
+                };
+                let offset = (pixel_y * width + pixel_x) as usize * 4;
+                output[offset..offset + 4].copy_from_slice(&color);
+            }
+        }
+    }
+
+    Ok(output.into_boxed_slice())
+}
@@ -0,0 +1,261 @@
+//! Adds support for the BCn block compression formats (also known as S3TC/DXTn and RGTC), as used on PC and
+//! the Nintendo Switch.
+//!
+//! # Format
+//! All formats store 4x4 blocks in row-major order, and images whose dimensions aren't a multiple of 4 are
+//! padded out to the next full block. All values are little-endian.
+//!
+//! | Format | Block Size | Description                                                            |
+//! |--------|------------|------------------------------------------------------------------------|
+//! | BC1    | 8          | Color block, with optional 1-bit alpha (DXT1)                          |
+//! | BC2    | 16         | Explicit 4-bit alpha, followed by a color block (DXT3)                 |
+//! | BC3    | 16         | Interpolated alpha block, followed by a color block (DXT5)             |
+//! | BC4    | 8          | A single interpolated channel, decoded to red (ATI1/RGTC1)             |
+//! | BC5    | 16         | Two interpolated channels, decoded to red and green (ATI2/RGTC2)       |
+//!
+//! Color blocks store two RGB565 endpoints and a 2-bit index per pixel, while interpolated blocks store two
+//! 8-bit endpoints and a 3-bit index per pixel.
+//!
+//! Textures on the Switch are additionally [swizzled](crate::tegra), which needs to be undone before
+//! decoding.
+//!
+//! # Usage
+//! All decoding outputs, and all encoding takes, tightly packed RGBA8 data.
+//! ```
+//! use orthrus_texture::bc::{self, Format};
+//!
+//! let rgba = vec![0xFF; 8 * 8 * 4];
+//! let encoded = bc::encode(&rgba, 8, 8, Format::BC3)?;
+//! assert_eq!(encoded.len(), bc::data_size(8, 8, Format::BC3));
+//!
+//! let decoded = bc::decode(&encoded, 8, 8, Format::BC3)?;
+//! assert_eq!(&*decoded, &*rgba);
+//! # Ok::<(), bc::Error>(())
+//! ```
+
+use snafu::prelude::*;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+use crate::s3tc;
+
+/// Error conditions for when encoding or decoding BCn textures.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown if the input is too small for the given dimensions and format.
+    #[snafu(display("Not enough data! Expected {expected:#X} bytes, got {actual:#X}."))]
+    InvalidSize { expected: usize, actual: usize },
+}
+type Result<T> = core::result::Result<T, Error>;
+
+/// All supported BCn formats.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Format {
+    BC1,
+    BC2,
+    BC3,
+    BC4,
+    BC5,
+}
+
+impl Format {
+    /// Returns the size of a single 4x4 block, in bytes.
+    #[must_use]
+    #[inline]
+    pub const fn block_bytes(self) -> usize {
+        match self {
+            Self::BC1 | Self::BC4 => 8,
+            Self::BC2 | Self::BC3 | Self::BC5 => 16,
+        }
+    }
+}
+
+/// Returns the number of bytes needed to store an image with the given dimensions and format, including
+/// any padding needed to fill out the last row and column of blocks.
+#[must_use]
+#[inline]
+pub const fn data_size(width: u32, height: u32, format: Format) -> usize {
+    width.div_ceil(4) as usize * height.div_ceil(4) as usize * format.block_bytes()
+}
+
+/// Decodes a BC1 color block, where `four_color_only` is set for BC2/BC3.
+#[inline]
+fn decode_color_block(block: &[u8], four_color_only: bool) -> [[u8; 4]; 16] {
+    let color0 = u16::from_le_bytes([block[0], block[1]]);
+    let color1 = u16::from_le_bytes([block[2], block[3]]);
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+    s3tc::decode_block(
+        color0,
+        color1,
+        core::array::from_fn(|n| (indices >> (n * 2)) as u8),
+        four_color_only,
+    )
+}
+
+/// Builds the eight values that an interpolated (BC3 alpha/BC4/BC5) block selects from.
+fn channel_palette(value0: u8, value1: u8) -> [u8; 8] {
+    let (a, b) = (u32::from(value0), u32::from(value1));
+    let mut palette = [value0, value1, 0, 0, 0, 0, 0, 0xFF];
+    match value0 > value1 {
+        true => {
+            for n in 1..7 {
+                palette[n + 1] = (((7 - n as u32) * a + n as u32 * b + 3) / 7) as u8;
+            }
+        }
+        false => {
+            for n in 1..5 {
+                palette[n + 1] = (((5 - n as u32) * a + n as u32 * b + 2) / 5) as u8;
+            }
+        }
+    }
+    palette
+}
+
+/// Decodes an interpolated single-channel block.
+#[inline]
+fn decode_channel_block(block: &[u8]) -> [u8; 16] {
+    let palette = channel_palette(block[0], block[1]);
+    let mut bits = [0u8; 8];
+    bits[..6].copy_from_slice(&block[2..8]);
+    let indices = u64::from_le_bytes(bits);
+    core::array::from_fn(|n| palette[((indices >> (n * 3)) & 7) as usize])
+}
+
+/// Encodes 16 values into an interpolated single-channel block, always using the eight-value mode.
+fn encode_channel_block(values: [u8; 16], output: &mut [u8]) {
+    let max = values.iter().copied().max().unwrap_or(0);
+    let min = values.iter().copied().min().unwrap_or(0);
+    let palette = channel_palette(max, min);
+
+    let mut indices = 0u64;
+    for (n, value) in values.into_iter().enumerate() {
+        let index = (0..8)
+            .min_by_key(|&index| (i32::from(value) - i32::from(palette[index])).unsigned_abs())
+            .unwrap();
+        indices |= (index as u64) << (n * 3);
+    }
+    output[0] = max;
+    output[1] = min;
+    output[2..8].copy_from_slice(&indices.to_le_bytes()[..6]);
+}
+
+/// Decodes a BCn texture into RGBA8 data.
+///
+/// # Errors
+/// Returns [`InvalidSize`](Error::InvalidSize) if there's not enough data for the given dimensions.
+pub fn decode(data: &[u8], width: u32, height: u32, format: Format) -> Result<Box<[u8]>> {
+    let expected = data_size(width, height, format);
+    ensure!(
+        data.len() >= expected,
+        InvalidSizeSnafu { expected, actual: data.len() }
+    );
+
+    let mut output = vec![0u8; width as usize * height as usize * 4];
+    let blocks_per_row = width.div_ceil(4);
+
+    for (index, block) in data[..expected].chunks_exact(format.block_bytes()).enumerate() {
+        let pixels = match format {
+            Format::BC1 => decode_color_block(block, false),
+            Format::BC2 => {
+                let alpha = u64::from_le_bytes(block[..8].try_into().unwrap());
+                let mut pixels = decode_color_block(&block[8..], true);
+                for (n, pixel) in pixels.iter_mut().enumerate() {
+                    pixel[3] = ((alpha >> (n * 4)) & 0xF) as u8 * 0x11;
+                }
+                pixels
+            }
+            Format::BC3 => {
+                let alpha = decode_channel_block(&block[..8]);
+                let mut pixels = decode_color_block(&block[8..], true);
+                for (pixel, alpha) in pixels.iter_mut().zip(alpha) {
+                    pixel[3] = alpha;
+                }
+                pixels
+            }
+            Format::BC4 => decode_channel_block(block).map(|red| [red, 0, 0, 0xFF]),
+            Format::BC5 => {
+                let (red, green) = (
+                    decode_channel_block(&block[..8]),
+                    decode_channel_block(&block[8..]),
+                );
+                core::array::from_fn(|n| [red[n], green[n], 0, 0xFF])
+            }
+        };
+
+        let x = (index as u32 % blocks_per_row) * 4;
+        let y = (index as u32 / blocks_per_row) * 4;
+        for (n, color) in pixels.into_iter().enumerate() {
+            let (pixel_x, pixel_y) = (x + n as u32 % 4, y + n as u32 / 4);
+            if pixel_x < width && pixel_y < height {
+                let offset = (pixel_y * width + pixel_x) as usize * 4;
+                output[offset..offset + 4].copy_from_slice(&color);
+            }
+        }
+    }
+
+    Ok(output.into_boxed_slice())
+}
+
+/// Encodes RGBA8 data into a BCn texture.
+///
+/// BC1 uses 1-bit alpha for any pixel with alpha below 128, BC4 only encodes the red channel, and BC5 only
+/// encodes the red and green channels. Pixels outside of the image (when the dimensions aren't a multiple of
+/// 4) are filled in by clamping to the nearest edge.
+///
+/// # Errors
+/// Returns [`InvalidSize`](Error::InvalidSize) if there's not enough data for the given dimensions.
+pub fn encode(rgba: &[u8], width: u32, height: u32, format: Format) -> Result<Box<[u8]>> {
+    let expected = width as usize * height as usize * 4;
+    ensure!(
+        rgba.len() >= expected,
+        InvalidSizeSnafu { expected, actual: rgba.len() }
+    );
+
+    let pixel = |x: u32, y: u32| -> [u8; 4] {
+        let offset = (y.min(height - 1) * width + x.min(width - 1)) as usize * 4;
+        rgba[offset..offset + 4].try_into().unwrap()
+    };
+
+    let mut output = vec![0u8; data_size(width, height, format)];
+    let blocks_per_row = width.div_ceil(4);
+
+    for (index, block) in output.chunks_exact_mut(format.block_bytes()).enumerate() {
+        let x = (index as u32 % blocks_per_row) * 4;
+        let y = (index as u32 / blocks_per_row) * 4;
+        let pixels: [[u8; 4]; 16] = core::array::from_fn(|n| pixel(x + n as u32 % 4, y + n as u32 / 4));
+
+        let color_block = |output: &mut [u8], allow_transparent: bool| {
+            let (color0, color1, indices) = s3tc::encode_block(&pixels, allow_transparent);
+            let indices = indices
+                .iter()
+                .enumerate()
+                .fold(0u32, |bits, (n, &index)| bits | (u32::from(index) << (n * 2)));
+            output[0..2].copy_from_slice(&color0.to_le_bytes());
+            output[2..4].copy_from_slice(&color1.to_le_bytes());
+            output[4..8].copy_from_slice(&indices.to_le_bytes());
+        };
+
+        match format {
+            Format::BC1 => color_block(block, true),
+            Format::BC2 => {
+                let alpha = pixels.iter().enumerate().fold(0u64, |bits, (n, pixel)| {
+                    bits | (u64::from(pixel[3] >> 4) << (n * 4))
+                });
+                block[..8].copy_from_slice(&alpha.to_le_bytes());
+                color_block(&mut block[8..], false);
+            }
+            Format::BC3 => {
+                encode_channel_block(pixels.map(|pixel| pixel[3]), &mut block[..8]);
+                color_block(&mut block[8..], false);
+            }
+            Format::BC4 => encode_channel_block(pixels.map(|pixel| pixel[0]), block),
+            Format::BC5 => {
+                encode_channel_block(pixels.map(|pixel| pixel[0]), &mut block[..8]);
+                encode_channel_block(pixels.map(|pixel| pixel[1]), &mut block[8..]);
+            }
+        }
+    }
+
+    Ok(output.into_boxed_slice())
+}
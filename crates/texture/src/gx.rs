@@ -0,0 +1,387 @@
+//! Adds support for the texture formats used by the GameCube/Wii GPU (GX).
+//!
+//! # Format
+//! All GX textures are stored as a sequence of tiles ("blocks"), where each block is always 32 bytes (64 for
+//! RGBA32), and the pixels inside each block are stored in row-major order. Blocks are also stored in
+//! row-major order, and images whose dimensions aren't a multiple of the block size are padded out to the
+//! next full block.
+//!
+//! | Format | ID  | Bits Per Pixel | Block Size | Description                                          |
+//! |--------|-----|----------------|------------|------------------------------------------------------|
+//! | I4     | 0x0 | 4              | 8x8        | Intensity (grayscale)                                |
+//! | I8     | 0x1 | 8              | 8x4        | Intensity (grayscale)                                |
+//! | IA4    | 0x2 | 8              | 8x4        | 4-bit alpha (high nibble), 4-bit intensity           |
+//! | IA8    | 0x3 | 16             | 4x4        | 8-bit alpha (first byte), 8-bit intensity            |
+//! | RGB565 | 0x4 | 16             | 4x4        | Opaque color                                         |
+//! | RGB5A3 | 0x5 | 16             | 4x4        | Either opaque RGB555 (top bit set), or A3 RGB444     |
+//! | RGBA32 | 0x6 | 32             | 4x4        | Each block stores all AR pairs, followed by GB pairs |
+//! | C4     | 0x8 | 4              | 8x8        | 4-bit palette index                                  |
+//! | C8     | 0x9 | 8              | 8x4        | 8-bit palette index                                  |
+//! | C14X2  | 0xA | 16             | 4x4        | 14-bit palette index                                 |
+//! | CMPR   | 0xE | 4              | 8x8        | Four 4x4 S3TC/DXT1 sub-blocks, big-endian            |
+//!
+//! Palettes (TLUTs) are stored as big-endian 16-bit colors, in either IA8, RGB565, or RGB5A3.
+//!
+//! # Usage
+//! All decoding outputs, and all encoding takes, tightly packed RGBA8 data.
+//! ```
+//! use orthrus_texture::gx::{self, TextureFormat};
+//!
+//! let rgba = vec![0x80; 8 * 8 * 4];
+//! let encoded = gx::encode(&rgba, 8, 8, TextureFormat::RGB5A3)?;
+//! assert_eq!(encoded.len(), gx::data_size(8, 8, TextureFormat::RGB5A3));
+//!
+//! let decoded = gx::decode(&encoded, 8, 8, TextureFormat::RGB5A3, None)?;
+//! assert_eq!(decoded.len(), rgba.len());
+//! # Ok::<(), gx::Error>(())
+//! ```
+
+use num_enum::TryFromPrimitive;
+use snafu::prelude::*;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+use crate::s3tc::{self, decode_rgb565, encode_rgb565, expand5};
+
+/// Error conditions for when encoding or decoding GX textures.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown if the input is too small for the given dimensions and format.
+    #[snafu(display("Not enough data! Expected {expected:#X} bytes, got {actual:#X}."))]
+    InvalidSize { expected: usize, actual: usize },
+
+    /// Thrown if trying to decode a paletted format without providing a palette.
+    #[snafu(display("Tried to decode a paletted format without a palette!"))]
+    MissingPalette,
+
+    /// Thrown if trying to encode to a format that isn't supported.
+    #[snafu(display("Encoding to {format:?} is not supported!"))]
+    UnsupportedFormat { format: TextureFormat },
+}
+type Result<T> = core::result::Result<T, Error>;
+
+/// All texture formats supported by GX.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, TryFromPrimitive)]
+#[repr(u8)]
+pub enum TextureFormat {
+    I4 = 0x0,
+    I8 = 0x1,
+    IA4 = 0x2,
+    IA8 = 0x3,
+    RGB565 = 0x4,
+    RGB5A3 = 0x5,
+    RGBA32 = 0x6,
+    C4 = 0x8,
+    C8 = 0x9,
+    C14X2 = 0xA,
+    CMPR = 0xE,
+}
+
+impl TextureFormat {
+    /// Returns the width and height of a single block, in pixels.
+    #[must_use]
+    #[inline]
+    pub const fn block_size(self) -> (u32, u32) {
+        match self {
+            Self::I4 | Self::C4 | Self::CMPR => (8, 8),
+            Self::I8 | Self::IA4 | Self::C8 => (8, 4),
+            Self::IA8 | Self::RGB565 | Self::RGB5A3 | Self::RGBA32 | Self::C14X2 => (4, 4),
+        }
+    }
+
+    /// Returns the size of a single block, in bytes.
+    #[must_use]
+    #[inline]
+    pub const fn block_bytes(self) -> usize {
+        match self {
+            Self::RGBA32 => 64,
+            _ => 32,
+        }
+    }
+
+    /// Returns whether this format stores palette indices instead of colors.
+    #[must_use]
+    #[inline]
+    pub const fn is_paletted(self) -> bool {
+        matches!(self, Self::C4 | Self::C8 | Self::C14X2)
+    }
+}
+
+/// All palette formats supported by GX.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default, TryFromPrimitive)]
+#[repr(u8)]
+pub enum PaletteFormat {
+    #[default]
+    IA8 = 0x0,
+    RGB565 = 0x1,
+    RGB5A3 = 0x2,
+}
+
+/// Returns the number of bytes needed to store an image with the given dimensions and format, including
+/// any padding needed to fill out the last row and column of blocks.
+#[must_use]
+#[inline]
+pub const fn data_size(width: u32, height: u32, format: TextureFormat) -> usize {
+    let (block_width, block_height) = format.block_size();
+    let blocks = width.div_ceil(block_width) as usize * height.div_ceil(block_height) as usize;
+    blocks * format.block_bytes()
+}
+
+#[inline]
+const fn expand3(value: u16) -> u8 {
+    let value = value as u8;
+    (value << 5) | (value << 2) | (value >> 1)
+}
+
+#[inline]
+const fn expand4(value: u16) -> u8 {
+    (value as u8) * 0x11
+}
+
+#[inline]
+const fn decode_rgb5a3(value: u16) -> [u8; 4] {
+    match value & 0x8000 != 0 {
+        true => [
+            expand5((value >> 10) & 0x1F),
+            expand5((value >> 5) & 0x1F),
+            expand5(value & 0x1F),
+            0xFF,
+        ],
+        false => [
+            expand4((value >> 8) & 0xF),
+            expand4((value >> 4) & 0xF),
+            expand4(value & 0xF),
+            expand3((value >> 12) & 0x7),
+        ],
+    }
+}
+
+#[inline]
+const fn decode_ia8(value: u16) -> [u8; 4] {
+    let intensity = value as u8;
+    [intensity, intensity, intensity, (value >> 8) as u8]
+}
+
+#[inline]
+const fn encode_rgb5a3(color: [u8; 4]) -> u16 {
+    // If alpha would quantize to fully opaque, we can use the higher-precision RGB555 mode instead
+    match color[3] >= 0xE0 {
+        true => {
+            0x8000 | ((color[0] as u16 >> 3) << 10) | ((color[1] as u16 >> 3) << 5) | (color[2] as u16 >> 3)
+        }
+        false => {
+            ((color[3] as u16 >> 5) << 12)
+                | ((color[0] as u16 >> 4) << 8)
+                | ((color[1] as u16 >> 4) << 4)
+                | (color[2] as u16 >> 4)
+        }
+    }
+}
+
+/// Converts a color to grayscale, using the ITU-R BT.601 weights.
+#[inline]
+const fn intensity(color: [u8; 4]) -> u8 {
+    ((color[0] as u32 * 299 + color[1] as u32 * 587 + color[2] as u32 * 114 + 500) / 1000) as u8
+}
+
+/// Decodes a GX palette (TLUT) into a list of RGBA8 colors.
+#[must_use]
+pub fn decode_palette(data: &[u8], format: PaletteFormat) -> Vec<[u8; 4]> {
+    data.chunks_exact(2)
+        .map(|bytes| {
+            let value = u16::from_be_bytes([bytes[0], bytes[1]]);
+            match format {
+                PaletteFormat::IA8 => decode_ia8(value),
+                PaletteFormat::RGB565 => decode_rgb565(value),
+                PaletteFormat::RGB5A3 => decode_rgb5a3(value),
+            }
+        })
+        .collect()
+}
+
+/// Decodes a single 4x4 CMPR sub-block into the output image.
+fn decode_cmpr_block(block: &[u8], output: &mut [u8], width: u32, height: u32, x: u32, y: u32) {
+    let color0 = u16::from_be_bytes([block[0], block[1]]);
+    let color1 = u16::from_be_bytes([block[2], block[3]]);
+    // Each row is a single byte, with the leftmost pixel in the top bits
+    let indices = core::array::from_fn(|n| block[4 + n / 4] >> (6 - (n % 4) * 2));
+    let pixels = s3tc::decode_block(color0, color1, indices, false);
+
+    for (n, color) in pixels.into_iter().enumerate() {
+        write_pixel(output, width, height, x + n as u32 % 4, y + n as u32 / 4, color);
+    }
+}
+
+#[inline]
+fn write_pixel(output: &mut [u8], width: u32, height: u32, x: u32, y: u32, color: [u8; 4]) {
+    if x < width && y < height {
+        let offset = (y * width + x) as usize * 4;
+        output[offset..offset + 4].copy_from_slice(&color);
+    }
+}
+
+/// Decodes a GX texture into RGBA8 data.
+///
+/// Paletted formats (C4/C8/C14X2) require the decoded palette, see [`decode_palette`].
+///
+/// # Errors
+/// Returns [`InvalidSize`](Error::InvalidSize) if there's not enough data for the given dimensions, or
+/// [`MissingPalette`](Error::MissingPalette) if decoding a paletted format without a palette.
+pub fn decode(
+    data: &[u8], width: u32, height: u32, format: TextureFormat, palette: Option<&[[u8; 4]]>,
+) -> Result<Box<[u8]>> {
+    let expected = data_size(width, height, format);
+    ensure!(
+        data.len() >= expected,
+        InvalidSizeSnafu { expected, actual: data.len() }
+    );
+    ensure!(!format.is_paletted() || palette.is_some(), MissingPaletteSnafu);
+    let palette = palette.unwrap_or(&[]);
+    let lookup = |index: usize| palette.get(index).copied().unwrap_or_default();
+
+    let mut output = vec![0u8; width as usize * height as usize * 4];
+    let (block_width, block_height) = format.block_size();
+
+    for (index, block) in data[..expected].chunks_exact(format.block_bytes()).enumerate() {
+        let blocks_per_row = width.div_ceil(block_width);
+        let x = (index as u32 % blocks_per_row) * block_width;
+        let y = (index as u32 / blocks_per_row) * block_height;
+
+        match format {
+            TextureFormat::RGBA32 => {
+                for n in 0..16 {
+                    let (ar, gb) = (&block[n * 2..], &block[32 + n * 2..]);
+                    let color = [ar[1], gb[0], gb[1], ar[0]];
+                    write_pixel(
+                        &mut output,
+                        width,
+                        height,
+                        x + n as u32 % 4,
+                        y + n as u32 / 4,
+                        color,
+                    );
+                }
+            }
+            TextureFormat::CMPR => {
+                for (n, sub_block) in block.chunks_exact(8).enumerate() {
+                    let (sub_x, sub_y) = ((n as u32 % 2) * 4, (n as u32 / 2) * 4);
+                    decode_cmpr_block(sub_block, &mut output, width, height, x + sub_x, y + sub_y);
+                }
+            }
+            _ => {
+                for n in 0..(block_width * block_height) as usize {
+                    let nibble = || match n % 2 {
+                        0 => block[n / 2] >> 4,
+                        _ => block[n / 2] & 0xF,
+                    };
+                    let halfword = || u16::from_be_bytes([block[n * 2], block[n * 2 + 1]]);
+
+                    let color = match format {
+                        TextureFormat::I4 => {
+                            let value = nibble() * 0x11;
+                            [value, value, value, value]
+                        }
+                        TextureFormat::I8 => [block[n], block[n], block[n], block[n]],
+                        TextureFormat::IA4 => {
+                            let value = (block[n] & 0xF) * 0x11;
+                            [value, value, value, (block[n] >> 4) * 0x11]
+                        }
+                        TextureFormat::IA8 => decode_ia8(halfword()),
+                        TextureFormat::RGB565 => decode_rgb565(halfword()),
+                        TextureFormat::RGB5A3 => decode_rgb5a3(halfword()),
+                        TextureFormat::C4 => lookup(nibble().into()),
+                        TextureFormat::C8 => lookup(block[n].into()),
+                        TextureFormat::C14X2 => lookup((halfword() & 0x3FFF).into()),
+                        TextureFormat::RGBA32 | TextureFormat::CMPR => unreachable!(),
+                    };
+                    let (pixel_x, pixel_y) = (n as u32 % block_width, n as u32 / block_width);
+                    write_pixel(&mut output, width, height, x + pixel_x, y + pixel_y, color);
+                }
+            }
+        }
+    }
+
+    Ok(output.into_boxed_slice())
+}
+
+/// Encodes a 4x4 group of pixels into a single CMPR sub-block.
+fn encode_cmpr_block(pixels: &[[u8; 4]; 16], output: &mut [u8]) {
+    let (color0, color1, indices) = s3tc::encode_block(pixels, true);
+    output[0..2].copy_from_slice(&color0.to_be_bytes());
+    output[2..4].copy_from_slice(&color1.to_be_bytes());
+    output[4..8].fill(0);
+    for (n, index) in indices.into_iter().enumerate() {
+        output[4 + n / 4] |= index << (6 - (n % 4) * 2);
+    }
+}
+
+/// Encodes RGBA8 data into a GX texture.
+///
+/// Pixels outside of the image (when the dimensions aren't a multiple of the block size) are filled in by
+/// clamping to the nearest edge, which gives better results for CMPR.
+///
+/// # Errors
+/// Returns [`InvalidSize`](Error::InvalidSize) if there's not enough data for the given dimensions, or
+/// [`UnsupportedFormat`](Error::UnsupportedFormat) if trying to encode to a paletted format.
+pub fn encode(rgba: &[u8], width: u32, height: u32, format: TextureFormat) -> Result<Box<[u8]>> {
+    let expected = width as usize * height as usize * 4;
+    ensure!(
+        rgba.len() >= expected,
+        InvalidSizeSnafu { expected, actual: rgba.len() }
+    );
+    ensure!(!format.is_paletted(), UnsupportedFormatSnafu { format });
+
+    let pixel = |x: u32, y: u32| -> [u8; 4] {
+        let offset = (y.min(height - 1) * width + x.min(width - 1)) as usize * 4;
+        rgba[offset..offset + 4].try_into().unwrap()
+    };
+
+    let mut output = vec![0u8; data_size(width, height, format)];
+    let (block_width, block_height) = format.block_size();
+    let blocks_per_row = width.div_ceil(block_width);
+
+    for (index, block) in output.chunks_exact_mut(format.block_bytes()).enumerate() {
+        let x = (index as u32 % blocks_per_row) * block_width;
+        let y = (index as u32 / blocks_per_row) * block_height;
+
+        match format {
+            TextureFormat::RGBA32 => {
+                for n in 0..16 {
+                    let color = pixel(x + n as u32 % 4, y + n as u32 / 4);
+                    block[n * 2..n * 2 + 2].copy_from_slice(&[color[3], color[0]]);
+                    block[32 + n * 2..32 + n * 2 + 2].copy_from_slice(&[color[1], color[2]]);
+                }
+            }
+            TextureFormat::CMPR => {
+                for (n, sub_block) in block.chunks_exact_mut(8).enumerate() {
+                    let (sub_x, sub_y) = (x + (n as u32 % 2) * 4, y + (n as u32 / 2) * 4);
+                    let pixels = core::array::from_fn(|i| pixel(sub_x + i as u32 % 4, sub_y + i as u32 / 4));
+                    encode_cmpr_block(&pixels, sub_block);
+                }
+            }
+            _ => {
+                for n in 0..(block_width * block_height) as usize {
+                    let color = pixel(x + n as u32 % block_width, y + n as u32 / block_width);
+                    match format {
+                        TextureFormat::I4 => block[n / 2] |= (intensity(color) >> 4) << (4 - (n % 2) * 4),
+                        TextureFormat::I8 => block[n] = intensity(color),
+                        TextureFormat::IA4 => block[n] = (color[3] & 0xF0) | (intensity(color) >> 4),
+                        TextureFormat::IA8 => {
+                            block[n * 2..n * 2 + 2].copy_from_slice(&[color[3], intensity(color)])
+                        }
+                        TextureFormat::RGB565 => {
+                            block[n * 2..n * 2 + 2].copy_from_slice(&encode_rgb565(color).to_be_bytes())
+                        }
+                        TextureFormat::RGB5A3 => {
+                            block[n * 2..n * 2 + 2].copy_from_slice(&encode_rgb5a3(color).to_be_bytes())
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(output.into_boxed_slice())
+}
@@ -0,0 +1,379 @@
+//! Reads just enough of common image files to describe them, without decoding any of their pixels, so that
+//! textures can be told apart before extracting or converting them.
+//!
+//! # Containers
+//! | Container | Magic     | Notes                                                                   |
+//! |-----------|-----------|-------------------------------------------------------------------------|
+//! | PNG       | `\x89PNG` | Every chunk is walked up to `IEND` to find the size of the file         |
+//! | DDS       | `DDS `    | Includes the DX10 header, volume textures don't report a size           |
+//! | SGI       | `\x01\xDA`| Used by Panda3D (`.rgb`), both verbatim and run-length encoded          |
+//! | BTI       | None      | GameCube/Wii textures, only recognized if every header field is valid   |
+//!
+//! # Usage
+//! ```
+//! use orthrus_texture::header::{self, Container};
+//!
+//! // An 8x4 I8 texture, which is a single block of data directly after the header
+//! let mut bti = vec![0u8; 0x40];
+//! bti[0x00] = 0x1;
+//! bti[0x02..0x04].copy_from_slice(&8u16.to_be_bytes());
+//! bti[0x04..0x06].copy_from_slice(&4u16.to_be_bytes());
+//! bti[0x18] = 1;
+//! bti[0x1C..0x20].copy_from_slice(&0x20u32.to_be_bytes());
+//!
+//! let header = header::read(&bti).unwrap();
+//! assert_eq!(header.container, Container::Bti);
+//! assert_eq!((header.width, header.height, header.format, header.mip_count), (8, 4, "I8", 1));
+//! assert!(!header.is_truncated(bti.len()));
+//! assert!(header.is_truncated(0x30));
+//! ```
+
+use crate::bc;
+use crate::gx::{self, TextureFormat};
+
+/// All image containers that can be described, see the [module documentation](self) for more information.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Container {
+    Png,
+    Dds,
+    Sgi,
+    Bti,
+}
+
+impl Container {
+    /// Returns a human-readable name for the container.
+    #[must_use]
+    #[inline]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Png => "PNG image",
+            Self::Dds => "DirectDraw Surface (DDS)",
+            Self::Sgi => "Silicon Graphics image (SGI)",
+            Self::Bti => "GameCube/Wii texture (BTI)",
+        }
+    }
+}
+
+/// Description of a single image file, see [`read`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Header {
+    /// Which container the image is stored in
+    pub container: Container,
+    /// Width of the full size image, in pixels
+    pub width: u32,
+    /// Height of the full size image, in pixels
+    pub height: u32,
+    /// Name of the pixel format, such as `"RGBA8"`, `"BC1"`, or `"CMPR"`
+    pub format: &'static str,
+    /// Number of mip levels, including the full size image
+    pub mip_count: u32,
+    /// Smallest size the whole file can be according to its header, or [`None`] if it can't be determined
+    pub expected_size: Option<usize>,
+}
+
+impl Header {
+    /// Returns whether a file of `size` bytes is too small to hold everything its header describes.
+    #[must_use]
+    #[inline]
+    pub fn is_truncated(&self, size: usize) -> bool {
+        self.expected_size.is_some_and(|expected| size < expected)
+    }
+}
+
+#[inline]
+fn read_u16_be(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+#[inline]
+fn read_u32_be(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+#[inline]
+fn read_u32_le(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+/// Returns the combined size of every mip level, where `size` returns the size of a single level.
+fn mip_chain(width: u32, height: u32, mip_count: u32, size: impl Fn(u32, u32) -> usize) -> usize {
+    (0..mip_count.min(32)).map(|level| size((width >> level).max(1), (height >> level).max(1))).sum()
+}
+
+/// Reads the header of `data`, returning [`None`] if it isn't a supported image.
+#[must_use]
+pub fn read(data: &[u8]) -> Option<Header> {
+    match data.get(..4)? {
+        b"\x89PNG" => read_png(data),
+        b"DDS " => read_dds(data),
+        [0x01, 0xDA, ..] => read_sgi(data),
+        _ => read_bti(data),
+    }
+}
+
+fn read_png(data: &[u8]) -> Option<Header> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1A\n";
+    if !data.starts_with(SIGNATURE) || data.get(12..16)? != b"IHDR" {
+        return None;
+    }
+    let (width, height) = (read_u32_be(data, 16)?, read_u32_be(data, 20)?);
+    let format = match (data.get(25)?, data.get(24)?) {
+        (0, 1) => "L1",
+        (0, 2) => "L2",
+        (0, 4) => "L4",
+        (0, 8) => "L8",
+        (0, 16) => "L16",
+        (2, 8) => "RGB8",
+        (2, 16) => "RGB16",
+        (3, 1) => "Indexed1",
+        (3, 2) => "Indexed2",
+        (3, 4) => "Indexed4",
+        (3, 8) => "Indexed8",
+        (4, 8) => "LA8",
+        (4, 16) => "LA16",
+        (6, 8) => "RGBA8",
+        (6, 16) => "RGBA16",
+        _ => return None,
+    };
+
+    // Each chunk is a length, type, data, then CRC, and the file ends after the IEND chunk
+    let mut position = SIGNATURE.len();
+    let expected_size = loop {
+        let (Some(length), Some(kind)) = (read_u32_be(data, position), data.get(position + 4..position + 8))
+        else {
+            // The file ends partway through a chunk header, so at least an IEND chunk is missing
+            break position + 12;
+        };
+        position = position.saturating_add(12).saturating_add(length as usize);
+        if kind == b"IEND" || position > data.len() {
+            break position;
+        }
+    };
+
+    Some(Header {
+        container: Container::Png,
+        width,
+        height,
+        format,
+        mip_count: 1,
+        expected_size: Some(expected_size),
+    })
+}
+
+fn read_dds(data: &[u8]) -> Option<Header> {
+    const HEADER_SIZE: usize = 0x80;
+    const FLAG_MIPMAP_COUNT: u32 = 0x20000;
+    const PIXEL_ALPHA: u32 = 0x1;
+    const PIXEL_FOURCC: u32 = 0x4;
+    const PIXEL_RGB: u32 = 0x40;
+    const PIXEL_LUMINANCE: u32 = 0x20000;
+    const CAPS2_CUBEMAP: u32 = 0x200;
+    const CAPS2_VOLUME: u32 = 0x200000;
+
+    if read_u32_le(data, 4)? != 124 {
+        return None;
+    }
+    let (flags, height, width) = (
+        read_u32_le(data, 8)?,
+        read_u32_le(data, 12)?,
+        read_u32_le(data, 16)?,
+    );
+    let mip_count = match flags & FLAG_MIPMAP_COUNT != 0 {
+        true => read_u32_le(data, 28)?.max(1),
+        false => 1,
+    };
+    let (pixel_flags, bit_count) = (read_u32_le(data, 80)?, read_u32_le(data, 88)?);
+    let caps2 = read_u32_le(data, 112)?;
+
+    // Block compressed formats, and uncompressed formats by their bytes per pixel
+    enum Layout {
+        Blocks(bc::Format),
+        BytesPerPixel(usize),
+        Unknown,
+    }
+    let (format, layout, header_size) = match pixel_flags & PIXEL_FOURCC != 0 {
+        true => match data.get(84..88)? {
+            b"DXT1" => ("BC1", Layout::Blocks(bc::Format::BC1), HEADER_SIZE),
+            b"DXT2" | b"DXT3" => ("BC2", Layout::Blocks(bc::Format::BC2), HEADER_SIZE),
+            b"DXT4" | b"DXT5" => ("BC3", Layout::Blocks(bc::Format::BC3), HEADER_SIZE),
+            b"ATI1" | b"BC4U" | b"BC4S" => ("BC4", Layout::Blocks(bc::Format::BC4), HEADER_SIZE),
+            b"ATI2" | b"BC5U" | b"BC5S" => ("BC5", Layout::Blocks(bc::Format::BC5), HEADER_SIZE),
+            // The DXGI format is stored in an extra header, see DXGI_FORMAT for all values
+            b"DX10" => {
+                let (format, layout) = match read_u32_le(data, HEADER_SIZE)? {
+                    28 | 29 => ("RGBA8", Layout::BytesPerPixel(4)),
+                    87 | 91 => ("BGRA8", Layout::BytesPerPixel(4)),
+                    71 | 72 => ("BC1", Layout::Blocks(bc::Format::BC1)),
+                    74 | 75 => ("BC2", Layout::Blocks(bc::Format::BC2)),
+                    77 | 78 => ("BC3", Layout::Blocks(bc::Format::BC3)),
+                    80 | 81 => ("BC4", Layout::Blocks(bc::Format::BC4)),
+                    83 | 84 => ("BC5", Layout::Blocks(bc::Format::BC5)),
+                    // BC6H and BC7 use the same block size as BC3
+                    95 | 96 => ("BC6H", Layout::Blocks(bc::Format::BC3)),
+                    98 | 99 => ("BC7", Layout::Blocks(bc::Format::BC3)),
+                    _ => ("DX10", Layout::Unknown),
+                };
+                (format, layout, HEADER_SIZE + 20)
+            }
+            _ => ("Unknown", Layout::Unknown, HEADER_SIZE),
+        },
+        false => {
+            let format = match (pixel_flags & (PIXEL_RGB | PIXEL_LUMINANCE), bit_count) {
+                (PIXEL_LUMINANCE, 8) => "L8",
+                (PIXEL_LUMINANCE, 16) => "LA8",
+                (PIXEL_RGB, 16) => "RGB16",
+                (PIXEL_RGB, 24) => "RGB8",
+                (PIXEL_RGB, 32) if pixel_flags & PIXEL_ALPHA != 0 => "RGBA8",
+                (PIXEL_RGB, 32) => "RGBX8",
+                _ => "Unknown",
+            };
+            let layout = match format {
+                "Unknown" => Layout::Unknown,
+                _ => Layout::BytesPerPixel(bit_count as usize / 8),
+            };
+            (format, layout, HEADER_SIZE)
+        }
+    };
+
+    // Cubemaps store a full mip chain for each face that's present
+    let faces = match caps2 & CAPS2_CUBEMAP != 0 {
+        true => ((caps2 >> 10) & 0x3F).count_ones().max(1) as usize,
+        false => 1,
+    };
+    let expected_size = match layout {
+        _ if caps2 & CAPS2_VOLUME != 0 => None,
+        Layout::Blocks(block) => {
+            Some(mip_chain(width, height, mip_count, |w, h| bc::data_size(w, h, block)) * faces + header_size)
+        }
+        Layout::BytesPerPixel(bytes) => Some(
+            mip_chain(width, height, mip_count, |w, h| w as usize * h as usize * bytes) * faces + header_size,
+        ),
+        Layout::Unknown => None,
+    };
+
+    Some(Header {
+        container: Container::Dds,
+        width,
+        height,
+        format,
+        mip_count,
+        expected_size,
+    })
+}
+
+fn read_sgi(data: &[u8]) -> Option<Header> {
+    const HEADER_SIZE: usize = 0x200;
+
+    let (storage, bytes_per_channel) = (*data.get(2)?, *data.get(3)?);
+    let dimension = read_u16_be(data, 4)?;
+    if storage > 1 || !(1..=2).contains(&bytes_per_channel) || !(1..=3).contains(&dimension) {
+        return None;
+    }
+    // Fewer dimensions means the remaining sizes are ignored, and treated as 1
+    let width = u32::from(read_u16_be(data, 6)?);
+    let height = match dimension >= 2 {
+        true => u32::from(read_u16_be(data, 8)?),
+        false => 1,
+    };
+    let channels = match dimension == 3 {
+        true => u32::from(read_u16_be(data, 10)?),
+        false => 1,
+    };
+    let format = match (channels, bytes_per_channel) {
+        (1, 1) => "L8",
+        (1, 2) => "L16",
+        (2, 1) => "LA8",
+        (2, 2) => "LA16",
+        (3, 1) => "RGB8",
+        (3, 2) => "RGB16",
+        (4, 1) => "RGBA8",
+        (4, 2) => "RGBA16",
+        _ => return None,
+    };
+
+    let rows = height as usize * channels as usize;
+    let expected_size = match storage {
+        0 => HEADER_SIZE + rows * width as usize * usize::from(bytes_per_channel),
+        // Run-length encoded rows are found using a table of offsets, followed by a table of lengths
+        _ => {
+            let tables = HEADER_SIZE + rows * 8;
+            (0..rows)
+                .map(|row| {
+                    let offset = read_u32_be(data, HEADER_SIZE + row * 4)?;
+                    let length = read_u32_be(data, HEADER_SIZE + (rows + row) * 4)?;
+                    Some(offset as usize + length as usize)
+                })
+                .try_fold(tables, |size, row| row.map(|row| size.max(row)))
+                .unwrap_or(tables)
+        }
+    };
+
+    Some(Header {
+        container: Container::Sgi,
+        width,
+        height,
+        format,
+        mip_count: 1,
+        expected_size: Some(expected_size),
+    })
+}
+
+fn read_bti(data: &[u8]) -> Option<Header> {
+    const HEADER_SIZE: usize = 0x20;
+
+    let header = data.get(..HEADER_SIZE)?;
+    let format = TextureFormat::try_from(header[0]).ok()?;
+    let (width, height) = (
+        u32::from(read_u16_be(header, 2)?),
+        u32::from(read_u16_be(header, 4)?),
+    );
+    let mip_count = u32::from(header[0x18]);
+    let (palette_entries, palette_offset) = (read_u16_be(header, 0xA)?, read_u32_be(header, 0xC)?);
+    let data_offset = read_u32_be(header, 0x1C)? as usize;
+
+    // There's no magic number, so make sure every field is something GX actually supports
+    let valid = (1..=1024).contains(&width)
+        && (1..=1024).contains(&height)
+        && header[0x6] <= 2 // Wrap S
+        && header[0x7] <= 2 // Wrap T
+        && header[0x8] <= 1 // Palette enabled
+        && header[0x9] <= 2 // Palette format
+        && header[0x10] <= 1 // Mipmaps enabled
+        && header[0x14] <= 5 // Minification filter
+        && header[0x15] <= 1 // Magnification filter
+        && (1..=11).contains(&mip_count)
+        && data_offset >= HEADER_SIZE
+        && data_offset <= data.len();
+    if !valid {
+        return None;
+    }
+
+    let format_name = match format {
+        TextureFormat::I4 => "I4",
+        TextureFormat::I8 => "I8",
+        TextureFormat::IA4 => "IA4",
+        TextureFormat::IA8 => "IA8",
+        TextureFormat::RGB565 => "RGB565",
+        TextureFormat::RGB5A3 => "RGB5A3",
+        TextureFormat::RGBA32 => "RGBA32",
+        TextureFormat::C4 => "C4",
+        TextureFormat::C8 => "C8",
+        TextureFormat::C14X2 => "C14X2",
+        TextureFormat::CMPR => "CMPR",
+    };
+    let image_end = data_offset + mip_chain(width, height, mip_count, |w, h| gx::data_size(w, h, format));
+    let palette_end = match format.is_paletted() {
+        true => palette_offset as usize + usize::from(palette_entries) * 2,
+        false => 0,
+    };
+
+    Some(Header {
+        container: Container::Bti,
+        width,
+        height,
+        format: format_name,
+        mip_count,
+        expected_size: Some(image_end.max(palette_end)),
+    })
+}
@@ -0,0 +1,22 @@
+//! This crate contains modules for [Orthrus](https://crates.io/crates/orthrus) that add support for the
+//! texture formats used by game consoles, so that each format crate can share the same codecs.
+//!
+//! All codecs decode to, and encode from, tightly packed RGBA8 data.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+mod no_std {
+    extern crate alloc;
+    pub use alloc::boxed::Box;
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+}
+
+pub mod astc;
+pub mod bc;
+pub mod gx;
+pub mod header;
+pub mod prelude;
+mod s3tc;
+pub mod tegra;
@@ -0,0 +1,31 @@
+//! Convenient re-exports of commonly used data types, designed to make crate usage painless.
+//!
+//! The contents of this module can be used by including the following in any module:
+//! ```ignore
+//! use orthrus_texture::prelude::*;
+//! ```
+
+pub mod astc {
+    #[doc(inline)]
+    pub use crate::astc::{decode, Error};
+}
+
+pub mod bc {
+    #[doc(inline)]
+    pub use crate::bc::{decode, encode, Error, Format};
+}
+
+pub mod gx {
+    #[doc(inline)]
+    pub use crate::gx::{decode, decode_palette, encode, Error, PaletteFormat, TextureFormat};
+}
+
+pub mod header {
+    #[doc(inline)]
+    pub use crate::header::{read, Container, Header};
+}
+
+pub mod tegra {
+    #[doc(inline)]
+    pub use crate::tegra::{deswizzle, swizzle, Error};
+}
@@ -0,0 +1,116 @@
+//! Shared S3TC (DXT1) color block handling, used by both GX CMPR and BC1-BC3.
+//!
+//! Each 4x4 block stores two RGB565 endpoints and a 2-bit index per pixel. The formats only differ in byte
+//! order and index layout, so callers are responsible for unpacking those into the plain values used here,
+//! with indices in row-major pixel order.
+
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
+#[inline]
+pub(crate) const fn expand5(value: u16) -> u8 {
+    let value = value as u8;
+    (value << 3) | (value >> 2)
+}
+
+#[inline]
+pub(crate) const fn expand6(value: u16) -> u8 {
+    let value = value as u8;
+    (value << 2) | (value >> 4)
+}
+
+#[inline]
+pub(crate) const fn decode_rgb565(value: u16) -> [u8; 4] {
+    [
+        expand5(value >> 11),
+        expand6((value >> 5) & 0x3F),
+        expand5(value & 0x1F),
+        0xFF,
+    ]
+}
+
+#[inline]
+pub(crate) const fn encode_rgb565(color: [u8; 4]) -> u16 {
+    ((color[0] as u16 >> 3) << 11) | ((color[1] as u16 >> 2) << 5) | (color[2] as u16 >> 3)
+}
+
+/// Builds the four colors that the indices select from.
+///
+/// If `color0 <= color1` and the format supports it, the block is in three-color mode, where index 3 is
+/// transparent black. BC2 and BC3 store alpha separately, so they always use four-color mode.
+pub(crate) fn palette(color0: u16, color1: u16, four_color_only: bool) -> [[u8; 4]; 4] {
+    let (c0, c1) = (decode_rgb565(color0), decode_rgb565(color1));
+    let four_color = four_color_only || color0 > color1;
+
+    let mut palette = [c0, c1, [0, 0, 0, 0xFF], [0, 0, 0, 0]];
+    for n in 0..3 {
+        let (a, b) = (u16::from(c0[n]), u16::from(c1[n]));
+        match four_color {
+            true => {
+                palette[2][n] = ((2 * a + b) / 3) as u8;
+                palette[3][n] = ((a + 2 * b) / 3) as u8;
+            }
+            false => palette[2][n] = ((a + b) / 2) as u8,
+        }
+    }
+    if four_color {
+        palette[3][3] = 0xFF;
+    }
+    palette
+}
+
+/// Decodes a color block into 16 RGBA8 pixels, in row-major order.
+#[inline]
+pub(crate) fn decode_block(
+    color0: u16, color1: u16, indices: [u8; 16], four_color_only: bool,
+) -> [[u8; 4]; 16] {
+    let palette = palette(color0, color1, four_color_only);
+    indices.map(|index| palette[usize::from(index & 3)])
+}
+
+/// Returns the distance between two colors, ignoring alpha.
+#[inline]
+fn color_distance(a: [u8; 4], b: [u8; 4]) -> u32 {
+    (0..3).map(|n| (i32::from(a[n]) - i32::from(b[n])).unsigned_abs().pow(2)).sum()
+}
+
+/// Encodes 16 RGBA8 pixels (in row-major order) into a color block, returning the endpoints and indices.
+///
+/// If `allow_transparent` is set, pixels with alpha below 128 will use three-color mode with a transparent
+/// index, otherwise alpha is ignored and four-color mode is always used.
+pub(crate) fn encode_block(pixels: &[[u8; 4]; 16], allow_transparent: bool) -> (u16, u16, [u8; 16]) {
+    let has_alpha = allow_transparent && pixels.iter().any(|pixel| pixel[3] < 0x80);
+
+    // Find the two opaque colors that are furthest apart, and use them as the endpoints
+    let opaque: Vec<[u8; 4]> =
+        pixels.iter().copied().filter(|pixel| !has_alpha || pixel[3] >= 0x80).collect();
+    let (mut color0, mut color1) = (0u16, 0u16);
+    let mut best = 0;
+    for (i, &a) in opaque.iter().enumerate() {
+        for &b in &opaque[i..] {
+            let distance = color_distance(a, b);
+            if distance >= best {
+                best = distance;
+                (color0, color1) = (encode_rgb565(a), encode_rgb565(b));
+            }
+        }
+    }
+
+    // The order of the endpoints determines whether we get a transparent color or a fourth opaque one
+    if (has_alpha && color0 > color1) || (!has_alpha && color0 < color1) {
+        core::mem::swap(&mut color0, &mut color1);
+    }
+
+    let four_color_only = !allow_transparent;
+    let palette = palette(color0, color1, four_color_only);
+    let candidates = match four_color_only || color0 > color1 {
+        true => 4,
+        false => 3,
+    };
+
+    let indices = pixels.map(|pixel| match has_alpha && pixel[3] < 0x80 {
+        true => 3,
+        false => (0..candidates).min_by_key(|&index| color_distance(pixel, palette[index])).unwrap() as u8,
+    });
+    (color0, color1, indices)
+}
@@ -0,0 +1,195 @@
+//! Adds support for the block-linear memory layout used by the Tegra X1 GPU in the Nintendo Switch.
+//!
+//! # Format
+//! Textures on the Switch are rarely stored in row-major order. Instead, they're split up into GOBs ("groups
+//! of bytes"), which are 64 bytes wide and 8 rows tall (512 bytes total), and GOBs are grouped into blocks
+//! that are one GOB wide and `block_height` GOBs tall. Blocks are then stored in row-major order, with each
+//! row of blocks padded out to a whole number of GOBs.
+//!
+//! Inside of a GOB, every 16-byte run of a row is kept together, but the runs themselves are interleaved:
+//!
+//! | Bits of the address | Source                                 |
+//! |---------------------|----------------------------------------|
+//! | 0-3                 | Byte offset in the row (bits 0-3 of x) |
+//! | 4                   | Bit 0 of y                             |
+//! | 5                   | Bit 4 of x                             |
+//! | 6-7                 | Bits 1-2 of y                          |
+//! | 8                   | Bit 5 of x                             |
+//!
+//! All dimensions here are in elements, which is a pixel for uncompressed formats, and a whole block for
+//! compressed formats (such as a 4x4 block of BC1). The block height is stored in the texture header, and
+//! each mipmap uses a smaller block height once it gets small enough (see [`mip_block_height`]).
+//!
+//! # Usage
+//! ```
+//! use orthrus_texture::tegra;
+//!
+//! // A 64x64 RGBA8 image, using 16-GOB-tall blocks
+//! let linear: Vec<u8> = (0..64 * 64 * 4).map(|n| n as u8).collect();
+//! let swizzled = tegra::swizzle(&linear, 64, 64, 4, 16)?;
+//! assert_eq!(swizzled.len(), tegra::surface_size(64, 64, 4, 16));
+//!
+//! let deswizzled = tegra::deswizzle(&swizzled, 64, 64, 4, 16)?;
+//! assert_eq!(&*deswizzled, &*linear);
+//! # Ok::<(), tegra::Error>(())
+//! ```
+
+use snafu::prelude::*;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std::*;
+
+/// Error conditions for when swizzling or deswizzling textures.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown if the input is too small for the given dimensions.
+    #[snafu(display("Not enough data! Expected {expected:#X} bytes, got {actual:#X}."))]
+    InvalidSize { expected: usize, actual: usize },
+
+    /// Thrown if the block height isn't a power of two between 1 and 32.
+    #[snafu(display("Invalid block height {block_height}!"))]
+    InvalidBlockHeight { block_height: u32 },
+}
+type Result<T> = core::result::Result<T, Error>;
+
+/// Width of a GOB, in bytes.
+pub const GOB_WIDTH: usize = 64;
+/// Height of a GOB, in rows.
+pub const GOB_HEIGHT: usize = 8;
+/// Size of a GOB, in bytes.
+pub const GOB_SIZE: usize = GOB_WIDTH * GOB_HEIGHT;
+
+/// Returns the block height (in GOBs) that the Switch SDK picks for a surface with the given height in
+/// elements, which is used for the first mipmap.
+#[must_use]
+#[inline]
+pub const fn block_height(height: u32) -> u32 {
+    match height.div_ceil(GOB_HEIGHT as u32) {
+        16.. => 16,
+        8.. => 8,
+        4.. => 4,
+        2.. => 2,
+        _ => 1,
+    }
+}
+
+/// Returns the block height (in GOBs) used for a mipmap with the given height in elements, given the block
+/// height of the first mipmap.
+///
+/// Smaller mipmaps shrink the block height until it's no more than twice as tall as the mipmap.
+#[must_use]
+#[inline]
+pub const fn mip_block_height(height: u32, block_height: u32) -> u32 {
+    let mut block_height = block_height;
+    while block_height > 1 && height <= (block_height / 2) * GOB_HEIGHT as u32 {
+        block_height /= 2;
+    }
+    block_height
+}
+
+/// Returns the number of bytes needed to store a block-linear surface, including all padding.
+#[must_use]
+#[inline]
+pub const fn surface_size(width: u32, height: u32, bytes_per_element: u32, block_height: u32) -> usize {
+    let width_in_gobs = (width as usize * bytes_per_element as usize).div_ceil(GOB_WIDTH);
+    let block_rows = GOB_HEIGHT * block_height as usize;
+    let height_in_blocks = (height as usize).div_ceil(block_rows);
+    width_in_gobs * height_in_blocks * GOB_SIZE * block_height as usize
+}
+
+/// Returns the offset of a byte in a block-linear surface, where `x` is in bytes and `y` is in rows.
+#[inline]
+const fn swizzled_offset(x: usize, y: usize, width_in_gobs: usize, block_height: usize) -> usize {
+    let block_rows = GOB_HEIGHT * block_height;
+    let block_size = GOB_SIZE * block_height;
+
+    let block = (y / block_rows) * width_in_gobs * block_size + (x / GOB_WIDTH) * block_size;
+    let gob = ((y % block_rows) / GOB_HEIGHT) * GOB_SIZE;
+    let (x, y) = (x % GOB_WIDTH, y % GOB_HEIGHT);
+    block + gob + (x / 32) * 256 + (y / 2) * 64 + ((x % 32) / 16) * 32 + (y % 2) * 16 + (x % 16)
+}
+
+/// Copies every 16-byte run between the linear and block-linear layouts, calling `copy` with the linear
+/// offset, the swizzled offset, and the run length.
+fn for_each_run(
+    width: u32, height: u32, bytes_per_element: u32, block_height: u32,
+    mut copy: impl FnMut(usize, usize, usize),
+) {
+    let row_size = width as usize * bytes_per_element as usize;
+    let width_in_gobs = row_size.div_ceil(GOB_WIDTH);
+    for y in 0..height as usize {
+        for x in (0..row_size).step_by(16) {
+            let length = (row_size - x).min(16);
+            copy(
+                y * row_size + x,
+                swizzled_offset(x, y, width_in_gobs, block_height as usize),
+                length,
+            );
+        }
+    }
+}
+
+/// Validates the parameters, and returns the size of the swizzled surface.
+fn check(
+    data: &[u8], width: u32, height: u32, bytes_per_element: u32, block_height: u32, swizzled: bool,
+) -> Result<usize> {
+    ensure!(
+        block_height.is_power_of_two() && block_height <= 32,
+        InvalidBlockHeightSnafu { block_height }
+    );
+    let size = surface_size(width, height, bytes_per_element, block_height);
+    let expected = match swizzled {
+        true => size,
+        false => width as usize * height as usize * bytes_per_element as usize,
+    };
+    ensure!(
+        data.len() >= expected,
+        InvalidSizeSnafu { expected, actual: data.len() }
+    );
+    Ok(size)
+}
+
+/// Converts a block-linear surface into tightly packed, row-major data.
+///
+/// # Errors
+/// Returns [`InvalidSize`](Error::InvalidSize) if there's not enough data for the given dimensions, or
+/// [`InvalidBlockHeight`](Error::InvalidBlockHeight) if the block height is invalid.
+pub fn deswizzle(
+    data: &[u8], width: u32, height: u32, bytes_per_element: u32, block_height: u32,
+) -> Result<Box<[u8]>> {
+    check(data, width, height, bytes_per_element, block_height, true)?;
+    let mut output = vec![0u8; width as usize * height as usize * bytes_per_element as usize];
+    for_each_run(
+        width,
+        height,
+        bytes_per_element,
+        block_height,
+        |linear, swizzled, length| {
+            output[linear..linear + length].copy_from_slice(&data[swizzled..swizzled + length]);
+        },
+    );
+    Ok(output.into_boxed_slice())
+}
+
+/// Converts tightly packed, row-major data into a block-linear surface. Any padding is filled with zeroes.
+///
+/// # Errors
+/// Returns [`InvalidSize`](Error::InvalidSize) if there's not enough data for the given dimensions, or
+/// [`InvalidBlockHeight`](Error::InvalidBlockHeight) if the block height is invalid.
+pub fn swizzle(
+    data: &[u8], width: u32, height: u32, bytes_per_element: u32, block_height: u32,
+) -> Result<Box<[u8]>> {
+    let size = check(data, width, height, bytes_per_element, block_height, false)?;
+    let mut output = vec![0u8; size];
+    for_each_run(
+        width,
+        height,
+        bytes_per_element,
+        block_height,
+        |linear, swizzled, length| {
+            output[swizzled..swizzled + length].copy_from_slice(&data[linear..linear + length]);
+        },
+    );
+    Ok(output.into_boxed_slice())
+}
@@ -0,0 +1,239 @@
+//! Content-addressed cache for the outputs of expensive operations, such as decompressing the same Yaz0 file
+//! in every run of a batch conversion.
+//!
+//! Each output is stored in its own file inside of the cache folder, named after a [`Key`] which is the SHA-256
+//! of the operation and its input, so the same folder can safely be shared between runs. Once the folder grows
+//! past its size limit, the least recently used outputs are removed first.
+//!
+//! A [`Pipeline`](crate::pipeline::Pipeline) can use a cache to skip its built-in steps, see
+//! [`Pipeline::cache`](crate::pipeline::Pipeline::cache).
+//!
+//! # Usage
+//! ```
+//! use orthrus_tools::cache::Cache;
+//!
+//! # let folder = std::env::temp_dir().join(format!("orthrus-cache-{}", std::process::id()));
+//! let cache = Cache::open(&folder)?.with_max_size(64 * 1024 * 1024);
+//!
+//! let key = Cache::key("yaz0-decompress", b"compressed data");
+//! assert!(cache.get(&key).is_none());
+//! cache.insert(&key, b"decompressed data")?;
+//! assert_eq!(cache.get(&key).as_deref(), Some(b"decompressed data".as_slice()));
+//!
+//! // Outputs can be invalidated one at a time, or all at once
+//! assert!(cache.remove(&key)?);
+//! cache.clear()?;
+//! # std::fs::remove_dir_all(folder)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+use sha2::{Digest, Sha256};
+use snafu::prelude::*;
+
+/// Error conditions for when reading or writing a cache.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown if unable to read or write the cache folder.
+    #[snafu(display("Filesystem Error {}", source))]
+    FileError { source: std::io::Error },
+}
+type Result<T> = core::result::Result<T, Error>;
+
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(source: std::io::Error) -> Self {
+        Error::FileError { source }
+    }
+}
+
+/// Identifies a single cached output, see [`Cache::key`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key([u8; 32]);
+
+impl core::fmt::Display for Key {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+/// A folder of cached outputs, see the [module documentation](self) for more information.
+///
+/// This only stores where the cache is and its limits, so it's cheap to clone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cache {
+    root: PathBuf,
+    max_size: u64,
+}
+
+impl Cache {
+    /// Opens the cache stored in `root`, creating the folder if it doesn't exist yet. The cache is unlimited
+    /// in size by default.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to create the folder.
+    #[inline]
+    pub fn open<P: AsRef<Path>>(root: P) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        std::fs::create_dir_all(&root)?;
+        Ok(Self { root, max_size: u64::MAX })
+    }
+
+    /// Limits the total size of all outputs, in bytes. This only takes effect the next time something is
+    /// inserted, or when calling [`trim`](Self::trim).
+    #[must_use]
+    #[inline]
+    pub const fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Returns the folder this cache is stored in.
+    #[must_use]
+    #[inline]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the key for the output of running `operation` on `input`. The operation name has to include
+    /// any settings that change the output, such as which compression algorithm is used.
+    #[must_use]
+    #[inline]
+    pub fn key(operation: &str, input: &[u8]) -> Key {
+        let mut hasher = Sha256::new();
+        hasher.update(operation.as_bytes());
+        // Separates the name from the input, so that different splits can't produce the same key
+        hasher.update([0]);
+        hasher.update(input);
+        Key(hasher.finalize().into())
+    }
+
+    /// Returns where the output for `key` is stored, using the first byte as a subfolder so that no single
+    /// folder gets too large.
+    fn path(&self, key: &Key) -> PathBuf {
+        let name = key.to_string();
+        self.root.join(&name[..2]).join(&name[2..])
+    }
+
+    /// Returns the cached output for `key`, if there is one.
+    ///
+    /// Any error while reading is treated as a cache miss, since the output can always be recreated.
+    #[must_use]
+    pub fn get(&self, key: &Key) -> Option<Box<[u8]>> {
+        let path = self.path(key);
+        let data = std::fs::read(&path).ok()?;
+        // Mark this output as recently used, so it's removed last when trimming
+        if let Ok(file) = File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(data.into_boxed_slice())
+    }
+
+    /// Stores `data` as the output for `key`, then removes old outputs if the cache is over its size limit.
+    /// Outputs larger than the whole cache are never stored.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to write the output or trim the cache.
+    pub fn insert(&self, key: &Key, data: &[u8]) -> Result<()> {
+        if data.len() as u64 > self.max_size {
+            return Ok(());
+        }
+
+        let path = self.path(key);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Write to a temporary file first, so that other processes never see a partial output. The counter
+        // keeps threads in the same process from writing to the same file
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let temp = path.with_extension(format!("{}-{count}.tmp", std::process::id()));
+        std::fs::write(&temp, data)?;
+        std::fs::rename(&temp, &path)?;
+
+        if self.max_size != u64::MAX {
+            self.trim()?;
+        }
+        Ok(())
+    }
+
+    /// Removes the output for `key`, returning whether there was one.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if the output exists but can't be removed.
+    pub fn remove(&self, key: &Key) -> Result<bool> {
+        match std::fs::remove_file(self.path(key)) {
+            Ok(()) => Ok(true),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Returns the path, size, and last use of every output in the cache.
+    fn entries(&self) -> Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let mut entries = Vec::new();
+        for folder in std::fs::read_dir(&self.root)? {
+            let folder = folder?;
+            if !folder.file_type()?.is_dir() {
+                continue;
+            }
+            for entry in std::fs::read_dir(folder.path())? {
+                let entry = entry?;
+                let metadata = entry.metadata()?;
+                // Skip anything that's still being written
+                if metadata.is_file() && entry.path().extension().is_none() {
+                    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    entries.push((entry.path(), metadata.len(), modified));
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Returns the total size of every output in the cache, in bytes.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to read the cache folder.
+    pub fn size(&self) -> Result<u64> {
+        Ok(self.entries()?.iter().map(|(_, size, _)| size).sum())
+    }
+
+    /// Removes the least recently used outputs until the cache fits within its size limit, returning how
+    /// many were removed.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to read the cache folder or remove an output.
+    pub fn trim(&self) -> Result<usize> {
+        let mut entries = self.entries()?;
+        let mut size: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        entries.sort_unstable_by_key(|(_, _, modified)| *modified);
+
+        let mut removed = 0;
+        for (path, length, _) in entries {
+            if size <= self.max_size {
+                break;
+            }
+            std::fs::remove_file(path)?;
+            size -= length;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
+    /// Removes every output in the cache, returning how many were removed.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to read the cache folder or remove an output.
+    pub fn clear(&self) -> Result<usize> {
+        let entries = self.entries()?;
+        for (path, _, _) in &entries {
+            std::fs::remove_file(path)?;
+        }
+        Ok(entries.len())
+    }
+}
@@ -0,0 +1,439 @@
+//! Generators for small but valid files in each supported container format, so that tests and fuzzers have
+//! reproducible inputs without needing any game data.
+//!
+//! Each generator takes a seed, which controls the number of files, their names, and their contents, and
+//! always produces the same output for the same seed. Outputs are kept small (a few KiB at most), since
+//! fuzzers work best when starting from minimal inputs.
+//!
+//! [`write`] saves the output of every generator to a folder, one subfolder per format, to seed a fuzzer's
+//! corpus.
+//!
+//! # Usage
+//! ```
+//! use orthrus_godot::prelude::*;
+//! use orthrus_jsystem::prelude::*;
+//! use orthrus_ncompress::prelude::*;
+//! use orthrus_nintendoware::prelude::*;
+//! use orthrus_panda3d::bam::BinaryAsset;
+//! use orthrus_panda3d::multifile2::Multifile;
+//! use orthrus_tools::corpus;
+//!
+//! for seed in 0..4 {
+//!     assert_eq!(corpus::yaz0(seed), corpus::yaz0(seed));
+//!     Yaz0::decompress_from(&corpus::yaz0(seed))?;
+//!     assert_eq!(Multifile::load(corpus::multifile(seed), 0)?.count(), corpus::file_count(seed));
+//!     let asset = BinaryAsset::load(corpus::bam(seed))?;
+//!     assert_eq!(asset.find_by_type("PandaNode").len(), corpus::file_count(seed));
+//!     assert_eq!(ResourceArchive::load(corpus::rarc(seed))?.root.files().len(), corpus::file_count(seed));
+//!     let pack = ResourcePack::load(std::io::Cursor::new(corpus::pck(seed)))?;
+//!     assert_eq!(pack.paths().count(), corpus::file_count(seed));
+//!     Switch::BFSAR::load(corpus::bfsar(seed))?;
+//!     assert_eq!(Switch::BARS::load(corpus::bars(seed))?.entries().len(), corpus::file_count(seed));
+//!     assert_eq!(Switch::OpusStream::load(corpus::opus(seed))?.packet_count(), corpus::file_count(seed));
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::io::Cursor;
+use std::path::Path;
+
+use orthrus_core::prelude::checksum::crc32;
+use orthrus_core::synthetic;
+use orthrus_godot::prelude::*;
+use orthrus_jsystem::prelude::*;
+use orthrus_jsystem::rarc2::{Directory, Entry, File};
+use orthrus_ncompress::prelude::*;
+use orthrus_nintendoware::prelude::*;
+use orthrus_panda3d::bam::BinaryAsset;
+use orthrus_panda3d::multifile2::Multifile;
+
+/// Function that generates a file from a seed, see [`GENERATORS`].
+pub type Generator = fn(u64) -> Box<[u8]>;
+
+/// Every generator, along with the name of the format and the extension to save it with.
+pub static GENERATORS: [(&str, &str, Generator); 8] = [
+    ("yaz0", "szs", yaz0),
+    ("multifile", "mf", multifile),
+    ("bam", "bam", bam),
+    ("rarc", "arc", rarc),
+    ("pck", "pck", pck),
+    ("bfsar", "bfsar", bfsar),
+    ("bars", "bars", bars),
+    ("opus", "opus", opus),
+];
+
+/// Returns how many files the archive generators store for `seed`, which is between one and four.
+#[must_use]
+#[inline]
+pub const fn file_count(seed: u64) -> usize {
+    1 + (seed % 4) as usize
+}
+
+/// Returns the name and contents of every file stored in the generated archives for `seed`.
+fn files(seed: u64) -> Vec<(String, Box<[u8]>)> {
+    (0..file_count(seed))
+        .map(|index| {
+            let seed = seed.wrapping_mul(31).wrapping_add(index as u64);
+            let length = 0x10 + (seed % 0x100) as usize;
+            // Alternate between compressible and incompressible data, to cover both kinds of match
+            let data = match index % 2 {
+                0 => synthetic::structured(length, seed),
+                _ => synthetic::random(length, seed),
+            };
+            (format!("file{index}.bin"), data)
+        })
+        .collect()
+}
+
+/// Generates a Yaz0 stream, containing up to 1 KiB of data.
+#[must_use]
+pub fn yaz0(seed: u64) -> Box<[u8]> {
+    let data = synthetic::structured(0x40 + (seed % 0x3C0) as usize, seed);
+    // Compressing data that's already in memory can't fail
+    Yaz0::compress_from(&data, yaz0::CompressionAlgo::MatchingOld, 0).unwrap_or_default()
+}
+
+/// Generates an uncompressed v1.1 Multifile, with every Subfile's index entry stored before its data.
+#[must_use]
+pub fn multifile(seed: u64) -> Box<[u8]> {
+    let files = files(seed);
+    let timestamp = 0x6000_0000 + seed as u32;
+
+    let mut output = Vec::new();
+    output.extend_from_slice(&Multifile::MAGIC);
+    output.extend_from_slice(&1u16.to_le_bytes());
+    output.extend_from_slice(&1u16.to_le_bytes());
+    // Scale factor of 1, so all offsets are in bytes
+    output.extend_from_slice(&1u32.to_le_bytes());
+    output.extend_from_slice(&timestamp.to_le_bytes());
+
+    // Each index entry is the offset of the next entry, the data's offset and length, attributes, timestamp,
+    // then the name with every byte inverted
+    let entry_size = |name: &str| 4 + 4 + 4 + 2 + 4 + 2 + name.len();
+    let index_size: usize = files.iter().map(|(name, _)| entry_size(name)).sum::<usize>() + 4;
+    let mut offset = output.len() + index_size;
+    for (name, data) in &files {
+        let next = output.len() + entry_size(name);
+        output.extend_from_slice(&(next as u32).to_le_bytes());
+        output.extend_from_slice(&(offset as u32).to_le_bytes());
+        output.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        output.extend_from_slice(&0u16.to_le_bytes());
+        output.extend_from_slice(&timestamp.to_le_bytes());
+        output.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        output.extend(name.bytes().map(|byte| 255 - byte));
+        offset += data.len();
+    }
+    output.extend_from_slice(&0u32.to_le_bytes());
+
+    for (_, data) in &files {
+        output.extend_from_slice(data);
+    }
+    output.into_boxed_slice()
+}
+
+/// Generates a little endian v6.45 BAM file, containing a `ModelRoot` named `root` with a `PandaNode` child
+/// for each file, named `node0`, `node1`, and so on.
+///
+/// Every node shares the same empty `RenderState`, identity `TransformState`, and empty `RenderEffects`.
+#[must_use]
+#[inline]
+pub fn bam(seed: u64) -> Box<[u8]> {
+    bam_with_nodes(file_count(seed))
+}
+
+/// Generates the same BAM file as [`bam`], but with `count` child nodes, for benchmarking large files.
+///
+/// # Panics
+/// Panics if `count` doesn't leave room for every object ID in 16 bits.
+#[must_use]
+pub fn bam_with_nodes(count: usize) -> Box<[u8]> {
+    // Datagrams are their length followed by their data
+    fn datagram(output: &mut Vec<u8>, body: &[u8]) {
+        output.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        output.extend_from_slice(body);
+    }
+    // Objects are a control code, a type handle (with its name the first time it's used), then the object ID
+    fn object(code: u8, handle: u16, type_name: &str, id: u16) -> Vec<u8> {
+        let mut object = vec![code];
+        object.extend_from_slice(&handle.to_le_bytes());
+        object.extend_from_slice(&(type_name.len() as u16).to_le_bytes());
+        object.extend_from_slice(type_name.as_bytes());
+        // No parent classes
+        object.push(0);
+        object.extend_from_slice(&id.to_le_bytes());
+        object
+    }
+    // PandaNodes point at objects 2 to 4 for their state, then have visible draw masks and no tags
+    fn node(object: &mut Vec<u8>, name: &str, parents: &[u16], children: &[u16]) {
+        object.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        object.extend_from_slice(name.as_bytes());
+        for pointer in [2u16, 3, 4] {
+            object.extend_from_slice(&pointer.to_le_bytes());
+        }
+        for mask in [0u32, !0, !0] {
+            object.extend_from_slice(&mask.to_le_bytes());
+        }
+        object.push(0);
+        object.extend_from_slice(&0u32.to_le_bytes());
+        object.extend_from_slice(&(parents.len() as u16).to_le_bytes());
+        for parent in parents {
+            object.extend_from_slice(&parent.to_le_bytes());
+        }
+        object.extend_from_slice(&(children.len() as u16).to_le_bytes());
+        for child in children {
+            object.extend_from_slice(&child.to_le_bytes());
+            object.extend_from_slice(&0i32.to_le_bytes());
+        }
+        object.extend_from_slice(&0u16.to_le_bytes());
+    }
+
+    let count = u16::try_from(count).ok().filter(|&count| count <= u16::MAX - 5).expect("Too many nodes");
+    let children: Vec<u16> = (5..5 + count).collect();
+    let mut output = BinaryAsset::MAGIC.to_vec();
+    // Version 6.45, little endian, using floats
+    datagram(&mut output, &[6, 0, 45, 0, 1, 0]);
+
+    let mut root = object(0, 1, "ModelRoot", 1);
+    node(&mut root, "root", &[], &children);
+    root.extend_from_slice(&[0, 0, 0]);
+    datagram(&mut output, &root);
+
+    let mut state = object(2, 2, "RenderState", 2);
+    state.extend_from_slice(&0u16.to_le_bytes());
+    datagram(&mut output, &state);
+    let mut transform = object(2, 3, "TransformState", 3);
+    transform.extend_from_slice(&1u32.to_le_bytes());
+    datagram(&mut output, &transform);
+    let mut effects = object(2, 4, "RenderEffects", 4);
+    effects.extend_from_slice(&0u16.to_le_bytes());
+    datagram(&mut output, &effects);
+
+    for (index, &id) in children.iter().enumerate() {
+        // The type is only named the first time it's used
+        let mut child = match index {
+            0 => object(2, 5, "PandaNode", id),
+            _ => [&[2u8][..], &5u16.to_le_bytes(), &id.to_le_bytes()].concat(),
+        };
+        node(&mut child, &format!("node{index}"), &[1], &[]);
+        datagram(&mut output, &child);
+    }
+
+    // Pop back out of the root object
+    datagram(&mut output, &[1]);
+    output.into_boxed_slice()
+}
+
+/// Generates a RARC archive, with every other file stored in a subdirectory.
+#[must_use]
+pub fn rarc(seed: u64) -> Box<[u8]> {
+    let mut root = Directory::new("root");
+    let mut subdirectory = Directory::new("data");
+    for (index, (name, data)) in files(seed).into_iter().enumerate() {
+        match index % 2 {
+            0 => root.entries.push(Entry::File(File::new(name, data))),
+            _ => subdirectory.entries.push(Entry::File(File::new(name, data))),
+        }
+    }
+    if !subdirectory.entries.is_empty() {
+        root.entries.push(Entry::Directory(subdirectory));
+    }
+    ResourceArchive::new(root).to_bytes()
+}
+
+/// Generates a standalone Godot 3 resource pack.
+#[must_use]
+pub fn pck(seed: u64) -> Box<[u8]> {
+    let mut output = Cursor::new(Vec::new());
+    // Writing to memory can't fail
+    let _ = ResourcePack::create(&mut output, &files(seed), (3, 5, 0));
+    output.into_inner().into_boxed_slice()
+}
+
+/// Generates a big endian BFSAR header, with empty string, info, and file blocks.
+///
+/// Sound archives have a fixed structure, so the seed only changes the version number.
+#[must_use]
+pub fn bfsar(seed: u64) -> Box<[u8]> {
+    fn block(magic: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut block = magic.to_vec();
+        block.extend_from_slice(&(8 + body.len() as u32).to_be_bytes());
+        block.extend_from_slice(body);
+        block
+    }
+    // References are an identifier, padding, then an offset relative to the start of the block's body
+    fn reference(identifier: u16, offset: u32) -> [u8; 8] {
+        let mut reference = [0u8; 8];
+        reference[..2].copy_from_slice(&identifier.to_be_bytes());
+        reference[4..].copy_from_slice(&offset.to_be_bytes());
+        reference
+    }
+
+    // An empty string table, followed by a Patricia tree without any nodes, so its root index is 0xFFFFFFFF
+    let mut strings = [reference(0x2400, 0x10), reference(0x2401, 0x14)].concat();
+    strings.extend_from_slice(&[0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0]);
+    let strings = block(b"STRG", &strings);
+
+    // An empty sound table, with every other section pointing at it too since they're all skipped
+    let mut info: Vec<u8> = (0x2100..0x2107).chain([0x220B]).flat_map(|id| reference(id, 0x40)).collect();
+    info.extend_from_slice(&0u32.to_be_bytes());
+    let info = block(b"INFO", &info);
+    let file = block(b"FILE", &[]);
+
+    let offsets = [0x40, 0x40 + strings.len(), 0x40 + strings.len() + info.len()];
+    let file_size = offsets[2] + file.len();
+
+    let mut output = Switch::BFSAR::MAGIC.to_vec();
+    output.extend_from_slice(&0xFEFFu16.to_be_bytes());
+    output.extend_from_slice(&0x40u16.to_be_bytes());
+    output.extend_from_slice(&[0, 2 + (seed % 4) as u8, 0, 0]);
+    output.extend_from_slice(&(file_size as u32).to_be_bytes());
+    output.extend_from_slice(&3u16.to_be_bytes());
+    output.extend_from_slice(&[0, 0]);
+    for ((identifier, offset), size) in
+        [0x2000u16, 0x2001, 0x2002].into_iter().zip(offsets).zip([strings.len(), info.len(), file.len()])
+    {
+        output.extend_from_slice(&reference(identifier, offset as u32));
+        output.extend_from_slice(&(size as u32).to_be_bytes());
+    }
+    output.resize(0x40, 0);
+
+    output.extend(strings);
+    output.extend(info);
+    output.extend(file);
+    output.into_boxed_slice()
+}
+
+/// Generates a Switch BARS archive with one single-channel BWAV per file, alternating between PCM16 and DSP
+/// ADPCM, each named after its file without the extension.
+#[must_use]
+pub fn bars(seed: u64) -> Box<[u8]> {
+    // AMTA blocks are only read for their name, so every other section offset is left as zero
+    fn amta(name: &str) -> Vec<u8> {
+        let mut strings = name.as_bytes().to_vec();
+        strings.resize((name.len() + 4) & !3, 0);
+        let mut amta = Switch::BARS::AMTA_MAGIC.to_vec();
+        amta.extend_from_slice(&0xFEFFu16.to_le_bytes());
+        amta.extend_from_slice(&0x0400u16.to_le_bytes());
+        amta.extend_from_slice(&(0x24 + strings.len() as u32).to_le_bytes());
+        amta.extend_from_slice(&[0; 12]);
+        amta.extend_from_slice(&0x1Cu32.to_le_bytes());
+        amta.extend_from_slice(&Switch::BARS::STRG_MAGIC);
+        amta.extend_from_slice(&(strings.len() as u32).to_le_bytes());
+        amta.extend(strings);
+        amta
+    }
+
+    fn bwav(index: usize, data: &[u8]) -> Vec<u8> {
+        // DSP ADPCM stores 14 samples in each 8-byte frame
+        let (codec, samples, data) = match index % 2 {
+            0 => (0u16, data.len() / 2, &data[..data.len() & !1]),
+            _ => (1u16, data.len() / 8 * 14, &data[..data.len() & !7]),
+        };
+        let mut bwav = Switch::BWAV::MAGIC.to_vec();
+        bwav.extend_from_slice(&0xFEFFu16.to_le_bytes());
+        bwav.extend_from_slice(&1u16.to_le_bytes());
+        bwav.extend_from_slice(&crc32(data).to_le_bytes());
+        bwav.extend_from_slice(&0u16.to_le_bytes());
+        bwav.extend_from_slice(&1u16.to_le_bytes());
+
+        bwav.extend_from_slice(&codec.to_le_bytes());
+        bwav.extend_from_slice(&2u16.to_le_bytes());
+        bwav.extend_from_slice(&48000u32.to_le_bytes());
+        bwav.extend_from_slice(&(samples as u32).to_le_bytes());
+        bwav.extend_from_slice(&(samples as u32).to_le_bytes());
+        for coefficient in [0x800i16, -0x400].repeat(8) {
+            bwav.extend_from_slice(&coefficient.to_le_bytes());
+        }
+        bwav.extend_from_slice(&0u32.to_le_bytes());
+        bwav.extend_from_slice(&0x60u32.to_le_bytes());
+        bwav.extend_from_slice(&0u32.to_le_bytes());
+        bwav.extend_from_slice(&u32::MAX.to_le_bytes());
+        bwav.extend_from_slice(&0u32.to_le_bytes());
+        bwav.extend_from_slice(&u16::from(data[0]).to_le_bytes());
+        bwav.resize(0x60, 0);
+        bwav.extend_from_slice(data);
+        bwav
+    }
+
+    // The runtime binary searches the hash table, so entries are sorted by the hash of their name
+    let mut entries: Vec<(u32, Vec<u8>, Vec<u8>)> = files(seed)
+        .iter()
+        .enumerate()
+        .map(|(index, (name, data))| {
+            let name = name.trim_end_matches(".bin");
+            (crc32(name.as_bytes()), amta(name), bwav(index, data))
+        })
+        .collect();
+    entries.sort_unstable_by_key(|(hash, ..)| *hash);
+
+    let mut body = Vec::new();
+    let mut offsets = Vec::new();
+    let header_size = 0x10 + entries.len() * 12;
+    for (_, amta, bwav) in &entries {
+        offsets.push((header_size + body.len()) as u32);
+        body.extend_from_slice(amta);
+        offsets.push((header_size + body.len()) as u32);
+        body.extend_from_slice(bwav);
+    }
+
+    let mut output = Switch::BARS::MAGIC.to_vec();
+    output.extend_from_slice(&((header_size + body.len()) as u32).to_le_bytes());
+    output.extend_from_slice(&0xFEFFu16.to_le_bytes());
+    output.extend_from_slice(&0x0102u16.to_le_bytes());
+    output.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+    for (hash, ..) in &entries {
+        output.extend_from_slice(&hash.to_le_bytes());
+    }
+    for offset in offsets {
+        output.extend_from_slice(&offset.to_le_bytes());
+    }
+    output.extend(body);
+    output.into_boxed_slice()
+}
+
+/// Generates a Switch Opus stream with one packet per file, using the file's contents as the packet. The
+/// packets aren't valid Opus data, so the stream can be parsed but not decoded.
+#[must_use]
+pub fn opus(seed: u64) -> Box<[u8]> {
+    let mut output = Switch::OpusStream::HEADER_CHUNK.to_le_bytes().to_vec();
+    output.extend_from_slice(&0x20u32.to_le_bytes());
+    output.extend_from_slice(&[0, 1 + (seed % 2) as u8, 0, 0]);
+    output.extend_from_slice(&48000u32.to_le_bytes());
+    output.extend_from_slice(&0x28u32.to_le_bytes());
+    output.extend_from_slice(&[0; 8]);
+    output.extend_from_slice(&0x138u16.to_le_bytes());
+    output.resize(0x28, 0);
+
+    // Each frame has a big endian size and final range in front of its packet
+    let frames: Vec<u8> = files(seed)
+        .iter()
+        .flat_map(|(_, data)| {
+            let mut frame = (data.len() as u32).to_be_bytes().to_vec();
+            frame.extend_from_slice(&crc32(data).to_be_bytes());
+            frame.extend_from_slice(data);
+            frame
+        })
+        .collect();
+    output.extend_from_slice(&Switch::OpusStream::DATA_CHUNK.to_le_bytes());
+    output.extend_from_slice(&(frames.len() as u32).to_le_bytes());
+    output.extend(frames);
+    output.into_boxed_slice()
+}
+
+/// Saves the output of every generator for each seed in `seeds` to `output`, as
+/// `{format}/{seed}.{extension}`, returning how many files were written.
+///
+/// # Errors
+/// Returns an error if unable to create the folders or write a file.
+pub fn write<P: AsRef<Path>>(output: P, seeds: core::ops::Range<u64>) -> std::io::Result<usize> {
+    let mut count = 0;
+    for (format, extension, generate) in &GENERATORS {
+        let folder = output.as_ref().join(format);
+        std::fs::create_dir_all(&folder)?;
+        for seed in seeds.clone() {
+            std::fs::write(folder.join(format!("{seed}.{extension}")), generate(seed))?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
@@ -0,0 +1,278 @@
+//! Helpers for the folder layouts produced by common dumping tools, so a dump can be used directly without
+//! having to know where each tool puts the game's files.
+//!
+//! Supported layouts:
+//! * Dolphin "Extract Entire Disc" - `sys/` (disc header, DOL, FST) and `files/`, optionally inside of a
+//!   partition folder such as `DATA/` for Wii discs
+//! * Switch - `romfs/` and `exefs/`, as produced by hactool, Ryujinx, yuzu, and most mod loaders
+//!
+//! Game paths are always `/`-separated and relative to the root of the game's file system, e.g.
+//! `/Effect/common.szs`. GameCube and Wii games look up files case-insensitively, so paths into a Dolphin
+//! dump are resolved case-insensitively, while Switch romfs paths have to match exactly.
+//!
+//! # Usage
+//! ```no_run
+//! use orthrus_tools::dump::Dump;
+//!
+//! let dump = Dump::open("extracted/GALE01")?;
+//! if let Some(path) = dump.resolve("/audio/menu01.hps") {
+//!     println!("Found at {}", path.display());
+//! }
+//! for path in dump.files()? {
+//!     println!("{path}: {}", orthrus_tools::dump::label(&path).unwrap_or("unknown"));
+//! }
+//! # Ok::<(), orthrus_tools::dump::Error>(())
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use snafu::prelude::*;
+
+use crate::title::Title;
+
+/// Error conditions for when opening a dump.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown if unable to read the dump's folders.
+    #[snafu(display("Filesystem Error {}", source))]
+    FileError { source: std::io::Error },
+
+    /// Thrown if the folder doesn't match any known dump layout.
+    #[snafu(display("Unable to recognize the layout of {}!", path.display()))]
+    UnknownLayout { path: PathBuf },
+}
+type Result<T> = core::result::Result<T, Error>;
+
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(source: std::io::Error) -> Self {
+        Error::FileError { source }
+    }
+}
+
+/// Known dump layouts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// GameCube or Wii disc extracted by Dolphin, with `sys/` and `files/` folders
+    Dolphin,
+    /// Switch title with `romfs/` and/or `exefs/` folders
+    Switch,
+}
+
+impl Layout {
+    /// Returns whether game paths should be matched case-insensitively for this layout.
+    #[must_use]
+    #[inline]
+    pub const fn case_insensitive(self) -> bool {
+        match self {
+            Self::Dolphin => true,
+            Self::Switch => false,
+        }
+    }
+
+    /// Returns the names of the folders containing the game's file system and its system files.
+    #[must_use]
+    #[inline]
+    const fn folders(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Dolphin => ("files", "sys"),
+            Self::Switch => ("romfs", "exefs"),
+        }
+    }
+}
+
+/// Partition folders that Dolphin may place a Wii disc's contents in.
+const DOLPHIN_PARTITIONS: [&str; 3] = ["DATA", "P-DATA", "UPDATE"];
+
+/// An extracted game dump, see the [module documentation](self) for more information.
+#[derive(Debug, Clone)]
+pub struct Dump {
+    root: PathBuf,
+    layout: Layout,
+}
+
+impl Dump {
+    /// Detects the layout of a dump, given either the dump's root folder or one of its partition folders.
+    ///
+    /// # Errors
+    /// Returns [`UnknownLayout`](Error::UnknownLayout) if no known layout is found.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let is_dir = |name: &str| path.join(name).is_dir();
+
+        if is_dir("sys") && is_dir("files") {
+            return Ok(Self { root: path.to_path_buf(), layout: Layout::Dolphin });
+        }
+        if is_dir("romfs") || is_dir("exefs") {
+            return Ok(Self { root: path.to_path_buf(), layout: Layout::Switch });
+        }
+        // Wii discs are extracted one folder per partition, so use the game's data partition
+        for partition in DOLPHIN_PARTITIONS {
+            let root = path.join(partition);
+            if root.join("sys").is_dir() && root.join("files").is_dir() {
+                return Ok(Self { root, layout: Layout::Dolphin });
+            }
+        }
+        UnknownLayoutSnafu { path }.fail()
+    }
+
+    /// Returns the detected layout.
+    #[must_use]
+    #[inline]
+    pub const fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Returns the folder containing the game's file system.
+    #[must_use]
+    #[inline]
+    pub fn files_root(&self) -> PathBuf {
+        self.root.join(self.layout.folders().0)
+    }
+
+    /// Returns the folder containing system files, such as `main.dol` or the Switch `main` NSO.
+    #[must_use]
+    #[inline]
+    pub fn system_root(&self) -> PathBuf {
+        self.root.join(self.layout.folders().1)
+    }
+
+    /// Returns where a game path is stored on disk, following the layout's casing rules, or [`None`] if it
+    /// doesn't exist.
+    #[must_use]
+    pub fn resolve(&self, path: &str) -> Option<PathBuf> {
+        let mut current = self.files_root();
+        for component in path.split('/').filter(|component| !component.is_empty()) {
+            if component == "." || component == ".." {
+                return None;
+            }
+            let exact = current.join(component);
+            current = match exact.exists() || !self.layout.case_insensitive() {
+                true => exact,
+                false => std::fs::read_dir(&current)
+                    .ok()?
+                    .filter_map(core::result::Result::ok)
+                    .find(|entry| entry.file_name().to_string_lossy().eq_ignore_ascii_case(component))?
+                    .path(),
+            };
+        }
+        current.exists().then_some(current)
+    }
+
+    /// Returns which game this dump is, read from `sys/boot.bin` and `opening.bnr` for Dolphin dumps, or
+    /// `control.nacp` for Switch dumps. Returns [`None`] if the metadata is missing or can't be parsed.
+    #[must_use]
+    pub fn title(&self) -> Option<Title> {
+        match self.layout {
+            Layout::Dolphin => {
+                let mut title =
+                    Title::from_disc_header(&std::fs::read(self.system_root().join("boot.bin")).ok()?)?;
+                // The header doesn't store the publisher, so take it from the banner if there is one
+                let banner = self.resolve("opening.bnr").and_then(|path| std::fs::read(path).ok());
+                if let Some(banner) = banner.as_deref().and_then(Title::from_banner) {
+                    title.publisher = banner.publisher;
+                }
+                Some(title)
+            }
+            // The control data is extracted separately from the program, so check next to and below the root
+            Layout::Switch => ["control.nacp", "control/control.nacp"]
+                .into_iter()
+                .find_map(|path| Title::from_nacp(&std::fs::read(self.root.join(path)).ok()?)),
+        }
+    }
+
+    /// Returns the game path of every file in the dump's file system, sorted.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if any folder can't be read.
+    pub fn files(&self) -> Result<Vec<String>> {
+        fn collect(path: &Path, prefix: &str, files: &mut Vec<String>) -> std::io::Result<()> {
+            for entry in std::fs::read_dir(path)? {
+                let entry = entry?;
+                let name = format!("{prefix}/{}", entry.file_name().to_string_lossy());
+                match entry.file_type()?.is_dir() {
+                    true => collect(&entry.path(), &name, files)?,
+                    false => files.push(name),
+                }
+            }
+            Ok(())
+        }
+
+        let mut files = Vec::new();
+        let root = self.files_root();
+        if root.is_dir() {
+            collect(&root, "", &mut files)?;
+        }
+        files.sort();
+        Ok(files)
+    }
+}
+
+/// Conventional locations of files, as (folder, file suffix, label), checked in order.
+///
+/// An empty folder matches any location.
+static CONVENTIONS: &[(&str, &str, &str)] = &[
+    // Dolphin system files
+    ("sys", "boot.bin", "GameCube/Wii disc header"),
+    ("sys", "bi2.bin", "GameCube/Wii disc header information"),
+    ("sys", "apploader.img", "GameCube/Wii apploader"),
+    ("sys", "fst.bin", "GameCube/Wii file system table"),
+    ("sys", "main.dol", "DOL executable"),
+    // Switch system files
+    ("exefs", "main.npdm", "Switch program metadata (NPDM)"),
+    ("exefs", "main", "Switch executable (NSO)"),
+    ("exefs", "rtld", "Switch executable (NSO)"),
+    ("exefs", "sdk", "Switch executable (NSO)"),
+    // Common first-party folder names
+    ("effect", ".szs", "Yaz0-compressed effect archive"),
+    ("effect", ".jpc", "JParticle effect container"),
+    ("stage", ".szs", "Yaz0-compressed stage archive"),
+    ("stage", ".arc", "Stage archive (RARC)"),
+    ("objectdata", ".arc", "Object archive (RARC)"),
+    ("sound", ".bfsar", "Sound archive (BFSAR)"),
+    ("stream", ".brstm", "Streamed audio (BRSTM)"),
+    // Fallbacks based on extension alone
+    ("", ".szs", "Yaz0-compressed archive"),
+    ("", ".szp", "Yay0-compressed archive"),
+    ("", ".arc", "Archive (RARC, possibly compressed)"),
+    ("", ".rel", "Relocatable module (REL)"),
+    ("", ".brstm", "Streamed audio (BRSTM)"),
+    ("", ".bfsar", "Sound archive (BFSAR)"),
+    ("", ".bntx", "Texture container (BNTX)"),
+    ("", ".bfres", "Resource container (BFRES)"),
+    ("", ".mf", "Panda3D Multifile"),
+    ("", ".pck", "Godot resource pack (PCK)"),
+];
+
+/// Returns a label for a file based only on its conventional location, such as `.szs` files under an
+/// `Effect` folder, for use when a file's contents alone aren't enough to identify it.
+///
+/// Folder names are matched case-insensitively against any parent folder in the path.
+///
+/// # Examples
+/// ```
+/// use orthrus_tools::dump::label;
+///
+/// assert_eq!(label("/Effect/common.szs"), Some("Yaz0-compressed effect archive"));
+/// assert_eq!(label("files/Data/menu.szs"), Some("Yaz0-compressed archive"));
+/// assert_eq!(label("exefs/main"), Some("Switch executable (NSO)"));
+/// assert_eq!(label("readme.txt"), None);
+/// ```
+#[must_use]
+pub fn label(path: &str) -> Option<&'static str> {
+    let path = path.replace('\\', "/").to_ascii_lowercase();
+    let (parents, name) = path.rsplit_once('/').unwrap_or(("", &path));
+    CONVENTIONS
+        .iter()
+        .find(|(folder, suffix, _)| {
+            let in_folder = folder.is_empty() || parents.split('/').any(|parent| parent == *folder);
+            // Extensions can match any name, but full file names have to match exactly
+            let named = match suffix.starts_with('.') {
+                true => name.ends_with(suffix),
+                false => name == *suffix,
+            };
+            in_folder && named
+        })
+        .map(|(_, _, label)| *label)
+}
@@ -0,0 +1,19 @@
+//! This crate contains modules for [Orthrus](https://crates.io/crates/orthrus) that build on top of the
+//! individual format crates, for workflows that span multiple formats at once.
+//!
+//! Unlike the format crates, this crate always requires `std`. The `crypto` feature enables reading encrypted
+//! Switch NCAs, using keys provided by the user.
+
+pub mod cache;
+pub mod corpus;
+pub mod dump;
+pub mod manifest;
+#[cfg(feature = "crypto")]
+pub mod nca;
+pub mod patch;
+pub mod pfs;
+pub mod pipeline;
+pub mod plugin;
+pub mod prelude;
+pub mod romfs;
+pub mod title;
@@ -0,0 +1,341 @@
+//! Integrity manifests for extracted file trees, to check that a tree still matches a known game version.
+//!
+//! A manifest lists every file's path, size, and MD5, along with which archive (and which member of that
+//! archive) it was extracted from, if known. Manifests can be built from a folder on disk, or one file at a
+//! time while extracting, and are stored as plain text with one file per line, as its path, size, MD5, and
+//! optionally archive and member, all separated by tabs.
+//!
+//! # Usage
+//! ```
+//! use orthrus_tools::manifest::{Manifest, Source, Status};
+//!
+//! # let folder = std::env::temp_dir().join(format!("orthrus-manifest-{}", std::process::id()));
+//! # std::fs::create_dir_all(&folder)?;
+//! std::fs::write(folder.join("stage.bin"), b"stage data")?;
+//!
+//! let mut manifest = Manifest::new();
+//! manifest.insert("stage.bin", b"stage data", Some(Source::new("Stage.arc", "stage/stage.bin")));
+//! manifest.insert("missing.bin", b"", None);
+//!
+//! // Manifests round-trip through their text form
+//! let manifest: Manifest = manifest.to_string().parse()?;
+//! let mismatches = manifest.verify(&folder)?;
+//! assert_eq!(mismatches.len(), 1);
+//! assert_eq!((mismatches[0].path.as_str(), mismatches[0].status), ("missing.bin", Status::Missing));
+//! # std::fs::remove_dir_all(folder)?;
+//! # Ok::<(), orthrus_tools::manifest::Error>(())
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+
+use orthrus_core::cancel::{CancellationToken, Progress};
+use orthrus_core::checksum::{md5, HashingReader, Md5};
+use orthrus_core::sanitize::{PathError, PathOptions};
+use snafu::prelude::*;
+
+/// Error conditions for when building, parsing, or verifying a manifest.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown if unable to read a file or folder.
+    #[snafu(display("Filesystem Error {}", source))]
+    FileError { source: std::io::Error },
+
+    /// Thrown if a line of a manifest doesn't have the expected fields.
+    #[snafu(display("Invalid manifest entry on line {}", line))]
+    InvalidEntry { line: usize },
+
+    /// Thrown if a file's path can't safely be joined onto the root folder, see [`PathOptions`].
+    #[snafu(display("{source}"))]
+    InvalidPath { source: PathError },
+}
+type Result<T> = core::result::Result<T, Error>;
+
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(source: std::io::Error) -> Self {
+        Error::FileError { source }
+    }
+}
+
+impl From<PathError> for Error {
+    #[inline]
+    fn from(source: PathError) -> Self {
+        Error::InvalidPath { source }
+    }
+}
+
+/// Where a file was extracted from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Source {
+    /// Path of the archive the file was stored in
+    pub archive: String,
+    /// Path of the file inside of the archive
+    pub member: String,
+}
+
+impl Source {
+    /// Creates a new source from an archive and the path of the file inside of it.
+    #[inline]
+    pub fn new<A: Into<String>, M: Into<String>>(archive: A, member: M) -> Self {
+        Self { archive: archive.into(), member: member.into() }
+    }
+}
+
+/// A single file listed in a [`Manifest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Path relative to the root of the tree, using `/` as the separator
+    pub path: String,
+    /// Size of the file, in bytes
+    pub size: u64,
+    /// MD5 of the file's contents
+    pub md5: [u8; 16],
+    /// Where the file was extracted from, if known
+    pub source: Option<Source>,
+}
+
+/// How a file differs from its [`Manifest`] entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The file exists but its contents have changed
+    Modified,
+    /// The file doesn't exist
+    Missing,
+}
+
+/// A file that doesn't match its [`Manifest`] entry, returned by [`Manifest::verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub path: String,
+    pub status: Status,
+}
+
+/// A list of files and their hashes, see the [module documentation](self) for more information.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Manifest {
+    entries: BTreeMap<String, Entry>,
+}
+
+/// Converts a path to the form stored in a manifest, with `/` separators and no leading slash.
+fn normalize(path: &str) -> String {
+    path.replace('\\', "/").trim_start_matches('/').to_owned()
+}
+
+/// Returns the size and MD5 of a file on disk, without reading all of it into memory at once.
+fn hash_file(path: &Path) -> std::io::Result<(u64, [u8; 16])> {
+    let mut reader = HashingReader::new(File::open(path)?, Md5::new());
+    let size = std::io::copy(&mut reader, &mut std::io::sink())?;
+    Ok((size, reader.finalize().1))
+}
+
+impl Manifest {
+    /// Creates an empty manifest.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a manifest from every file inside of `root`, without any sources.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if any file or folder can't be read.
+    #[inline]
+    pub fn from_dir<P: AsRef<Path>>(root: P) -> Result<Self> {
+        Self::from_dir_with_cancellation(root, &CancellationToken::new()).map(Progress::into_inner)
+    }
+
+    /// Builds a manifest from every file inside of `root` the same way as [`from_dir`](Self::from_dir), but
+    /// stops once `token` is cancelled. The manifest then only lists the files that were hashed before that.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if any file or folder can't be read.
+    pub fn from_dir_with_cancellation<P: AsRef<Path>>(
+        root: P, token: &CancellationToken,
+    ) -> Result<Progress<Self>> {
+        fn collect(
+            manifest: &mut Manifest, path: &Path, prefix: &str, token: &CancellationToken,
+        ) -> std::io::Result<()> {
+            for entry in std::fs::read_dir(path)? {
+                if token.is_cancelled() {
+                    return Ok(());
+                }
+                let entry = entry?;
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let name = match prefix.is_empty() {
+                    true => name,
+                    false => format!("{prefix}/{name}"),
+                };
+                match entry.file_type()?.is_dir() {
+                    true => collect(manifest, &entry.path(), &name, token)?,
+                    false => {
+                        let (size, md5) = hash_file(&entry.path())?;
+                        manifest.entries.insert(name.clone(), Entry { path: name, size, md5, source: None });
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        let mut manifest = Self::new();
+        collect(&mut manifest, root.as_ref(), "", token)?;
+        match token.is_cancelled() {
+            true => Ok(Progress::Cancelled(manifest)),
+            false => Ok(Progress::Complete(manifest)),
+        }
+    }
+
+    /// Reads a manifest previously written with [`save`](Self::save).
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to read the file, or
+    /// [`InvalidEntry`](Error::InvalidEntry) if it isn't a valid manifest.
+    #[inline]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// Writes the manifest to a file.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to write the file.
+    #[inline]
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        Ok(std::fs::write(path, self.to_string())?)
+    }
+
+    /// Adds a file with the given contents, replacing any previous entry for the same path.
+    #[inline]
+    pub fn insert(&mut self, path: &str, data: &[u8], source: Option<Source>) {
+        let path = normalize(path);
+        let entry = Entry { path: path.clone(), size: data.len() as u64, md5: md5(data), source };
+        self.entries.insert(path, entry);
+    }
+
+    /// Returns the entry for `path`, if there is one.
+    #[must_use]
+    #[inline]
+    pub fn get(&self, path: &str) -> Option<&Entry> {
+        self.entries.get(&normalize(path))
+    }
+
+    /// Returns every entry, sorted by path.
+    #[inline]
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.values()
+    }
+
+    /// Returns the number of files in the manifest.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the manifest doesn't list any files.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Checks every file in the manifest against the tree in `root`, returning each file that's modified or
+    /// missing. Files that aren't listed in the manifest are ignored.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if a file exists but can't be read, or
+    /// [`InvalidPath`](Error::InvalidPath) if a file's path is unsafe, such as one containing `..`.
+    ///
+    /// # Examples
+    /// ```
+    /// use orthrus_tools::manifest::{Error, Manifest};
+    ///
+    /// // Paths are never allowed to point outside of the root
+    /// let mut manifest = Manifest::new();
+    /// manifest.insert("../outside.bin", b"data", None);
+    /// assert!(matches!(manifest.verify(std::env::temp_dir()), Err(Error::InvalidPath { .. })));
+    /// ```
+    #[inline]
+    pub fn verify<P: AsRef<Path>>(&self, root: P) -> Result<Vec<Mismatch>> {
+        self.verify_with_cancellation(root, &CancellationToken::new()).map(Progress::into_inner)
+    }
+
+    /// Checks every file in the manifest the same way as [`verify`](Self::verify), but stops once `token` is
+    /// cancelled. Only the mismatches found before that are returned, so files that weren't checked yet
+    /// aren't reported either way.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if a file exists but can't be read, or
+    /// [`InvalidPath`](Error::InvalidPath) if a file's path is unsafe, such as one containing `..`.
+    pub fn verify_with_cancellation<P: AsRef<Path>>(
+        &self, root: P, token: &CancellationToken,
+    ) -> Result<Progress<Vec<Mismatch>>> {
+        let root = root.as_ref();
+        let mut mismatches = Vec::new();
+        for entry in self.entries.values() {
+            if token.is_cancelled() {
+                return Ok(Progress::Cancelled(mismatches));
+            }
+            let path = PathOptions::DEFAULT.join(root, &entry.path)?;
+            let status = match std::fs::metadata(&path) {
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => Some(Status::Missing),
+                Err(error) => return Err(error.into()),
+                Ok(metadata) if !metadata.is_file() => Some(Status::Missing),
+                // Only hash the file if the size matches, since that's much cheaper to check
+                Ok(metadata) if metadata.len() != entry.size => Some(Status::Modified),
+                Ok(_) => (hash_file(&path)?.1 != entry.md5).then_some(Status::Modified),
+            };
+            if let Some(status) = status {
+                mismatches.push(Mismatch { path: entry.path.clone(), status });
+            }
+        }
+        Ok(Progress::Complete(mismatches))
+    }
+}
+
+impl core::fmt::Display for Manifest {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for entry in self.entries.values() {
+            write!(f, "{}\t{}\t", entry.path, entry.size)?;
+            entry.md5.iter().try_for_each(|byte| write!(f, "{byte:02x}"))?;
+            if let Some(source) = &entry.source {
+                write!(f, "\t{}\t{}", source.archive, source.member)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl core::str::FromStr for Manifest {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let mut manifest = Self::new();
+        for (index, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let invalid = || InvalidEntrySnafu { line: index + 1 }.build();
+            let fields: Vec<&str> = line.split('\t').collect();
+            let (path, size, hash, source) = match fields[..] {
+                [path, size, hash] => (path, size, hash, None),
+                [path, size, hash, archive, member] => (path, size, hash, Some(Source::new(archive, member))),
+                _ => return Err(invalid()),
+            };
+
+            ensure!(
+                hash.len() == 32 && hash.is_ascii(),
+                InvalidEntrySnafu { line: index + 1 }
+            );
+            let mut md5 = [0u8; 16];
+            for (i, byte) in md5.iter_mut().enumerate() {
+                *byte = u8::from_str_radix(&hash[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+            }
+            let size = size.parse().map_err(|_| invalid())?;
+            let path = normalize(path);
+            manifest.entries.insert(path.clone(), Entry { path, size, md5, source });
+        }
+        Ok(manifest)
+    }
+}
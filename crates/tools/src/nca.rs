@@ -0,0 +1,500 @@
+//! Reader for Switch NCA (Nintendo Content Archive) files, to get at the RomFS of a game dump without having
+//! to extract it with another tool first.
+//!
+//! NCAs are encrypted, so reading one requires the console's keys, which have to be dumped from the user's
+//! own console and passed in with [`Keys`]. No keys are included with Orthrus. Only NCA3 files with plain or
+//! AES-CTR sections are supported, which covers base games and DLC; update NCAs that patch a base game's
+//! RomFS can't be read on their own.
+//!
+//! # Usage
+//! ```no_run
+//! use orthrus_tools::nca::{Keys, Nca};
+//! use orthrus_tools::pfs::PartitionFs;
+//!
+//! let mut keys = Keys::open("prod.keys")?;
+//! keys.load("title.keys")?;
+//!
+//! // NSP packages hold each NCA as a file
+//! let mut package = PartitionFs::new(std::fs::File::open("game.nsp")?)?;
+//! let mut nca = Nca::new(package.open("0123456789abcdef0123456789abcdef.nca")?, &keys)?;
+//! nca.romfs()?.extract("extracted")?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use orthrus_core::aes::{Aes128, Xts, BLOCK_SIZE};
+use snafu::prelude::*;
+
+use crate::romfs::{self, RomFs};
+
+/// Error conditions for when reading keys or NCAs.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown if unable to read a file.
+    #[snafu(display("Filesystem Error {}", source))]
+    FileError { source: std::io::Error },
+
+    /// Thrown if a line of a key file isn't a name and a hexadecimal key.
+    #[snafu(display("Invalid key on line {}", line))]
+    InvalidKey { line: usize },
+
+    /// Thrown if a key needed to decrypt the NCA wasn't provided.
+    #[snafu(display("Missing key {}!", name))]
+    MissingKey { name: String },
+
+    /// Thrown if the header doesn't decrypt to a valid NCA3 header, usually because of a wrong header key.
+    #[snafu(display("Invalid NCA header!"))]
+    InvalidHeader,
+
+    /// Thrown if a section uses an encryption type that isn't supported.
+    #[snafu(display("Unsupported encryption for section {}: {:?}", index, encryption))]
+    UnsupportedEncryption { index: usize, encryption: Encryption },
+
+    /// Thrown if trying to open a section that doesn't exist, or the NCA doesn't have a RomFS.
+    #[snafu(display("Unable to find the requested section in the NCA!"))]
+    SectionNotFound,
+
+    /// Thrown if the RomFS inside of the NCA can't be read.
+    #[snafu(display("{source}"))]
+    RomFsError { source: romfs::Error },
+}
+type Result<T> = core::result::Result<T, Error>;
+
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(source: std::io::Error) -> Self {
+        Error::FileError { source }
+    }
+}
+
+impl From<romfs::Error> for Error {
+    #[inline]
+    fn from(source: romfs::Error) -> Self {
+        Error::RomFsError { source }
+    }
+}
+
+/// Parses a string of hexadecimal digits into bytes.
+fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len()).step_by(2).map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok()).collect()
+}
+
+/// Keys used to decrypt NCAs, as dumped from a console.
+///
+/// These are read from text files with one `name = key` pair per line, in the same format as `prod.keys` and
+/// `title.keys` from Lockpick and hactool. Entries named with a 32-digit rights ID are treated as title keys.
+#[derive(Clone, Default)]
+pub struct Keys {
+    keys: HashMap<String, Vec<u8>>,
+    title_keys: HashMap<[u8; 16], [u8; 16]>,
+}
+
+impl core::fmt::Debug for Keys {
+    // Only print which keys are loaded, never the keys themselves
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Keys")
+            .field("keys", &self.keys.keys().collect::<Vec<_>>())
+            .field("title_keys", &self.title_keys.len())
+            .finish()
+    }
+}
+
+impl Keys {
+    /// Creates an empty set of keys.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads keys from a file, see [`Keys`] for the format.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to read the file, or
+    /// [`InvalidKey`](Error::InvalidKey) if a line isn't a valid key.
+    #[inline]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// Adds the keys from another file, such as `title.keys`, replacing any keys with the same name.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to read the file, or
+    /// [`InvalidKey`](Error::InvalidKey) if a line isn't a valid key.
+    #[inline]
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        self.parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Adds every key listed in `text`, replacing any keys with the same name.
+    ///
+    /// # Errors
+    /// Returns [`InvalidKey`](Error::InvalidKey) if a line isn't a valid key.
+    pub fn parse(&mut self, text: &str) -> Result<()> {
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with([';', '#']) {
+                continue;
+            }
+            let invalid = || InvalidKeySnafu { line: index + 1 }.build();
+            let (name, key) = line.split_once('=').ok_or_else(invalid)?;
+            let (name, key) = (
+                name.trim().to_ascii_lowercase(),
+                parse_hex(key.trim()).ok_or_else(invalid)?,
+            );
+            match (name.len() == 32).then(|| parse_hex(&name)).flatten() {
+                Some(rights_id) => {
+                    let rights_id = rights_id.try_into().map_err(|_| invalid())?;
+                    self.title_keys.insert(rights_id, key.try_into().map_err(|_| invalid())?);
+                }
+                None => {
+                    self.keys.insert(name, key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds a single named key, such as `header_key`.
+    #[inline]
+    pub fn insert(&mut self, name: &str, key: &[u8]) {
+        self.keys.insert(name.to_ascii_lowercase(), key.to_vec());
+    }
+
+    /// Adds the title key for a rights ID.
+    #[inline]
+    pub fn insert_title_key(&mut self, rights_id: [u8; 16], key: [u8; 16]) {
+        self.title_keys.insert(rights_id, key);
+    }
+
+    /// Returns the key called `name`, if it was provided and is the right size.
+    fn get<const N: usize>(&self, name: &str) -> Result<[u8; N]> {
+        self.keys.get(name).and_then(|key| key.as_slice().try_into().ok()).context(MissingKeySnafu { name })
+    }
+}
+
+impl core::str::FromStr for Keys {
+    type Err = Error;
+
+    #[inline]
+    fn from_str(text: &str) -> Result<Self> {
+        let mut keys = Self::new();
+        keys.parse(text)?;
+        Ok(keys)
+    }
+}
+
+/// What kind of content an NCA holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentType {
+    /// Executable code and the game's RomFS
+    Program,
+    /// Content metadata, listing the other NCAs in a title
+    Meta,
+    /// Title name, icon, and other settings (NACP)
+    Control,
+    /// Offline HTML manuals
+    Manual,
+    /// System data
+    Data,
+    /// Add-on content
+    PublicData,
+}
+
+/// What file system a section contains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsType {
+    /// A RomFS, see [`romfs`](crate::romfs)
+    RomFs,
+    /// A PartitionFS, see [`pfs`](crate::pfs)
+    PartitionFs,
+}
+
+/// How a section is encrypted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encryption {
+    /// Stored as plain text
+    None,
+    /// AES-XTS, which isn't used by retail content
+    Xts,
+    /// AES-CTR, used by almost every section
+    Ctr,
+    /// AES-CTR with per-region counters, used by update patches
+    CtrEx,
+}
+
+/// One of the (up to four) file systems stored inside of an [`Nca`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// Index of the section in the NCA's header
+    pub index: usize,
+    /// What file system the section contains
+    pub fs_type: FsType,
+    /// How the section is encrypted
+    pub encryption: Encryption,
+    /// Offset of the section, relative to the start of the NCA
+    pub offset: u64,
+    /// Size of the section, in bytes
+    pub size: u64,
+    /// Offset and size of the file system inside of the section, after its hash tables
+    data: (u64, u64),
+    /// Upper half of the AES-CTR counter
+    counter: u64,
+}
+
+/// An NCA file, see the [module documentation](self) for more information.
+#[derive(Debug)]
+pub struct Nca<R> {
+    reader: R,
+    start: u64,
+    content_type: ContentType,
+    program_id: u64,
+    rights_id: [u8; 16],
+    sections: Vec<Section>,
+    key: Option<Aes128>,
+}
+
+impl<R: Read + Seek> Nca<R> {
+    /// Unique identifier that tells us if we're reading an NCA3 file.
+    pub const MAGIC: [u8; 4] = *b"NCA3";
+
+    /// Size of the encrypted header, including every section's header.
+    const HEADER_SIZE: usize = 0xC00;
+
+    /// NCA sizes and offsets are stored in units of this many bytes, which is also the XTS sector size.
+    const MEDIA_SIZE: u64 = 0x200;
+
+    /// Decrypts the header of the NCA that starts at the reader's current position, along with the key needed
+    /// to read its sections.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to read the file,
+    /// [`MissingKey`](Error::MissingKey) if a required key wasn't provided, or
+    /// [`InvalidHeader`](Error::InvalidHeader) if the header doesn't decrypt properly.
+    pub fn new(mut reader: R, keys: &Keys) -> Result<Self> {
+        let start = reader.stream_position()?;
+        let mut header = vec![0u8; Self::HEADER_SIZE];
+        reader.read_exact(&mut header)?;
+        Xts::new(&keys.get("header_key")?).decrypt(&mut header, Self::MEDIA_SIZE as usize, 0);
+        ensure!(header[0x200..0x204] == Self::MAGIC, InvalidHeaderSnafu);
+
+        let content_type = match header[0x205] {
+            0 => ContentType::Program,
+            1 => ContentType::Meta,
+            2 => ContentType::Control,
+            3 => ContentType::Manual,
+            4 => ContentType::Data,
+            5 => ContentType::PublicData,
+            _ => return InvalidHeaderSnafu.fail(),
+        };
+        let read_u32 = |offset: usize| u32::from_le_bytes(header[offset..offset + 4].try_into().unwrap());
+        let read_u64 = |offset: usize| u64::from_le_bytes(header[offset..offset + 8].try_into().unwrap());
+        let program_id = read_u64(0x210);
+        let rights_id: [u8; 16] = header[0x230..0x240].try_into().unwrap();
+
+        let mut sections = Vec::new();
+        for index in 0..4 {
+            let entry = 0x240 + index * 0x10;
+            let (begin, end) = (u64::from(read_u32(entry)), u64::from(read_u32(entry + 4)));
+            if end == 0 {
+                continue;
+            }
+            ensure!(begin < end, InvalidHeaderSnafu);
+
+            let fs_header = 0x400 + index * 0x200;
+            let (fs_type, data) = match header[fs_header + 2] {
+                // The RomFS is the last level of the integrity (IVFC) hash tree
+                0 => (
+                    FsType::RomFs,
+                    (read_u64(fs_header + 0x90), read_u64(fs_header + 0x98)),
+                ),
+                // The PartitionFS is the second region of the SHA-256 hash table
+                1 => (
+                    FsType::PartitionFs,
+                    (read_u64(fs_header + 0x40), read_u64(fs_header + 0x48)),
+                ),
+                _ => return InvalidHeaderSnafu.fail(),
+            };
+            let encryption = match header[fs_header + 4] {
+                1 => Encryption::None,
+                2 => Encryption::Xts,
+                3 => Encryption::Ctr,
+                4 => Encryption::CtrEx,
+                _ => return InvalidHeaderSnafu.fail(),
+            };
+            let (offset, size) = (begin * Self::MEDIA_SIZE, (end - begin) * Self::MEDIA_SIZE);
+            ensure!(
+                data.0.checked_add(data.1).is_some_and(|end| end <= size),
+                InvalidHeaderSnafu
+            );
+            let counter = read_u64(fs_header + 0x140);
+            sections.push(Section { index, fs_type, encryption, offset, size, data, counter });
+        }
+
+        let key = match sections.iter().any(|section| section.encryption == Encryption::Ctr) {
+            true => Some(Self::content_key(&header, keys)?),
+            false => None,
+        };
+        Ok(Self { reader, start, content_type, program_id, rights_id, sections, key })
+    }
+
+    /// Derives the key that sections are encrypted with, either from the title key for titles with a rights
+    /// ID, or the header's key area otherwise.
+    fn content_key(header: &[u8], keys: &Keys) -> Result<Aes128> {
+        // Key generation 0 and 1 both use the first master key, and the field was moved for later generations
+        let revision = header[0x206].max(header[0x220]).saturating_sub(1);
+        let rights_id: [u8; 16] = header[0x230..0x240].try_into().unwrap();
+
+        let mut key = [0u8; 16];
+        match rights_id == [0; 16] {
+            true => {
+                let area = match header[0x207] {
+                    0 => "application",
+                    1 => "ocean",
+                    2 => "system",
+                    _ => return InvalidHeaderSnafu.fail(),
+                };
+                key.copy_from_slice(&header[0x320..0x330]);
+                Aes128::new(&keys.get(&format!("key_area_key_{area}_{revision:02x}"))?).decrypt_ecb(&mut key);
+            }
+            false => {
+                let name = rights_id.iter().map(|byte| format!("{byte:02x}")).collect::<String>();
+                key = *keys.title_keys.get(&rights_id).context(MissingKeySnafu { name })?;
+                Aes128::new(&keys.get(&format!("titlekek_{revision:02x}"))?).decrypt_ecb(&mut key);
+            }
+        }
+        Ok(Aes128::new(&key))
+    }
+
+    /// Returns what kind of content the NCA holds.
+    #[must_use]
+    #[inline]
+    pub const fn content_type(&self) -> ContentType {
+        self.content_type
+    }
+
+    /// Returns the title ID of the program the NCA belongs to.
+    #[must_use]
+    #[inline]
+    pub const fn program_id(&self) -> u64 {
+        self.program_id
+    }
+
+    /// Returns the rights ID, for titles that are encrypted with a title key.
+    #[must_use]
+    #[inline]
+    pub fn rights_id(&self) -> Option<[u8; 16]> {
+        (self.rights_id != [0; 16]).then_some(self.rights_id)
+    }
+
+    /// Returns every section that's present in the NCA.
+    #[must_use]
+    #[inline]
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// Returns a reader over the decrypted file system stored in section `index`, which can be passed to
+    /// [`RomFs`] or [`PartitionFs`](crate::pfs::PartitionFs) depending on its [`FsType`].
+    ///
+    /// # Errors
+    /// Returns [`SectionNotFound`](Error::SectionNotFound) if the section doesn't exist, or
+    /// [`UnsupportedEncryption`](Error::UnsupportedEncryption) if its encryption isn't supported.
+    pub fn open_section(&mut self, index: usize) -> Result<SectionReader<'_, R>> {
+        let section =
+            self.sections.iter().find(|section| section.index == index).context(SectionNotFoundSnafu)?;
+        let key = match section.encryption {
+            Encryption::None => None,
+            Encryption::Ctr => self.key.as_ref(),
+            encryption => return UnsupportedEncryptionSnafu { index, encryption }.fail(),
+        };
+        Ok(SectionReader {
+            reader: &mut self.reader,
+            key,
+            counter: section.counter,
+            start: self.start,
+            offset: section.offset + section.data.0,
+            size: section.data.1,
+            position: 0,
+        })
+    }
+
+    /// Opens the RomFS stored in the first RomFS section.
+    ///
+    /// # Errors
+    /// Returns [`SectionNotFound`](Error::SectionNotFound) if the NCA doesn't have a RomFS,
+    /// [`UnsupportedEncryption`](Error::UnsupportedEncryption) if its encryption isn't supported, or
+    /// [`RomFsError`](Error::RomFsError) if it isn't a valid RomFS.
+    pub fn romfs(&mut self) -> Result<RomFs<SectionReader<'_, R>>> {
+        let section = self.sections.iter().find(|section| section.fs_type == FsType::RomFs);
+        let index = section.context(SectionNotFoundSnafu)?.index;
+        Ok(RomFs::new(self.open_section(index)?)?)
+    }
+}
+
+/// Reader over a single section of an [`Nca`], which decrypts data as it's read.
+#[derive(Debug)]
+pub struct SectionReader<'a, R> {
+    reader: &'a mut R,
+    key: Option<&'a Aes128>,
+    counter: u64,
+    /// Position of the NCA inside of the reader
+    start: u64,
+    /// Offset of the section's data, relative to the start of the NCA
+    offset: u64,
+    size: u64,
+    position: u64,
+}
+
+impl<R: Read + Seek> Read for SectionReader<'_, R> {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        // Limit how much is decrypted at once, since the whole buffer has to be copied
+        let length =
+            (buffer.len() as u64).min(self.size.saturating_sub(self.position)).min(0x10_0000) as usize;
+        if length == 0 {
+            return Ok(0);
+        }
+        let offset = self.offset + self.position;
+        let Some(key) = self.key else {
+            self.reader.seek(SeekFrom::Start(self.start + offset))?;
+            let length = self.reader.read(&mut buffer[..length])?;
+            self.position += length as u64;
+            return Ok(length);
+        };
+
+        // Counter mode can only start on a block boundary, and the counter is based on the offset in the NCA
+        let skip = (offset % BLOCK_SIZE as u64) as usize;
+        let aligned = offset - skip as u64;
+        let mut data = vec![0u8; (skip + length).next_multiple_of(BLOCK_SIZE)];
+        self.reader.seek(SeekFrom::Start(self.start + aligned))?;
+        self.reader.read_exact(&mut data)?;
+        key.apply_ctr(
+            u128::from(self.counter) << 64 | u128::from(aligned >> 4),
+            &mut data,
+        );
+
+        buffer[..length].copy_from_slice(&data[skip..skip + length]);
+        self.position += length as u64;
+        Ok(length)
+    }
+}
+
+impl<R> Seek for SectionReader<'_, R> {
+    fn seek(&mut self, position: SeekFrom) -> std::io::Result<u64> {
+        let position = match position {
+            SeekFrom::Start(position) => Some(position),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+        };
+        self.position = position.ok_or_else(|| std::io::Error::from(std::io::ErrorKind::InvalidInput))?;
+        Ok(self.position)
+    }
+}
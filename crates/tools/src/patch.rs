@@ -0,0 +1,588 @@
+//! Binary patches between an original and modified file, so mods can be distributed without including any
+//! of the original game data.
+//!
+//! Two formats are supported:
+//! * IPS - the classic format, limited to files up to 16 MiB, which only stores changed bytes at fixed offsets
+//! * BPS - the modern format, with no size limit, which can also copy moved data and validates every file
+//!   with a CRC-32
+//!
+//! BPS should be preferred unless a specific tool requires IPS, since it handles data being inserted or moved
+//! (such as a rebuilt archive) far better. xdelta/VCDIFF patches are not supported yet.
+//!
+//! # Usage
+//! ```
+//! use orthrus_tools::patch::{self, Format};
+//!
+//! let original = b"Hello, world! This is the original file.";
+//! let modified = b"Hello, world! This is the modified file, which is longer.";
+//!
+//! let bps = patch::create(Format::Bps, original, modified)?;
+//! assert_eq!(&*patch::apply(&bps, original)?, modified);
+//!
+//! let ips = patch::create(Format::Ips, original, modified)?;
+//! assert_eq!(&*patch::apply(&ips, original)?, modified);
+//! # Ok::<(), patch::Error>(())
+//! ```
+//!
+//! Patches can also be produced from the output of a [`Pipeline`](crate::pipeline::Pipeline), or applied as
+//! one of its steps using [`Pipeline::apply_patch`](crate::pipeline::Pipeline::apply_patch).
+
+use std::collections::HashMap;
+
+use orthrus_core::checksum::crc32;
+use orthrus_core::prelude::*;
+use snafu::prelude::*;
+
+/// Error conditions for when creating or applying patches.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown if the patch doesn't start with a known magic.
+    #[snafu(display("Unknown patch format! Expected IPS or BPS."))]
+    InvalidMagic,
+
+    /// Thrown if the patch ends unexpectedly, or refers to data outside of either file.
+    #[snafu(display("Patch is truncated or corrupted!"))]
+    InvalidPatch,
+
+    /// Thrown if a file is too large to be stored in the given patch format.
+    #[snafu(display("{format} patches only support files up to {limit:#X} bytes, got {size:#X}!"))]
+    TooLarge {
+        format: &'static str,
+        size: usize,
+        limit: usize,
+    },
+
+    /// Thrown if a checksum stored in a BPS patch doesn't match, such as when patching the wrong file.
+    #[snafu(display("{which} checksum mismatch! Expected {expected:#010X}, got {actual:#010X}."))]
+    ChecksumMismatch {
+        which: &'static str,
+        expected: u32,
+        actual: u32,
+    },
+
+    /// Thrown if the patched file is larger than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+}
+
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Self::LimitExceeded { source }
+    }
+}
+type Result<T> = core::result::Result<T, Error>;
+
+/// Supported patch formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// International Patching System
+    Ips,
+    /// Beat Patching System
+    Bps,
+}
+
+impl Format {
+    /// Returns the format of a patch based on its magic, if known.
+    #[must_use]
+    #[inline]
+    pub fn identify(patch: &[u8]) -> Option<Self> {
+        match patch {
+            [b'P', b'A', b'T', b'C', b'H', ..] => Some(Self::Ips),
+            [b'B', b'P', b'S', b'1', ..] => Some(Self::Bps),
+            _ => None,
+        }
+    }
+
+    /// Returns the usual file extension for this format.
+    #[must_use]
+    #[inline]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Ips => "ips",
+            Self::Bps => "bps",
+        }
+    }
+}
+
+/// Creates a patch in the given format, which turns `original` into `modified`.
+///
+/// # Errors
+/// Returns [`TooLarge`](Error::TooLarge) if the files can't be stored in the chosen format.
+#[inline]
+pub fn create(format: Format, original: &[u8], modified: &[u8]) -> Result<Box<[u8]>> {
+    match format {
+        Format::Ips => Ips::create(original, modified),
+        Format::Bps => Bps::create(original, modified),
+    }
+}
+
+/// Applies a patch to `original`, detecting its format automatically.
+///
+/// # Errors
+/// Returns [`InvalidMagic`](Error::InvalidMagic) if the patch format is unknown, or any error from the
+/// format-specific `apply`.
+#[inline]
+pub fn apply(patch: &[u8], original: &[u8]) -> Result<Box<[u8]>> {
+    apply_with_limits(patch, original, &ResourceLimits::UNLIMITED)
+}
+
+/// Applies a patch to `original`, detecting its format automatically, and stopping if the patched file would
+/// be larger than `limits.max_output_size` bytes. Use this instead of [`apply`] for untrusted patches.
+///
+/// # Errors
+/// Returns the same errors as [`apply`], or [`LimitExceeded`](Error::LimitExceeded) if the patched file is
+/// larger than the limit.
+#[inline]
+pub fn apply_with_limits(patch: &[u8], original: &[u8], limits: &ResourceLimits) -> Result<Box<[u8]>> {
+    match Format::identify(patch) {
+        Some(Format::Ips) => {
+            let output = Ips::apply(patch, original)?;
+            limits.check_output_size(output.len() as u64)?;
+            Ok(output)
+        }
+        Some(Format::Bps) => Bps::apply_with_limits(patch, original, limits),
+        None => InvalidMagicSnafu.fail(),
+    }
+}
+
+/// Simple cursor over a patch, which returns [`Error::InvalidPatch`] if it runs out of data.
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    #[inline]
+    fn read_slice(&mut self, length: usize) -> Result<&'a [u8]> {
+        let end = self.position.checked_add(length).context(InvalidPatchSnafu)?;
+        let slice = self.data.get(self.position..end).context(InvalidPatchSnafu)?;
+        self.position += length;
+        Ok(slice)
+    }
+
+    #[inline]
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_slice(1)?[0])
+    }
+
+    #[inline]
+    fn read_be(&mut self, length: usize) -> Result<usize> {
+        Ok(self.read_slice(length)?.iter().fold(0, |value, &byte| value << 8 | usize::from(byte)))
+    }
+
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.data.len() - self.position
+    }
+}
+
+/// International Patching System, which stores a list of offsets along with the bytes to write there.
+pub struct Ips;
+
+impl Ips {
+    /// Largest file size that can be patched, since offsets are stored as 24-bit values.
+    pub const MAX_SIZE: usize = 0x1000000;
+
+    /// Largest amount of data that a single record can contain.
+    const MAX_RECORD: usize = 0xFFFF;
+
+    /// Offset that would be read as the "EOF" marker, so records can't start here.
+    const EOF_OFFSET: usize = 0x454F46;
+
+    /// Minimum run length before a run of identical bytes is stored as an RLE record.
+    const MIN_RLE: usize = 8;
+
+    /// Creates an IPS patch which turns `original` into `modified`.
+    ///
+    /// If `modified` is smaller than `original`, the new size is stored using the common truncation
+    /// extension.
+    ///
+    /// # Errors
+    /// Returns [`TooLarge`](Error::TooLarge) if either file is larger than [`MAX_SIZE`](Self::MAX_SIZE).
+    pub fn create(original: &[u8], modified: &[u8]) -> Result<Box<[u8]>> {
+        for size in [original.len(), modified.len()] {
+            ensure!(
+                size <= Self::MAX_SIZE,
+                TooLargeSnafu { format: "IPS", size, limit: Self::MAX_SIZE }
+            );
+        }
+
+        let differs = |offset: usize| original.get(offset) != Some(&modified[offset]);
+        let mut output = b"PATCH".to_vec();
+        let mut offset = 0;
+        while offset < modified.len() {
+            if !differs(offset) {
+                offset += 1;
+                continue;
+            }
+
+            // Records can't start at the offset that spells "EOF", so include the unchanged byte before it
+            let start = match offset == Self::EOF_OFFSET {
+                true => offset - 1,
+                false => offset,
+            };
+
+            // Extend the record until the data matches again, or it hits the size limit
+            let mut end = offset + 1;
+            while end < modified.len() && end - start < Self::MAX_RECORD && differs(end) {
+                end += 1;
+            }
+            Self::write_record(&mut output, start, &modified[start..end]);
+            offset = end;
+        }
+
+        output.extend_from_slice(b"EOF");
+        if modified.len() < original.len() {
+            output.extend_from_slice(&(modified.len() as u32).to_be_bytes()[1..]);
+        }
+        Ok(output.into_boxed_slice())
+    }
+
+    /// Writes a single record, using RLE for any long runs of identical bytes.
+    fn write_record(output: &mut Vec<u8>, mut offset: usize, data: &[u8]) {
+        let mut data = data;
+        while !data.is_empty() {
+            let run = data.iter().take_while(|&&byte| byte == data[0]).count();
+            let (length, rle) = match run >= Self::MIN_RLE && offset != Self::EOF_OFFSET {
+                // Don't let the next record start at the "EOF" offset either
+                true => match offset + run == Self::EOF_OFFSET && run < data.len() {
+                    true => (run - 1, true),
+                    false => (run, true),
+                },
+                // Stop a literal record right before the next long run
+                false => (
+                    (1..data.len())
+                        .find(|&n| {
+                            data[n..].iter().take_while(|&&byte| byte == data[n]).count() >= Self::MIN_RLE
+                                && offset + n != Self::EOF_OFFSET
+                        })
+                        .unwrap_or(data.len()),
+                    false,
+                ),
+            };
+
+            output.extend_from_slice(&(offset as u32).to_be_bytes()[1..]);
+            match rle {
+                true => {
+                    output.extend_from_slice(&[0, 0]);
+                    output.extend_from_slice(&(length as u16).to_be_bytes());
+                    output.push(data[0]);
+                }
+                false => {
+                    output.extend_from_slice(&(length as u16).to_be_bytes());
+                    output.extend_from_slice(&data[..length]);
+                }
+            }
+            offset += length;
+            data = &data[length..];
+        }
+    }
+
+    /// Applies an IPS patch to `original`, returning the patched file.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if this isn't an IPS patch, or
+    /// [`InvalidPatch`](Error::InvalidPatch) if it is truncated.
+    pub fn apply(patch: &[u8], original: &[u8]) -> Result<Box<[u8]>> {
+        let mut reader = Reader { data: patch, position: 0 };
+        ensure!(reader.read_slice(5)? == b"PATCH", InvalidMagicSnafu);
+
+        let mut output = original.to_vec();
+        loop {
+            let offset = reader.read_be(3)?;
+            if offset == Self::EOF_OFFSET {
+                break;
+            }
+
+            let (length, byte) = match reader.read_be(2)? {
+                0 => (reader.read_be(2)?, Some(reader.read_u8()?)),
+                length => (length, None),
+            };
+            if output.len() < offset + length {
+                output.resize(offset + length, 0);
+            }
+            match byte {
+                Some(byte) => output[offset..offset + length].fill(byte),
+                None => output[offset..offset + length].copy_from_slice(reader.read_slice(length)?),
+            }
+        }
+
+        // Optional truncation extension
+        if reader.remaining() >= 3 {
+            output.truncate(reader.read_be(3)?);
+        }
+        Ok(output.into_boxed_slice())
+    }
+}
+
+/// Beat Patching System, which builds the modified file from a list of copies from either file, and verifies
+/// both files using CRC-32 checksums.
+pub struct Bps;
+
+/// Actions used to build the modified file in a BPS patch.
+#[derive(Clone, Copy)]
+enum Action {
+    /// Copies from the same offset in the original file
+    SourceRead = 0,
+    /// Copies data stored in the patch itself
+    TargetRead = 1,
+    /// Copies from anywhere in the original file
+    SourceCopy = 2,
+    /// Copies from earlier in the modified file, which may overlap the current position
+    TargetCopy = 3,
+}
+
+impl Bps {
+    /// Length of the data used to find matching data in either file.
+    const HASH_LENGTH: usize = 4;
+
+    /// Minimum length before a copy from elsewhere in either file is used instead of literal data.
+    const MIN_COPY: usize = 8;
+
+    /// Writes a number using BPS's variable-length encoding.
+    fn write_number(output: &mut Vec<u8>, mut value: u64) {
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                output.push(0x80 | byte);
+                break;
+            }
+            output.push(byte);
+            value -= 1;
+        }
+    }
+
+    /// Reads a number using BPS's variable-length encoding.
+    fn read_number(reader: &mut Reader<'_>) -> Result<u64> {
+        let (mut value, mut shift) = (0u64, 1u64);
+        loop {
+            let byte = reader.read_u8()?;
+            value = value.checked_add(u64::from(byte & 0x7F) * shift).context(InvalidPatchSnafu)?;
+            if byte & 0x80 != 0 {
+                return Ok(value);
+            }
+            shift = shift.checked_shl(7).filter(|&shift| shift != 0).context(InvalidPatchSnafu)?;
+            value = value.checked_add(shift).context(InvalidPatchSnafu)?;
+        }
+    }
+
+    /// Writes an action along with its length.
+    #[inline]
+    fn write_action(output: &mut Vec<u8>, action: Action, length: usize) {
+        Self::write_number(output, ((length as u64 - 1) << 2) | action as u64);
+    }
+
+    /// Writes the relative offset used by copy actions, and updates the current offset.
+    #[inline]
+    fn write_offset(output: &mut Vec<u8>, current: &mut usize, offset: usize, length: usize) {
+        let relative = offset as i64 - *current as i64;
+        Self::write_number(output, (relative.unsigned_abs() << 1) | u64::from(relative < 0));
+        *current = offset + length;
+    }
+
+    /// Returns how many bytes match between two slices.
+    #[inline]
+    fn match_length(a: &[u8], b: &[u8]) -> usize {
+        a.iter().zip(b).take_while(|(a, b)| a == b).count()
+    }
+
+    /// Creates a BPS patch which turns `original` into `modified`.
+    ///
+    /// Data that was moved within the file (e.g. when an archive is rebuilt with a larger file) is found using
+    /// a hash table of both files, so patches stay small even when most offsets change.
+    ///
+    /// # Errors
+    /// This currently can't fail, but returns a [`Result`] to match [`Ips::create`].
+    pub fn create(original: &[u8], modified: &[u8]) -> Result<Box<[u8]>> {
+        let mut output = b"BPS1".to_vec();
+        Self::write_number(&mut output, original.len() as u64);
+        Self::write_number(&mut output, modified.len() as u64);
+        // No metadata
+        Self::write_number(&mut output, 0);
+
+        // Index every position in the original file, keeping the first occurrence of each
+        let mut source_hashes: HashMap<&[u8], usize> = HashMap::new();
+        for (offset, window) in original.windows(Self::HASH_LENGTH).enumerate() {
+            source_hashes.entry(window).or_insert(offset);
+        }
+        let mut target_hashes: HashMap<&[u8], usize> = HashMap::new();
+
+        let (mut source_offset, mut target_offset) = (0, 0);
+        let mut literal_start = None;
+        let mut position = 0;
+        while position < modified.len() {
+            let remaining = &modified[position..];
+
+            // Prefer reading from the same offset, since it doesn't need to store an offset
+            let mut best = (
+                Action::SourceRead,
+                0,
+                original.get(position..).map_or(0, |source| Self::match_length(source, remaining)),
+            );
+            if best.2 < Self::MIN_COPY && remaining.len() >= Self::HASH_LENGTH {
+                let key = &remaining[..Self::HASH_LENGTH];
+                if let Some(&offset) = source_hashes.get(key) {
+                    let length = Self::match_length(&original[offset..], remaining);
+                    if length > best.2 {
+                        best = (Action::SourceCopy, offset, length);
+                    }
+                }
+                if let Some(&offset) = target_hashes.get(key) {
+                    let length = Self::match_length(&modified[offset..], remaining);
+                    if length > best.2 {
+                        best = (Action::TargetCopy, offset, length);
+                    }
+                }
+            }
+
+            let (action, offset, length) = best;
+            let worthwhile = match action {
+                Action::SourceRead => length >= Self::HASH_LENGTH || (length > 0 && literal_start.is_none()),
+                _ => length >= Self::MIN_COPY,
+            };
+            if !worthwhile {
+                literal_start.get_or_insert(position);
+                if remaining.len() >= Self::HASH_LENGTH {
+                    target_hashes.entry(&remaining[..Self::HASH_LENGTH]).or_insert(position);
+                }
+                position += 1;
+                continue;
+            }
+
+            if let Some(start) = literal_start.take() {
+                Self::write_action(&mut output, Action::TargetRead, position - start);
+                output.extend_from_slice(&modified[start..position]);
+            }
+            Self::write_action(&mut output, action, length);
+            match action {
+                Action::SourceCopy => Self::write_offset(&mut output, &mut source_offset, offset, length),
+                Action::TargetCopy => Self::write_offset(&mut output, &mut target_offset, offset, length),
+                _ => {}
+            }
+            for n in position..(position + length).min(modified.len().saturating_sub(Self::HASH_LENGTH - 1)) {
+                target_hashes.entry(&modified[n..n + Self::HASH_LENGTH]).or_insert(n);
+            }
+            position += length;
+        }
+        if let Some(start) = literal_start {
+            Self::write_action(&mut output, Action::TargetRead, position - start);
+            output.extend_from_slice(&modified[start..position]);
+        }
+
+        output.extend_from_slice(&crc32(original).to_le_bytes());
+        output.extend_from_slice(&crc32(modified).to_le_bytes());
+        output.extend_from_slice(&crc32(&output).to_le_bytes());
+        Ok(output.into_boxed_slice())
+    }
+
+    /// Applies a BPS patch to `original`, returning the patched file.
+    ///
+    /// # Errors
+    /// Returns [`InvalidMagic`](Error::InvalidMagic) if this isn't a BPS patch,
+    /// [`InvalidPatch`](Error::InvalidPatch) if it's corrupted, or
+    /// [`ChecksumMismatch`](Error::ChecksumMismatch) if either file or the patch itself doesn't match the
+    /// stored checksums.
+    #[inline]
+    pub fn apply(patch: &[u8], original: &[u8]) -> Result<Box<[u8]>> {
+        Self::apply_with_limits(patch, original, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Applies a BPS patch to `original`, returning the patched file as long as it isn't larger than
+    /// `limits.max_output_size` bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// use orthrus_core::prelude::*;
+    /// use orthrus_tools::patch::{self, Bps};
+    ///
+    /// let bps = Bps::create(b"original", &[0u8; 0x1000])?;
+    /// let limits = ResourceLimits { max_output_size: 0x100, ..Default::default() };
+    /// let result = Bps::apply_with_limits(&bps, b"original", &limits);
+    /// assert!(matches!(result, Err(patch::Error::LimitExceeded { .. })));
+    /// # Ok::<(), patch::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns the same errors as [`apply`](Self::apply), or [`LimitExceeded`](Error::LimitExceeded) if the
+    /// patched file is larger than the limit.
+    pub fn apply_with_limits(patch: &[u8], original: &[u8], limits: &ResourceLimits) -> Result<Box<[u8]>> {
+        ensure!(patch.len() >= 16, InvalidPatchSnafu);
+        let (body, footer) = patch.split_at(patch.len() - 12);
+        let checksum = |n: usize| u32::from_le_bytes(footer[n * 4..n * 4 + 4].try_into().unwrap());
+        Self::verify("Patch", checksum(2), crc32(&patch[..patch.len() - 4]))?;
+        Self::verify("Source", checksum(0), crc32(original))?;
+
+        let mut reader = Reader { data: body, position: 0 };
+        ensure!(reader.read_slice(4)? == b"BPS1", InvalidMagicSnafu);
+        let source_size = usize::try_from(Self::read_number(&mut reader)?).ok().context(InvalidPatchSnafu)?;
+        let target_size = usize::try_from(Self::read_number(&mut reader)?).ok().context(InvalidPatchSnafu)?;
+        ensure!(source_size == original.len(), InvalidPatchSnafu);
+        let metadata_size =
+            usize::try_from(Self::read_number(&mut reader)?).ok().context(InvalidPatchSnafu)?;
+        reader.read_slice(metadata_size)?;
+
+        limits.check_output_size(target_size as u64)?;
+
+        // The stored size can't be trusted until the checksum of the output is verified, so only reserve
+        // as much as could plausibly be needed
+        let mut output = Vec::with_capacity(target_size.min(patch.len().saturating_add(original.len())));
+        let (mut source_offset, mut target_offset) = (0usize, 0usize);
+        while reader.remaining() > 0 {
+            let value = Self::read_number(&mut reader)?;
+            let length = usize::try_from((value >> 2) + 1).ok().context(InvalidPatchSnafu)?;
+            ensure!(length <= target_size - output.len(), InvalidPatchSnafu);
+
+            match value & 3 {
+                0 => {
+                    let start = output.len();
+                    let data = original.get(start..start + length).context(InvalidPatchSnafu)?;
+                    output.extend_from_slice(data);
+                }
+                1 => output.extend_from_slice(reader.read_slice(length)?),
+                2 => {
+                    source_offset = Self::read_offset(&mut reader, source_offset)?;
+                    let end = source_offset.checked_add(length).context(InvalidPatchSnafu)?;
+                    output.extend_from_slice(original.get(source_offset..end).context(InvalidPatchSnafu)?);
+                    source_offset = end;
+                }
+                _ => {
+                    target_offset = Self::read_offset(&mut reader, target_offset)?;
+                    ensure!(target_offset < output.len(), InvalidPatchSnafu);
+                    // The source can overlap with the destination, so this has to be done one byte at a time
+                    for n in 0..length {
+                        output.push(output[target_offset + n]);
+                    }
+                    target_offset += length;
+                }
+            }
+        }
+        ensure!(output.len() == target_size, InvalidPatchSnafu);
+
+        Self::verify("Target", checksum(1), crc32(&output))?;
+        Ok(output.into_boxed_slice())
+    }
+
+    /// Reads a relative offset used by copy actions, and returns the new absolute offset.
+    #[inline]
+    fn read_offset(reader: &mut Reader<'_>, current: usize) -> Result<usize> {
+        let value = Self::read_number(reader)?;
+        let relative = usize::try_from(value >> 1).ok().context(InvalidPatchSnafu)?;
+        match value & 1 {
+            0 => current.checked_add(relative),
+            _ => current.checked_sub(relative),
+        }
+        .context(InvalidPatchSnafu)
+    }
+
+    #[inline]
+    fn verify(which: &'static str, expected: u32, actual: u32) -> Result<()> {
+        ensure!(
+            expected == actual,
+            ChecksumMismatchSnafu { which, expected, actual }
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+//! Reader for the Switch PartitionFS (PFS0) format, a flat list of files that's used for NSP packages and
+//! ExeFS sections.
+//!
+//! NSP packages store each of a title's NCAs as a file, which can then be opened with the `nca` module when
+//! the `crypto` feature is enabled.
+//!
+//! # Usage
+//! ```no_run
+//! use orthrus_tools::pfs::PartitionFs;
+//!
+//! let mut package = PartitionFs::new(std::fs::File::open("game.nsp")?)?;
+//! for entry in package.files() {
+//!     println!("{} ({} bytes)", entry.name, entry.size);
+//! }
+//! # Ok::<(), orthrus_tools::pfs::Error>(())
+//! ```
+
+use std::io::{Read, Seek, SeekFrom};
+
+use orthrus_core::prelude::*;
+use snafu::prelude::*;
+
+/// Error conditions for when reading a PartitionFS.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown if unable to read the file.
+    #[snafu(display("Filesystem Error {}", source))]
+    FileError { source: std::io::Error },
+
+    /// Thrown if the header or file table isn't valid.
+    #[snafu(display("Invalid PartitionFS header!"))]
+    InvalidHeader,
+
+    /// Thrown if a file's offset is out of range.
+    #[snafu(display("Invalid PartitionFS data! {reason}"))]
+    InvalidData { reason: &'static str },
+
+    /// Thrown if the partition is larger than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+
+    /// Thrown if trying to open a file that isn't in the partition.
+    #[snafu(display("Unable to find {name} in the PartitionFS!"))]
+    FileNotFound { name: String },
+}
+type Result<T> = core::result::Result<T, Error>;
+
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(source: std::io::Error) -> Self {
+        Error::FileError { source }
+    }
+}
+
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Error::LimitExceeded { source }
+    }
+}
+
+/// A single file stored in a [`PartitionFs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Name of the file
+    pub name: String,
+    /// Offset of the file's data, relative to the start of the partition
+    pub offset: u64,
+    /// Size of the file, in bytes
+    pub size: u64,
+}
+
+/// A PartitionFS, see the [module documentation](self) for more information.
+#[derive(Debug)]
+pub struct PartitionFs<R> {
+    reader: R,
+    start: u64,
+    files: Vec<Entry>,
+}
+
+impl<R: Read + Seek> PartitionFs<R> {
+    /// Unique identifier that tells us if we're reading a PartitionFS.
+    pub const MAGIC: [u8; 4] = *b"PFS0";
+
+    /// Size of each entry in the file table.
+    const ENTRY_SIZE: u64 = 0x18;
+
+    /// Reads the file table of the PartitionFS that starts at the reader's current position.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to read the file, or
+    /// [`InvalidHeader`](Error::InvalidHeader) if it isn't a valid PartitionFS.
+    #[inline]
+    pub fn new(reader: R) -> Result<Self> {
+        Self::new_with_limits(reader, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Reads the file table the same way as [`new`](Self::new), returning an error instead of reading a
+    /// larger table, more files, or more total file data than `limits` allow.
+    ///
+    /// PartitionFS doesn't have directories, so `max_depth` isn't used.
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the partition exceeds `limits`, or any error from
+    /// [`new`](Self::new).
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// use orthrus_tools::pfs::{Error, PartitionFs};
+    ///
+    /// let header = *b"PFS0\x02\0\0\0\0\0\0\0\0\0\0\0";
+    /// let limits = ResourceLimits { max_entries: 1, ..Default::default() };
+    /// let result = PartitionFs::new_with_limits(std::io::Cursor::new(header), &limits);
+    /// assert!(matches!(result, Err(Error::LimitExceeded { .. })));
+    /// ```
+    pub fn new_with_limits(mut reader: R, limits: &ResourceLimits) -> Result<Self> {
+        let start = reader.stream_position()?;
+        let mut header = [0u8; 0x10];
+        reader.read_exact(&mut header)?;
+        ensure!(header[..4] == Self::MAGIC, InvalidHeaderSnafu);
+        let count = u64::from(u32::from_le_bytes([header[4], header[5], header[6], header[7]]));
+        let strings_size = u64::from(u32::from_le_bytes([header[8], header[9], header[10], header[11]]));
+        limits.check_entries(count)?;
+
+        // The file table is immediately followed by the string table
+        let size = count * Self::ENTRY_SIZE + strings_size;
+        limits.check_output_size(size)?;
+        let mut table = Vec::new();
+        (&mut reader).take(size).read_to_end(&mut table)?;
+        ensure!(table.len() as u64 == size, InvalidHeaderSnafu);
+        let (entries, strings) = table.split_at((count * Self::ENTRY_SIZE) as usize);
+        let data_offset = 0x10 + size;
+
+        let files = entries
+            .chunks_exact(Self::ENTRY_SIZE as usize)
+            .map(|entry| {
+                let offset = u64::from_le_bytes(entry[0..8].try_into().ok()?);
+                let size = u64::from_le_bytes(entry[8..16].try_into().ok()?);
+                let name = strings.get(u32::from_le_bytes(entry[16..20].try_into().ok()?) as usize..)?;
+                let name = &name[..name.iter().position(|&byte| byte == 0)?];
+                Some(Entry {
+                    name: String::from_utf8_lossy(name).into_owned(),
+                    offset: data_offset.checked_add(offset)?,
+                    size,
+                })
+            })
+            .collect::<Option<Vec<_>>>()
+            .context(InvalidHeaderSnafu)?;
+        let total_size = files.iter().fold(0u64, |total, entry| total.saturating_add(entry.size));
+        limits.check_output_size(total_size)?;
+        Ok(Self { reader, start, files })
+    }
+
+    /// Returns every file in the partition, in the order they're stored.
+    #[must_use]
+    #[inline]
+    pub fn files(&self) -> &[Entry] {
+        &self.files
+    }
+
+    /// Seeks to the start of the file called `name`, returning the underlying reader so that it can be passed
+    /// to another parser.
+    ///
+    /// # Errors
+    /// Returns [`FileNotFound`](Error::FileNotFound) if there's no file called `name`, or
+    /// [`FileError`](Error::FileError) if unable to seek.
+    pub fn open(&mut self, name: &str) -> Result<&mut R> {
+        let entry = self.files.iter().find(|entry| entry.name == name).context(FileNotFoundSnafu { name })?;
+        let offset =
+            self.start.checked_add(entry.offset).context(InvalidDataSnafu { reason: "File Out Of Range" })?;
+        self.reader.seek(SeekFrom::Start(offset))?;
+        Ok(&mut self.reader)
+    }
+
+    /// Reads the entire contents of the file called `name`.
+    ///
+    /// # Errors
+    /// Returns [`FileNotFound`](Error::FileNotFound) if there's no file called `name`, or
+    /// [`FileError`](Error::FileError) if unable to read its data.
+    pub fn read(&mut self, name: &str) -> Result<Vec<u8>> {
+        let size = self.files.iter().find(|entry| entry.name == name).map_or(0, |entry| entry.size);
+        let mut data = Vec::new();
+        self.open(name)?.take(size).read_to_end(&mut data)?;
+        if data.len() as u64 != size {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(data)
+    }
+
+    /// Returns the underlying reader.
+    #[must_use]
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
@@ -0,0 +1,355 @@
+//! Chains of operations applied to a file in memory, such as decompressing an archive, replacing a file
+//! inside of it, then repacking and recompressing it again.
+//!
+//! Each step takes ownership of the data produced by the previous step, so nothing gets written to disk and
+//! data is only copied when a format requires it (e.g. when compressing or rebuilding an archive).
+//!
+//! # Usage
+//! ```
+//! use orthrus_jsystem::rarc2::{Directory, Entry, File, ResourceArchive};
+//! use orthrus_ncompress::prelude::*;
+//! use orthrus_tools::prelude::*;
+//!
+//! # let mut root = Directory::new("scene");
+//! # root.entries.push(Entry::File(File::new("model.bmd", b"old model".as_slice())));
+//! # let data = ResourceArchive::new(root).to_bytes();
+//! # let szs = Yaz0::compress_from(&data, yaz0::CompressionAlgo::MatchingOld, 0)?;
+//! // Replace a file inside of a Yaz0-compressed RARC
+//! let output = Pipeline::new()
+//!     .yaz0_decompress()
+//!     .archive::<ResourceArchive>(Edits::new().replace("model.bmd", b"new model".as_slice()))
+//!     .yaz0_compress(yaz0::CompressionAlgo::MatchingOld)
+//!     .run(szs)?;
+//!
+//! let archive = ResourceArchive::load(Yaz0::decompress_from(&output)?)?;
+//! assert_eq!(&*archive.file("model.bmd").unwrap().data, b"new model");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Files inside of an archive can also have their own pipeline, using [`Edits::member`], and any other
+//! operation can be added using [`Pipeline::then`].
+//!
+//! When the same files are processed repeatedly, the output of each built-in step can be kept in a
+//! [`Cache`] using [`Pipeline::cache`], so that unchanged inputs are only decompressed or patched once.
+//!
+//! A pipeline can also be stopped from another thread using [`Pipeline::cancellation`], which is checked
+//! before each step. Steps always run to completion, and the input is never modified in place, so nothing is
+//! left half-processed.
+
+use std::borrow::Cow;
+
+use orthrus_core::cancel::CancellationToken;
+use orthrus_jsystem::rarc2::ResourceArchive;
+use orthrus_ncompress::prelude::*;
+use snafu::prelude::*;
+
+use crate::cache::Cache;
+use crate::plugin::{ArchiveFormat, DynArchive};
+
+/// Error type returned by individual steps, which is kept as the source of [`Error::Step`].
+pub type StepError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Error conditions for when running a [`Pipeline`].
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown if any step fails, along with which step it was.
+    #[snafu(display("Step {index} ({name}) failed: {source}"))]
+    Step {
+        index: usize,
+        name: Cow<'static, str>,
+        source: StepError,
+    },
+
+    /// Thrown if trying to edit a file that doesn't exist in an archive.
+    #[snafu(display("Unable to find {path} in {format}!"))]
+    MemberNotFound { format: &'static str, path: String },
+
+    /// Thrown if the pipeline was cancelled, along with how many steps had already finished.
+    #[snafu(display("Cancelled after {completed} steps!"))]
+    Cancelled { completed: usize },
+}
+
+/// Formats that can be opened, have their files edited, and then be repacked by a [`Pipeline`].
+pub trait Archive: Sized {
+    /// Name of the format, used for error messages.
+    const NAME: &'static str;
+
+    /// Error returned when the archive fails to load.
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Parses the archive, taking ownership of the data.
+    ///
+    /// # Errors
+    /// Returns an error if the data isn't a valid archive.
+    fn load(data: Box<[u8]>) -> Result<Self, Self::Error>;
+
+    /// Returns a mutable reference to the contents of the file at the given path.
+    fn member_mut(&mut self, path: &str) -> Option<&mut Box<[u8]>>;
+
+    /// Serializes the archive, including any edits.
+    fn to_bytes(&self) -> Box<[u8]>;
+}
+
+impl Archive for ResourceArchive {
+    const NAME: &'static str = "RARC";
+
+    type Error = orthrus_jsystem::rarc2::Error;
+
+    #[inline]
+    fn load(data: Box<[u8]>) -> Result<Self, Self::Error> {
+        Self::load(data)
+    }
+
+    #[inline]
+    fn member_mut(&mut self, path: &str) -> Option<&mut Box<[u8]>> {
+        self.file_mut(path).map(|file| &mut file.data)
+    }
+
+    #[inline]
+    fn to_bytes(&self) -> Box<[u8]> {
+        self.to_bytes()
+    }
+}
+
+/// A single operation in a [`Pipeline`].
+type Step = Box<dyn FnOnce(Box<[u8]>) -> Result<Box<[u8]>, StepError>>;
+
+/// A single operation in a [`Pipeline`], along with its name and what to store its output as in a [`Cache`].
+struct Entry {
+    name: Cow<'static, str>,
+    operation: Option<String>,
+    step: Step,
+}
+
+/// A list of operations to run in order, see the [module documentation](self) for more information.
+#[derive(Default)]
+#[must_use]
+pub struct Pipeline {
+    steps: Vec<Entry>,
+    cache: Option<Cache>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl Pipeline {
+    /// Creates a new empty pipeline, which returns its input unchanged.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores the output of every built-in step in `cache`, and skips any step whose output is already
+    /// stored. Custom steps added using [`then`](Self::then) always run.
+    ///
+    /// Pipelines passed to [`Edits::member`] don't share this cache, and need to be given their own.
+    #[inline]
+    pub fn cache(mut self, cache: Cache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Stops the pipeline before the next step once `token` is cancelled.
+    ///
+    /// Pipelines passed to [`Edits::member`] don't share this token, and need to be given their own.
+    #[inline]
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Adds a custom step to the end of the pipeline, where `name` is used to identify it in errors.
+    #[inline]
+    pub fn then<N, F, E>(self, name: N, step: F) -> Self
+    where
+        N: Into<Cow<'static, str>>,
+        F: FnOnce(Box<[u8]>) -> Result<Box<[u8]>, E> + 'static,
+        E: Into<StepError>,
+    {
+        self.then_cached(name, None, step)
+    }
+
+    /// Adds a step whose output only depends on its input and `operation`, so it can be cached.
+    fn then_cached<N, F, E>(mut self, name: N, operation: Option<String>, step: F) -> Self
+    where
+        N: Into<Cow<'static, str>>,
+        F: FnOnce(Box<[u8]>) -> Result<Box<[u8]>, E> + 'static,
+        E: Into<StepError>,
+    {
+        let step = Box::new(|data| step(data).map_err(Into::into));
+        self.steps.push(Entry { name: name.into(), operation, step });
+        self
+    }
+
+    /// Adds a step that decompresses Yaz0 data.
+    #[inline]
+    pub fn yaz0_decompress(self) -> Self {
+        self.then_cached("Yaz0 decompress", Some("yaz0-decompress".into()), |data| {
+            Yaz0::decompress_from(&data)
+        })
+    }
+
+    /// Adds a step that compresses data using Yaz0.
+    #[inline]
+    pub fn yaz0_compress(self, algorithm: yaz0::CompressionAlgo) -> Self {
+        let operation = match algorithm {
+            yaz0::CompressionAlgo::MatchingOld => Some("yaz0-compress-matching-old".into()),
+            yaz0::CompressionAlgo::NintendoEadGc => Some("yaz0-compress-nintendo-ead-gc".into()),
+            yaz0::CompressionAlgo::WiiUNlib => Some("yaz0-compress-wii-u-nlib".into()),
+            yaz0::CompressionAlgo::ModernGreedy => Some("yaz0-compress-modern-greedy".into()),
+            _ => None,
+        };
+        self.then_cached("Yaz0 compress", operation, move |data| {
+            Yaz0::compress_from(&data, algorithm, 0)
+        })
+    }
+
+    /// Adds a step that decompresses Yay0 data.
+    #[inline]
+    pub fn yay0_decompress(self) -> Self {
+        self.then_cached("Yay0 decompress", Some("yay0-decompress".into()), |data| {
+            Yay0::decompress_from(&data)
+        })
+    }
+
+    /// Adds a step that compresses data using Yay0.
+    #[inline]
+    pub fn yay0_compress(self, algorithm: yay0::CompressionAlgo) -> Self {
+        let operation = match algorithm {
+            yay0::CompressionAlgo::MatchingOld => Some("yay0-compress-matching-old".into()),
+            yay0::CompressionAlgo::NintendoEadGc => Some("yay0-compress-nintendo-ead-gc".into()),
+            yay0::CompressionAlgo::ModernGreedy => Some("yay0-compress-modern-greedy".into()),
+            _ => None,
+        };
+        self.then_cached("Yay0 compress", operation, move |data| {
+            Yay0::compress_from(&data, algorithm, 0)
+        })
+    }
+
+    /// Adds a step that applies an IPS or BPS patch, see [`patch`](crate::patch) for more information.
+    #[inline]
+    pub fn apply_patch<P: Into<Box<[u8]>>>(self, patch: P) -> Self {
+        let patch = patch.into();
+        // The output depends on the patch as well, so it's included in the operation
+        let operation = format!("apply-patch-{}", Cache::key("patch", &patch));
+        self.then_cached("Apply patch", Some(operation), move |data| {
+            crate::patch::apply(&patch, &data)
+        })
+    }
+
+    /// Adds a step that opens the data as an [`Archive`], applies all `edits` in order, and repacks it.
+    #[inline]
+    pub fn archive<A: Archive + 'static>(self, edits: Edits) -> Self {
+        self.then(format!("{} edit", A::NAME), move |data| -> Result<_, StepError> {
+            let mut archive = A::load(data)?;
+            edits.apply(A::NAME, &mut archive)?;
+            Ok(Archive::to_bytes(&archive))
+        })
+    }
+
+    /// Adds a step that opens the data using an archive format that isn't known until runtime, such as one
+    /// added by a [`Plugin`](crate::plugin::Plugin), then applies all `edits` in order and repacks it.
+    #[inline]
+    pub fn archive_format(self, format: ArchiveFormat, edits: Edits) -> Self {
+        self.then(
+            format!("{} edit", format.name),
+            move |data| -> Result<_, StepError> {
+                let mut archive = format.load(data)?;
+                edits.apply(format.name, archive.as_mut())?;
+                Ok(archive.to_bytes())
+            },
+        )
+    }
+
+    /// Runs every step in order, returning the output of the last step.
+    ///
+    /// If a [`Cache`] was provided, it's checked before each built-in step, and failing to store an output
+    /// in it is ignored since the step itself succeeded.
+    ///
+    /// # Errors
+    /// Returns [`Step`](Error::Step) if any step fails, [`MemberNotFound`](Error::MemberNotFound) if an
+    /// archive edit refers to a file that doesn't exist, or [`Cancelled`](Error::Cancelled) if the pipeline
+    /// was cancelled.
+    #[inline]
+    pub fn run<I: Into<Box<[u8]>>>(self, input: I) -> Result<Box<[u8]>, Error> {
+        let cache = self.cache;
+        let cancellation = self.cancellation;
+        self.steps.into_iter().enumerate().try_fold(input.into(), |data, (index, entry)| {
+            if cancellation.as_ref().is_some_and(CancellationToken::is_cancelled) {
+                return Err(Error::Cancelled { completed: index });
+            }
+            let Entry { name, operation, step } = entry;
+            let key = match (&cache, operation) {
+                (Some(cache), Some(operation)) => {
+                    let key = Cache::key(&operation, &data);
+                    if let Some(output) = cache.get(&key) {
+                        return Ok(output);
+                    }
+                    Some((cache, key))
+                }
+                _ => None,
+            };
+
+            let output = step(data).map_err(|source| match source.downcast::<Error>() {
+                // Keep missing files and cancellation as-is so they're easier to match on, even from nested
+                // pipelines
+                Ok(error) if matches!(*error, Error::MemberNotFound { .. } | Error::Cancelled { .. }) => {
+                    *error
+                }
+                Ok(error) => Error::Step { index, name, source: error },
+                Err(source) => Error::Step { index, name, source },
+            })?;
+            if let Some((cache, key)) = key {
+                let _ = cache.insert(&key, &output);
+            }
+            Ok(output)
+        })
+    }
+}
+
+/// A single change to a file inside of an archive.
+enum Edit {
+    Replace(Box<[u8]>),
+    Pipeline(Pipeline),
+}
+
+/// A list of changes to make to files inside of an [`Archive`], used by [`Pipeline::archive`].
+#[derive(Default)]
+#[must_use]
+pub struct Edits {
+    edits: Vec<(String, Edit)>,
+}
+
+impl Edits {
+    /// Creates a new empty list of edits.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the contents of the file at `path`.
+    #[inline]
+    pub fn replace<P: Into<String>, D: Into<Box<[u8]>>>(mut self, path: P, data: D) -> Self {
+        self.edits.push((path.into(), Edit::Replace(data.into())));
+        self
+    }
+
+    /// Runs a separate pipeline on the file at `path`, such as for an archive nested inside of another.
+    #[inline]
+    pub fn member<P: Into<String>>(mut self, path: P, pipeline: Pipeline) -> Self {
+        self.edits.push((path.into(), Edit::Pipeline(pipeline)));
+        self
+    }
+
+    fn apply(self, format: &'static str, archive: &mut dyn DynArchive) -> Result<(), Error> {
+        for (path, edit) in self.edits {
+            let Some(member) = archive.member_mut(&path) else {
+                return MemberNotFoundSnafu { format, path }.fail();
+            };
+            *member = match edit {
+                Edit::Replace(data) => data,
+                Edit::Pipeline(pipeline) => pipeline.run(core::mem::take(member))?,
+            };
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+//! Registration of formats and commands from other crates, so that community-maintained formats for specific
+//! games can be added to a tool built on Orthrus without forking it.
+//!
+//! A [`Plugin`] adds everything it supports to a [`Registry`], which a tool builds once at startup:
+//! * Detectors, which identify a file using its [`FileIdentifier`] implementation
+//! * Archives, which can be opened and edited by a [`Pipeline`](crate::pipeline::Pipeline) using
+//!   [`archive_format`](crate::pipeline::Pipeline::archive_format)
+//! * Commands, which a command line tool can run by name with the rest of its arguments
+//!
+//! Each registration replaces any earlier one with the same name, so a plugin can also override a built-in
+//! format, such as with a game-specific variant of it.
+//!
+//! # Usage
+//! ```
+//! use orthrus_core::prelude::*;
+//! use orthrus_jsystem::rarc2::ResourceArchive;
+//! use orthrus_tools::plugin::{Plugin, Registry};
+//!
+//! // A format from another crate, which is recognized by its magic number
+//! struct Level;
+//!
+//! impl FileIdentifier for Level {
+//!     fn identify(data: &[u8]) -> Option<FileInfo> {
+//!         data.starts_with(b"LEVL").then(|| FileInfo::new("Example level".to_owned(), None))
+//!     }
+//! }
+//!
+//! struct ExamplePlugin;
+//!
+//! impl Plugin for ExamplePlugin {
+//!     fn name(&self) -> &'static str {
+//!         "example"
+//!     }
+//!
+//!     fn register(&self, registry: &mut Registry) {
+//!         registry
+//!             .add_detector::<Level>("Example level")
+//!             .add_archive::<ResourceArchive>()
+//!             .add_command("levels", "List every level in a folder", |args| {
+//!                 println!("Listing levels in {args:?}");
+//!                 Ok(())
+//!             });
+//!     }
+//! }
+//!
+//! let registry = Registry::new().with(&ExamplePlugin);
+//! let formats = registry.identify(b"LEVL\x00\x01", false);
+//! assert_eq!(formats[0].0, "Example level");
+//! assert!(registry.archive("RARC").is_some());
+//!
+//! let command = registry.command("levels").unwrap();
+//! (command.run)(&["stages".to_owned()])?;
+//! # Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+//! ```
+
+use orthrus_core::prelude::*;
+
+use crate::pipeline::{Archive, StepError};
+
+/// Error type returned by a [`Command`].
+pub type CommandError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Entry point of a [`Command`], which is given every argument after the command's name.
+pub type CommandFn = fn(&[String]) -> Result<(), CommandError>;
+
+/// A set of formats and commands that can be added to a [`Registry`].
+pub trait Plugin {
+    /// Name of the plugin, which is listed by [`Registry::plugins`].
+    fn name(&self) -> &'static str;
+
+    /// Adds every detector, archive, and command this plugin supports to `registry`.
+    fn register(&self, registry: &mut Registry);
+}
+
+/// Identifies files of a single format, see [`Registry::add_detector`].
+#[derive(Debug, Clone, Copy)]
+pub struct Detector {
+    /// Name of the format, used when listing what a file was identified as
+    pub name: &'static str,
+    /// See [`FileIdentifier::identify`]
+    pub identify: IdentifyFn,
+    /// See [`FileIdentifier::identify_deep`]
+    pub identify_deep: IdentifyFn,
+}
+
+/// An [`Archive`] that has been opened without knowing its type, see [`ArchiveFormat::load`].
+pub trait DynArchive {
+    /// See [`Archive::member_mut`].
+    fn member_mut(&mut self, path: &str) -> Option<&mut Box<[u8]>>;
+
+    /// See [`Archive::to_bytes`].
+    fn to_bytes(&self) -> Box<[u8]>;
+}
+
+impl<A: Archive> DynArchive for A {
+    #[inline]
+    fn member_mut(&mut self, path: &str) -> Option<&mut Box<[u8]>> {
+        Archive::member_mut(self, path)
+    }
+
+    #[inline]
+    fn to_bytes(&self) -> Box<[u8]> {
+        Archive::to_bytes(self)
+    }
+}
+
+/// Parses an archive of a single format, taking ownership of the data, see [`ArchiveFormat`].
+pub type ArchiveLoader = fn(Box<[u8]>) -> Result<Box<dyn DynArchive>, StepError>;
+
+/// Opens archives of a single format, see [`Registry::add_archive`].
+#[derive(Debug, Clone, Copy)]
+pub struct ArchiveFormat {
+    /// Name of the format, from [`Archive::NAME`]
+    pub name: &'static str,
+    load: ArchiveLoader,
+}
+
+impl ArchiveFormat {
+    /// Returns the format for `A`.
+    #[must_use]
+    #[inline]
+    pub fn of<A: Archive + 'static>() -> Self {
+        fn load<A: Archive + 'static>(data: Box<[u8]>) -> Result<Box<dyn DynArchive>, StepError> {
+            Ok(Box::new(A::load(data)?))
+        }
+        Self { name: A::NAME, load: load::<A> }
+    }
+
+    /// Parses the archive, taking ownership of the data.
+    ///
+    /// # Errors
+    /// Returns the archive's own error if the data isn't a valid archive.
+    #[inline]
+    pub fn load(&self, data: Box<[u8]>) -> Result<Box<dyn DynArchive>, StepError> {
+        (self.load)(data)
+    }
+}
+
+/// A subcommand added by a plugin, see [`Registry::add_command`].
+#[derive(Debug, Clone, Copy)]
+pub struct Command {
+    /// Name used to run the command
+    pub name: &'static str,
+    /// Short description, for listing every command
+    pub description: &'static str,
+    /// Runs the command
+    pub run: CommandFn,
+}
+
+/// Every detector, archive, and command added by a set of [`Plugin`]s.
+///
+/// See the [module documentation](self) for more information.
+#[derive(Debug, Default)]
+#[must_use]
+pub struct Registry {
+    plugins: Vec<&'static str>,
+    detectors: Vec<Detector>,
+    archives: Vec<ArchiveFormat>,
+    commands: Vec<Command>,
+}
+
+impl Registry {
+    /// Creates a new registry without any plugins.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds everything supported by `plugin`, returning the registry so plugins can be chained.
+    #[inline]
+    pub fn with<P: Plugin + ?Sized>(mut self, plugin: &P) -> Self {
+        self.add(plugin);
+        self
+    }
+
+    /// Adds everything supported by `plugin`.
+    #[inline]
+    pub fn add<P: Plugin + ?Sized>(&mut self, plugin: &P) -> &mut Self {
+        self.plugins.push(plugin.name());
+        plugin.register(self);
+        self
+    }
+
+    /// Adds a detector for `T`, replacing any existing detector with the same name.
+    #[inline]
+    pub fn add_detector<T: FileIdentifier>(&mut self, name: &'static str) -> &mut Self {
+        self.detectors.retain(|detector| detector.name != name);
+        self.detectors.push(Detector { name, identify: T::identify, identify_deep: T::identify_deep });
+        self
+    }
+
+    /// Adds `A` as an archive format, replacing any existing format with the same [`Archive::NAME`].
+    #[inline]
+    pub fn add_archive<A: Archive + 'static>(&mut self) -> &mut Self {
+        self.archives.retain(|format| format.name != A::NAME);
+        self.archives.push(ArchiveFormat::of::<A>());
+        self
+    }
+
+    /// Adds a command, replacing any existing command with the same name.
+    #[inline]
+    pub fn add_command(
+        &mut self, name: &'static str, description: &'static str, run: CommandFn,
+    ) -> &mut Self {
+        self.commands.retain(|command| command.name != name);
+        self.commands.push(Command { name, description, run });
+        self
+    }
+
+    /// Returns the name of every plugin that was added, in the order they were added.
+    #[must_use]
+    #[inline]
+    pub fn plugins(&self) -> &[&'static str] {
+        &self.plugins
+    }
+
+    /// Returns every detector, in the order they were added.
+    #[must_use]
+    #[inline]
+    pub fn detectors(&self) -> &[Detector] {
+        &self.detectors
+    }
+
+    /// Returns every archive format, in the order they were added.
+    #[must_use]
+    #[inline]
+    pub fn archives(&self) -> &[ArchiveFormat] {
+        &self.archives
+    }
+
+    /// Returns every command, in the order they were added.
+    #[must_use]
+    #[inline]
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// Returns the archive format with the given name, if any.
+    #[must_use]
+    #[inline]
+    pub fn archive(&self, name: &str) -> Option<&ArchiveFormat> {
+        self.archives.iter().find(|format| format.name == name)
+    }
+
+    /// Returns the command with the given name, if any.
+    #[must_use]
+    #[inline]
+    pub fn command(&self, name: &str) -> Option<&Command> {
+        self.commands.iter().find(|command| command.name == name)
+    }
+
+    /// Returns every format that `data` could be along with the name of its detector, which is empty if
+    /// nothing recognized it. `deep_scan` uses [`FileIdentifier::identify_deep`] instead.
+    #[must_use]
+    pub fn identify(&self, data: &[u8], deep_scan: bool) -> Vec<(&'static str, FileInfo)> {
+        self.detectors
+            .iter()
+            .filter_map(|detector| {
+                let identify = match deep_scan {
+                    true => detector.identify_deep,
+                    false => detector.identify,
+                };
+                Some((detector.name, identify(data)?))
+            })
+            .collect()
+    }
+}
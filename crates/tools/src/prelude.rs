@@ -0,0 +1,70 @@
+//! Convenient re-exports of commonly used data types, designed to make crate usage painless.
+//!
+//! The contents of this module can be used by including the following in any module:
+//! ```ignore
+//! use orthrus_tools::prelude::*;
+//! ```
+
+#[doc(inline)]
+pub use crate::cache::Cache;
+#[doc(inline)]
+pub use crate::manifest::Manifest;
+#[doc(inline)]
+pub use crate::pipeline::{Archive, Edits, Pipeline};
+#[doc(inline)]
+pub use crate::plugin::{Plugin, Registry};
+#[doc(inline)]
+pub use crate::title::Title;
+
+pub mod cache {
+    #[doc(inline)]
+    pub use crate::cache::{Cache, Error, Key};
+}
+
+pub mod dump {
+    #[doc(inline)]
+    pub use crate::dump::{label, Dump, Error, Layout};
+}
+
+pub mod manifest {
+    #[doc(inline)]
+    pub use crate::manifest::{Entry, Error, Manifest, Mismatch, Source, Status};
+}
+
+#[cfg(feature = "crypto")]
+pub mod nca {
+    #[doc(inline)]
+    pub use crate::nca::{ContentType, Encryption, Error, FsType, Keys, Nca, Section, SectionReader};
+}
+
+pub mod patch {
+    #[doc(inline)]
+    pub use crate::patch::{apply, create, Bps, Error, Format, Ips};
+}
+
+pub mod pfs {
+    #[doc(inline)]
+    pub use crate::pfs::{Entry, Error, PartitionFs};
+}
+
+pub mod pipeline {
+    #[doc(inline)]
+    pub use crate::pipeline::Error;
+}
+
+pub mod plugin {
+    #[doc(inline)]
+    pub use crate::plugin::{
+        ArchiveFormat, Command, CommandError, CommandFn, Detector, DynArchive, Plugin, Registry,
+    };
+}
+
+pub mod romfs {
+    #[doc(inline)]
+    pub use crate::romfs::{Entry, Error, RomFs};
+}
+
+pub mod title {
+    #[doc(inline)]
+    pub use crate::title::{Platform, Region, Title};
+}
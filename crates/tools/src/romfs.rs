@@ -0,0 +1,297 @@
+//! Reader for the Switch RomFS file system, which stores a title's read-only game files.
+//!
+//! A RomFS image is usually stored inside of an NCA (see the `nca` module, enabled by the `crypto` feature),
+//! but can also be read directly from any decrypted image, such as one produced by hactool's `--romfs`
+//! option. [`RomFs::extract`] writes the files out using the same `romfs/` layout as other dumping tools, so
+//! the result can be opened with [`Dump`](crate::dump::Dump).
+//!
+//! # Usage
+//! ```no_run
+//! use orthrus_tools::romfs::RomFs;
+//!
+//! let mut romfs = RomFs::new(std::fs::File::open("romfs.bin")?)?;
+//! for entry in romfs.files() {
+//!     println!("{} ({} bytes)", entry.path, entry.size);
+//! }
+//! let archive = romfs.read("/Sound/Resource/SoundData.bfsar")?;
+//! # Ok::<(), orthrus_tools::romfs::Error>(())
+//! ```
+
+use std::collections::HashSet;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use orthrus_core::prelude::*;
+use snafu::prelude::*;
+
+/// Error conditions for when reading a RomFS image.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Thrown if unable to read the image, or write an extracted file.
+    #[snafu(display("Filesystem Error {}", source))]
+    FileError { source: std::io::Error },
+
+    /// Thrown if the header doesn't describe a valid RomFS image.
+    #[snafu(display("Invalid RomFS header!"))]
+    InvalidHeader,
+
+    /// Thrown if a directory or file entry is out of bounds, or the entries form a loop.
+    #[snafu(display("Invalid RomFS entry at offset {:#X}", offset))]
+    InvalidEntry { offset: u32 },
+
+    /// Thrown if an offset in the image is out of range.
+    #[snafu(display("Invalid RomFS data! {reason}"))]
+    InvalidData { reason: &'static str },
+
+    /// Thrown if the image is larger than the provided [`ResourceLimits`] allow.
+    #[snafu(display("{source}"))]
+    LimitExceeded { source: LimitError },
+
+    /// Thrown if trying to read a file that isn't in the image.
+    #[snafu(display("Unable to find {path} in the RomFS!"))]
+    FileNotFound { path: String },
+
+    /// Thrown if a file's name can't safely be extracted, see [`PathOptions`].
+    #[snafu(display("{source}"))]
+    InvalidPath { source: PathError },
+}
+type Result<T> = core::result::Result<T, Error>;
+
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(source: std::io::Error) -> Self {
+        Error::FileError { source }
+    }
+}
+
+impl From<LimitError> for Error {
+    #[inline]
+    fn from(source: LimitError) -> Self {
+        Error::LimitExceeded { source }
+    }
+}
+
+impl From<PathError> for Error {
+    #[inline]
+    fn from(source: PathError) -> Self {
+        Error::InvalidPath { source }
+    }
+}
+
+/// Size of the RomFS header.
+const HEADER_SIZE: usize = 0x50;
+
+/// Marks the end of a list of sibling entries, or a directory without any children.
+const EMPTY: u32 = 0xFFFF_FFFF;
+
+/// A single file stored in a [`RomFs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Path from the root of the file system, starting with `/`
+    pub path: String,
+    /// Offset of the file's data, relative to the start of the image
+    pub offset: u64,
+    /// Size of the file, in bytes
+    pub size: u64,
+}
+
+/// A RomFS image, see the [module documentation](self) for more information.
+#[derive(Debug)]
+pub struct RomFs<R> {
+    reader: R,
+    start: u64,
+    files: Vec<Entry>,
+}
+
+/// Reads a little-endian `u32` out of a metadata table.
+fn read_u32(table: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        table.get(offset..offset.checked_add(4)?)?.try_into().ok()?,
+    ))
+}
+
+/// Reads a little-endian `u64` out of a metadata table.
+fn read_u64(table: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        table.get(offset..offset.checked_add(8)?)?.try_into().ok()?,
+    ))
+}
+
+/// Reads an entry's name, which is stored as its length followed by UTF-8 text.
+fn read_name(table: &[u8], offset: usize) -> Option<String> {
+    let length = read_u32(table, offset)? as usize;
+    let name = table.get(offset + 4..(offset + 4).checked_add(length)?)?;
+    Some(String::from_utf8_lossy(name).into_owned())
+}
+
+/// Returns the absolute position of `entry`'s data, for an image that starts at `start`.
+fn data_start(start: u64, entry: &Entry) -> Result<u64> {
+    start.checked_add(entry.offset).context(InvalidDataSnafu { reason: "File Out Of Range" })
+}
+
+impl<R: Read + Seek> RomFs<R> {
+    /// Reads the directory and file tables of the RomFS image that starts at the reader's current position.
+    ///
+    /// # Errors
+    /// Returns [`FileError`](Error::FileError) if unable to read the image,
+    /// [`InvalidHeader`](Error::InvalidHeader) if it isn't a RomFS image, or
+    /// [`InvalidEntry`](Error::InvalidEntry) if its tables are corrupted.
+    #[inline]
+    pub fn new(reader: R) -> Result<Self> {
+        Self::new_with_limits(reader, &ResourceLimits::UNLIMITED)
+    }
+
+    /// Reads the image the same way as [`new`](Self::new), returning an error instead of reading larger
+    /// tables, more files, more nested directories, or more total file data than `limits` allow.
+    ///
+    /// # Errors
+    /// Returns [`LimitExceeded`](Error::LimitExceeded) if the image exceeds `limits`, or any error from
+    /// [`new`](Self::new).
+    ///
+    /// # Examples
+    /// ```
+    /// # use orthrus_core::prelude::*;
+    /// use orthrus_tools::romfs::{Error, RomFs};
+    ///
+    /// // A header with a 0x80-byte directory table
+    /// let mut header = [0u8; 0x50];
+    /// header[0x00] = 0x50;
+    /// header[0x20] = 0x80;
+    /// let limits = ResourceLimits { max_output_size: 0x40, ..Default::default() };
+    /// let result = RomFs::new_with_limits(std::io::Cursor::new(header), &limits);
+    /// assert!(matches!(result, Err(Error::LimitExceeded { .. })));
+    /// ```
+    pub fn new_with_limits(mut reader: R, limits: &ResourceLimits) -> Result<Self> {
+        let start = reader.stream_position()?;
+        let mut header = [0u8; HEADER_SIZE];
+        reader.read_exact(&mut header)?;
+        let field = |index: usize| read_u64(&header, index * 8).unwrap_or_default();
+        ensure!(field(0) == HEADER_SIZE as u64, InvalidHeaderSnafu);
+
+        // Only the metadata tables are needed, since the hash tables only speed up lookups
+        let mut read_table = |offset: u64, size: u64| -> Result<Vec<u8>> {
+            limits.check_output_size(size)?;
+            let offset =
+                start.checked_add(offset).context(InvalidDataSnafu { reason: "Table Out Of Range" })?;
+            reader.seek(SeekFrom::Start(offset))?;
+            let mut table = Vec::new();
+            (&mut reader).take(size).read_to_end(&mut table)?;
+            ensure!(table.len() as u64 == size, InvalidHeaderSnafu);
+            Ok(table)
+        };
+        let directories = read_table(field(3), field(4))?;
+        let file_table = read_table(field(7), field(8))?;
+        let data_offset = field(9);
+
+        let mut files = Vec::new();
+        let mut total_size = 0u64;
+        // Every entry can only be reached once, otherwise the tables contain a loop
+        let mut visited = HashSet::from([(true, 0u32)]);
+        let mut pending = vec![(0u32, String::new(), 0u32)];
+        while let Some((directory, path, depth)) = pending.pop() {
+            limits.check_depth(depth)?;
+            let invalid = |offset: u32| InvalidEntrySnafu { offset }.build();
+
+            let mut file = read_u32(&directories, directory as usize + 0xC).ok_or(invalid(directory))?;
+            while file != EMPTY {
+                ensure!(visited.insert((false, file)), InvalidEntrySnafu { offset: file });
+                let base = file as usize;
+                let entry = (|| {
+                    Some(Entry {
+                        path: format!("{path}/{}", read_name(&file_table, base + 0x1C)?),
+                        offset: data_offset.checked_add(read_u64(&file_table, base + 0x8)?)?,
+                        size: read_u64(&file_table, base + 0x10)?,
+                    })
+                })();
+                let entry = entry.ok_or(invalid(file))?;
+                total_size = total_size.saturating_add(entry.size);
+                limits.check_output_size(total_size)?;
+                files.push(entry);
+                limits.check_entries(files.len() as u64)?;
+                file = read_u32(&file_table, base + 0x4).ok_or(invalid(file))?;
+            }
+
+            let mut child = read_u32(&directories, directory as usize + 0x8).ok_or(invalid(directory))?;
+            while child != EMPTY {
+                ensure!(visited.insert((true, child)), InvalidEntrySnafu { offset: child });
+                let name = read_name(&directories, child as usize + 0x14).ok_or(invalid(child))?;
+                let sibling = read_u32(&directories, child as usize + 0x4).ok_or(invalid(child))?;
+                pending.push((child, format!("{path}/{name}"), depth + 1));
+                child = sibling;
+            }
+        }
+
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(Self { reader, start, files })
+    }
+
+    /// Returns every file in the image, sorted by path.
+    #[must_use]
+    #[inline]
+    pub fn files(&self) -> &[Entry] {
+        &self.files
+    }
+
+    /// Returns the entry for `path`, which can be given with or without a leading `/`.
+    #[must_use]
+    pub fn get(&self, path: &str) -> Option<&Entry> {
+        let path = path.trim_start_matches('/');
+        self.files.iter().find(|entry| &entry.path[1..] == path)
+    }
+
+    /// Reads the entire contents of the file at `path`.
+    ///
+    /// # Errors
+    /// Returns [`FileNotFound`](Error::FileNotFound) if there's no file at `path`, or
+    /// [`FileError`](Error::FileError) if unable to read its data.
+    pub fn read(&mut self, path: &str) -> Result<Vec<u8>> {
+        let entry = self.get(path).context(FileNotFoundSnafu { path })?.clone();
+        self.reader.seek(SeekFrom::Start(data_start(self.start, &entry)?))?;
+        let mut data = Vec::new();
+        (&mut self.reader).take(entry.size).read_to_end(&mut data)?;
+        if data.len() as u64 != entry.size {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(data)
+    }
+
+    /// Extracts every file into a `romfs/` folder inside of `output`, returning how many files were written.
+    ///
+    /// # Errors
+    /// Returns [`InvalidPath`](Error::InvalidPath) if a file's name is unsafe, or
+    /// [`FileError`](Error::FileError) if unable to read a file or write it to disk.
+    #[inline]
+    pub fn extract<P: AsRef<Path>>(&mut self, output: P) -> Result<usize> {
+        self.extract_into(&Extraction::direct(output.as_ref()), &PathOptions::DEFAULT)
+    }
+
+    /// Extracts every file into a `romfs/` folder inside of wherever `extraction` puts them, such as a
+    /// staging folder or nowhere at all for a dry run, sanitizing each path with `options`. The
+    /// [`Extraction`] still needs to be committed afterwards.
+    ///
+    /// # Errors
+    /// Returns the same errors as [`extract`](Self::extract).
+    pub fn extract_into(&mut self, extraction: &Extraction, options: &PathOptions) -> Result<usize> {
+        for entry in &self.files {
+            let path = Path::new("romfs").join(options.sanitize(&entry.path[1..])?);
+            let Some(path) = extraction.prepare(path, entry.size)? else {
+                continue;
+            };
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            self.reader.seek(SeekFrom::Start(data_start(self.start, entry)?))?;
+            let mut file = std::fs::File::create(path)?;
+            std::io::copy(&mut (&mut self.reader).take(entry.size), &mut file)?;
+        }
+        Ok(self.files.len())
+    }
+
+    /// Returns the underlying reader.
+    #[must_use]
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
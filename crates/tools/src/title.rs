@@ -0,0 +1,365 @@
+//! Parsers for the metadata files that sit next to a game's assets, so tools can tell which game (and which
+//! region) a file or dump belongs to without having to ask.
+//!
+//! Supported files:
+//! * GameCube/Wii disc header - `sys/boot.bin` in a Dolphin dump, with the game ID and internal name
+//! * GameCube/Wii banner - `opening.bnr`, either `BNR1`/`BNR2` (GameCube) or `IMET` (Wii)
+//! * Wii U `meta.xml` - from the `meta/` folder of a title
+//! * Switch NACP - `control.nacp` from a title's control data
+//! * PlayStation `PARAM.SFO`
+//!
+//! All parsers return [`None`] if the data doesn't look like the expected file, since none of these formats
+//! are ever needed to load the assets themselves.
+//!
+//! # Usage
+//! ```
+//! use orthrus_tools::title::{Platform, Region, Title};
+//!
+//! let mut header = vec![0u8; 0x440];
+//! header[..6].copy_from_slice(b"GALE01");
+//! header[0x1C..0x20].copy_from_slice(&0xC2339F3Du32.to_be_bytes());
+//! header[0x20..0x36].copy_from_slice(b"Super Smash Bros Melee");
+//!
+//! let title = Title::parse("sys/boot.bin", &header).unwrap();
+//! assert_eq!(title.platform, Platform::GameCube);
+//! assert_eq!(title.region, Some(Region::America));
+//! assert_eq!(title.to_string(), "Super Smash Bros Melee (GALE01, America)");
+//! ```
+
+use core::fmt;
+
+/// Consoles that [`Title`] metadata can come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Platform {
+    GameCube,
+    Wii,
+    WiiU,
+    Switch,
+    PlayStation,
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::GameCube => "GameCube",
+            Self::Wii => "Wii",
+            Self::WiiU => "Wii U",
+            Self::Switch => "Switch",
+            Self::PlayStation => "PlayStation",
+        })
+    }
+}
+
+/// Regions a game can be released in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Region {
+    Japan,
+    America,
+    Europe,
+    Australia,
+    Korea,
+    China,
+    Taiwan,
+    Asia,
+    /// Released in multiple regions with the same data, or region-free
+    Global,
+}
+
+impl fmt::Display for Region {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Identifying information about a game, see the [module documentation](self) for more information.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Title {
+    /// Console the metadata came from
+    pub platform: Platform,
+    /// Name of the game, preferring English when multiple languages are available
+    pub name: String,
+    /// Publisher or developer of the game, if stored
+    pub publisher: Option<String>,
+    /// Game ID or title ID, such as `GALE01` or `0100000000010000`
+    pub id: Option<String>,
+    /// Region the game was released in, if it can be determined
+    pub region: Option<Region>,
+    /// Version of the game, if stored
+    pub version: Option<String>,
+}
+
+impl fmt::Display for Title {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        match (&self.id, self.region) {
+            (Some(id), Some(region)) => write!(f, " ({id}, {region})"),
+            (Some(id), None) => write!(f, " ({id})"),
+            (None, Some(region)) => write!(f, " ({region})"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// Decodes a null-terminated string, treating each byte as Latin-1 since that's a superset of ASCII.
+fn latin1(data: &[u8]) -> String {
+    data.iter()
+        .take_while(|&&byte| byte != 0)
+        .map(|&byte| char::from(byte))
+        .collect::<String>()
+        .trim()
+        .to_owned()
+}
+
+/// Decodes a null-terminated UTF-8 string, replacing any invalid sequences.
+fn utf8(data: &[u8]) -> String {
+    let end = data.iter().position(|&byte| byte == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..end]).trim().to_owned()
+}
+
+/// Decodes a null-terminated big-endian UTF-16 string.
+fn utf16_be(data: &[u8]) -> String {
+    let units = data.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]]));
+    char::decode_utf16(units.take_while(|&unit| unit != 0))
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect::<String>()
+        .trim()
+        .to_owned()
+}
+
+/// Returns `Some` only for non-empty strings.
+fn non_empty(text: String) -> Option<String> {
+    (!text.is_empty()).then_some(text)
+}
+
+/// Returns the region of a GameCube/Wii game from the last letter of its 4-character game code.
+const fn disc_region(code: u8) -> Option<Region> {
+    match code {
+        b'J' => Some(Region::Japan),
+        b'E' => Some(Region::America),
+        // Europe has a separate code for each set of languages, but they're all PAL releases
+        b'P' | b'D' | b'F' | b'S' | b'I' | b'H' | b'X' | b'Y' | b'Z' => Some(Region::Europe),
+        b'U' => Some(Region::Australia),
+        b'K' | b'Q' | b'T' => Some(Region::Korea),
+        b'W' => Some(Region::Taiwan),
+        b'C' => Some(Region::China),
+        _ => None,
+    }
+}
+
+impl Title {
+    /// Parses any supported metadata file, using `path` to tell apart the formats that don't have a magic
+    /// number (NACP and `meta.xml`).
+    #[must_use]
+    pub fn parse(path: &str, data: &[u8]) -> Option<Self> {
+        let name = path.rsplit(['/', '\\']).next().unwrap_or(path).to_ascii_lowercase();
+        match name.as_str() {
+            "control.nacp" => Self::from_nacp(data),
+            "meta.xml" => Self::from_meta_xml(core::str::from_utf8(data).ok()?),
+            _ => Self::from_banner(data)
+                .or_else(|| Self::from_disc_header(data))
+                .or_else(|| Self::from_sfo(data)),
+        }
+    }
+
+    /// Parses a GameCube or Wii disc header (`boot.bin`), which is also the start of every disc image.
+    #[must_use]
+    pub fn from_disc_header(data: &[u8]) -> Option<Self> {
+        const WII_MAGIC: u32 = 0x5D1C9EA3;
+        const GAMECUBE_MAGIC: u32 = 0xC2339F3D;
+
+        let magic = |offset: usize| Some(u32::from_be_bytes(data.get(offset..offset + 4)?.try_into().ok()?));
+        let platform = match (magic(0x18)?, magic(0x1C)?) {
+            (WII_MAGIC, _) => Platform::Wii,
+            (_, GAMECUBE_MAGIC) => Platform::GameCube,
+            _ => return None,
+        };
+        let id = data.get(..6)?;
+        if !id.iter().all(u8::is_ascii_alphanumeric) {
+            return None;
+        }
+
+        Some(Self {
+            platform,
+            name: latin1(data.get(0x20..0x400)?),
+            publisher: None,
+            id: Some(latin1(id)),
+            region: disc_region(id[3]),
+            version: Some(data.get(7)?.to_string()),
+        })
+    }
+
+    /// Parses a GameCube (`BNR1`/`BNR2`) or Wii (`IMET`) banner, usually stored as `opening.bnr`.
+    ///
+    /// GameCube banners from Japan are stored as Shift-JIS, which is decoded as Latin-1 instead, so only
+    /// ASCII names are readable.
+    #[must_use]
+    pub fn from_banner(data: &[u8]) -> Option<Self> {
+        match data.get(..4)? {
+            // A single description for BNR1, or one for each language (English first) for BNR2
+            b"BNR1" | b"BNR2" => {
+                let description = data.get(0x1820..0x1960)?;
+                let name = non_empty(latin1(&description[0x40..0x80]))
+                    .or_else(|| non_empty(latin1(&description[..0x20])))?;
+                Some(Self {
+                    platform: Platform::GameCube,
+                    name,
+                    publisher: non_empty(latin1(&description[0x80..0xC0]))
+                        .or_else(|| non_empty(latin1(&description[0x20..0x40]))),
+                    id: None,
+                    region: None,
+                    version: None,
+                })
+            }
+            // Disc banners have a 0x40-byte header before IMET, but channel banners don't
+            _ => {
+                let start = match (data.get(..4)?, data.get(0x40..0x44)) {
+                    (b"IMET", _) => 0,
+                    (_, Some(b"IMET")) => 0x40,
+                    _ => return None,
+                };
+                // Names are stored in Japanese, English, German, French, Spanish, Italian, Dutch, then
+                // Chinese and Korean, with 42 UTF-16 characters each
+                let names = data.get(start + 0x1C..start + 0x1C + 10 * 0x54)?;
+                let name = [1, 0, 2, 3, 4, 5, 6, 7, 8, 9].into_iter().find_map(|language| {
+                    non_empty(utf16_be(&names[language * 0x54..(language + 1) * 0x54]))
+                })?;
+                Some(Self {
+                    platform: Platform::Wii,
+                    name,
+                    publisher: None,
+                    id: None,
+                    region: None,
+                    version: None,
+                })
+            }
+        }
+    }
+
+    /// Parses a Wii U `meta.xml`, preferring the English name.
+    #[must_use]
+    pub fn from_meta_xml(text: &str) -> Option<Self> {
+        /// Returns the text inside of the first `tag` element, with any entities decoded.
+        fn element(text: &str, tag: &str) -> Option<String> {
+            let start = text.find(&format!("<{tag}"))?;
+            let content = &text[start + text[start..].find('>')? + 1..];
+            let content = &content[..content.find(&format!("</{tag}>"))?];
+            let content = content
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&");
+            non_empty(content.trim().replace('\n', " "))
+        }
+
+        if !text.contains("<menu") {
+            return None;
+        }
+        const LANGUAGES: [&str; 12] = [
+            "en", "ja", "fr", "de", "it", "es", "zhs", "ko", "nl", "pt", "ru", "zht",
+        ];
+        let name = LANGUAGES.iter().find_map(|language| element(text, &format!("longname_{language}")))?;
+        let publisher = LANGUAGES.iter().find_map(|language| element(text, &format!("publisher_{language}")));
+
+        // Each bit is a region the title can be played in
+        let region = element(text, "region").and_then(|region| u32::from_str_radix(&region, 16).ok());
+        let region = match region {
+            Some(0x1) => Some(Region::Japan),
+            Some(0x2) => Some(Region::America),
+            Some(0x4) => Some(Region::Europe),
+            Some(0x10) => Some(Region::China),
+            Some(0x20) => Some(Region::Korea),
+            Some(0x40) => Some(Region::Taiwan),
+            Some(0) | None => None,
+            Some(_) => Some(Region::Global),
+        };
+
+        Some(Self {
+            platform: Platform::WiiU,
+            name,
+            publisher,
+            id: element(text, "title_id"),
+            region,
+            version: element(text, "title_version"),
+        })
+    }
+
+    /// Parses a Switch NACP (`control.nacp`), preferring the American English name. Switch titles are
+    /// region-free, so the region is always [`Global`](Region::Global).
+    #[must_use]
+    pub fn from_nacp(data: &[u8]) -> Option<Self> {
+        const SIZE: usize = 0x4000;
+        const TITLE_SIZE: usize = 0x300;
+
+        let data = data.get(..SIZE)?;
+        // There are 16 titles, one for each language, each with a name followed by the publisher
+        let (name, publisher) = data[..16 * TITLE_SIZE].chunks_exact(TITLE_SIZE).find_map(|title| {
+            Some((
+                non_empty(utf8(&title[..0x200]))?,
+                non_empty(utf8(&title[0x200..])),
+            ))
+        })?;
+        let id = u64::from_le_bytes(data[0x3038..0x3040].try_into().ok()?);
+
+        Some(Self {
+            platform: Platform::Switch,
+            name,
+            publisher,
+            id: (id != 0).then(|| format!("{id:016X}")),
+            region: Some(Region::Global),
+            version: non_empty(utf8(&data[0x3060..0x3070])),
+        })
+    }
+
+    /// Parses a PlayStation `PARAM.SFO`.
+    #[must_use]
+    pub fn from_sfo(data: &[u8]) -> Option<Self> {
+        let read_u16 =
+            |offset: usize| Some(u16::from_le_bytes(data.get(offset..offset + 2)?.try_into().ok()?));
+        let read_u32 =
+            |offset: usize| Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?));
+        if data.get(..4)? != b"\0PSF" {
+            return None;
+        }
+        let (keys, values, count) = (read_u32(0x8)? as usize, read_u32(0xC)? as usize, read_u32(0x10)?);
+
+        // Each entry is the offset of its key, its format, the length and capacity of its value, then the
+        // offset of its value, but only strings are needed here
+        let value = |wanted: &str| {
+            (0..count as usize).find_map(|index| {
+                let entry = 0x14 + index * 0x10;
+                let key = data.get(keys + usize::from(read_u16(entry)?)..)?;
+                let key = &key[..key.iter().position(|&byte| byte == 0)?];
+                let (format, length) = (read_u16(entry + 2)?, read_u32(entry + 4)? as usize);
+                let offset = values + read_u32(entry + 0xC)? as usize;
+                match (key == wanted.as_bytes(), format) {
+                    (true, 0x0004 | 0x0204) => non_empty(utf8(data.get(offset..offset + length)?)),
+                    _ => None,
+                }
+            })
+        };
+
+        // Disc and store IDs store the region as the third letter, such as BLUS or NPEB
+        let id = value("TITLE_ID");
+        let region = id.as_deref().map(str::as_bytes).and_then(|id| match id.get(2) {
+            Some(b'U') => Some(Region::America),
+            Some(b'E') => Some(Region::Europe),
+            Some(b'J') => Some(Region::Japan),
+            Some(b'A') => Some(Region::Asia),
+            Some(b'K') => Some(Region::Korea),
+            _ => None,
+        });
+
+        Some(Self {
+            platform: Platform::PlayStation,
+            name: value("TITLE")?,
+            publisher: None,
+            id,
+            region,
+            version: value("APP_VER").or_else(|| value("VERSION")),
+        })
+    }
+}
@@ -1,27 +1,158 @@
 // The identification system will get very bulky since it staticly links every function so it gets
 // its own file
-use orthrus_core::prelude::*;
-use orthrus_ncompress::prelude::*;
-use orthrus_panda3d::prelude::*;
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-static SHALLOW_SCAN: [IdentifyFn; 3] = [Yay0::identify, Yaz0::identify, Multifile::identify];
+use orthrus::core::prelude::*;
+use orthrus::ncompress::prelude::*;
+use orthrus::ncompress::scan;
+use orthrus::nintendoware::bfres::BFRES;
+use orthrus::nintendoware::prelude::*;
+use orthrus::panda3d::prelude::*;
 
-static DEEP_SCAN: [IdentifyFn; 3] = [Yay0::identify_deep, Yaz0::identify_deep, Multifile::identify_deep];
+static SHALLOW_SCAN: [(&str, IdentifyFn); 9] = [
+    ("Yay0-compressed file", Yay0::identify),
+    ("Yaz0-compressed file", Yaz0::identify),
+    ("Panda3D Multifile archive", Multifile::identify),
+    ("NintendoWare Sound Archive (BFSAR)", SoundArchive::identify),
+    ("NintendoWare Resource File (BFRES)", BFRES::identify),
+    ("NintendoWare NX Texture container (BNTX)", Switch::BNTX::identify),
+    ("NintendoWare Audio Resource Set (BARS)", Switch::BARS::identify),
+    ("NintendoWare Binary Wave (BWAV)", Switch::BWAV::identify),
+    ("Nintendo Switch Opus stream", Switch::OpusStream::identify),
+];
 
-pub(crate) fn identify_file(input: &str, deep_scan: bool) {
-    let data = std::fs::read(input).expect("Unable to open file for identification!");
+// LZ11 only has a single byte of magic, so it's only checked when the whole stream can be walked
+static DEEP_SCAN: [(&str, IdentifyFn); 10] = [
+    ("Yay0-compressed file", Yay0::identify_deep),
+    ("Yaz0-compressed file", Yaz0::identify_deep),
+    ("LZ11-compressed file", Lz11::identify_deep),
+    ("Panda3D Multifile archive", Multifile::identify_deep),
+    ("NintendoWare Sound Archive (BFSAR)", SoundArchive::identify_deep),
+    ("NintendoWare Resource File (BFRES)", BFRES::identify_deep),
+    (
+        "NintendoWare NX Texture container (BNTX)",
+        Switch::BNTX::identify_deep,
+    ),
+    (
+        "NintendoWare Audio Resource Set (BARS)",
+        Switch::BARS::identify_deep,
+    ),
+    ("NintendoWare Binary Wave (BWAV)", Switch::BWAV::identify_deep),
+    ("Nintendo Switch Opus stream", Switch::OpusStream::identify_deep),
+];
 
-    let mut identified_types: Vec<FileInfo> = vec![];
-    let scan_list = if deep_scan { &DEEP_SCAN } else { &SHALLOW_SCAN };
+/// Formats that don't have their own identification yet, but can be recognized from their header.
+fn signatures() -> [(&'static str, identify::Signature); 3] {
+    use identify::{Check, Signature};
+    // NintendoWare headers store a byte order mark, which is either way around depending on the platform
+    let byte_order = |offset| {
+        [
+            Signature::new().magic(offset, b"\xFE\xFF"),
+            Signature::new().magic(offset, b"\xFF\xFE"),
+        ]
+    };
+    // Every RARC written by Nintendo's tools has a 0x20-byte header
+    let rarc = Signature::new().magic(0, b"RARC").u32(4, Endian::Big, Check::WithinData).u32(
+        8,
+        Endian::Big,
+        Check::Equals(0x20),
+    );
+    [
+        ("JSystem Resource Archive (RARC)", rarc),
+        (
+            "Godot resource pack (PCK)",
+            Signature::new().magic(0, b"GDPC").u32(4, Endian::Little, Check::Range(0..=2)),
+        ),
+        (
+            "NintendoWare BRSTM streamed audio (NW4R)",
+            Signature::new().magic(0, b"RSTM").any_of(byte_order(4)),
+        ),
+    ]
+}
 
-    for identifier in scan_list {
-        if let Some(identity) = identifier(&data) {
-            identified_types.push(identity);
+/// Describes `data` if it's a recognized image, where `deep_scan` also checks that the file is complete.
+/// Returns the name of the image format along with the description.
+fn texture_type(data: &[u8], deep_scan: bool) -> Option<(&'static str, FileInfo)> {
+    let header = orthrus::texture::header::read(data)?;
+    let mut info = format!(
+        "{}, {}x{} {}, {} mip level{}",
+        header.container.name(),
+        header.width,
+        header.height,
+        header.format,
+        header.mip_count,
+        if header.mip_count == 1 { "" } else { "s" }
+    );
+    if let (true, Some(expected)) = (deep_scan, header.expected_size) {
+        match data.len().cmp(&expected) {
+            core::cmp::Ordering::Less => {
+                info += &format!(
+                    " (truncated, expected {expected:#X} bytes but found {:#X})",
+                    data.len()
+                );
+            }
+            core::cmp::Ordering::Greater => {
+                info += &format!(" ({:#X} bytes of trailing data)", data.len() - expected);
+            }
+            core::cmp::Ordering::Equal => {}
+        }
+    }
+    Some((header.container.name(), FileInfo::new(info, None)))
+}
+
+/// Returns every filetype that `data` could be along with the name of its format, which is empty if nothing
+/// recognized it.
+fn identify_formats(data: &[u8], deep_scan: bool) -> Vec<(&'static str, FileInfo)> {
+    identify_with_signatures(data, deep_scan, &signatures())
+}
+
+/// Same as [`identify_formats`], but with the signatures already built so they can be reused when checking
+/// many offsets of the same file.
+fn identify_with_signatures(
+    data: &[u8], deep_scan: bool, signatures: &[(&'static str, identify::Signature)],
+) -> Vec<(&'static str, FileInfo)> {
+    let mut identified_types = vec![];
+    let scan_list: &[_] = if deep_scan { &DEEP_SCAN } else { &SHALLOW_SCAN };
+
+    for (format, identifier) in scan_list {
+        if let Some(identity) = identifier(data) {
+            identified_types.push((*format, identity));
+        }
+    }
+    for (format, signature) in signatures {
+        if let Some(identity) = signature.identify(data, format) {
+            identified_types.push((*format, identity));
         }
     }
+    identified_types.extend(texture_type(data, deep_scan));
+    identified_types
+}
+
+/// Returns every filetype that `data` could be, which is empty if nothing recognized it.
+pub(crate) fn identify_types(data: &[u8], deep_scan: bool) -> Vec<FileInfo> {
+    identify_formats(data, deep_scan).into_iter().map(|(_, identity)| identity).collect()
+}
+
+pub(crate) fn identify_file(input: &str, deep_scan: bool) {
+    let data = std::fs::read(input).expect("Unable to open file for identification!");
+    let mut identified_types = identify_types(&data, deep_scan);
+    // Some metadata files can only be recognized by their name, so these are checked separately
+    if let Some(title) = orthrus::tools::title::Title::parse(input, &data) {
+        identified_types.push(FileInfo::new(
+            format!("{} title metadata for {title}", title.platform),
+            None,
+        ));
+    }
 
+    // Fall back to where the file is stored if its contents aren't enough
     match identified_types.len() {
-        0 => println!("{input}: data"),
+        0 => match orthrus::tools::dump::label(input) {
+            Some(label) => println!("{input}: data (conventionally {label})"),
+            None => println!("{input}: data"),
+        },
         1 => {
             println!("{input}: {}", identified_types[0].info);
             if let Some(payload) = identified_types[0].payload.as_ref() {
@@ -43,11 +174,12 @@ pub(crate) fn identify_file(input: &str, deep_scan: bool) {
 fn identify_deep(data: &[u8], indent: usize) {
     let mut identified_types: Vec<FileInfo> = vec![];
 
-    for identifier in DEEP_SCAN {
+    for (_, identifier) in DEEP_SCAN {
         if let Some(identity) = identifier(data) {
             identified_types.push(identity);
         }
     }
+    identified_types.extend(texture_type(data, true).map(|(_, identity)| identity));
 
     let indentation = "    ".repeat(indent);
 
@@ -70,3 +202,240 @@ fn identify_deep(data: &[u8], indent: usize) {
         }
     }
 }
+
+/// Totals from identifying every file in a folder, see [`identify_directory`].
+#[derive(Default)]
+struct DirectoryReport {
+    files: usize,
+    /// How many files were identified as each format, where files with multiple possible formats count
+    /// towards each of them
+    formats: BTreeMap<&'static str, usize>,
+    /// Files that weren't recognized, relative to the folder
+    unknown: Vec<String>,
+    /// Files that couldn't be read, relative to the folder
+    unreadable: Vec<String>,
+}
+
+impl core::fmt::Display for DirectoryReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let width = self.files.to_string().len();
+        writeln!(f, "{} files scanned", self.files)?;
+        // Most common formats first, then alphabetically
+        let mut formats: Vec<_> = self.formats.iter().collect();
+        formats.sort_by(|a, b| b.1.cmp(a.1));
+        for (format, count) in formats {
+            writeln!(f, "{count:>width$} {format}")?;
+        }
+        writeln!(f, "{:>width$} unknown", self.unknown.len())?;
+        for path in &self.unknown {
+            writeln!(f, "- {path}")?;
+        }
+        if !self.unreadable.is_empty() {
+            writeln!(f, "{:>width$} unreadable", self.unreadable.len())?;
+            for path in &self.unreadable {
+                writeln!(f, "- {path}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the path of every file inside of `root`, relative to it and sorted so reports are the same on
+/// every run.
+fn collect_files(root: &Path) -> std::io::Result<Vec<String>> {
+    fn collect(files: &mut Vec<String>, path: &Path, prefix: &str) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let name = match prefix.is_empty() {
+                true => name,
+                false => format!("{prefix}/{name}"),
+            };
+            match entry.file_type()?.is_dir() {
+                true => collect(files, &entry.path(), &name)?,
+                false => files.push(name),
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    collect(&mut files, root, "")?;
+    files.sort_unstable();
+    Ok(files)
+}
+
+/// Identifies every file inside of `input` using every core, and prints how many files were found of each
+/// format along with every file that wasn't recognized.
+pub(crate) fn identify_directory(input: &str, deep_scan: bool) -> std::io::Result<()> {
+    let root = Path::new(input);
+    let files = collect_files(root)?;
+    let threads = std::thread::available_parallelism().map_or(1, NonZeroUsize::get).min(files.len());
+
+    // Workers take the next file from a shared counter, so large files don't hold up a whole batch
+    let next = AtomicUsize::new(0);
+    let results: Vec<Option<Vec<&'static str>>> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                let (files, next) = (&files, &next);
+                scope.spawn(move || {
+                    let mut results = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(path) = files.get(index) else {
+                            break;
+                        };
+                        let formats = std::fs::read(root.join(path)).ok().map(|data| {
+                            identify_formats(&data, deep_scan).into_iter().map(|(format, _)| format).collect()
+                        });
+                        results.push((index, formats));
+                    }
+                    results
+                })
+            })
+            .collect();
+
+        let mut results = vec![None; files.len()];
+        for worker in workers {
+            // Identification never panics on bad data, so a worker only fails if something is very wrong
+            for (index, formats) in worker.join().expect("Identification thread panicked!") {
+                results[index] = formats;
+            }
+        }
+        results
+    });
+
+    let mut report = DirectoryReport { files: files.len(), ..Default::default() };
+    for (path, formats) in files.into_iter().zip(results) {
+        match formats {
+            Some(formats) if formats.is_empty() => report.unknown.push(path),
+            Some(formats) => {
+                for format in formats {
+                    *report.formats.entry(format).or_default() += 1;
+                }
+            }
+            None => report.unreadable.push(path),
+        }
+    }
+    print!("{report}");
+    Ok(())
+}
+
+/// A known format found inside of a larger file, see [`carve_file`].
+struct Carved {
+    offset: usize,
+    size: usize,
+    formats: Vec<(&'static str, FileInfo)>,
+}
+
+/// Returns the size stored in the header of `data`, for formats that record their own size. Anything found
+/// inside of that range belongs to this file, so scanning can skip past it.
+fn declared_size(data: &[u8]) -> Option<usize> {
+    let read = |offset: usize, endian| {
+        let field = data.get(offset..offset + 4)?.try_into().ok()?;
+        let size = match endian {
+            Endian::Big => u32::from_be_bytes(field),
+            Endian::Little => u32::from_le_bytes(field),
+        };
+        Some(size as usize)
+    };
+    let byte_order = |offset: usize| match data.get(offset..offset + 2)? {
+        [0xFE, 0xFF] => Some(Endian::Big),
+        [0xFF, 0xFE] => Some(Endian::Little),
+        _ => None,
+    };
+    match data.get(..4)? {
+        b"RARC" => read(4, Endian::Big),
+        b"RSTM" => read(8, Endian::Big),
+        b"BARS" => read(4, byte_order(8)?),
+        b"FSAR" | b"CSAR" => read(0xC, byte_order(4)?),
+        _ => None,
+    }
+}
+
+/// Finds every known format embedded inside of `data`, checking every offset that's a multiple of `align`.
+///
+/// Hits have to make sense for the data around them: compressed streams have to decompress without going out
+/// of bounds, and formats that store their own size have to fit inside of the rest of the data. Anything that
+/// doesn't store its size is assumed to continue until the next hit.
+fn carve(data: &[u8], deep_scan: bool, align: usize) -> Vec<Carved> {
+    let signatures = signatures();
+    // Walking every compressed stream is the only way to know where it ends, so do them all in one pass
+    let streams: BTreeMap<usize, usize> = scan::find_streams(data)
+        .into_iter()
+        .map(|(offset, header)| (offset, header.compressed_size))
+        .collect();
+
+    let mut carved: Vec<Carved> = Vec::new();
+    // Nothing we can identify is smaller than a compressed stream's header
+    let mut offset = 0;
+    while offset + 0x10 <= data.len() {
+        let remaining = &data[offset..];
+        let mut formats = identify_with_signatures(remaining, deep_scan, &signatures);
+        formats.retain(|(format, _)| {
+            !matches!(*format, "Yay0-compressed file" | "Yaz0-compressed file")
+                || streams.contains_key(&offset)
+        });
+
+        let size = match streams.get(&offset) {
+            Some(&size) => Some(size),
+            None => declared_size(remaining),
+        };
+        match (formats.is_empty(), size) {
+            // The header claims more data than there is, so this is most likely a coincidence
+            (false, Some(size)) if size < 0x10 || size > remaining.len() => offset += align,
+            (false, Some(size)) => {
+                carved.push(Carved { offset, size, formats });
+                offset = (offset + size).next_multiple_of(align);
+            }
+            (false, None) => {
+                carved.push(Carved { offset, size: 0, formats });
+                offset += align;
+            }
+            (true, _) => offset += align,
+        }
+    }
+
+    // Anything without a known size runs until the next hit, or the end of the data
+    let ends: Vec<usize> = carved.iter().skip(1).map(|hit| hit.offset).chain([data.len()]).collect();
+    for (hit, end) in carved.iter_mut().zip(ends) {
+        if hit.size == 0 {
+            hit.size = end - hit.offset;
+        }
+    }
+    carved
+}
+
+/// Scans `input` for every known format embedded inside of it, such as files inside of an unknown archive or
+/// a RAM dump, and prints the offset and size of each one. If `output` is given, each one is also written to
+/// it, named after its offset.
+pub(crate) fn carve_file(
+    input: &str, deep_scan: bool, align: usize, output: Option<&Path>,
+) -> std::io::Result<()> {
+    let data = std::fs::read(input)?;
+    let carved = carve(&data, deep_scan, align.max(1));
+    if let Some(output) = output {
+        std::fs::create_dir_all(output)?;
+    }
+
+    for hit in &carved {
+        let info: Vec<&str> = hit.formats.iter().map(|(_, identity)| identity.info.as_str()).collect();
+        println!(
+            "{:#010X} ({:#X} bytes): {}",
+            hit.offset,
+            hit.size,
+            info.join(" / ")
+        );
+        if let Some(output) = output {
+            let path = output.join(format!("{:08X}.bin", hit.offset));
+            log::info!("Writing file {}", path.display());
+            std::fs::write(path, &data[hit.offset..hit.offset + hit.size])?;
+        }
+    }
+    println!(
+        "{} file{} found",
+        carved.len(),
+        if carved.len() == 1 { "" } else { "s" }
+    );
+    Ok(())
+}
@@ -0,0 +1,97 @@
+//! Orthrus is a modding toolkit that supports a wide array of game file formats, split into a crate for each
+//! engine or middleware. This crate re-exports all of them in one place, so that tools only need to depend on
+//! (and keep up to date) a single crate, and is the same API the `orthrus` command line application is built
+//! on.
+//!
+//! Each module is enabled by the feature of the same name, and `full` enables every module:
+//! * [`core`] is always available, for data streams, checksums, and other shared functionality.
+//! * `ncompress` - Nintendo compression formats, such as Yay0, Yaz0, and LZ11.
+//! * `panda3d` - Panda3D Multifiles and BAM models.
+//! * `godot` - Godot resource packs and project settings.
+//! * `jsystem` - Nintendo JSystem archives, textures, and animations.
+//! * `nintendoware` - NintendoWare audio and resource containers.
+//! * `texture` (experimental) - Console texture codecs.
+//! * `tools` (experimental) - Workflows that span multiple formats.
+//!
+//! The `crypto` feature additionally lets `tools` decrypt Switch NCAs, with keys provided by the user, and
+//! the `simd` feature speeds up finding matches when compressing with `ncompress`.
+//!
+//! The `cli` feature (enabled by default) only builds the command line application, so libraries should use
+//! `default-features = false`.
+//!
+//! # Stability
+//! This crate follows semver for everything reachable through the stable modules, so any breaking change in
+//! one of them is a breaking release here, even if the underlying crate's version was already bumped. The
+//! experimental modules are exempt, and can change in any release until they're stabilized.
+//!
+//! # Usage
+//! ```
+//! use orthrus::prelude::*;
+//!
+//! let compressed = Yaz0::compress_from(b"Hello, world!", yaz0::CompressionAlgo::MatchingOld, 0)?;
+//! assert_eq!(&*Yaz0::decompress_from(&compressed)?, b"Hello, world!");
+//! # Ok::<(), yaz0::Error>(())
+//! ```
+
+// The command line's dependencies are still passed to the library
+#[cfg(feature = "cli")]
+use {
+    anyhow as _, argp as _, env_logger as _, log as _, mimalloc as _, owo_colors as _, paste as _,
+    serde_json as _,
+};
+
+#[doc(inline)]
+pub use orthrus_core as core;
+#[cfg(feature = "godot")]
+#[doc(inline)]
+pub use orthrus_godot as godot;
+#[cfg(feature = "jsystem")]
+#[doc(inline)]
+pub use orthrus_jsystem as jsystem;
+#[cfg(feature = "ncompress")]
+#[doc(inline)]
+pub use orthrus_ncompress as ncompress;
+#[cfg(feature = "nintendoware")]
+#[doc(inline)]
+pub use orthrus_nintendoware as nintendoware;
+#[cfg(feature = "panda3d")]
+#[doc(inline)]
+pub use orthrus_panda3d as panda3d;
+#[cfg(feature = "texture")]
+#[doc(inline)]
+pub use orthrus_texture as texture;
+#[cfg(feature = "tools")]
+#[doc(inline)]
+pub use orthrus_tools as tools;
+
+/// Convenient re-exports of the preludes of every enabled module, designed to make crate usage painless.
+///
+/// The contents of this module can be used by including the following in any module:
+/// ```ignore
+/// use orthrus::prelude::*;
+/// ```
+pub mod prelude {
+    #[doc(inline)]
+    pub use orthrus_core::prelude::*;
+    #[cfg(feature = "godot")]
+    #[doc(inline)]
+    pub use orthrus_godot::prelude::*;
+    #[cfg(feature = "jsystem")]
+    #[doc(inline)]
+    pub use orthrus_jsystem::prelude::*;
+    #[cfg(feature = "ncompress")]
+    #[doc(inline)]
+    pub use orthrus_ncompress::prelude::*;
+    #[cfg(feature = "nintendoware")]
+    #[doc(inline)]
+    pub use orthrus_nintendoware::prelude::*;
+    #[cfg(feature = "panda3d")]
+    #[doc(inline)]
+    pub use orthrus_panda3d::prelude::*;
+    #[cfg(feature = "texture")]
+    #[doc(inline)]
+    pub use orthrus_texture::prelude::*;
+    #[cfg(feature = "tools")]
+    #[doc(inline)]
+    pub use orthrus_tools::prelude::*;
+}
@@ -4,24 +4,37 @@ use mimalloc::MiMalloc;
 static GLOBAL: MiMalloc = MiMalloc;
 
 use std::io::prelude::*;
-use std::path::PathBuf;
+use std::path::Path;
 
 use anyhow::Result;
 use env_logger::Builder;
 use log::{Level, LevelFilter};
-use orthrus_godot::prelude::*;
-use orthrus_jsystem::prelude::*;
-use orthrus_ncompress::prelude::*;
-use orthrus_nintendoware::prelude::*;
-use orthrus_panda3d::prelude::*;
+use orthrus::core::data::{DataStream, Endian, ReadExt, RetryPolicy, SeekExt};
+use orthrus::core::extract::{ExtractMode, Extraction, OverwritePolicy};
+use orthrus::core::limits::ResourceLimits;
+use orthrus::core::sanitize::PathOptions;
+use orthrus::core::util::{append_suffix, output_dir, output_path};
+use orthrus::godot::prelude::*;
+use orthrus::jsystem::prelude::*;
+use orthrus::ncompress::prelude::*;
+use orthrus::ncompress::scan;
+use orthrus::nintendoware::prelude::*;
+use orthrus::panda3d::prelude::*;
+use orthrus::tools::manifest::{Manifest, Source, Status};
 use owo_colors::OwoColorize;
 
 mod identify;
 mod menu;
+mod serve;
 use menu::{
     exactly_one_true, GodotModules, JSystemModules, Modules, NCompressModules, NintendoWareModules,
     Panda3dModules,
 };
+// Every module is used through the library, but they're still passed to the binary
+use {
+    orthrus_core as _, orthrus_godot as _, orthrus_jsystem as _, orthrus_ncompress as _,
+    orthrus_nintendoware as _, orthrus_panda3d as _, orthrus_texture as _, orthrus_tools as _,
+};
 
 fn color_level(level: Level) -> String {
     match level {
@@ -45,6 +58,226 @@ const fn level_filter(verbose: usize) -> LevelFilter {
     }
 }
 
+/// Reads a list of `key = value` lines from a file, skipping blank lines and lines starting with `#`.
+fn read_pairs(path: &str, expected: &str) -> Result<Vec<(String, String)>> {
+    let mut pairs = Vec::new();
+    for line in std::fs::read_to_string(path)?.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            anyhow::bail!("Expected `{expected}`, found {line:?}");
+        };
+        pairs.push((key.trim().to_owned(), value.trim().to_owned()));
+    }
+    Ok(pairs)
+}
+
+/// Picks how archives are extracted based on the global `--dry-run` and `--staged` switches.
+fn extract_mode(dry_run: bool, staged: bool) -> Result<ExtractMode> {
+    match (dry_run, staged) {
+        (true, true) => anyhow::bail!("--dry-run and --staged can't be used together"),
+        (true, false) => Ok(ExtractMode::DryRun),
+        (false, true) => Ok(ExtractMode::Staged),
+        (false, false) => Ok(ExtractMode::Direct),
+    }
+}
+
+/// Picks what happens to extracted files that collide with another, based on the global `--overwrite` option.
+fn overwrite_policy(name: Option<&str>) -> Result<OverwritePolicy> {
+    match name {
+        Some(name) => OverwritePolicy::from_name(name).ok_or_else(|| {
+            anyhow::anyhow!("Unsupported overwrite policy {name}, expected overwrite, skip, rename, or error")
+        }),
+        None => Ok(OverwritePolicy::default()),
+    }
+}
+
+/// Finishes an extraction, moving any staged files into place, or listing what would have been written
+/// during a dry run.
+fn finish_extraction(extraction: Extraction) -> Result<()> {
+    let dry_run = extraction.mode() == ExtractMode::DryRun;
+    let output = extraction.output().to_owned();
+    let files = extraction.commit()?;
+    for file in &files {
+        let path = output.join(&file.path);
+        let note = match (file.skipped, &file.renamed_from) {
+            (true, _) => " (skipped, collides with another file)".to_owned(),
+            (false, Some(original)) => format!(" (renamed from {})", original.display()),
+            (false, None) if file.overwrites => " (replaces existing file)".to_owned(),
+            (false, None) => String::new(),
+        };
+        match dry_run {
+            true => println!("{} ({} bytes){note}", path.display(), file.size),
+            false if !note.is_empty() => log::warn!("{}{note}", path.display()),
+            false => {}
+        }
+    }
+    if dry_run {
+        let count = files.iter().filter(|file| !file.skipped).count();
+        println!("{count} files would be written");
+    }
+    Ok(())
+}
+
+/// Extracts every stream of the given format embedded in a file, naming each one after its offset.
+fn extract_streams<P: AsRef<Path>>(input: &str, output: P, format: scan::Format) -> Result<()> {
+    let data = std::fs::read(input)?;
+    std::fs::create_dir_all(&output)?;
+    for (offset, header) in scan::find_streams(&data) {
+        if header.format != format {
+            continue;
+        }
+        let Some(stream) = header.decompress(&data[offset..]) else {
+            continue;
+        };
+        let path = output.as_ref().join(format!("{offset:08X}.bin"));
+        log::info!("Writing file {}", path.display());
+        std::fs::write(path, stream)?;
+    }
+    Ok(())
+}
+
+/// Decompresses a Yay0, Yaz0, or LZ11-compressed file and recompresses it using the format named `to`,
+/// writing it next to the input with that format's usual extension if no output is given.
+fn convert_compression(input: &str, output: Option<String>, to: &str) -> Result<()> {
+    let (format, extension) = match to.to_ascii_lowercase().as_str() {
+        "yay0" => ("Yay0", "szp"),
+        "yaz0" => ("Yaz0", "szs"),
+        "lz11" => ("LZ11", "lz"),
+        _ => anyhow::bail!("Unsupported format {to}, expected yay0, yaz0, or lz11"),
+    };
+
+    let data = std::fs::read(input)?;
+    // LZ11 only has a single byte of magic, so check it last
+    let (detected, data) = if data.starts_with(&Yay0::MAGIC) {
+        ("Yay0", Yay0::decompress_from(&data)?)
+    } else if data.starts_with(&Yaz0::MAGIC) {
+        ("Yaz0", Yaz0::decompress_from(&data)?)
+    } else if data.first() == Some(&Lz11::MAGIC) {
+        ("LZ11", Lz11::decompress_from(&data)?)
+    } else {
+        anyhow::bail!("{input} isn't compressed using Yay0, Yaz0, or LZ11");
+    };
+    if detected == format {
+        eprintln!("{input} is already compressed using {format}!");
+        return Ok(());
+    }
+    log::info!("Converting file {input} from {detected} to {format}");
+
+    let data = match format {
+        "Yay0" => Yay0::compress_from(&data, yay0::CompressionAlgo::MatchingOld, 0)?,
+        "Yaz0" => Yaz0::compress_from(&data, yaz0::CompressionAlgo::MatchingOld, 0)?,
+        _ => Lz11::compress_from(&data)?,
+    };
+    let output = output_path(input, output, extension);
+    log::info!("Writing file {}", output.display());
+    std::fs::write(output, data)?;
+    Ok(())
+}
+
+/// Prints every animation in a BAM file, along with how many values each joint stores for each channel and
+/// whether it actually moves.
+fn print_animations(asset: &BinaryAsset) {
+    let animations = asset.animations();
+    if animations.is_empty() {
+        println!("No animations found");
+    }
+    for animation in animations {
+        let animated = animation.joints.iter().filter(|joint| joint.is_animated()).count();
+        let seconds = match animation.fps > 0.0 {
+            true => f32::from(animation.num_frames) / animation.fps,
+            false => 0.0,
+        };
+        println!(
+            "{}: {} fps, {} frames ({seconds:.2} seconds), {} joints ({animated} animated, {} constant)",
+            animation.name,
+            animation.fps,
+            animation.num_frames,
+            animation.joints.len(),
+            animation.joints.len() - animated
+        );
+
+        // Line up every table size under the letter of its channel, with joints indented by their depth
+        let name_width = animation
+            .joints
+            .iter()
+            .map(|joint| joint.depth * 2 + joint.name.len())
+            .chain(["joint".len()])
+            .max()
+            .unwrap_or_default();
+        let size_width = animation
+            .joints
+            .iter()
+            .flat_map(|joint| joint.table_sizes)
+            .map(|size| size.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let channels: Vec<String> =
+            bam::AnimatedJoint::CHANNELS.iter().map(|channel| format!("{channel:>size_width$}")).collect();
+        println!("    {:name_width$} {}", "joint", channels.join(" "));
+        for joint in &animation.joints {
+            let name = format!("{}{}", "  ".repeat(joint.depth), joint.name);
+            let sizes: Vec<String> =
+                joint.table_sizes.iter().map(|size| format!("{size:>size_width$}")).collect();
+            let state = match joint.is_animated() {
+                true => "animated",
+                false => "constant",
+            };
+            println!("    {name:name_width$} {}  {state}", sizes.join(" "));
+        }
+    }
+}
+
+/// Prints the path of every file inside of a Multifile or Godot PCK, reading only the index.
+fn list_entries<T: Read + Seek, R: RetryPolicy>(data: &mut DataStream<T, R>) -> Result<()> {
+    let mut magic = [0u8; 4];
+    data.read_length(&mut magic)?;
+    data.set_position(0)?;
+
+    let count = match magic == ResourcePack::MAGIC {
+        true => {
+            let pack = ResourcePack::load(&mut **data)?;
+            pack.paths().map(|path| println!("{path}")).count()
+        }
+        // Multifiles can start with comment lines, so anything else is assumed to be one
+        false => Multifile::list_from(data, 0)?
+            .iter()
+            .filter(|subfile| !subfile.is_deleted())
+            .map(|subfile| println!("{}", subfile.name()))
+            .count(),
+    };
+    log::info!("{count} files found");
+    Ok(())
+}
+
+/// Lists every file inside of an archive, which can be on a web server when built with the `http` feature,
+/// in which case only the archive's index is downloaded.
+fn list_archive(input: &str) -> Result<()> {
+    match input.starts_with("http://") || input.starts_with("https://") {
+        #[cfg(feature = "http")]
+        true => {
+            let reader = orthrus::core::http::HttpReader::open(input)?;
+            let retry = orthrus::core::data::Retry::new(3, std::time::Duration::from_secs(1));
+            let mut data = DataStream::new(reader, Endian::Little).with_retry(retry);
+            list_entries(&mut data)?;
+            log::info!(
+                "Downloaded {} of {} in {} requests",
+                orthrus::core::util::format_size(data.downloaded() as usize),
+                orthrus::core::util::format_size(data.size() as usize),
+                data.requests()
+            );
+            Ok(())
+        }
+        #[cfg(not(feature = "http"))]
+        true => anyhow::bail!("Listing a URL requires building with the http feature"),
+        false => {
+            let file = std::io::BufReader::new(std::fs::File::open(input)?);
+            list_entries(&mut DataStream::new(file, Endian::Little))
+        }
+    }
+}
+
 fn main() -> Result<()> {
     //Parse command line input
     let args: menu::Orthrus = argp::parse_args_or_exit(argp::DEFAULT);
@@ -57,84 +290,123 @@ fn main() -> Result<()> {
                 writeln!(
                     buf,
                     "[{}] {} {}",
-                    orthrus_core::time::current_time().unwrap(), // Use your custom time function
-                    color_level(record.level()),                 // Colored log level
-                    record.args()                                // Log message
+                    orthrus::core::time::current_time().unwrap(), // Use your custom time function
+                    color_level(record.level()),                  // Colored log level
+                    record.args()                                 // Log message
                 )
             })
             .filter(None, level_filter(args.verbose))
             .init();
     }
 
+    if args.dump_commands {
+        print!("{}", menu::schema::to_json(&menu::Orthrus::COMMAND));
+        return Ok(());
+    }
+    if let Some(shell) = args.completions {
+        match menu::schema::Shell::from_name(&shell) {
+            Some(shell) => print!("{}", menu::schema::completions(shell, &menu::Orthrus::COMMAND)),
+            None => eprintln!("Unsupported shell {shell}, expected bash, zsh, fish, or powershell!"),
+        }
+        return Ok(());
+    }
+    let mode = extract_mode(args.dry_run, args.staged)?;
+    let policy = overwrite_policy(args.overwrite.as_deref())?;
+    let Some(nested) = args.nested else {
+        eprintln!("Please select a module, or run with --help to see them all!");
+        return Ok(());
+    };
+
     // Apologies for this mess, I care more about the crate usage than the command line parsing,
     // it'll get replaced by ui eventually
-    match args.nested {
-        Modules::IdentifyFile(params) => {
-            crate::identify::identify_file(&params.input, params.deep_scan);
+    match nested {
+        Modules::IdentifyFile(params) => match params.recursive {
+            true => crate::identify::identify_directory(&params.input, params.deep_scan)?,
+            false => crate::identify::identify_file(&params.input, params.deep_scan),
+        },
+        Modules::Carve(params) => {
+            let output = params.extract.then(|| output_dir(params.output));
+            crate::identify::carve_file(&params.input, params.deep_scan, params.align, output.as_deref())?;
+        }
+        Modules::List(params) => list_archive(&params.input)?,
+        Modules::Serve(params) => {
+            crate::serve::serve(Path::new(params.root.as_deref().unwrap_or(".")))?;
         }
+        Modules::Manifest(params) => match params.verify {
+            Some(manifest) => {
+                let mismatches = Manifest::open(manifest)?.verify(&params.input)?;
+                for mismatch in &mismatches {
+                    match mismatch.status {
+                        Status::Modified => eprintln!("{}: modified", mismatch.path),
+                        Status::Missing => eprintln!("{}: missing", mismatch.path),
+                    }
+                }
+                if !mismatches.is_empty() {
+                    anyhow::bail!("{} files don't match the manifest", mismatches.len());
+                }
+            }
+            None => {
+                let manifest = Manifest::from_dir(&params.input)?;
+                match params.output {
+                    Some(output) => manifest.save(output)?,
+                    None => print!("{manifest}"),
+                }
+            }
+        },
         Modules::NintendoCompression(module) => match module.nested {
-            NCompressModules::Yay0(params) => match exactly_one_true(&[params.decompress, params.compress]) {
-                Some(0) => {
-                    log::info!("Decompressing file {}", &params.input);
-                    let data = Yay0::decompress_from_path(&params.input)?;
-                    let output = if let Some(output) = params.output {
-                        output
-                    } else {
-                        let mut new_path = PathBuf::from(params.input);
-                        new_path.set_extension("arc");
-                        new_path.to_string_lossy().into_owned()
-                    };
-                    log::info!("Writing file {}", output);
-                    std::fs::write(output, data)?;
-                }
-                Some(1) => {
-                    log::info!("Compressing file {}", &params.input);
-                    let data =
-                        Yay0::compress_from_path(&params.input, yay0::CompressionAlgo::MatchingOld, 0)?;
-                    let output = if let Some(output) = params.output {
-                        output
-                    } else {
-                        let mut new_path = PathBuf::from(params.input);
-                        new_path.set_extension("szp");
-                        new_path.to_string_lossy().into_owned()
-                    };
-                    log::info!("Writing file {}", output);
-                    std::fs::write(output, data)?;
-                }
-                None => eprintln!("Please select exactly one operation!"),
-                _ => unreachable!("Oops! Forgot to cover all operations."),
-            },
-            NCompressModules::Yaz0(params) => match exactly_one_true(&[params.decompress, params.compress]) {
-                Some(0) => {
-                    log::info!("Decompressing file {}", &params.input);
-                    let data = Yaz0::decompress_from_path(&params.input)?;
-                    let output = if let Some(output) = params.output {
-                        output
-                    } else {
-                        let mut new_path = PathBuf::from(params.input);
-                        new_path.set_extension("arc");
-                        new_path.to_string_lossy().into_owned()
-                    };
-                    log::info!("Writing file {}", output);
-                    std::fs::write(output, data)?;
-                }
-                Some(1) => {
-                    log::info!("Compressing file {}", &params.input);
-                    let data =
-                        Yaz0::compress_from_path(&params.input, yaz0::CompressionAlgo::MatchingOld, 0)?;
-                    let output = if let Some(output) = params.output {
-                        output
-                    } else {
-                        let mut new_path = PathBuf::from(params.input);
-                        new_path.set_extension("szs");
-                        new_path.to_string_lossy().into_owned()
-                    };
-                    log::info!("Writing file {}", output);
-                    std::fs::write(output, data)?;
-                }
-                None => eprintln!("Please select exactly one operation!"),
-                _ => unreachable!("Oops! Forgot to cover all operations."),
-            },
+            NCompressModules::Convert(params) => {
+                convert_compression(&params.input, params.output, &params.to)?;
+            }
+            NCompressModules::Yay0(params) => {
+                match exactly_one_true(&[params.decompress, params.compress, params.scan]) {
+                    Some(0) => {
+                        log::info!("Decompressing file {}", &params.input);
+                        let data = Yay0::decompress_from_path(&params.input)?;
+                        let output = output_path(&params.input, params.output, "arc");
+                        log::info!("Writing file {}", output.display());
+                        std::fs::write(output, data)?;
+                    }
+                    Some(1) => {
+                        log::info!("Compressing file {}", &params.input);
+                        let data =
+                            Yay0::compress_from_path(&params.input, yay0::CompressionAlgo::MatchingOld, 0)?;
+                        let output = output_path(&params.input, params.output, "szp");
+                        log::info!("Writing file {}", output.display());
+                        std::fs::write(output, data)?;
+                    }
+                    Some(2) => {
+                        let output = output_dir(params.output);
+                        extract_streams(&params.input, output, scan::Format::Yay0)?;
+                    }
+                    None => eprintln!("Please select exactly one operation!"),
+                    _ => unreachable!("Oops! Forgot to cover all operations."),
+                }
+            }
+            NCompressModules::Yaz0(params) => {
+                match exactly_one_true(&[params.decompress, params.compress, params.scan]) {
+                    Some(0) => {
+                        log::info!("Decompressing file {}", &params.input);
+                        let data = Yaz0::decompress_from_path(&params.input)?;
+                        let output = output_path(&params.input, params.output, "arc");
+                        log::info!("Writing file {}", output.display());
+                        std::fs::write(output, data)?;
+                    }
+                    Some(1) => {
+                        log::info!("Compressing file {}", &params.input);
+                        let data =
+                            Yaz0::compress_from_path(&params.input, yaz0::CompressionAlgo::MatchingOld, 0)?;
+                        let output = output_path(&params.input, params.output, "szs");
+                        log::info!("Writing file {}", output.display());
+                        std::fs::write(output, data)?;
+                    }
+                    Some(2) => {
+                        let output = output_dir(params.output);
+                        extract_streams(&params.input, output, scan::Format::Yaz0)?;
+                    }
+                    None => eprintln!("Please select exactly one operation!"),
+                    _ => unreachable!("Oops! Forgot to cover all operations."),
+                }
+            }
         },
         Modules::Panda3D(module) => match module.nested {
             Panda3dModules::Multifile(data) => {
@@ -142,29 +414,144 @@ fn main() -> Result<()> {
                     Some(0) => {
                         // Ideally I could log each file path as it's written but I would have
                         // to refactor Multifile to use slice_take
-                        let output = data.output.unwrap_or_else(|| ".".to_string());
-                        orthrus_panda3d::multifile2::Multifile::extract_from_file(data.input, output)?;
+                        let extraction = Extraction::new(output_dir(data.output), mode)?.overwrite(policy);
+                        orthrus::panda3d::multifile2::Multifile::extract_from_file_into(
+                            data.input,
+                            &extraction,
+                            &PathOptions::DEFAULT,
+                            &ResourceLimits::UNLIMITED,
+                        )?;
+                        finish_extraction(extraction)?;
                     }
                     None => eprintln!("Please select exactly one operation!"),
                     _ => unreachable!("Oops! Forgot to cover all operations."),
                 }
             }
             Panda3dModules::BAM(data) => {
-                let asset = BinaryAsset::open(data.input)?;
+                let mut input = std::fs::read(&data.input)?;
+                let asset = BinaryAsset::load_borrowed(&input)?;
+
+                if data.info {
+                    println!("BAM version {}", asset.version());
+                    println!("{:?}-endian", asset.endian());
+                    match asset.use_double() {
+                        true => println!("64-bit floats"),
+                        false => println!("32-bit floats"),
+                    }
+                    println!("{} objects", asset.nodes.len());
+                }
+
+                if data.anim_report {
+                    print_animations(&asset);
+                }
+
+                if let Some(dotfile) = data.dotfile {
+                    orthrus::panda3d::bam::GraphWriter::write_nodes(&asset.nodes, dotfile)?;
+                }
 
+                if let Some(patch) = data.patch {
+                    let patch = Patch::parse(&std::fs::read_to_string(patch)?)?;
+                    patch.apply(&asset, &mut input)?;
+                    let output =
+                        data.output.map_or_else(|| append_suffix(&data.input, "_patched"), Into::into);
+                    log::info!(
+                        "Applied {} edits, writing file {}",
+                        patch.edits.len(),
+                        output.display()
+                    );
+                    std::fs::write(output, input)?;
+                }
+            }
+            Panda3dModules::CrossRef(data) => {
+                let crossref = CrossReference::scan(&data.input)?;
+                for (path, error) in crossref.errors() {
+                    log::warn!("{path}: {error}");
+                }
+                log::info!(
+                    "{} models, {} unreferenced textures, {} missing textures",
+                    crossref.models().count(),
+                    crossref.unreferenced().len(),
+                    crossref.missing().len()
+                );
+                match data.output {
+                    Some(output) => std::fs::write(output, crossref.to_json())?,
+                    None => print!("{}", crossref.to_json()),
+                }
                 if let Some(dotfile) = data.dotfile {
-                    orthrus_panda3d::bam::GraphWriter::write_nodes(&asset.nodes, dotfile)?;
+                    std::fs::write(dotfile, crossref.to_dot())?;
                 }
             }
         },
         Modules::JSystem(module) => match module.nested {
             JSystemModules::RARC(data) => {
-                ResourceArchive::open(data.input)?;
+                let archive = ResourceArchive::open(&data.input)?;
+                match data.extract {
+                    true => {
+                        let extraction = Extraction::new(output_dir(data.output), mode)?.overwrite(policy);
+                        let count = archive.extract_all_into(&extraction, &PathOptions::DEFAULT)?;
+                        finish_extraction(extraction)?;
+                        log::info!("Extracted {count} files");
+                        if let Some(path) = data.manifest.filter(|_| mode != ExtractMode::DryRun) {
+                            let mut manifest = Manifest::new();
+                            for (member, file) in archive.root.files() {
+                                manifest.insert(&member, &file.data, Some(Source::new(&data.input, &member)));
+                            }
+                            manifest.save(path)?;
+                        }
+                    }
+                    false if data.report => println!("{}", archive.to_bytes_with_report().1),
+                    false => {
+                        for (path, file) in archive.root.files() {
+                            let compressed = if file.is_compressed() { ", compressed" } else { "" };
+                            println!(
+                                "{path} ({:#X} bytes, ID {}, {}{compressed})",
+                                file.data.len(),
+                                file.id,
+                                file.preload()
+                            );
+                        }
+                    }
+                }
+            }
+            JSystemModules::Materials(data) => {
+                let table = MaterialTable::open(&data.input)?;
+                match data.output {
+                    Some(output) => std::fs::write(output, table.to_json()?)?,
+                    None => println!("{}", table.to_json()?),
+                }
             }
         },
         Modules::NintendoWare(module) => match module.nested {
             NintendoWareModules::BFSAR(data) => {
-                Switch::BFSAR::open(data.input)?;
+                let mut archive = SoundArchive::open(&data.input)?;
+                if data.rename.is_some() || data.replace.is_some() {
+                    if let Some(renames) = data.rename {
+                        for (name, new_name) in read_pairs(&renames, "OLD_NAME = NEW_NAME")? {
+                            archive.rename(&name, &new_name)?;
+                        }
+                    }
+                    if let Some(replacements) = data.replace {
+                        for (id, path) in read_pairs(&replacements, "FILE_ID = path/to/file")? {
+                            let Ok(id) = id.parse() else {
+                                anyhow::bail!("Expected a file ID, found {id:?}");
+                            };
+                            archive.replace_file(id, std::fs::read(path)?)?;
+                        }
+                    }
+                    let output =
+                        data.output.map_or_else(|| append_suffix(&data.input, "_modified"), Into::into);
+                    log::info!("Writing file {}", output.display());
+                    std::fs::write(output, archive.to_bytes()?)?;
+                } else if data.json {
+                    println!("{}", archive.to_json()?);
+                } else {
+                    log::info!(
+                        "{} Sound Archive {} with {} sounds",
+                        archive.platform(),
+                        archive.version(),
+                        archive.sound_count()
+                    );
+                }
             }
             NintendoWareModules::BRSTM(data) => {
                 let _stream = Wii::StreamFile::open(data.input)?;
@@ -172,7 +559,35 @@ fn main() -> Result<()> {
         },
         Modules::Godot(module) => match module.nested {
             GodotModules::Godot(data) => {
-                ResourcePack::open(data.input)?;
+                let mismatches = if data.extract {
+                    let extraction = Extraction::new(output_dir(data.output), mode)?.overwrite(policy);
+                    let options = pck::ExtractOptions::new().verify(data.verify).remap_imports(data.remap);
+                    let (count, mismatches) =
+                        ResourcePack::extract_from_file_into(data.input, &extraction, &options)?;
+                    finish_extraction(extraction)?;
+                    log::info!("Extracted {count} files");
+                    mismatches
+                } else if data.verify {
+                    let pack = ResourcePack::open(&data.input)?;
+                    pack.verify_checksums(std::io::BufReader::new(std::fs::File::open(&data.input)?))?
+                } else if data.report {
+                    let pack = ResourcePack::open(&data.input)?;
+                    let input = std::io::BufReader::new(std::fs::File::open(&data.input)?);
+                    let files = pack.read_files(input)?;
+                    let mut output = std::io::Cursor::new(Vec::new());
+                    let report = ResourcePack::create_with_report(&mut output, &files, pack.godot_version())?;
+                    println!("{report}");
+                    Vec::new()
+                } else {
+                    ResourcePack::open(data.input)?;
+                    Vec::new()
+                };
+                for mismatch in &mismatches {
+                    eprintln!("{}: checksum mismatch", mismatch.path);
+                }
+                if !mismatches.is_empty() {
+                    anyhow::bail!("{} files failed checksum verification", mismatches.len());
+                }
             }
         },
     }
@@ -1,12 +1,9 @@
 use argp::FromArgs;
 
 use super::create_submodule;
+use super::schema::{Command, Flag, Positional};
 
-create_submodule!(
-    Godot,
-    "Support for the Godot game engine",
-    Godot(GodotFlags)
-);
+create_submodule!(Godot, "Support for the Godot game engine", Godot(GodotFlags));
 
 #[derive(FromArgs, PartialEq, Eq, Debug)]
 #[argp(subcommand, name = "pck")]
@@ -16,6 +13,20 @@ pub struct GodotFlags {
     #[argp(description = "Extract all files from the PCK")]
     pub extract: bool,
 
+    #[argp(switch, short = 'c')]
+    #[argp(description = "Verify the MD5 of each file, and report any that don't match")]
+    pub verify: bool,
+
+    #[argp(switch, short = 'm')]
+    #[argp(
+        description = "Move imported files back next to their resources, so the output opens in the editor"
+    )]
+    pub remap: bool,
+
+    #[argp(switch, short = 'r')]
+    #[argp(description = "Print the size and padding of every file if the PCK was rebuilt")]
+    pub report: bool,
+
     //Extract requires output so just ask for both
     #[argp(positional)]
     #[argp(description = "PCK to be processed")]
@@ -25,3 +36,33 @@ pub struct GodotFlags {
     #[argp(description = "Directory to extract to")]
     pub output: Option<String>,
 }
+
+impl GodotFlags {
+    pub(super) const COMMAND: Command = Command {
+        name: "pck",
+        description: "Godot Resource Pack",
+        flags: &[
+            Flag::switch("extract", Some('x'), "Extract all files from the PCK"),
+            Flag::switch(
+                "verify",
+                Some('c'),
+                "Verify the MD5 of each file, and report any that don't match",
+            ),
+            Flag::switch(
+                "remap",
+                Some('m'),
+                "Move imported files back next to their resources, so the output opens in the editor",
+            ),
+            Flag::switch(
+                "report",
+                Some('r'),
+                "Print the size and padding of every file if the PCK was rebuilt",
+            ),
+        ],
+        positionals: &[
+            Positional::required("input", "PCK to be processed"),
+            Positional::optional("output", "Directory to extract to"),
+        ],
+        subcommands: &[],
+    };
+}
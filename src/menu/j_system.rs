@@ -1,11 +1,13 @@
 use argp::FromArgs;
 
 use super::create_submodule;
+use super::schema::{Command, Flag, Positional};
 
 create_submodule!(
     JSystem,
     "Support for Nintendo's JSystem Middleware",
-    RARC(RARCFlags)
+    RARC(RARCFlags),
+    Materials(MaterialsFlags)
 );
 
 #[derive(FromArgs, PartialEq, Eq, Debug)]
@@ -16,6 +18,14 @@ pub struct RARCFlags {
     #[argp(description = "Extract all files from the RARC")]
     pub extract: bool,
 
+    #[argp(option)]
+    #[argp(description = "Also write a manifest of the extracted files")]
+    pub manifest: Option<String>,
+
+    #[argp(switch, short = 'r')]
+    #[argp(description = "Print the size and padding of every file if the RARC was rebuilt")]
+    pub report: bool,
+
     //Extract requires output so just ask for both
     #[argp(positional)]
     #[argp(description = "RARC to be processed")]
@@ -25,3 +35,55 @@ pub struct RARCFlags {
     #[argp(description = "Directory to extract to")]
     pub output: Option<String>,
 }
+
+impl RARCFlags {
+    pub(super) const COMMAND: Command = Command {
+        name: "rarc",
+        description: "JSystem Resource Archive",
+        flags: &[
+            Flag::switch("extract", Some('x'), "Extract all files from the RARC"),
+            Flag::option(
+                "manifest",
+                None,
+                "file",
+                "Also write a manifest of the extracted files",
+            ),
+            Flag::switch(
+                "report",
+                Some('r'),
+                "Print the size and padding of every file if the RARC was rebuilt",
+            ),
+        ],
+        positionals: &[
+            Positional::required("input", "RARC to be processed"),
+            Positional::optional("output", "Directory to extract to"),
+        ],
+        subcommands: &[],
+    };
+}
+
+#[derive(FromArgs, PartialEq, Eq, Debug)]
+#[argp(subcommand, name = "materials")]
+#[argp(description = "Dump the materials of a J3D model (BMD/BDL/BMT) as JSON")]
+pub struct MaterialsFlags {
+    #[argp(positional)]
+    #[argp(description = "Model to be processed")]
+    pub input: String,
+
+    #[argp(positional)]
+    #[argp(description = "File to write the JSON to, instead of printing it")]
+    pub output: Option<String>,
+}
+
+impl MaterialsFlags {
+    pub(super) const COMMAND: Command = Command {
+        name: "materials",
+        description: "Dump the materials of a J3D model (BMD/BDL/BMT) as JSON",
+        flags: &[],
+        positionals: &[
+            Positional::required("input", "Model to be processed"),
+            Positional::optional("output", "File to write the JSON to, instead of printing it"),
+        ],
+        subcommands: &[],
+    };
+}
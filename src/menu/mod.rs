@@ -1,5 +1,6 @@
 use argp::FromArgs;
 use paste::paste;
+use schema::{Command, Flag, Positional};
 
 macro_rules! declare_module {
     ($($name:ident),+) => {
@@ -17,6 +18,8 @@ macro_rules! declare_module {
 
 declare_module!(godot, j_system, n_compress, nintendo_ware, panda3d);
 
+pub mod schema;
+
 /// Top-level command
 #[derive(FromArgs, PartialEq, Eq, Debug)]
 #[argp(description = "A new way to modify games.")]
@@ -25,8 +28,87 @@ pub struct Orthrus {
     #[argp(description = "Logging level (0 = Off, 1 = Error, 2 = Warn, 3 = Info, 4 = Debug, 5 = Trace)")]
     pub verbose: usize,
 
+    #[argp(switch, global)]
+    #[argp(description = "List the files that extracting would write, without writing anything")]
+    pub dry_run: bool,
+
+    #[argp(switch, global)]
+    #[argp(description = "Stage extracted files, and only move them into place if extraction succeeds")]
+    pub staged: bool,
+
+    #[argp(option, global)]
+    #[argp(description = "How to handle files that already exist (overwrite, skip, rename, or error)")]
+    pub overwrite: Option<String>,
+
+    #[argp(switch)]
+    #[argp(description = "Print every command and flag as JSON, then exit")]
+    pub dump_commands: bool,
+
+    #[argp(option)]
+    #[argp(description = "Print a completion script for bash, zsh, fish, or powershell, then exit")]
+    pub completions: Option<String>,
+
     #[argp(subcommand)]
-    pub nested: Modules,
+    pub nested: Option<Modules>,
+}
+
+impl Orthrus {
+    pub const COMMAND: Command = Command {
+        name: "orthrus",
+        description: "A new way to modify games.",
+        flags: &[
+            Flag::option(
+                "verbose",
+                Some('v'),
+                "level",
+                "Logging level (0 = Off, 1 = Error, 2 = Warn, 3 = Info, 4 = Debug, 5 = Trace)",
+            )
+            .global(),
+            Flag::switch(
+                "dry-run",
+                None,
+                "List the files that extracting would write, without writing anything",
+            )
+            .global(),
+            Flag::switch(
+                "staged",
+                None,
+                "Stage extracted files, and only move them into place if extraction succeeds",
+            )
+            .global(),
+            Flag::option(
+                "overwrite",
+                None,
+                "policy",
+                "How to handle files that already exist (overwrite, skip, rename, or error)",
+            )
+            .global(),
+            Flag::switch(
+                "dump-commands",
+                None,
+                "Print every command and flag as JSON, then exit",
+            ),
+            Flag::option(
+                "completions",
+                None,
+                "shell",
+                "Print a completion script for bash, zsh, fish, or powershell, then exit",
+            ),
+        ],
+        positionals: &[],
+        subcommands: &[
+            IdentifyOption::COMMAND,
+            CarveOption::COMMAND,
+            ListOption::COMMAND,
+            ServeOption::COMMAND,
+            ManifestOption::COMMAND,
+            NCompressOption::COMMAND,
+            Panda3dOption::COMMAND,
+            JSystemOption::COMMAND,
+            NintendoWareOption::COMMAND,
+            GodotOption::COMMAND,
+        ],
+    };
 }
 
 /// These are all the "modules" that Orthrus supports via command line.
@@ -35,6 +117,10 @@ pub struct Orthrus {
 #[non_exhaustive]
 pub enum Modules {
     IdentifyFile(IdentifyOption),
+    Carve(CarveOption),
+    List(ListOption),
+    Serve(ServeOption),
+    Manifest(ManifestOption),
     NintendoCompression(NCompressOption),
     Panda3D(Panda3dOption),
     JSystem(JSystemOption),
@@ -48,15 +134,185 @@ pub enum Modules {
 #[argp(description = "Identify a file and print relevant information")]
 pub struct IdentifyOption {
     #[argp(switch, long = "deep")]
-    #[argp(description = "Allow Orthrus to do more compute-intensive operations when scanning.")]
+    #[argp(description = "Allow slower operations when scanning, such as compression statistics.")]
     pub deep_scan: bool,
 
+    #[argp(switch, short = 'r')]
+    #[argp(description = "Identify every file in a folder, and count how many of each format were found")]
+    pub recursive: bool,
+
     //We always need an input file, output file can be optional with a default
     #[argp(positional)]
     #[argp(description = "Input file to be processed")]
     pub input: String,
 }
 
+impl IdentifyOption {
+    const COMMAND: Command = Command {
+        name: "info",
+        description: "Identify a file and print relevant information",
+        flags: &[
+            Flag::switch(
+                "deep",
+                None,
+                "Allow slower operations when scanning, such as compression statistics.",
+            ),
+            Flag::switch(
+                "recursive",
+                Some('r'),
+                "Identify every file in a folder, and count how many of each format were found",
+            ),
+        ],
+        positionals: &[Positional::required("input", "Input file to be processed")],
+        subcommands: &[],
+    };
+}
+
+/// Command to find known formats embedded inside of an unknown file.
+#[derive(FromArgs, PartialEq, Eq, Debug)]
+#[argp(subcommand, name = "carve")]
+#[argp(description = "Scan a file for embedded files of any known format, such as in a RAM dump")]
+pub struct CarveOption {
+    #[argp(switch, long = "deep")]
+    #[argp(description = "Allow slower operations when scanning, such as compression statistics.")]
+    pub deep_scan: bool,
+
+    #[argp(switch, short = 'x')]
+    #[argp(description = "Extract every file that was found, named after its offset")]
+    pub extract: bool,
+
+    #[argp(option, default = "1")]
+    #[argp(description = "Only check offsets that are a multiple of this, which is much faster")]
+    pub align: usize,
+
+    #[argp(positional)]
+    #[argp(description = "File to be scanned")]
+    pub input: String,
+
+    #[argp(positional)]
+    #[argp(description = "Directory to extract to")]
+    pub output: Option<String>,
+}
+
+impl CarveOption {
+    const COMMAND: Command = Command {
+        name: "carve",
+        description: "Scan a file for embedded files of any known format, such as in a RAM dump",
+        flags: &[
+            Flag::switch(
+                "deep",
+                None,
+                "Allow slower operations when scanning, such as compression statistics.",
+            ),
+            Flag::switch(
+                "extract",
+                Some('x'),
+                "Extract every file that was found, named after its offset",
+            ),
+            Flag::option(
+                "align",
+                None,
+                "bytes",
+                "Only check offsets that are a multiple of this, which is much faster",
+            ),
+        ],
+        positionals: &[
+            Positional::required("input", "File to be scanned"),
+            Positional::optional("output", "Directory to extract to"),
+        ],
+        subcommands: &[],
+    };
+}
+
+/// Command to list the files inside of an archive, without extracting it.
+#[derive(FromArgs, PartialEq, Eq, Debug)]
+#[argp(subcommand, name = "ls")]
+#[argp(description = "List the files inside of a Multifile or PCK, which can be a URL with the http feature")]
+pub struct ListOption {
+    #[argp(positional)]
+    #[argp(description = "Archive to be listed, either a path or an http(s):// URL")]
+    pub input: String,
+}
+
+impl ListOption {
+    const COMMAND: Command = Command {
+        name: "ls",
+        description: "List the files inside of a Multifile or PCK, which can be a URL with the http feature",
+        flags: &[],
+        positionals: &[Positional::required(
+            "input",
+            "Archive to be listed, either a path or an http(s):// URL",
+        )],
+        subcommands: &[],
+    };
+}
+
+/// Command to run as a server, for editors that need to make many requests.
+#[derive(FromArgs, PartialEq, Eq, Debug)]
+#[argp(subcommand, name = "serve")]
+#[argp(description = "Answer JSON-RPC requests over stdin and stdout, keeping parsed archives cached")]
+pub struct ServeOption {
+    #[argp(option, short = 'r')]
+    #[argp(description = "Folder that output paths are relative to, defaults to the current folder")]
+    pub root: Option<String>,
+}
+
+impl ServeOption {
+    const COMMAND: Command = Command {
+        name: "serve",
+        description: "Answer JSON-RPC requests over stdin and stdout, keeping parsed archives cached",
+        flags: &[Flag::option(
+            "root",
+            Some('r'),
+            "root",
+            "Folder that output paths are relative to, defaults to the current folder",
+        )],
+        positionals: &[],
+        subcommands: &[],
+    };
+}
+
+/// Command to record the hashes of an extracted tree, or check a tree against them.
+#[derive(FromArgs, PartialEq, Eq, Debug)]
+#[argp(subcommand, name = "manifest")]
+#[argp(description = "Write a manifest of every file in a folder, or verify a folder against one")]
+pub struct ManifestOption {
+    #[argp(option, short = 'o')]
+    #[argp(description = "File to write the manifest to, instead of printing it")]
+    pub output: Option<String>,
+
+    #[argp(option)]
+    #[argp(description = "Check the folder against this manifest instead of writing one")]
+    pub verify: Option<String>,
+
+    #[argp(positional)]
+    #[argp(description = "Folder to be processed")]
+    pub input: String,
+}
+
+impl ManifestOption {
+    const COMMAND: Command = Command {
+        name: "manifest",
+        description: "Write a manifest of every file in a folder, or verify a folder against one",
+        flags: &[
+            Flag::option(
+                "output",
+                Some('o'),
+                "file",
+                "File to write the manifest to, instead of printing it",
+            ),
+            Flag::option(
+                "verify",
+                None,
+                "manifest",
+                "Check the folder against this manifest instead of writing one",
+            ),
+        ],
+        positionals: &[Positional::required("input", "Folder to be processed")],
+        subcommands: &[],
+    };
+}
+
 #[must_use]
 pub fn exactly_one_true(bools: &[bool]) -> Option<usize> {
     let mut count: usize = 0;
@@ -99,6 +355,16 @@ macro_rules! create_submodule {
             pub enum [<$module_name Modules>] {
                 $( $submodule_name($submodule_type) ),*
             }
+
+            impl [<$module_name Option>] {
+                pub(crate) const COMMAND: $crate::menu::schema::Command = $crate::menu::schema::Command {
+                    name: stringify!([<$module_name:lower>]),
+                    description: $module_description,
+                    flags: &[],
+                    positionals: &[],
+                    subcommands: &[$( $submodule_type::COMMAND ),*],
+                };
+            }
         }
     };
 }
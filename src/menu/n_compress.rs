@@ -1,14 +1,51 @@
 use argp::FromArgs;
 
 use super::create_submodule;
+use super::schema::{Command, Flag, Positional};
 
 create_submodule!(
     NCompress,
     "Support for Nintendo compression formats",
+    Convert(ConvertFlags),
     Yay0(Yay0Flags),
     Yaz0(Yaz0Flags)
 );
 
+#[derive(FromArgs, PartialEq, Eq, Debug)]
+#[argp(subcommand, name = "convert")]
+#[argp(description = "Recompress a Yay0, Yaz0, or LZ11-compressed file using another format")]
+pub struct ConvertFlags {
+    #[argp(option)]
+    #[argp(description = "Format to recompress to (yay0, yaz0, or lz11)")]
+    pub to: String,
+
+    #[argp(positional)]
+    #[argp(description = "Input file to be processed")]
+    pub input: String,
+
+    #[argp(positional)]
+    #[argp(description = "Output file to write to")]
+    pub output: Option<String>,
+}
+
+impl ConvertFlags {
+    pub(super) const COMMAND: Command = Command {
+        name: "convert",
+        description: "Recompress a Yay0, Yaz0, or LZ11-compressed file using another format",
+        flags: &[Flag::option(
+            "to",
+            None,
+            "format",
+            "Format to recompress to (yay0, yaz0, or lz11)",
+        )],
+        positionals: &[
+            Positional::required("input", "Input file to be processed"),
+            Positional::optional("output", "Output file to write to"),
+        ],
+        subcommands: &[],
+    };
+}
+
 #[derive(FromArgs, PartialEq, Eq, Debug)]
 #[argp(subcommand, name = "yay0")]
 #[argp(description = "Nintendo Yay0-compressed data")]
@@ -21,16 +58,41 @@ pub struct Yay0Flags {
     #[argp(description = "Compress a binary file using Yay0")]
     pub compress: bool,
 
+    #[argp(switch, short = 's')]
+    #[argp(description = "Extract every Yay0-compressed stream embedded in a file, such as a ROM")]
+    pub scan: bool,
+
     //We always need an input file, output file can be optional with a default
     #[argp(positional)]
     #[argp(description = "Input file to be processed")]
     pub input: String,
 
     #[argp(positional)]
-    #[argp(description = "Output file to write to")]
+    #[argp(description = "Output file to write to, or folder when scanning")]
     pub output: Option<String>,
 }
 
+impl Yay0Flags {
+    pub(super) const COMMAND: Command = Command {
+        name: "yay0",
+        description: "Nintendo Yay0-compressed data",
+        flags: &[
+            Flag::switch("decompress", Some('d'), "Decompress a Yay0-compressed file"),
+            Flag::switch("compress", Some('c'), "Compress a binary file using Yay0"),
+            Flag::switch(
+                "scan",
+                Some('s'),
+                "Extract every Yay0-compressed stream embedded in a file, such as a ROM",
+            ),
+        ],
+        positionals: &[
+            Positional::required("input", "Input file to be processed"),
+            Positional::optional("output", "Output file to write to, or folder when scanning"),
+        ],
+        subcommands: &[],
+    };
+}
+
 #[derive(FromArgs, PartialEq, Eq, Debug)]
 #[argp(subcommand, name = "yaz0")]
 #[argp(description = "Nintendo Yaz0-compressed data")]
@@ -43,12 +105,37 @@ pub struct Yaz0Flags {
     #[argp(description = "Compress a binary file using Yaz0")]
     pub compress: bool,
 
+    #[argp(switch, short = 's')]
+    #[argp(description = "Extract every Yaz0-compressed stream embedded in a file, such as a ROM")]
+    pub scan: bool,
+
     //We always need an input file, output file can be optional with a default
     #[argp(positional)]
     #[argp(description = "Input file to be processed")]
     pub input: String,
 
     #[argp(positional)]
-    #[argp(description = "Output file to write to")]
+    #[argp(description = "Output file to write to, or folder when scanning")]
     pub output: Option<String>,
 }
+
+impl Yaz0Flags {
+    pub(super) const COMMAND: Command = Command {
+        name: "yaz0",
+        description: "Nintendo Yaz0-compressed data",
+        flags: &[
+            Flag::switch("decompress", Some('d'), "Decompress a Yaz0-compressed file"),
+            Flag::switch("compress", Some('c'), "Compress a binary file using Yaz0"),
+            Flag::switch(
+                "scan",
+                Some('s'),
+                "Extract every Yaz0-compressed stream embedded in a file, such as a ROM",
+            ),
+        ],
+        positionals: &[
+            Positional::required("input", "Input file to be processed"),
+            Positional::optional("output", "Output file to write to, or folder when scanning"),
+        ],
+        subcommands: &[],
+    };
+}
@@ -1,6 +1,7 @@
 use argp::FromArgs;
 
 use super::create_submodule;
+use super::schema::{Command, Flag, Positional};
 
 create_submodule!(
     NintendoWare,
@@ -26,6 +27,23 @@ pub struct BRSTMFlags {
     pub output: Option<String>,
 }
 
+impl BRSTMFlags {
+    pub(super) const COMMAND: Command = Command {
+        name: "brstm",
+        description: "Binary File Stream",
+        flags: &[Flag::switch(
+            "decode",
+            Some('d'),
+            "Decode the BRSTM into a WAV file",
+        )],
+        positionals: &[
+            Positional::required("input", "BRSTM file to be processed"),
+            Positional::optional("output", "WAV file to output to"),
+        ],
+        subcommands: &[],
+    };
+}
+
 #[derive(FromArgs, PartialEq, Eq, Debug)]
 #[argp(subcommand, name = "bfsar")]
 #[argp(description = "Binary File Sound Archive")]
@@ -34,7 +52,58 @@ pub struct BFSARFlags {
     #[argp(description = "Parse the BFSAR and print relevant information")]
     pub info: bool,
 
+    #[argp(switch, short = 'j')]
+    #[argp(description = "Print the metadata of every sound as JSON")]
+    pub json: bool,
+
+    #[argp(option)]
+    #[argp(description = "Rename items using a file with one `OLD_NAME = NEW_NAME` per line")]
+    pub rename: Option<String>,
+
+    #[argp(option)]
+    #[argp(description = "Replace files using a file with one `FILE_ID = path/to/file` per line")]
+    pub replace: Option<String>,
+
+    #[argp(option, short = 'o')]
+    #[argp(description = "File to write the modified BFSAR to, instead of next to the input")]
+    pub output: Option<String>,
+
     #[argp(positional)]
     #[argp(description = "BFSAR to be processed")]
     pub input: String,
 }
+
+impl BFSARFlags {
+    pub(super) const COMMAND: Command = Command {
+        name: "bfsar",
+        description: "Binary File Sound Archive",
+        flags: &[
+            Flag::switch(
+                "info",
+                Some('i'),
+                "Parse the BFSAR and print relevant information",
+            ),
+            Flag::switch("json", Some('j'), "Print the metadata of every sound as JSON"),
+            Flag::option(
+                "rename",
+                None,
+                "file",
+                "Rename items using a file with one `OLD_NAME = NEW_NAME` per line",
+            ),
+            Flag::option(
+                "replace",
+                None,
+                "file",
+                "Replace files using a file with one `FILE_ID = path/to/file` per line",
+            ),
+            Flag::option(
+                "output",
+                Some('o'),
+                "file",
+                "File to write the modified BFSAR to, instead of next to the input",
+            ),
+        ],
+        positionals: &[Positional::required("input", "BFSAR to be processed")],
+        subcommands: &[],
+    };
+}
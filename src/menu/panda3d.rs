@@ -1,12 +1,14 @@
 use argp::FromArgs;
 
 use super::create_submodule;
+use super::schema::{Command, Flag, Positional};
 
 create_submodule!(
     Panda3d,
     "Support for the Panda3D Engine",
     Multifile(MultifileFlags),
-    BAM(BAMFlags)
+    BAM(BAMFlags),
+    CrossRef(CrossRefFlags)
 );
 
 #[derive(FromArgs, PartialEq, Eq, Debug)]
@@ -26,6 +28,23 @@ pub struct MultifileFlags {
     pub output: Option<String>,
 }
 
+impl MultifileFlags {
+    pub(super) const COMMAND: Command = Command {
+        name: "multifile",
+        description: "Panda3D Multifile Archive",
+        flags: &[Flag::switch(
+            "extract",
+            Some('x'),
+            "Extract all files from the Multifile",
+        )],
+        positionals: &[
+            Positional::required("input", "Multifile to be processed"),
+            Positional::optional("output", "Directory to extract to"),
+        ],
+        subcommands: &[],
+    };
+}
+
 #[derive(FromArgs, PartialEq, Eq, Debug)]
 #[argp(subcommand, name = "bam")]
 #[argp(description = "Panda3D Binary Model")]
@@ -34,6 +53,10 @@ pub struct BAMFlags {
     #[argp(description = "Display info about the BAM file")]
     pub info: bool,
 
+    #[argp(switch)]
+    #[argp(description = "List every animation, and which joints are animated or constant")]
+    pub anim_report: bool,
+
     #[argp(positional)]
     #[argp(description = "BAM file to be processed")]
     pub input: String,
@@ -41,4 +64,77 @@ pub struct BAMFlags {
     #[argp(option, short = 'd')]
     #[argp(description = "Graphviz output filepath")]
     pub dotfile: Option<String>,
+
+    #[argp(option, short = 'p')]
+    #[argp(description = "Apply a patch file, editing texture paths and transforms in place")]
+    pub patch: Option<String>,
+
+    #[argp(option, short = 'o')]
+    #[argp(description = "File to write the patched BAM to, instead of next to the input")]
+    pub output: Option<String>,
+}
+
+impl BAMFlags {
+    pub(super) const COMMAND: Command = Command {
+        name: "bam",
+        description: "Panda3D Binary Model",
+        flags: &[
+            Flag::switch("info", Some('i'), "Display info about the BAM file"),
+            Flag::switch(
+                "anim-report",
+                None,
+                "List every animation, and which joints are animated or constant",
+            ),
+            Flag::option("dotfile", Some('d'), "dotfile", "Graphviz output filepath"),
+            Flag::option(
+                "patch",
+                Some('p'),
+                "file",
+                "Apply a patch file, editing texture paths and transforms in place",
+            ),
+            Flag::option(
+                "output",
+                Some('o'),
+                "file",
+                "File to write the patched BAM to, instead of next to the input",
+            ),
+        ],
+        positionals: &[Positional::required("input", "BAM file to be processed")],
+        subcommands: &[],
+    };
+}
+
+#[derive(FromArgs, PartialEq, Eq, Debug)]
+#[argp(subcommand, name = "crossref")]
+#[argp(description = "Cross-reference the textures used by every model in an asset tree")]
+pub struct CrossRefFlags {
+    #[argp(positional)]
+    #[argp(description = "Folder containing Multifiles and loose files to scan")]
+    pub input: String,
+
+    #[argp(positional)]
+    #[argp(description = "JSON output filepath, otherwise printed")]
+    pub output: Option<String>,
+
+    #[argp(option, short = 'd')]
+    #[argp(description = "Graphviz output filepath")]
+    pub dotfile: Option<String>,
+}
+
+impl CrossRefFlags {
+    pub(super) const COMMAND: Command = Command {
+        name: "crossref",
+        description: "Cross-reference the textures used by every model in an asset tree",
+        flags: &[Flag::option(
+            "dotfile",
+            Some('d'),
+            "dotfile",
+            "Graphviz output filepath",
+        )],
+        positionals: &[
+            Positional::required("input", "Folder containing Multifiles and loose files to scan"),
+            Positional::optional("output", "JSON output filepath, otherwise printed"),
+        ],
+        subcommands: &[],
+    };
 }
@@ -0,0 +1,429 @@
+//! Static description of every command and flag, used to generate shell completions and a machine-readable
+//! schema for tools wrapping the command line.
+//!
+//! argp doesn't expose its definitions at runtime, so each flag struct has a matching `COMMAND` constant next
+//! to it, which must be kept in sync when adding or changing flags.
+
+use std::fmt::Write;
+
+/// A command or subcommand, along with all of its arguments.
+#[derive(Debug, Clone, Copy)]
+pub struct Command {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub flags: &'static [Flag],
+    pub positionals: &'static [Positional],
+    pub subcommands: &'static [Command],
+}
+
+/// A `--long` flag, which is either a switch or takes a value.
+#[derive(Debug, Clone, Copy)]
+pub struct Flag {
+    pub long: &'static str,
+    pub short: Option<char>,
+    /// Name of the value this flag takes, if it isn't a switch
+    pub value: Option<&'static str>,
+    /// Whether this flag is also accepted by every subcommand
+    pub global: bool,
+    pub description: &'static str,
+}
+
+impl Flag {
+    #[must_use]
+    pub const fn switch(long: &'static str, short: Option<char>, description: &'static str) -> Self {
+        Self { long, short, value: None, global: false, description }
+    }
+
+    #[must_use]
+    pub const fn option(
+        long: &'static str, short: Option<char>, value: &'static str, description: &'static str,
+    ) -> Self {
+        Self { long, short, value: Some(value), global: false, description }
+    }
+
+    #[must_use]
+    pub const fn global(mut self) -> Self {
+        self.global = true;
+        self
+    }
+}
+
+/// A positional argument, such as an input file.
+#[derive(Debug, Clone, Copy)]
+pub struct Positional {
+    pub name: &'static str,
+    pub optional: bool,
+    pub description: &'static str,
+}
+
+impl Positional {
+    #[must_use]
+    pub const fn required(name: &'static str, description: &'static str) -> Self {
+        Self { name, optional: false, description }
+    }
+
+    #[must_use]
+    pub const fn optional(name: &'static str, description: &'static str) -> Self {
+        Self { name, optional: true, description }
+    }
+}
+
+/// Every shell that completion scripts can be generated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::enum_variant_names)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    PowerShell,
+}
+
+impl Shell {
+    /// Returns the shell with the given name, if it's supported.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "bash" => Some(Self::Bash),
+            "zsh" => Some(Self::Zsh),
+            "fish" => Some(Self::Fish),
+            "powershell" | "pwsh" => Some(Self::PowerShell),
+            _ => None,
+        }
+    }
+}
+
+/// A command along with the space-separated path of command names leading to it, and the flags it accepts
+/// (including global flags from its parents).
+struct Entry {
+    path: String,
+    command: Command,
+    flags: Vec<Flag>,
+}
+
+/// Flattens the command tree, so each shell only has to handle a list of paths.
+fn entries(root: &Command) -> Vec<Entry> {
+    fn visit(command: &Command, path: String, globals: &[Flag], entries: &mut Vec<Entry>) {
+        let mut flags: Vec<Flag> = command.flags.to_vec();
+        flags.extend_from_slice(globals);
+        let globals: Vec<Flag> = flags.iter().filter(|flag| flag.global).copied().collect();
+        entries.push(Entry { path: path.clone(), command: *command, flags });
+        for subcommand in command.subcommands {
+            visit(
+                subcommand,
+                format!("{path} {}", subcommand.name),
+                &globals,
+                entries,
+            );
+        }
+    }
+
+    let mut entries = Vec::new();
+    visit(root, root.name.to_string(), &[], &mut entries);
+    entries
+}
+
+/// Returns every name a flag can be written as, including `--help`, which argp adds to every command.
+fn flag_names(flags: &[Flag]) -> Vec<(String, &'static str)> {
+    let mut names = Vec::new();
+    for flag in flags {
+        names.push((format!("--{}", flag.long), flag.description));
+        if let Some(short) = flag.short {
+            names.push((format!("-{short}"), flag.description));
+        }
+    }
+    names.push(("--help".to_string(), "Display usage information"));
+    names
+}
+
+/// Generates a completion script for the given shell, covering every command under `root`.
+#[must_use]
+pub fn completions(shell: Shell, root: &Command) -> String {
+    let entries = entries(root);
+    let mut output = String::new();
+    // Writing to a String can't fail
+    let _ = match shell {
+        Shell::Bash => write_bash(&mut output, root, &entries),
+        Shell::Zsh => write_zsh(&mut output, root, &entries),
+        Shell::Fish => write_fish(&mut output, root, &entries),
+        Shell::PowerShell => write_powershell(&mut output, root, &entries),
+    };
+    output
+}
+
+fn write_bash(output: &mut String, root: &Command, entries: &[Entry]) -> std::fmt::Result {
+    let name = root.name;
+    let paths: Vec<String> = entries[1..].iter().map(|entry| format!("\"{}\"", entry.path)).collect();
+
+    writeln!(output, "_{name}() {{")?;
+    writeln!(
+        output,
+        "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\" cmd=\"{name}\" word"
+    )?;
+    writeln!(
+        output,
+        "    for word in \"${{COMP_WORDS[@]:1:COMP_CWORD-1}}\"; do"
+    )?;
+    writeln!(output, "        case \"$cmd $word\" in")?;
+    writeln!(output, "            {}) cmd=\"$cmd $word\" ;;", paths.join("|"))?;
+    writeln!(output, "        esac")?;
+    writeln!(output, "    done")?;
+    writeln!(output, "    case \"$cmd\" in")?;
+    for entry in entries {
+        let mut words: Vec<String> =
+            entry.command.subcommands.iter().map(|sub| sub.name.to_string()).collect();
+        words.extend(flag_names(&entry.flags).into_iter().map(|(flag, _)| flag));
+        let reply = format!("COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))", words.join(" "));
+        // Commands without subcommands take files, so only complete flags once one is started
+        match entry.command.subcommands.is_empty() {
+            true => writeln!(
+                output,
+                "        \"{}\") [[ \"$cur\" == -* ]] && {reply} ;;",
+                entry.path
+            )?,
+            false => writeln!(output, "        \"{}\") {reply} ;;", entry.path)?,
+        }
+    }
+    writeln!(output, "    esac")?;
+    writeln!(output, "}}")?;
+    writeln!(output, "complete -o default -F _{name} {name}")
+}
+
+fn write_zsh(output: &mut String, root: &Command, entries: &[Entry]) -> std::fmt::Result {
+    let name = root.name;
+    let escape = |text: &str| text.replace('\'', "'\\''");
+    let paths: Vec<String> = entries[1..].iter().map(|entry| format!("\"{}\"", entry.path)).collect();
+
+    writeln!(output, "#compdef {name}")?;
+    writeln!(output)?;
+    writeln!(output, "_{name}() {{")?;
+    writeln!(output, "    local cmd=\"{name}\" word")?;
+    writeln!(output, "    local -a candidates")?;
+    writeln!(output, "    for word in \"${{(@)words[2,CURRENT-1]}}\"; do")?;
+    writeln!(output, "        case \"$cmd $word\" in")?;
+    writeln!(output, "            ({}) cmd=\"$cmd $word\" ;;", paths.join("|"))?;
+    writeln!(output, "        esac")?;
+    writeln!(output, "    done")?;
+    writeln!(output, "    case \"$cmd\" in")?;
+    for entry in entries {
+        writeln!(output, "        (\"{}\")", entry.path)?;
+        writeln!(output, "            candidates=(")?;
+        for sub in entry.command.subcommands {
+            writeln!(
+                output,
+                "                '{}:{}'",
+                sub.name,
+                escape(sub.description)
+            )?;
+        }
+        for (flag, description) in flag_names(&entry.flags) {
+            writeln!(output, "                '{flag}:{}'", escape(description))?;
+        }
+        writeln!(output, "            )")?;
+        match entry.command.subcommands.is_empty() {
+            true => writeln!(
+                output,
+                "            if [[ \"$PREFIX\" == -* ]]; then _describe 'option' candidates; else _files; fi ;;"
+            )?,
+            false => writeln!(output, "            _describe 'command' candidates ;;")?,
+        }
+    }
+    writeln!(output, "    esac")?;
+    writeln!(output, "}}")?;
+    writeln!(output)?;
+    writeln!(output, "if [ \"$funcstack[1]\" = \"_{name}\" ]; then")?;
+    writeln!(output, "    _{name} \"$@\"")?;
+    writeln!(output, "else")?;
+    writeln!(output, "    compdef _{name} {name}")?;
+    writeln!(output, "fi")
+}
+
+fn write_fish(output: &mut String, root: &Command, entries: &[Entry]) -> std::fmt::Result {
+    let name = root.name;
+    let escape = |text: &str| text.replace('\\', "\\\\").replace('\'', "\\'");
+    let paths: Vec<String> = entries[1..].iter().map(|entry| format!("'{}'", entry.path)).collect();
+
+    writeln!(output, "function __{name}_command")?;
+    writeln!(output, "    set -l cmd {name}")?;
+    writeln!(output, "    for word in (commandline -opc)[2..-1]")?;
+    writeln!(output, "        switch \"$cmd $word\"")?;
+    writeln!(output, "            case {}", paths.join(" "))?;
+    writeln!(output, "                set cmd \"$cmd $word\"")?;
+    writeln!(output, "        end")?;
+    writeln!(output, "    end")?;
+    writeln!(output, "    echo $cmd")?;
+    writeln!(output, "end")?;
+    writeln!(output)?;
+    for entry in entries {
+        let condition = format!("-n 'test (__{name}_command) = \"{}\"'", entry.path);
+        for sub in entry.command.subcommands {
+            writeln!(
+                output,
+                "complete -c {name} {condition} -f -a {} -d '{}'",
+                sub.name,
+                escape(sub.description)
+            )?;
+        }
+        for flag in &entry.flags {
+            let short = flag.short.map(|short| format!(" -s {short}")).unwrap_or_default();
+            let value = if flag.value.is_some() { " -r" } else { "" };
+            writeln!(
+                output,
+                "complete -c {name} {condition}{short} -l {}{value} -d '{}'",
+                flag.long,
+                escape(flag.description)
+            )?;
+        }
+        writeln!(
+            output,
+            "complete -c {name} {condition} -l help -d 'Display usage information'"
+        )?;
+    }
+    Ok(())
+}
+
+fn write_powershell(output: &mut String, root: &Command, entries: &[Entry]) -> std::fmt::Result {
+    let name = root.name;
+    let escape = |text: &str| text.replace('\'', "''");
+    let paths: Vec<String> = entries[1..].iter().map(|entry| format!("'{}'", entry.path)).collect();
+
+    writeln!(output, "using namespace System.Management.Automation")?;
+    writeln!(output)?;
+    writeln!(
+        output,
+        "Register-ArgumentCompleter -Native -CommandName '{name}' -ScriptBlock {{"
+    )?;
+    writeln!(output, "    param($wordToComplete, $commandAst, $cursorPosition)")?;
+    writeln!(output, "    $paths = @({})", paths.join(", "))?;
+    writeln!(output, "    $cmd = '{name}'")?;
+    writeln!(
+        output,
+        "    foreach ($element in $commandAst.CommandElements | Select-Object -Skip 1) {{"
+    )?;
+    writeln!(
+        output,
+        "        if ($element.Extent.EndOffset -ge $cursorPosition) {{ break }}"
+    )?;
+    writeln!(output, "        $next = \"$cmd $($element.Extent.Text)\"")?;
+    writeln!(output, "        if ($paths -contains $next) {{ $cmd = $next }}")?;
+    writeln!(output, "    }}")?;
+    writeln!(output, "    $results = switch ($cmd) {{")?;
+    for entry in entries {
+        writeln!(output, "        '{}' {{", entry.path)?;
+        for sub in entry.command.subcommands {
+            writeln!(
+                output,
+                "            [CompletionResult]::new('{0}', '{0}', [CompletionResultType]::ParameterValue, '{1}')",
+                sub.name,
+                escape(sub.description)
+            )?;
+        }
+        // Commands without subcommands take files, so only complete flags once one is started
+        if entry.command.subcommands.is_empty() {
+            writeln!(
+                output,
+                "            if (-not $wordToComplete.StartsWith('-')) {{ break }}"
+            )?;
+        }
+        for (flag, description) in flag_names(&entry.flags) {
+            writeln!(
+                output,
+                "            [CompletionResult]::new('{flag}', '{flag}', [CompletionResultType]::ParameterName, '{}')",
+                escape(description)
+            )?;
+        }
+        writeln!(output, "        }}")?;
+    }
+    writeln!(output, "    }}")?;
+    writeln!(
+        output,
+        "    $results | Where-Object {{ $_.CompletionText -like \"$wordToComplete*\" }}"
+    )?;
+    writeln!(output, "}}")
+}
+
+/// Escapes a string for use inside of a JSON string literal.
+fn json_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// Describes every command under `root` as JSON, for tools that wrap the command line.
+#[must_use]
+pub fn to_json(root: &Command) -> String {
+    fn write_command(output: &mut String, command: &Command, indent: usize) -> std::fmt::Result {
+        let pad = "  ".repeat(indent);
+        writeln!(output, "{{")?;
+        writeln!(output, "{pad}  \"name\": {},", json_string(command.name))?;
+        writeln!(
+            output,
+            "{pad}  \"description\": {},",
+            json_string(command.description)
+        )?;
+
+        write!(output, "{pad}  \"flags\": [")?;
+        for (i, flag) in command.flags.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            let short = flag.short.map_or("null".to_string(), |short| json_string(&format!("-{short}")));
+            let value = flag.value.map_or("null".to_string(), json_string);
+            write!(
+                output,
+                "{separator}\n{pad}    {{ \"long\": {}, \"short\": {short}, \"value\": {value}, \"global\": {}, \
+                 \"description\": {} }}",
+                json_string(&format!("--{}", flag.long)),
+                flag.global,
+                json_string(flag.description)
+            )?;
+        }
+        match command.flags.is_empty() {
+            true => writeln!(output, "],")?,
+            false => writeln!(output, "\n{pad}  ],")?,
+        }
+
+        write!(output, "{pad}  \"positionals\": [")?;
+        for (i, positional) in command.positionals.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            write!(
+                output,
+                "{separator}\n{pad}    {{ \"name\": {}, \"optional\": {}, \"description\": {} }}",
+                json_string(positional.name),
+                positional.optional,
+                json_string(positional.description)
+            )?;
+        }
+        match command.positionals.is_empty() {
+            true => writeln!(output, "],")?,
+            false => writeln!(output, "\n{pad}  ],")?,
+        }
+
+        write!(output, "{pad}  \"subcommands\": [")?;
+        for (i, subcommand) in command.subcommands.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            write!(output, "{separator}\n{pad}    ")?;
+            write_command(output, subcommand, indent + 2)?;
+        }
+        match command.subcommands.is_empty() {
+            true => writeln!(output, "]")?,
+            false => writeln!(output, "\n{pad}  ]")?,
+        }
+        write!(output, "{pad}}}")
+    }
+
+    let mut output = String::new();
+    // Writing to a String can't fail
+    let _ = write_command(&mut output, root, 0);
+    output.push('\n');
+    output
+}
@@ -0,0 +1,281 @@
+//! Long-running server mode, which answers JSON-RPC 2.0 requests over stdin and stdout, so that editors can
+//! query assets repeatedly without starting a new process each time.
+//!
+//! Each request and response is a single line of JSON, and batches are supported. Parsed archives are cached
+//! between requests, and are reloaded whenever the file on disk changes. Only the process that started the
+//! server can send requests, and every output path is relative to the root folder it was started with.
+//!
+//! Supported methods:
+//! * `identify {path, deep?}` returns every filetype that the file could be.
+//! * `list {path}` returns the path of every file inside a RARC or PCK archive.
+//! * `extract {path, output}` extracts every file from a RARC, PCK, or Multifile archive, and returns how
+//!   many files were written.
+//! * `convert {path, output, format, operation}` runs `"compress"` or `"decompress"` using `"yay0"`,
+//!   `"yaz0"`, or `"lz11"`, and returns the size of the output.
+//! * `evict {path?}` drops one or every archive from the cache, and returns how many were dropped.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{bail, Result};
+use orthrus::core::sanitize::PathOptions;
+use orthrus::godot::prelude::*;
+use orthrus::jsystem::prelude::*;
+use orthrus::ncompress::prelude::*;
+use orthrus::panda3d::multifile2::Multifile;
+use serde_json::{json, Value};
+// Error codes defined by the JSON-RPC 2.0 specification
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+/// Used for any error returned by the operation itself, such as a file failing to parse
+const SERVER_ERROR: i32 = -32000;
+
+/// Error returned to the client in place of a result.
+struct RpcError {
+    code: i32,
+    message: String,
+}
+
+impl RpcError {
+    fn invalid_params(key: &str) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: format!("Missing or invalid parameter {key:?}"),
+        }
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(error: anyhow::Error) -> Self {
+        Self { code: SERVER_ERROR, message: format!("{error:#}") }
+    }
+}
+
+/// Any archive that can be kept in memory between requests.
+enum Archive {
+    Rarc(ResourceArchive),
+    Pack(ResourcePack),
+    Multifile(Multifile),
+}
+
+impl Archive {
+    fn open(path: &Path) -> Result<Self> {
+        let mut magic = [0u8; 4];
+        std::io::Read::read_exact(&mut std::fs::File::open(path)?, &mut magic)?;
+        match &magic {
+            b"RARC" | b"CRAR" => Ok(Self::Rarc(ResourceArchive::open(path)?)),
+            b"GDPC" => Ok(Self::Pack(ResourcePack::open(path)?)),
+            _ => match Multifile::open(path, 0) {
+                Ok(multifile) => Ok(Self::Multifile(multifile)),
+                Err(_) => bail!("{} is not a supported archive", path.display()),
+            },
+        }
+    }
+}
+
+/// An archive along with the metadata it was loaded with, to detect when the file has changed.
+struct Cached {
+    modified: Option<SystemTime>,
+    len: u64,
+    archive: Archive,
+}
+
+/// Archives that have already been parsed, by their canonical path.
+#[derive(Default)]
+struct Cache {
+    archives: HashMap<PathBuf, Cached>,
+}
+
+impl Cache {
+    /// Returns the archive at `path`, loading it if it isn't cached or if the file has changed since.
+    fn archive(&mut self, path: &Path) -> Result<(PathBuf, &mut Archive)> {
+        let path = path.canonicalize()?;
+        let metadata = std::fs::metadata(&path)?;
+        let (modified, len) = (metadata.modified().ok(), metadata.len());
+
+        let cached = match self.archives.entry(path.clone()) {
+            Entry::Occupied(entry) if entry.get().modified == modified && entry.get().len == len => {
+                entry.into_mut()
+            }
+            entry => {
+                log::info!("Loading archive {}", path.display());
+                let archive = Archive::open(&path)?;
+                let cached = Cached { modified, len, archive };
+                match entry {
+                    Entry::Occupied(mut entry) => {
+                        entry.insert(cached);
+                        entry.into_mut()
+                    }
+                    Entry::Vacant(entry) => entry.insert(cached),
+                }
+            }
+        };
+        Ok((path, &mut cached.archive))
+    }
+}
+
+/// Answers requests from stdin until it's closed, writing each response to stdout.
+pub(crate) fn serve(root: &Path) -> Result<()> {
+    let mut server = Server {
+        root: root.to_owned(),
+        cache: Cache::default(),
+        session: Session::new(),
+    };
+    let mut stdout = std::io::stdout().lock();
+
+    for line in std::io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str(&line) {
+            Ok(Value::Array(requests)) if !requests.is_empty() => {
+                let responses: Vec<Value> =
+                    requests.iter().filter_map(|request| server.handle_request(request)).collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            Ok(request) => server.handle_request(&request),
+            Err(error) => Some(error_response(Value::Null, PARSE_ERROR, &error.to_string())),
+        };
+        if let Some(response) = response {
+            writeln!(stdout, "{response}")?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+fn error_response(id: Value, code: i32, message: &str) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message}})
+}
+
+fn param<'a>(params: &'a Value, key: &str) -> Result<&'a str, RpcError> {
+    params.get(key).and_then(Value::as_str).ok_or_else(|| RpcError::invalid_params(key))
+}
+
+/// State kept between requests.
+struct Server {
+    /// Folder that every output path is relative to
+    root: PathBuf,
+    cache: Cache,
+    /// Match-finder tables reused between compression requests
+    session: Session,
+}
+
+impl Server {
+    /// Runs a single request, returning its response, or [`None`] if it was a notification.
+    fn handle_request(&mut self, request: &Value) -> Option<Value> {
+        let id = request.get("id").cloned();
+        let version = request.get("jsonrpc").and_then(Value::as_str);
+        let (Some("2.0"), Some(method)) = (version, request.get("method").and_then(Value::as_str)) else {
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "Invalid request",
+            ));
+        };
+
+        let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+        log::debug!("Handling {method} request");
+        let result = self.call(method, &params);
+
+        // Notifications don't have an id, and never get a response
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err(error) => error_response(id, error.code, &error.message),
+        })
+    }
+
+    /// Returns the output path of a request, which can't leave the root folder.
+    fn output(&self, params: &Value) -> Result<PathBuf, RpcError> {
+        let output = param(params, "output")?;
+        Ok(PathOptions::DEFAULT.join(&self.root, output).map_err(anyhow::Error::from)?)
+    }
+
+    fn call(&mut self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "identify" => {
+                let deep = match params.get("deep") {
+                    Some(deep) => deep.as_bool().ok_or_else(|| RpcError::invalid_params("deep"))?,
+                    None => false,
+                };
+                let data = std::fs::read(param(params, "path")?).map_err(anyhow::Error::from)?;
+                let types = crate::identify::identify_types(&data, deep);
+                Ok(types.into_iter().map(|identity| identity.info).collect())
+            }
+            "list" => match self.cache.archive(Path::new(param(params, "path")?))?.1 {
+                Archive::Rarc(archive) => {
+                    Ok(archive.root.files().into_iter().map(|(path, _)| path).collect())
+                }
+                Archive::Pack(pack) => Ok(pack.paths().collect()),
+                Archive::Multifile(_) => {
+                    Err(anyhow::anyhow!("Listing Multifile contents isn't supported yet").into())
+                }
+            },
+            "extract" => {
+                let output = self.output(params)?;
+                let count = match self.cache.archive(Path::new(param(params, "path")?))? {
+                    (_, Archive::Rarc(archive)) => {
+                        archive.extract_all(output).map_err(anyhow::Error::from)?
+                    }
+                    (_, Archive::Multifile(multifile)) => {
+                        multifile.extract_all(output).map_err(anyhow::Error::from)?
+                    }
+                    // Only the file table is kept in memory, so the contents are read straight from disk
+                    (path, Archive::Pack(_)) => {
+                        ResourcePack::extract_from_file(path, output).map_err(anyhow::Error::from)?
+                    }
+                };
+                Ok(count.into())
+            }
+            "convert" => {
+                let (format, operation) = (param(params, "format")?, param(params, "operation")?);
+                let output = self.output(params)?;
+                let data = std::fs::read(param(params, "path")?).map_err(anyhow::Error::from)?;
+                let data = convert(&data, format, operation, &mut self.session)?;
+                std::fs::write(output, &data).map_err(anyhow::Error::from)?;
+                Ok(data.len().into())
+            }
+            "evict" => {
+                let count = match params.get("path") {
+                    Some(path) => {
+                        let path = path.as_str().ok_or_else(|| RpcError::invalid_params("path"))?;
+                        let path = Path::new(path).canonicalize().map_err(anyhow::Error::from)?;
+                        usize::from(self.cache.archives.remove(&path).is_some())
+                    }
+                    None => self.cache.archives.drain().count(),
+                };
+                Ok(count.into())
+            }
+            _ => Err(RpcError { code: METHOD_NOT_FOUND, message: format!("Unknown method {method:?}") }),
+        }
+    }
+}
+
+fn convert(data: &[u8], format: &str, operation: &str, session: &mut Session) -> Result<Box<[u8]>, RpcError> {
+    let output = match (format, operation) {
+        ("yay0", "decompress") => Yay0::decompress_from(data).map_err(anyhow::Error::from)?,
+        ("yay0", "compress") => {
+            Yay0::compress_from_with_session(data, yay0::CompressionAlgo::MatchingOld, 0, session)
+                .map_err(anyhow::Error::from)?
+        }
+        ("yaz0", "decompress") => Yaz0::decompress_from(data).map_err(anyhow::Error::from)?,
+        ("yaz0", "compress") => {
+            Yaz0::compress_from_with_session(data, yaz0::CompressionAlgo::MatchingOld, 0, session)
+                .map_err(anyhow::Error::from)?
+        }
+        ("lz11", "decompress") => Lz11::decompress_from(data).map_err(anyhow::Error::from)?,
+        ("lz11", "compress") => {
+            Lz11::compress_from_with_session(data, session).map_err(anyhow::Error::from)?
+        }
+        ("yay0" | "yaz0" | "lz11", _) => return Err(RpcError::invalid_params("operation")),
+        _ => return Err(RpcError::invalid_params("format")),
+    };
+    Ok(output)
+}